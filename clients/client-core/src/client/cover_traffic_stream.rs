@@ -1,20 +1,20 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::gateway_failover::SelfAddressReceiver;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::topology_control::TopologyAccessor;
+use crate::runtime::{self, spawn_future, Sleep};
 use futures::task::{Context, Poll};
 use futures::{Future, Stream, StreamExt};
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
-use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::cover::generate_loop_cover_packet;
 use nymsphinx::utils::sample_poisson_duration;
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::pin::Pin;
 use std::sync::Arc;
-use tokio::task::JoinHandle;
-use tokio::time;
+use std::time::Duration;
 
 pub struct LoopCoverTrafficStream<R>
 where
@@ -24,24 +24,28 @@ where
     ack_key: Arc<AckKey>,
 
     /// Average delay an acknowledgement packet is going to get delay at a single mixnode.
-    average_ack_delay: time::Duration,
+    average_ack_delay: Duration,
 
     /// Average delay a data packet is going to get delay at a single mixnode.
-    average_packet_delay: time::Duration,
+    average_packet_delay: Duration,
 
     /// Average delay between sending subsequent cover packets.
-    average_cover_message_sending_delay: time::Duration,
+    average_cover_message_sending_delay: Duration,
+
+    /// Number of mix hops the cover packets are going to take.
+    num_mix_hops: u8,
 
     /// Internal state, determined by `average_message_sending_delay`,
     /// used to keep track of when a next packet should be sent out.
-    next_delay: Pin<Box<time::Sleep>>,
+    next_delay: Pin<Box<Sleep>>,
 
     /// Channel used for sending prepared sphinx packets to `MixTrafficController` that sends them
     /// out to the network without any further delays.
     mix_tx: BatchMixMessageSender,
 
-    /// Represents full address of this client.
-    our_full_destination: Recipient,
+    /// Represents full address of this client. It changes whenever the client switches over
+    /// to one of its backup gateways.
+    our_full_destination: SelfAddressReceiver,
 
     /// Instance of a cryptographically secure random number generator.
     rng: R,
@@ -84,13 +88,15 @@ where
 // obviously when we finally make shared rng that is on 'higher' level, this should become
 // generic `R`
 impl LoopCoverTrafficStream<OsRng> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ack_key: Arc<AckKey>,
-        average_ack_delay: time::Duration,
-        average_packet_delay: time::Duration,
-        average_cover_message_sending_delay: time::Duration,
+        average_ack_delay: Duration,
+        average_packet_delay: Duration,
+        average_cover_message_sending_delay: Duration,
+        num_mix_hops: u8,
         mix_tx: BatchMixMessageSender,
-        our_full_destination: Recipient,
+        our_full_destination: SelfAddressReceiver,
         topology_access: TopologyAccessor,
     ) -> Self {
         let rng = OsRng;
@@ -100,7 +106,8 @@ impl LoopCoverTrafficStream<OsRng> {
             average_ack_delay,
             average_packet_delay,
             average_cover_message_sending_delay,
-            next_delay: Box::pin(time::sleep(Default::default())),
+            num_mix_hops,
+            next_delay: Box::pin(runtime::sleep(Default::default())),
             mix_tx,
             our_full_destination,
             rng,
@@ -114,11 +121,13 @@ impl LoopCoverTrafficStream<OsRng> {
         // TODO for way down the line: in very rare cases (during topology update) we might have
         // to wait a really tiny bit before actually obtaining the permit hence messing with our
         // poisson delay, but is it really a problem?
+        let our_full_destination = *self.our_full_destination.borrow();
         let topology_permit = self.topology_access.get_read_permit().await;
         // the ack is sent back to ourselves (and then ignored)
         let topology_ref_option = topology_permit.try_get_valid_topology_ref(
-            &self.our_full_destination,
-            Some(&self.our_full_destination),
+            &our_full_destination,
+            Some(&our_full_destination),
+            self.num_mix_hops,
         );
         if topology_ref_option.is_none() {
             warn!("No valid topology detected - won't send any loop cover message this time");
@@ -130,9 +139,10 @@ impl LoopCoverTrafficStream<OsRng> {
             &mut self.rng,
             topology_ref,
             &*self.ack_key,
-            &self.our_full_destination,
+            &our_full_destination,
             self.average_ack_delay,
             self.average_packet_delay,
+            self.num_mix_hops,
         )
         .expect("Somehow failed to generate a loop cover message with a valid topology");
 
@@ -149,12 +159,12 @@ impl LoopCoverTrafficStream<OsRng> {
 
         // JS: due to identical logical structure to OutQueueControl::on_message(), this is also
         // presumably required to prevent bugs in the future. Exact reason is still unknown to me.
-        tokio::task::yield_now().await;
+        runtime::yield_now().await;
     }
 
     async fn run(&mut self) {
         // we should set initial delay only when we actually start the stream
-        self.next_delay = Box::pin(time::sleep(sample_poisson_duration(
+        self.next_delay = Box::pin(runtime::sleep(sample_poisson_duration(
             &mut self.rng,
             self.average_cover_message_sending_delay,
         )));
@@ -164,8 +174,8 @@ impl LoopCoverTrafficStream<OsRng> {
         }
     }
 
-    pub fn start(mut self) -> JoinHandle<()> {
-        tokio::spawn(async move {
+    pub fn start(mut self) {
+        spawn_future(async move {
             self.run().await;
         })
     }
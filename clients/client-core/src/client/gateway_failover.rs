@@ -0,0 +1,417 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::key_manager::KeyManager;
+use crate::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use crate::config::{Config, GatewayEndpoint};
+use config::NymConfig;
+use crypto::asymmetric::identity;
+use gateway_client::GatewayClient;
+use gateway_requests::registration::handshake::SharedKeys;
+use log::*;
+use nymsphinx::addressing::clients::Recipient;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Channel announcing the current address of this client. As the address includes the identity
+/// of the gateway, it changes whenever the client switches over to a backup gateway.
+pub type SelfAddressSender = watch::Sender<Recipient>;
+pub type SelfAddressReceiver = watch::Receiver<Recipient>;
+
+pub fn self_address_channel(
+    initial_address: Recipient,
+) -> (SelfAddressSender, SelfAddressReceiver) {
+    watch::channel(initial_address)
+}
+
+/// Everything required to connect to a gateway the client has previously registered with.
+pub struct GatewayDetails {
+    endpoint: GatewayEndpoint,
+    identity: identity::PublicKey,
+    shared_key: Arc<SharedKeys>,
+}
+
+impl GatewayDetails {
+    pub fn new(
+        endpoint: GatewayEndpoint,
+        shared_key: Arc<SharedKeys>,
+    ) -> Result<Self, identity::Ed25519RecoveryError> {
+        let identity = identity::PublicKey::from_base58_string(&endpoint.gateway_id)?;
+        Ok(GatewayDetails {
+            endpoint,
+            identity,
+            shared_key,
+        })
+    }
+}
+
+/// Saves the client configuration with the provided current and backup gateway endpoints.
+pub type GatewayConfigSaver =
+    Box<dyn FnMut(&GatewayEndpoint, &[GatewayEndpoint]) -> io::Result<()> + Send>;
+
+/// Writes the outcome of a switch over to the disk, so that the client would keep on using
+/// the new gateway after it gets restarted.
+pub struct GatewayPersistence {
+    key_pathfinder: ClientKeyPathfinder,
+    save_config: GatewayConfigSaver,
+}
+
+impl GatewayPersistence {
+    pub fn new(key_pathfinder: ClientKeyPathfinder, save_config: GatewayConfigSaver) -> Self {
+        GatewayPersistence {
+            key_pathfinder,
+            save_config,
+        }
+    }
+
+    /// Persists the gateways into the config file of the client with the provided base config.
+    /// The file is reloaded before being updated so that nothing else in it is overwritten.
+    pub fn new_from_config<T>(
+        config: &Config<T>,
+        base_config_mut: fn(&mut T) -> &mut Config<T>,
+    ) -> Self
+    where
+        T: NymConfig + 'static,
+    {
+        let id = config.get_id();
+        let save_config = Box::new(
+            move |current: &GatewayEndpoint, backups: &[GatewayEndpoint]| {
+                let mut config = T::load_from_file(Some(&id))?;
+                let base_config = base_config_mut(&mut config);
+                base_config.with_gateway_endpoint(current.clone());
+                base_config.with_backup_gateway_endpoints(backups.to_vec());
+                config.save_to_file(None)
+            },
+        );
+
+        Self::new(ClientKeyPathfinder::new_from_config(config), save_config)
+    }
+
+    fn persist(
+        &mut self,
+        current_gateway: &GatewayDetails,
+        backup_gateways: &VecDeque<GatewayDetails>,
+    ) -> io::Result<()> {
+        // store the keys first so that the config would never point to a key we don't have
+        pemstore::store_key(
+            current_gateway.shared_key.as_ref(),
+            self.key_pathfinder.gateway_shared_key(),
+        )?;
+        for backup in backup_gateways {
+            pemstore::store_key(
+                backup.shared_key.as_ref(),
+                &self
+                    .key_pathfinder
+                    .backup_gateway_shared_key(&backup.endpoint.gateway_id),
+            )?;
+        }
+
+        let backup_endpoints = backup_gateways
+            .iter()
+            .map(|backup| backup.endpoint.clone())
+            .collect::<Vec<_>>();
+        (self.save_config)(&current_gateway.endpoint, &backup_endpoints)
+    }
+}
+
+/// Keeps track of the gateways the client could switch over to once its current gateway
+/// becomes persistently unreachable.
+pub struct GatewayFailover {
+    current_gateway: GatewayDetails,
+    backup_gateways: VecDeque<GatewayDetails>,
+
+    /// Number of consecutive failures to send packets to the gateway after which
+    /// the switch over is attempted.
+    failover_threshold: usize,
+    self_address: SelfAddressSender,
+
+    /// If set, the gateways are written to the disk after each successful switch over.
+    persistence: Option<GatewayPersistence>,
+}
+
+impl GatewayFailover {
+    pub fn new(
+        current_gateway: GatewayDetails,
+        backup_gateways: Vec<GatewayDetails>,
+        failover_threshold: usize,
+        self_address: SelfAddressSender,
+    ) -> Self {
+        GatewayFailover {
+            current_gateway,
+            backup_gateways: backup_gateways.into(),
+            failover_threshold,
+            self_address,
+            persistence: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_persistence(mut self, persistence: GatewayPersistence) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
+
+    /// Creates the failover out of the configured gateways. Backup gateways whose shared keys
+    /// are not available are skipped.
+    pub fn new_from_config<T: NymConfig>(
+        config: &Config<T>,
+        key_manager: &KeyManager,
+        self_address: SelfAddressSender,
+    ) -> Self {
+        let current_gateway = GatewayDetails::new(
+            config.get_gateway_endpoint().clone(),
+            key_manager.gateway_shared_key(),
+        )
+        .expect("provided gateway id is invalid!");
+
+        let backup_gateways = config
+            .get_backup_gateway_endpoints()
+            .iter()
+            .filter_map(|endpoint| {
+                let shared_key = key_manager.backup_gateway_shared_key(&endpoint.gateway_id)?;
+                match GatewayDetails::new(endpoint.clone(), shared_key) {
+                    Ok(details) => Some(details),
+                    Err(err) => {
+                        warn!(
+                            "backup gateway id {} is invalid - {}",
+                            endpoint.gateway_id, err
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self::new(
+            current_gateway,
+            backup_gateways,
+            config.get_gateway_failover_threshold(),
+            self_address,
+        )
+    }
+
+    pub(crate) fn should_fail_over(&self, consecutive_failures: usize) -> bool {
+        !self.backup_gateways.is_empty()
+            && self.failover_threshold > 0
+            && consecutive_failures % self.failover_threshold == 0
+    }
+
+    async fn try_switch_gateway(
+        gateway_client: &mut GatewayClient,
+        gateway: &GatewayDetails,
+    ) -> bool {
+        info!("Attempting to switch over to gateway {}", gateway.identity);
+        match gateway_client
+            .switch_gateway(
+                gateway.endpoint.gateway_listener.clone(),
+                gateway.identity,
+                gateway.endpoint.gateway_owner.clone(),
+                Arc::clone(&gateway.shared_key),
+            )
+            .await
+        {
+            Ok(_) => true,
+            Err(err) => {
+                warn!(
+                    "Failed to switch over to gateway {} - {}",
+                    gateway.identity, err
+                );
+                false
+            }
+        }
+    }
+
+    /// Tries each of the backup gateways in turn until connection with one of them succeeds.
+    /// The gateway we switched away from becomes the last backup. Returns whether the switch
+    /// has happened.
+    pub(crate) async fn fail_over(&mut self, gateway_client: &mut GatewayClient) -> bool {
+        for _ in 0..self.backup_gateways.len() {
+            let next_gateway = self.backup_gateways.pop_front().unwrap();
+            if Self::try_switch_gateway(gateway_client, &next_gateway).await {
+                self.promote_backup(next_gateway);
+                return true;
+            }
+            self.backup_gateways.push_back(next_gateway);
+        }
+
+        // none of the backups worked, so go back to our current gateway in case it has recovered
+        // in the meantime, so that the client would be left in a consistent state
+        error!("Failed to switch over to any of the backup gateways");
+        Self::try_switch_gateway(gateway_client, &self.current_gateway).await;
+        false
+    }
+
+    /// Makes the provided gateway the current one, with the gateway we switched away from
+    /// becoming the last backup.
+    fn promote_backup(&mut self, next_gateway: GatewayDetails) {
+        let previous_gateway = std::mem::replace(&mut self.current_gateway, next_gateway);
+        self.backup_gateways.push_back(previous_gateway);
+        self.announce_new_address();
+
+        if let Some(persistence) = self.persistence.as_mut() {
+            if let Err(err) = persistence.persist(&self.current_gateway, &self.backup_gateways) {
+                warn!(
+                    "Failed to persist the switch over to gateway {} - the client will go back to its old gateway after restart - {}",
+                    self.current_gateway.identity, err
+                );
+            }
+        }
+    }
+
+    fn announce_new_address(&self) {
+        let old_address = *self.self_address.borrow();
+        let new_address = Recipient::new(
+            *old_address.identity(),
+            *old_address.encryption_key(),
+            self.current_gateway.identity,
+        );
+        info!(
+            "Switched over to gateway {}. The new address of this client is: {}",
+            self.current_gateway.identity, new_address
+        );
+
+        // this succeeds even if nobody is listening for the changes at the moment
+        self.self_address.send_replace(new_address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::encryption;
+    use crypto::generic_array::typenum::Unsigned;
+    use gateway_requests::registration::handshake::SharedKeySize;
+    use rand::rngs::OsRng;
+    use std::sync::Mutex;
+
+    fn gateway(key_byte: u8) -> GatewayDetails {
+        let identity = identity::KeyPair::new(&mut OsRng);
+        let endpoint = GatewayEndpoint {
+            gateway_id: identity.public_key().to_base58_string(),
+            gateway_owner: "owner".to_string(),
+            gateway_listener: format!("ws://127.0.0.{}:9000", key_byte),
+        };
+        let shared_key =
+            SharedKeys::try_from_bytes(&vec![key_byte; SharedKeySize::to_usize()]).unwrap();
+        GatewayDetails::new(endpoint, Arc::new(shared_key)).unwrap()
+    }
+
+    fn failover(
+        num_backups: u8,
+        failover_threshold: usize,
+    ) -> (GatewayFailover, SelfAddressReceiver) {
+        let current_gateway = gateway(0);
+        let address = Recipient::new(
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+            *encryption::KeyPair::new(&mut OsRng).public_key(),
+            current_gateway.identity,
+        );
+        let (address_sender, address_receiver) = self_address_channel(address);
+        let backups = (1..=num_backups).map(gateway).collect();
+
+        let failover =
+            GatewayFailover::new(current_gateway, backups, failover_threshold, address_sender);
+        (failover, address_receiver)
+    }
+
+    fn gateway_ids(failover: &GatewayFailover) -> Vec<String> {
+        std::iter::once(&failover.current_gateway)
+            .chain(failover.backup_gateways.iter())
+            .map(|gateway| gateway.endpoint.gateway_id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn fails_over_every_threshold_consecutive_failures() {
+        let (failover, _) = failover(2, 3);
+        assert!(!failover.should_fail_over(1));
+        assert!(!failover.should_fail_over(2));
+        assert!(failover.should_fail_over(3));
+        assert!(!failover.should_fail_over(4));
+        assert!(failover.should_fail_over(6));
+    }
+
+    #[test]
+    fn never_fails_over_without_backups_or_threshold() {
+        let (without_backups, _) = failover(0, 3);
+        assert!(!without_backups.should_fail_over(3));
+
+        let (without_threshold, _) = failover(2, 0);
+        assert!(!without_threshold.should_fail_over(3));
+    }
+
+    #[test]
+    fn promoting_backup_rotates_gateways() {
+        let (mut failover, _) = failover(2, 3);
+        let initial_ids = gateway_ids(&failover);
+
+        let next_gateway = failover.backup_gateways.pop_front().unwrap();
+        failover.promote_backup(next_gateway);
+        assert_eq!(
+            gateway_ids(&failover),
+            vec![
+                initial_ids[1].clone(),
+                initial_ids[2].clone(),
+                initial_ids[0].clone()
+            ]
+        );
+
+        // once all the backups were used up, we come back to the original gateway
+        for _ in 0..2 {
+            let next_gateway = failover.backup_gateways.pop_front().unwrap();
+            failover.promote_backup(next_gateway);
+        }
+        assert_eq!(gateway_ids(&failover), initial_ids);
+    }
+
+    #[test]
+    fn promoting_backup_announces_new_address() {
+        let (mut failover, mut address_receiver) = failover(1, 3);
+        let old_address = *address_receiver.borrow_and_update();
+
+        let next_gateway = failover.backup_gateways.pop_front().unwrap();
+        let next_identity = next_gateway.identity;
+        failover.promote_backup(next_gateway);
+
+        assert!(address_receiver.has_changed().unwrap());
+        let new_address = *address_receiver.borrow();
+        assert_eq!(new_address.gateway(), &next_identity);
+        assert_eq!(new_address.identity(), old_address.identity());
+        assert_eq!(new_address.encryption_key(), old_address.encryption_key());
+    }
+
+    #[test]
+    fn promoting_backup_persists_gateways() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved = Arc::new(Mutex::new(None));
+        let saved_clone = Arc::clone(&saved);
+        let persistence = GatewayPersistence::new(
+            ClientKeyPathfinder::new_in_directory(dir.path()),
+            Box::new(move |current, backups| {
+                *saved_clone.lock().unwrap() = Some((current.clone(), backups.to_vec()));
+                Ok(())
+            }),
+        );
+        let (failover, _) = failover(1, 3);
+        let mut failover = failover.with_persistence(persistence);
+        let old_gateway_id = failover.current_gateway.endpoint.gateway_id.clone();
+
+        let next_gateway = failover.backup_gateways.pop_front().unwrap();
+        let next_endpoint = next_gateway.endpoint.clone();
+        failover.promote_backup(next_gateway);
+
+        let (current, backups) = saved.lock().unwrap().take().unwrap();
+        assert_eq!(current, next_endpoint);
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].gateway_id, old_gateway_id);
+
+        let pathfinder = ClientKeyPathfinder::new_in_directory(dir.path());
+        let current_key: SharedKeys = pemstore::load_key(pathfinder.gateway_shared_key()).unwrap();
+        let backup_key: SharedKeys =
+            pemstore::load_key(&pathfinder.backup_gateway_shared_key(&old_gateway_id)).unwrap();
+        assert_eq!(current_key.to_bytes(), vec![1; SharedKeySize::to_usize()]);
+        assert_eq!(backup_key.to_bytes(), vec![0; SharedKeySize::to_usize()]);
+    }
+}
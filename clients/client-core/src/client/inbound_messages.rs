@@ -1,20 +1,87 @@
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::anonymous_replies::{AnonymousSenderTag, ReplySurb};
 
 pub type InputMessageSender = mpsc::UnboundedSender<InputMessage>;
 pub type InputMessageReceiver = mpsc::UnboundedReceiver<InputMessage>;
 
+pub type MessageSentReceiver = oneshot::Receiver<()>;
+
+pub type DeliveryStatusSender = mpsc::UnboundedSender<DeliveryStatus>;
+pub type DeliveryStatusReceiver = mpsc::UnboundedReceiver<DeliveryStatus>;
+
+/// Delivery status of a message, as inferred from the acknowledgements received for all of its
+/// fragments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// All fragments of the message have been acknowledged by the recipient's gateway.
+    Delivered,
+
+    /// Some fragment of the message has not been acknowledged in time and is getting retransmitted.
+    Retrying,
+
+    /// Some fragment of the message has reached the maximum number of retransmissions
+    /// and the client has given up on delivering the message.
+    Failed,
+}
+
+/// Notifies the creator of an [`InputMessage`] once all of its packets have been sent to the
+/// mix network. If the message could not be sent at all, for example due to invalid network
+/// topology, the notifier is dropped instead and the receiver gets cancelled.
+#[derive(Debug)]
+pub struct MessageSentNotifier(oneshot::Sender<()>);
+
+impl MessageSentNotifier {
+    pub fn new() -> (Self, MessageSentReceiver) {
+        let (sender, receiver) = oneshot::channel();
+        (MessageSentNotifier(sender), receiver)
+    }
+
+    pub(crate) fn notify(self) {
+        // the receiver might have been dropped if nobody is interested in the result anymore,
+        // which is perfectly fine
+        let _ = self.0.send(());
+    }
+}
+
 #[derive(Debug)]
 pub enum InputMessage {
     Fresh {
         recipient: Recipient,
         data: Vec<u8>,
         with_reply_surb: bool,
+        // if not specified, the default number of mix hops of the client is used
+        mix_hops: Option<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+        delivery_notifier: Option<DeliveryStatusSender>,
     },
     Reply {
         reply_surb: ReplySurb,
         data: Vec<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+    },
+    /// Message with a batch of reply SURBs attached under our anonymous sender tag,
+    /// so that the recipient could send back replies of arbitrary length.
+    Anonymous {
+        recipient: Recipient,
+        data: Vec<u8>,
+        reply_surbs: u32,
+        // if not specified, the default number of mix hops of the client is used
+        mix_hops: Option<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+        delivery_notifier: Option<DeliveryStatusSender>,
+    },
+    /// Reply to an anonymous sender using the reply SURBs it has previously sent us.
+    TaggedReply {
+        sender_tag: AnonymousSenderTag,
+        data: Vec<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+    },
+    /// Additional reply SURBs requested by the recipient we have previously sent anonymous
+    /// messages to. It is created by the client itself upon receiving such request.
+    AdditionalReplySurbs {
+        sender_tag: AnonymousSenderTag,
+        amount: u32,
     },
 }
 
@@ -24,10 +91,95 @@ impl InputMessage {
             recipient,
             data,
             with_reply_surb,
+            mix_hops: None,
+            sent_notifier: None,
+            delivery_notifier: None,
+        }
+    }
+
+    pub fn new_fresh_with_mix_hops(
+        recipient: Recipient,
+        data: Vec<u8>,
+        with_reply_surb: bool,
+        mix_hops: u8,
+    ) -> Self {
+        InputMessage::Fresh {
+            recipient,
+            data,
+            with_reply_surb,
+            mix_hops: Some(mix_hops),
+            sent_notifier: None,
+            delivery_notifier: None,
         }
     }
 
     pub fn new_reply(reply_surb: ReplySurb, data: Vec<u8>) -> Self {
-        InputMessage::Reply { reply_surb, data }
+        InputMessage::Reply {
+            reply_surb,
+            data,
+            sent_notifier: None,
+        }
+    }
+
+    pub fn new_anonymous(recipient: Recipient, data: Vec<u8>, reply_surbs: u32) -> Self {
+        InputMessage::Anonymous {
+            recipient,
+            data,
+            reply_surbs,
+            mix_hops: None,
+            sent_notifier: None,
+            delivery_notifier: None,
+        }
+    }
+
+    pub fn new_anonymous_with_mix_hops(
+        recipient: Recipient,
+        data: Vec<u8>,
+        reply_surbs: u32,
+        mix_hops: u8,
+    ) -> Self {
+        InputMessage::Anonymous {
+            recipient,
+            data,
+            reply_surbs,
+            mix_hops: Some(mix_hops),
+            sent_notifier: None,
+            delivery_notifier: None,
+        }
+    }
+
+    pub fn new_tagged_reply(sender_tag: AnonymousSenderTag, data: Vec<u8>) -> Self {
+        InputMessage::TaggedReply {
+            sender_tag,
+            data,
+            sent_notifier: None,
+        }
+    }
+
+    /// Requests a notification once all packets of this message have been sent to the mix network.
+    pub fn with_sent_notifier(mut self, notifier: MessageSentNotifier) -> Self {
+        match &mut self {
+            InputMessage::Fresh { sent_notifier, .. }
+            | InputMessage::Reply { sent_notifier, .. }
+            | InputMessage::Anonymous { sent_notifier, .. }
+            | InputMessage::TaggedReply { sent_notifier, .. } => *sent_notifier = Some(notifier),
+            InputMessage::AdditionalReplySurbs { .. } => (),
+        }
+        self
+    }
+
+    /// Requests notifications about the delivery status of this message.
+    /// Note that it has no effect on replies as we do not keep track of their acknowledgements.
+    pub fn with_delivery_notifier(mut self, notifier: DeliveryStatusSender) -> Self {
+        if let InputMessage::Fresh {
+            delivery_notifier, ..
+        }
+        | InputMessage::Anonymous {
+            delivery_notifier, ..
+        } = &mut self
+        {
+            *delivery_notifier = Some(notifier)
+        }
+        self
     }
 }
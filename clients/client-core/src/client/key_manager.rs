@@ -7,6 +7,7 @@ use gateway_requests::registration::handshake::SharedKeys;
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
 use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
 use std::io;
 use std::sync::Arc;
 
@@ -27,6 +28,9 @@ pub struct KeyManager {
     /// shared key derived with the gateway during "registration handshake"
     gateway_shared_key: Option<Arc<SharedKeys>>,
 
+    /// shared keys derived with each of the backup gateways, keyed by their identities.
+    backup_gateway_shared_keys: HashMap<String, Arc<SharedKeys>>,
+
     /// key used for producing and processing acknowledgement packets.
     ack_key: Arc<AckKey>,
 }
@@ -53,6 +57,7 @@ impl KeyManager {
             identity_keypair: Arc::new(identity::KeyPair::new(rng)),
             encryption_keypair: Arc::new(encryption::KeyPair::new(rng)),
             gateway_shared_key: None,
+            backup_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(AckKey::new(rng)),
         }
     }
@@ -65,6 +70,43 @@ impl KeyManager {
         self.gateway_shared_key = Some(gateway_shared_key)
     }
 
+    /// After shared key with a backup gateway is derived, puts its ownership to this instance of a [`KeyManager`].
+    pub fn insert_backup_gateway_shared_key(
+        &mut self,
+        gateway_id: String,
+        gateway_shared_key: Arc<SharedKeys>,
+    ) {
+        self.backup_gateway_shared_keys
+            .insert(gateway_id, gateway_shared_key);
+    }
+
+    /// Loads previously stored shared keys of the specified backup gateways from the disk.
+    /// Gateways whose keys could not be loaded are skipped.
+    pub fn load_backup_gateway_shared_keys<'a, I>(
+        &mut self,
+        client_pathfinder: &ClientKeyPathfinder,
+        gateway_ids: I,
+    ) where
+        I: IntoIterator<Item = &'a str>,
+    {
+        for gateway_id in gateway_ids {
+            match pemstore::load_key::<SharedKeys>(
+                &client_pathfinder.backup_gateway_shared_key(gateway_id),
+            ) {
+                Ok(shared_key) => {
+                    self.insert_backup_gateway_shared_key(
+                        gateway_id.to_owned(),
+                        Arc::new(shared_key),
+                    );
+                }
+                Err(err) => warn!(
+                    "failed to load shared key of the backup gateway {} - {}",
+                    gateway_id, err
+                ),
+            }
+        }
+    }
+
     /// Loads previously stored keys from the disk.
     pub fn load_keys(client_pathfinder: &ClientKeyPathfinder) -> io::Result<Self> {
         let identity_keypair: identity::KeyPair =
@@ -89,6 +131,7 @@ impl KeyManager {
             identity_keypair: Arc::new(identity_keypair),
             encryption_keypair: Arc::new(encryption_keypair),
             gateway_shared_key: Some(Arc::new(gateway_shared_key)),
+            backup_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(ack_key),
         })
     }
@@ -125,6 +168,13 @@ impl KeyManager {
             }
         }
 
+        for (gateway_id, gate_key) in &self.backup_gateway_shared_keys {
+            pemstore::store_key(
+                gate_key.as_ref(),
+                &client_pathfinder.backup_gateway_shared_key(gateway_id),
+            )?
+        }
+
         Ok(())
     }
 
@@ -149,6 +199,11 @@ impl KeyManager {
         )
     }
 
+    /// Gets an atomically reference counted pointer to [`SharedKey`] derived with the specified backup gateway.
+    pub fn backup_gateway_shared_key(&self, gateway_id: &str) -> Option<Arc<SharedKeys>> {
+        self.backup_gateway_shared_keys.get(gateway_id).cloned()
+    }
+
     /// Gets an atomically reference counted pointer to [`AckKey`].
     pub fn ack_key(&self) -> Arc<AckKey> {
         Arc::clone(&self.ack_key)
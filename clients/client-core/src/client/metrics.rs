@@ -0,0 +1,77 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use metrics_common::{Counter, MetricsEncoder};
+
+#[cfg(not(target_arch = "wasm32"))]
+use metrics_common::MetricsServer;
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::SocketAddr;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::task::JoinHandle;
+
+/// Counters updated by the client tasks and exposed under the `/metrics` endpoint if it's enabled.
+#[derive(Clone, Default)]
+pub struct ClientMetrics {
+    pub(crate) packets_sent: Counter,
+    pub(crate) ack_retransmissions: Counter,
+    pub(crate) topology_refresh_failures: Counter,
+    pub(crate) gateway_failovers: Counter,
+}
+
+impl ClientMetrics {
+    pub fn new() -> Self {
+        ClientMetrics::default()
+    }
+
+    pub fn encode(&self) -> String {
+        let mut encoder = MetricsEncoder::new();
+        encoder
+            .counter(
+                "nym_client_packets_sent_total",
+                "Number of sphinx packets, both real and cover, sent to the gateway since startup",
+                self.packets_sent.get(),
+            )
+            .counter(
+                "nym_client_ack_retransmissions_total",
+                "Number of packets retransmitted due to not receiving their acknowledgement in time",
+                self.ack_retransmissions.get(),
+            )
+            .counter(
+                "nym_client_topology_refresh_failures_total",
+                "Number of failed attempts to obtain a usable network topology",
+                self.topology_refresh_failures.get(),
+            )
+            .counter(
+                "nym_client_gateway_failovers_total",
+                "Number of times the client has switched over to one of its backup gateways",
+                self.gateway_failovers.get(),
+            );
+        encoder.finish()
+    }
+
+    /// Starts serving the metrics in the Prometheus text format on the provided address.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_server(&self, address: SocketAddr) -> JoinHandle<()> {
+        let metrics = self.clone();
+        MetricsServer::new(address, move || metrics.encode()).start()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoded_metrics_reflect_counters() {
+        let metrics = ClientMetrics::new();
+        metrics.packets_sent.inc_by(10);
+        metrics.clone().ack_retransmissions.inc();
+
+        let encoded = metrics.encode();
+        assert!(encoded.contains("nym_client_packets_sent_total 10\n"));
+        assert!(encoded.contains("nym_client_ack_retransmissions_total 1\n"));
+        assert!(encoded.contains("nym_client_topology_refresh_failures_total 0\n"));
+        assert!(encoded.contains("nym_client_gateway_failovers_total 0\n"));
+    }
+}
@@ -1,12 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::gateway_failover::GatewayFailover;
+use crate::client::metrics::ClientMetrics;
+use crate::runtime::spawn_future;
 use futures::channel::mpsc;
 use futures::StreamExt;
 use gateway_client::GatewayClient;
 use log::*;
 use nymsphinx::forwarding::packet::MixPacket;
-use tokio::task::JoinHandle;
 
 pub type BatchMixMessageSender = mpsc::UnboundedSender<Vec<MixPacket>>;
 pub type BatchMixMessageReceiver = mpsc::UnboundedReceiver<Vec<MixPacket>>;
@@ -19,6 +21,11 @@ pub struct MixTrafficController {
     gateway_client: GatewayClient,
     mix_rx: BatchMixMessageReceiver,
 
+    /// Backup gateways to switch over to once the current one becomes persistently unreachable.
+    gateway_failover: GatewayFailover,
+
+    metrics: ClientMetrics,
+
     // TODO: this is temporary work-around.
     // in long run `gateway_client` will be moved away from `MixTrafficController` anyway.
     consecutive_gateway_failure_count: usize,
@@ -28,16 +35,21 @@ impl MixTrafficController {
     pub fn new(
         mix_rx: BatchMixMessageReceiver,
         gateway_client: GatewayClient,
+        gateway_failover: GatewayFailover,
+        metrics: ClientMetrics,
     ) -> MixTrafficController {
         MixTrafficController {
             gateway_client,
             mix_rx,
+            gateway_failover,
+            metrics,
             consecutive_gateway_failure_count: 0,
         }
     }
 
     async fn on_messages(&mut self, mut mix_packets: Vec<MixPacket>) {
         debug_assert!(!mix_packets.is_empty());
+        let packets = mix_packets.len() as u64;
 
         let result = if mix_packets.len() == 1 {
             let mix_packet = mix_packets.pop().unwrap();
@@ -52,14 +64,24 @@ impl MixTrafficController {
             Err(e) => {
                 error!("Failed to send sphinx packet(s) to the gateway! - {:?}", e);
                 self.consecutive_gateway_failure_count += 1;
-                if self.consecutive_gateway_failure_count == MAX_FAILURE_COUNT {
-                    // todo: in the future this should initiate a 'graceful' shutdown or try
-                    // to reconnect?
-                    panic!("failed to send sphinx packet to the gateway {} times in a row - assuming the gateway is dead. Can't do anything about it yet :(", MAX_FAILURE_COUNT)
+                if self
+                    .gateway_failover
+                    .should_fail_over(self.consecutive_gateway_failure_count)
+                    && self
+                        .gateway_failover
+                        .fail_over(&mut self.gateway_client)
+                        .await
+                {
+                    self.metrics.gateway_failovers.inc();
+                    self.consecutive_gateway_failure_count = 0;
+                } else if self.consecutive_gateway_failure_count == MAX_FAILURE_COUNT {
+                    // todo: in the future this should initiate a 'graceful' shutdown
+                    panic!("failed to send sphinx packet to the gateway {} times in a row - assuming the gateway is dead and none of the backup gateways could be used", MAX_FAILURE_COUNT)
                 }
             }
             Ok(_) => {
                 trace!("We *might* have managed to forward sphinx packet(s) to the gateway!");
+                self.metrics.packets_sent.inc_by(packets);
                 self.consecutive_gateway_failure_count = 0;
             }
         }
@@ -71,8 +93,8 @@ impl MixTrafficController {
         }
     }
 
-    pub fn start(mut self) -> JoinHandle<()> {
-        tokio::spawn(async move {
+    pub fn start(mut self) {
+        spawn_future(async move {
             self.run().await;
         })
     }
@@ -1,8 +1,11 @@
 pub mod cover_traffic_stream;
+pub mod gateway_failover;
 pub mod inbound_messages;
 pub mod key_manager;
+pub mod metrics;
 pub mod mix_traffic;
 pub mod real_messages_control;
 pub mod received_buffer;
+pub mod received_reply_surbs;
 pub mod reply_key_storage;
 pub mod topology_control;
@@ -6,6 +6,7 @@ use crate::client::real_messages_control::acknowledgement_control::Retransmissio
 use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use futures::StreamExt;
 use log::*;
+use metrics_common::Counter;
 use nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue, QueueKey};
 use nymsphinx::chunking::fragment::FragmentIdentifier;
 use nymsphinx::Delay as SphinxDelay;
@@ -18,6 +19,11 @@ pub(crate) type ActionSender = UnboundedSender<Action>;
 // The actual data being sent off as well as potential key to the delay queue
 type PendingAckEntry = (Arc<PendingAcknowledgement>, Option<QueueKey>);
 
+// Number of retransmissions after which a message whose delivery is being tracked is reported
+// as failed, unless an explicit `maximum_retransmissions` was set. Untracked messages are
+// retransmitted until they are eventually acknowledged.
+const DEFAULT_TRACKED_MAXIMUM_RETRANSMISSIONS: u32 = 10;
+
 // we can either:
 // - have a completely new set of packets we just sent and need to create entries for
 // - received an ack so we want to remove an entry
@@ -68,13 +74,31 @@ pub(super) struct Config {
 
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
+
+    /// Maximum number of times a single packet is going to be retransmitted before giving up
+    /// on delivering the entire message. If not set, only packets of tracked messages are given up
+    /// on (after `DEFAULT_TRACKED_MAXIMUM_RETRANSMISSIONS`) and the rest are retransmitted
+    /// indefinitely.
+    maximum_retransmissions: Option<u32>,
 }
 
 impl Config {
-    pub(super) fn new(ack_wait_addition: Duration, ack_wait_multiplier: f64) -> Self {
+    pub(super) fn new(
+        ack_wait_addition: Duration,
+        ack_wait_multiplier: f64,
+        maximum_retransmissions: Option<u32>,
+    ) -> Self {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
+            maximum_retransmissions,
+        }
+    }
+
+    fn should_give_up(&self, retransmissions: u32, is_tracked: bool) -> bool {
+        match self.maximum_retransmissions {
+            Some(maximum_retransmissions) => retransmissions >= maximum_retransmissions,
+            None => is_tracked && retransmissions >= DEFAULT_TRACKED_MAXIMUM_RETRANSMISSIONS,
         }
     }
 }
@@ -99,12 +123,16 @@ pub(super) struct ActionController {
 
     /// Channel for notifying `RetransmissionRequestListener` about expired acknowledgements.
     retransmission_sender: RetransmissionRequestSender,
+
+    /// Number of retransmissions requested since startup.
+    retransmissions: Counter,
 }
 
 impl ActionController {
     pub(super) fn new(
         config: Config,
         retransmission_sender: RetransmissionRequestSender,
+        retransmissions: Counter,
     ) -> (Self, ActionSender) {
         let (sender, receiver) = mpsc::unbounded();
         (
@@ -114,6 +142,7 @@ impl ActionController {
                 pending_acks_timers: NonExhaustiveDelayQueue::new(),
                 incoming_actions: receiver,
                 retransmission_sender,
+                retransmissions,
             },
             sender,
         )
@@ -167,7 +196,11 @@ impl ActionController {
                     frag_id
                 );
             }
-            Some((_, queue_key)) => {
+            Some((pending_ack_data, queue_key)) => {
+                if let Some(delivery_tracker) = &pending_ack_data.delivery_tracker {
+                    delivery_tracker.fragment_delivered()
+                }
+
                 if let Some(queue_key) = queue_key {
                     // there are no possible checks here, we must GUARANTEE that we NEVER try
                     // to remove an entry that doesn't exist (and we MUST GUARANTEE that
@@ -208,6 +241,37 @@ impl ActionController {
         }
     }
 
+    // removes the given pending ack alongside all other fragments of the same message, since
+    // without the failed fragment the recipient will not be able to reconstruct it anyway
+    fn give_up_on_delivery(&mut self, frag_id: FragmentIdentifier) {
+        let (pending_ack_data, _) = match self.pending_acks_data.remove(&frag_id) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        let delivery_tracker = match &pending_ack_data.delivery_tracker {
+            Some(delivery_tracker) => delivery_tracker,
+            None => return,
+        };
+        delivery_tracker.delivery_failed();
+
+        let pending_acks_timers = &mut self.pending_acks_timers;
+        self.pending_acks_data
+            .retain(|_, (other_pending_ack, queue_key)| {
+                let same_message = other_pending_ack
+                    .delivery_tracker
+                    .as_ref()
+                    .map(|tracker| Arc::ptr_eq(tracker, delivery_tracker))
+                    .unwrap_or_default();
+                if same_message {
+                    if let Some(queue_key) = queue_key {
+                        pending_acks_timers.remove(queue_key);
+                    }
+                }
+                !same_message
+            });
+    }
+
     // note: when the entry expires it's automatically removed from pending_acks_timers
     fn handle_expired_ack_timer(&mut self, expired_ack: Expired<FragmentIdentifier>) {
         // I'm honestly not sure how to handle it, because getting it means other things in our
@@ -224,6 +288,25 @@ impl ActionController {
                 panic!("Ack expired before it was even scheduled!")
             }
             *queue_key = None;
+
+            if self.config.should_give_up(
+                pending_ack_data.retransmissions,
+                pending_ack_data.delivery_tracker.is_some(),
+            ) {
+                warn!(
+                    "{} has reached the maximum number of retransmissions - giving up on delivering it",
+                    frag_id
+                );
+                self.give_up_on_delivery(frag_id);
+                return;
+            }
+
+            if let Some(delivery_tracker) = &pending_ack_data.delivery_tracker {
+                delivery_tracker.fragment_retransmitted()
+            }
+
+            self.retransmissions.inc();
+
             // downgrading an arc and then upgrading vs cloning is difference of 30ns vs 15ns
             // so it's literally a NO difference while it might prevent us from unnecessarily
             // resending data (in maybe 1 in 1 million cases, but it's something)
@@ -257,3 +340,27 @@ impl ActionController {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_tracked_messages_are_given_up_on_by_default() {
+        let config = Config::new(Duration::from_secs(1), 1.5, None);
+
+        assert!(!config.should_give_up(DEFAULT_TRACKED_MAXIMUM_RETRANSMISSIONS - 1, true));
+        assert!(config.should_give_up(DEFAULT_TRACKED_MAXIMUM_RETRANSMISSIONS, true));
+        assert!(!config.should_give_up(u32::MAX, false));
+    }
+
+    #[test]
+    fn explicit_maximum_applies_to_all_messages() {
+        let config = Config::new(Duration::from_secs(1), 1.5, Some(3));
+
+        assert!(!config.should_give_up(2, true));
+        assert!(!config.should_give_up(2, false));
+        assert!(config.should_give_up(3, true));
+        assert!(config.should_give_up(3, false));
+    }
+}
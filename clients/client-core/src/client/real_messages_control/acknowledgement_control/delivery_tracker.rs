@@ -0,0 +1,67 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::inbound_messages::{DeliveryStatus, DeliveryStatusSender};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Aggregates acknowledgements received for all `Fragment`s of a single message in order to
+/// notify the sender about the delivery status of the message as a whole.
+///
+/// It is shared between all `PendingAcknowledgement`s of the message.
+#[derive(Debug)]
+pub(crate) struct MessageDeliveryTracker {
+    /// Number of fragments of the message that have not been acknowledged yet.
+    remaining_fragments: AtomicUsize,
+
+    /// Indicates whether the final status (either `Delivered` or `Failed`) has already been sent.
+    finished: AtomicBool,
+
+    /// Channel used for notifying the sender of the message about any status changes.
+    notifier: DeliveryStatusSender,
+}
+
+impl MessageDeliveryTracker {
+    pub(super) fn new(num_fragments: usize, notifier: DeliveryStatusSender) -> Self {
+        MessageDeliveryTracker {
+            remaining_fragments: AtomicUsize::new(num_fragments),
+            finished: AtomicBool::new(false),
+            notifier,
+        }
+    }
+
+    fn notify(&self, status: DeliveryStatus) {
+        // the receiver might have been dropped if nobody is interested in the status anymore,
+        // which is perfectly fine
+        let _ = self.notifier.unbounded_send(status);
+    }
+
+    fn finish(&self, status: DeliveryStatus) {
+        if !self.finished.swap(true, Ordering::SeqCst) {
+            self.notify(status)
+        }
+    }
+
+    pub(super) fn has_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    /// Marks one of the fragments of the message as acknowledged.
+    pub(super) fn fragment_delivered(&self) {
+        if self.remaining_fragments.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.finish(DeliveryStatus::Delivered)
+        }
+    }
+
+    /// Indicates one of the fragments of the message is about to get retransmitted.
+    pub(super) fn fragment_retransmitted(&self) {
+        if !self.has_finished() {
+            self.notify(DeliveryStatus::Retrying)
+        }
+    }
+
+    /// Indicates we gave up on retransmitting one of the fragments of the message, meaning
+    /// it's not going to get reconstructed by the recipient.
+    pub(super) fn delivery_failed(&self) {
+        self.finish(DeliveryStatus::Failed)
+    }
+}
@@ -2,20 +2,63 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::action_controller::{Action, ActionSender};
-use super::PendingAcknowledgement;
+use super::{MessageDeliveryTracker, PendingAcknowledgement};
+use crate::client::gateway_failover::SelfAddressReceiver;
+use crate::client::received_reply_surbs::{PendingReply, ReceivedReplySurbsMap};
 use crate::client::reply_key_storage::ReplyKeyStorage;
 use crate::client::{
-    inbound_messages::{InputMessage, InputMessageReceiver},
-    real_messages_control::real_traffic_stream::{BatchRealMessageSender, RealMessage},
+    inbound_messages::{
+        DeliveryStatusSender, InputMessage, InputMessageReceiver, MessageSentNotifier,
+    },
+    real_messages_control::real_traffic_stream::{
+        BatchRealMessageSender, MessageSentTracker, RealMessage,
+    },
     topology_control::TopologyAccessor,
 };
+use crate::runtime::{self, current_timestamp};
 use futures::StreamExt;
 use log::*;
-use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::anonymous_replies::{
+    AnonymousSenderTag, ReplyContent, ReplySurb, SurbEncryptionKey,
+};
+use nymsphinx::chunking::fragment::Fragment;
 use nymsphinx::preparer::MessagePreparer;
 use nymsphinx::{acknowledgements::AckKey, addressing::clients::Recipient};
 use rand::{CryptoRng, Rng};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use topology::NymTopology;
+
+/// Maximum number of recipients of our anonymous messages we keep the sender tags for.
+/// Once it's exceeded, the tag that has not been used for the longest time is forgotten.
+const MAX_TAGGED_RECIPIENTS: usize = 10_000;
+
+/// Interval at which expired reply SURB state is removed and timed out requests for additional
+/// reply SURBs are retried.
+const REPLY_SURBS_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Configurable parameters of the reply SURBs handling of the [`InputMessageListener`].
+pub(crate) struct ReplySurbsConfig {
+    /// Minimum number of reply SURBs of an anonymous sender we want to keep available.
+    /// Once there are fewer of them, additional ones are requested.
+    minimum_reply_surb_storage_threshold: u32,
+
+    /// Maximum number of reply SURBs that are requested or sent in response to a single request.
+    maximum_reply_surb_request_size: u32,
+}
+
+impl ReplySurbsConfig {
+    pub(crate) fn new(
+        minimum_reply_surb_storage_threshold: u32,
+        maximum_reply_surb_request_size: u32,
+    ) -> Self {
+        ReplySurbsConfig {
+            minimum_reply_surb_storage_threshold,
+            maximum_reply_surb_request_size,
+        }
+    }
+}
 
 /// Module responsible for dealing with the received messages: splitting them, creating acknowledgements,
 /// putting everything into sphinx packets, etc.
@@ -26,12 +69,25 @@ where
 {
     ack_key: Arc<AckKey>,
     ack_recipient: Recipient,
+    self_address: SelfAddressReceiver,
     input_receiver: InputMessageReceiver,
     message_preparer: MessagePreparer<R>,
     action_sender: ActionSender,
     real_message_sender: BatchRealMessageSender,
     topology_access: TopologyAccessor,
     reply_key_storage: ReplyKeyStorage,
+    received_reply_surbs: ReceivedReplySurbsMap,
+    reply_surbs_config: ReplySurbsConfig,
+
+    /// Tags we used for sending anonymous messages to particular recipients,
+    /// so that each recipient would see all our messages as coming from the same sender.
+    sender_tags: HashMap<[u8; Recipient::LEN], AnonymousSenderTag>,
+
+    /// Reverse of `sender_tags` used to determine to whom additional reply SURBs should be sent,
+    /// alongside the timestamps of when we last sent any reply SURBs under the tags. Once they
+    /// are older than the maximum reply SURB age, the tags are forgotten as the recipients
+    /// can no longer use them.
+    tagged_recipients: HashMap<AnonymousSenderTag, (Recipient, u64)>,
 }
 
 impl<R> InputMessageListener<R>
@@ -43,30 +99,103 @@ where
     #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         ack_key: Arc<AckKey>,
-        ack_recipient: Recipient,
+        self_address: SelfAddressReceiver,
         input_receiver: InputMessageReceiver,
         message_preparer: MessagePreparer<R>,
         action_sender: ActionSender,
         real_message_sender: BatchRealMessageSender,
         topology_access: TopologyAccessor,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
+        reply_surbs_config: ReplySurbsConfig,
     ) -> Self {
+        let ack_recipient = *self_address.borrow();
         InputMessageListener {
             ack_key,
             ack_recipient,
+            self_address,
             input_receiver,
             message_preparer,
             action_sender,
             real_message_sender,
             topology_access,
             reply_key_storage,
+            received_reply_surbs,
+            reply_surbs_config,
+            sender_tags: HashMap::new(),
+            tagged_recipients: HashMap::new(),
+        }
+    }
+
+    fn sender_tag(&mut self, recipient: Recipient) -> AnonymousSenderTag {
+        let now = current_timestamp();
+        if let Some(sender_tag) = self.sender_tags.get(&recipient.to_bytes()) {
+            if let Some((_, last_used)) = self.tagged_recipients.get_mut(sender_tag) {
+                *last_used = now;
+            }
+            return *sender_tag;
+        }
+
+        if self.tagged_recipients.len() >= MAX_TAGGED_RECIPIENTS {
+            let least_recently_used = self
+                .tagged_recipients
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(sender_tag, _)| *sender_tag);
+            if let Some(sender_tag) = least_recently_used {
+                self.forget_sender_tag(&sender_tag)
+            }
+        }
+
+        let sender_tag = AnonymousSenderTag::new_random(&mut rand::rngs::OsRng);
+        self.sender_tags.insert(recipient.to_bytes(), sender_tag);
+        self.tagged_recipients.insert(sender_tag, (recipient, now));
+        sender_tag
+    }
+
+    fn forget_sender_tag(&mut self, sender_tag: &AnonymousSenderTag) {
+        if let Some((recipient, _)) = self.tagged_recipients.remove(sender_tag) {
+            self.sender_tags.remove(&recipient.to_bytes());
+        }
+    }
+
+    fn remove_expired_sender_tags(&mut self) {
+        let now = current_timestamp();
+        let maximum_age = self.received_reply_surbs.maximum_surb_age().as_secs();
+        let tagged_recipients = &mut self.tagged_recipients;
+        tagged_recipients.retain(|_, (_, last_used)| now.saturating_sub(*last_used) <= maximum_age);
+        self.sender_tags
+            .retain(|_, sender_tag| tagged_recipients.contains_key(sender_tag));
+    }
+
+    /// Makes sure our current address is used for all acknowledgements and reply SURBs,
+    /// as it changes whenever the client switches over to a different gateway.
+    fn refresh_self_address(&mut self) {
+        self.ack_recipient = *self.self_address.borrow();
+        self.message_preparer.set_sender_address(self.ack_recipient);
+    }
+
+    fn store_reply_keys(&self, reply_keys: Vec<SurbEncryptionKey>) {
+        for reply_key in reply_keys {
+            self.reply_key_storage
+                .insert_encryption_key(reply_key)
+                .expect("Failed to insert surb reply key to the store!")
         }
     }
 
     // we require topology for replies to generate surb_acks
-    async fn handle_reply(&mut self, reply_surb: ReplySurb, data: Vec<u8>) -> Option<RealMessage> {
+    async fn handle_reply(
+        &mut self,
+        reply_surb: ReplySurb,
+        data: Vec<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+    ) -> Option<RealMessage> {
         let topology_permit = self.topology_access.get_read_permit().await;
-        let topology = match topology_permit.try_get_valid_topology_ref(&self.ack_recipient, None) {
+        let topology = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            None,
+            self.message_preparer.num_mix_hops(),
+        ) {
             Some(topology_ref) => topology_ref,
             None => {
                 warn!("Could not process the message - the network topology is invalid");
@@ -83,7 +212,12 @@ where
                 // TODO: later probably write pending ack here
                 // and deal with them....
                 // ... somehow
-                Some(RealMessage::new(mix_packet, reply_id))
+                let real_message = RealMessage::new(mix_packet, reply_id);
+                Some(match sent_notifier {
+                    Some(notifier) => real_message
+                        .with_sent_tracker(Arc::new(MessageSentTracker::new(1, notifier))),
+                    None => real_message,
+                })
             }
             Err(err) => {
                 // TODO: should we have some mechanism to indicate to the user that the `reply_surb`
@@ -94,16 +228,96 @@ where
         }
     }
 
-    async fn handle_fresh_message(
+    async fn prepare_reply_fragment(
         &mut self,
-        recipient: Recipient,
-        content: Vec<u8>,
-        with_reply_surb: bool,
-    ) -> Option<Vec<RealMessage>> {
-        let topology_permit = self.topology_access.get_read_permit().await;
-        let topology = match topology_permit
-            .try_get_valid_topology_ref(&self.ack_recipient, Some(&recipient))
+        fragment: Fragment,
+        reply_surb: ReplySurb,
+        topology: &NymTopology,
+        sent_tracker: Option<&Arc<MessageSentTracker>>,
+    ) -> Option<RealMessage> {
+        match self
+            .message_preparer
+            .prepare_reply_fragment_for_sending(fragment, reply_surb, topology, &self.ack_key)
+            .await
         {
+            Ok((mix_packet, reply_id)) => {
+                let real_message = RealMessage::new(mix_packet, reply_id);
+                Some(match sent_tracker {
+                    Some(tracker) => real_message.with_sent_tracker(Arc::clone(tracker)),
+                    None => real_message,
+                })
+            }
+            Err(err) => {
+                warn!("failed to prepare reply fragment - {:?}", err);
+                None
+            }
+        }
+    }
+
+    /// Uses one of the stored reply SURBs of the sender to ask it for additional ones,
+    /// unless such request is already in progress.
+    async fn request_additional_reply_surbs(
+        &mut self,
+        sender_tag: AnonymousSenderTag,
+        required: usize,
+        topology: &NymTopology,
+    ) -> Option<RealMessage> {
+        if !self.received_reply_surbs.mark_surbs_requested(sender_tag) {
+            trace!(
+                "additional reply SURBs have already been requested from {}",
+                sender_tag
+            );
+            return None;
+        }
+
+        let reply_surb = match self.received_reply_surbs.take_surbs(&sender_tag, 1) {
+            Some(mut reply_surbs) => reply_surbs.pop().unwrap(),
+            None => {
+                warn!(
+                    "there are no reply SURBs left to request additional ones from {}",
+                    sender_tag
+                );
+                self.received_reply_surbs.clear_surbs_request(&sender_tag);
+                return None;
+            }
+        };
+
+        let amount = (required as u32)
+            .saturating_add(self.reply_surbs_config.minimum_reply_surb_storage_threshold)
+            .min(self.reply_surbs_config.maximum_reply_surb_request_size);
+        debug!(
+            "requesting {} additional reply SURBs from {}",
+            amount, sender_tag
+        );
+
+        // the request is tiny so it always fits in a single fragment
+        let mut fragments = self
+            .message_preparer
+            .prepare_and_split_reply(ReplyContent::SurbRequest { sender_tag, amount });
+        debug_assert_eq!(fragments.len(), 1);
+
+        let request = self
+            .prepare_reply_fragment(fragments.pop().unwrap(), reply_surb, topology, None)
+            .await;
+        if request.is_none() {
+            self.received_reply_surbs.clear_surbs_request(&sender_tag);
+        }
+        request
+    }
+
+    async fn handle_tagged_reply(
+        &mut self,
+        sender_tag: AnonymousSenderTag,
+        data: Vec<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+    ) -> Option<Vec<RealMessage>> {
+        let topology_access = self.topology_access.clone();
+        let topology_permit = topology_access.get_read_permit().await;
+        let topology = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            None,
+            self.message_preparer.num_mix_hops(),
+        ) {
             Some(topology_ref) => topology_ref,
             None => {
                 warn!("Could not process the message - the network topology is invalid");
@@ -111,41 +325,138 @@ where
             }
         };
 
-        // split the message, attach optional reply surb
-        let (split_message, reply_key) = self
+        let available = self.received_reply_surbs.available_surbs(&sender_tag);
+        if available == 0 {
+            warn!("there are no reply SURBs available for {}", sender_tag);
+            return None;
+        }
+
+        // make sure to preserve ordering of the replies if some are already waiting for more SURBs
+        // and to always keep at least a single SURB around so that we could request more of them
+        let required = self.message_preparer.number_of_reply_fragments(data.len());
+        if self.received_reply_surbs.has_pending_replies(&sender_tag) || available <= required {
+            debug!(
+                "not enough reply SURBs available for {} ({} required, {} available). The reply is going to be sent once more of them arrive",
+                sender_tag, required, available
+            );
+            if !self.received_reply_surbs.insert_pending_reply(
+                sender_tag,
+                PendingReply {
+                    data,
+                    sent_notifier,
+                },
+            ) {
+                warn!(
+                    "there are too many replies waiting for reply SURBs of {} - the reply is going to be dropped",
+                    sender_tag
+                );
+            }
+            return self
+                .request_additional_reply_surbs(
+                    sender_tag,
+                    (required + 1).saturating_sub(available),
+                    topology,
+                )
+                .await
+                .map(|request| vec![request]);
+        }
+
+        let reply_surbs = self
+            .received_reply_surbs
+            .take_surbs(&sender_tag, required)
+            .expect("the reply SURBs were taken out of the storage by somebody else!");
+
+        let fragments = self
             .message_preparer
-            .prepare_and_split_message(content, with_reply_surb, topology)
-            .expect("somehow the topology was invalid after all!");
+            .prepare_and_split_reply(ReplyContent::Data(data));
+        debug_assert_eq!(fragments.len(), required);
 
-        if let Some(reply_key) = reply_key {
-            self.reply_key_storage
-                .insert_encryption_key(reply_key)
-                .expect("Failed to insert surb reply key to the store!")
+        // the notifier fires only once the last one of the fragments leaves the out queue
+        let sent_tracker = sent_notifier
+            .map(|notifier| Arc::new(MessageSentTracker::new(fragments.len(), notifier)));
+        let mut real_messages = Vec::with_capacity(fragments.len() + 1);
+        for (fragment, reply_surb) in fragments.into_iter().zip(reply_surbs.into_iter()) {
+            match self
+                .prepare_reply_fragment(fragment, reply_surb, topology, sent_tracker.as_ref())
+                .await
+            {
+                Some(real_message) => real_messages.push(real_message),
+                // there's no point in sending an incomplete reply
+                None => return None,
+            }
+        }
+
+        // and make sure we won't run out of the SURBs in the future
+        if self.received_reply_surbs.available_surbs(&sender_tag)
+            < self.reply_surbs_config.minimum_reply_surb_storage_threshold as usize
+        {
+            if let Some(request) = self
+                .request_additional_reply_surbs(sender_tag, 0, topology)
+                .await
+            {
+                real_messages.push(request)
+            }
         }
 
+        Some(real_messages)
+    }
+
+    /// Puts all fragments of a fresh message into sphinx packets and creates acknowledgements
+    /// for each of them.
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_fragments_for_sending(
+        &mut self,
+        fragments: Vec<Fragment>,
+        recipient: Recipient,
+        mix_hops: u8,
+        topology: &NymTopology,
+        sent_notifier: Option<MessageSentNotifier>,
+        delivery_notifier: Option<DeliveryStatusSender>,
+    ) -> Vec<RealMessage> {
+        // the notifier fires only once the last one of the fragments leaves the out queue
+        let sent_tracker = sent_notifier
+            .map(|notifier| Arc::new(MessageSentTracker::new(fragments.len(), notifier)));
+        let delivery_tracker = delivery_notifier
+            .map(|notifier| Arc::new(MessageDeliveryTracker::new(fragments.len(), notifier)));
+
         // encrypt chunks, put them inside sphinx packets and generate acks
-        let mut pending_acks = Vec::with_capacity(split_message.len());
-        let mut real_messages = Vec::with_capacity(split_message.len());
-        for message_chunk in split_message {
+        let mut pending_acks = Vec::with_capacity(fragments.len());
+        let mut real_messages = Vec::with_capacity(fragments.len());
+        for message_chunk in fragments {
             // we need to clone it because we need to keep it in memory in case we had to retransmit
             // it. And then we'd need to recreate entire ACK again.
             let chunk_clone = message_chunk.clone();
             let prepared_fragment = self
                 .message_preparer
-                .prepare_chunk_for_sending(chunk_clone, topology, &self.ack_key, &recipient)
+                .prepare_chunk_for_sending_with_mix_hops(
+                    chunk_clone,
+                    topology,
+                    &self.ack_key,
+                    &recipient,
+                    mix_hops,
+                )
                 .await
                 .unwrap();
 
-            real_messages.push(RealMessage::new(
+            let real_message = RealMessage::new(
                 prepared_fragment.mix_packet,
                 message_chunk.fragment_identifier(),
-            ));
+            );
+            real_messages.push(match &sent_tracker {
+                Some(tracker) => real_message.with_sent_tracker(Arc::clone(tracker)),
+                None => real_message,
+            });
 
-            pending_acks.push(PendingAcknowledgement::new(
+            let pending_ack = PendingAcknowledgement::new(
                 message_chunk,
                 prepared_fragment.total_delay,
                 recipient,
-            ));
+                mix_hops,
+            );
+            pending_acks.push(match &delivery_tracker {
+                Some(tracker) => pending_ack.with_delivery_tracker(Arc::clone(tracker)),
+                None => pending_ack,
+            });
         }
 
         // tells the controller to put this into the hashmap
@@ -153,23 +464,237 @@ where
             .unbounded_send(Action::new_insert(pending_acks))
             .unwrap();
 
-        Some(real_messages)
+        real_messages
+    }
+
+    async fn handle_fresh_message(
+        &mut self,
+        recipient: Recipient,
+        content: Vec<u8>,
+        with_reply_surb: bool,
+        mix_hops: Option<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+        delivery_notifier: Option<DeliveryStatusSender>,
+    ) -> Option<Vec<RealMessage>> {
+        let mix_hops = mix_hops.unwrap_or_else(|| self.message_preparer.num_mix_hops());
+
+        let topology_access = self.topology_access.clone();
+        let topology_permit = topology_access.get_read_permit().await;
+        let topology = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            Some(&recipient),
+            mix_hops,
+        ) {
+            Some(topology_ref) => topology_ref,
+            None => {
+                warn!(
+                    "Could not process the message - the network topology is invalid for {} mix hops",
+                    mix_hops
+                );
+                return None;
+            }
+        };
+
+        // split the message, attach optional reply surb
+        let (split_message, reply_key) = self
+            .message_preparer
+            .prepare_and_split_message(content, with_reply_surb, topology)
+            .expect("somehow the topology was invalid after all!");
+
+        if let Some(reply_key) = reply_key {
+            self.store_reply_keys(vec![reply_key])
+        }
+
+        Some(
+            self.prepare_fragments_for_sending(
+                split_message,
+                recipient,
+                mix_hops,
+                topology,
+                sent_notifier,
+                delivery_notifier,
+            )
+            .await,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_anonymous_message(
+        &mut self,
+        recipient: Recipient,
+        content: Vec<u8>,
+        reply_surbs: u32,
+        mix_hops: Option<u8>,
+        sent_notifier: Option<MessageSentNotifier>,
+        delivery_notifier: Option<DeliveryStatusSender>,
+    ) -> Option<Vec<RealMessage>> {
+        let mix_hops = mix_hops.unwrap_or_else(|| self.message_preparer.num_mix_hops());
+
+        let topology_access = self.topology_access.clone();
+        let topology_permit = topology_access.get_read_permit().await;
+        let topology = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            Some(&recipient),
+            mix_hops,
+        ) {
+            Some(topology_ref) => topology_ref,
+            None => {
+                warn!(
+                    "Could not process the message - the network topology is invalid for {} mix hops",
+                    mix_hops
+                );
+                return None;
+            }
+        };
+
+        let reply_surbs = if reply_surbs > self.reply_surbs_config.maximum_reply_surb_request_size {
+            debug!(
+                "capping the number of attached reply SURBs from {} to {}",
+                reply_surbs, self.reply_surbs_config.maximum_reply_surb_request_size
+            );
+            self.reply_surbs_config.maximum_reply_surb_request_size
+        } else {
+            reply_surbs
+        };
+
+        let sender_tag = self.sender_tag(recipient);
+        let (split_message, reply_keys) = self
+            .message_preparer
+            .prepare_and_split_repliable_message(content, sender_tag, reply_surbs, topology)
+            .expect("somehow the topology was invalid after all!");
+        self.store_reply_keys(reply_keys);
+
+        Some(
+            self.prepare_fragments_for_sending(
+                split_message,
+                recipient,
+                mix_hops,
+                topology,
+                sent_notifier,
+                delivery_notifier,
+            )
+            .await,
+        )
+    }
+
+    async fn handle_additional_reply_surbs(
+        &mut self,
+        sender_tag: AnonymousSenderTag,
+        amount: u32,
+    ) -> Option<Vec<RealMessage>> {
+        let recipient = match self.tagged_recipients.get_mut(&sender_tag) {
+            Some((recipient, last_used)) => {
+                *last_used = current_timestamp();
+                *recipient
+            }
+            None => {
+                warn!(
+                    "received a request for additional reply SURBs for an unknown tag {}",
+                    sender_tag
+                );
+                return None;
+            }
+        };
+
+        let amount = amount.min(self.reply_surbs_config.maximum_reply_surb_request_size);
+        let mix_hops = self.message_preparer.num_mix_hops();
+
+        let topology_access = self.topology_access.clone();
+        let topology_permit = topology_access.get_read_permit().await;
+        let topology = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            Some(&recipient),
+            mix_hops,
+        ) {
+            Some(topology_ref) => topology_ref,
+            None => {
+                warn!("Could not send additional reply SURBs - the network topology is invalid");
+                return None;
+            }
+        };
+
+        debug!(
+            "sending {} additional reply SURBs to {}",
+            amount, sender_tag
+        );
+        let (split_message, reply_keys) = self
+            .message_preparer
+            .prepare_and_split_additional_reply_surbs(sender_tag, amount, topology)
+            .expect("somehow the topology was invalid after all!");
+        self.store_reply_keys(reply_keys);
+
+        Some(
+            self.prepare_fragments_for_sending(
+                split_message,
+                recipient,
+                mix_hops,
+                topology,
+                None,
+                None,
+            )
+            .await,
+        )
     }
 
     async fn on_input_message(&mut self, msg: InputMessage) {
+        self.refresh_self_address();
+
         let real_messages = match msg {
             InputMessage::Fresh {
                 recipient,
                 data,
                 with_reply_surb,
+                mix_hops,
+                sent_notifier,
+                delivery_notifier,
             } => {
-                self.handle_fresh_message(recipient, data, with_reply_surb)
-                    .await
+                self.handle_fresh_message(
+                    recipient,
+                    data,
+                    with_reply_surb,
+                    mix_hops,
+                    sent_notifier,
+                    delivery_notifier,
+                )
+                .await
             }
-            InputMessage::Reply { reply_surb, data } => self
-                .handle_reply(reply_surb, data)
+            InputMessage::Reply {
+                reply_surb,
+                data,
+                sent_notifier,
+            } => self
+                .handle_reply(reply_surb, data, sent_notifier)
                 .await
                 .map(|message| vec![message]),
+            InputMessage::Anonymous {
+                recipient,
+                data,
+                reply_surbs,
+                mix_hops,
+                sent_notifier,
+                delivery_notifier,
+            } => {
+                self.handle_anonymous_message(
+                    recipient,
+                    data,
+                    reply_surbs,
+                    mix_hops,
+                    sent_notifier,
+                    delivery_notifier,
+                )
+                .await
+            }
+            InputMessage::TaggedReply {
+                sender_tag,
+                data,
+                sent_notifier,
+            } => {
+                self.handle_tagged_reply(sender_tag, data, sent_notifier)
+                    .await
+            }
+            InputMessage::AdditionalReplySurbs { sender_tag, amount } => {
+                self.handle_additional_reply_surbs(sender_tag, amount).await
+            }
         };
 
         // there's no point in trying to send nothing
@@ -181,10 +706,68 @@ where
         }
     }
 
+    /// Removes expired reply SURB state and asks again for additional reply SURBs
+    /// whose previous requests have timed out (or failed to get sent).
+    async fn maintain_reply_surbs(&mut self) {
+        self.received_reply_surbs.remove_expired();
+        self.remove_expired_sender_tags();
+
+        let awaiting_surbs = self.received_reply_surbs.senders_awaiting_surbs();
+        if awaiting_surbs.is_empty() {
+            return;
+        }
+
+        self.refresh_self_address();
+        let topology_access = self.topology_access.clone();
+        let topology_permit = topology_access.get_read_permit().await;
+        let topology = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            None,
+            self.message_preparer.num_mix_hops(),
+        ) {
+            Some(topology_ref) => topology_ref,
+            None => {
+                warn!("Could not request additional reply SURBs - the network topology is invalid");
+                return;
+            }
+        };
+
+        let mut requests = Vec::with_capacity(awaiting_surbs.len());
+        for (sender_tag, pending_size) in awaiting_surbs {
+            debug!(
+                "there are replies to {} still waiting for additional reply SURBs, requesting them again",
+                sender_tag
+            );
+            let required = self
+                .message_preparer
+                .number_of_reply_fragments(pending_size);
+            if let Some(request) = self
+                .request_additional_reply_surbs(sender_tag, required, topology)
+                .await
+            {
+                requests.push(request)
+            }
+        }
+
+        if !requests.is_empty() {
+            self.real_message_sender.unbounded_send(requests).unwrap();
+        }
+    }
+
     pub(super) async fn run(&mut self) {
         debug!("Started InputMessageListener");
-        while let Some(input_msg) = self.input_receiver.next().await {
-            self.on_input_message(input_msg).await;
+        let mut maintenance_timer = Box::pin(runtime::sleep(REPLY_SURBS_MAINTENANCE_INTERVAL));
+        loop {
+            tokio::select! {
+                input_msg = self.input_receiver.next() => match input_msg {
+                    Some(input_msg) => self.on_input_message(input_msg).await,
+                    None => break,
+                },
+                _ = &mut maintenance_timer => {
+                    self.maintain_reply_surbs().await;
+                    maintenance_timer = Box::pin(runtime::sleep(REPLY_SURBS_MAINTENANCE_INTERVAL));
+                }
+            }
         }
         error!("TODO: error msg. Or maybe panic?")
     }
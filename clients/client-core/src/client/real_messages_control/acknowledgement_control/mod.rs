@@ -3,16 +3,20 @@
 
 use self::{
     acknowledgement_listener::AcknowledgementListener, action_controller::ActionController,
-    input_message_listener::InputMessageListener,
+    delivery_tracker::MessageDeliveryTracker, input_message_listener::InputMessageListener,
     retransmission_request_listener::RetransmissionRequestListener,
     sent_notification_listener::SentNotificationListener,
 };
 use super::real_traffic_stream::BatchRealMessageSender;
+use crate::client::gateway_failover::SelfAddressReceiver;
+use crate::client::received_reply_surbs::ReceivedReplySurbsMap;
 use crate::client::reply_key_storage::ReplyKeyStorage;
 use crate::client::{inbound_messages::InputMessageReceiver, topology_control::TopologyAccessor};
+use crate::runtime::spawn_future;
 use futures::channel::mpsc;
 use gateway_client::AcknowledgementReceiver;
 use log::*;
+use metrics_common::Counter;
 use nymsphinx::{
     acknowledgements::AckKey,
     addressing::clients::Recipient,
@@ -25,10 +29,10 @@ use std::{
     sync::{Arc, Weak},
     time::Duration,
 };
-use tokio::task::JoinHandle;
 
 mod acknowledgement_listener;
 mod action_controller;
+mod delivery_tracker;
 mod input_message_listener;
 mod retransmission_request_listener;
 mod sent_notification_listener;
@@ -53,20 +57,43 @@ pub(crate) struct PendingAcknowledgement {
     message_chunk: Fragment,
     delay: SphinxDelay,
     recipient: Recipient,
+    /// Number of mix hops the fragment was sent through, so that any retransmissions would
+    /// take the same number of them.
+    mix_hops: u8,
+    /// Number of times the fragment has already been retransmitted.
+    retransmissions: u32,
+    /// If the sender of the message is interested in its delivery status, tracker shared between
+    /// all fragments of the message.
+    delivery_tracker: Option<Arc<MessageDeliveryTracker>>,
 }
 
 impl PendingAcknowledgement {
     /// Creates new instance of `PendingAcknowledgement` using the provided data.
-    fn new(message_chunk: Fragment, delay: SphinxDelay, recipient: Recipient) -> Self {
+    fn new(
+        message_chunk: Fragment,
+        delay: SphinxDelay,
+        recipient: Recipient,
+        mix_hops: u8,
+    ) -> Self {
         PendingAcknowledgement {
             message_chunk,
             delay,
             recipient,
+            mix_hops,
+            retransmissions: 0,
+            delivery_tracker: None,
         }
     }
 
+    fn with_delivery_tracker(mut self, delivery_tracker: Arc<MessageDeliveryTracker>) -> Self {
+        self.delivery_tracker = Some(delivery_tracker);
+        self
+    }
+
+    // called as part of the retransmission as a new sphinx packet has been created
     fn update_delay(&mut self, new_delay: SphinxDelay) {
         self.delay = new_delay;
+        self.retransmissions += 1;
     }
 }
 
@@ -119,20 +146,43 @@ pub(super) struct Config {
 
     /// Average delay a data packet is going to get delayed at a single mixnode.
     average_packet_delay: Duration,
+
+    /// Number of mix hops the packets are going to take, unless specified otherwise
+    /// for a particular message.
+    num_mix_hops: u8,
+
+    /// Maximum number of times a single packet is going to be retransmitted before giving up
+    /// on delivering the entire message. If not set, only tracked messages are given up on.
+    maximum_retransmissions: Option<u32>,
+
+    /// Minimum number of reply SURBs of an anonymous sender we want to keep available.
+    minimum_reply_surb_storage_threshold: u32,
+
+    /// Maximum number of reply SURBs that are requested or sent in a single message.
+    maximum_reply_surb_request_size: u32,
 }
 
 impl Config {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         ack_wait_addition: Duration,
         ack_wait_multiplier: f64,
         average_ack_delay: Duration,
         average_packet_delay: Duration,
+        num_mix_hops: u8,
+        maximum_retransmissions: Option<u32>,
+        minimum_reply_surb_storage_threshold: u32,
+        maximum_reply_surb_request_size: u32,
     ) -> Self {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
             average_ack_delay,
             average_packet_delay,
+            num_mix_hops,
+            maximum_retransmissions,
+            minimum_reply_surb_storage_threshold,
+            maximum_reply_surb_request_size,
         }
     }
 }
@@ -152,28 +202,35 @@ impl<R> AcknowledgementController<R>
 where
     R: 'static + CryptoRng + Rng + Clone + Send,
 {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         config: Config,
         rng: R,
         topology_access: TopologyAccessor,
         ack_key: Arc<AckKey>,
-        ack_recipient: Recipient,
+        self_address: SelfAddressReceiver,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
         connectors: AcknowledgementControllerConnectors,
+        retransmissions: Counter,
     ) -> Self {
         let (retransmission_tx, retransmission_rx) = mpsc::unbounded();
 
-        let action_config =
-            action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier);
+        let action_config = action_controller::Config::new(
+            config.ack_wait_addition,
+            config.ack_wait_multiplier,
+            config.maximum_retransmissions,
+        );
         let (action_controller, action_sender) =
-            ActionController::new(action_config, retransmission_tx);
+            ActionController::new(action_config, retransmission_tx, retransmissions);
 
         let message_preparer = MessagePreparer::new(
             rng,
-            ack_recipient,
+            *self_address.borrow(),
             config.average_packet_delay,
             config.average_ack_delay,
-        );
+        )
+        .with_mix_hops(config.num_mix_hops);
 
         // will listen for any acks coming from the network
         let acknowledgement_listener = AcknowledgementListener::new(
@@ -185,19 +242,24 @@ where
         // will listen for any new messages from the client
         let input_message_listener = InputMessageListener::new(
             Arc::clone(&ack_key),
-            ack_recipient,
+            self_address.clone(),
             connectors.input_receiver,
             message_preparer.clone(),
             action_sender.clone(),
             connectors.real_message_sender.clone(),
             topology_access.clone(),
             reply_key_storage,
+            received_reply_surbs,
+            input_message_listener::ReplySurbsConfig::new(
+                config.minimum_reply_surb_storage_threshold,
+                config.maximum_reply_surb_request_size,
+            ),
         );
 
         // will listen for any ack timeouts and trigger retransmission
         let retransmission_request_listener = RetransmissionRequestListener::new(
             Arc::clone(&ack_key),
-            ack_recipient,
+            self_address,
             message_preparer,
             action_sender.clone(),
             connectors.real_message_sender,
@@ -219,7 +281,7 @@ where
         }
     }
 
-    pub(super) async fn run(&mut self) {
+    pub(super) fn start(mut self) {
         let mut acknowledgement_listener = self.acknowledgement_listener.take().unwrap();
         let mut input_message_listener = self.input_message_listener.take().unwrap();
         let mut retransmission_request_listener =
@@ -230,47 +292,25 @@ where
         // the below are log messages are errors as at the current stage we do not expect any of
         // the task to ever finish. This will of course change once we introduce
         // graceful shutdowns.
-        let ack_listener_fut = tokio::spawn(async move {
+        spawn_future(async move {
             acknowledgement_listener.run().await;
             error!("The acknowledgement listener has finished execution!");
-            acknowledgement_listener
         });
-        let input_listener_fut = tokio::spawn(async move {
+        spawn_future(async move {
             input_message_listener.run().await;
             error!("The input listener has finished execution!");
-            input_message_listener
         });
-        let retransmission_req_fut = tokio::spawn(async move {
+        spawn_future(async move {
             retransmission_request_listener.run().await;
             error!("The retransmission request listener has finished execution!");
-            retransmission_request_listener
         });
-        let sent_notification_fut = tokio::spawn(async move {
+        spawn_future(async move {
             sent_notification_listener.run().await;
             error!("The sent notification listener has finished execution!");
-            sent_notification_listener
         });
-        let action_controller_fut = tokio::spawn(async move {
+        spawn_future(async move {
             action_controller.run().await;
             error!("The controller has finished execution!");
-            action_controller
         });
-
-        // technically we don't have to bring `AcknowledgementController` back to a valid state
-        // but we can do it, so why not? Perhaps it might be useful if we wanted to allow
-        // for restarts of certain modules without killing the entire process.
-        self.acknowledgement_listener = Some(ack_listener_fut.await.unwrap());
-        self.input_message_listener = Some(input_listener_fut.await.unwrap());
-        self.retransmission_request_listener = Some(retransmission_req_fut.await.unwrap());
-        self.sent_notification_listener = Some(sent_notification_fut.await.unwrap());
-        self.action_controller = Some(action_controller_fut.await.unwrap());
-    }
-
-    #[allow(dead_code)]
-    pub(super) fn start(mut self) -> JoinHandle<Self> {
-        tokio::spawn(async move {
-            self.run().await;
-            self
-        })
     }
 }
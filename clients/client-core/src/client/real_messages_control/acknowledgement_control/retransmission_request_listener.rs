@@ -4,6 +4,7 @@
 use super::action_controller::{Action, ActionSender};
 use super::PendingAcknowledgement;
 use super::RetransmissionRequestReceiver;
+use crate::client::gateway_failover::SelfAddressReceiver;
 use crate::client::{
     real_messages_control::real_traffic_stream::{BatchRealMessageSender, RealMessage},
     topology_control::TopologyAccessor,
@@ -22,6 +23,7 @@ where
 {
     ack_key: Arc<AckKey>,
     ack_recipient: Recipient,
+    self_address: SelfAddressReceiver,
     message_preparer: MessagePreparer<R>,
     action_sender: ActionSender,
     real_message_sender: BatchRealMessageSender,
@@ -35,16 +37,18 @@ where
 {
     pub(super) fn new(
         ack_key: Arc<AckKey>,
-        ack_recipient: Recipient,
+        self_address: SelfAddressReceiver,
         message_preparer: MessagePreparer<R>,
         action_sender: ActionSender,
         real_message_sender: BatchRealMessageSender,
         request_receiver: RetransmissionRequestReceiver,
         topology_access: TopologyAccessor,
     ) -> Self {
+        let ack_recipient = *self_address.borrow();
         RetransmissionRequestListener {
             ack_key,
             ack_recipient,
+            self_address,
             message_preparer,
             action_sender,
             real_message_sender,
@@ -53,7 +57,16 @@ where
         }
     }
 
+    /// Makes sure our current address is used for all acknowledgements,
+    /// as it changes whenever the client switches over to a different gateway.
+    fn refresh_self_address(&mut self) {
+        self.ack_recipient = *self.self_address.borrow();
+        self.message_preparer.set_sender_address(self.ack_recipient);
+    }
+
     async fn on_retransmission_request(&mut self, timed_out_ack: Weak<PendingAcknowledgement>) {
+        self.refresh_self_address();
+
         let timed_out_ack = match timed_out_ack.upgrade() {
             Some(timed_out_ack) => timed_out_ack,
             None => {
@@ -66,9 +79,11 @@ where
         let frag_id = chunk_clone.fragment_identifier();
 
         let topology_permit = self.topology_access.get_read_permit().await;
-        let topology_ref = match topology_permit
-            .try_get_valid_topology_ref(&self.ack_recipient, Some(packet_recipient))
-        {
+        let topology_ref = match topology_permit.try_get_valid_topology_ref(
+            &self.ack_recipient,
+            Some(packet_recipient),
+            timed_out_ack.mix_hops,
+        ) {
             Some(topology_ref) => topology_ref,
             None => {
                 warn!("Could not retransmit the packet - the network topology is invalid");
@@ -82,7 +97,13 @@ where
 
         let prepared_fragment = self
             .message_preparer
-            .prepare_chunk_for_sending(chunk_clone, topology_ref, &self.ack_key, packet_recipient)
+            .prepare_chunk_for_sending_with_mix_hops(
+                chunk_clone,
+                topology_ref,
+                &self.ack_key,
+                packet_recipient,
+                timed_out_ack.mix_hops,
+            )
             .await
             .unwrap();
 
@@ -8,26 +8,28 @@
 use self::{
     acknowledgement_control::AcknowledgementController, real_traffic_stream::OutQueueControl,
 };
+use crate::client::gateway_failover::SelfAddressReceiver;
+use crate::client::metrics::ClientMetrics;
 use crate::client::real_messages_control::acknowledgement_control::AcknowledgementControllerConnectors;
+use crate::client::received_reply_surbs::ReceivedReplySurbsMap;
 use crate::client::reply_key_storage::ReplyKeyStorage;
 use crate::client::{
     inbound_messages::InputMessageReceiver, mix_traffic::BatchMixMessageSender,
     topology_control::TopologyAccessor,
 };
+use crate::runtime::spawn_future;
 use futures::channel::mpsc;
 use gateway_client::AcknowledgementReceiver;
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
-use nymsphinx::addressing::clients::Recipient;
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::task::JoinHandle;
 
 mod acknowledgement_control;
 mod real_traffic_stream;
 
-// TODO: ack_key and self_recipient shouldn't really be part of this config
+// TODO: ack_key and self_address shouldn't really be part of this config
 pub struct Config {
     /// Key used to decrypt contents of received SURBAcks
     ack_key: Arc<AckKey>,
@@ -38,8 +40,13 @@ pub struct Config {
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
 
-    /// Address of `this` client.
-    self_recipient: Recipient,
+    /// Maximum number of times a single packet is going to be retransmitted before giving up
+    /// on delivering the entire message. If not set, only tracked messages are given up on.
+    maximum_retransmissions: Option<u32>,
+
+    /// Address of `this` client. It changes whenever the client switches over to one of its
+    /// backup gateways.
+    self_address: SelfAddressReceiver,
 
     /// Average delay between sending subsequent packets from this client.
     average_message_sending_delay: Duration,
@@ -49,26 +56,52 @@ pub struct Config {
 
     /// Average delay an acknowledgement packet is going to get delayed at a single mixnode.
     average_ack_delay_duration: Duration,
+
+    /// Number of mix hops the packets are going to take, unless specified otherwise
+    /// for a particular message.
+    num_mix_hops: u8,
+
+    /// Minimum number of reply SURBs of an anonymous sender this client wants to keep available.
+    minimum_reply_surb_storage_threshold: u32,
+
+    /// Maximum number of reply SURBs this client is going to request or send in a single message.
+    maximum_reply_surb_request_size: u32,
+
+    /// Counters of this client exposed under the metrics endpoint.
+    metrics: ClientMetrics,
 }
 
 impl Config {
+    // at this point I'm not entirely sure how to deal with this warning without
+    // some considerable refactoring
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ack_key: Arc<AckKey>,
         ack_wait_multiplier: f64,
         ack_wait_addition: Duration,
+        maximum_retransmissions: Option<u32>,
         average_ack_delay_duration: Duration,
         average_message_sending_delay: Duration,
         average_packet_delay_duration: Duration,
-        self_recipient: Recipient,
+        num_mix_hops: u8,
+        minimum_reply_surb_storage_threshold: u32,
+        maximum_reply_surb_request_size: u32,
+        self_address: SelfAddressReceiver,
+        metrics: ClientMetrics,
     ) -> Self {
         Config {
             ack_key,
             ack_wait_addition,
             ack_wait_multiplier,
-            self_recipient,
+            maximum_retransmissions,
+            self_address,
             average_message_sending_delay,
             average_packet_delay_duration,
             average_ack_delay_duration,
+            num_mix_hops,
+            minimum_reply_surb_storage_threshold,
+            maximum_reply_surb_request_size,
+            metrics,
         }
     }
 }
@@ -91,6 +124,7 @@ impl RealMessagesController<OsRng> {
         mix_sender: BatchMixMessageSender,
         topology_access: TopologyAccessor,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
     ) -> Self {
         let rng = OsRng;
 
@@ -109,6 +143,10 @@ impl RealMessagesController<OsRng> {
             config.ack_wait_multiplier,
             config.average_ack_delay_duration,
             config.average_packet_delay_duration,
+            config.num_mix_hops,
+            config.maximum_retransmissions,
+            config.minimum_reply_surb_storage_threshold,
+            config.maximum_reply_surb_request_size,
         );
 
         let ack_control = AcknowledgementController::new(
@@ -116,15 +154,18 @@ impl RealMessagesController<OsRng> {
             rng,
             topology_access.clone(),
             Arc::clone(&config.ack_key),
-            config.self_recipient,
+            config.self_address.clone(),
             reply_key_storage,
+            received_reply_surbs,
             ack_controller_connectors,
+            config.metrics.ack_retransmissions.clone(),
         );
 
         let out_queue_config = real_traffic_stream::Config::new(
             config.average_ack_delay_duration,
             config.average_packet_delay_duration,
             config.average_message_sending_delay,
+            config.num_mix_hops,
         );
 
         let out_queue_control = OutQueueControl::new(
@@ -134,7 +175,7 @@ impl RealMessagesController<OsRng> {
             mix_sender,
             real_message_receiver,
             rng,
-            config.self_recipient,
+            config.self_address,
             topology_access,
         );
 
@@ -144,35 +185,17 @@ impl RealMessagesController<OsRng> {
         }
     }
 
-    pub(super) async fn run(&mut self) {
+    pub fn start(mut self) {
         let mut out_queue_control = self.out_queue_control.take().unwrap();
-        let mut ack_control = self.ack_control.take().unwrap();
+        let ack_control = self.ack_control.take().unwrap();
 
         // the below are log messages are errors as at the current stage we do not expect any of
         // the task to ever finish. This will of course change once we introduce
         // graceful shutdowns.
-        let out_queue_control_fut = tokio::spawn(async move {
+        spawn_future(async move {
             out_queue_control.run_out_queue_control().await;
             error!("The out queue controller has finished execution!");
-            out_queue_control
         });
-        let ack_control_fut = tokio::spawn(async move {
-            ack_control.run().await;
-            error!("The acknowledgement controller has finished execution!");
-            ack_control
-        });
-
-        // technically we don't have to bring `RealMessagesController` back to a valid state
-        // but we can do it, so why not? Perhaps it might be useful if we wanted to allow
-        // for restarts of certain modules without killing the entire process.
-        self.out_queue_control = Some(out_queue_control_fut.await.unwrap());
-        self.ack_control = Some(ack_control_fut.await.unwrap());
-    }
-
-    pub fn start(mut self) -> JoinHandle<Self> {
-        tokio::spawn(async move {
-            self.run().await;
-            self
-        })
+        ack_control.start();
     }
 }
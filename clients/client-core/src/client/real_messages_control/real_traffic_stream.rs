@@ -1,15 +1,17 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::gateway_failover::SelfAddressReceiver;
+use crate::client::inbound_messages::MessageSentNotifier;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::real_messages_control::acknowledgement_control::SentPacketNotificationSender;
 use crate::client::topology_control::TopologyAccessor;
+use crate::runtime::{self, Sleep};
 use futures::channel::mpsc;
 use futures::task::{Context, Poll};
 use futures::{Future, Stream, StreamExt};
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
-use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::chunking::fragment::FragmentIdentifier;
 use nymsphinx::cover::generate_loop_cover_packet;
 use nymsphinx::forwarding::packet::MixPacket;
@@ -17,9 +19,9 @@ use nymsphinx::utils::sample_poisson_duration;
 use rand::{CryptoRng, Rng};
 use std::collections::VecDeque;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::time;
 
 /// Configurable parameters of the `OutQueueControl`
 pub(crate) struct Config {
@@ -31,6 +33,9 @@ pub(crate) struct Config {
 
     /// Average delay between sending subsequent packets.
     average_message_sending_delay: Duration,
+
+    /// Number of mix hops the cover packets are going to take.
+    num_mix_hops: u8,
 }
 
 impl Config {
@@ -38,11 +43,13 @@ impl Config {
         average_ack_delay: Duration,
         average_packet_delay: Duration,
         average_message_sending_delay: Duration,
+        num_mix_hops: u8,
     ) -> Self {
         Config {
             average_ack_delay,
             average_packet_delay,
             average_message_sending_delay,
+            num_mix_hops,
         }
     }
 }
@@ -62,7 +69,7 @@ where
 
     /// Internal state, determined by `average_message_sending_delay`,
     /// used to keep track of when a next packet should be sent out.
-    next_delay: Pin<Box<time::Sleep>>,
+    next_delay: Pin<Box<Sleep>>,
 
     /// Channel used for sending prepared sphinx packets to `MixTrafficController` that sends them
     /// out to the network without any further delays.
@@ -72,8 +79,9 @@ where
     /// before being sent out into the network.
     real_receiver: BatchRealMessageReceiver,
 
-    /// Represents full address of this client.
-    our_full_destination: Recipient,
+    /// Represents full address of this client. It changes whenever the client switches over
+    /// to one of its backup gateways.
+    our_full_destination: SelfAddressReceiver,
 
     /// Instance of a cryptographically secure random number generator.
     rng: R,
@@ -85,9 +93,41 @@ where
     received_buffer: VecDeque<RealMessage>,
 }
 
+/// Counts the packets of a single message leaving the out queue in order to notify its sender
+/// once all of them got sent.
+///
+/// It is shared between all `RealMessage`s of the message.
+#[derive(Debug)]
+pub(crate) struct MessageSentTracker {
+    /// Number of packets of the message that have not been sent yet.
+    remaining_packets: AtomicUsize,
+
+    /// Notifier of the sender of the message. It is taken out once all packets got sent.
+    notifier: Mutex<Option<MessageSentNotifier>>,
+}
+
+impl MessageSentTracker {
+    pub(crate) fn new(num_packets: usize, notifier: MessageSentNotifier) -> Self {
+        MessageSentTracker {
+            remaining_packets: AtomicUsize::new(num_packets),
+            notifier: Mutex::new(Some(notifier)),
+        }
+    }
+
+    /// Marks one of the packets of the message as sent.
+    fn packet_sent(&self) {
+        if self.remaining_packets.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if let Some(notifier) = self.notifier.lock().unwrap().take() {
+                notifier.notify()
+            }
+        }
+    }
+}
+
 pub(crate) struct RealMessage {
     mix_packet: MixPacket,
     fragment_id: FragmentIdentifier,
+    sent_tracker: Option<Arc<MessageSentTracker>>,
 }
 
 impl RealMessage {
@@ -95,8 +135,14 @@ impl RealMessage {
         RealMessage {
             mix_packet,
             fragment_id,
+            sent_tracker: None,
         }
     }
+
+    pub(crate) fn with_sent_tracker(mut self, sent_tracker: Arc<MessageSentTracker>) -> Self {
+        self.sent_tracker = Some(sent_tracker);
+        self
+    }
 }
 
 // messages are already prepared, etc. the real point of it is to forward it to mix_traffic
@@ -172,14 +218,14 @@ where
         mix_tx: BatchMixMessageSender,
         real_receiver: BatchRealMessageReceiver,
         rng: R,
-        our_full_destination: Recipient,
+        our_full_destination: SelfAddressReceiver,
         topology_access: TopologyAccessor,
     ) -> Self {
         OutQueueControl {
             config,
             ack_key,
             sent_notifier,
-            next_delay: Box::pin(time::sleep(Default::default())),
+            next_delay: Box::pin(runtime::sleep(Default::default())),
             mix_tx,
             real_receiver,
             our_full_destination,
@@ -200,16 +246,19 @@ where
     async fn on_message(&mut self, next_message: StreamMessage) {
         trace!("created new message");
 
+        let mut message_sent_tracker = None;
         let next_message = match next_message {
             StreamMessage::Cover => {
                 // TODO for way down the line: in very rare cases (during topology update) we might have
                 // to wait a really tiny bit before actually obtaining the permit hence messing with our
                 // poisson delay, but is it really a problem?
+                let our_full_destination = *self.our_full_destination.borrow();
                 let topology_permit = self.topology_access.get_read_permit().await;
                 // the ack is sent back to ourselves (and then ignored)
                 let topology_ref_option = topology_permit.try_get_valid_topology_ref(
-                    &self.our_full_destination,
-                    Some(&self.our_full_destination),
+                    &our_full_destination,
+                    Some(&our_full_destination),
+                    self.config.num_mix_hops,
                 );
                 if topology_ref_option.is_none() {
                     warn!(
@@ -223,15 +272,22 @@ where
                     &mut self.rng,
                     topology_ref,
                     &*self.ack_key,
-                    &self.our_full_destination,
+                    &our_full_destination,
                     self.config.average_ack_delay,
                     self.config.average_packet_delay,
+                    self.config.num_mix_hops,
                 )
                 .expect("Somehow failed to generate a loop cover message with a valid topology")
             }
             StreamMessage::Real(real_message) => {
-                self.sent_notify(real_message.fragment_id);
-                real_message.mix_packet
+                let RealMessage {
+                    mix_packet,
+                    fragment_id,
+                    sent_tracker,
+                } = *real_message;
+                self.sent_notify(fragment_id);
+                message_sent_tracker = sent_tracker;
+                mix_packet
             }
         };
 
@@ -241,18 +297,23 @@ where
         // in either case there's no recovery and we can only panic
         self.mix_tx.unbounded_send(vec![next_message]).unwrap();
 
+        // if it was the last remaining packet of the message, its creator is going to be notified
+        if let Some(tracker) = message_sent_tracker {
+            tracker.packet_sent()
+        }
+
         // JS: Not entirely sure why or how it fixes stuff, but without the yield call,
         // the UnboundedReceiver [of mix_rx] will not get a chance to read anything
         // JS2: Basically it was the case that with high enough rate, the stream had already a next value
         // ready and hence was immediately re-scheduled causing other tasks to be starved;
         // yield makes it go back the scheduling queue regardless of its value availability
-        tokio::task::yield_now().await;
+        runtime::yield_now().await;
     }
 
     // Send messages at certain rate and if no real traffic is available, send cover message.
     async fn run_normal_out_queue(&mut self) {
         // we should set initial delay only when we actually start the stream
-        self.next_delay = Box::pin(time::sleep(sample_poisson_duration(
+        self.next_delay = Box::pin(runtime::sleep(sample_poisson_duration(
             &mut self.rng,
             self.config.average_message_sending_delay,
         )));
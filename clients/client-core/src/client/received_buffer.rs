@@ -1,21 +1,28 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::inbound_messages::{InputMessage, InputMessageSender};
+use crate::client::received_reply_surbs::ReceivedReplySurbsMap;
 use crate::client::reply_key_storage::ReplyKeyStorage;
+use crate::runtime::spawn_future;
 use crypto::asymmetric::encryption;
-use crypto::symmetric::stream_cipher;
 use crypto::Digest;
 use futures::channel::mpsc;
 use futures::lock::Mutex;
 use futures::StreamExt;
 use gateway_client::MixnetMessageReceiver;
 use log::*;
-use nymsphinx::anonymous_replies::{encryption_key::EncryptionKeyDigest, SurbEncryptionKey};
-use nymsphinx::params::{ReplySurbEncryptionAlgorithm, ReplySurbKeyDigestAlgorithm};
-use nymsphinx::receiver::{MessageReceiver, MessageRecoveryError, ReconstructedMessage};
+use nymsphinx::anonymous_replies::{
+    encryption_key::EncryptionKeyDigest, AnonymousSenderTag, ReplyContent, ReplySurb,
+    SurbEncryptionKey,
+};
+use nymsphinx::chunking::fragment::Fragment;
+use nymsphinx::params::ReplySurbKeyDigestAlgorithm;
+use nymsphinx::receiver::{
+    MessageReceiver, MessageRecoveryError, ReconstructedMessage, RecoveredMessage,
+};
 use std::collections::HashSet;
 use std::sync::Arc;
-use tokio::task::JoinHandle;
 
 // Buffer Requests to say "hey, send any reconstructed messages to this channel"
 // or to say "hey, I'm going offline, don't send anything more to me. Just buffer them instead"
@@ -42,7 +49,63 @@ struct ReceivedMessagesBufferInner {
 }
 
 impl ReceivedMessagesBufferInner {
-    fn process_received_fragment(&mut self, raw_fragment: Vec<u8>) -> Option<ReconstructedMessage> {
+    fn mark_sets_as_reconstructed(&mut self, used_sets: Vec<i32>) {
+        for set_id in used_sets {
+            if !self.recently_reconstructed.insert(set_id) {
+                // or perhaps we should even panic at this point?
+                error!("Reconstructed another message containing already used set id!")
+            }
+        }
+    }
+
+    fn is_recently_reconstructed(&self, fragment: &Fragment) -> bool {
+        if self.recently_reconstructed.contains(&fragment.id()) {
+            debug!("Received a chunk of already re-assembled message ({:?})! It probably got here because the ack got lost", fragment.id());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn process_received_reply(
+        &mut self,
+        reply_ciphertext: &[u8],
+        reply_key: SurbEncryptionKey,
+    ) -> Option<ReplyContent> {
+        let fragment_data = self
+            .message_receiver
+            .recover_reply_plaintext(&reply_key, reply_ciphertext);
+
+        let fragment = match self.message_receiver.recover_fragment(&fragment_data) {
+            Err(e) => {
+                warn!("failed to recover reply fragment from raw data: {:?}. The whole underlying reply might be corrupted and unrecoverable!", e);
+                return None;
+            }
+            Ok(frag) => frag,
+        };
+
+        if self.is_recently_reconstructed(&fragment) {
+            return None;
+        }
+
+        match self.message_receiver.insert_new_reply_fragment(fragment) {
+            Err(MessageRecoveryError::MalformedReconstructedMessage(message_sets)) => {
+                warn!("Received reply was malformed!");
+                self.mark_sets_as_reconstructed(message_sets);
+                None
+            }
+            Err(_) => unreachable!(
+                "no other error kind should have been returned here! If so, it's a bug!"
+            ),
+            Ok(Some((content, used_sets))) => {
+                self.mark_sets_as_reconstructed(used_sets);
+                Some(content)
+            }
+            Ok(None) => None,
+        }
+    }
+
+    fn process_received_fragment(&mut self, raw_fragment: Vec<u8>) -> Option<RecoveredMessage> {
         let fragment_data = match self
             .message_receiver
             .recover_plaintext(self.local_encryption_keypair.private_key(), raw_fragment)
@@ -67,8 +130,7 @@ impl ReceivedMessagesBufferInner {
             Ok(frag) => frag,
         };
 
-        if self.recently_reconstructed.contains(&fragment.id()) {
-            debug!("Received a chunk of already re-assembled message ({:?})! It probably got here because the ack got lost", fragment.id());
+        if self.is_recently_reconstructed(&fragment) {
             return None;
         }
 
@@ -77,12 +139,7 @@ impl ReceivedMessagesBufferInner {
             Err(err) => match err {
                 MessageRecoveryError::MalformedReconstructedMessage(message_sets) => {
                     // TODO: should we really insert reconstructed sets? could this be abused for some attack?
-                    for set_id in message_sets {
-                        if !self.recently_reconstructed.insert(set_id) {
-                            // or perhaps we should even panic at this point?
-                            error!("Reconstructed another message containing already used set id!")
-                        }
-                    }
+                    self.mark_sets_as_reconstructed(message_sets);
                     None
                 }
                 _ => unreachable!(
@@ -90,14 +147,9 @@ impl ReceivedMessagesBufferInner {
                 ),
             },
             Ok(reconstruction_result) => match reconstruction_result {
-                Some((reconstructed_message, used_sets)) => {
-                    for set_id in used_sets {
-                        if !self.recently_reconstructed.insert(set_id) {
-                            // or perhaps we should even panic at this point?
-                            error!("Reconstructed another message containing already used set id!")
-                        }
-                    }
-                    Some(reconstructed_message)
+                Some((recovered_message, used_sets)) => {
+                    self.mark_sets_as_reconstructed(used_sets);
+                    Some(recovered_message)
                 }
                 None => None,
             },
@@ -114,12 +166,21 @@ struct ReceivedMessagesBuffer {
     /// Storage containing keys to all [`ReplySURB`]s ever sent out that we did not receive back.
     // There's no need to put it behind a Mutex since it's already properly concurrent
     reply_key_storage: ReplyKeyStorage,
+
+    /// Storage of reply SURBs received from anonymous senders alongside replies waiting for them.
+    received_reply_surbs: ReceivedReplySurbsMap,
+
+    /// Channel used for sending requests for additional reply SURBs and replies that were
+    /// waiting for them back to the input message listener.
+    input_sender: InputMessageSender,
 }
 
 impl ReceivedMessagesBuffer {
     fn new(
         local_encryption_keypair: Arc<encryption::KeyPair>,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
+        input_sender: InputMessageSender,
     ) -> Self {
         ReceivedMessagesBuffer {
             inner: Arc::new(Mutex::new(ReceivedMessagesBufferInner {
@@ -130,6 +191,8 @@ impl ReceivedMessagesBuffer {
                 recently_reconstructed: HashSet::new(),
             })),
             reply_key_storage,
+            received_reply_surbs,
+            input_sender,
         }
     }
 
@@ -177,26 +240,78 @@ impl ReceivedMessagesBuffer {
         self.inner.lock().await.messages.extend(msgs)
     }
 
-    fn process_received_reply(
-        reply_ciphertext: &[u8],
-        reply_key: SurbEncryptionKey,
-    ) -> Option<ReconstructedMessage> {
-        let zero_iv = stream_cipher::zero_iv::<ReplySurbEncryptionAlgorithm>();
+    fn send_input_message(&self, message: InputMessage) {
+        if let Err(err) = self.input_sender.unbounded_send(message) {
+            error!("The input message listener has gone offline - {:?}", err);
+        }
+    }
 
-        let mut reply_msg = stream_cipher::decrypt::<ReplySurbEncryptionAlgorithm>(
-            reply_key.inner(),
-            &zero_iv,
-            reply_ciphertext,
+    /// Stores reply SURBs received from an anonymous sender and sends out all replies
+    /// that were waiting for them.
+    fn store_received_reply_surbs(
+        &self,
+        sender_tag: AnonymousSenderTag,
+        reply_surbs: Vec<ReplySurb>,
+    ) {
+        debug!(
+            "Received {} reply SURBs from {}",
+            reply_surbs.len(),
+            sender_tag
         );
-        if let Err(err) = MessageReceiver::remove_padding(&mut reply_msg) {
-            warn!("Received reply had malformed padding! - {:?}", err);
-            None
-        } else {
+        for pending in self
+            .received_reply_surbs
+            .insert_surbs(sender_tag, reply_surbs)
+        {
+            self.send_input_message(InputMessage::TaggedReply {
+                sender_tag,
+                data: pending.data,
+                sent_notifier: pending.sent_notifier,
+            })
+        }
+    }
+
+    fn handle_recovered_message(
+        &self,
+        recovered_message: RecoveredMessage,
+    ) -> Option<ReconstructedMessage> {
+        match recovered_message {
+            RecoveredMessage::Message {
+                message,
+                reply_surbs,
+            } => {
+                if let Some(sender_tag) = message.sender_tag {
+                    if !reply_surbs.is_empty() {
+                        self.store_received_reply_surbs(sender_tag, reply_surbs)
+                    }
+                }
+                Some(message)
+            }
+            RecoveredMessage::AdditionalReplySurbs {
+                sender_tag,
+                reply_surbs,
+            } => {
+                self.store_received_reply_surbs(sender_tag, reply_surbs);
+                None
+            }
+        }
+    }
+
+    fn handle_reply_content(&self, content: ReplyContent) -> Option<ReconstructedMessage> {
+        match content {
             // TODO: perhaps having to say it doesn't have a surb an indication the type should be changed?
-            Some(ReconstructedMessage {
-                message: reply_msg,
+            ReplyContent::Data(message) => Some(ReconstructedMessage {
+                message,
                 reply_surb: None,
-            })
+                sender_tag: None,
+            }),
+            ReplyContent::SurbRequest { sender_tag, amount } => {
+                debug!(
+                    "Received a request for {} additional reply SURBs for {}",
+                    amount, sender_tag
+                );
+                self.send_input_message(InputMessage::AdditionalReplySurbs { sender_tag, amount });
+                None
+            }
         }
     }
 
@@ -229,15 +344,18 @@ impl ReceivedMessagesBuffer {
                 .get_and_remove_encryption_key(possible_key_digest)
                 .expect("storage operation failed!")
             {
-                if let Some(completed_message) = Self::process_received_reply(
-                    &msg[reply_surb_digest_size..],
-                    reply_encryption_key,
-                ) {
+                if let Some(completed_message) = inner_guard
+                    .process_received_reply(&msg[reply_surb_digest_size..], reply_encryption_key)
+                    .and_then(|content| self.handle_reply_content(content))
+                {
                     completed_messages.push(completed_message)
                 }
             } else {
                 // otherwise - it's a 'normal' message
-                if let Some(completed_message) = inner_guard.process_received_fragment(msg) {
+                if let Some(completed_message) = inner_guard
+                    .process_received_fragment(msg)
+                    .and_then(|recovered| self.handle_recovered_message(recovered))
+                {
                     completed_messages.push(completed_message)
                 }
             }
@@ -290,8 +408,8 @@ impl RequestReceiver {
         }
     }
 
-    fn start(mut self) -> JoinHandle<()> {
-        tokio::spawn(async move {
+    fn start(mut self) {
+        spawn_future(async move {
             while let Some(request) = self.query_receiver.next().await {
                 match request {
                     ReceivedBufferMessage::ReceiverAnnounce(sender) => {
@@ -321,8 +439,8 @@ impl FragmentedMessageReceiver {
             mixnet_packet_receiver,
         }
     }
-    fn start(mut self) -> JoinHandle<()> {
-        tokio::spawn(async move {
+    fn start(mut self) {
+        spawn_future(async move {
             while let Some(new_messages) = self.mixnet_packet_receiver.next().await {
                 self.received_buffer.handle_new_received(new_messages).await;
             }
@@ -341,9 +459,15 @@ impl ReceivedMessagesBufferController {
         query_receiver: ReceivedBufferRequestReceiver,
         mixnet_packet_receiver: MixnetMessageReceiver,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
+        input_sender: InputMessageSender,
     ) -> Self {
-        let received_buffer =
-            ReceivedMessagesBuffer::new(local_encryption_keypair, reply_key_storage);
+        let received_buffer = ReceivedMessagesBuffer::new(
+            local_encryption_keypair,
+            reply_key_storage,
+            received_reply_surbs,
+            input_sender,
+        );
 
         ReceivedMessagesBufferController {
             fragmented_message_receiver: FragmentedMessageReceiver::new(
@@ -355,7 +479,6 @@ impl ReceivedMessagesBufferController {
     }
 
     pub fn start(self) {
-        // TODO: should we do anything with JoinHandle(s) returned by start methods?
         self.fragmented_message_receiver.start();
         self.request_receiver.start();
     }
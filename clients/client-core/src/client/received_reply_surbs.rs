@@ -0,0 +1,370 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::inbound_messages::MessageSentNotifier;
+use crate::runtime::current_timestamp;
+use log::*;
+use nymsphinx::anonymous_replies::{AnonymousSenderTag, ReplySurb};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long we wait for the additional reply SURBs we have requested before asking for them again.
+const REPLY_SURB_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum number of reply SURBs kept for a single sender. Once it's exceeded, the oldest ones
+/// are dropped.
+const MAX_STORED_SURBS_PER_SENDER: usize = 1000;
+
+/// Maximum number of replies to a single sender that can wait for additional reply SURBs.
+const MAX_PENDING_REPLIES_PER_SENDER: usize = 100;
+
+/// Reply that could not have been sent yet as there were not enough reply SURBs available for it.
+#[derive(Debug)]
+pub(crate) struct PendingReply {
+    pub(crate) data: Vec<u8>,
+    pub(crate) sent_notifier: Option<MessageSentNotifier>,
+}
+
+#[derive(Debug, Default)]
+struct ReceivedReplySurbsMapInner {
+    /// Reply SURBs alongside the timestamps of when they were received, oldest first.
+    reply_surbs: HashMap<AnonymousSenderTag, VecDeque<(u64, ReplySurb)>>,
+
+    /// Replies alongside the timestamps of when they were queued, oldest first.
+    pending_replies: HashMap<AnonymousSenderTag, VecDeque<(u64, PendingReply)>>,
+
+    /// Tags of all senders we have requested additional reply SURBs from alongside
+    /// the timestamps of the latest requests.
+    pending_requests: HashMap<AnonymousSenderTag, u64>,
+}
+
+/// Storage of all reply SURBs received from anonymous senders, alongside replies
+/// waiting for additional SURBs to arrive, keyed by the tags of said senders.
+///
+/// The SURBs (and replies) are only kept for as long as the senders keep the corresponding
+/// encryption keys, so that we would not attempt to use the ones that are already worthless.
+// Note: all operations are short and never held across `.await` so a simple mutex is sufficient
+#[derive(Debug, Clone)]
+pub struct ReceivedReplySurbsMap {
+    inner: Arc<Mutex<ReceivedReplySurbsMapInner>>,
+    maximum_surb_age: Duration,
+}
+
+impl ReceivedReplySurbsMap {
+    pub fn new(maximum_surb_age: Duration) -> Self {
+        ReceivedReplySurbsMap {
+            inner: Default::default(),
+            maximum_surb_age,
+        }
+    }
+
+    pub(crate) fn maximum_surb_age(&self) -> Duration {
+        self.maximum_surb_age
+    }
+
+    fn has_expired(&self, created_at: u64, now: u64) -> bool {
+        now.saturating_sub(created_at) > self.maximum_surb_age.as_secs()
+    }
+
+    /// Stores newly received reply SURBs of the particular sender and returns all replies
+    /// that were waiting for them.
+    pub(crate) fn insert_surbs(
+        &self,
+        sender_tag: AnonymousSenderTag,
+        surbs: Vec<ReplySurb>,
+    ) -> Vec<PendingReply> {
+        let now = current_timestamp();
+        let mut guard = self.inner.lock().unwrap();
+        guard.pending_requests.remove(&sender_tag);
+
+        let stored = guard.reply_surbs.entry(sender_tag).or_default();
+        stored.extend(surbs.into_iter().map(|surb| (now, surb)));
+        if stored.len() > MAX_STORED_SURBS_PER_SENDER {
+            let excess = stored.len() - MAX_STORED_SURBS_PER_SENDER;
+            debug!(
+                "dropping {} oldest reply SURBs of {} as there are too many of them",
+                excess, sender_tag
+            );
+            stored.drain(..excess);
+        }
+
+        guard
+            .pending_replies
+            .remove(&sender_tag)
+            .map(|replies| replies.into_iter().map(|(_, reply)| reply).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn available_surbs(&self, sender_tag: &AnonymousSenderTag) -> usize {
+        self.inner
+            .lock()
+            .unwrap()
+            .reply_surbs
+            .get(sender_tag)
+            .map(|surbs| surbs.len())
+            .unwrap_or_default()
+    }
+
+    /// Takes the specified number of reply SURBs of the sender out of the storage,
+    /// unless fewer of them are available.
+    pub(crate) fn take_surbs(
+        &self,
+        sender_tag: &AnonymousSenderTag,
+        amount: usize,
+    ) -> Option<Vec<ReplySurb>> {
+        let mut guard = self.inner.lock().unwrap();
+        let surbs = guard.reply_surbs.get_mut(sender_tag)?;
+        if surbs.len() < amount {
+            return None;
+        }
+        Some(surbs.drain(..amount).map(|(_, surb)| surb).collect())
+    }
+
+    pub(crate) fn has_pending_replies(&self, sender_tag: &AnonymousSenderTag) -> bool {
+        self.inner
+            .lock()
+            .unwrap()
+            .pending_replies
+            .contains_key(sender_tag)
+    }
+
+    /// Queues the reply until additional reply SURBs of the sender arrive. Returns false
+    /// if there are already too many replies waiting for them, in which case it's dropped.
+    pub(crate) fn insert_pending_reply(
+        &self,
+        sender_tag: AnonymousSenderTag,
+        reply: PendingReply,
+    ) -> bool {
+        let mut guard = self.inner.lock().unwrap();
+        let pending = guard.pending_replies.entry(sender_tag).or_default();
+        if pending.len() >= MAX_PENDING_REPLIES_PER_SENDER {
+            return false;
+        }
+        pending.push_back((current_timestamp(), reply));
+        true
+    }
+
+    /// Marks additional reply SURBs as requested from the sender. Returns false if there
+    /// is already such request in progress that has not timed out yet.
+    pub(crate) fn mark_surbs_requested(&self, sender_tag: AnonymousSenderTag) -> bool {
+        let now = current_timestamp();
+        let mut guard = self.inner.lock().unwrap();
+        if let Some(requested_at) = guard.pending_requests.get(&sender_tag) {
+            if now.saturating_sub(*requested_at) < REPLY_SURB_REQUEST_TIMEOUT.as_secs() {
+                return false;
+            }
+        }
+        guard.pending_requests.insert(sender_tag, now);
+        true
+    }
+
+    /// Clears the in-progress request for additional reply SURBs, for example if it failed
+    /// to get sent.
+    pub(crate) fn clear_surbs_request(&self, sender_tag: &AnonymousSenderTag) {
+        self.inner
+            .lock()
+            .unwrap()
+            .pending_requests
+            .remove(sender_tag);
+    }
+
+    /// Returns tags of all senders with replies waiting for additional reply SURBs that we are
+    /// able to request (again), alongside the total size of said replies.
+    pub(crate) fn senders_awaiting_surbs(&self) -> Vec<(AnonymousSenderTag, usize)> {
+        let now = current_timestamp();
+        let guard = self.inner.lock().unwrap();
+        guard
+            .pending_replies
+            .iter()
+            .filter(|(sender_tag, _)| {
+                let request_timed_out = guard
+                    .pending_requests
+                    .get(sender_tag)
+                    .map(|requested_at| {
+                        now.saturating_sub(*requested_at) >= REPLY_SURB_REQUEST_TIMEOUT.as_secs()
+                    })
+                    .unwrap_or(true);
+                let can_request = guard
+                    .reply_surbs
+                    .get(sender_tag)
+                    .map(|surbs| !surbs.is_empty())
+                    .unwrap_or_default();
+                request_timed_out && can_request
+            })
+            .map(|(sender_tag, replies)| {
+                let size = replies.iter().map(|(_, reply)| reply.data.len()).sum();
+                (*sender_tag, size)
+            })
+            .collect()
+    }
+
+    /// Removes all reply SURBs and pending replies older than the maximum SURB age.
+    pub(crate) fn remove_expired(&self) {
+        let now = current_timestamp();
+        let mut guard = self.inner.lock().unwrap();
+
+        let mut removed_surbs = 0;
+        for surbs in guard.reply_surbs.values_mut() {
+            while matches!(surbs.front(), Some((received_at, _)) if self.has_expired(*received_at, now))
+            {
+                surbs.pop_front();
+                removed_surbs += 1;
+            }
+        }
+        guard.reply_surbs.retain(|_, surbs| !surbs.is_empty());
+
+        let mut removed_replies = 0;
+        for replies in guard.pending_replies.values_mut() {
+            while matches!(replies.front(), Some((queued_at, _)) if self.has_expired(*queued_at, now))
+            {
+                // dropping the reply also drops its notifier, letting the sender know it has failed
+                replies.pop_front();
+                removed_replies += 1;
+            }
+        }
+        guard
+            .pending_replies
+            .retain(|_, replies| !replies.is_empty());
+
+        guard
+            .pending_requests
+            .retain(|_, requested_at| !self.has_expired(*requested_at, now));
+
+        if removed_surbs > 0 || removed_replies > 0 {
+            debug!(
+                "Removed {} expired reply SURBs and {} expired pending replies",
+                removed_surbs, removed_replies
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    const MAX_SURB_AGE: Duration = Duration::from_secs(60 * 60);
+
+    const DUMMY_SURB: &str = "CjfVbHbfAjbC3W1BvNHGXmM8KNAnDNYGaHMLqVDxRYeo352csAihstup9bvqXam4dTWgfHak6KYwL9STaxWJ47E8XFZbSEvs7hEsfCkxr6K9WJuSBPK84GDDEvad8ZAuMCoaXsAd5S2Lj9a5eYyzG4SL1jHzhSMni55LyJwumxo1ZTGZNXggxw1RREosvyzNrW9Rsi3owyPqLCwXpiei2tHZty8w8midVvg8vDa7ZEJD842CLv8D4ohynSG7gDpqTrhkRaqYAuz7dzqNbMXLJRM7v823Jn16fA1L7YQxmcaUdUigyRSgTdb4i9ebiLGSyJ1iDe6Acz613PQZh6Ua3bZ2zVKq3dSycpDm9ngarRK4zJrAaUxRkdih8YzW3BY4nL9eqkfKA4N1TWCLaRU7zpSaf8yMEwrAZReU3d5zLV8c5KBfa2w8R5anhQeBojduZEGEad8kkHuKU52Zg93FeWHvH1qgZaEJMHH4nN7gKXz9mvWDhYwyF4vt3Uy2NhCHC3N5pL1gMme27YcoPcTEia1fxKZtnt6rtEozzTrAgCJGswigkFbkafiV5QaJwLKTUxtzhkZ57eEuLPte9UvJHzhhXUQ2CV7R2BUkJjYZy3Zsx6YYvdYWiAFFkWUwNEGA4QpShUHciBfsQVHQ7pN41YcyYUhbywQDFnTVgEmdUZ1XCBi3gyK5U3tDQmFzP1u9m3mWrUA8qB9mRDE7ptNDm5c3c1458L6uXLUth7sdMaa1Was5LCmCdmNDtvNpCDAEt1in6q6mrZFR85aCSU9b1baNGwZoCqPpPvydkVe63gXWoi8ebvdyxARrqACFrSB3ZdY3uJBw8CTMNkKK6MvcefMkSVVsbLd36TQAtYSCqrpiMc5dQuKcEu5QfciwvWYXYx8WFNAgKwP2mv49KCTvfozNDUCbjzDwSx92Zv5zjG8HbFpB13bY9UZGeyTPvv7gGxCzjGjJGbW6FRAheRQaaje5fUgCNM95Tv7wBmAMRHHFgWafeK1sdFH7dtCX9u898HucGTaboSKLsVh8J78gbbkHErwjMh7y9YRkceq5TTYS5da4kHnyNKYWSbxgZrmFg44XGKoeYcqoHB3XTZrdsf7F5fFeNwnihkmADvhAcaxXUmVqq4rQFZH84a1iC3WBWXYcqiZH2L7ujGWV7mMDT4HBEerDYjc8rNY4xGTPfivCrBCJW1i14aqW8xRdsdgTM88eTksvC3WPJLJ7iMzfKXeL7fMW1Ek6QGyQtLBW98vEESpdcDg6DeZ5rMz6VqjTGGqcCaFGfHoqtfxMDaBAEsyQ8h7XDX6dg1wq9wH6j4Tw7Tj1MEv1b8uj5NJkozZdzVdYA2QyE2Dp8vuurQG6uVdTDNww2d88RBQ8sVgjxN8gR45y4woJLhFAaNTAtrY6wDTxyXST13ni6oyqdYxjFVk9Am4v3DzH7Y2K8iRVSHfTk4FRbPULyaeK6wt2anvMJH1XdvVRgc14h67MnBxMgMD1UFk8AErN7CDj26fppe3c5G6KozJe4cSqQUGbBjVzBnrHCruqrfZBn5hNZHTV37bQiomqhRQXohxhuKEnNrGbAe1xNvJr9X";
+
+    fn dummy_surbs(n: usize) -> Vec<ReplySurb> {
+        (0..n)
+            .map(|_| ReplySurb::from_base58_string(DUMMY_SURB).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn surbs_are_only_taken_if_enough_are_available() {
+        let storage = ReceivedReplySurbsMap::new(MAX_SURB_AGE);
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let other_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        assert!(storage.insert_surbs(sender_tag, dummy_surbs(3)).is_empty());
+        assert_eq!(storage.available_surbs(&sender_tag), 3);
+        assert_eq!(storage.available_surbs(&other_tag), 0);
+
+        assert!(storage.take_surbs(&sender_tag, 4).is_none());
+        assert!(storage.take_surbs(&other_tag, 1).is_none());
+        assert_eq!(storage.take_surbs(&sender_tag, 2).unwrap().len(), 2);
+        assert_eq!(storage.available_surbs(&sender_tag), 1);
+    }
+
+    #[test]
+    fn pending_replies_are_returned_once_surbs_arrive() {
+        let storage = ReceivedReplySurbsMap::new(MAX_SURB_AGE);
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        assert!(storage.mark_surbs_requested(sender_tag));
+        assert!(!storage.mark_surbs_requested(sender_tag));
+
+        assert!(storage.insert_pending_reply(
+            sender_tag,
+            PendingReply {
+                data: b"foomp".to_vec(),
+                sent_notifier: None,
+            },
+        ));
+        assert!(storage.has_pending_replies(&sender_tag));
+
+        let pending = storage.insert_surbs(sender_tag, dummy_surbs(1));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].data, b"foomp".to_vec());
+        assert!(!storage.has_pending_replies(&sender_tag));
+
+        // and the request is no longer in progress
+        assert!(storage.mark_surbs_requested(sender_tag));
+    }
+
+    fn dummy_reply() -> PendingReply {
+        PendingReply {
+            data: b"foomp".to_vec(),
+            sent_notifier: None,
+        }
+    }
+
+    #[test]
+    fn timed_out_surb_requests_can_be_retried() {
+        let storage = ReceivedReplySurbsMap::new(MAX_SURB_AGE);
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        storage.insert_surbs(sender_tag, dummy_surbs(1));
+        assert!(storage.insert_pending_reply(sender_tag, dummy_reply()));
+        assert_eq!(storage.senders_awaiting_surbs(), vec![(sender_tag, 5)]);
+
+        assert!(storage.mark_surbs_requested(sender_tag));
+        assert!(storage.senders_awaiting_surbs().is_empty());
+
+        // pretend the request was sent a while ago
+        *storage
+            .inner
+            .lock()
+            .unwrap()
+            .pending_requests
+            .get_mut(&sender_tag)
+            .unwrap() -= REPLY_SURB_REQUEST_TIMEOUT.as_secs();
+        assert_eq!(storage.senders_awaiting_surbs(), vec![(sender_tag, 5)]);
+        assert!(storage.mark_surbs_requested(sender_tag));
+
+        // but we can't request anything without having any reply SURBs left
+        storage.take_surbs(&sender_tag, 1).unwrap();
+        storage.clear_surbs_request(&sender_tag);
+        assert!(storage.senders_awaiting_surbs().is_empty());
+    }
+
+    #[test]
+    fn pending_replies_are_bounded() {
+        let storage = ReceivedReplySurbsMap::new(MAX_SURB_AGE);
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        for _ in 0..MAX_PENDING_REPLIES_PER_SENDER {
+            assert!(storage.insert_pending_reply(sender_tag, dummy_reply()));
+        }
+        assert!(!storage.insert_pending_reply(sender_tag, dummy_reply()));
+
+        let pending = storage.insert_surbs(sender_tag, dummy_surbs(1));
+        assert_eq!(pending.len(), MAX_PENDING_REPLIES_PER_SENDER);
+    }
+
+    #[test]
+    fn expired_surbs_and_replies_are_removed() {
+        let storage = ReceivedReplySurbsMap::new(MAX_SURB_AGE);
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let other_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        storage.insert_surbs(sender_tag, dummy_surbs(2));
+        assert!(storage.insert_pending_reply(other_tag, dummy_reply()));
+        {
+            let mut guard = storage.inner.lock().unwrap();
+            let expired = current_timestamp() - MAX_SURB_AGE.as_secs() - 1;
+            guard.reply_surbs.get_mut(&sender_tag).unwrap()[0].0 = expired;
+            guard.pending_replies.get_mut(&other_tag).unwrap()[0].0 = expired;
+        }
+        storage.insert_surbs(sender_tag, dummy_surbs(1));
+
+        storage.remove_expired();
+        assert_eq!(storage.available_surbs(&sender_tag), 2);
+        assert!(!storage.has_pending_replies(&other_tag));
+    }
+}
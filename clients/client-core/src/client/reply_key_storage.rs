@@ -1,20 +1,55 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crypto::generic_array::typenum::Unsigned;
+use crate::runtime::{self, current_timestamp, spawn_future};
 use log::*;
-use nymsphinx::anonymous_replies::{
-    encryption_key::EncryptionKeyDigest, SurbEncryptionKey, SurbEncryptionKeySize,
-};
+use nymsphinx::anonymous_replies::{encryption_key::EncryptionKeyDigest, SurbEncryptionKey};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crypto::generic_array::typenum::Unsigned;
+#[cfg(not(target_arch = "wasm32"))]
+use nymsphinx::anonymous_replies::SurbEncryptionKeySize;
+#[cfg(not(target_arch = "wasm32"))]
+use std::convert::TryInto;
+#[cfg(not(target_arch = "wasm32"))]
+use std::mem::size_of;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use task::ShutdownListener;
+
+#[cfg(target_arch = "wasm32")]
+use std::collections::HashMap;
+#[cfg(target_arch = "wasm32")]
+use std::sync::{Arc, Mutex};
 
+/// Interval at which any modifications to the storage are flushed to the disk. Rather than
+/// flushing after every single insertion (and removal), the writes are batched together.
+#[cfg(not(target_arch = "wasm32"))]
+const FLUSH_EVERY_MS: u64 = 1_000;
+
+// note: in wasm the keys are only kept in memory, so no operation can ever fail
 #[derive(Debug)]
 pub enum ReplyKeyStorageError {
+    #[cfg(not(target_arch = "wasm32"))]
     DbReadError(sled::Error),
+    #[cfg(not(target_arch = "wasm32"))]
     DbWriteError(sled::Error),
+    #[cfg(not(target_arch = "wasm32"))]
     DbOpenError(sled::Error),
 }
 
+/// Basic information about the current state of the [`ReplyKeyStorage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplyKeyStorageStats {
+    /// Number of reply keys currently stored.
+    pub stored_keys: usize,
+
+    /// Size of the underlying database on the disk, in bytes.
+    pub size_on_disk: u64,
+}
+
 /// Permanent storage for keys in all sent [`ReplySURB`]
 ///
 /// Each sent out [`ReplySURB`] has a new key associated with it that is going to be used for
@@ -22,44 +57,115 @@ pub enum ReplyKeyStorageError {
 /// key to use for that purpose. We do it based on received `H(t)` which has to be included
 /// with each reply.
 /// Moreover, there is no restriction when the [`ReplySURB`] might get used so we need to
-/// have a permanent storage for all the keys that we might ever see in the future.
+/// have a permanent storage for the keys. However, to prevent the storage from growing without
+/// bound, each key is stored alongside its creation time and is removed once it becomes older
+/// than the specified maximum age, after which any reply using it is going to be ignored.
+///
+/// In wasm there is no persistent storage available, so the keys only live as long as the client.
 #[derive(Debug, Clone)]
 pub struct ReplyKeyStorage {
+    #[cfg(not(target_arch = "wasm32"))]
     db: sled::Db,
+    #[cfg(target_arch = "wasm32")]
+    keys: Arc<Mutex<HashMap<EncryptionKeyDigest, (u64, SurbEncryptionKey)>>>,
+    max_key_age: Duration,
+}
+
+impl ReplyKeyStorage {
+    fn has_expired(&self, created_at: u64, now: u64) -> bool {
+        now.saturating_sub(created_at) > self.max_key_age.as_secs()
+    }
+
+    // TOOD: perhaps we could also store some part of original message here too?
+    pub fn insert_encryption_key(
+        &mut self,
+        encryption_key: SurbEncryptionKey,
+    ) -> Result<(), ReplyKeyStorageError> {
+        self.insert_encryption_key_with_timestamp(encryption_key, current_timestamp())
+    }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl ReplyKeyStorage {
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReplyKeyStorageError> {
-        let db = match sled::open(path) {
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        max_key_age: Duration,
+    ) -> Result<Self, ReplyKeyStorageError> {
+        let db = match sled::Config::new()
+            .path(path)
+            .flush_every_ms(Some(FLUSH_EVERY_MS))
+            .open()
+        {
             Err(e) => return Err(ReplyKeyStorageError::DbOpenError(e)),
             Ok(db) => db,
         };
 
-        Ok(ReplyKeyStorage { db })
+        let storage = ReplyKeyStorage { db, max_key_age };
+        storage.upgrade_legacy_entries()?;
+        let removed = storage.remove_expired_keys()?;
+        if removed > 0 {
+            info!("Removed {} expired reply keys from the storage", removed);
+        }
+
+        Ok(storage)
+    }
+
+    // keys stored by the older versions of the client did not include their creation time.
+    // Since we have no idea when they were created, treat them as if they were created just now.
+    fn upgrade_legacy_entries(&self) -> Result<(), ReplyKeyStorageError> {
+        let now = current_timestamp();
+        for entry in self.db.iter() {
+            let (digest, raw_entry) = entry.map_err(ReplyKeyStorageError::DbReadError)?;
+            if raw_entry.len() == SurbEncryptionKeySize::USIZE {
+                let upgraded = Self::make_entry(now, raw_entry.as_ref());
+                self.db
+                    .insert(digest, upgraded)
+                    .map_err(ReplyKeyStorageError::DbWriteError)?;
+            }
+        }
+        Ok(())
+    }
+
+    // created_at || key
+    fn make_entry(created_at: u64, key_bytes: &[u8]) -> Vec<u8> {
+        created_at
+            .to_be_bytes()
+            .iter()
+            .chain(key_bytes.iter())
+            .cloned()
+            .collect()
     }
 
-    fn read_encryption_key(&self, raw_key: sled::IVec) -> SurbEncryptionKey {
-        let key_bytes_ref = raw_key.as_ref();
+    // created_at || key
+    fn read_entry(&self, raw_entry: sled::IVec) -> (u64, SurbEncryptionKey) {
+        let entry_bytes_ref = raw_entry.as_ref();
         // if this fails it means we have some database corruption and we
         // absolutely can't continue
 
-        if key_bytes_ref.len() != SurbEncryptionKeySize::USIZE {
+        if entry_bytes_ref.len() != size_of::<u64>() + SurbEncryptionKeySize::USIZE {
             error!("REPLY KEY STORAGE DATA CORRUPTION - ENCRYPTION KEY HAS INVALID LENGTH");
             panic!("REPLY KEY STORAGE DATA CORRUPTION - ENCRYPTION KEY HAS INVALID LENGTH");
         }
 
+        // this can't fail as we've just checked the length
+        let created_at =
+            u64::from_be_bytes(entry_bytes_ref[..size_of::<u64>()].try_into().unwrap());
+
         // this can only fail if the bytes have invalid length but we already asserted it
-        SurbEncryptionKey::try_from_bytes(key_bytes_ref).unwrap()
+        let key = SurbEncryptionKey::try_from_bytes(&entry_bytes_ref[size_of::<u64>()..]).unwrap();
+
+        (created_at, key)
     }
 
-    // TOOD: perhaps we could also store some part of original message here too?
-    pub fn insert_encryption_key(
+    fn insert_encryption_key_with_timestamp(
         &mut self,
         encryption_key: SurbEncryptionKey,
+        created_at: u64,
     ) -> Result<(), ReplyKeyStorageError> {
         let digest = encryption_key.compute_digest();
+        let entry = Self::make_entry(created_at, &encryption_key.to_bytes());
 
-        let insertion_result = match self.db.insert(digest, encryption_key.to_bytes()) {
+        match self.db.insert(digest, entry) {
             Err(e) => Err(ReplyKeyStorageError::DbWriteError(e)),
             Ok(existing_key) => {
                 if existing_key.is_some() {
@@ -67,11 +173,87 @@ impl ReplyKeyStorage {
                 };
                 Ok(())
             }
+        }
+    }
+
+    // Once we use key once, we do not expect to use it again
+    pub fn get_and_remove_encryption_key(
+        &self,
+        key_digest: EncryptionKeyDigest,
+    ) -> Result<Option<SurbEncryptionKey>, ReplyKeyStorageError> {
+        let existing_entry = match self.db.remove(key_digest) {
+            Err(e) => return Err(ReplyKeyStorageError::DbReadError(e)),
+            Ok(existing_entry) => existing_entry,
         };
 
-        // TODO: perhaps we could implement some batching mechanism to avoid frequent flushes?
-        self.db.flush().unwrap();
-        insertion_result
+        Ok(existing_entry.and_then(|existing_entry| {
+            let (created_at, key) = self.read_entry(existing_entry);
+            // the key might have expired in between the pruning runs
+            if self.has_expired(created_at, current_timestamp()) {
+                debug!("Received reply using an already expired key");
+                None
+            } else {
+                Some(key)
+            }
+        }))
+    }
+
+    /// Removes all keys older than the maximum key age. Returns the number of removed keys.
+    pub fn remove_expired_keys(&self) -> Result<usize, ReplyKeyStorageError> {
+        let now = current_timestamp();
+        let mut removed = 0;
+        for entry in self.db.iter() {
+            let (digest, raw_entry) = entry.map_err(ReplyKeyStorageError::DbReadError)?;
+            let (created_at, _) = self.read_entry(raw_entry);
+            if self.has_expired(created_at, now) {
+                self.db
+                    .remove(digest)
+                    .map_err(ReplyKeyStorageError::DbWriteError)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Explicitly flushes all pending writes to the disk.
+    pub fn flush(&self) -> Result<(), ReplyKeyStorageError> {
+        self.db
+            .flush()
+            .map(|_| ())
+            .map_err(ReplyKeyStorageError::DbWriteError)
+    }
+
+    pub fn stats(&self) -> Result<ReplyKeyStorageStats, ReplyKeyStorageError> {
+        Ok(ReplyKeyStorageStats {
+            stored_keys: self.db.len(),
+            size_on_disk: self
+                .db
+                .size_on_disk()
+                .map_err(ReplyKeyStorageError::DbReadError)?,
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ReplyKeyStorage {
+    pub fn new_ephemeral(max_key_age: Duration) -> Self {
+        ReplyKeyStorage {
+            keys: Default::default(),
+            max_key_age,
+        }
+    }
+
+    fn insert_encryption_key_with_timestamp(
+        &mut self,
+        encryption_key: SurbEncryptionKey,
+        created_at: u64,
+    ) -> Result<(), ReplyKeyStorageError> {
+        let digest = encryption_key.compute_digest();
+        let mut keys = self.keys.lock().unwrap();
+        if keys.insert(digest, (created_at, encryption_key)).is_some() {
+            panic!("HASH COLLISION DETECTED")
+        }
+        Ok(())
     }
 
     // Once we use key once, we do not expect to use it again
@@ -79,16 +261,197 @@ impl ReplyKeyStorage {
         &self,
         key_digest: EncryptionKeyDigest,
     ) -> Result<Option<SurbEncryptionKey>, ReplyKeyStorageError> {
-        let removal_result = match self.db.remove(key_digest) {
-            Err(e) => Err(ReplyKeyStorageError::DbReadError(e)),
-            Ok(existing_key) => {
-                Ok(existing_key.map(|existing_key| self.read_encryption_key(existing_key)))
+        let existing_entry = self.keys.lock().unwrap().remove(&key_digest);
+        Ok(existing_entry.and_then(|(created_at, key)| {
+            // the key might have expired in between the pruning runs
+            if self.has_expired(created_at, current_timestamp()) {
+                debug!("Received reply using an already expired key");
+                None
+            } else {
+                Some(key)
             }
-        };
+        }))
+    }
+
+    /// Removes all keys older than the maximum key age. Returns the number of removed keys.
+    pub fn remove_expired_keys(&self) -> Result<usize, ReplyKeyStorageError> {
+        let now = current_timestamp();
+        let mut keys = self.keys.lock().unwrap();
+        let stored = keys.len();
+        keys.retain(|_, (created_at, _)| !self.has_expired(*created_at, now));
+        Ok(stored - keys.len())
+    }
+
+    pub fn stats(&self) -> Result<ReplyKeyStorageStats, ReplyKeyStorageError> {
+        Ok(ReplyKeyStorageStats {
+            stored_keys: self.keys.lock().unwrap().len(),
+            size_on_disk: 0,
+        })
+    }
+}
+
+/// Periodically removes expired keys from the [`ReplyKeyStorage`].
+pub struct ReplyKeyStoragePruner {
+    storage: ReplyKeyStorage,
+    pruning_interval: Duration,
+}
+
+impl ReplyKeyStoragePruner {
+    pub fn new(storage: ReplyKeyStorage, pruning_interval: Duration) -> Self {
+        ReplyKeyStoragePruner {
+            storage,
+            pruning_interval,
+        }
+    }
+
+    async fn run(&mut self) {
+        loop {
+            runtime::sleep(self.pruning_interval).await;
+            match self.storage.remove_expired_keys() {
+                Err(err) => error!("Failed to remove expired reply keys - {:?}", err),
+                Ok(0) => trace!("There were no expired reply keys to remove"),
+                Ok(removed) => debug!("Removed {} expired reply keys", removed),
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn start(mut self) {
+        spawn_future(async move { self.run().await })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_with_shutdown(mut self, mut shutdown: ShutdownListener) {
+        spawn_future(async move {
+            tokio::select! {
+                _ = self.run() => (),
+                _ = shutdown.recv() => trace!("ReplyKeyStoragePruner: Received shutdown"),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    const MAX_KEY_AGE: Duration = Duration::from_secs(60 * 60);
+
+    fn new_key() -> SurbEncryptionKey {
+        SurbEncryptionKey::new(&mut OsRng)
+    }
+
+    #[test]
+    fn stored_key_can_be_retrieved_only_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = ReplyKeyStorage::load(dir.path(), MAX_KEY_AGE).unwrap();
+
+        let key = new_key();
+        let digest = key.compute_digest();
+        storage.insert_encryption_key(key.clone()).unwrap();
+
+        let retrieved = storage.get_and_remove_encryption_key(digest).unwrap();
+        assert_eq!(retrieved.unwrap().to_bytes(), key.to_bytes());
+        assert!(storage
+            .get_and_remove_encryption_key(digest)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn expired_keys_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = ReplyKeyStorage::load(dir.path(), MAX_KEY_AGE).unwrap();
+
+        let expired_key = new_key();
+        let expired_digest = expired_key.compute_digest();
+        let created_at = current_timestamp() - MAX_KEY_AGE.as_secs() - 1;
+        storage
+            .insert_encryption_key_with_timestamp(expired_key, created_at)
+            .unwrap();
+
+        let fresh_key = new_key();
+        let fresh_digest = fresh_key.compute_digest();
+        storage.insert_encryption_key(fresh_key).unwrap();
+
+        assert_eq!(storage.stats().unwrap().stored_keys, 2);
+        assert_eq!(storage.remove_expired_keys().unwrap(), 1);
+        assert_eq!(storage.stats().unwrap().stored_keys, 1);
+
+        assert!(storage
+            .get_and_remove_encryption_key(expired_digest)
+            .unwrap()
+            .is_none());
+        assert!(storage
+            .get_and_remove_encryption_key(fresh_digest)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn expired_key_is_not_returned_before_pruning() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = ReplyKeyStorage::load(dir.path(), MAX_KEY_AGE).unwrap();
+
+        let expired_key = new_key();
+        let expired_digest = expired_key.compute_digest();
+        let created_at = current_timestamp() - MAX_KEY_AGE.as_secs() - 1;
+        storage
+            .insert_encryption_key_with_timestamp(expired_key, created_at)
+            .unwrap();
+
+        assert!(storage
+            .get_and_remove_encryption_key(expired_digest)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn keys_are_recovered_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let key = new_key();
+        let digest = key.compute_digest();
+        let expired_key = new_key();
+        let expired_digest = expired_key.compute_digest();
+        {
+            let mut storage = ReplyKeyStorage::load(dir.path(), MAX_KEY_AGE).unwrap();
+            storage.insert_encryption_key(key.clone()).unwrap();
+            let created_at = current_timestamp() - MAX_KEY_AGE.as_secs() - 1;
+            storage
+                .insert_encryption_key_with_timestamp(expired_key, created_at)
+                .unwrap();
+            storage.flush().unwrap();
+        }
+
+        // expired keys are removed when the storage is loaded
+        let storage = ReplyKeyStorage::load(dir.path(), MAX_KEY_AGE).unwrap();
+        assert_eq!(storage.stats().unwrap().stored_keys, 1);
+
+        let retrieved = storage.get_and_remove_encryption_key(digest).unwrap();
+        assert_eq!(retrieved.unwrap().to_bytes(), key.to_bytes());
+        assert!(storage
+            .get_and_remove_encryption_key(expired_digest)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn legacy_keys_are_upgraded_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let key = new_key();
+        let digest = key.compute_digest();
+        {
+            // keys used to be stored without their creation time
+            let db = sled::open(dir.path()).unwrap();
+            db.insert(digest, key.to_bytes()).unwrap();
+            db.flush().unwrap();
+        }
 
-        // TODO: not sure how to feel about flushing it every single time here...
-        // same with insertion
-        self.db.flush().unwrap();
-        removal_result
+        let storage = ReplyKeyStorage::load(dir.path(), MAX_KEY_AGE).unwrap();
+        let retrieved = storage.get_and_remove_encryption_key(digest).unwrap();
+        assert_eq!(retrieved.unwrap().to_bytes(), key.to_bytes());
     }
 }
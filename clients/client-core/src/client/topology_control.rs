@@ -1,9 +1,10 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::metrics::ClientMetrics;
+use crate::runtime::{self, spawn_future};
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::params::DEFAULT_NUM_MIX_HOPS;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use std::ops::Deref;
@@ -11,7 +12,6 @@ use std::sync::Arc;
 use std::time;
 use std::time::Duration;
 use tokio::sync::{RwLock, RwLockReadGuard};
-use tokio::task::JoinHandle;
 use topology::{nym_topology_from_bonds, NymTopology};
 use url::Url;
 
@@ -49,11 +49,13 @@ impl<'a> Deref for TopologyReadPermit<'a> {
 
 impl<'a> TopologyReadPermit<'a> {
     /// Using provided topology read permit, tries to get an immutable reference to the underlying
-    /// topology. For obvious reasons the lifetime of the topology reference is bound to the permit.
+    /// topology, such that packets could be routed through the specified number of mix hops.
+    /// For obvious reasons the lifetime of the topology reference is bound to the permit.
     pub(super) fn try_get_valid_topology_ref(
         &'a self,
         ack_recipient: &Recipient,
         packet_recipient: Option<&Recipient>,
+        num_mix_hops: u8,
     ) -> Option<&'a NymTopology> {
         // Note: implicit deref with Deref for TopologyReadPermit is happening here
         let topology_ref_option = self.permit.as_ref();
@@ -61,7 +63,7 @@ impl<'a> TopologyReadPermit<'a> {
             None => None,
             Some(topology_ref) => {
                 // see if it's possible to route the packet to both gateways
-                if !topology_ref.can_construct_path_through(DEFAULT_NUM_MIX_HOPS)
+                if !topology_ref.can_construct_path_through(num_mix_hops)
                     || !topology_ref.gateway_exists(ack_recipient.gateway())
                     || if let Some(packet_recipient) = packet_recipient {
                         !topology_ref.gateway_exists(packet_recipient.gateway())
@@ -106,16 +108,18 @@ impl TopologyAccessor {
         self.inner.read().await.into()
     }
 
-    async fn update_global_topology(&self, new_topology: Option<NymTopology>) {
+    /// Replaces the current view of the network. Normally only called by the `TopologyRefresher`,
+    /// but allows using a fixed topology for clients that are not connected to the validators.
+    pub async fn update_global_topology(&self, new_topology: Option<NymTopology>) {
         self.inner.write().await.update(new_topology);
     }
 
     // only used by the client at startup to get a slightly more reasonable error message
     // (currently displays as unused because health checker is disabled due to required changes)
-    pub async fn is_routable(&self) -> bool {
+    pub async fn is_routable(&self, num_mix_hops: u8) -> bool {
         match &self.inner.read().await.0 {
             None => false,
-            Some(ref topology) => topology.can_construct_path_through(DEFAULT_NUM_MIX_HOPS),
+            Some(ref topology) => topology.can_construct_path_through(num_mix_hops),
         }
     }
 }
@@ -130,6 +134,7 @@ pub struct TopologyRefresherConfig {
     validator_api_urls: Vec<Url>,
     refresh_rate: time::Duration,
     client_version: String,
+    num_mix_hops: u8,
 }
 
 impl TopologyRefresherConfig {
@@ -137,11 +142,13 @@ impl TopologyRefresherConfig {
         validator_api_urls: Vec<Url>,
         refresh_rate: time::Duration,
         client_version: String,
+        num_mix_hops: u8,
     ) -> Self {
         TopologyRefresherConfig {
             validator_api_urls,
             refresh_rate,
             client_version,
+            num_mix_hops,
         }
     }
 }
@@ -154,12 +161,22 @@ pub struct TopologyRefresher {
     topology_accessor: TopologyAccessor,
     refresh_rate: Duration,
 
+    /// Number of mix hops the packets are going to take by default. The topology must have
+    /// at least that many layers in order to be usable.
+    num_mix_hops: u8,
+
     currently_used_api: usize,
     was_latest_valid: bool,
+
+    metrics: ClientMetrics,
 }
 
 impl TopologyRefresher {
-    pub fn new(mut cfg: TopologyRefresherConfig, topology_accessor: TopologyAccessor) -> Self {
+    pub fn new(
+        mut cfg: TopologyRefresherConfig,
+        topology_accessor: TopologyAccessor,
+        metrics: ClientMetrics,
+    ) -> Self {
         cfg.validator_api_urls.shuffle(&mut thread_rng());
 
         TopologyRefresher {
@@ -168,8 +185,10 @@ impl TopologyRefresher {
             validator_api_urls: cfg.validator_api_urls,
             topology_accessor,
             refresh_rate: cfg.refresh_rate,
+            num_mix_hops: cfg.num_mix_hops,
             currently_used_api: 0,
             was_latest_valid: true,
+            metrics,
         }
     }
 
@@ -186,9 +205,11 @@ impl TopologyRefresher {
 
     /// Verifies whether nodes a reasonably distributed among all mix layers.
     ///
-    /// In ideal world we would have 33% nodes on layer 1, 33% on layer 2 and 33% on layer 3.
+    /// In ideal world we would have an equal share of nodes on each of the N layers,
+    /// i.e. 33% on each layer in the standard 3-layer setup.
     /// However, this is a rather unrealistic expectation, instead we check whether there exists
-    /// a layer with more than 66% of nodes or with fewer than 15% and if so, we trigger a failure.
+    /// a layer with more than twice its fair share of nodes (66% for 3 layers) or with fewer
+    /// than 45% of it (15% for 3 layers) and if so, we trigger a failure.
     ///
     /// # Arguments
     ///
@@ -199,44 +220,30 @@ impl TopologyRefresher {
         active_topology: &NymTopology,
         mixnodes_count: usize,
     ) -> bool {
-        let mixes = active_topology.mixes();
         if active_topology.gateways().is_empty() {
             return false;
         }
 
-        // trivial check to see if have at least a single node on each layer (regardless of active set size)
-        if mixes.get(&1).is_none() || mixes.get(&2).is_none() || mixes.get(&3).is_none() {
+        // trivial check to see if have at least a single node on each layer we're going
+        // to route our packets through (regardless of active set size)
+        let num_layers = active_topology.num_usable_layers();
+        if num_layers < self.num_mix_hops {
             return false;
         }
 
-        let upper_bound = (mixnodes_count as f32 * 0.66) as usize;
-        let lower_bound = (mixnodes_count as f32 * 0.15) as usize;
-
-        let layer1 = mixes.get(&1).unwrap().len();
-        let layer2 = mixes.get(&2).unwrap().len();
-        let layer3 = mixes.get(&3).unwrap().len();
-
-        if layer1 < lower_bound || layer1 > upper_bound {
-            warn!(
-                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
-                mixnodes_count, layer1, layer2, layer3
-            );
-            return false;
-        }
+        let layer_sizes: Vec<_> = (1..=num_layers)
+            .map(|layer| active_topology.mixes_in_layer(layer).len())
+            .collect();
 
-        if layer2 < lower_bound || layer2 > upper_bound {
-            warn!(
-                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
-                mixnodes_count, layer1, layer2, layer3
-            );
-            return false;
-        }
+        let fair_share = mixnodes_count as f32 / num_layers as f32;
+        let upper_bound = (fair_share * 2.0) as usize;
+        let lower_bound = (fair_share * 0.45) as usize;
 
-        if layer3 < lower_bound || layer3 > upper_bound {
-            warn!(
-                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
-                mixnodes_count, layer1, layer2, layer3
-            );
+        if layer_sizes
+            .iter()
+            .any(|&size| size < lower_bound || size > upper_bound)
+        {
+            warn!("nodes: {}, layers: {:?}", mixnodes_count, layer_sizes);
             return false;
         }
 
@@ -264,9 +271,20 @@ impl TopologyRefresher {
             Ok(gateways) => gateways,
         };
 
+        // the epoch is only used for choosing sphinx keys of mixnodes that are rotating them.
+        // mixnodes keep accepting their old keys for a while, so it's fine to carry on without it
+        let current_epoch = match self.validator_client.get_cached_current_epoch().await {
+            Err(err) => {
+                warn!("failed to get the current epoch - {}", err);
+                None
+            }
+            Ok(epoch) => epoch.map(|epoch| epoch.id()),
+        };
+
         let mixnodes_count = mixnodes.len();
-        let topology =
+        let mut topology =
             nym_topology_from_bonds(mixnodes, gateways).filter_system_version(&self.client_version);
+        topology.set_current_epoch(current_epoch);
 
         if !self.check_layer_distribution(&topology, mixnodes_count) {
             warn!("The current filtered active topology has extremely skewed layer distribution. It cannot be used.");
@@ -281,6 +299,7 @@ impl TopologyRefresher {
         let new_topology = self.get_current_compatible_topology().await;
 
         if new_topology.is_none() {
+            self.metrics.topology_refresh_failures.inc();
             self.use_next_validator_api();
         }
 
@@ -300,13 +319,13 @@ impl TopologyRefresher {
     }
 
     pub async fn is_topology_routable(&self) -> bool {
-        self.topology_accessor.is_routable().await
+        self.topology_accessor.is_routable(self.num_mix_hops).await
     }
 
-    pub fn start(mut self) -> JoinHandle<()> {
-        tokio::spawn(async move {
+    pub fn start(mut self) {
+        spawn_future(async move {
             loop {
-                tokio::time::sleep(self.refresh_rate).await;
+                runtime::sleep(self.refresh_rate).await;
                 self.refresh().await;
             }
         })
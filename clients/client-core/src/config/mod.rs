@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use config::NymConfig;
+use nymsphinx::params::{DEFAULT_NUM_MIX_HOPS, MAX_NUM_MIX_HOPS};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 use url::Url;
@@ -12,6 +14,8 @@ pub mod persistence;
 
 pub const MISSING_VALUE: &str = "MISSING VALUE";
 
+const DEFAULT_METRICS_LISTENING_ADDRESS: &str = "127.0.0.1:9191";
+
 // 'DEBUG'
 const DEFAULT_ACK_WAIT_MULTIPLIER: f64 = 1.5;
 
@@ -21,6 +25,12 @@ const DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(20)
 const DEFAULT_AVERAGE_PACKET_DELAY: Duration = Duration::from_millis(50);
 const DEFAULT_TOPOLOGY_REFRESH_RATE: Duration = Duration::from_secs(5 * 60); // every 5min
 const DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT: Duration = Duration::from_millis(5_000);
+const DEFAULT_MAXIMUM_REPLY_SURB_AGE: Duration = Duration::from_secs(24 * 60 * 60); // 24h
+const DEFAULT_REPLY_KEYS_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60); // every 10min
+const DEFAULT_MINIMUM_REPLY_SURB_STORAGE_THRESHOLD: u32 = 10;
+const DEFAULT_MAXIMUM_REPLY_SURB_REQUEST_SIZE: u32 = 100;
+const DEFAULT_NUMBER_OF_BACKUP_GATEWAYS: usize = 2;
+const DEFAULT_GATEWAY_FAILOVER_THRESHOLD: usize = 3;
 // Set this to a high value for now, so that we don't risk sporadic timeouts that might cause
 // bought bandwidth tokens to not have time to be spent; Once we remove the gateway from the
 // bandwidth bridging protocol, we can come back to a smaller timeout value
@@ -35,6 +45,8 @@ pub fn missing_string_value() -> String {
 pub struct Config<T> {
     client: Client<T>,
 
+    #[serde(default)]
+    metrics: Metrics,
     #[serde(default)]
     logging: Logging,
     #[serde(default)]
@@ -102,6 +114,16 @@ impl<T: NymConfig> Config<T> {
                 self::Client::<T>::default_reply_encryption_key_store_path(&id);
         }
 
+        if self
+            .client
+            .backup_gateway_shared_keys_directory
+            .as_os_str()
+            .is_empty()
+        {
+            self.client.backup_gateway_shared_keys_directory =
+                self::Client::<T>::default_backup_gateway_shared_keys_directory(&id);
+        }
+
         if self.client.database_path.as_os_str().is_empty() {
             self.client.database_path = self::Client::<T>::default_database_path(&id);
         }
@@ -121,6 +143,13 @@ impl<T: NymConfig> Config<T> {
         self.client.gateway_endpoint.gateway_id = id.into();
     }
 
+    pub fn with_backup_gateway_endpoints(
+        &mut self,
+        backup_gateway_endpoints: Vec<GatewayEndpoint>,
+    ) {
+        self.client.backup_gateway_endpoints = backup_gateway_endpoints;
+    }
+
     #[cfg(not(feature = "coconut"))]
     pub fn with_eth_private_key<S: Into<String>>(&mut self, eth_private_key: S) {
         self.client.eth_private_key = eth_private_key.into();
@@ -135,6 +164,10 @@ impl<T: NymConfig> Config<T> {
         self.client.validator_api_urls = validator_api_urls;
     }
 
+    pub fn with_num_mix_hops(&mut self, num_mix_hops: u8) {
+        self.debug.num_mix_hops = num_mix_hops;
+    }
+
     pub fn set_high_default_traffic_volume(&mut self) {
         self.debug.average_packet_delay = Duration::from_millis(10);
         self.debug.loop_cover_traffic_average_delay = Duration::from_millis(2_000_000); // basically don't really send cover messages
@@ -177,6 +210,10 @@ impl<T: NymConfig> Config<T> {
         self.client.gateway_shared_key_file.clone()
     }
 
+    pub fn get_backup_gateway_shared_keys_directory(&self) -> PathBuf {
+        self.client.backup_gateway_shared_keys_directory.clone()
+    }
+
     pub fn get_reply_encryption_key_store_path(&self) -> PathBuf {
         self.client.reply_encryption_key_store_path.clone()
     }
@@ -205,6 +242,10 @@ impl<T: NymConfig> Config<T> {
         &self.client.gateway_endpoint
     }
 
+    pub fn get_backup_gateway_endpoints(&self) -> &[GatewayEndpoint] {
+        &self.client.backup_gateway_endpoints
+    }
+
     pub fn get_database_path(&self) -> PathBuf {
         self.client.database_path.clone()
     }
@@ -236,6 +277,10 @@ impl<T: NymConfig> Config<T> {
         self.debug.ack_wait_addition
     }
 
+    pub fn get_maximum_retransmissions(&self) -> Option<u32> {
+        self.debug.maximum_retransmissions
+    }
+
     pub fn get_loop_cover_traffic_average_delay(&self) -> Duration {
         self.debug.loop_cover_traffic_average_delay
     }
@@ -256,6 +301,48 @@ impl<T: NymConfig> Config<T> {
         self.debug.topology_resolution_timeout
     }
 
+    pub fn get_maximum_reply_surb_age(&self) -> Duration {
+        self.debug.maximum_reply_surb_age
+    }
+
+    pub fn get_reply_keys_pruning_interval(&self) -> Duration {
+        self.debug.reply_keys_pruning_interval
+    }
+
+    pub fn get_minimum_reply_surb_storage_threshold(&self) -> u32 {
+        self.debug.minimum_reply_surb_storage_threshold
+    }
+
+    pub fn get_maximum_reply_surb_request_size(&self) -> u32 {
+        self.debug.maximum_reply_surb_request_size
+    }
+
+    pub fn get_num_mix_hops(&self) -> u8 {
+        self.debug.num_mix_hops
+    }
+
+    pub fn get_number_of_backup_gateways(&self) -> usize {
+        self.debug.number_of_backup_gateways
+    }
+
+    pub fn get_gateway_failover_threshold(&self) -> usize {
+        self.debug.gateway_failover_threshold
+    }
+
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.metrics.enabled
+    }
+
+    pub fn get_metrics_listening_address(&self) -> SocketAddr {
+        self.metrics.listening_address
+    }
+
+    /// Checks whether the configured number of mix hops can actually be used
+    /// for constructing sphinx packets.
+    pub fn has_valid_num_mix_hops(&self) -> bool {
+        (1..=MAX_NUM_MIX_HOPS).contains(&self.debug.num_mix_hops)
+    }
+
     pub fn get_version(&self) -> &str {
         &self.client.version
     }
@@ -265,6 +352,7 @@ impl<T: NymConfig> Default for Config<T> {
     fn default() -> Self {
         Config {
             client: Client::<T>::default(),
+            metrics: Default::default(),
             logging: Default::default(),
             debug: Default::default(),
         }
@@ -328,6 +416,10 @@ pub struct Client<T> {
     /// for all communication with it.
     gateway_shared_key_file: PathBuf,
 
+    /// Path to the directory containing shared keys derived with each of the backup gateways.
+    #[serde(default)]
+    backup_gateway_shared_keys_directory: PathBuf,
+
     /// Path to file containing key used for encrypting and decrypting the content of an
     /// acknowledgement so that nobody besides the client knows which packet it refers to.
     ack_key_file: PathBuf,
@@ -339,6 +431,11 @@ pub struct Client<T> {
     /// Information regarding how the client should send data to gateway.
     gateway_endpoint: GatewayEndpoint,
 
+    /// Gateways the client has also registered with during init and is going to switch over to,
+    /// in order, if the primary gateway becomes persistently unreachable.
+    #[serde(default)]
+    backup_gateway_endpoints: Vec<GatewayEndpoint>,
+
     /// Path to the database containing bandwidth credentials of this client.
     database_path: PathBuf,
 
@@ -371,9 +468,11 @@ impl<T: NymConfig> Default for Client<T> {
             private_encryption_key_file: Default::default(),
             public_encryption_key_file: Default::default(),
             gateway_shared_key_file: Default::default(),
+            backup_gateway_shared_keys_directory: Default::default(),
             ack_key_file: Default::default(),
             reply_encryption_key_store_path: Default::default(),
             gateway_endpoint: Default::default(),
+            backup_gateway_endpoints: Vec::new(),
             database_path: Default::default(),
             #[cfg(not(feature = "coconut"))]
             eth_private_key: "".to_string(),
@@ -406,6 +505,10 @@ impl<T: NymConfig> Client<T> {
         T::default_data_directory(Some(id)).join("gateway_shared.pem")
     }
 
+    fn default_backup_gateway_shared_keys_directory(id: &str) -> PathBuf {
+        T::default_data_directory(Some(id)).join("backup_gateways")
+    }
+
     fn default_ack_key_file(id: &str) -> PathBuf {
         T::default_data_directory(Some(id)).join("ack_key.pem")
     }
@@ -418,6 +521,26 @@ impl<T: NymConfig> Client<T> {
     }
 }
 
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Metrics {
+    /// Indicates whether the client should expose its metrics in the Prometheus text format
+    /// under the `/metrics` endpoint.
+    enabled: bool,
+
+    /// Socket address on which the metrics endpoint is going to be listening.
+    listening_address: SocketAddr,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            enabled: false,
+            listening_address: DEFAULT_METRICS_LISTENING_ADDRESS.parse().unwrap(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Logging {}
@@ -450,6 +573,12 @@ pub struct Debug {
     #[serde(with = "humantime_serde")]
     ack_wait_addition: Duration,
 
+    /// Maximum number of times a single packet is going to be retransmitted before the client
+    /// gives up on delivering the message it belongs to. If not set, the client only gives up on
+    /// messages whose delivery is being tracked, such as identified websocket `send` requests,
+    /// while all other packets are going to be retransmitted until they are eventually acknowledged.
+    maximum_retransmissions: Option<u32>,
+
     /// The parameter of Poisson distribution determining how long, on average,
     /// it is going to take for another loop cover traffic message to be sent.
     #[serde(with = "humantime_serde")]
@@ -477,6 +606,39 @@ pub struct Debug {
     /// did not reach its destination.
     #[serde(with = "humantime_serde")]
     topology_resolution_timeout: Duration,
+
+    /// Maximum age of the sent reply SURBs. Once it's reached, the encryption key associated with
+    /// the SURB is removed from the storage and any reply using it is going to be ignored.
+    /// Reply SURBs received from anonymous senders are kept for the same amount of time.
+    #[serde(with = "humantime_serde")]
+    maximum_reply_surb_age: Duration,
+
+    /// The uniform delay every which expired reply SURB encryption keys are removed from the storage.
+    #[serde(with = "humantime_serde")]
+    reply_keys_pruning_interval: Duration,
+
+    /// Minimum number of reply SURBs received from an anonymous sender that are kept available
+    /// for replying to it. Once there are fewer of them left, additional ones are requested
+    /// from the sender.
+    minimum_reply_surb_storage_threshold: u32,
+
+    /// Maximum number of reply SURBs that are going to be sent in response to a single request
+    /// for additional ones coming from the recipient of our anonymous messages.
+    maximum_reply_surb_request_size: u32,
+
+    /// Number of mix hops each packet sent by this client is going to take before reaching
+    /// the gateway of its recipient. Increasing it improves anonymity at the cost of latency.
+    /// Note that it can be overridden for individual messages.
+    num_mix_hops: u8,
+
+    /// Number of additional gateways, besides the primary one, the client registers with
+    /// during init so that it could switch over to them should the primary one fail.
+    number_of_backup_gateways: usize,
+
+    /// Number of consecutive failures to send packets to the current gateway (each already
+    /// including all reconnection attempts) after which the client switches over to its next
+    /// backup gateway.
+    gateway_failover_threshold: usize,
 }
 
 impl Default for Debug {
@@ -486,11 +648,19 @@ impl Default for Debug {
             average_ack_delay: DEFAULT_AVERAGE_PACKET_DELAY,
             ack_wait_multiplier: DEFAULT_ACK_WAIT_MULTIPLIER,
             ack_wait_addition: DEFAULT_ACK_WAIT_ADDITION,
+            maximum_retransmissions: None,
             loop_cover_traffic_average_delay: DEFAULT_LOOP_COVER_STREAM_AVERAGE_DELAY,
             message_sending_average_delay: DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY,
             gateway_response_timeout: DEFAULT_GATEWAY_RESPONSE_TIMEOUT,
             topology_refresh_rate: DEFAULT_TOPOLOGY_REFRESH_RATE,
             topology_resolution_timeout: DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT,
+            maximum_reply_surb_age: DEFAULT_MAXIMUM_REPLY_SURB_AGE,
+            reply_keys_pruning_interval: DEFAULT_REPLY_KEYS_PRUNING_INTERVAL,
+            minimum_reply_surb_storage_threshold: DEFAULT_MINIMUM_REPLY_SURB_STORAGE_THRESHOLD,
+            maximum_reply_surb_request_size: DEFAULT_MAXIMUM_REPLY_SURB_REQUEST_SIZE,
+            num_mix_hops: DEFAULT_NUM_MIX_HOPS,
+            number_of_backup_gateways: DEFAULT_NUMBER_OF_BACKUP_GATEWAYS,
+            gateway_failover_threshold: DEFAULT_GATEWAY_FAILOVER_THRESHOLD,
         }
     }
 }
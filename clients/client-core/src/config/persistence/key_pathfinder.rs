@@ -12,19 +12,25 @@ pub struct ClientKeyPathfinder {
     encryption_private_key: PathBuf,
     encryption_public_key: PathBuf,
     gateway_shared_key: PathBuf,
+    backup_gateway_shared_keys: PathBuf,
     ack_key: PathBuf,
 }
 
 impl ClientKeyPathfinder {
     pub fn new(id: String) -> Self {
         let os_config_dir = dirs::config_dir().expect("no config directory known for this OS"); // grabs the OS default config dir
-        let config_dir = os_config_dir.join("nym").join("clients").join(id);
+        Self::new_in_directory(os_config_dir.join("nym").join("clients").join(id))
+    }
+
+    pub fn new_in_directory<P: AsRef<Path>>(config_dir: P) -> Self {
+        let config_dir = config_dir.as_ref();
         ClientKeyPathfinder {
             identity_private_key: config_dir.join("private_identity.pem"),
             identity_public_key: config_dir.join("public_identity.pem"),
             encryption_private_key: config_dir.join("public_encryption.pem"),
             encryption_public_key: config_dir.join("private_encryption.pem"),
             gateway_shared_key: config_dir.join("gateway_shared.pem"),
+            backup_gateway_shared_keys: config_dir.join("backup_gateways"),
             ack_key: config_dir.join("ack_key.pem"),
         }
     }
@@ -36,6 +42,7 @@ impl ClientKeyPathfinder {
             encryption_private_key: config.get_private_encryption_key_file(),
             encryption_public_key: config.get_public_encryption_key_file(),
             gateway_shared_key: config.get_gateway_shared_key_file(),
+            backup_gateway_shared_keys: config.get_backup_gateway_shared_keys_directory(),
             ack_key: config.get_ack_key_file(),
         }
     }
@@ -60,6 +67,11 @@ impl ClientKeyPathfinder {
         &self.gateway_shared_key
     }
 
+    pub fn backup_gateway_shared_key(&self, gateway_id: &str) -> PathBuf {
+        self.backup_gateway_shared_keys
+            .join(format!("{}.pem", gateway_id))
+    }
+
     pub fn ack_key(&self) -> &Path {
         &self.ack_key
     }
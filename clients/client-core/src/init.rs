@@ -7,6 +7,7 @@ use std::{sync::Arc, time::Duration};
 
 use config::NymConfig;
 use crypto::asymmetric::{encryption, identity};
+use gateway_client::error::GatewayClientError;
 use gateway_client::GatewayClient;
 use gateway_requests::registration::handshake::SharedKeys;
 use nymsphinx::addressing::clients::Recipient;
@@ -19,13 +20,10 @@ use url::Url;
 
 use crate::{
     client::key_manager::KeyManager,
-    config::{persistence::key_pathfinder::ClientKeyPathfinder, Config},
+    config::{persistence::key_pathfinder::ClientKeyPathfinder, Config, GatewayEndpoint},
 };
 
-pub async fn query_gateway_details(
-    validator_servers: Vec<Url>,
-    chosen_gateway_id: Option<&str>,
-) -> gateway::Node {
+async fn query_compatible_gateways(validator_servers: Vec<Url>) -> Vec<gateway::Node> {
     let validator_api = validator_servers
         .choose(&mut thread_rng())
         .expect("The list of validator apis is empty");
@@ -38,7 +36,14 @@ pub async fn query_gateway_details(
         .filter_map(|gateway| gateway.try_into().ok())
         .collect::<Vec<gateway::Node>>();
 
-    let filtered_gateways = valid_gateways.filter_by_version(env!("CARGO_PKG_VERSION"));
+    valid_gateways.filter_by_version(env!("CARGO_PKG_VERSION"))
+}
+
+pub async fn query_gateway_details(
+    validator_servers: Vec<Url>,
+    chosen_gateway_id: Option<&str>,
+) -> gateway::Node {
+    let filtered_gateways = query_compatible_gateways(validator_servers).await;
 
     // if we have chosen particular gateway - use it, otherwise choose a random one.
     // (remember that in active topology all gateways have at least 100 reputation so should
@@ -57,28 +62,72 @@ pub async fn query_gateway_details(
     }
 }
 
+/// Chooses up to `count` random gateways, different from the primary one, that the client
+/// could switch over to should its primary gateway fail.
+pub async fn query_backup_gateways(
+    validator_servers: Vec<Url>,
+    primary_gateway_id: &str,
+    count: usize,
+) -> Vec<gateway::Node> {
+    let candidates = query_compatible_gateways(validator_servers)
+        .await
+        .into_iter()
+        .filter(|gateway| gateway.identity_key.to_base58_string() != primary_gateway_id)
+        .collect::<Vec<_>>();
+
+    candidates
+        .choose_multiple(&mut rand::thread_rng(), count)
+        .cloned()
+        .collect()
+}
+
+/// Generates fresh client keys, registers with the primary gateway and all provided backup gateways
+/// and stores all the keys on the disk. Returns endpoints of the backup gateways
+/// the registration with succeeded.
 pub async fn register_with_gateway_and_store_keys<T>(
     gateway_details: gateway::Node,
+    backup_gateways: Vec<gateway::Node>,
     config: &Config<T>,
-) where
+) -> Vec<GatewayEndpoint>
+where
     T: NymConfig,
 {
     let mut rng = OsRng;
     let mut key_manager = KeyManager::new(&mut rng);
 
-    let shared_keys = register_with_gateway(&gateway_details, key_manager.identity_keypair()).await;
+    let shared_keys = register_with_gateway(&gateway_details, key_manager.identity_keypair())
+        .await
+        .expect("failed to register with the gateway!");
     key_manager.insert_gateway_shared_key(shared_keys);
 
+    let mut backup_endpoints = Vec::with_capacity(backup_gateways.len());
+    for backup_gateway in backup_gateways {
+        let gateway_id = backup_gateway.identity_key.to_base58_string();
+        match register_with_gateway(&backup_gateway, key_manager.identity_keypair()).await {
+            Ok(shared_keys) => {
+                key_manager.insert_backup_gateway_shared_key(gateway_id, shared_keys);
+                backup_endpoints.push(backup_gateway.into());
+            }
+            Err(err) => log::warn!(
+                "failed to register with the backup gateway {} - {}. It is not going to be used",
+                gateway_id,
+                err
+            ),
+        }
+    }
+
     let pathfinder = ClientKeyPathfinder::new_from_config(config);
     key_manager
         .store_keys(&pathfinder)
         .expect("Failed to generated keys");
+
+    backup_endpoints
 }
 
 async fn register_with_gateway(
     gateway: &gateway::Node,
     our_identity: Arc<identity::KeyPair>,
-) -> Arc<SharedKeys> {
+) -> Result<Arc<SharedKeys>, GatewayClientError> {
     let timeout = Duration::from_millis(1500);
     let mut gateway_client = GatewayClient::new_init(
         gateway.clients_address(),
@@ -87,14 +136,8 @@ async fn register_with_gateway(
         our_identity.clone(),
         timeout,
     );
-    gateway_client
-        .establish_connection()
-        .await
-        .expect("failed to establish connection with the gateway!");
-    gateway_client
-        .perform_initial_authentication()
-        .await
-        .expect("failed to register with the gateway!")
+    gateway_client.establish_connection().await?;
+    gateway_client.perform_initial_authentication().await
 }
 
 pub fn show_address<T>(config: &Config<T>)
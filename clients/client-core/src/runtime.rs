@@ -0,0 +1,139 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+// Thin layer over the runtime primitives used by the client tasks. Natively everything is
+// backed by tokio, while in wasm the tasks are spawned onto the javascript event loop and
+// the timers are provided by the `fluvio-wasm-timer` crate. The timers are public so that they
+// could also be exercised by the wasm test suite.
+
+use futures::Future;
+use std::time::Duration;
+
+#[cfg(target_arch = "wasm32")]
+use fluvio_wasm_timer::Delay;
+#[cfg(target_arch = "wasm32")]
+pub use fluvio_wasm_timer::Instant;
+#[cfg(target_arch = "wasm32")]
+use std::pin::Pin;
+#[cfg(target_arch = "wasm32")]
+use std::task::{Context, Poll};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(not(target_arch = "wasm32"))]
+pub use tokio::time::{Instant, Sleep};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn_future<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(future);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn spawn_future<F>(future: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sleep(duration: Duration) -> Sleep {
+    tokio::time::sleep(duration)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep::new(Instant::now() + duration)
+}
+
+/// Current unix timestamp, in seconds.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the system clock is set to before 1970")
+        .as_secs()
+}
+
+// `SystemTime` is not available in wasm
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn current_timestamp() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn yield_now() {
+    tokio::task::yield_now().await
+}
+
+/// Gives other tasks running on the javascript event loop a chance to make progress.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn yield_now() {
+    struct YieldNow {
+        yielded: bool,
+    }
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.yielded {
+                return Poll::Ready(());
+            }
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    YieldNow { yielded: false }.await
+}
+
+/// Resettable timer mirroring the subset of the API of `tokio::time::Sleep` used by the client.
+#[cfg(target_arch = "wasm32")]
+pub struct Sleep {
+    deadline: Instant,
+    delay: Pin<Box<Delay>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Sleep {
+    fn new(deadline: Instant) -> Self {
+        Sleep {
+            deadline,
+            delay: Box::pin(Self::delay_until(deadline)),
+        }
+    }
+
+    fn delay_until(deadline: Instant) -> Delay {
+        let now = Instant::now();
+        if deadline > now {
+            Delay::new(deadline.duration_since(now))
+        } else {
+            Delay::new(Duration::from_secs(0))
+        }
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    pub fn reset(self: Pin<&mut Self>, deadline: Instant) {
+        let this = self.get_mut();
+        this.deadline = deadline;
+        this.delay = Box::pin(Self::delay_until(deadline));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // if the underlying timer has failed, there's nothing better we could do than to fire
+        self.delay.as_mut().poll(cx).map(|_| ())
+    }
+}
@@ -61,6 +61,9 @@ eth_endpoint = '{{ client.eth_endpoint }}'
 # communication with particular gateway.
 gateway_shared_key_file = '{{ client.gateway_shared_key_file }}'
 
+# Path to the directory containing shared keys derived with each of the backup gateways.
+backup_gateway_shared_keys_directory = '{{ client.backup_gateway_shared_keys_directory }}'
+
 # Path to file containing key used for encrypting and decrypting the content of an
 # acknowledgement so that nobody besides the client knows which packet it refers to.
 ack_key_file = '{{ client.ack_key_file }}'
@@ -80,6 +83,15 @@ gateway_owner = '{{ client.gateway_endpoint.gateway_owner }}'
 # Address of the gateway listener to which all client requests should be sent.
 gateway_listener = '{{ client.gateway_endpoint.gateway_listener }}'
 
+# Gateways the client has also registered with and is going to switch over to, in order,
+# if the gateway above becomes persistently unreachable.
+{{#each client.backup_gateway_endpoints }}
+[[client.backup_gateway_endpoints]]
+gateway_id = '{{ this.gateway_id }}'
+gateway_owner = '{{ this.gateway_owner }}'
+gateway_listener = '{{ this.gateway_listener }}'
+{{/each}}
+
 
 
 ##### socket config options #####
@@ -94,6 +106,17 @@ socket_type = '{{ socket.socket_type }}'
 listening_port = {{ socket.listening_port }}
 
 
+##### metrics configuration options #####
+
+[metrics]
+
+# Indicates whether the client should expose its metrics in the Prometheus text format
+# under the `/metrics` endpoint.
+enabled = {{ metrics.enabled }}
+
+# Socket address on which the metrics endpoint is going to be listening.
+listening_address = '{{ metrics.listening_address }}'
+
 ##### logging configuration options #####
 
 [logging]
@@ -112,5 +135,14 @@ average_ack_delay = '{{ debug.average_ack_delay }}'
 loop_cover_traffic_average_delay = '{{ debug.loop_cover_traffic_average_delay }}'
 message_sending_average_delay = '{{ debug.message_sending_average_delay }}'
 
+# Maximum number of times a single packet is going to be retransmitted before the client
+# gives up on delivering the message it belongs to. If not set, only messages whose delivery
+# is being tracked are given up on and all other packets are retransmitted until acknowledged.
+# maximum_retransmissions = 10
+
+# Number of mix hops each packet is going to take before reaching the recipient's gateway.
+# More hops improve anonymity at the cost of latency. It must be between 1 and 3.
+num_mix_hops = {{ debug.num_mix_hops }}
+
 "#
 }
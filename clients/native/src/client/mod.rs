@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use client_core::client::cover_traffic_stream::LoopCoverTrafficStream;
+use client_core::client::gateway_failover::{
+    self_address_channel, GatewayFailover, GatewayPersistence, SelfAddressReceiver,
+    SelfAddressSender,
+};
 use client_core::client::inbound_messages::{
     InputMessage, InputMessageReceiver, InputMessageSender,
 };
 use client_core::client::key_manager::KeyManager;
+use client_core::client::metrics::ClientMetrics;
 use client_core::client::mix_traffic::{
     BatchMixMessageReceiver, BatchMixMessageSender, MixTrafficController,
 };
@@ -15,7 +20,8 @@ use client_core::client::received_buffer::{
     ReceivedBufferMessage, ReceivedBufferRequestReceiver, ReceivedBufferRequestSender,
     ReceivedMessagesBufferController, ReconstructedMessagesReceiver,
 };
-use client_core::client::reply_key_storage::ReplyKeyStorage;
+use client_core::client::received_reply_surbs::ReceivedReplySurbsMap;
+use client_core::client::reply_key_storage::{ReplyKeyStorage, ReplyKeyStoragePruner};
 use client_core::client::topology_control::{
     TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
 };
@@ -32,6 +38,7 @@ use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::addressing::nodes::NodeIdentity;
 use nymsphinx::anonymous_replies::ReplySurb;
 use nymsphinx::receiver::ReconstructedMessage;
+use task::ShutdownNotifier;
 
 use crate::client::config::{Config, SocketType};
 use crate::websocket;
@@ -46,6 +53,12 @@ pub struct NymClient {
     /// KeyManager object containing smart pointers to all relevant keys used by the client.
     key_manager: KeyManager,
 
+    /// Counters updated by the client tasks and exposed under the metrics endpoint, if enabled.
+    metrics: ClientMetrics,
+
+    /// Used to notify the client tasks to gracefully shutdown.
+    shutdown: ShutdownNotifier,
+
     /// Channel used for transforming 'raw' messages into sphinx packets and sending them
     /// through the mix network.
     /// It is only available if the client started with the websocket listener disabled.
@@ -59,11 +72,22 @@ pub struct NymClient {
 impl NymClient {
     pub fn new(config: Config) -> Self {
         let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
-        let key_manager = KeyManager::load_keys(&pathfinder).expect("failed to load stored keys");
+        let mut key_manager =
+            KeyManager::load_keys(&pathfinder).expect("failed to load stored keys");
+        key_manager.load_backup_gateway_shared_keys(
+            &pathfinder,
+            config
+                .get_base()
+                .get_backup_gateway_endpoints()
+                .iter()
+                .map(|endpoint| endpoint.gateway_id.as_str()),
+        );
 
         NymClient {
             config,
             key_manager,
+            metrics: ClientMetrics::new(),
+            shutdown: ShutdownNotifier::default(),
             input_tx: None,
             receive_tx: None,
         }
@@ -85,6 +109,7 @@ impl NymClient {
         &self,
         topology_accessor: TopologyAccessor,
         mix_tx: BatchMixMessageSender,
+        self_address: SelfAddressReceiver,
     ) {
         info!("Starting loop cover traffic stream...");
 
@@ -95,8 +120,9 @@ impl NymClient {
             self.config
                 .get_base()
                 .get_loop_cover_traffic_average_delay(),
+            self.config.get_base().get_num_mix_hops(),
             mix_tx,
-            self.as_mix_recipient(),
+            self_address,
             topology_accessor,
         )
         .start();
@@ -106,18 +132,27 @@ impl NymClient {
         &self,
         topology_accessor: TopologyAccessor,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
         ack_receiver: AcknowledgementReceiver,
         input_receiver: InputMessageReceiver,
         mix_sender: BatchMixMessageSender,
+        self_address: SelfAddressReceiver,
     ) {
         let controller_config = real_messages_control::Config::new(
             self.key_manager.ack_key(),
             self.config.get_base().get_ack_wait_multiplier(),
             self.config.get_base().get_ack_wait_addition(),
+            self.config.get_base().get_maximum_retransmissions(),
             self.config.get_base().get_average_ack_delay(),
             self.config.get_base().get_message_sending_average_delay(),
             self.config.get_base().get_average_packet_delay(),
-            self.as_mix_recipient(),
+            self.config.get_base().get_num_mix_hops(),
+            self.config
+                .get_base()
+                .get_minimum_reply_surb_storage_threshold(),
+            self.config.get_base().get_maximum_reply_surb_request_size(),
+            self_address,
+            self.metrics.clone(),
         );
 
         info!("Starting real traffic stream...");
@@ -129,10 +164,20 @@ impl NymClient {
             mix_sender,
             topology_accessor,
             reply_key_storage,
+            received_reply_surbs,
         )
         .start();
     }
 
+    fn start_reply_key_storage_pruner(&self, reply_key_storage: ReplyKeyStorage) {
+        info!("Starting reply key storage pruner...");
+        ReplyKeyStoragePruner::new(
+            reply_key_storage,
+            self.config.get_base().get_reply_keys_pruning_interval(),
+        )
+        .start_with_shutdown(self.shutdown.subscribe());
+    }
+
     // buffer controlling all messages fetched from provider
     // required so that other components would be able to use them (say the websocket)
     fn start_received_messages_buffer_controller(
@@ -140,6 +185,8 @@ impl NymClient {
         query_receiver: ReceivedBufferRequestReceiver,
         mixnet_receiver: MixnetMessageReceiver,
         reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
+        input_sender: InputMessageSender,
     ) {
         info!("Starting received messages buffer controller...");
         ReceivedMessagesBufferController::new(
@@ -147,6 +194,8 @@ impl NymClient {
             query_receiver,
             mixnet_receiver,
             reply_key_storage,
+            received_reply_surbs,
+            input_sender,
         )
         .start()
     }
@@ -217,9 +266,13 @@ impl NymClient {
             self.config.get_base().get_validator_api_endpoints(),
             self.config.get_base().get_topology_refresh_rate(),
             env!("CARGO_PKG_VERSION").to_string(),
+            self.config.get_base().get_num_mix_hops(),
+        );
+        let mut topology_refresher = TopologyRefresher::new(
+            topology_refresher_config,
+            topology_accessor,
+            self.metrics.clone(),
         );
-        let mut topology_refresher =
-            TopologyRefresher::new(topology_refresher_config, topology_accessor);
         // before returning, block entire runtime to refresh the current network view so that any
         // components depending on topology would see a non-empty view
         info!("Obtaining initial network topology");
@@ -245,20 +298,42 @@ impl NymClient {
         &mut self,
         mix_rx: BatchMixMessageReceiver,
         gateway_client: GatewayClient,
+        self_address: SelfAddressSender,
     ) {
         info!("Starting mix traffic controller...");
-        MixTrafficController::new(mix_rx, gateway_client).start();
+        let gateway_failover = GatewayFailover::new_from_config(
+            self.config.get_base(),
+            &self.key_manager,
+            self_address,
+        )
+        .with_persistence(GatewayPersistence::new_from_config(
+            self.config.get_base(),
+            Config::get_base_mut,
+        ));
+        MixTrafficController::new(
+            mix_rx,
+            gateway_client,
+            gateway_failover,
+            self.metrics.clone(),
+        )
+        .start();
+    }
+
+    fn start_metrics_server(&self) {
+        info!("Starting metrics server...");
+        self.metrics
+            .start_server(self.config.get_base().get_metrics_listening_address());
     }
 
     fn start_websocket_listener(
         &self,
         buffer_requester: ReceivedBufferRequestSender,
         msg_input: InputMessageSender,
+        self_address: SelfAddressReceiver,
     ) {
         info!("Starting websocket listener...");
 
-        let websocket_handler =
-            websocket::Handler::new(msg_input, buffer_requester, self.as_mix_recipient());
+        let websocket_handler = websocket::Handler::new(msg_input, buffer_requester, self_address);
 
         websocket::Listener::new(self.config.get_listening_port()).start(websocket_handler);
     }
@@ -319,6 +394,10 @@ impl NymClient {
         println!(
             "Received SIGINT - the client will terminate now (threads are not yet nicely stopped, if you see stack traces that's alright)."
         );
+
+        info!("Sending shutdown");
+        self.shutdown.signal_shutdown().ok();
+        self.shutdown.wait_for_shutdown().await;
     }
 
     pub async fn start(&mut self) {
@@ -347,39 +426,68 @@ impl NymClient {
         let (ack_sender, ack_receiver) = mpsc::unbounded();
         let shared_topology_accessor = TopologyAccessor::new();
 
-        let reply_key_storage =
-            ReplyKeyStorage::load(self.config.get_base().get_reply_encryption_key_store_path())
-                .expect("Failed to load reply key storage!");
+        let reply_key_storage = ReplyKeyStorage::load(
+            self.config.get_base().get_reply_encryption_key_store_path(),
+            self.config.get_base().get_maximum_reply_surb_age(),
+        )
+        .expect("Failed to load reply key storage!");
+
+        // reply SURBs received from anonymous senders alongside replies waiting for them
+        let received_reply_surbs =
+            ReceivedReplySurbsMap::new(self.config.get_base().get_maximum_reply_surb_age());
+
+        // announces our current address, which changes whenever we switch over to a backup gateway
+        let (self_address_sender, self_address_receiver) =
+            self_address_channel(self.as_mix_recipient());
+
+        if self.config.get_base().get_metrics_enabled() {
+            self.start_metrics_server();
+        }
 
         // the components are started in very specific order. Unless you know what you are doing,
         // do not change that.
         self.start_topology_refresher(shared_topology_accessor.clone())
             .await;
+        self.start_reply_key_storage_pruner(reply_key_storage.clone());
         self.start_received_messages_buffer_controller(
             received_buffer_request_receiver,
             mixnet_messages_receiver,
             reply_key_storage.clone(),
+            received_reply_surbs.clone(),
+            input_sender.clone(),
         );
 
         let gateway_client = self
             .start_gateway_client(mixnet_messages_sender, ack_sender)
             .await;
 
-        self.start_mix_traffic_controller(sphinx_message_receiver, gateway_client);
+        self.start_mix_traffic_controller(
+            sphinx_message_receiver,
+            gateway_client,
+            self_address_sender,
+        );
         self.start_real_traffic_controller(
             shared_topology_accessor.clone(),
             reply_key_storage,
+            received_reply_surbs,
             ack_receiver,
             input_receiver,
             sphinx_message_sender.clone(),
+            self_address_receiver.clone(),
         );
 
-        self.start_cover_traffic_stream(shared_topology_accessor, sphinx_message_sender);
+        self.start_cover_traffic_stream(
+            shared_topology_accessor,
+            sphinx_message_sender,
+            self_address_receiver.clone(),
+        );
 
         match self.config.get_socket_type() {
-            SocketType::WebSocket => {
-                self.start_websocket_listener(received_buffer_request_sender, input_sender)
-            }
+            SocketType::WebSocket => self.start_websocket_listener(
+                received_buffer_request_sender,
+                input_sender,
+                self_address_receiver,
+            ),
             SocketType::None => {
                 // if we did not start the socket, it means we're running (supposedly) in the native mode
                 // and hence we should announce 'ourselves' to the buffer
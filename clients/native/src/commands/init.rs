@@ -121,8 +121,12 @@ pub(crate) async fn execute(args: &Init) {
     let override_config_fields = OverrideConfig::from(args.clone());
     config = override_config(config, override_config_fields);
 
-    let gateway = setup_gateway(id, register_gateway, user_chosen_gateway_id, &config).await;
+    let (gateway, backup_gateways) =
+        setup_gateway(id, register_gateway, user_chosen_gateway_id, &config).await;
     config.get_base_mut().with_gateway_endpoint(gateway);
+    config
+        .get_base_mut()
+        .with_backup_gateway_endpoints(backup_gateways);
 
     let config_save_location = config.get_config_file_save_location();
     config
@@ -147,7 +151,7 @@ async fn setup_gateway(
     register: bool,
     user_chosen_gateway_id: Option<&str>,
     config: &Config,
-) -> GatewayEndpoint {
+) -> (GatewayEndpoint, Vec<GatewayEndpoint>) {
     if register {
         // Get the gateway details by querying the validator-api. Either pick one at random or use
         // the chosen one if it's among the available ones.
@@ -159,13 +163,25 @@ async fn setup_gateway(
         .await;
         log::debug!("Querying gateway gives: {}", gateway);
 
+        // Also choose some other gateways to fall back to should the primary one ever fail
+        let backup_gateways = client_core::init::query_backup_gateways(
+            config.get_base().get_validator_api_endpoints(),
+            &gateway.identity_key.to_base58_string(),
+            config.get_base().get_number_of_backup_gateways(),
+        )
+        .await;
+
         // Registering with gateway by setting up and writing shared keys to disk
         log::trace!("Registering gateway");
-        client_core::init::register_with_gateway_and_store_keys(gateway.clone(), config.get_base())
-            .await;
+        let backup_gateways = client_core::init::register_with_gateway_and_store_keys(
+            gateway.clone(),
+            backup_gateways,
+            config.get_base(),
+        )
+        .await;
         println!("Saved all generated keys");
 
-        gateway.into()
+        (gateway.into(), backup_gateways)
     } else if user_chosen_gateway_id.is_some() {
         // Just set the config, don't register or create any keys
         // This assumes that the user knows what they are doing, and that the existing keys are
@@ -177,11 +193,31 @@ async fn setup_gateway(
         )
         .await;
         log::debug!("Querying gateway gives: {}", gateway);
-        gateway.into()
+
+        // keep whatever backup gateways we have already registered with
+        let gateway: GatewayEndpoint = gateway.into();
+        let backup_gateways = Config::load_from_file(Some(id))
+            .map(|existing_config| {
+                existing_config
+                    .get_base()
+                    .get_backup_gateway_endpoints()
+                    .iter()
+                    .filter(|backup| backup.gateway_id != gateway.gateway_id)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        (gateway, backup_gateways)
     } else {
         println!("Not registering gateway, will reuse existing config and keys");
         match Config::load_from_file(Some(id)) {
-            Ok(existing_config) => existing_config.get_base().get_gateway_endpoint().clone(),
+            Ok(existing_config) => (
+                existing_config.get_base().get_gateway_endpoint().clone(),
+                existing_config
+                    .get_base()
+                    .get_backup_gateway_endpoints()
+                    .to_vec(),
+            ),
             Err(err) => {
                 panic!(
                     "Unable to configure gateway: {err}. \n
@@ -9,6 +9,7 @@ use crate::{
 use clap::Args;
 use config::NymConfig;
 use log::*;
+use nymsphinx::params::MAX_NUM_MIX_HOPS;
 use version_checker::is_minor_version_compatible;
 
 #[derive(Args, Clone)]
@@ -112,5 +113,14 @@ pub(crate) async fn execute(args: &Run) {
         return;
     }
 
+    if !config.get_base().has_valid_num_mix_hops() {
+        error!(
+            "the configured number of mix hops ({}) is invalid - it must be between 1 and {}",
+            config.get_base().get_num_mix_hops(),
+            MAX_NUM_MIX_HOPS
+        );
+        return;
+    }
+
     NymClient::new(config).run_forever().await;
 }
@@ -2,16 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use client_core::client::{
-    inbound_messages::{InputMessage, InputMessageSender},
+    gateway_failover::SelfAddressReceiver,
+    inbound_messages::{DeliveryStatus, InputMessage, InputMessageSender, MessageSentNotifier},
     received_buffer::{
         ReceivedBufferMessage, ReceivedBufferRequestSender, ReconstructedMessagesReceiver,
     },
 };
 use futures::channel::mpsc;
-use futures::{SinkExt, StreamExt};
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, FuturesUnordered, SelectAll};
+use futures::{FutureExt, SinkExt, StreamExt};
 use log::*;
-use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::anonymous_replies::{AnonymousSenderTag, ReplySurb};
 use nymsphinx::receiver::ReconstructedMessage;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{
@@ -19,7 +21,11 @@ use tokio_tungstenite::{
     tungstenite::{protocol::Message as WsMessage, Error as WsError},
     WebSocketStream,
 };
-use websocket_requests::{requests::ClientRequest, responses::ServerResponse};
+use websocket_requests::{
+    error::{self, ErrorKind},
+    requests::{ClientRequest, RequestId},
+    responses::ServerResponse,
+};
 
 enum ReceivedResponseType {
     Binary,
@@ -32,12 +38,21 @@ impl Default for ReceivedResponseType {
     }
 }
 
+// resolves once the message sent as part of the identified request either left the client
+// (`true`) or got dropped without being sent (`false`)
+type PendingSend = BoxFuture<'static, (RequestId, bool)>;
+
+// delivery status updates of the message sent as part of the identified request
+type DeliveryStatusUpdates = BoxStream<'static, (RequestId, DeliveryStatus)>;
+
 pub(crate) struct Handler {
     msg_input: InputMessageSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: SelfAddressReceiver,
     socket: Option<WebSocketStream<TcpStream>>,
     received_response_type: ReceivedResponseType,
+    pending_sends: FuturesUnordered<PendingSend>,
+    delivery_status_updates: SelectAll<DeliveryStatusUpdates>,
 }
 
 // clone is used to use handler on a new connection, which initially is `None`
@@ -46,9 +61,11 @@ impl Clone for Handler {
         Handler {
             msg_input: self.msg_input.clone(),
             buffer_requester: self.buffer_requester.clone(),
-            self_full_address: self.self_full_address,
+            self_full_address: self.self_full_address.clone(),
             socket: None,
             received_response_type: Default::default(),
+            pending_sends: FuturesUnordered::new(),
+            delivery_status_updates: SelectAll::new(),
         }
     }
 }
@@ -65,7 +82,7 @@ impl Handler {
     pub(crate) fn new(
         msg_input: InputMessageSender,
         buffer_requester: ReceivedBufferRequestSender,
-        self_full_address: Recipient,
+        self_full_address: SelfAddressReceiver,
     ) -> Self {
         Handler {
             msg_input,
@@ -73,49 +90,151 @@ impl Handler {
             self_full_address,
             socket: None,
             received_response_type: Default::default(),
+            pending_sends: FuturesUnordered::new(),
+            delivery_status_updates: SelectAll::new(),
+        }
+    }
+
+    // if the client has identified its request, it's going to get notified once the message
+    // has actually been sent to the mix network
+    fn track_sending(
+        &mut self,
+        input_msg: InputMessage,
+        request_id: Option<RequestId>,
+    ) -> InputMessage {
+        match request_id {
+            None => input_msg,
+            Some(request_id) => {
+                let (notifier, receiver) = MessageSentNotifier::new();
+                self.pending_sends
+                    .push(async move { (request_id, receiver.await.is_ok()) }.boxed());
+                input_msg.with_sent_notifier(notifier)
+            }
+        }
+    }
+
+    // similarly, it's going to get notified about the delivery status of the message
+    fn track_delivery(
+        &mut self,
+        input_msg: InputMessage,
+        request_id: Option<RequestId>,
+    ) -> InputMessage {
+        match request_id {
+            None => input_msg,
+            Some(request_id) => {
+                let (notifier, receiver) = mpsc::unbounded();
+                self.delivery_status_updates
+                    .push(receiver.map(move |status| (request_id, status)).boxed());
+                input_msg.with_delivery_notifier(notifier)
+            }
         }
     }
 
     fn handle_send(
         &mut self,
+        request_id: Option<RequestId>,
         recipient: Recipient,
         message: Vec<u8>,
         with_reply_surb: bool,
+        mix_hops: Option<u8>,
     ) -> Option<ServerResponse> {
         // the ack control is now responsible for chunking, etc.
-        let input_msg = InputMessage::new_fresh(recipient, message, with_reply_surb);
+        let input_msg = match mix_hops {
+            Some(mix_hops) => {
+                InputMessage::new_fresh_with_mix_hops(recipient, message, with_reply_surb, mix_hops)
+            }
+            None => InputMessage::new_fresh(recipient, message, with_reply_surb),
+        };
+        let input_msg = self.track_sending(input_msg, request_id);
+        let input_msg = self.track_delivery(input_msg, request_id);
         self.msg_input.unbounded_send(input_msg).unwrap();
 
         None
     }
 
-    fn handle_reply(&mut self, reply_surb: ReplySurb, message: Vec<u8>) -> Option<ServerResponse> {
+    fn handle_reply(
+        &mut self,
+        request_id: Option<RequestId>,
+        reply_surb: ReplySurb,
+        message: Vec<u8>,
+    ) -> Option<ServerResponse> {
         if message.len() > ReplySurb::max_msg_len(Default::default()) {
-            return Some(ServerResponse::new_error(format!("too long message to put inside a reply SURB. Received: {} bytes and maximum is {} bytes", message.len(), ReplySurb::max_msg_len(Default::default()))));
+            return Some(ServerResponse::Error(error::Error::new(ErrorKind::TooLongReplyMessage, format!("too long message to put inside a reply SURB. Received: {} bytes and maximum is {} bytes", message.len(), ReplySurb::max_msg_len(Default::default())))));
         }
 
         let input_msg = InputMessage::new_reply(reply_surb, message);
+        let input_msg = self.track_sending(input_msg, request_id);
+        self.msg_input.unbounded_send(input_msg).unwrap();
+
+        None
+    }
+
+    fn handle_send_anonymous(
+        &mut self,
+        request_id: Option<RequestId>,
+        recipient: Recipient,
+        message: Vec<u8>,
+        reply_surbs: u32,
+        mix_hops: Option<u8>,
+    ) -> Option<ServerResponse> {
+        let input_msg = match mix_hops {
+            Some(mix_hops) => {
+                InputMessage::new_anonymous_with_mix_hops(recipient, message, reply_surbs, mix_hops)
+            }
+            None => InputMessage::new_anonymous(recipient, message, reply_surbs),
+        };
+        let input_msg = self.track_sending(input_msg, request_id);
+        let input_msg = self.track_delivery(input_msg, request_id);
+        self.msg_input.unbounded_send(input_msg).unwrap();
+
+        None
+    }
+
+    fn handle_reply_with_tag(
+        &mut self,
+        request_id: Option<RequestId>,
+        sender_tag: AnonymousSenderTag,
+        message: Vec<u8>,
+    ) -> Option<ServerResponse> {
+        // the reply is going to get split across as many reply SURBs as required
+        let input_msg = InputMessage::new_tagged_reply(sender_tag, message);
+        let input_msg = self.track_sending(input_msg, request_id);
         self.msg_input.unbounded_send(input_msg).unwrap();
 
         None
     }
 
     fn handle_self_address(&self) -> ServerResponse {
-        ServerResponse::SelfAddress(self.self_full_address)
+        ServerResponse::SelfAddress(*self.self_full_address.borrow())
     }
 
-    fn handle_request(&mut self, request: ClientRequest) -> Option<ServerResponse> {
+    fn handle_request(
+        &mut self,
+        request_id: Option<RequestId>,
+        request: ClientRequest,
+    ) -> Option<ServerResponse> {
         match request {
             ClientRequest::Send {
                 recipient,
                 message,
                 with_reply_surb,
-            } => self.handle_send(recipient, message, with_reply_surb),
+                mix_hops,
+            } => self.handle_send(request_id, recipient, message, with_reply_surb, mix_hops),
             ClientRequest::Reply {
                 message,
                 reply_surb,
-            } => self.handle_reply(reply_surb, message),
+            } => self.handle_reply(request_id, reply_surb, message),
             ClientRequest::SelfAddress => Some(self.handle_self_address()),
+            ClientRequest::SendAnonymous {
+                recipient,
+                message,
+                reply_surbs,
+                mix_hops,
+            } => self.handle_send_anonymous(request_id, recipient, message, reply_surbs, mix_hops),
+            ClientRequest::ReplyWithTag {
+                sender_tag,
+                message,
+            } => self.handle_reply_with_tag(request_id, sender_tag, message),
         }
     }
 
@@ -124,28 +243,28 @@ impl Handler {
         trace!("Content: {:?}", msg);
 
         self.received_response_type = ReceivedResponseType::Text;
-        let client_request = ClientRequest::try_from_text(msg);
+        let (request_id, client_request) = ClientRequest::try_from_text(msg);
 
         let response = match client_request {
             Err(err) => Some(ServerResponse::Error(err)),
-            Ok(req) => self.handle_request(req),
+            Ok(req) => self.handle_request(request_id, req),
         };
 
-        response.map(|resp| WsMessage::text(resp.into_text()))
+        response.map(|resp| WsMessage::text(resp.into_text(request_id)))
     }
 
     fn handle_binary_message(&mut self, msg: Vec<u8>) -> Option<WsMessage> {
         debug!("Handling binary message request");
 
         self.received_response_type = ReceivedResponseType::Binary;
-        let client_request = ClientRequest::try_from_binary(msg);
+        let (request_id, client_request) = ClientRequest::try_from_binary(msg);
 
         let response = match client_request {
             Err(err) => Some(ServerResponse::Error(err)),
-            Ok(req) => self.handle_request(req),
+            Ok(req) => self.handle_request(request_id, req),
         };
 
-        response.map(|resp| WsMessage::Binary(resp.into_binary()))
+        response.map(|resp| WsMessage::Binary(resp.into_binary(request_id)))
     }
 
     fn handle_ws_request(&mut self, raw_request: WsMessage) -> Option<WsMessage> {
@@ -168,7 +287,7 @@ impl Handler {
         reconstructed_messages
             .into_iter()
             .map(ServerResponse::Received)
-            .map(|resp| Ok(WsMessage::Binary(resp.into_binary())))
+            .map(|resp| Ok(WsMessage::Binary(resp.into_binary(None))))
             .collect()
     }
 
@@ -181,7 +300,7 @@ impl Handler {
         reconstructed_messages
             .into_iter()
             .map(ServerResponse::Received)
-            .map(|resp| Ok(WsMessage::Text(resp.into_text())))
+            .map(|resp| Ok(WsMessage::Text(resp.into_text(None))))
             .collect()
     }
 
@@ -207,6 +326,20 @@ impl Handler {
             .await
     }
 
+    async fn push_websocket_notification(
+        &mut self,
+        request_id: Option<RequestId>,
+        response: ServerResponse,
+    ) -> Result<(), WsError> {
+        // same as with received messages, respond in the format the client has last used
+        let response_message = match self.received_response_type {
+            ReceivedResponseType::Binary => WsMessage::Binary(response.into_binary(request_id)),
+            ReceivedResponseType::Text => WsMessage::Text(response.into_text(request_id)),
+        };
+
+        self.send_websocket_response(response_message).await
+    }
+
     async fn send_websocket_response(&mut self, msg: WsMessage) -> Result<(), WsError> {
         match self.socket {
             // TODO: more closely investigate difference between `Sink::send` and `Sink::send_all`
@@ -217,8 +350,11 @@ impl Handler {
         }
     }
 
-    async fn next_websocket_request(&mut self) -> Option<Result<WsMessage, WsError>> {
-        match self.socket {
+    // takes the socket rather than `self` so that it could be polled alongside other fields
+    async fn next_websocket_request(
+        socket: &mut Option<WebSocketStream<TcpStream>>,
+    ) -> Option<Result<WsMessage, WsError>> {
+        match socket {
             Some(ref mut ws_stream) => ws_stream.next().await,
             None => None,
         }
@@ -228,7 +364,7 @@ impl Handler {
         loop {
             tokio::select! {
                 // we can either get a client request from the websocket
-                socket_msg = self.next_websocket_request() => {
+                socket_msg = Self::next_websocket_request(&mut self.socket) => {
                     if socket_msg.is_none() {
                         break;
                     }
@@ -254,6 +390,50 @@ impl Handler {
                         }
                     }
                 }
+                // or a notification that a message sent as part of an identified request has left the client
+                Some((request_id, sent)) = self.pending_sends.next(), if !self.pending_sends.is_empty() => {
+                    let response = if sent {
+                        ServerResponse::Sent
+                    } else {
+                        ServerResponse::Error(error::Error::new(
+                            ErrorKind::MessageNotSent,
+                            "failed to send the message to the mix network".to_string(),
+                        ))
+                    };
+                    if let Err(err) = self.push_websocket_notification(Some(request_id), response).await {
+                        warn!(
+                            "Failed to send message over websocket: {}. Assuming the connection is dead.",
+                            err
+                        );
+                        break;
+                    }
+                }
+                // or an update on the delivery status of such message
+                Some((request_id, status)) = self.delivery_status_updates.next(), if !self.delivery_status_updates.is_empty() => {
+                    let response = match status {
+                        DeliveryStatus::Delivered => ServerResponse::Delivered,
+                        DeliveryStatus::Retrying => ServerResponse::Retrying,
+                        DeliveryStatus::Failed => ServerResponse::Failed,
+                    };
+                    if let Err(err) = self.push_websocket_notification(Some(request_id), response).await {
+                        warn!(
+                            "Failed to send message over websocket: {}. Assuming the connection is dead.",
+                            err
+                        );
+                        break;
+                    }
+                }
+                // or our address changing after switching over to a different gateway
+                Ok(_) = self.self_full_address.changed() => {
+                    let response = ServerResponse::AddressChanged(*self.self_full_address.borrow());
+                    if let Err(err) = self.push_websocket_notification(None, response).await {
+                        warn!(
+                            "Failed to send message over websocket: {}. Assuming the connection is dead.",
+                            err
+                        );
+                        break;
+                    }
+                }
                 // or a reconstructed mix message that we need to push back to the client
                 mix_messages = msg_receiver.next() => {
                     let mix_messages = mix_messages.expect(
@@ -279,6 +459,9 @@ impl Handler {
         };
         self.socket = Some(ws_stream);
 
+        // the application is expected to query our address, so only later changes are announced
+        self.self_full_address.borrow_and_update();
+
         let (reconstructed_sender, reconstructed_receiver) = mpsc::unbounded();
 
         // tell the buffer to start sending stuff to us
@@ -30,7 +30,8 @@ impl Error {
 }
 
 #[repr(u8)]
-#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ErrorKind {
     /// The received request contained no data.
     EmptyRequest = 0x01,
@@ -44,6 +45,13 @@ pub enum ErrorKind {
     /// The received request is malformed.
     MalformedRequest = 0x04,
 
+    /// The message provided in the request was too long to be put inside a reply SURB.
+    TooLongReplyMessage = 0x05,
+
+    /// The message provided in the request could not be sent to the mix network,
+    /// for example due to lack of valid network topology.
+    MessageNotSent = 0x06,
+
     // that's an arbitrary division but let's keep 1-127 (hex 0x01 - 0x7F) values request-specific
     // and 128-254 (hex 0x80 - 0xFE) for responses
     /// The received response contained no data.
@@ -69,6 +77,8 @@ impl ErrorKind {
             ErrorKind::TooShortRequest => "received request did not contain enough data",
             ErrorKind::UnknownRequest => "unknown request type",
             ErrorKind::MalformedRequest => "malformed request",
+            ErrorKind::TooLongReplyMessage => "message too long to be put inside a reply SURB",
+            ErrorKind::MessageNotSent => "message could not be sent to the mix network",
 
             ErrorKind::EmptyResponse => "received response contained no data",
             ErrorKind::TooShortResponse => "received response did not contain enough data",
@@ -7,7 +7,9 @@
 use crate::error::{self, ErrorKind};
 use crate::text::ClientRequestText;
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::anonymous_replies::requests::SENDER_TAG_SIZE;
+use nymsphinx::anonymous_replies::{AnonymousSenderTag, ReplySurb};
+use nymsphinx::params::MAX_NUM_MIX_HOPS;
 use std::convert::{TryFrom, TryInto};
 use std::mem::size_of;
 
@@ -20,6 +22,26 @@ pub const REPLY_REQUEST_TAG: u8 = 0x01;
 /// Value tag representing [`SelfAddress`] variant of the [`ClientRequest`]
 pub const SELF_ADDRESS_REQUEST_TAG: u8 = 0x02;
 
+/// Value tag representing [`Send`] variant of the [`ClientRequest`] with explicitly specified
+/// number of mix hops the message should take.
+pub const SEND_WITH_MIX_HOPS_REQUEST_TAG: u8 = 0x03;
+
+/// Value tag representing [`SendAnonymous`] variant of the [`ClientRequest`]
+pub const SEND_ANONYMOUS_REQUEST_TAG: u8 = 0x04;
+
+/// Value tag representing [`ReplyWithTag`] variant of the [`ClientRequest`]
+pub const REPLY_WITH_TAG_REQUEST_TAG: u8 = 0x05;
+
+/// Value tag indicating the [`ClientRequest`] that follows is preceded by a client-chosen
+/// [`RequestId`].
+pub const REQUEST_WITH_ID_TAG: u8 = 0xFF;
+
+/// Optional identifier chosen by the client that is attached to every [`ServerResponse`]
+/// sent as a direct result of handling the request.
+///
+/// [`ServerResponse`]: crate::responses::ServerResponse
+pub type RequestId = u64;
+
 #[allow(non_snake_case)]
 #[derive(Debug)]
 pub enum ClientRequest {
@@ -28,22 +50,73 @@ pub enum ClientRequest {
         message: Vec<u8>,
         // Perhaps we could change it to a number to indicate how many reply_SURBs we want to include?
         with_reply_surb: bool,
+        // if not specified, the message is going to take the default number of mix hops
+        // of the client
+        mix_hops: Option<u8>,
     },
     Reply {
         message: Vec<u8>,
         reply_surb: ReplySurb,
     },
     SelfAddress,
+    /// Sends the message without revealing our address to the recipient. Instead, the specified
+    /// number of reply SURBs is attached to it so that the recipient could reply
+    /// with (potentially multi-packet) messages under our anonymous sender tag.
+    SendAnonymous {
+        recipient: Recipient,
+        message: Vec<u8>,
+        reply_surbs: u32,
+        // if not specified, the message is going to take the default number of mix hops
+        // of the client
+        mix_hops: Option<u8>,
+    },
+    /// Replies to an anonymous sender using the reply SURBs it has previously sent us.
+    ReplyWithTag {
+        sender_tag: AnonymousSenderTag,
+        message: Vec<u8>,
+    },
+}
+
+/// Makes sure the requested number of mix hops could actually be used for constructing
+/// sphinx packets.
+pub(crate) fn validate_mix_hops(mix_hops: u8) -> Result<u8, error::Error> {
+    if mix_hops == 0 || mix_hops > MAX_NUM_MIX_HOPS {
+        Err(error::Error::new(
+            ErrorKind::MalformedRequest,
+            format!(
+                "invalid number of mix hops {}. It must be between 1 and {}",
+                mix_hops, MAX_NUM_MIX_HOPS
+            ),
+        ))
+    } else {
+        Ok(mix_hops)
+    }
 }
 
 // we could have been parsing it directly TryFrom<WsMessage>, but we want to retain
 // information about whether it came from binary or text to send appropriate response back
 impl ClientRequest {
     // SEND_REQUEST_TAG || with_surb || recipient || data_len || data
-    fn serialize_send(recipient: Recipient, data: Vec<u8>, with_reply_surb: bool) -> Vec<u8> {
+    // OR
+    // SEND_WITH_MIX_HOPS_REQUEST_TAG || with_surb || mix_hops || recipient || data_len || data
+    fn serialize_send(
+        recipient: Recipient,
+        data: Vec<u8>,
+        with_reply_surb: bool,
+        mix_hops: Option<u8>,
+    ) -> Vec<u8> {
+        let header = match mix_hops {
+            None => vec![SEND_REQUEST_TAG, with_reply_surb as u8],
+            Some(mix_hops) => vec![
+                SEND_WITH_MIX_HOPS_REQUEST_TAG,
+                with_reply_surb as u8,
+                mix_hops,
+            ],
+        };
+
         let data_len_bytes = (data.len() as u64).to_be_bytes();
-        std::iter::once(SEND_REQUEST_TAG)
-            .chain(std::iter::once(with_reply_surb as u8))
+        header
+            .into_iter()
             .chain(recipient.to_bytes().iter().cloned()) // will not be length prefixed because the length is constant
             .chain(data_len_bytes.iter().cloned())
             .chain(data.into_iter())
@@ -51,18 +124,27 @@ impl ClientRequest {
     }
 
     // SEND_REQUEST_TAG || with_reply || recipient || data_len || data
+    // OR
+    // SEND_WITH_MIX_HOPS_REQUEST_TAG || with_reply || mix_hops || recipient || data_len || data
     fn deserialize_send(b: &[u8]) -> Result<Self, error::Error> {
-        // we need to have at least 1 (tag) + 1 (reply flag) + Recipient::LEN + sizeof<u64> bytes
-        if b.len() < 2 + Recipient::LEN + size_of::<u64>() {
+        // this MUST match because it was called by 'deserialize'
+        debug_assert!(b[0] == SEND_REQUEST_TAG || b[0] == SEND_WITH_MIX_HOPS_REQUEST_TAG);
+
+        // 1 (tag) + 1 (reply flag) [+ 1 (mix hops)]
+        let header_len = if b[0] == SEND_WITH_MIX_HOPS_REQUEST_TAG {
+            3
+        } else {
+            2
+        };
+
+        // we need to have at least the header + Recipient::LEN + sizeof<u64> bytes
+        if b.len() < header_len + Recipient::LEN + size_of::<u64>() {
             return Err(error::Error::new(
                 ErrorKind::TooShortRequest,
                 "not enough data provided to recover 'send'".to_string(),
             ));
         }
 
-        // this MUST match because it was called by 'deserialize'
-        debug_assert_eq!(b[0], SEND_REQUEST_TAG);
-
         let with_reply_surb = match b[1] {
             0 => false,
             1 => true,
@@ -74,8 +156,16 @@ impl ClientRequest {
             }
         };
 
+        let mix_hops = if header_len == 3 {
+            Some(validate_mix_hops(b[2])?)
+        } else {
+            None
+        };
+
+        let b = &b[header_len..];
+
         let mut recipient_bytes = [0u8; Recipient::LEN];
-        recipient_bytes.copy_from_slice(&b[2..2 + Recipient::LEN]);
+        recipient_bytes.copy_from_slice(&b[..Recipient::LEN]);
         let recipient = match Recipient::try_from_bytes(recipient_bytes) {
             Ok(recipient) => recipient,
             Err(err) => {
@@ -86,9 +176,9 @@ impl ClientRequest {
             }
         };
 
-        let data_len_bytes = &b[2 + Recipient::LEN..2 + Recipient::LEN + size_of::<u64>()];
+        let data_len_bytes = &b[Recipient::LEN..Recipient::LEN + size_of::<u64>()];
         let data_len = u64::from_be_bytes(data_len_bytes.try_into().unwrap());
-        let data = &b[2 + Recipient::LEN + size_of::<u64>()..];
+        let data = &b[Recipient::LEN + size_of::<u64>()..];
         if data.len() as u64 != data_len {
             return Err(error::Error::new(
                 ErrorKind::MalformedRequest,
@@ -104,6 +194,7 @@ impl ClientRequest {
             with_reply_surb,
             recipient,
             message: data.to_vec(),
+            mix_hops,
         })
     }
 
@@ -189,6 +280,128 @@ impl ClientRequest {
         })
     }
 
+    // SEND_ANONYMOUS_REQUEST_TAG || reply_surbs || mix_hops || recipient || data_len || data
+    // where mix_hops of 0 indicates the default number of hops of the client should be used
+    fn serialize_send_anonymous(
+        recipient: Recipient,
+        data: Vec<u8>,
+        reply_surbs: u32,
+        mix_hops: Option<u8>,
+    ) -> Vec<u8> {
+        let data_len_bytes = (data.len() as u64).to_be_bytes();
+        std::iter::once(SEND_ANONYMOUS_REQUEST_TAG)
+            .chain(reply_surbs.to_be_bytes().iter().cloned())
+            .chain(std::iter::once(mix_hops.unwrap_or_default()))
+            .chain(recipient.to_bytes().iter().cloned()) // will not be length prefixed because the length is constant
+            .chain(data_len_bytes.iter().cloned())
+            .chain(data.into_iter())
+            .collect()
+    }
+
+    // SEND_ANONYMOUS_REQUEST_TAG || reply_surbs || mix_hops || recipient || data_len || data
+    fn deserialize_send_anonymous(b: &[u8]) -> Result<Self, error::Error> {
+        // this MUST match because it was called by 'deserialize'
+        debug_assert_eq!(b[0], SEND_ANONYMOUS_REQUEST_TAG);
+
+        // 1 (tag) + 4 (reply surbs) + 1 (mix hops)
+        let header_len = 1 + size_of::<u32>() + 1;
+
+        // we need to have at least the header + Recipient::LEN + sizeof<u64> bytes
+        if b.len() < header_len + Recipient::LEN + size_of::<u64>() {
+            return Err(error::Error::new(
+                ErrorKind::TooShortRequest,
+                "not enough data provided to recover 'send anonymous'".to_string(),
+            ));
+        }
+
+        let reply_surbs = u32::from_be_bytes(b[1..1 + size_of::<u32>()].try_into().unwrap());
+        let mix_hops = match b[header_len - 1] {
+            0 => None,
+            mix_hops => Some(validate_mix_hops(mix_hops)?),
+        };
+
+        let b = &b[header_len..];
+
+        let mut recipient_bytes = [0u8; Recipient::LEN];
+        recipient_bytes.copy_from_slice(&b[..Recipient::LEN]);
+        let recipient = match Recipient::try_from_bytes(recipient_bytes) {
+            Ok(recipient) => recipient,
+            Err(err) => {
+                return Err(error::Error::new(
+                    ErrorKind::MalformedRequest,
+                    format!("malformed recipient: {:?}", err),
+                ))
+            }
+        };
+
+        let data_len_bytes = &b[Recipient::LEN..Recipient::LEN + size_of::<u64>()];
+        let data_len = u64::from_be_bytes(data_len_bytes.try_into().unwrap());
+        let data = &b[Recipient::LEN + size_of::<u64>()..];
+        if data.len() as u64 != data_len {
+            return Err(error::Error::new(
+                ErrorKind::MalformedRequest,
+                format!(
+                    "data len has inconsistent length. specified: {} got: {}",
+                    data_len,
+                    data.len()
+                ),
+            ));
+        }
+
+        Ok(ClientRequest::SendAnonymous {
+            recipient,
+            message: data.to_vec(),
+            reply_surbs,
+            mix_hops,
+        })
+    }
+
+    // REPLY_WITH_TAG_REQUEST_TAG || sender_tag || message_len || message
+    fn serialize_reply_with_tag(message: Vec<u8>, sender_tag: AnonymousSenderTag) -> Vec<u8> {
+        let message_len_bytes = (message.len() as u64).to_be_bytes();
+
+        std::iter::once(REPLY_WITH_TAG_REQUEST_TAG)
+            .chain(sender_tag.to_bytes().iter().cloned())
+            .chain(message_len_bytes.iter().cloned())
+            .chain(message.into_iter())
+            .collect()
+    }
+
+    // REPLY_WITH_TAG_REQUEST_TAG || sender_tag || message_len || message
+    fn deserialize_reply_with_tag(b: &[u8]) -> Result<Self, error::Error> {
+        if b.len() < 1 + SENDER_TAG_SIZE + size_of::<u64>() {
+            return Err(error::Error::new(
+                ErrorKind::TooShortRequest,
+                "not enough data provided to recover 'reply with tag'".to_string(),
+            ));
+        }
+
+        // this MUST match because it was called by 'deserialize'
+        debug_assert_eq!(b[0], REPLY_WITH_TAG_REQUEST_TAG);
+
+        // the length has been checked above so this can't fail
+        let sender_tag = AnonymousSenderTag::try_from_slice(&b[1..1 + SENDER_TAG_SIZE]).unwrap();
+
+        let len_bound = 1 + SENDER_TAG_SIZE + size_of::<u64>();
+        let message_len = u64::from_be_bytes(b[1 + SENDER_TAG_SIZE..len_bound].try_into().unwrap());
+        let message = &b[len_bound..];
+        if message.len() as u64 != message_len {
+            return Err(error::Error::new(
+                ErrorKind::MalformedRequest,
+                format!(
+                    "message len has inconsistent length. specified: {} got: {}",
+                    message_len,
+                    message.len()
+                ),
+            ));
+        }
+
+        Ok(ClientRequest::ReplyWithTag {
+            sender_tag,
+            message: message.to_vec(),
+        })
+    }
+
     // SELF_ADDRESS_REQUEST_TAG
     fn serialize_self_address() -> Vec<u8> {
         std::iter::once(SELF_ADDRESS_REQUEST_TAG).collect()
@@ -208,7 +421,8 @@ impl ClientRequest {
                 recipient,
                 message,
                 with_reply_surb,
-            } => Self::serialize_send(recipient, message, with_reply_surb),
+                mix_hops,
+            } => Self::serialize_send(recipient, message, with_reply_surb, mix_hops),
 
             ClientRequest::Reply {
                 message,
@@ -216,6 +430,18 @@ impl ClientRequest {
             } => Self::serialize_reply(message, reply_surb),
 
             ClientRequest::SelfAddress => Self::serialize_self_address(),
+
+            ClientRequest::SendAnonymous {
+                recipient,
+                message,
+                reply_surbs,
+                mix_hops,
+            } => Self::serialize_send_anonymous(recipient, message, reply_surbs, mix_hops),
+
+            ClientRequest::ReplyWithTag {
+                sender_tag,
+                message,
+            } => Self::serialize_reply_with_tag(message, sender_tag),
         }
     }
 
@@ -242,9 +468,11 @@ impl ClientRequest {
 
         // determine what kind of request that is and try to deserialize it
         match request_tag {
-            SEND_REQUEST_TAG => Self::deserialize_send(b),
+            SEND_REQUEST_TAG | SEND_WITH_MIX_HOPS_REQUEST_TAG => Self::deserialize_send(b),
             REPLY_REQUEST_TAG => Self::deserialize_reply(b),
             SELF_ADDRESS_REQUEST_TAG => Ok(Self::deserialize_self_address(b)),
+            SEND_ANONYMOUS_REQUEST_TAG => Self::deserialize_send_anonymous(b),
+            REPLY_WITH_TAG_REQUEST_TAG => Self::deserialize_reply_with_tag(b),
             n => Err(error::Error::new(
                 ErrorKind::UnknownRequest,
                 format!("type {}", n),
@@ -252,17 +480,72 @@ impl ClientRequest {
         }
     }
 
-    pub fn try_from_binary(raw_req: Vec<u8>) -> Result<Self, error::Error> {
-        Self::deserialize(&raw_req)
+    // REQUEST_WITH_ID_TAG || request_id || request
+    // OR
+    // request
+    pub fn serialize_with_id(self, request_id: Option<RequestId>) -> Vec<u8> {
+        match request_id {
+            None => self.serialize(),
+            Some(request_id) => std::iter::once(REQUEST_WITH_ID_TAG)
+                .chain(request_id.to_be_bytes().iter().cloned())
+                .chain(self.serialize().into_iter())
+                .collect(),
+        }
     }
 
-    pub fn try_from_text(raw_req: String) -> Result<Self, error::Error> {
+    // REQUEST_WITH_ID_TAG || request_id || request
+    // OR
+    // request
+    //
+    // note that the request id is returned even if the request itself turned out to be malformed
+    // so that the resultant error response could still be attributed to it
+    pub fn deserialize_with_id(b: &[u8]) -> (Option<RequestId>, Result<Self, error::Error>) {
+        if b.first() != Some(&REQUEST_WITH_ID_TAG) {
+            return (None, Self::deserialize(b));
+        }
+
+        if b.len() < 1 + size_of::<RequestId>() {
+            return (
+                None,
+                Err(error::Error::new(
+                    ErrorKind::TooShortRequest,
+                    "not enough data provided to recover request id".to_string(),
+                )),
+            );
+        }
+
+        let request_id = RequestId::from_be_bytes(
+            b[1..1 + size_of::<RequestId>()]
+                .as_ref()
+                .try_into()
+                .unwrap(),
+        );
+        (
+            Some(request_id),
+            Self::deserialize(&b[1 + size_of::<RequestId>()..]),
+        )
+    }
+
+    pub fn try_from_binary(raw_req: Vec<u8>) -> (Option<RequestId>, Result<Self, error::Error>) {
+        Self::deserialize_with_id(&raw_req)
+    }
+
+    pub fn try_from_text(raw_req: String) -> (Option<RequestId>, Result<Self, error::Error>) {
         // use the intermediate string structure and let serde do bunch of work for us
-        let text_req = ClientRequestText::try_from(raw_req).map_err(|json_err| {
-            error::Error::new(ErrorKind::MalformedRequest, json_err.to_string())
-        })?;
+        let text_req = match ClientRequestText::try_from(raw_req) {
+            Ok(text_req) => text_req,
+            Err(json_err) => {
+                return (
+                    None,
+                    Err(error::Error::new(
+                        ErrorKind::MalformedRequest,
+                        json_err.to_string(),
+                    )),
+                )
+            }
+        };
 
-        text_req.try_into()
+        (text_req.request_id, text_req.request.try_into())
     }
 }
 
@@ -280,6 +563,7 @@ mod tests {
             recipient,
             message: b"foomp".to_vec(),
             with_reply_surb: false,
+            mix_hops: None,
         };
 
         let bytes = send_request_no_surb.serialize();
@@ -289,8 +573,10 @@ mod tests {
                 recipient,
                 message,
                 with_reply_surb,
+                mix_hops,
             } => {
                 assert_eq!(recipient.to_string(), recipient_string);
+                assert!(mix_hops.is_none());
                 assert_eq!(message, b"foomp".to_vec());
                 assert!(!with_reply_surb)
             }
@@ -301,6 +587,7 @@ mod tests {
             recipient,
             message: b"foomp".to_vec(),
             with_reply_surb: true,
+            mix_hops: None,
         };
 
         let bytes = send_request_surb.serialize();
@@ -310,8 +597,10 @@ mod tests {
                 recipient,
                 message,
                 with_reply_surb,
+                mix_hops,
             } => {
                 assert_eq!(recipient.to_string(), recipient_string);
+                assert!(mix_hops.is_none());
                 assert_eq!(message, b"foomp".to_vec());
                 assert!(with_reply_surb)
             }
@@ -319,6 +608,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn send_request_with_mix_hops_serialization_works() {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let recipient_string = recipient.to_string();
+
+        let send_request = ClientRequest::Send {
+            recipient,
+            message: b"foomp".to_vec(),
+            with_reply_surb: true,
+            mix_hops: Some(2),
+        };
+
+        let bytes = send_request.serialize();
+        assert_eq!(bytes[0], SEND_WITH_MIX_HOPS_REQUEST_TAG);
+        let recovered = ClientRequest::deserialize(&bytes).unwrap();
+        match recovered {
+            ClientRequest::Send {
+                recipient,
+                message,
+                with_reply_surb,
+                mix_hops,
+            } => {
+                assert_eq!(recipient.to_string(), recipient_string);
+                assert_eq!(message, b"foomp".to_vec());
+                assert!(with_reply_surb);
+                assert_eq!(mix_hops, Some(2));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn send_request_with_invalid_mix_hops_is_rejected() {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+
+        for mix_hops in [0, MAX_NUM_MIX_HOPS + 1] {
+            let send_request = ClientRequest::Send {
+                recipient,
+                message: b"foomp".to_vec(),
+                with_reply_surb: false,
+                mix_hops: Some(mix_hops),
+            };
+
+            let err = ClientRequest::deserialize(&send_request.serialize()).unwrap_err();
+            assert!(err.kind == ErrorKind::MalformedRequest);
+        }
+    }
+
     #[test]
     fn reply_request_serialization_works() {
         let reply_surb_string = "CjfVbHbfAjbC3W1BvNHGXmM8KNAnDNYGaHMLqVDxRYeo352csAihstup9bvqXam4dTWgfHak6KYwL9STaxWJ47E8XFZbSEvs7hEsfCkxr6K9WJuSBPK84GDDEvad8ZAuMCoaXsAd5S2Lj9a5eYyzG4SL1jHzhSMni55LyJwumxo1ZTGZNXggxw1RREosvyzNrW9Rsi3owyPqLCwXpiei2tHZty8w8midVvg8vDa7ZEJD842CLv8D4ohynSG7gDpqTrhkRaqYAuz7dzqNbMXLJRM7v823Jn16fA1L7YQxmcaUdUigyRSgTdb4i9ebiLGSyJ1iDe6Acz613PQZh6Ua3bZ2zVKq3dSycpDm9ngarRK4zJrAaUxRkdih8YzW3BY4nL9eqkfKA4N1TWCLaRU7zpSaf8yMEwrAZReU3d5zLV8c5KBfa2w8R5anhQeBojduZEGEad8kkHuKU52Zg93FeWHvH1qgZaEJMHH4nN7gKXz9mvWDhYwyF4vt3Uy2NhCHC3N5pL1gMme27YcoPcTEia1fxKZtnt6rtEozzTrAgCJGswigkFbkafiV5QaJwLKTUxtzhkZ57eEuLPte9UvJHzhhXUQ2CV7R2BUkJjYZy3Zsx6YYvdYWiAFFkWUwNEGA4QpShUHciBfsQVHQ7pN41YcyYUhbywQDFnTVgEmdUZ1XCBi3gyK5U3tDQmFzP1u9m3mWrUA8qB9mRDE7ptNDm5c3c1458L6uXLUth7sdMaa1Was5LCmCdmNDtvNpCDAEt1in6q6mrZFR85aCSU9b1baNGwZoCqPpPvydkVe63gXWoi8ebvdyxARrqACFrSB3ZdY3uJBw8CTMNkKK6MvcefMkSVVsbLd36TQAtYSCqrpiMc5dQuKcEu5QfciwvWYXYx8WFNAgKwP2mv49KCTvfozNDUCbjzDwSx92Zv5zjG8HbFpB13bY9UZGeyTPvv7gGxCzjGjJGbW6FRAheRQaaje5fUgCNM95Tv7wBmAMRHHFgWafeK1sdFH7dtCX9u898HucGTaboSKLsVh8J78gbbkHErwjMh7y9YRkceq5TTYS5da4kHnyNKYWSbxgZrmFg44XGKoeYcqoHB3XTZrdsf7F5fFeNwnihkmADvhAcaxXUmVqq4rQFZH84a1iC3WBWXYcqiZH2L7ujGWV7mMDT4HBEerDYjc8rNY4xGTPfivCrBCJW1i14aqW8xRdsdgTM88eTksvC3WPJLJ7iMzfKXeL7fMW1Ek6QGyQtLBW98vEESpdcDg6DeZ5rMz6VqjTGGqcCaFGfHoqtfxMDaBAEsyQ8h7XDX6dg1wq9wH6j4Tw7Tj1MEv1b8uj5NJkozZdzVdYA2QyE2Dp8vuurQG6uVdTDNww2d88RBQ8sVgjxN8gR45y4woJLhFAaNTAtrY6wDTxyXST13ni6oyqdYxjFVk9Am4v3DzH7Y2K8iRVSHfTk4FRbPULyaeK6wt2anvMJH1XdvVRgc14h67MnBxMgMD1UFk8AErN7CDj26fppe3c5G6KozJe4cSqQUGbBjVzBnrHCruqrfZBn5hNZHTV37bQiomqhRQXohxhuKEnNrGbAe1xNvJr9X";
@@ -342,6 +679,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn send_anonymous_request_serialization_works() {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let recipient_string = recipient.to_string();
+
+        for expected_mix_hops in [None, Some(2)] {
+            let send_request = ClientRequest::SendAnonymous {
+                recipient,
+                message: b"foomp".to_vec(),
+                reply_surbs: 42,
+                mix_hops: expected_mix_hops,
+            };
+
+            let bytes = send_request.serialize();
+            assert_eq!(bytes[0], SEND_ANONYMOUS_REQUEST_TAG);
+            let recovered = ClientRequest::deserialize(&bytes).unwrap();
+            match recovered {
+                ClientRequest::SendAnonymous {
+                    recipient,
+                    message,
+                    reply_surbs,
+                    mix_hops,
+                } => {
+                    assert_eq!(recipient.to_string(), recipient_string);
+                    assert_eq!(message, b"foomp".to_vec());
+                    assert_eq!(reply_surbs, 42);
+                    assert_eq!(mix_hops, expected_mix_hops);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn reply_with_tag_request_serialization_works() {
+        let sender_tag = AnonymousSenderTag::from_bytes([42; SENDER_TAG_SIZE]);
+        let reply_request = ClientRequest::ReplyWithTag {
+            sender_tag,
+            message: b"foomp".to_vec(),
+        };
+
+        let bytes = reply_request.serialize();
+        let recovered = ClientRequest::deserialize(&bytes).unwrap();
+        match recovered {
+            ClientRequest::ReplyWithTag {
+                sender_tag: recovered_tag,
+                message,
+            } => {
+                assert_eq!(recovered_tag, sender_tag);
+                assert_eq!(message, b"foomp".to_vec());
+            }
+            _ => unreachable!(),
+        }
+
+        let text_request = format!(
+            r#"{{"type":"replyWithTag","message":"foomp","senderTag":"{}"}}"#,
+            sender_tag
+        );
+        let (_, recovered) = ClientRequest::try_from_text(text_request);
+        match recovered.unwrap() {
+            ClientRequest::ReplyWithTag {
+                sender_tag: recovered_tag,
+                message,
+            } => {
+                assert_eq!(recovered_tag, sender_tag);
+                assert_eq!(message, b"foomp".to_vec());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn request_id_is_recovered_alongside_the_request() {
+        let bytes = ClientRequest::SelfAddress.serialize_with_id(Some(42));
+        assert_eq!(bytes[0], REQUEST_WITH_ID_TAG);
+
+        let (request_id, recovered) = ClientRequest::deserialize_with_id(&bytes);
+        assert_eq!(request_id, Some(42));
+        assert!(matches!(recovered.unwrap(), ClientRequest::SelfAddress));
+
+        let bytes = ClientRequest::SelfAddress.serialize_with_id(None);
+        let (request_id, recovered) = ClientRequest::deserialize_with_id(&bytes);
+        assert!(request_id.is_none());
+        assert!(matches!(recovered.unwrap(), ClientRequest::SelfAddress));
+    }
+
+    #[test]
+    fn request_id_is_recovered_for_malformed_request() {
+        let mut bytes = ClientRequest::SelfAddress.serialize_with_id(Some(42));
+        // replace the actual request tag with something undefined
+        *bytes.last_mut().unwrap() = 0xAB;
+
+        let (request_id, recovered) = ClientRequest::deserialize_with_id(&bytes);
+        assert_eq!(request_id, Some(42));
+        assert_eq!(recovered.unwrap_err().kind, ErrorKind::UnknownRequest);
+    }
+
+    #[test]
+    fn request_id_is_recovered_from_text_request() {
+        let (request_id, recovered) =
+            ClientRequest::try_from_text(r#"{"type":"selfAddress","requestId":42}"#.to_string());
+        assert_eq!(request_id, Some(42));
+        assert!(matches!(recovered.unwrap(), ClientRequest::SelfAddress));
+
+        let (request_id, recovered) =
+            ClientRequest::try_from_text(r#"{"type":"selfAddress"}"#.to_string());
+        assert!(request_id.is_none());
+        assert!(matches!(recovered.unwrap(), ClientRequest::SelfAddress));
+    }
+
     #[test]
     fn self_address_request_serialization_works() {
         let self_address_request = ClientRequest::SelfAddress;
@@ -7,9 +7,11 @@
 #![allow(unknown_lints)] // due to using `clippy::branches_sharing_code` which does not exist on `stable` just yet
 
 use crate::error::{self, ErrorKind};
+use crate::requests::RequestId;
 use crate::text::ServerResponseText;
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::anonymous_replies::requests::SENDER_TAG_SIZE;
+use nymsphinx::anonymous_replies::{AnonymousSenderTag, ReplySurb};
 use nymsphinx::receiver::ReconstructedMessage;
 use std::convert::TryInto;
 use std::mem::size_of;
@@ -23,10 +25,49 @@ pub const RECEIVED_RESPONSE_TAG: u8 = 0x01;
 /// Value tag representing [`SelfAddress`] variant of the [`ServerResponse`]
 pub const SELF_ADDRESS_RESPONSE_TAG: u8 = 0x02;
 
+/// Value tag representing [`Sent`] variant of the [`ServerResponse`]
+pub const SENT_RESPONSE_TAG: u8 = 0x03;
+
+/// Value tag representing [`Delivered`] variant of the [`ServerResponse`]
+pub const DELIVERED_RESPONSE_TAG: u8 = 0x04;
+
+/// Value tag representing [`Retrying`] variant of the [`ServerResponse`]
+pub const RETRYING_RESPONSE_TAG: u8 = 0x05;
+
+/// Value tag representing [`Failed`] variant of the [`ServerResponse`]
+pub const FAILED_RESPONSE_TAG: u8 = 0x06;
+
+/// Value tag representing [`AddressChanged`] variant of the [`ServerResponse`]
+pub const ADDRESS_CHANGED_RESPONSE_TAG: u8 = 0x07;
+
+/// Value of the reply flag of the [`Received`] variant of the [`ServerResponse`] indicating
+/// the message came from an anonymous sender and is followed by its tag rather than a reply SURB.
+pub const WITH_SENDER_TAG_FLAG: u8 = 0x02;
+
+/// Value tag indicating the [`ServerResponse`] that follows is preceded by the [`RequestId`]
+/// of the request that caused it.
+pub const RESPONSE_WITH_ID_TAG: u8 = 0xFF;
+
 #[derive(Debug)]
 pub enum ServerResponse {
     Received(ReconstructedMessage),
     SelfAddress(Recipient),
+    /// All packets of the message provided in the request have been sent to the mix network.
+    /// Note that it is only ever sent for requests that specified their [`RequestId`].
+    Sent,
+    /// All packets of the message provided in the request have been acknowledged.
+    /// Note that it is only ever sent for `Send` requests that specified their [`RequestId`].
+    Delivered,
+    /// Some packet of the message provided in the request has not been acknowledged in time
+    /// and is getting retransmitted.
+    /// Note that it is only ever sent for `Send` requests that specified their [`RequestId`].
+    Retrying,
+    /// The client has given up on delivering the message provided in the request.
+    /// Note that it is only ever sent for `Send` requests that specified their [`RequestId`].
+    Failed,
+    /// The client has switched over to a different gateway and hence its address has changed.
+    /// It is pushed to all connected applications without any prior request.
+    AddressChanged(Recipient),
     Error(error::Error),
 }
 
@@ -39,9 +80,19 @@ impl ServerResponse {
     }
 
     // RECEIVED_RESPONSE_TAG || with_reply || (surb_len || surb) || msg_len || msg
+    // OR
+    // RECEIVED_RESPONSE_TAG || 2 || sender_tag || msg_len || msg
     fn serialize_received(reconstructed_message: ReconstructedMessage) -> Vec<u8> {
         let message_len_bytes = (reconstructed_message.message.len() as u64).to_be_bytes();
-        if let Some(reply_surb) = reconstructed_message.reply_surb {
+        if let Some(sender_tag) = reconstructed_message.sender_tag {
+            // 2 || sender_tag || msg_len || msg
+            std::iter::once(RECEIVED_RESPONSE_TAG)
+                .chain(std::iter::once(WITH_SENDER_TAG_FLAG))
+                .chain(sender_tag.to_bytes().iter().cloned())
+                .chain(message_len_bytes.iter().cloned())
+                .chain(reconstructed_message.message.into_iter())
+                .collect()
+        } else if let Some(reply_surb) = reconstructed_message.reply_surb {
             let reply_surb_bytes = reply_surb.to_bytes();
             let surb_len_bytes = (reply_surb_bytes.len() as u64).to_be_bytes();
 
@@ -63,7 +114,47 @@ impl ServerResponse {
         }
     }
 
+    // RECEIVED_RESPONSE_TAG || 2 || sender_tag || msg_len || msg
+    fn deserialize_received_with_sender_tag(b: &[u8]) -> Result<Self, error::Error> {
+        if b.len() < 2 + SENDER_TAG_SIZE + size_of::<u64>() {
+            return Err(error::Error::new(
+                ErrorKind::TooShortResponse,
+                "not enough data provided to recover 'received' with sender tag".to_string(),
+            ));
+        }
+
+        // the length has been checked above so this can't fail
+        let sender_tag = AnonymousSenderTag::try_from_slice(&b[2..2 + SENDER_TAG_SIZE]).unwrap();
+
+        let len_bound = 2 + SENDER_TAG_SIZE + size_of::<u64>();
+        let message_len = u64::from_be_bytes(
+            b[2 + SENDER_TAG_SIZE..len_bound]
+                .as_ref()
+                .try_into()
+                .unwrap(),
+        );
+        let message = &b[len_bound..];
+        if message.len() as u64 != message_len {
+            return Err(error::Error::new(
+                ErrorKind::MalformedResponse,
+                format!(
+                    "message len has inconsistent length. specified: {} got: {}",
+                    message_len,
+                    message.len()
+                ),
+            ));
+        }
+
+        Ok(ServerResponse::Received(ReconstructedMessage {
+            message: message.to_vec(),
+            reply_surb: None,
+            sender_tag: Some(sender_tag),
+        }))
+    }
+
     // RECEIVED_RESPONSE_TAG || with_reply || (surb_len || surb) || msg_len || msg
+    // OR
+    // RECEIVED_RESPONSE_TAG || 2 || sender_tag || msg_len || msg
     fn deserialize_received(b: &[u8]) -> Result<Self, error::Error> {
         // this MUST match because it was called by 'deserialize'
         debug_assert_eq!(b[0], RECEIVED_RESPONSE_TAG);
@@ -76,6 +167,10 @@ impl ServerResponse {
             ));
         }
 
+        if b[1] == WITH_SENDER_TAG_FLAG {
+            return Self::deserialize_received_with_sender_tag(b);
+        }
+
         let with_reply_surb = match b[1] {
             0 => false,
             1 => true,
@@ -135,6 +230,7 @@ impl ServerResponse {
             Ok(ServerResponse::Received(ReconstructedMessage {
                 message: message.to_vec(),
                 reply_surb: Some(reply_surb),
+                sender_tag: None,
             }))
         } else {
             let message_len =
@@ -154,21 +250,26 @@ impl ServerResponse {
             Ok(ServerResponse::Received(ReconstructedMessage {
                 message: message.to_vec(),
                 reply_surb: None,
+                sender_tag: None,
             }))
         }
     }
 
     // SELF_ADDRESS_RESPONSE_TAG || self_address
-    fn serialize_self_address(address: Recipient) -> Vec<u8> {
-        std::iter::once(SELF_ADDRESS_RESPONSE_TAG)
+    // OR
+    // ADDRESS_CHANGED_RESPONSE_TAG || self_address
+    fn serialize_address(tag: u8, address: Recipient) -> Vec<u8> {
+        std::iter::once(tag)
             .chain(address.to_bytes().iter().cloned())
             .collect()
     }
 
     // SELF_ADDRESS_RESPONSE_TAG || self_address
-    fn deserialize_self_address(b: &[u8]) -> Result<Self, error::Error> {
+    // OR
+    // ADDRESS_CHANGED_RESPONSE_TAG || self_address
+    fn deserialize_address(b: &[u8]) -> Result<Self, error::Error> {
         // this MUST match because it was called by 'deserialize'
-        debug_assert_eq!(b[0], SELF_ADDRESS_RESPONSE_TAG);
+        debug_assert!(b[0] == SELF_ADDRESS_RESPONSE_TAG || b[0] == ADDRESS_CHANGED_RESPONSE_TAG);
 
         if b.len() != 1 + Recipient::LEN {
             return Err(error::Error::new(
@@ -190,7 +291,48 @@ impl ServerResponse {
             }
         };
 
-        Ok(ServerResponse::SelfAddress(recipient))
+        if b[0] == ADDRESS_CHANGED_RESPONSE_TAG {
+            Ok(ServerResponse::AddressChanged(recipient))
+        } else {
+            Ok(ServerResponse::SelfAddress(recipient))
+        }
+    }
+
+    // SENT_RESPONSE_TAG
+    fn serialize_sent() -> Vec<u8> {
+        std::iter::once(SENT_RESPONSE_TAG).collect()
+    }
+
+    // SENT_RESPONSE_TAG
+    fn deserialize_sent(b: &[u8]) -> Self {
+        // this MUST match because it was called by 'deserialize'
+        debug_assert_eq!(b[0], SENT_RESPONSE_TAG);
+
+        ServerResponse::Sent
+    }
+
+    // DELIVERED_RESPONSE_TAG
+    // OR
+    // RETRYING_RESPONSE_TAG
+    // OR
+    // FAILED_RESPONSE_TAG
+    fn serialize_delivery_status(tag: u8) -> Vec<u8> {
+        std::iter::once(tag).collect()
+    }
+
+    // DELIVERED_RESPONSE_TAG
+    // OR
+    // RETRYING_RESPONSE_TAG
+    // OR
+    // FAILED_RESPONSE_TAG
+    fn deserialize_delivery_status(b: &[u8]) -> Self {
+        // this MUST match because it was called by 'deserialize'
+        match b[0] {
+            DELIVERED_RESPONSE_TAG => ServerResponse::Delivered,
+            RETRYING_RESPONSE_TAG => ServerResponse::Retrying,
+            FAILED_RESPONSE_TAG => ServerResponse::Failed,
+            _ => unreachable!(),
+        }
     }
 
     // ERROR_RESPONSE_TAG || err_code || msg_len || msg
@@ -220,6 +362,8 @@ impl ServerResponse {
             _ if b[1] == (ErrorKind::TooShortRequest as u8) => ErrorKind::TooShortRequest,
             _ if b[1] == (ErrorKind::UnknownRequest as u8) => ErrorKind::UnknownRequest,
             _ if b[1] == (ErrorKind::MalformedRequest as u8) => ErrorKind::MalformedRequest,
+            _ if b[1] == (ErrorKind::TooLongReplyMessage as u8) => ErrorKind::TooLongReplyMessage,
+            _ if b[1] == (ErrorKind::MessageNotSent as u8) => ErrorKind::MessageNotSent,
 
             _ if b[1] == (ErrorKind::EmptyResponse as u8) => ErrorKind::EmptyResponse,
             _ if b[1] == (ErrorKind::TooShortResponse as u8) => ErrorKind::TooShortResponse,
@@ -271,7 +415,16 @@ impl ServerResponse {
             ServerResponse::Received(reconstructed_message) => {
                 Self::serialize_received(reconstructed_message)
             }
-            ServerResponse::SelfAddress(address) => Self::serialize_self_address(address),
+            ServerResponse::SelfAddress(address) => {
+                Self::serialize_address(SELF_ADDRESS_RESPONSE_TAG, address)
+            }
+            ServerResponse::Sent => Self::serialize_sent(),
+            ServerResponse::Delivered => Self::serialize_delivery_status(DELIVERED_RESPONSE_TAG),
+            ServerResponse::Retrying => Self::serialize_delivery_status(RETRYING_RESPONSE_TAG),
+            ServerResponse::Failed => Self::serialize_delivery_status(FAILED_RESPONSE_TAG),
+            ServerResponse::AddressChanged(address) => {
+                Self::serialize_address(ADDRESS_CHANGED_RESPONSE_TAG, address)
+            }
             ServerResponse::Error(err) => Self::serialize_error(err),
         }
     }
@@ -301,7 +454,13 @@ impl ServerResponse {
         // determine what kind of response that is and try to deserialize it
         match response_tag {
             RECEIVED_RESPONSE_TAG => Self::deserialize_received(b),
-            SELF_ADDRESS_RESPONSE_TAG => Self::deserialize_self_address(b),
+            SELF_ADDRESS_RESPONSE_TAG | ADDRESS_CHANGED_RESPONSE_TAG => {
+                Self::deserialize_address(b)
+            }
+            SENT_RESPONSE_TAG => Ok(Self::deserialize_sent(b)),
+            DELIVERED_RESPONSE_TAG | RETRYING_RESPONSE_TAG | FAILED_RESPONSE_TAG => {
+                Ok(Self::deserialize_delivery_status(b))
+            }
             ERROR_RESPONSE_TAG => Self::deserialize_error(b),
             n => Err(error::Error::new(
                 ErrorKind::UnknownResponse,
@@ -310,13 +469,54 @@ impl ServerResponse {
         }
     }
 
-    pub fn into_binary(self) -> Vec<u8> {
-        self.serialize()
+    // RESPONSE_WITH_ID_TAG || request_id || response
+    // OR
+    // response
+    pub fn serialize_with_id(self, request_id: Option<RequestId>) -> Vec<u8> {
+        match request_id {
+            None => self.serialize(),
+            Some(request_id) => std::iter::once(RESPONSE_WITH_ID_TAG)
+                .chain(request_id.to_be_bytes().iter().cloned())
+                .chain(self.serialize().into_iter())
+                .collect(),
+        }
     }
 
-    pub fn into_text(self) -> String {
+    // RESPONSE_WITH_ID_TAG || request_id || response
+    // OR
+    // response
+    pub fn deserialize_with_id(b: &[u8]) -> Result<(Option<RequestId>, Self), error::Error> {
+        if b.first() != Some(&RESPONSE_WITH_ID_TAG) {
+            return Ok((None, Self::deserialize(b)?));
+        }
+
+        if b.len() < 1 + size_of::<RequestId>() {
+            return Err(error::Error::new(
+                ErrorKind::TooShortResponse,
+                "not enough data provided to recover request id".to_string(),
+            ));
+        }
+
+        let request_id = RequestId::from_be_bytes(
+            b[1..1 + size_of::<RequestId>()]
+                .as_ref()
+                .try_into()
+                .unwrap(),
+        );
+        let response = Self::deserialize(&b[1 + size_of::<RequestId>()..])?;
+        Ok((Some(request_id), response))
+    }
+
+    pub fn into_binary(self, request_id: Option<RequestId>) -> Vec<u8> {
+        self.serialize_with_id(request_id)
+    }
+
+    pub fn into_text(self, request_id: Option<RequestId>) -> String {
         // use the intermediate string structure and let serde do bunch of work for us
-        let text_resp = ServerResponseText::from(self);
+        let text_resp = ServerResponseText {
+            request_id,
+            response: self.into(),
+        };
 
         text_resp.into()
     }
@@ -333,6 +533,7 @@ mod tests {
         let received_with_surb = ServerResponse::Received(ReconstructedMessage {
             message: b"foomp".to_vec(),
             reply_surb: Some(ReplySurb::from_base58_string(reply_surb_string).unwrap()),
+            sender_tag: None,
         });
         let bytes = received_with_surb.serialize();
         let recovered = ServerResponse::deserialize(&bytes).unwrap();
@@ -350,6 +551,7 @@ mod tests {
         let received_without_surb = ServerResponse::Received(ReconstructedMessage {
             message: b"foomp".to_vec(),
             reply_surb: None,
+            sender_tag: None,
         });
         let bytes = received_without_surb.serialize();
         let recovered = ServerResponse::deserialize(&bytes).unwrap();
@@ -360,6 +562,39 @@ mod tests {
             }
             _ => unreachable!(),
         }
+
+        let sender_tag = AnonymousSenderTag::from_bytes([42; SENDER_TAG_SIZE]);
+        let received_with_sender_tag = ServerResponse::Received(ReconstructedMessage {
+            message: b"foomp".to_vec(),
+            reply_surb: None,
+            sender_tag: Some(sender_tag),
+        });
+        let bytes = received_with_sender_tag.serialize();
+        assert_eq!(bytes[1], WITH_SENDER_TAG_FLAG);
+        let recovered = ServerResponse::deserialize(&bytes).unwrap();
+        match recovered {
+            ServerResponse::Received(reconstructed) => {
+                assert_eq!(reconstructed.message, b"foomp".to_vec());
+                assert!(reconstructed.reply_surb.is_none());
+                assert_eq!(reconstructed.sender_tag, Some(sender_tag))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn text_received_response_contains_sender_tag() {
+        let sender_tag = AnonymousSenderTag::from_bytes([42; SENDER_TAG_SIZE]);
+        let received = ServerResponse::Received(ReconstructedMessage {
+            message: b"foomp".to_vec(),
+            reply_surb: None,
+            sender_tag: Some(sender_tag),
+        });
+
+        let text: serde_json::Value = serde_json::from_str(&received.into_text(None)).unwrap();
+        assert_eq!(text["type"], "received");
+        assert_eq!(text["senderTag"], sender_tag.to_base58_string());
+        assert!(text["replySurb"].is_null());
     }
 
     #[test]
@@ -378,6 +613,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn address_changed_response_serialization_works() {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let recipient_string = recipient.to_string();
+
+        let bytes = ServerResponse::AddressChanged(recipient).serialize();
+        assert_eq!(bytes[0], ADDRESS_CHANGED_RESPONSE_TAG);
+        let recovered = ServerResponse::deserialize(&bytes).unwrap();
+        match recovered {
+            ServerResponse::AddressChanged(recipient) => {
+                assert_eq!(recipient.to_string(), recipient_string)
+            }
+            _ => unreachable!(),
+        }
+
+        let text: serde_json::Value =
+            serde_json::from_str(&ServerResponse::AddressChanged(recipient).into_text(None))
+                .unwrap();
+        assert_eq!(text["type"], "addressChanged");
+        assert_eq!(text["address"], recipient_string);
+    }
+
+    #[test]
+    fn sent_response_serialization_works() {
+        let bytes = ServerResponse::Sent.serialize();
+        let recovered = ServerResponse::deserialize(&bytes).unwrap();
+        assert!(matches!(recovered, ServerResponse::Sent));
+    }
+
+    #[test]
+    fn delivery_status_responses_serialization_works() {
+        let bytes = ServerResponse::Delivered.serialize_with_id(Some(42));
+        let (request_id, recovered) = ServerResponse::deserialize_with_id(&bytes).unwrap();
+        assert_eq!(request_id, Some(42));
+        assert!(matches!(recovered, ServerResponse::Delivered));
+
+        let bytes = ServerResponse::Retrying.serialize_with_id(Some(42));
+        let (request_id, recovered) = ServerResponse::deserialize_with_id(&bytes).unwrap();
+        assert_eq!(request_id, Some(42));
+        assert!(matches!(recovered, ServerResponse::Retrying));
+
+        let bytes = ServerResponse::Failed.serialize_with_id(Some(42));
+        let (request_id, recovered) = ServerResponse::deserialize_with_id(&bytes).unwrap();
+        assert_eq!(request_id, Some(42));
+        assert!(matches!(recovered, ServerResponse::Failed));
+    }
+
+    #[test]
+    fn request_id_is_recovered_alongside_the_response() {
+        let bytes = ServerResponse::Sent.serialize_with_id(Some(42));
+        assert_eq!(bytes[0], RESPONSE_WITH_ID_TAG);
+
+        let (request_id, recovered) = ServerResponse::deserialize_with_id(&bytes).unwrap();
+        assert_eq!(request_id, Some(42));
+        assert!(matches!(recovered, ServerResponse::Sent));
+
+        let bytes = ServerResponse::Sent.serialize_with_id(None);
+        let (request_id, recovered) = ServerResponse::deserialize_with_id(&bytes).unwrap();
+        assert!(request_id.is_none());
+        assert!(matches!(recovered, ServerResponse::Sent));
+    }
+
+    #[test]
+    fn text_error_response_contains_error_kind_and_request_id() {
+        let error_response = ServerResponse::Error(error::Error::new(
+            ErrorKind::TooLongReplyMessage,
+            "foomp message".to_string(),
+        ));
+
+        let text: serde_json::Value =
+            serde_json::from_str(&error_response.into_text(Some(42))).unwrap();
+        assert_eq!(text["type"], "error");
+        assert_eq!(text["kind"], "tooLongReplyMessage");
+        assert_eq!(
+            text["message"],
+            "message too long to be put inside a reply SURB:foomp message"
+        );
+        assert_eq!(text["requestId"], 42);
+    }
+
+    #[test]
+    fn text_response_without_request_id_omits_it() {
+        let text: serde_json::Value =
+            serde_json::from_str(&ServerResponse::Sent.into_text(None)).unwrap();
+        assert_eq!(text["type"], "sent");
+        assert!(text.get("requestId").is_none());
+    }
+
     #[test]
     fn error_response_serialization_works() {
         let dummy_error = error::Error::new(ErrorKind::UnknownRequest, "foomp message".to_string());
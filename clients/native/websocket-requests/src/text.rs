@@ -2,24 +2,35 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::ErrorKind;
-use crate::requests::ClientRequest;
+use crate::requests::{validate_mix_hops, ClientRequest, RequestId};
 use crate::responses::ServerResponse;
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::anonymous_replies::{AnonymousSenderTag, ReplySurb};
 use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
 
 // local text equivalent of `ClientRequest` for easier serialization + deserialization with serde
 // TODO: figure out if there's an easy way to avoid defining it
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ClientRequestText {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) request_id: Option<RequestId>,
+    #[serde(flatten)]
+    pub(super) request: ClientRequestTextBody,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "camelCase")]
-pub(super) enum ClientRequestText {
+pub(super) enum ClientRequestTextBody {
     #[serde(rename_all = "camelCase")]
     Send {
         message: String,
         recipient: String,
         with_reply_surb: bool,
+        #[serde(default)]
+        mix_hops: Option<u8>,
     },
     SelfAddress,
     #[serde(rename_all = "camelCase")]
@@ -27,6 +38,19 @@ pub(super) enum ClientRequestText {
         message: String,
         reply_surb: String,
     },
+    #[serde(rename_all = "camelCase")]
+    SendAnonymous {
+        message: String,
+        recipient: String,
+        reply_surbs: u32,
+        #[serde(default)]
+        mix_hops: Option<u8>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReplyWithTag {
+        message: String,
+        sender_tag: String,
+    },
 }
 
 impl TryFrom<String> for ClientRequestText {
@@ -37,29 +61,32 @@ impl TryFrom<String> for ClientRequestText {
     }
 }
 
-impl TryInto<ClientRequest> for ClientRequestText {
+impl TryInto<ClientRequest> for ClientRequestTextBody {
     type Error = crate::error::Error;
 
     fn try_into(self) -> Result<ClientRequest, Self::Error> {
         match self {
-            ClientRequestText::Send {
+            ClientRequestTextBody::Send {
                 message,
                 recipient,
                 with_reply_surb,
+                mix_hops,
             } => {
                 let message_bytes = message.into_bytes();
                 let recipient = Recipient::try_from_base58_string(recipient).map_err(|err| {
                     Self::Error::new(ErrorKind::MalformedRequest, err.to_string())
                 })?;
+                let mix_hops = mix_hops.map(validate_mix_hops).transpose()?;
 
                 Ok(ClientRequest::Send {
                     message: message_bytes,
                     recipient,
                     with_reply_surb,
+                    mix_hops,
                 })
             }
-            ClientRequestText::SelfAddress => Ok(ClientRequest::SelfAddress),
-            ClientRequestText::Reply {
+            ClientRequestTextBody::SelfAddress => Ok(ClientRequest::SelfAddress),
+            ClientRequestTextBody::Reply {
                 message,
                 reply_surb,
             } => {
@@ -73,6 +100,40 @@ impl TryInto<ClientRequest> for ClientRequestText {
                     reply_surb,
                 })
             }
+            ClientRequestTextBody::SendAnonymous {
+                message,
+                recipient,
+                reply_surbs,
+                mix_hops,
+            } => {
+                let message_bytes = message.into_bytes();
+                let recipient = Recipient::try_from_base58_string(recipient).map_err(|err| {
+                    Self::Error::new(ErrorKind::MalformedRequest, err.to_string())
+                })?;
+                let mix_hops = mix_hops.map(validate_mix_hops).transpose()?;
+
+                Ok(ClientRequest::SendAnonymous {
+                    message: message_bytes,
+                    recipient,
+                    reply_surbs,
+                    mix_hops,
+                })
+            }
+            ClientRequestTextBody::ReplyWithTag {
+                message,
+                sender_tag,
+            } => {
+                let message_bytes = message.into_bytes();
+                let sender_tag =
+                    AnonymousSenderTag::try_from_base58_string(sender_tag).map_err(|err| {
+                        Self::Error::new(ErrorKind::MalformedRequest, err.to_string())
+                    })?;
+
+                Ok(ClientRequest::ReplyWithTag {
+                    message: message_bytes,
+                    sender_tag,
+                })
+            }
         }
     }
 }
@@ -80,18 +141,37 @@ impl TryInto<ClientRequest> for ClientRequestText {
 // local text equivalent of `ServerResponse` for easier serialization + deserialization with serde
 // TODO: figure out if there's an easy way to avoid defining it
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ServerResponseText {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) request_id: Option<RequestId>,
+    #[serde(flatten)]
+    pub(super) response: ServerResponseTextBody,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "camelCase")]
-pub(super) enum ServerResponseText {
+pub(super) enum ServerResponseTextBody {
     #[serde(rename_all = "camelCase")]
     Received {
         message: String,
         reply_surb: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sender_tag: Option<String>,
     },
     SelfAddress {
         address: String,
     },
+    Sent,
+    Delivered,
+    Retrying,
+    Failed,
+    AddressChanged {
+        address: String,
+    },
     Error {
+        kind: ErrorKind,
         message: String,
     },
 }
@@ -116,23 +196,36 @@ impl From<ServerResponseText> for String {
     }
 }
 
-impl From<ServerResponse> for ServerResponseText {
+impl From<ServerResponse> for ServerResponseTextBody {
     fn from(resp: ServerResponse) -> Self {
         match resp {
             ServerResponse::Received(reconstructed) => {
-                ServerResponseText::Received {
+                ServerResponseTextBody::Received {
                     // TODO: ask DH what is more appropriate, lossy utf8 conversion or returning error and then
                     // pure binary later
                     message: String::from_utf8_lossy(&reconstructed.message).into_owned(),
                     reply_surb: reconstructed
                         .reply_surb
                         .map(|reply_surb| reply_surb.to_base58_string()),
+                    sender_tag: reconstructed
+                        .sender_tag
+                        .map(|sender_tag| sender_tag.to_base58_string()),
                 }
             }
-            ServerResponse::SelfAddress(recipient) => ServerResponseText::SelfAddress {
+            ServerResponse::SelfAddress(recipient) => ServerResponseTextBody::SelfAddress {
+                address: recipient.to_string(),
+            },
+            ServerResponse::Sent => ServerResponseTextBody::Sent,
+            ServerResponse::Delivered => ServerResponseTextBody::Delivered,
+            ServerResponse::Retrying => ServerResponseTextBody::Retrying,
+            ServerResponse::Failed => ServerResponseTextBody::Failed,
+            ServerResponse::AddressChanged(recipient) => ServerResponseTextBody::AddressChanged {
                 address: recipient.to_string(),
             },
-            ServerResponse::Error(err) => ServerResponseText::Error {
+            // the message keeps its original format (`description:details`) for backwards
+            // compatibility, clients interested in the exact error should look at its kind instead
+            ServerResponse::Error(err) => ServerResponseTextBody::Error {
+                kind: err.kind,
                 message: err.to_string(),
             },
         }
@@ -66,6 +66,11 @@ impl Config {
         self
     }
 
+    pub fn with_anonymous_replies(mut self, send_anonymously: bool) -> Self {
+        self.socks5.send_anonymously = send_anonymously;
+        self
+    }
+
     // getters
     pub fn get_config_file_save_location(&self) -> PathBuf {
         self.config_directory().join(Self::config_file_name())
@@ -87,6 +92,10 @@ impl Config {
     pub fn get_listening_port(&self) -> u16 {
         self.socks5.listening_port
     }
+
+    pub fn get_send_anonymously(&self) -> bool {
+        self.socks5.send_anonymously
+    }
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -97,6 +106,11 @@ pub struct Socks5 {
 
     /// The mix address of the provider to which all requests are going to be sent.
     provider_mix_address: String,
+
+    /// Specifies whether this client is going to hide its address from the service provider
+    /// by attaching reply SURBs to its requests instead.
+    #[serde(default)]
+    send_anonymously: bool,
 }
 
 impl Socks5 {
@@ -104,6 +118,7 @@ impl Socks5 {
         Socks5 {
             listening_port: DEFAULT_SOCKS5_LISTENING_PORT,
             provider_mix_address: provider_mix_address.into(),
+            send_anonymously: false,
         }
     }
 }
@@ -113,6 +128,7 @@ impl Default for Socks5 {
         Socks5 {
             listening_port: DEFAULT_SOCKS5_LISTENING_PORT,
             provider_mix_address: "".into(),
+            send_anonymously: false,
         }
     }
 }
@@ -77,6 +77,7 @@ pub(crate) struct OverrideConfig {
     validators: Option<String>,
     port: Option<u16>,
     fastmode: bool,
+    use_anonymous_replies: bool,
 
     #[cfg(all(feature = "eth", not(feature = "coconut")))]
     enabled_credentials_mode: bool,
@@ -122,6 +123,10 @@ pub(crate) fn override_config(mut config: Config, args: OverrideConfig) -> Confi
         config = config.with_port(port);
     }
 
+    if args.use_anonymous_replies {
+        config = config.with_anonymous_replies(true);
+    }
+
     #[cfg(all(not(feature = "eth"), not(feature = "coconut")))]
     {
         config
@@ -9,6 +9,7 @@ use crate::{
 use clap::Args;
 use config::NymConfig;
 use log::*;
+use nymsphinx::params::MAX_NUM_MIX_HOPS;
 use version_checker::is_minor_version_compatible;
 
 #[derive(Args, Clone)]
@@ -38,6 +39,11 @@ pub(crate) struct Run {
     #[clap(short, long)]
     port: Option<u16>,
 
+    /// Hide the address of this client from the service provider by attaching reply SURBs
+    /// to the requests instead
+    #[clap(long)]
+    use_anonymous_replies: bool,
+
     /// Set this client to work in a enabled credentials mode that would attempt to use gateway
     /// with bandwidth credential requirement. If this value is set, --eth-endpoint and
     /// --eth-private-key don't need to be set.
@@ -64,6 +70,7 @@ impl From<Run> for OverrideConfig {
             validators: run_config.validators,
             port: run_config.port,
             fastmode: false,
+            use_anonymous_replies: run_config.use_anonymous_replies,
 
             #[cfg(all(feature = "eth", not(feature = "coconut")))]
             enabled_credentials_mode: run_config.enabled_credentials_mode,
@@ -118,5 +125,14 @@ pub(crate) async fn execute(args: &Run) {
         return;
     }
 
+    if !config.get_base().has_valid_num_mix_hops() {
+        error!(
+            "the configured number of mix hops ({}) is invalid - it must be between 1 and {}",
+            config.get_base().get_num_mix_hops(),
+            MAX_NUM_MIX_HOPS
+        );
+        return;
+    }
+
     NymClient::new(config).run_forever().await;
 }
@@ -1,9 +1,11 @@
 #![forbid(unsafe_code)]
 
 use super::authentication::{AuthenticationMethods, Authenticator, User};
+use super::mixnet_responses::PendingConnects;
 use super::request::{SocksCommand, SocksRequest};
-use super::types::{ResponseCode, SocksProxyError};
-use super::{RESERVED, SOCKS_VERSION};
+use super::types::{AddrType, ResponseCode, SocksProxyError};
+use super::udp::UdpRelay;
+use super::{ANONYMOUS_REQUEST_REPLY_SURBS, RESERVED, SOCKS_VERSION};
 use client_core::client::inbound_messages::InputMessage;
 use client_core::client::inbound_messages::InputMessageSender;
 use futures::channel::mpsc;
@@ -12,17 +14,24 @@ use log::*;
 use nymsphinx::addressing::clients::Recipient;
 use pin_project::pin_project;
 use proxy_helpers::connection_controller::{
-    ConnectionReceiver, ControllerCommand, ControllerSender,
+    ConnectionReceiver, ConnectionSender, ControllerCommand, ControllerSender,
 };
 use proxy_helpers::proxy_runner::ProxyRunner;
 use rand::RngCore;
-use socks5_requests::{ConnectionId, Message, RemoteAddress, Request};
+use socks5_requests::{ConnectStatus, ConnectionId, Message, RemoteAddress, Request};
 use std::io;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::pin::Pin;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
 use tokio::{self, net::TcpStream};
 
+// how long we are going to wait for the service provider to tell us whether it managed to
+// connect to the remote. Note that it includes the mixnet roundtrip.
+// If the provider has never reported any result, after this time we assume it's running an older
+// version that does not support it and fall back to proxying the data straight away.
+const CONNECT_RESULT_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[pin_project(project = StateProject)]
 enum StreamState {
     Available(TcpStream),
@@ -131,6 +140,7 @@ impl AsyncWrite for StreamState {
 /// SphinxSocksServer.
 pub(crate) struct SocksClient {
     controller_sender: ControllerSender,
+    pending_connects: PendingConnects,
     stream: StreamState,
     auth_nmethods: u8,
     authenticator: Authenticator,
@@ -138,6 +148,7 @@ pub(crate) struct SocksClient {
     input_sender: InputMessageSender,
     connection_id: ConnectionId,
     service_provider: Recipient,
+    send_anonymously: bool,
     self_address: Recipient,
     started_proxy: bool,
 }
@@ -161,12 +172,15 @@ impl SocksClient {
         authenticator: Authenticator,
         input_sender: InputMessageSender,
         service_provider: Recipient,
+        send_anonymously: bool,
         controller_sender: ControllerSender,
+        pending_connects: PendingConnects,
         self_address: Recipient,
     ) -> Self {
         let connection_id = Self::generate_random();
         SocksClient {
             controller_sender,
+            pending_connects,
             connection_id,
             stream: StreamState::Available(stream),
             auth_nmethods: 0,
@@ -174,6 +188,7 @@ impl SocksClient {
             authenticator,
             input_sender,
             service_provider,
+            send_anonymously,
             self_address,
             started_proxy: false,
         }
@@ -223,17 +238,57 @@ impl SocksClient {
     }
 
     async fn send_connect_to_mixnet(&mut self, remote_address: RemoteAddress) {
-        let req = Request::new_connect(self.connection_id, remote_address, self.self_address);
-        let msg = Message::Request(req);
-
-        let input_message = InputMessage::new_fresh(self.service_provider, msg.into_bytes(), false);
+        let input_message = if self.send_anonymously {
+            // rather than revealing our address, give the provider some reply SURBs to respond with
+            let req = Request::new_anonymous_connect(self.connection_id, remote_address);
+            InputMessage::new_anonymous(
+                self.service_provider,
+                Message::Request(req).into_bytes(),
+                ANONYMOUS_REQUEST_REPLY_SURBS,
+            )
+        } else {
+            let req = Request::new_connect(self.connection_id, remote_address, self.self_address);
+            InputMessage::new_fresh(
+                self.service_provider,
+                Message::Request(req).into_bytes(),
+                false,
+            )
+        };
         self.input_sender.unbounded_send(input_message).unwrap();
     }
 
-    async fn run_proxy(&mut self, conn_receiver: ConnectionReceiver, remote_proxy_target: String) {
-        self.send_connect_to_mixnet(remote_proxy_target.clone())
-            .await;
+    /// Asks the service provider to connect to the remote and waits for the result of that attempt.
+    async fn connect_remote(&mut self, remote_address: RemoteAddress) -> ConnectStatus {
+        if self.pending_connects.provider_is_legacy() {
+            // the provider won't tell us anything, so just optimistically start proxying
+            self.send_connect_to_mixnet(remote_address).await;
+            return ConnectStatus::Success;
+        }
+
+        let connect_result = self.pending_connects.register(self.connection_id);
+        self.send_connect_to_mixnet(remote_address).await;
+
+        match tokio::time::timeout(CONNECT_RESULT_TIMEOUT, connect_result).await {
+            Ok(Ok(status)) => status,
+            Ok(Err(_)) => {
+                // this should never happen as we never drop the sender without resolving it
+                error!(
+                    "the connect result channel got dropped (id: {})",
+                    self.connection_id
+                );
+                ConnectStatus::GeneralFailure
+            }
+            Err(_) => {
+                if self.pending_connects.timed_out(self.connection_id) {
+                    ConnectStatus::Success
+                } else {
+                    ConnectStatus::TtlExpired
+                }
+            }
+        }
+    }
 
+    async fn run_proxy(&mut self, conn_receiver: ConnectionReceiver, remote_proxy_target: String) {
         let stream = self.stream.run_proxy();
         let local_stream_remote = stream
             .peer_addr()
@@ -276,13 +331,25 @@ impl SocksClient {
             // Use the Proxy to connect to the specified addr/port
             SocksCommand::Connect => {
                 trace!("Connecting to: {:?}", remote_address.clone());
-                self.acknowledge_socks5().await;
 
+                // register the connection before sending the request so that we wouldn't lose
+                // any data that might arrive before the connect result
                 self.started_proxy = true;
                 self.controller_sender
                     .unbounded_send(ControllerCommand::Insert(self.connection_id, mix_sender))
                     .unwrap();
 
+                let connect_status = self.connect_remote(remote_address.clone()).await;
+                if connect_status != ConnectStatus::Success {
+                    info!(
+                        "Failed to connect to {} - {:?} (id: {})",
+                        remote_address, connect_status, self.connection_id
+                    );
+                    self.reply_socks5(connect_status.into()).await?;
+                    return self.shutdown().await;
+                }
+                self.reply_socks5(ResponseCode::Success).await?;
+
                 info!(
                     "Starting proxy for {} (id: {})",
                     remote_address.clone(),
@@ -296,19 +363,90 @@ impl SocksClient {
             }
 
             SocksCommand::Bind => unimplemented!(), // not handled
-            SocksCommand::UdpAssociate => unimplemented!(), // not handled
+            SocksCommand::UdpAssociate => {
+                // note: the requested address is the one the client is *going to* send datagrams
+                // from, if known, and we do not rely on it
+                self.run_udp_association(mix_sender, mix_receiver).await?;
+            }
         };
 
         Ok(())
     }
 
-    /// Writes a Socks5 header back to the requesting client's TCP stream,
-    /// basically saying "I acknowledge your request and am dealing with it".
-    async fn acknowledge_socks5(&mut self) {
+    async fn run_udp_association(
+        &mut self,
+        mix_sender: ConnectionSender,
+        mix_receiver: ConnectionReceiver,
+    ) -> Result<(), SocksProxyError> {
+        let (local_ip, client_ip) = match &self.stream {
+            StreamState::Available(stream) => (stream.local_addr()?.ip(), stream.peer_addr()?.ip()),
+            StreamState::RunningProxy => panic!("invalid state"),
+        };
+
+        let relay = UdpRelay::new(
+            local_ip,
+            client_ip,
+            self.connection_id,
+            self.input_sender.clone(),
+            self.service_provider,
+            self.send_anonymously,
+            self.self_address,
+        )
+        .await?;
+        let relay_address = relay.local_addr()?;
+        self.acknowledge_socks5_with_address(relay_address).await?;
+
+        self.started_proxy = true;
+        self.controller_sender
+            .unbounded_send(ControllerCommand::Insert(self.connection_id, mix_sender))
+            .unwrap();
+
+        info!(
+            "Starting UDP relay on {} (id: {})",
+            relay_address, self.connection_id
+        );
+        relay.run(&mut self.stream, mix_receiver).await;
+        info!(
+            "UDP relay on {} is finished (id: {})",
+            relay_address, self.connection_id
+        );
+
+        Ok(())
+    }
+
+    /// Writes a Socks5 header back to the requesting client's TCP stream including the provided
+    /// bound address, i.e. where the client should send its datagrams to.
+    async fn acknowledge_socks5_with_address(
+        &mut self,
+        address: SocketAddr,
+    ) -> Result<(), SocksProxyError> {
+        let (addr_type, addr_bytes) = match address.ip() {
+            IpAddr::V4(ip) => (AddrType::V4, ip.octets().to_vec()),
+            IpAddr::V6(ip) => (AddrType::V6, ip.octets().to_vec()),
+        };
+
+        let response: Vec<_> = [
+            SOCKS_VERSION,
+            ResponseCode::Success as u8,
+            RESERVED,
+            addr_type as u8,
+        ]
+        .into_iter()
+        .chain(addr_bytes.into_iter())
+        .chain(address.port().to_be_bytes().into_iter())
+        .collect();
+
+        self.stream.write_all(&response).await?;
+        Ok(())
+    }
+
+    /// Writes a Socks5 reply with the provided code back to the requesting client's TCP stream.
+    /// On success, it's basically saying "I acknowledge your request and am dealing with it".
+    async fn reply_socks5(&mut self, response_code: ResponseCode) -> Result<(), SocksProxyError> {
         self.stream
             .write_all(&[
                 SOCKS_VERSION,
-                ResponseCode::Success as u8,
+                response_code as u8,
                 RESERVED,
                 1,
                 127,
@@ -318,8 +456,8 @@ impl SocksClient {
                 0,
                 0,
             ])
-            .await
-            .unwrap();
+            .await?;
+        Ok(())
     }
 
     /// Authenticate the incoming request. Each request is checked for its
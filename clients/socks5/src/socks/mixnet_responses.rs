@@ -1,16 +1,113 @@
+use super::udp::encode_udp_reply;
 use client_core::client::received_buffer::ReconstructedMessagesReceiver;
 use client_core::client::received_buffer::{ReceivedBufferMessage, ReceivedBufferRequestSender};
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
 use log::*;
 use nymsphinx::receiver::ReconstructedMessage;
 use proxy_helpers::connection_controller::{ControllerCommand, ControllerSender};
-use socks5_requests::Message;
+use socks5_requests::{ConnectStatus, ConnectionId, Message};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Whether the service provider is known to report the results of its connection attempts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConnectResultSupport {
+    /// We haven't heard back from the provider yet.
+    Unknown,
+
+    /// The provider has sent us at least a single connect result.
+    Supported,
+
+    /// The provider has failed to report the result of our first connection attempt, so it's
+    /// most likely running an older version that just starts proxying the data.
+    Unsupported,
+}
+
+struct PendingConnectsInner {
+    pending: HashMap<ConnectionId, oneshot::Sender<ConnectStatus>>,
+    support: ConnectResultSupport,
+}
+
+impl Default for PendingConnectsInner {
+    fn default() -> Self {
+        PendingConnectsInner {
+            pending: HashMap::new(),
+            support: ConnectResultSupport::Unknown,
+        }
+    }
+}
+
+/// Keeps track of local connections waiting for the service provider to report the result of
+/// its connection attempt to the remote.
+#[derive(Clone, Default)]
+pub(crate) struct PendingConnects {
+    inner: Arc<Mutex<PendingConnectsInner>>,
+}
+
+impl PendingConnects {
+    pub(crate) fn register(&self, connection_id: ConnectionId) -> oneshot::Receiver<ConnectStatus> {
+        let (sender, receiver) = oneshot::channel();
+        self.inner
+            .lock()
+            .unwrap()
+            .pending
+            .insert(connection_id, sender);
+        receiver
+    }
+
+    /// Checks whether the service provider has been determined to never report connect results.
+    pub(crate) fn provider_is_legacy(&self) -> bool {
+        self.inner.lock().unwrap().support == ConnectResultSupport::Unsupported
+    }
+
+    /// Removes the timed out connection and decides whether it should still be attempted
+    /// (i.e. if the provider has never reported any connect result, it's most likely running
+    /// an older version and we should fall back to the legacy behaviour of just proxying the data).
+    pub(crate) fn timed_out(&self, connection_id: ConnectionId) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending.remove(&connection_id);
+        match inner.support {
+            ConnectResultSupport::Supported => false,
+            ConnectResultSupport::Unsupported => true,
+            ConnectResultSupport::Unknown => {
+                warn!("the service provider has not reported the result of our connection attempt - assuming it's running an older version that does not support it");
+                inner.support = ConnectResultSupport::Unsupported;
+                true
+            }
+        }
+    }
+
+    fn resolve(&self, connection_id: ConnectionId, status: ConnectStatus) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.support != ConnectResultSupport::Supported {
+            debug!("the service provider reports the results of its connection attempts");
+            inner.support = ConnectResultSupport::Supported;
+        }
+
+        match inner.pending.remove(&connection_id) {
+            // if the receiver is gone, the connection has already timed out
+            Some(sender) => {
+                if sender.send(status).is_err() {
+                    debug!(
+                        "connection {} is no longer waiting for its result",
+                        connection_id
+                    )
+                }
+            }
+            None => debug!(
+                "received connect result for unknown connection {}",
+                connection_id
+            ),
+        }
+    }
+}
 
 pub(crate) struct MixnetResponseListener {
     buffer_requester: ReceivedBufferRequestSender,
     mix_response_receiver: ReconstructedMessagesReceiver,
     controller_sender: ControllerSender,
+    pending_connects: PendingConnects,
 }
 
 impl Drop for MixnetResponseListener {
@@ -25,6 +122,7 @@ impl MixnetResponseListener {
     pub(crate) fn new(
         buffer_requester: ReceivedBufferRequestSender,
         controller_sender: ControllerSender,
+        pending_connects: PendingConnects,
     ) -> Self {
         let (mix_response_sender, mix_response_receiver) = mpsc::unbounded();
         buffer_requester
@@ -35,6 +133,7 @@ impl MixnetResponseListener {
             buffer_requester,
             mix_response_receiver,
             controller_sender,
+            pending_connects,
         }
     }
 
@@ -54,6 +153,24 @@ impl MixnetResponseListener {
                 return;
             }
             Ok(Message::Response(data)) => data,
+            Ok(Message::DatagramResponse(datagram)) => {
+                match encode_udp_reply(&datagram.source_address, datagram.data) {
+                    Ok(reply) => self
+                        .controller_sender
+                        .unbounded_send(ControllerCommand::SendDatagram(
+                            datagram.connection_id,
+                            reply,
+                        ))
+                        .unwrap(),
+                    Err(err) => warn!("failed to encode received datagram - {}", err),
+                }
+                return;
+            }
+            Ok(Message::ConnectResponse(connect_response)) => {
+                self.pending_connects
+                    .resolve(connect_response.connection_id, connect_response.status);
+                return;
+            }
         };
 
         self.controller_sender
@@ -74,3 +191,31 @@ impl MixnetResponseListener {
         error!("We should never see this message");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_is_assumed_legacy_if_it_has_never_reported_results() {
+        let pending_connects = PendingConnects::default();
+        let _result = pending_connects.register(42);
+
+        assert!(!pending_connects.provider_is_legacy());
+        assert!(pending_connects.timed_out(42));
+        assert!(pending_connects.provider_is_legacy());
+    }
+
+    #[test]
+    fn timeouts_are_failures_once_provider_has_reported_results() {
+        let pending_connects = PendingConnects::default();
+        let mut first = pending_connects.register(1);
+        let _second = pending_connects.register(2);
+
+        pending_connects.resolve(1, ConnectStatus::Success);
+        assert_eq!(first.try_recv().unwrap(), Some(ConnectStatus::Success));
+
+        assert!(!pending_connects.timed_out(2));
+        assert!(!pending_connects.provider_is_legacy());
+    }
+}
@@ -6,9 +6,14 @@ pub(crate) mod mixnet_responses;
 mod request;
 pub mod server;
 pub mod types;
+mod udp;
 pub mod utils;
 
 /// Version of socks
 const SOCKS_VERSION: u8 = 0x05;
 
 const RESERVED: u8 = 0x00;
+
+/// Number of reply SURBs attached to each request sent to the service provider when
+/// the client is hiding its address. The client-core is going to top them up on demand.
+const ANONYMOUS_REQUEST_REPLY_SURBS: u32 = 10;
@@ -1,11 +1,12 @@
 use super::authentication::Authenticator;
 use super::client::SocksClient;
 use super::{
-    mixnet_responses::MixnetResponseListener,
+    mixnet_responses::{MixnetResponseListener, PendingConnects},
     types::{ResponseCode, SocksProxyError},
 };
 use client_core::client::{
-    inbound_messages::InputMessageSender, received_buffer::ReceivedBufferRequestSender,
+    gateway_failover::SelfAddressReceiver, inbound_messages::InputMessageSender,
+    received_buffer::ReceivedBufferRequestSender,
 };
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
@@ -18,7 +19,8 @@ pub struct SphinxSocksServer {
     authenticator: Authenticator,
     listening_address: SocketAddr,
     service_provider: Recipient,
-    self_address: Recipient,
+    send_anonymously: bool,
+    self_address: SelfAddressReceiver,
 }
 
 impl SphinxSocksServer {
@@ -27,7 +29,8 @@ impl SphinxSocksServer {
         port: u16,
         authenticator: Authenticator,
         service_provider: Recipient,
-        self_address: Recipient,
+        send_anonymously: bool,
+        self_address: SelfAddressReceiver,
     ) -> Self {
         // hardcode ip as we (presumably) ONLY want to listen locally. If we change it, we can
         // just modify the config
@@ -37,6 +40,7 @@ impl SphinxSocksServer {
             authenticator,
             listening_address: format!("{}:{}", ip, port).parse().unwrap(),
             service_provider,
+            send_anonymously,
             self_address,
         }
     }
@@ -57,9 +61,15 @@ impl SphinxSocksServer {
             active_streams_controller.run().await;
         });
 
+        // connections waiting for the result of their connection attempt
+        let pending_connects = PendingConnects::default();
+
         // listener for mix messages
-        let mut mixnet_response_listener =
-            MixnetResponseListener::new(buffer_requester, controller_sender.clone());
+        let mut mixnet_response_listener = MixnetResponseListener::new(
+            buffer_requester,
+            controller_sender.clone(),
+            pending_connects.clone(),
+        );
 
         tokio::spawn(async move {
             mixnet_response_listener.run().await;
@@ -73,8 +83,11 @@ impl SphinxSocksServer {
                     self.authenticator.clone(),
                     input_sender.clone(),
                     self.service_provider,
+                    self.send_anonymously,
                     controller_sender.clone(),
-                    self.self_address,
+                    pending_connects.clone(),
+                    // new connections always use our current address
+                    *self.self_address.borrow(),
                 );
 
                 tokio::spawn(async move {
@@ -1,4 +1,6 @@
 use snafu::Snafu;
+use socks5_requests::ConnectStatus;
+
 #[derive(Debug, Snafu)]
 /// Possible SOCKS5 Response Codes
 pub(crate) enum ResponseCode {
@@ -21,6 +23,23 @@ pub(crate) enum ResponseCode {
     AddrTypeNotSupported = 0x08,
 }
 
+impl From<ConnectStatus> for ResponseCode {
+    fn from(status: ConnectStatus) -> Self {
+        match status {
+            ConnectStatus::Success => ResponseCode::Success,
+            ConnectStatus::GeneralFailure => ResponseCode::Failure,
+            ConnectStatus::NotAllowed => ResponseCode::RuleFailure,
+            ConnectStatus::NetworkUnreachable => ResponseCode::NetworkUnreachable,
+            ConnectStatus::HostUnreachable => ResponseCode::HostUnreachable,
+            ConnectStatus::ConnectionRefused => ResponseCode::ConnectionRefused,
+            ConnectStatus::TtlExpired => ResponseCode::TtlExpired,
+            // there's no dedicated reply code, but it's the closest match as the service provider
+            // refused the connection based on its rules
+            ConnectStatus::LimitExceeded => ResponseCode::RuleFailure,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum SocksProxyError {
     GenericError(Box<dyn std::error::Error + Send + Sync>),
@@ -0,0 +1,339 @@
+use super::types::{AddrType, SocksProxyError};
+use super::utils as socks_utils;
+use super::{ANONYMOUS_REQUEST_REPLY_SURBS, RESERVED};
+use client_core::client::inbound_messages::{InputMessage, InputMessageSender};
+use futures::StreamExt;
+use log::*;
+use nymsphinx::addressing::clients::Recipient;
+use proxy_helpers::connection_controller::ConnectionReceiver;
+use socks5_requests::{ConnectionId, Message, RemoteAddress, Request};
+use std::net::{IpAddr, SocketAddr};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::UdpSocket;
+
+// maximum size of a UDP datagram we're willing to receive from the local application
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum UdpPacketError {
+    TooShort,
+    FragmentationNotSupported,
+    UnsupportedAddressType(u8),
+    InvalidSourceAddress,
+}
+
+impl std::fmt::Display for UdpPacketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UdpPacketError::TooShort => write!(f, "udp packet is too short"),
+            UdpPacketError::FragmentationNotSupported => {
+                write!(f, "udp fragmentation is not supported")
+            }
+            UdpPacketError::UnsupportedAddressType(typ) => {
+                write!(f, "unsupported address type {}", typ)
+            }
+            UdpPacketError::InvalidSourceAddress => write!(f, "invalid datagram source address"),
+        }
+    }
+}
+
+impl std::error::Error for UdpPacketError {}
+
+/// Parses a datagram received from the local application. Each of them is prefixed with
+/// the following header:
+///
+/// +----+------+------+----------+----------+----------+
+/// |RSV | FRAG | ATYP | DST.ADDR | DST.PORT |   DATA   |
+/// +----+------+------+----------+----------+----------+
+/// | 2  |  1   |  1   | Variable |    2     | Variable |
+/// +----+------+------+----------+----------+----------+
+///
+/// Returns the target address (as `host:port`) alongside the actual data.
+pub(crate) fn parse_udp_request(packet: &[u8]) -> Result<(RemoteAddress, &[u8]), UdpPacketError> {
+    if packet.len() < 4 {
+        return Err(UdpPacketError::TooShort);
+    }
+
+    // we do not support reassembly of fragmented datagrams, so as per RFC1928 we drop them
+    if packet[2] != 0 {
+        return Err(UdpPacketError::FragmentationNotSupported);
+    }
+
+    let addr_type = AddrType::from(packet[3] as usize)
+        .ok_or(UdpPacketError::UnsupportedAddressType(packet[3]))?;
+
+    let (addr, remaining) = match addr_type {
+        AddrType::V4 => split_checked(&packet[4..], 4)?,
+        AddrType::V6 => split_checked(&packet[4..], 16)?,
+        AddrType::Domain => {
+            let domain_len = *packet.get(4).ok_or(UdpPacketError::TooShort)? as usize;
+            split_checked(&packet[5..], domain_len)?
+        }
+    };
+
+    let (port, data) = split_checked(remaining, 2)?;
+    let port = u16::from_be_bytes([port[0], port[1]]);
+
+    let address = socks_utils::pretty_print_addr(&addr_type, addr);
+    let remote_address = if addr_type == AddrType::V6 {
+        format!("[{}]:{}", address, port)
+    } else {
+        format!("{}:{}", address, port)
+    };
+
+    Ok((remote_address, data))
+}
+
+fn split_checked(b: &[u8], at: usize) -> Result<(&[u8], &[u8]), UdpPacketError> {
+    if b.len() < at {
+        Err(UdpPacketError::TooShort)
+    } else {
+        Ok(b.split_at(at))
+    }
+}
+
+/// Prepends the SOCKS5 UDP header (with the address of the remote that has sent the datagram)
+/// to the data so that it could be forwarded to the local application.
+pub(crate) fn encode_udp_reply(
+    source_address: &str,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, UdpPacketError> {
+    // the network requester always sends us the actual socket address of the remote
+    let source: SocketAddr = source_address
+        .parse()
+        .map_err(|_| UdpPacketError::InvalidSourceAddress)?;
+
+    let (addr_type, addr_bytes) = match source.ip() {
+        IpAddr::V4(ip) => (AddrType::V4, ip.octets().to_vec()),
+        IpAddr::V6(ip) => (AddrType::V6, ip.octets().to_vec()),
+    };
+
+    Ok([RESERVED, RESERVED, 0, addr_type as u8]
+        .into_iter()
+        .chain(addr_bytes.into_iter())
+        .chain(source.port().to_be_bytes().into_iter())
+        .chain(data.into_iter())
+        .collect())
+}
+
+/// Relays datagrams between a local application and the service provider for the duration
+/// of a single UDP association.
+pub(crate) struct UdpRelay {
+    socket: UdpSocket,
+    client_ip: IpAddr,
+    client_address: Option<SocketAddr>,
+    connection_id: ConnectionId,
+    input_sender: InputMessageSender,
+    service_provider: Recipient,
+    send_anonymously: bool,
+    self_address: Recipient,
+}
+
+impl UdpRelay {
+    pub(crate) async fn new(
+        bind_ip: IpAddr,
+        client_ip: IpAddr,
+        connection_id: ConnectionId,
+        input_sender: InputMessageSender,
+        service_provider: Recipient,
+        send_anonymously: bool,
+        self_address: Recipient,
+    ) -> Result<Self, SocksProxyError> {
+        let socket = UdpSocket::bind(SocketAddr::new(bind_ip, 0)).await?;
+        Ok(UdpRelay {
+            socket,
+            client_ip,
+            client_address: None,
+            connection_id,
+            input_sender,
+            service_provider,
+            send_anonymously,
+            self_address,
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> Result<SocketAddr, SocksProxyError> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    fn handle_local_datagram(&mut self, datagram: &[u8], source: SocketAddr) {
+        // as per RFC1928, only accept datagrams from the client that requested the association
+        if source.ip() != self.client_ip {
+            warn!(
+                "Received a datagram from unexpected source {} (association {})",
+                source, self.connection_id
+            );
+            return;
+        }
+        self.client_address = Some(source);
+
+        let (remote_address, data) = match parse_udp_request(datagram) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                debug!("Dropping invalid local datagram - {}", err);
+                return;
+            }
+        };
+
+        trace!(
+            "Sending {} bytes datagram to {} (association {})",
+            data.len(),
+            remote_address,
+            self.connection_id
+        );
+        let input_message = if self.send_anonymously {
+            let req =
+                Request::new_anonymous_datagram(self.connection_id, remote_address, data.to_vec());
+            InputMessage::new_anonymous(
+                self.service_provider,
+                Message::Request(req).into_bytes(),
+                ANONYMOUS_REQUEST_REPLY_SURBS,
+            )
+        } else {
+            let req = Request::new_datagram(
+                self.connection_id,
+                remote_address,
+                self.self_address,
+                data.to_vec(),
+            );
+            InputMessage::new_fresh(
+                self.service_provider,
+                Message::Request(req).into_bytes(),
+                false,
+            )
+        };
+        self.input_sender.unbounded_send(input_message).unwrap();
+    }
+
+    async fn handle_mix_datagram(&self, reply: Vec<u8>) {
+        match self.client_address {
+            Some(client_address) => {
+                if let Err(err) = self.socket.send_to(&reply, client_address).await {
+                    warn!("Failed to forward datagram to {} - {}", client_address, err)
+                }
+            }
+            None => debug!("Received a datagram before the local client has sent anything"),
+        }
+    }
+
+    /// Runs the relay until the controlling TCP connection gets closed.
+    /// As per RFC1928, the UDP association terminates when it happens.
+    pub(crate) async fn run<R>(
+        mut self,
+        mut control_stream: R,
+        mut mix_receiver: ConnectionReceiver,
+    ) where
+        R: AsyncRead + Unpin,
+    {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let mut control_buf = [0u8; 64];
+
+        loop {
+            tokio::select! {
+                received = self.socket.recv_from(&mut buf) => match received {
+                    Ok((n, source)) => self.handle_local_datagram(&buf[..n], source),
+                    Err(err) => {
+                        error!("Failed to read from the udp socket - {}", err);
+                        break;
+                    }
+                },
+                mix_message = mix_receiver.next() => match mix_message {
+                    Some(message) => self.handle_mix_datagram(message.payload).await,
+                    None => {
+                        debug!("Mix receiver for association {} got closed", self.connection_id);
+                        break;
+                    }
+                },
+                read = control_stream.read(&mut control_buf) => match read {
+                    // any data on the control connection is ignored,
+                    // we only care about its closure
+                    Ok(0) | Err(_) => {
+                        debug!(
+                            "Control connection of association {} got closed",
+                            self.connection_id
+                        );
+                        break;
+                    }
+                    Ok(_) => {}
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_ipv4_request() {
+        let packet = [0, 0, 0, 1, 1, 1, 1, 1, 0, 53, 42, 43];
+        let (address, data) = parse_udp_request(&packet).unwrap();
+        assert_eq!("1.1.1.1:53", address);
+        assert_eq!(&[42, 43], data);
+    }
+
+    #[test]
+    fn parsing_domain_request() {
+        let mut packet = vec![0, 0, 0, 3, 7];
+        packet.extend_from_slice(b"foo.com");
+        packet.extend_from_slice(&[1, 187]);
+        let (address, data) = parse_udp_request(&packet).unwrap();
+        assert_eq!("foo.com:443", address);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn parsing_ipv6_request() {
+        let mut packet = vec![0, 0, 0, 4];
+        packet.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        packet.extend_from_slice(&[0, 53, 1]);
+        let (address, data) = parse_udp_request(&packet).unwrap();
+        assert_eq!(
+            address.parse::<SocketAddr>().unwrap(),
+            "[::1]:53".parse().unwrap()
+        );
+        assert_eq!(&[1], data);
+    }
+
+    #[test]
+    fn parsing_fails_for_fragmented_request() {
+        let packet = [0, 0, 1, 1, 1, 1, 1, 1, 0, 53];
+        assert_eq!(
+            UdpPacketError::FragmentationNotSupported,
+            parse_udp_request(&packet).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn parsing_fails_for_truncated_request() {
+        assert_eq!(
+            UdpPacketError::TooShort,
+            parse_udp_request(&[0, 0, 0]).unwrap_err()
+        );
+        assert_eq!(
+            UdpPacketError::TooShort,
+            parse_udp_request(&[0, 0, 0, 1, 1, 1, 1, 1, 0]).unwrap_err()
+        );
+        assert_eq!(
+            UdpPacketError::TooShort,
+            parse_udp_request(&[0, 0, 0, 3, 10, 102, 111]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn encoded_reply_can_be_parsed_back() {
+        let reply = encode_udp_reply("1.2.3.4:5353", vec![1, 2, 3]).unwrap();
+        let (address, data) = parse_udp_request(&reply).unwrap();
+        assert_eq!("1.2.3.4:5353", address);
+        assert_eq!(&[1, 2, 3], data);
+    }
+
+    #[test]
+    fn encoding_reply_fails_for_domain_source() {
+        assert_eq!(
+            UdpPacketError::InvalidSourceAddress,
+            encode_udp_reply("foo.com:53", vec![]).unwrap_err()
+        );
+    }
+}
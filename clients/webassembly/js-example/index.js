@@ -88,13 +88,13 @@ function displaySend(message) {
  */
 function displayReceived(message) {
     const content = message.message
-    const replySurb = message.replySurb
+    const replySurb = message.reply_surb
 
     let timestamp = new Date().toISOString().substr(11, 12);
     let receivedDiv = document.createElement("div")
     let paragraph = document.createElement("p")
     paragraph.setAttribute('style', 'color: green')
-    let paragraphContent = document.createTextNode(timestamp + " received >>> " + content + ((replySurb != null) ? "Reply SURB was attached here (but we can't do anything with it yet" : " (NO REPLY-SURB AVAILABLE)"))
+    let paragraphContent = document.createTextNode(timestamp + " received >>> " + content + ((replySurb != null) ? " (reply SURB was attached - use `client.reply` to respond)" : " (NO REPLY-SURB AVAILABLE)"))
     paragraph.appendChild(paragraphContent)
     receivedDiv.appendChild(paragraph)
     document.getElementById("output").appendChild(receivedDiv)
@@ -1,32 +1,69 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use client_core::client::cover_traffic_stream::LoopCoverTrafficStream;
+use client_core::client::gateway_failover::{
+    self_address_channel, GatewayDetails, GatewayFailover, SelfAddressReceiver, SelfAddressSender,
+};
+use client_core::client::inbound_messages::{
+    InputMessage, InputMessageReceiver, InputMessageSender,
+};
+use client_core::client::metrics::ClientMetrics;
+use client_core::client::mix_traffic::{
+    BatchMixMessageReceiver, BatchMixMessageSender, MixTrafficController,
+};
+use client_core::client::real_messages_control::{self, RealMessagesController};
+use client_core::client::received_buffer::{
+    ReceivedBufferMessage, ReceivedBufferRequestReceiver, ReceivedBufferRequestSender,
+    ReceivedMessagesBufferController,
+};
+use client_core::client::received_reply_surbs::ReceivedReplySurbsMap;
+use client_core::client::reply_key_storage::{ReplyKeyStorage, ReplyKeyStoragePruner};
+use client_core::client::topology_control::{
+    TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
+};
+use client_core::config::GatewayEndpoint;
 use crypto::asymmetric::{encryption, identity};
 use futures::channel::mpsc;
-use gateway_client::GatewayClient;
+use gateway_client::{
+    AcknowledgementReceiver, AcknowledgementSender, GatewayClient, MixnetMessageReceiver,
+    MixnetMessageSender,
+};
+use gateway_requests::registration::handshake::SharedKeys;
 use nymsphinx::acknowledgements::AckKey;
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::preparer::MessagePreparer;
+use nymsphinx::anonymous_replies::ReplySurb;
+use nymsphinx::params::DEFAULT_NUM_MIX_HOPS;
 use rand::rngs::OsRng;
-use received_processor::ReceivedMessagesProcessor;
+use received_processor::{forward_delivery_status, forward_received_messages, TrackedMessageId};
 use std::sync::Arc;
 use std::time::Duration;
-use topology::{gateway, nym_topology_from_bonds, NymTopology};
+use topology::gateway;
 use url::Url;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::spawn_local;
-use wasm_utils::{console_log, console_warn};
+use wasm_utils::console_log;
 
 pub(crate) mod received_processor;
 
 const DEFAULT_AVERAGE_PACKET_DELAY: Duration = Duration::from_millis(200);
 const DEFAULT_AVERAGE_ACK_DELAY: Duration = Duration::from_millis(200);
 const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_millis(1_500);
+const DEFAULT_ACK_WAIT_MULTIPLIER: f64 = 1.5;
+const DEFAULT_ACK_WAIT_ADDITION: Duration = Duration::from_millis(1_500);
+const DEFAULT_LOOP_COVER_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(200);
+const DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_TOPOLOGY_REFRESH_RATE: Duration = Duration::from_secs(5 * 60); // every 5min
+const DEFAULT_MAXIMUM_REPLY_SURB_AGE: Duration = Duration::from_secs(24 * 60 * 60); // 24h
+const DEFAULT_REPLY_KEYS_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60); // every 10min
+const DEFAULT_MINIMUM_REPLY_SURB_STORAGE_THRESHOLD: u32 = 10;
+const DEFAULT_MAXIMUM_REPLY_SURB_REQUEST_SIZE: u32 = 100;
 
 #[wasm_bindgen]
 pub struct NymClient {
     validator_server: Url,
     disabled_credentials_mode: bool,
+    maximum_retransmissions: Option<u32>,
 
     // TODO: technically this doesn't need to be an Arc since wasm is run on a single thread
     // however, once we eventually combine this code with the native-client's, it will make things
@@ -35,17 +72,16 @@ pub struct NymClient {
     encryption_keys: Arc<encryption::KeyPair>,
     ack_key: Arc<AckKey>,
 
-    message_preparer: Option<MessagePreparer<OsRng>>,
-    // message_receiver: MessageReceiver,
+    self_address: Option<Recipient>,
+    metrics: ClientMetrics,
 
-    // TODO: this should be stored somewhere persistently
-    // received_keys: HashSet<SURBEncryptionKey>,
-    topology: Option<NymTopology>,
-    gateway_client: Option<GatewayClient>,
+    /// Channel used for passing messages to the real traffic controller once the client is set up.
+    input_sender: Option<InputMessageSender>,
 
     // callbacks
     on_message: Option<js_sys::Function>,
     on_gateway_connect: Option<js_sys::Function>,
+    on_delivery_status: Option<js_sys::Function>,
 }
 
 #[wasm_bindgen]
@@ -65,14 +101,15 @@ impl NymClient {
             validator_server: validator_server
                 .parse()
                 .expect("malformed validator server url provided"),
-            message_preparer: None,
-            // received_keys: Default::default(),
-            topology: None,
-            gateway_client: None,
+            self_address: None,
+            metrics: ClientMetrics::new(),
+            input_sender: None,
 
             on_message: None,
             on_gateway_connect: None,
+            on_delivery_status: None,
             disabled_credentials_mode: true,
+            maximum_retransmissions: None,
         }
     }
 
@@ -80,6 +117,10 @@ impl NymClient {
         self.on_message = Some(on_message);
     }
 
+    pub fn set_on_delivery_status(&mut self, on_delivery_status: js_sys::Function) {
+        self.on_delivery_status = Some(on_delivery_status);
+    }
+
     pub fn set_on_gateway_connect(&mut self, on_connect: js_sys::Function) {
         console_log!("setting on connect...");
         self.on_gateway_connect = Some(on_connect)
@@ -93,15 +134,16 @@ impl NymClient {
         self.disabled_credentials_mode = disabled_credentials_mode;
     }
 
+    // Maximum number of times a single packet is going to be retransmitted before giving up on
+    // delivering the entire message. By default, only the delivery of messages reported through
+    // `on_delivery_status` is eventually given up on and other packets are retransmitted indefinitely.
+    pub fn set_maximum_retransmissions(&mut self, maximum_retransmissions: u32) {
+        self.maximum_retransmissions = Some(maximum_retransmissions);
+    }
+
     fn self_recipient(&self) -> Recipient {
-        Recipient::new(
-            *self.identity.public_key(),
-            *self.encryption_keys.public_key(),
-            self.gateway_client
-                .as_ref()
-                .expect("gateway connection was not established!")
-                .gateway_identity(),
-        )
+        self.self_address
+            .expect("gateway connection was not established!")
     }
 
     pub fn self_address(&self) -> String {
@@ -109,20 +151,197 @@ impl NymClient {
     }
 
     // Right now it's impossible to have async exported functions to take `&self` rather than self
-    pub async fn initial_setup(self) -> Self {
-        let disabled_credentials_mode = self.disabled_credentials_mode;
+    pub async fn initial_setup(mut self) -> Self {
+        let on_message = self.on_message.take().expect("on_message was not set!");
 
-        let bandwidth_controller = None;
-
-        let mut client = self.get_and_update_topology().await;
-        let gateway = client.choose_gateway();
+        let topology_accessor = TopologyAccessor::new();
+        self.start_topology_refresher(topology_accessor.clone())
+            .await;
+        let gateway = Self::choose_gateway(&topology_accessor).await;
 
         let (mixnet_messages_sender, mixnet_messages_receiver) = mpsc::unbounded();
         let (ack_sender, ack_receiver) = mpsc::unbounded();
 
+        let (gateway_client, shared_keys) = self
+            .start_gateway_client(&gateway, mixnet_messages_sender, ack_sender)
+            .await;
+
+        let self_address = Recipient::new(
+            *self.identity.public_key(),
+            *self.encryption_keys.public_key(),
+            gateway.identity_key,
+        );
+        self.self_address = Some(self_address);
+        match self.on_gateway_connect.as_ref() {
+            Some(callback) => {
+                callback
+                    .call0(&JsValue::null())
+                    .expect("on connect callback failed!");
+            }
+            None => console_log!("Gateway connection established - no callback specified"),
+        };
+
+        let (self_address_sender, self_address_receiver) = self_address_channel(self_address);
+        let (mix_tx, mix_rx) = mpsc::unbounded();
+        let (input_sender, input_receiver) = mpsc::unbounded();
+        let (received_buffer_request_sender, received_buffer_request_receiver) = mpsc::unbounded();
+
+        // the keys are only kept in memory, so any replies to our reply SURBs must arrive
+        // before the page is closed
+        let reply_key_storage = ReplyKeyStorage::new_ephemeral(DEFAULT_MAXIMUM_REPLY_SURB_AGE);
+        let received_reply_surbs = ReceivedReplySurbsMap::new(DEFAULT_MAXIMUM_REPLY_SURB_AGE);
+
+        self.start_received_messages_buffer_controller(
+            received_buffer_request_receiver,
+            mixnet_messages_receiver,
+            reply_key_storage.clone(),
+            received_reply_surbs.clone(),
+            input_sender.clone(),
+        );
+        ReplyKeyStoragePruner::new(
+            reply_key_storage.clone(),
+            DEFAULT_REPLY_KEYS_PRUNING_INTERVAL,
+        )
+        .start();
+
+        self.start_real_traffic_controller(
+            topology_accessor.clone(),
+            reply_key_storage,
+            received_reply_surbs,
+            ack_receiver,
+            input_receiver,
+            mix_tx.clone(),
+            self_address_receiver.clone(),
+        );
+
+        self.start_mix_traffic_controller(
+            &gateway,
+            shared_keys,
+            mix_rx,
+            gateway_client,
+            self_address_sender,
+        );
+        self.start_cover_traffic_stream(topology_accessor, mix_tx, self_address_receiver);
+
+        Self::start_received_messages_forwarder(received_buffer_request_sender, on_message);
+        self.input_sender = Some(input_sender);
+
+        self
+    }
+
+    // Right now it's impossible to have async exported functions to take `&mut self` rather than mut self
+    // TODO: try Rc<RefCell<Self>> approach?
+    pub async fn send_message(self, message: String, recipient: String) -> Self {
+        console_log!("Sending {} to {}", message, recipient);
+        self.send_binary(message.into_bytes(), recipient).await
+    }
+
+    pub async fn send_binary(self, message: Vec<u8>, recipient: String) -> Self {
+        let recipient = Recipient::try_from_base58_string(recipient).unwrap();
+        self.send_input_message(InputMessage::new_fresh(recipient, message, false));
+        self
+    }
+
+    // Once all fragments of the message get acknowledged, the `on_delivery_status` callback
+    // is going to be called with the provided `message_id`. It is also going to get called
+    // whenever any of the fragments is retransmitted or once we give up on delivering it.
+    pub async fn send_tracked_message(
+        self,
+        message: String,
+        recipient: String,
+        message_id: TrackedMessageId,
+    ) -> Self {
+        let recipient = Recipient::try_from_base58_string(recipient).unwrap();
+
+        let (delivery_sender, delivery_receiver) = mpsc::unbounded();
+        spawn_local(forward_delivery_status(
+            message_id,
+            delivery_receiver,
+            self.on_delivery_status.clone(),
+        ));
+
+        let input_message = InputMessage::new_fresh(recipient, message.into_bytes(), false)
+            .with_delivery_notifier(delivery_sender);
+        self.send_input_message(input_message);
+        self
+    }
+
+    // The attached reply SURB is going to be passed to the recipient alongside the message,
+    // so that it could send us back a single anonymous reply.
+    pub async fn send_with_reply_surb(self, message: Vec<u8>, recipient: String) -> Self {
+        let recipient = Recipient::try_from_base58_string(recipient).unwrap();
+        self.send_input_message(InputMessage::new_fresh(recipient, message, true));
+        self
+    }
+
+    // Sends an anonymous reply using the base58-encoded reply SURB received alongside a message.
+    pub async fn reply(self, reply_surb: String, message: Vec<u8>) -> Self {
+        let reply_surb =
+            ReplySurb::from_base58_string(reply_surb).expect("malformed reply SURB provided");
+        self.send_input_message(InputMessage::new_reply(reply_surb, message));
+        self
+    }
+}
+
+impl NymClient {
+    fn send_input_message(&self, message: InputMessage) {
+        self.input_sender
+            .as_ref()
+            .expect("the client was not set up!")
+            .unbounded_send(message)
+            .expect("the real traffic controller has stopped!")
+    }
+
+    async fn choose_gateway(topology_accessor: &TopologyAccessor) -> gateway::Node {
+        let topology_permit = topology_accessor.get_read_permit().await;
+        let topology = topology_permit
+            .as_ref()
+            .as_ref()
+            .expect("did not obtain topology before");
+
+        // choose the first one available
+        assert!(!topology.gateways().is_empty());
+        topology.gateways().first().unwrap().clone()
+    }
+
+    // future responsible for periodically polling the validator api and updating
+    // the current global view of topology
+    async fn start_topology_refresher(&self, topology_accessor: TopologyAccessor) {
+        let topology_refresher_config = TopologyRefresherConfig::new(
+            vec![self.validator_server.clone()],
+            DEFAULT_TOPOLOGY_REFRESH_RATE,
+            env!("CARGO_PKG_VERSION").to_string(),
+            DEFAULT_NUM_MIX_HOPS,
+        );
+        let mut topology_refresher = TopologyRefresher::new(
+            topology_refresher_config,
+            topology_accessor,
+            self.metrics.clone(),
+        );
+        console_log!("Obtaining initial network topology");
+        topology_refresher.refresh().await;
+
+        if !topology_refresher.is_topology_routable().await {
+            panic!(
+                "The current network topology seem to be insufficient to route any packets through\
+                - check if enough nodes and a gateway are online"
+            );
+        }
+
+        topology_refresher.start();
+    }
+
+    async fn start_gateway_client(
+        &self,
+        gateway: &gateway::Node,
+        mixnet_messages_sender: MixnetMessageSender,
+        ack_sender: AcknowledgementSender,
+    ) -> (GatewayClient, Arc<SharedKeys>) {
+        let bandwidth_controller = None;
+
         let mut gateway_client = GatewayClient::new(
             gateway.clients_address(),
-            Arc::clone(&client.identity),
+            Arc::clone(&self.identity),
             gateway.identity_key,
             gateway.owner.clone(),
             None,
@@ -132,144 +351,361 @@ impl NymClient {
             bandwidth_controller,
         );
 
-        if disabled_credentials_mode {
+        if self.disabled_credentials_mode {
             gateway_client.set_disabled_credentials_mode(true)
         }
 
-        gateway_client
+        let shared_keys = gateway_client
             .authenticate_and_start()
             .await
             .expect("could not authenticate and start up the gateway connection");
 
-        client.gateway_client = Some(gateway_client);
-        match client.on_gateway_connect.as_ref() {
-            Some(callback) => {
-                callback
-                    .call0(&JsValue::null())
-                    .expect("on connect callback failed!");
-            }
-            None => console_log!("Gateway connection established - no callback specified"),
-        };
+        (gateway_client, shared_keys)
+    }
 
-        let rng = rand::rngs::OsRng;
-        let message_preparer = MessagePreparer::new(
-            rng,
-            client.self_recipient(),
-            DEFAULT_AVERAGE_PACKET_DELAY,
+    // buffer controlling all messages fetched from the gateway
+    fn start_received_messages_buffer_controller(
+        &self,
+        query_receiver: ReceivedBufferRequestReceiver,
+        mixnet_receiver: MixnetMessageReceiver,
+        reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
+        input_sender: InputMessageSender,
+    ) {
+        ReceivedMessagesBufferController::new(
+            Arc::clone(&self.encryption_keys),
+            query_receiver,
+            mixnet_receiver,
+            reply_key_storage,
+            received_reply_surbs,
+            input_sender,
+        )
+        .start()
+    }
+
+    // controller for preparing real messages, including their retransmission on missing acks
+    fn start_real_traffic_controller(
+        &self,
+        topology_accessor: TopologyAccessor,
+        reply_key_storage: ReplyKeyStorage,
+        received_reply_surbs: ReceivedReplySurbsMap,
+        ack_receiver: AcknowledgementReceiver,
+        input_receiver: InputMessageReceiver,
+        mix_sender: BatchMixMessageSender,
+        self_address: SelfAddressReceiver,
+    ) {
+        let controller_config = real_messages_control::Config::new(
+            Arc::clone(&self.ack_key),
+            DEFAULT_ACK_WAIT_MULTIPLIER,
+            DEFAULT_ACK_WAIT_ADDITION,
+            self.maximum_retransmissions,
             DEFAULT_AVERAGE_ACK_DELAY,
+            DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY,
+            DEFAULT_AVERAGE_PACKET_DELAY,
+            DEFAULT_NUM_MIX_HOPS,
+            DEFAULT_MINIMUM_REPLY_SURB_STORAGE_THRESHOLD,
+            DEFAULT_MAXIMUM_REPLY_SURB_REQUEST_SIZE,
+            self_address,
+            self.metrics.clone(),
         );
 
-        let received_processor = ReceivedMessagesProcessor::new(
-            Arc::clone(&client.encryption_keys),
-            Arc::clone(&client.ack_key),
-        );
+        RealMessagesController::new(
+            controller_config,
+            ack_receiver,
+            input_receiver,
+            mix_sender,
+            topology_accessor,
+            reply_key_storage,
+            received_reply_surbs,
+        )
+        .start();
+    }
 
-        client.message_preparer = Some(message_preparer);
+    // controller for sending sphinx packets to the gateway (either real traffic or cover traffic)
+    fn start_mix_traffic_controller(
+        &self,
+        gateway: &gateway::Node,
+        shared_keys: Arc<SharedKeys>,
+        mix_rx: BatchMixMessageReceiver,
+        gateway_client: GatewayClient,
+        self_address: SelfAddressSender,
+    ) {
+        let current_gateway =
+            GatewayDetails::new(GatewayEndpoint::from(gateway.clone()), shared_keys)
+                .expect("the gateway has an invalid identity!");
+        // the wasm client does not register with any backup gateways
+        let gateway_failover = GatewayFailover::new(current_gateway, Vec::new(), 0, self_address);
+
+        MixTrafficController::new(
+            mix_rx,
+            gateway_client,
+            gateway_failover,
+            self.metrics.clone(),
+        )
+        .start();
+    }
 
-        spawn_local(received_processor.start_processing(
-            mixnet_messages_receiver,
-            ack_receiver,
-            client.on_message.take().expect("on_message was not set!"),
+    // future constantly pumping loop cover traffic at some specified average rate
+    fn start_cover_traffic_stream(
+        &self,
+        topology_accessor: TopologyAccessor,
+        mix_tx: BatchMixMessageSender,
+        self_address: SelfAddressReceiver,
+    ) {
+        LoopCoverTrafficStream::new(
+            Arc::clone(&self.ack_key),
+            DEFAULT_AVERAGE_ACK_DELAY,
+            DEFAULT_AVERAGE_PACKET_DELAY,
+            DEFAULT_LOOP_COVER_STREAM_AVERAGE_DELAY,
+            DEFAULT_NUM_MIX_HOPS,
+            mix_tx,
+            self_address,
+            topology_accessor,
+        )
+        .start();
+    }
+
+    fn start_received_messages_forwarder(
+        buffer_requester: ReceivedBufferRequestSender,
+        on_message: js_sys::Function,
+    ) {
+        let (reconstructed_sender, reconstructed_receiver) = mpsc::unbounded();
+        buffer_requester
+            .unbounded_send(ReceivedBufferMessage::ReceiverAnnounce(
+                reconstructed_sender,
+            ))
+            .expect("the received messages buffer has stopped!");
+
+        spawn_local(forward_received_messages(
+            reconstructed_receiver,
+            on_message,
         ));
+    }
+}
 
-        client
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use mixnet_contract_common::Layer;
+    use nymsphinx::addressing::nodes::NymNodeRoutingAddress;
+    use nymsphinx::forwarding::packet::MixPacket;
+    use nymsphinx::ProcessedPacket;
+    use std::collections::HashMap;
+    use topology::{mix, NymTopology};
+    use wasm_bindgen_test::*;
+
+    // upper bound on the number of packets, including the loop cover traffic, inspected by a test
+    const MAX_INSPECTED_PACKETS: usize = 200;
+
+    struct TestNetwork {
+        topology: NymTopology,
+        // sphinx keys of the mixnodes of subsequent layers
+        mix_keys: Vec<encryption::KeyPair>,
+        own_gateway: gateway::Node,
+        recipient: Recipient,
     }
 
-    // Right now it's impossible to have async exported functions to take `&mut self` rather than mut self
-    // TODO: try Rc<RefCell<Self>> approach?
-    pub async fn send_message(mut self, message: String, recipient: String) -> Self {
-        console_log!("Sending {} to {}", message, recipient);
+    fn gateway_node(address: &str) -> gateway::Node {
+        gateway::Node {
+            owner: "N/A".to_string(),
+            stake: 0,
+            location: "N/A".to_string(),
+            host: address.parse().unwrap(),
+            mix_host: format!("{}:1789", address).parse().unwrap(),
+            clients_port: 9000,
+            identity_key: *identity::KeyPair::new(&mut OsRng).public_key(),
+            sphinx_key: *encryption::KeyPair::new(&mut OsRng).public_key(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
 
-        let message_bytes = message.into_bytes();
-        let recipient = Recipient::try_from_base58_string(recipient).unwrap();
+    impl TestNetwork {
+        fn new() -> Self {
+            let mut mixes = HashMap::new();
+            let mut mix_keys = Vec::new();
+            for (i, layer) in [Layer::One, Layer::Two, Layer::Three]
+                .into_iter()
+                .enumerate()
+            {
+                let address = format!("10.0.0.{}", i + 1);
+                let sphinx_keys = encryption::KeyPair::new(&mut OsRng);
+                let node = mix::Node {
+                    owner: "N/A".to_string(),
+                    stake: 0,
+                    delegation: 0,
+                    host: address.parse().unwrap(),
+                    mix_host: format!("{}:1789", address).parse().unwrap(),
+                    identity_key: *identity::KeyPair::new(&mut OsRng).public_key(),
+                    sphinx_key: *sphinx_keys.public_key(),
+                    sphinx_key_rotation: None,
+                    layer,
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                };
+                mixes.insert(layer as u8, vec![node]);
+                mix_keys.push(sphinx_keys);
+            }
 
-        let topology = self
-            .topology
-            .as_ref()
-            .expect("did not obtain topology before");
+            let own_gateway = gateway_node("10.0.1.1");
+            let recipient_gateway = gateway_node("10.0.1.2");
+            let recipient = Recipient::new(
+                *identity::KeyPair::new(&mut OsRng).public_key(),
+                *encryption::KeyPair::new(&mut OsRng).public_key(),
+                recipient_gateway.identity_key,
+            );
+
+            TestNetwork {
+                topology: NymTopology::new(mixes, vec![own_gateway.clone(), recipient_gateway]),
+                mix_keys,
+                own_gateway,
+                recipient,
+            }
+        }
 
-        let message_preparer = self.message_preparer.as_mut().unwrap();
+        fn first_mix_address(&self) -> NymNodeRoutingAddress {
+            self.topology.mixes()[&(Layer::One as u8)][0]
+                .mix_host
+                .into()
+        }
 
-        let (split_message, _reply_keys) = message_preparer
-            .prepare_and_split_message(message_bytes, false, topology)
-            .expect("failed to split the message");
+        // unwraps the packet at every mixnode to find out which gateway it is going to end up at
+        fn destination_gateway(&self, packet: MixPacket) -> NymNodeRoutingAddress {
+            let mut next_hop = packet.next_hop();
+            let mut packet = packet.into_sphinx_packet();
+            for keys in &self.mix_keys {
+                match packet.process(&keys.private_key().into()).unwrap() {
+                    ProcessedPacket::ForwardHop(forwarded, address, _) => {
+                        packet = forwarded;
+                        next_hop = NymNodeRoutingAddress::try_from(address).unwrap();
+                    }
+                    ProcessedPacket::FinalHop(..) => {
+                        panic!("the packet did not go through all of the mixnodes")
+                    }
+                }
+            }
+            next_hop
+        }
 
-        let mut mix_packets = Vec::with_capacity(split_message.len());
-        for message_chunk in split_message {
-            // don't bother with acks etc. for time being
-            let prepared_fragment = message_preparer
-                .prepare_chunk_for_sending(message_chunk, topology, &self.ack_key, &recipient)
-                .await
+        fn recipient_gateway_address(&self) -> NymNodeRoutingAddress {
+            let gateway = self
+                .topology
+                .gateways()
+                .iter()
+                .find(|gateway| gateway.identity_key == *self.recipient.gateway())
                 .unwrap();
-
-            console_warn!("packet is going to have round trip time of {:?}, but we're not going to do anything for acks anyway ", prepared_fragment.total_delay);
-            mix_packets.push(prepared_fragment.mix_packet);
+            gateway.mix_host.into()
         }
-        self.gateway_client
-            .as_mut()
-            .unwrap()
-            .batch_send_mix_packets(mix_packets)
-            .await
-            .unwrap();
-        self
     }
 
-    pub(crate) fn choose_gateway(&self) -> &gateway::Node {
-        let topology = self
-            .topology
-            .as_ref()
-            .expect("did not obtain topology before");
+    // channels of the real traffic controller that would normally be connected to the gateway
+    struct GatewayChannels {
+        mix_receiver: BatchMixMessageReceiver,
+        _ack_sender: AcknowledgementSender,
+        _self_address_sender: SelfAddressSender,
+    }
 
-        // choose the first one available
-        assert!(!topology.gateways().is_empty());
-        topology.gateways().first().unwrap()
+    impl GatewayChannels {
+        // waits for a packet that, as opposed to the loop cover traffic, is not sent back to us
+        async fn wait_for_packet_to(
+            &mut self,
+            network: &TestNetwork,
+            gateway: NymNodeRoutingAddress,
+        ) {
+            let mut inspected = 0;
+            while inspected < MAX_INSPECTED_PACKETS {
+                let packets = self
+                    .mix_receiver
+                    .next()
+                    .await
+                    .expect("the real traffic controller has stopped");
+                for packet in packets {
+                    inspected += 1;
+                    assert_eq!(packet.next_hop(), network.first_mix_address());
+                    if network.destination_gateway(packet) == gateway {
+                        return;
+                    }
+                }
+            }
+            panic!("no packet has been sent to {}", gateway)
+        }
     }
 
-    // Right now it's impossible to have async exported functions to take `&mut self` rather than mut self
-    // self: Rc<Self>
-    // or this: Rc<RefCell<Self>>
-    pub async fn get_and_update_topology(mut self) -> Self {
-        let new_topology = self.get_nym_topology().await;
-        self.update_topology(new_topology);
-        self
+    // sets up the client as `initial_setup` would, but without connecting to the validators
+    // or the gateway
+    async fn start_client(network: &TestNetwork) -> (NymClient, GatewayChannels) {
+        let mut client = NymClient::new("http://localhost:8080".to_string());
+        let self_address = Recipient::new(
+            *client.identity.public_key(),
+            *client.encryption_keys.public_key(),
+            network.own_gateway.identity_key,
+        );
+
+        let topology_accessor = TopologyAccessor::new();
+        topology_accessor
+            .update_global_topology(Some(network.topology.clone()))
+            .await;
+
+        let (self_address_sender, self_address_receiver) = self_address_channel(self_address);
+        let (ack_sender, ack_receiver) = mpsc::unbounded();
+        let (mix_sender, mix_receiver) = mpsc::unbounded();
+        let (input_sender, input_receiver) = mpsc::unbounded();
+
+        client.start_real_traffic_controller(
+            topology_accessor,
+            ReplyKeyStorage::new_ephemeral(DEFAULT_MAXIMUM_REPLY_SURB_AGE),
+            ReceivedReplySurbsMap::new(DEFAULT_MAXIMUM_REPLY_SURB_AGE),
+            ack_receiver,
+            input_receiver,
+            mix_sender,
+            self_address_receiver,
+        );
+        client.self_address = Some(self_address);
+        client.input_sender = Some(input_sender);
+
+        let channels = GatewayChannels {
+            mix_receiver,
+            _ack_sender: ack_sender,
+            _self_address_sender: self_address_sender,
+        };
+        (client, channels)
     }
 
-    pub(crate) fn update_topology(&mut self, topology: NymTopology) {
-        self.topology = Some(topology)
+    #[wasm_bindgen_test]
+    async fn send_binary_goes_through_the_real_traffic_controller() {
+        let network = TestNetwork::new();
+        let (client, mut channels) = start_client(&network).await;
+
+        // not valid utf8
+        let _client = client
+            .send_binary(vec![0, 159, 146, 150], network.recipient.to_string())
+            .await;
+
+        channels
+            .wait_for_packet_to(&network, network.recipient_gateway_address())
+            .await;
     }
 
-    // when updated to 0.10.0, to prevent headache later on, this function requires those two imports:
-    // use js_sys::Promise;
-    // use wasm_bindgen_futures::future_to_promise;
-    //
-    // pub fn get_full_topology_json(&self) -> Promise {
-    //     let validator_client_config = validator_client::Config::new(
-    //         vec![self.validator_server.clone()],
-    //         &self.mixnet_contract_address,
-    //     );
-    //     let validator_client = validator_client::Client::new(validator_client_config);
-    //
-    //     future_to_promise(async move {
-    //         let topology = &validator_client.get_active_topology().await.unwrap();
-    //         Ok(JsValue::from_serde(&topology).unwrap())
-    //     })
-    // }
-
-    pub(crate) async fn get_nym_topology(&self) -> NymTopology {
-        let validator_client = validator_client::ApiClient::new(self.validator_server.clone());
-
-        let mixnodes = match validator_client.get_cached_active_mixnodes().await {
-            Err(err) => panic!("{:?}", err),
-            Ok(mixes) => mixes,
-        };
+    #[wasm_bindgen_test]
+    async fn reply_goes_through_the_real_traffic_controller() {
+        let network = TestNetwork::new();
+        let (client, mut channels) = start_client(&network).await;
 
-        let gateways = match validator_client.get_cached_gateways().await {
-            Err(err) => panic!("{}", err),
-            Ok(gateways) => gateways,
-        };
+        // reply SURB the recipient would have attached to its message
+        let reply_surb = ReplySurb::construct(
+            &mut OsRng,
+            &network.recipient,
+            DEFAULT_AVERAGE_PACKET_DELAY,
+            &network.topology,
+            DEFAULT_NUM_MIX_HOPS,
+        )
+        .unwrap();
+
+        let _client = client
+            .reply(reply_surb.to_base58_string(), b"foomp".to_vec())
+            .await;
 
-        let topology = nym_topology_from_bonds(mixnodes, gateways);
-        let version = env!("CARGO_PKG_VERSION");
-        topology.filter_system_version(version)
+        channels
+            .wait_for_packet_to(&network, network.recipient_gateway_address())
+            .await;
     }
 }
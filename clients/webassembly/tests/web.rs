@@ -1,13 +1,72 @@
-//! Test suite for the Web and headless browsers.
+//! Test suite for node.js and headless browsers. Run with `wasm-pack test --node`.
 
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_bindgen_test;
+use client_core::runtime::{self, Instant};
+use futures::StreamExt;
+use nonexhaustive_delayqueue::NonExhaustiveDelayQueue;
+use std::time::Duration;
 use wasm_bindgen_test::*;
 
-wasm_bindgen_test_configure!(run_in_browser);
+#[wasm_bindgen_test]
+async fn delay_queue_returns_entries_in_expiry_order() {
+    let mut queue = NonExhaustiveDelayQueue::new();
+    queue.insert(3, Duration::from_millis(150));
+    queue.insert(1, Duration::from_millis(50));
+    queue.insert(2, Duration::from_millis(100));
+
+    for expected in 1..=3 {
+        let expired = queue.next().await.unwrap();
+        assert_eq!(expired.into_inner(), expected);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn delay_queue_returns_entry_inserted_while_waiting_if_it_expires_sooner() {
+    let mut queue = NonExhaustiveDelayQueue::new();
+    queue.insert("later", Duration::from_millis(200));
+    assert!(futures::poll!(queue.next()).is_pending());
+
+    queue.insert("sooner", Duration::from_millis(50));
+    assert_eq!(queue.next().await.unwrap().into_inner(), "sooner");
+    assert_eq!(queue.next().await.unwrap().into_inner(), "later");
+}
+
+#[wasm_bindgen_test]
+async fn delay_queue_does_not_return_removed_entries() {
+    let mut queue = NonExhaustiveDelayQueue::new();
+    let removed_key = queue.insert("removed", Duration::from_millis(50));
+    queue.insert("kept", Duration::from_millis(100));
+
+    let removed = queue.remove(&removed_key);
+    assert_eq!(removed.key(), removed_key);
+    assert_eq!(removed.into_inner(), "removed");
+
+    assert_eq!(queue.next().await.unwrap().into_inner(), "kept");
+    // the queue is now empty, but it should never get exhausted
+    assert!(futures::poll!(queue.next()).is_pending());
+}
 
 #[wasm_bindgen_test]
-fn pass() {
-    assert_eq!(1 + 1, 2);
+async fn sleep_completes_after_its_deadline() {
+    let sleep = runtime::sleep(Duration::from_millis(50));
+    let deadline = sleep.deadline();
+    assert!(deadline > Instant::now());
+
+    sleep.await;
+    assert!(Instant::now() >= deadline);
+}
+
+#[wasm_bindgen_test]
+async fn reset_sleep_completes_at_the_new_deadline() {
+    let mut sleep = Box::pin(runtime::sleep(Duration::from_secs(60)));
+    assert!(futures::poll!(sleep.as_mut()).is_pending());
+
+    let new_deadline = Instant::now() + Duration::from_millis(50);
+    sleep.as_mut().reset(new_deadline);
+    assert_eq!(sleep.deadline(), new_deadline);
+
+    sleep.await;
+    assert!(Instant::now() >= new_deadline);
 }
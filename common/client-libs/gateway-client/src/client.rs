@@ -155,6 +155,37 @@ impl GatewayClient {
         self.gateway_identity
     }
 
+    /// Drops the connection to the current gateway and connects to, and authenticates with,
+    /// the provided one instead using the shared key derived with it during prior registration.
+    /// The channels for received packets and acknowledgements are kept intact.
+    pub async fn switch_gateway(
+        &mut self,
+        gateway_address: String,
+        gateway_identity: identity::PublicKey,
+        gateway_owner: String,
+        shared_key: Arc<SharedKeys>,
+    ) -> Result<(), GatewayClientError> {
+        // the old connection is most likely dead anyway, so we don't really care whether
+        // we managed to close it cleanly
+        if let Err(err) = self.close_connection().await {
+            debug!(
+                "failed to cleanly close the connection to the previous gateway - {}",
+                err
+            );
+        }
+        self.connection = SocketState::NotConnected;
+        self.authenticated = false;
+        self.bandwidth_remaining = 0;
+
+        self.gateway_address = gateway_address;
+        self.gateway_identity = gateway_identity;
+        self.gateway_owner = gateway_owner;
+        self.shared_key = Some(shared_key);
+
+        self.authenticate_and_start().await?;
+        Ok(())
+    }
+
     pub fn remaining_bandwidth(&self) -> i64 {
         self.bandwidth_remaining
     }
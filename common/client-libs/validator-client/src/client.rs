@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{validator_api, ValidatorClientError};
-use mixnet_contract_common::{GatewayBond, IdentityKeyRef, MixNodeBond};
+use mixnet_contract_common::{GatewayBond, IdentityKeyRef, Interval, MixNodeBond};
+use service_provider_directory_common::service::ServiceInfo;
 use url::Url;
 use validator_api_requests::coconut::{
     BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, VerificationKeyResponse,
@@ -18,14 +19,14 @@ use validator_api_requests::models::{MixNodeBondAnnotated, UptimeResponse};
 
 #[cfg(feature = "nymd-client")]
 use crate::nymd::{
-    self, error::NymdError, CosmWasmClient, NymdClient, QueryNymdClient, SigningNymdClient,
+    self, error::NymdError, traits::ServiceProviderDirectoryQueryClient, CosmWasmClient,
+    NymdClient, QueryNymdClient, SigningNymdClient,
 };
 
 #[cfg(feature = "nymd-client")]
 use mixnet_contract_common::{
-    mixnode::DelegationEvent, ContractStateParams, Delegation, IdentityKey, Interval,
-    MixnetContractVersion, MixnodeRewardingStatusResponse, RewardedSetNodeStatus,
-    RewardedSetUpdateDetails,
+    mixnode::DelegationEvent, ContractStateParams, Delegation, IdentityKey, MixnetContractVersion,
+    MixnodeRewardingStatusResponse, RewardedSetNodeStatus, RewardedSetUpdateDetails,
 };
 #[cfg(feature = "nymd-client")]
 use network_defaults::NymNetworkDetails;
@@ -45,6 +46,7 @@ pub struct Config {
     gateway_page_limit: Option<u32>,
     mixnode_delegations_page_limit: Option<u32>,
     rewarded_set_page_limit: Option<u32>,
+    service_page_limit: Option<u32>,
 }
 
 #[cfg(feature = "nymd-client")]
@@ -74,6 +76,7 @@ impl Config {
             gateway_page_limit: None,
             mixnode_delegations_page_limit: None,
             rewarded_set_page_limit: None,
+            service_page_limit: None,
         })
     }
 
@@ -109,6 +112,11 @@ impl Config {
         self.rewarded_set_page_limit = limit;
         self
     }
+
+    pub fn with_service_page_limit(mut self, limit: Option<u32>) -> Config {
+        self.service_page_limit = limit;
+        self
+    }
 }
 
 #[cfg(feature = "nymd-client")]
@@ -121,6 +129,7 @@ pub struct Client<C> {
     gateway_page_limit: Option<u32>,
     mixnode_delegations_page_limit: Option<u32>,
     rewarded_set_page_limit: Option<u32>,
+    service_page_limit: Option<u32>,
 
     // ideally they would have been read-only, but unfortunately rust doesn't have such features
     pub validator_api: validator_api::Client,
@@ -147,6 +156,7 @@ impl Client<SigningNymdClient> {
             gateway_page_limit: config.gateway_page_limit,
             mixnode_delegations_page_limit: config.mixnode_delegations_page_limit,
             rewarded_set_page_limit: config.rewarded_set_page_limit,
+            service_page_limit: config.service_page_limit,
             validator_api: validator_api_client,
             nymd: nymd_client,
         })
@@ -180,6 +190,7 @@ impl Client<QueryNymdClient> {
             gateway_page_limit: config.gateway_page_limit,
             mixnode_delegations_page_limit: config.mixnode_delegations_page_limit,
             rewarded_set_page_limit: config.rewarded_set_page_limit,
+            service_page_limit: config.service_page_limit,
             validator_api: validator_api_client,
             nymd: nymd_client,
         })
@@ -208,6 +219,14 @@ impl<C> Client<C> {
         self.nymd.mixnet_contract_address().clone()
     }
 
+    // the directory contract is optional, so this should be checked before querying it
+    pub fn has_service_provider_directory_contract(&self) -> bool {
+        self.nymd
+            .current_config()
+            .service_provider_directory_contract_address
+            .is_some()
+    }
+
     pub async fn get_cached_mixnodes(&self) -> Result<Vec<MixNodeBond>, ValidatorClientError> {
         Ok(self.validator_api.get_mixnodes().await?)
     }
@@ -246,6 +265,14 @@ impl<C> Client<C> {
         Ok(self.validator_api.get_gateways().await?)
     }
 
+    pub async fn get_cached_current_epoch(&self) -> Result<Option<Interval>, ValidatorClientError> {
+        Ok(self.validator_api.get_current_epoch().await?)
+    }
+
+    pub async fn get_cached_services(&self) -> Result<Vec<ServiceInfo>, ValidatorClientError> {
+        Ok(self.validator_api.get_services().await?)
+    }
+
     pub async fn get_contract_settings(&self) -> Result<ContractStateParams, ValidatorClientError>
     where
         C: CosmWasmClient + Sync,
@@ -529,6 +556,29 @@ impl<C> Client<C> {
         Ok(gateways)
     }
 
+    pub async fn get_all_nymd_services(&self) -> Result<Vec<ServiceInfo>, ValidatorClientError>
+    where
+        C: CosmWasmClient + Sync + Send,
+    {
+        let mut services = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = self
+                .nymd
+                .get_services_paged(start_after.take(), self.service_page_limit)
+                .await?;
+            services.append(&mut paged_response.services);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res)
+            } else {
+                break;
+            }
+        }
+
+        Ok(services)
+    }
+
     pub async fn get_all_nymd_single_mixnode_delegations(
         &self,
         identity: IdentityKey,
@@ -648,6 +698,14 @@ impl ApiClient {
         Ok(self.validator_api.get_gateways().await?)
     }
 
+    pub async fn get_cached_current_epoch(&self) -> Result<Option<Interval>, ValidatorClientError> {
+        Ok(self.validator_api.get_current_epoch().await?)
+    }
+
+    pub async fn get_cached_services(&self) -> Result<Vec<ServiceInfo>, ValidatorClientError> {
+        Ok(self.validator_api.get_services().await?)
+    }
+
     pub async fn get_gateway_core_status_count(
         &self,
         identity: IdentityKeyRef<'_>,
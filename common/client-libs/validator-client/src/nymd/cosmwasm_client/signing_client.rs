@@ -19,7 +19,7 @@ use cosmrs::proto::cosmos::tx::signing::v1beta1::SignMode;
 use cosmrs::rpc::endpoint::broadcast;
 use cosmrs::rpc::{Error as TendermintRpcError, HttpClient, HttpClientUrl, SimpleRequest};
 use cosmrs::staking::{MsgDelegate, MsgUndelegate};
-use cosmrs::tx::{self, Msg, SignDoc, SignerInfo};
+use cosmrs::tx::{self, Msg};
 use cosmrs::{cosmwasm, rpc, AccountId, Any, Tx};
 use log::debug;
 use serde::Serialize;
@@ -675,34 +675,8 @@ pub trait SigningCosmWasmClient: CosmWasmClient {
         memo: impl Into<String> + Send + 'static,
         signer_data: SignerData,
     ) -> Result<tx::Raw, NymdError> {
-        let signer_accounts = self.signer().try_derive_accounts()?;
-        let account_from_signer = signer_accounts
-            .iter()
-            .find(|account| &account.address == signer_address)
-            .ok_or_else(|| NymdError::SigningAccountNotFound(signer_address.clone()))?;
-
-        // TODO: WTF HOW IS TIMEOUT_HEIGHT SUPPOSED TO GET DETERMINED?
-        // IT DOESNT EXIST IN COSMJS!!
-        // try to set to 0
-        let timeout_height = 0u32;
-
-        let tx_body = tx::Body::new(messages, memo, timeout_height);
-        let signer_info =
-            SignerInfo::single_direct(Some(account_from_signer.public_key), signer_data.sequence);
-        let auth_info = signer_info.auth_info(fee);
-
-        // ideally I'd prefer to have the entire error put into the NymdError::SigningFailure
-        // but I'm super hesitant to trying to downcast the eyre::Report to cosmrs::error::Error
-        let sign_doc = SignDoc::new(
-            &tx_body,
-            &auth_info,
-            &signer_data.chain_id,
-            signer_data.account_number,
-        )
-        .map_err(|_| NymdError::SigningFailure)?;
-
         self.signer()
-            .sign_direct_with_account(account_from_signer, sign_doc)
+            .sign_transaction(signer_address, messages, fee, memo, signer_data)
     }
 
     async fn sign(
@@ -8,21 +8,23 @@ use crate::nymd::cosmwasm_client::types::{
 };
 use crate::nymd::error::NymdError;
 use crate::nymd::fee::DEFAULT_SIMULATED_GAS_MULTIPLIER;
+use crate::nymd::offline_signing::SignedTransaction;
 use crate::nymd::wallet::DirectSecp256k1HdWallet;
 use cosmrs::cosmwasm;
 use cosmrs::rpc::Error as TendermintRpcError;
 use cosmrs::rpc::HttpClientUrl;
+use cosmrs::tendermint::chain;
 use cosmrs::tx::Msg;
 use cosmwasm_std::Uint128;
 use execute::execute;
 use mixnet_contract_common::mixnode::DelegationEvent;
 use mixnet_contract_common::{
     ContractStateParams, Delegation, ExecuteMsg, Gateway, GatewayBond, GatewayBondResponse,
-    GatewayOwnershipResponse, IdentityKey, Interval, LayerDistribution, MixNode, MixNodeBond,
-    MixOwnershipResponse, MixnetContractVersion, MixnodeBondResponse,
+    GatewayConfigUpdate, GatewayOwnershipResponse, IdentityKey, Interval, LayerDistribution,
+    MixNode, MixNodeBond, MixOwnershipResponse, MixnetContractVersion, MixnodeBondResponse,
     MixnodeRewardingStatusResponse, PagedDelegatorDelegationsResponse, PagedGatewayResponse,
     PagedMixDelegationsResponse, PagedMixnodeResponse, PagedRewardedSetResponse, QueryMsg,
-    RewardedSetUpdateDetails,
+    RewardedSetUpdateDetails, SphinxKeyRotation,
 };
 use serde::Serialize;
 use std::convert::TryInto;
@@ -58,6 +60,7 @@ pub mod coin;
 pub mod cosmwasm_client;
 pub mod error;
 pub mod fee;
+pub mod offline_signing;
 pub mod traits;
 pub mod wallet;
 
@@ -72,6 +75,8 @@ pub struct Config {
     pub(crate) bandwidth_claim_contract_address: Option<AccountId>,
     pub(crate) coconut_bandwidth_contract_address: Option<AccountId>,
     pub(crate) multisig_contract_address: Option<AccountId>,
+    pub(crate) coconut_dkg_contract_address: Option<AccountId>,
+    pub(crate) service_provider_directory_contract_address: Option<AccountId>,
     // TODO: add this in later commits
     // pub(crate) gas_price: GasPrice,
 }
@@ -125,6 +130,17 @@ impl Config {
                 details.contracts.multisig_contract_address.as_ref(),
                 prefix,
             )?,
+            coconut_dkg_contract_address: Self::parse_optional_account(
+                details.contracts.coconut_dkg_contract_address.as_ref(),
+                prefix,
+            )?,
+            service_provider_directory_contract_address: Self::parse_optional_account(
+                details
+                    .contracts
+                    .service_provider_directory_contract_address
+                    .as_ref(),
+                prefix,
+            )?,
         })
     }
 }
@@ -232,6 +248,14 @@ impl<C> NymdClient<C> {
         self.config.multisig_contract_address = Some(address);
     }
 
+    pub fn set_coconut_dkg_contract_address(&mut self, address: AccountId) {
+        self.config.coconut_dkg_contract_address = Some(address);
+    }
+
+    pub fn set_service_provider_directory_contract_address(&mut self, address: AccountId) {
+        self.config.service_provider_directory_contract_address = Some(address);
+    }
+
     // TODO: this should get changed into Result<&AccountId, NymdError> (or Option<&AccountId> in future commits
     // note: what unwrap is doing here is just moving a failure that would have normally
     // occurred in `connect` when attempting to parse an empty address,
@@ -278,6 +302,25 @@ impl<C> NymdClient<C> {
         self.config.multisig_contract_address.as_ref().unwrap()
     }
 
+    // TODO: this should get changed into Result<&AccountId, NymdError> (or Option<&AccountId> in future commits
+    // note: what unwrap is doing here is just moving a failure that would have normally
+    // occurred in `connect` when attempting to parse an empty address,
+    // so it's not introducing new source of failure (just moves it)
+    pub fn coconut_dkg_contract_address(&self) -> &AccountId {
+        self.config.coconut_dkg_contract_address.as_ref().unwrap()
+    }
+
+    // TODO: this should get changed into Result<&AccountId, NymdError> (or Option<&AccountId> in future commits
+    // note: what unwrap is doing here is just moving a failure that would have normally
+    // occurred in `connect` when attempting to parse an empty address,
+    // so it's not introducing new source of failure (just moves it)
+    pub fn service_provider_directory_contract_address(&self) -> &AccountId {
+        self.config
+            .service_provider_directory_contract_address
+            .as_ref()
+            .unwrap()
+    }
+
     pub fn set_simulated_gas_multiplier(&mut self, multiplier: f32) {
         self.simulated_gas_multiplier = multiplier;
     }
@@ -404,6 +447,35 @@ impl<C> NymdClient<C> {
         self.client.get_tx(id).await
     }
 
+    /// Obtains the account number and the current sequence of the specified account,
+    /// which are required for signing transactions offline.
+    pub async fn get_sequence(&self, address: &AccountId) -> Result<SequenceResponse, NymdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        self.client.get_sequence(address).await
+    }
+
+    pub async fn get_chain_id(&self) -> Result<chain::Id, NymdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        self.client.get_chain_id().await
+    }
+
+    /// Broadcasts a transaction that has been signed offline and monitors its inclusion in a block.
+    pub async fn broadcast_signed_transaction(
+        &self,
+        transaction: SignedTransaction,
+    ) -> Result<TxResponse, NymdError>
+    where
+        C: CosmWasmClient + Sync,
+    {
+        self.client
+            .broadcast_tx(transaction.into_transaction())
+            .await
+    }
+
     pub async fn get_total_supply(&self) -> Result<Vec<Coin>, NymdError>
     where
         C: CosmWasmClient + Sync,
@@ -1296,6 +1368,31 @@ impl<C> NymdClient<C> {
             .await
     }
 
+    /// Announces the sphinx key that is going to replace the current key of the bonded mixnode
+    /// once the specified epoch begins.
+    pub async fn announce_sphinx_key_rotation(
+        &self,
+        rotation: SphinxKeyRotation,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>
+    where
+        C: SigningCosmWasmClient + Sync,
+    {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+
+        let req = ExecuteMsg::AnnounceSphinxKeyRotation { rotation };
+        self.client
+            .execute(
+                self.address(),
+                self.mixnet_contract_address(),
+                &req,
+                fee,
+                "Announcing sphinx key rotation from rust!",
+                vec![],
+            )
+            .await
+    }
+
     /// Delegates specified amount of stake to particular mixnode.
     pub async fn delegate_to_mixnode(
         &self,
@@ -1558,6 +1655,30 @@ impl<C> NymdClient<C> {
             .await
     }
 
+    /// Updates the host, ports, location and version of the bonded gateway without unbonding it.
+    pub async fn update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>
+    where
+        C: SigningCosmWasmClient + Sync,
+    {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+
+        let req = ExecuteMsg::UpdateGatewayConfig { new_config };
+        self.client
+            .execute(
+                self.address(),
+                self.mixnet_contract_address(),
+                &req,
+                fee,
+                "Updating gateway configuration from rust!",
+                vec![],
+            )
+            .await
+    }
+
     /// Unbond a gateway on behalf of the owner, removing it from the
     /// network and reclaiming staked coins
     pub async fn unbond_gateway_on_behalf(
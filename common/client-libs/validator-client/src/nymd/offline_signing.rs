@@ -0,0 +1,390 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing of transactions without access to the chain, for example on an air-gapped machine.
+//!
+//! The process is split into three steps, each of which might be performed on a different machine:
+//! 1. an [`UnsignedTransaction`] is built out of the messages that are meant to be executed,
+//! 2. it is signed using explicitly provided account number, sequence and fee,
+//!    producing a [`SignedTransaction`],
+//! 3. the signed bytes are broadcast to the chain, for example with
+//!    [`NymdClient::broadcast_signed_transaction`](crate::nymd::NymdClient::broadcast_signed_transaction).
+//!
+//! Both transaction types can be (de)serialized so that they could be moved between the machines as files.
+
+use crate::nymd::cosmwasm_client::types::SignerData;
+use crate::nymd::error::NymdError;
+use crate::nymd::wallet::DirectSecp256k1HdWallet;
+use crate::nymd::{Coin, Config};
+use cosmrs::bank::MsgSend;
+use cosmrs::tendermint::abci::Transaction;
+use cosmrs::tx::{self, AccountNumber, Msg, SequenceNumber};
+use cosmrs::{cosmwasm, AccountId, Any};
+use mixnet_contract_common::ExecuteMsg;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use vesting_contract_common::ExecuteMsg as VestingExecuteMsg;
+
+/// Protobuf-encoded message to be included in a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodedMessage {
+    pub type_url: String,
+
+    #[serde(with = "base64_bytes")]
+    pub value: Vec<u8>,
+}
+
+impl From<Any> for EncodedMessage {
+    fn from(message: Any) -> Self {
+        EncodedMessage {
+            type_url: message.type_url,
+            value: message.value,
+        }
+    }
+}
+
+impl From<EncodedMessage> for Any {
+    fn from(message: EncodedMessage) -> Self {
+        Any {
+            type_url: message.type_url,
+            value: message.value,
+        }
+    }
+}
+
+const EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+const SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+/// Content of an [`EncodedMessage`] decoded so that it could be reviewed before getting signed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedMessage {
+    ExecuteContract {
+        sender: AccountId,
+        contract: AccountId,
+        msg: serde_json::Value,
+        funds: Vec<Coin>,
+    },
+    Send {
+        from_address: AccountId,
+        to_address: AccountId,
+        amount: Vec<Coin>,
+    },
+    /// Message of a type this library does not know how to decode.
+    Unknown { type_url: String },
+}
+
+impl EncodedMessage {
+    pub fn decode(&self) -> Result<DecodedMessage, NymdError> {
+        let any = Any::from(self.clone());
+        match self.type_url.as_str() {
+            EXECUTE_CONTRACT_TYPE_URL => {
+                let msg = cosmwasm::MsgExecuteContract::from_any(&any).map_err(|_| {
+                    NymdError::DeserializationError("MsgExecuteContract".to_owned())
+                })?;
+                Ok(DecodedMessage::ExecuteContract {
+                    sender: msg.sender,
+                    contract: msg.contract,
+                    msg: serde_json::from_slice(&msg.msg)?,
+                    funds: msg.funds.into_iter().map(Into::into).collect(),
+                })
+            }
+            SEND_TYPE_URL => {
+                let msg = MsgSend::from_any(&any)
+                    .map_err(|_| NymdError::DeserializationError("MsgSend".to_owned()))?;
+                Ok(DecodedMessage::Send {
+                    from_address: msg.from_address,
+                    to_address: msg.to_address,
+                    amount: msg.amount.into_iter().map(Into::into).collect(),
+                })
+            }
+            _ => Ok(DecodedMessage::Unknown {
+                type_url: self.type_url.clone(),
+            }),
+        }
+    }
+}
+
+/// Messages, alongside the memo, that are going to be included in a transaction once it's signed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    signer_address: String,
+    messages: Vec<EncodedMessage>,
+    memo: String,
+}
+
+impl UnsignedTransaction {
+    pub fn new(signer_address: &AccountId, memo: impl Into<String>) -> Self {
+        UnsignedTransaction {
+            signer_address: signer_address.to_string(),
+            messages: Vec::new(),
+            memo: memo.into(),
+        }
+    }
+
+    pub fn signer_address(&self) -> Result<AccountId, NymdError> {
+        self.signer_address
+            .parse()
+            .map_err(|_| NymdError::MalformedAccountAddress(self.signer_address.clone()))
+    }
+
+    pub fn memo(&self) -> &str {
+        &self.memo
+    }
+
+    pub fn messages(&self) -> Vec<Any> {
+        self.messages.iter().cloned().map(Into::into).collect()
+    }
+
+    /// Decodes all included messages so that their content could be reviewed before signing.
+    pub fn decoded_messages(&self) -> Result<Vec<DecodedMessage>, NymdError> {
+        self.messages.iter().map(EncodedMessage::decode).collect()
+    }
+
+    pub fn add_message(&mut self, message: Any) {
+        self.messages.push(message.into())
+    }
+
+    pub fn add_contract_execution<M>(
+        &mut self,
+        contract_address: &AccountId,
+        msg: &M,
+        funds: Vec<Coin>,
+    ) -> Result<(), NymdError>
+    where
+        M: ?Sized + Serialize,
+    {
+        let execute_msg = cosmwasm::MsgExecuteContract {
+            sender: self.signer_address()?,
+            contract: contract_address.clone(),
+            msg: serde_json::to_vec(msg)?,
+            funds: funds.into_iter().map(Into::into).collect(),
+        }
+        .to_any()
+        .map_err(|_| NymdError::SerializationError("MsgExecuteContract".to_owned()))?;
+
+        self.add_message(execute_msg);
+        Ok(())
+    }
+
+    pub fn add_mixnet_contract_execution(
+        &mut self,
+        config: &Config,
+        msg: &ExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> Result<(), NymdError> {
+        let contract_address = config
+            .mixnet_contract_address
+            .as_ref()
+            .ok_or(NymdError::NoContractAddressAvailable)?;
+        self.add_contract_execution(contract_address, msg, funds)
+    }
+
+    pub fn add_vesting_contract_execution(
+        &mut self,
+        config: &Config,
+        msg: &VestingExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> Result<(), NymdError> {
+        let contract_address = config
+            .vesting_contract_address
+            .as_ref()
+            .ok_or(NymdError::NoContractAddressAvailable)?;
+        self.add_contract_execution(contract_address, msg, funds)
+    }
+
+    /// Signs the transaction without contacting the chain. Note that the provided account number,
+    /// sequence and chain id must match the on-chain values at the time of broadcasting,
+    /// otherwise the transaction is going to get rejected.
+    pub fn sign(
+        &self,
+        wallet: &DirectSecp256k1HdWallet,
+        fee: tx::Fee,
+        signer_data: SignerData,
+    ) -> Result<SignedTransaction, NymdError> {
+        let chain_id = signer_data.chain_id.to_string();
+        let account_number = signer_data.account_number;
+        let sequence = signer_data.sequence;
+
+        let tx_raw = wallet.sign_transaction(
+            &self.signer_address()?,
+            self.messages(),
+            fee,
+            self.memo.clone(),
+            signer_data,
+        )?;
+        let tx_bytes = tx_raw
+            .to_bytes()
+            .map_err(|_| NymdError::SerializationError("Tx".to_owned()))?;
+
+        Ok(SignedTransaction {
+            signer_address: self.signer_address.clone(),
+            chain_id,
+            account_number,
+            sequence,
+            tx_bytes,
+        })
+    }
+}
+
+/// Fully signed transaction ready to be broadcast to the chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub signer_address: String,
+    pub chain_id: String,
+    pub account_number: AccountNumber,
+    pub sequence: SequenceNumber,
+
+    #[serde(with = "base64_bytes")]
+    pub tx_bytes: Vec<u8>,
+}
+
+impl SignedTransaction {
+    /// Hash under which the transaction is going to be included in a block.
+    pub fn hash(&self) -> tx::Hash {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Sha256::digest(&self.tx_bytes));
+        tx::Hash::Sha256(hash)
+    }
+
+    pub fn into_transaction(self) -> Transaction {
+        self.tx_bytes.into()
+    }
+}
+
+mod base64_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(bytes))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::decode(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nymd::Gas;
+    use cosmrs::proto::cosmos::tx::v1beta1::TxRaw;
+    use network_defaults::all::Network::MAINNET;
+    use network_defaults::NymNetworkDetails;
+    use prost::Message;
+
+    fn test_wallet() -> DirectSecp256k1HdWallet {
+        let mnemonic = "crush minute paddle tobacco message debate cabin peace bar jacket execute twenty winner view sure mask popular couch penalty fragile demise fresh pizza stove";
+        DirectSecp256k1HdWallet::from_mnemonic(&MAINNET.bech32_prefix(), mnemonic.parse().unwrap())
+            .unwrap()
+    }
+
+    fn test_signer_data() -> SignerData {
+        SignerData {
+            account_number: 42,
+            sequence: 7,
+            chain_id: "nyx".parse().unwrap(),
+        }
+    }
+
+    fn test_fee() -> tx::Fee {
+        let amount = Coin::new(5000, "unym");
+        tx::Fee::from_amount_and_gas(amount.into(), Gas::from(200000u64))
+    }
+
+    fn test_unsigned_transaction(signer: &AccountId) -> UnsignedTransaction {
+        let config =
+            Config::try_from_nym_network_details(&NymNetworkDetails::new_mainnet()).unwrap();
+        let mut unsigned = UnsignedTransaction::new(signer, "offline signing test");
+        unsigned
+            .add_mixnet_contract_execution(
+                &config,
+                &ExecuteMsg::UnbondMixnode {},
+                vec![Coin::new(100, "unym")],
+            )
+            .unwrap();
+        unsigned
+    }
+
+    #[test]
+    fn unsigned_transaction_survives_serialization() {
+        let wallet = test_wallet();
+        let signer = wallet.try_derive_accounts().unwrap()[0].address.clone();
+        let unsigned = test_unsigned_transaction(&signer);
+
+        let serialized = serde_json::to_string(&unsigned).unwrap();
+        let deserialized: UnsignedTransaction = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(unsigned, deserialized);
+        assert_eq!(deserialized.signer_address().unwrap(), signer);
+    }
+
+    #[test]
+    fn contract_executions_can_be_decoded() {
+        let wallet = test_wallet();
+        let signer = wallet.try_derive_accounts().unwrap()[0].address.clone();
+        let mut unsigned = test_unsigned_transaction(&signer);
+        unsigned.add_message(Any {
+            type_url: "/foo.bar.MsgBaz".to_owned(),
+            value: vec![1, 2, 3],
+        });
+
+        let config =
+            Config::try_from_nym_network_details(&NymNetworkDetails::new_mainnet()).unwrap();
+        let decoded = unsigned.decoded_messages().unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                DecodedMessage::ExecuteContract {
+                    sender: signer,
+                    contract: config.mixnet_contract_address.unwrap(),
+                    msg: serde_json::to_value(&ExecuteMsg::UnbondMixnode {}).unwrap(),
+                    funds: vec![Coin::new(100, "unym")],
+                },
+                DecodedMessage::Unknown {
+                    type_url: "/foo.bar.MsgBaz".to_owned()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn signing_uses_provided_signer_data() {
+        let wallet = test_wallet();
+        let signer = wallet.try_derive_accounts().unwrap()[0].address.clone();
+        let unsigned = test_unsigned_transaction(&signer);
+
+        let signed = unsigned
+            .sign(&wallet, test_fee(), test_signer_data())
+            .unwrap();
+        assert_eq!(signed.chain_id, "nyx");
+        assert_eq!(signed.account_number, 42);
+        assert_eq!(signed.sequence, 7);
+
+        // signing is deterministic, so the same inputs must produce the same transaction
+        let resigned = unsigned
+            .sign(&wallet, test_fee(), test_signer_data())
+            .unwrap();
+        assert_eq!(signed.tx_bytes, resigned.tx_bytes);
+
+        let serialized = serde_json::to_string(&signed).unwrap();
+        let deserialized: SignedTransaction = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(signed, deserialized);
+
+        let raw = TxRaw::decode(deserialized.tx_bytes.as_slice()).unwrap();
+        assert_eq!(raw.signatures.len(), 1);
+    }
+
+    #[test]
+    fn signing_fails_for_unknown_signer() {
+        let wallet = test_wallet();
+        let other_signer: AccountId = "n1h5hgn94nsq4kh99rjj794hr5h5q6yfm2lr52es".parse().unwrap();
+        let unsigned = test_unsigned_transaction(&other_signer);
+
+        assert!(matches!(
+            unsigned.sign(&wallet, test_fee(), test_signer_data()),
+            Err(NymdError::SigningAccountNotFound(_))
+        ))
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nymd::error::NymdError;
+use crate::nymd::{CosmWasmClient, NymdClient};
+
+use coconut_dkg_common::dealer::{
+    DealerDetailsResponse, PagedDealerResponse, PagedDealingsResponse,
+};
+use coconut_dkg_common::msg::QueryMsg;
+use coconut_dkg_common::types::{Epoch, Threshold};
+use coconut_dkg_common::verification_key::PagedVKSharesResponse;
+
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait DkgQueryClient {
+    async fn get_current_dkg_epoch(&self) -> Result<Epoch, NymdError>;
+    async fn get_current_dkg_epoch_threshold(&self) -> Result<Option<Threshold>, NymdError>;
+    async fn get_dealer_details(
+        &self,
+        dealer_address: String,
+    ) -> Result<DealerDetailsResponse, NymdError>;
+    async fn get_current_dealers_paged(
+        &self,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedDealerResponse, NymdError>;
+    async fn get_dealings_paged(
+        &self,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedDealingsResponse, NymdError>;
+    async fn get_vk_shares_paged(
+        &self,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedVKSharesResponse, NymdError>;
+}
+
+#[async_trait]
+impl<C: CosmWasmClient + Sync + Send> DkgQueryClient for NymdClient<C> {
+    async fn get_current_dkg_epoch(&self) -> Result<Epoch, NymdError> {
+        let request = QueryMsg::GetCurrentEpochState {};
+        self.client
+            .query_contract_smart(self.coconut_dkg_contract_address(), &request)
+            .await
+    }
+
+    async fn get_current_dkg_epoch_threshold(&self) -> Result<Option<Threshold>, NymdError> {
+        let request = QueryMsg::GetCurrentThreshold {};
+        self.client
+            .query_contract_smart(self.coconut_dkg_contract_address(), &request)
+            .await
+    }
+
+    async fn get_dealer_details(
+        &self,
+        dealer_address: String,
+    ) -> Result<DealerDetailsResponse, NymdError> {
+        let request = QueryMsg::GetDealerDetails { dealer_address };
+        self.client
+            .query_contract_smart(self.coconut_dkg_contract_address(), &request)
+            .await
+    }
+
+    async fn get_current_dealers_paged(
+        &self,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedDealerResponse, NymdError> {
+        let request = QueryMsg::GetCurrentDealers {
+            start_after,
+            limit: page_limit,
+        };
+        self.client
+            .query_contract_smart(self.coconut_dkg_contract_address(), &request)
+            .await
+    }
+
+    async fn get_dealings_paged(
+        &self,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedDealingsResponse, NymdError> {
+        let request = QueryMsg::GetDealings {
+            start_after,
+            limit: page_limit,
+        };
+        self.client
+            .query_contract_smart(self.coconut_dkg_contract_address(), &request)
+            .await
+    }
+
+    async fn get_vk_shares_paged(
+        &self,
+        start_after: Option<String>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedVKSharesResponse, NymdError> {
+        let request = QueryMsg::GetVerificationKeyShares {
+            start_after,
+            limit: page_limit,
+        };
+        self.client
+            .query_contract_smart(self.coconut_dkg_contract_address(), &request)
+            .await
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nymd::cosmwasm_client::signing_client::SigningCosmWasmClient;
+use crate::nymd::cosmwasm_client::types::ExecuteResult;
+use crate::nymd::error::NymdError;
+use crate::nymd::{Fee, NymdClient};
+use coconut_dkg_common::msg::ExecuteMsg;
+use coconut_dkg_common::types::{EncodedBTEPublicKeyWithProof, EncodedVerificationKeyShare};
+use cosmwasm_std::Binary;
+
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait DkgSigningClient {
+    async fn register_dealer(
+        &self,
+        bte_key_with_proof: EncodedBTEPublicKeyWithProof,
+        announce_address: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    async fn submit_dealings(
+        &self,
+        dealings: Vec<Binary>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    async fn submit_verification_key_share(
+        &self,
+        share: EncodedVerificationKeyShare,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    async fn advance_dkg_epoch_state(&self, fee: Option<Fee>) -> Result<ExecuteResult, NymdError>;
+}
+
+#[async_trait]
+impl<C: SigningCosmWasmClient + Sync + Send> DkgSigningClient for NymdClient<C> {
+    async fn register_dealer(
+        &self,
+        bte_key_with_proof: EncodedBTEPublicKeyWithProof,
+        announce_address: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::RegisterDealer {
+            bte_key_with_proof,
+            announce_address,
+        };
+        self.client
+            .execute(
+                self.address(),
+                self.coconut_dkg_contract_address(),
+                &req,
+                fee,
+                "CoconutDkg::RegisterDealer",
+                vec![],
+            )
+            .await
+    }
+
+    async fn submit_dealings(
+        &self,
+        dealings: Vec<Binary>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::CommitDealing { dealings };
+        self.client
+            .execute(
+                self.address(),
+                self.coconut_dkg_contract_address(),
+                &req,
+                fee,
+                "CoconutDkg::CommitDealing",
+                vec![],
+            )
+            .await
+    }
+
+    async fn submit_verification_key_share(
+        &self,
+        share: EncodedVerificationKeyShare,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::CommitVerificationKeyShare { share };
+        self.client
+            .execute(
+                self.address(),
+                self.coconut_dkg_contract_address(),
+                &req,
+                fee,
+                "CoconutDkg::CommitVerificationKeyShare",
+                vec![],
+            )
+            .await
+    }
+
+    async fn advance_dkg_epoch_state(&self, fee: Option<Fee>) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::AdvanceEpochState {};
+        self.client
+            .execute(
+                self.address(),
+                self.coconut_dkg_contract_address(),
+                &req,
+                fee,
+                "CoconutDkg::AdvanceEpochState",
+                vec![],
+            )
+            .await
+    }
+}
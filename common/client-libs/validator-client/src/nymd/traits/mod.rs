@@ -3,14 +3,22 @@
 
 mod coconut_bandwidth_query_client;
 mod coconut_bandwidth_signing_client;
+mod dkg_query_client;
+mod dkg_signing_client;
 mod multisig_query_client;
 mod multisig_signing_client;
+mod service_provider_directory_query_client;
+mod service_provider_directory_signing_client;
 mod vesting_query_client;
 mod vesting_signing_client;
 
 pub use coconut_bandwidth_query_client::CoconutBandwidthQueryClient;
 pub use coconut_bandwidth_signing_client::CoconutBandwidthSigningClient;
+pub use dkg_query_client::DkgQueryClient;
+pub use dkg_signing_client::DkgSigningClient;
 pub use multisig_query_client::MultisigQueryClient;
 pub use multisig_signing_client::MultisigSigningClient;
+pub use service_provider_directory_query_client::ServiceProviderDirectoryQueryClient;
+pub use service_provider_directory_signing_client::ServiceProviderDirectorySigningClient;
 pub use vesting_query_client::VestingQueryClient;
 pub use vesting_signing_client::VestingSigningClient;
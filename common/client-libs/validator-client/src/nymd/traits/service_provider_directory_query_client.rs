@@ -0,0 +1,60 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nymd::error::NymdError;
+use crate::nymd::{CosmWasmClient, NymdClient};
+
+use service_provider_directory_common::msg::QueryMsg;
+use service_provider_directory_common::service::{
+    OwnedServicesResponse, PagedServicesResponse, ServiceId, ServiceResponse,
+};
+
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait ServiceProviderDirectoryQueryClient {
+    async fn get_service(&self, service_id: ServiceId) -> Result<ServiceResponse, NymdError>;
+    async fn get_services_paged(
+        &self,
+        start_after: Option<ServiceId>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedServicesResponse, NymdError>;
+    async fn get_services_by_owner(
+        &self,
+        owner: String,
+    ) -> Result<OwnedServicesResponse, NymdError>;
+}
+
+#[async_trait]
+impl<C: CosmWasmClient + Sync + Send> ServiceProviderDirectoryQueryClient for NymdClient<C> {
+    async fn get_service(&self, service_id: ServiceId) -> Result<ServiceResponse, NymdError> {
+        let request = QueryMsg::GetService { service_id };
+        self.client
+            .query_contract_smart(self.service_provider_directory_contract_address(), &request)
+            .await
+    }
+
+    async fn get_services_paged(
+        &self,
+        start_after: Option<ServiceId>,
+        page_limit: Option<u32>,
+    ) -> Result<PagedServicesResponse, NymdError> {
+        let request = QueryMsg::GetServices {
+            start_after,
+            limit: page_limit,
+        };
+        self.client
+            .query_contract_smart(self.service_provider_directory_contract_address(), &request)
+            .await
+    }
+
+    async fn get_services_by_owner(
+        &self,
+        owner: String,
+    ) -> Result<OwnedServicesResponse, NymdError> {
+        let request = QueryMsg::GetServicesByOwner { owner };
+        self.client
+            .query_contract_smart(self.service_provider_directory_contract_address(), &request)
+            .await
+    }
+}
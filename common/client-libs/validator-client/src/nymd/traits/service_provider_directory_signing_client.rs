@@ -0,0 +1,123 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nymd::cosmwasm_client::signing_client::SigningCosmWasmClient;
+use crate::nymd::cosmwasm_client::types::ExecuteResult;
+use crate::nymd::error::NymdError;
+use crate::nymd::{Coin, Fee, NymdClient};
+use service_provider_directory_common::msg::ExecuteMsg;
+use service_provider_directory_common::service::{ServiceId, ServiceType};
+
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait ServiceProviderDirectorySigningClient {
+    #[allow(clippy::too_many_arguments)]
+    async fn announce_service(
+        &self,
+        client_address: String,
+        gateway: String,
+        service_type: ServiceType,
+        description: String,
+        owner_signature: String,
+        pledge: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    async fn update_service(
+        &self,
+        service_id: ServiceId,
+        client_address: Option<String>,
+        gateway: Option<String>,
+        description: Option<String>,
+        owner_signature: Option<String>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    async fn delete_service(
+        &self,
+        service_id: ServiceId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+}
+
+#[async_trait]
+impl<C: SigningCosmWasmClient + Sync + Send> ServiceProviderDirectorySigningClient
+    for NymdClient<C>
+{
+    async fn announce_service(
+        &self,
+        client_address: String,
+        gateway: String,
+        service_type: ServiceType,
+        description: String,
+        owner_signature: String,
+        pledge: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::Announce {
+            client_address,
+            gateway,
+            service_type,
+            description,
+            owner_signature,
+        };
+        self.client
+            .execute(
+                self.address(),
+                self.service_provider_directory_contract_address(),
+                &req,
+                fee,
+                "ServiceProviderDirectory::Announce",
+                vec![pledge],
+            )
+            .await
+    }
+
+    async fn update_service(
+        &self,
+        service_id: ServiceId,
+        client_address: Option<String>,
+        gateway: Option<String>,
+        description: Option<String>,
+        owner_signature: Option<String>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::Update {
+            service_id,
+            client_address,
+            gateway,
+            description,
+            owner_signature,
+        };
+        self.client
+            .execute(
+                self.address(),
+                self.service_provider_directory_contract_address(),
+                &req,
+                fee,
+                "ServiceProviderDirectory::Update",
+                vec![],
+            )
+            .await
+    }
+
+    async fn delete_service(
+        &self,
+        service_id: ServiceId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::Delete { service_id };
+        self.client
+            .execute(
+                self.address(),
+                self.service_provider_directory_contract_address(),
+                &req,
+                fee,
+                "ServiceProviderDirectory::Delete",
+                vec![],
+            )
+            .await
+    }
+}
@@ -6,7 +6,9 @@ use crate::nymd::cosmwasm_client::types::ExecuteResult;
 use crate::nymd::error::NymdError;
 use crate::nymd::{Coin, Fee, NymdClient};
 use async_trait::async_trait;
-use mixnet_contract_common::{Gateway, IdentityKey, IdentityKeyRef, MixNode};
+use mixnet_contract_common::{
+    Gateway, GatewayConfigUpdate, IdentityKey, IdentityKeyRef, MixNode, SphinxKeyRotation,
+};
 use vesting_contract_common::messages::{ExecuteMsg as VestingExecuteMsg, VestingSpecification};
 
 #[async_trait]
@@ -17,6 +19,12 @@ pub trait VestingSigningClient {
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NymdError>;
 
+    async fn vesting_announce_sphinx_key_rotation(
+        &self,
+        rotation: SphinxKeyRotation,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+
     async fn update_mixnet_address(
         &self,
         address: &str,
@@ -33,6 +41,12 @@ pub trait VestingSigningClient {
 
     async fn vesting_unbond_gateway(&self, fee: Option<Fee>) -> Result<ExecuteResult, NymdError>;
 
+    async fn vesting_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+
     async fn vesting_track_unbond_gateway(
         &self,
         owner: &str,
@@ -116,6 +130,25 @@ impl<C: SigningCosmWasmClient + Sync + Send> VestingSigningClient for NymdClient
             .await
     }
 
+    async fn vesting_announce_sphinx_key_rotation(
+        &self,
+        rotation: SphinxKeyRotation,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = VestingExecuteMsg::AnnounceSphinxKeyRotation { rotation };
+        self.client
+            .execute(
+                self.address(),
+                self.vesting_contract_address(),
+                &req,
+                fee,
+                "VestingContract::AnnounceSphinxKeyRotation",
+                vec![],
+            )
+            .await
+    }
+
     async fn update_mixnet_address(
         &self,
         address: &str,
@@ -177,6 +210,25 @@ impl<C: SigningCosmWasmClient + Sync + Send> VestingSigningClient for NymdClient
             .await
     }
 
+    async fn vesting_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = VestingExecuteMsg::UpdateGatewayConfig { new_config };
+        self.client
+            .execute(
+                self.address(),
+                self.vesting_contract_address(),
+                &req,
+                fee,
+                "VestingContract::UpdateGatewayConfig",
+                vec![],
+            )
+            .await
+    }
+
     async fn vesting_track_unbond_gateway(
         &self,
         owner: &str,
@@ -1,13 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::nymd::cosmwasm_client::types::SignerData;
 use crate::nymd::error::NymdError;
 use config::defaults;
 use cosmrs::bip32::{DerivationPath, XPrv};
 use cosmrs::crypto::secp256k1::SigningKey;
 use cosmrs::crypto::PublicKey;
-use cosmrs::tx::SignDoc;
-use cosmrs::{tx, AccountId};
+use cosmrs::tx::{SignDoc, SignerInfo};
+use cosmrs::{tx, AccountId, Any};
 
 /// Derivation information required to derive a keypair and an address from a mnemonic.
 #[derive(Debug, Clone)]
@@ -131,6 +132,44 @@ impl DirectSecp256k1HdWallet {
 
         self.sign_direct_with_account(account, sign_doc)
     }
+
+    /// Creates and signs a transaction containing the provided messages using explicitly specified
+    /// signer data, so that it does not have to be retrieved from the chain.
+    pub fn sign_transaction(
+        &self,
+        signer_address: &AccountId,
+        messages: Vec<Any>,
+        fee: tx::Fee,
+        memo: impl Into<String>,
+        signer_data: SignerData,
+    ) -> Result<tx::Raw, NymdError> {
+        let accounts = self.try_derive_accounts()?;
+        let account = accounts
+            .iter()
+            .find(|account| &account.address == signer_address)
+            .ok_or_else(|| NymdError::SigningAccountNotFound(signer_address.clone()))?;
+
+        // TODO: WTF HOW IS TIMEOUT_HEIGHT SUPPOSED TO GET DETERMINED?
+        // IT DOESNT EXIST IN COSMJS!!
+        // try to set to 0
+        let timeout_height = 0u32;
+
+        let tx_body = tx::Body::new(messages, memo, timeout_height);
+        let signer_info = SignerInfo::single_direct(Some(account.public_key), signer_data.sequence);
+        let auth_info = signer_info.auth_info(fee);
+
+        // ideally I'd prefer to have the entire error put into the NymdError::SigningFailure
+        // but I'm super hesitant to trying to downcast the eyre::Report to cosmrs::error::Error
+        let sign_doc = SignDoc::new(
+            &tx_body,
+            &auth_info,
+            &signer_data.chain_id,
+            signer_data.account_number,
+        )
+        .map_err(|_| NymdError::SigningFailure)?;
+
+        self.sign_direct_with_account(account, sign_doc)
+    }
 }
 
 #[must_use]
@@ -3,17 +3,19 @@
 
 use crate::validator_api::error::ValidatorAPIError;
 use crate::validator_api::routes::{CORE_STATUS_COUNT, SINCE_ARG};
-use mixnet_contract_common::{GatewayBond, IdentityKeyRef, MixNodeBond};
+use mixnet_contract_common::{GatewayBond, IdentityKeyRef, Interval, MixNodeBond};
 use serde::{Deserialize, Serialize};
+use service_provider_directory_common::service::ServiceInfo;
 use std::collections::HashMap;
 use url::Url;
 use validator_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, VerificationKeyResponse,
-    VerifyCredentialBody, VerifyCredentialResponse,
+    BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, DkgStatusResponse,
+    VerificationKeyResponse, VerifyCredentialBody, VerifyCredentialResponse,
 };
 use validator_api_requests::models::{
     CoreNodeStatusResponse, InclusionProbabilityResponse, MixNodeBondAnnotated,
-    MixnodeStatusResponse, RewardEstimationResponse, StakeSaturationResponse, UptimeResponse,
+    MixnodeStatusResponse, RewardEstimationResponse, StakeSaturationResponse,
+    UptimeHistoryResponse, UptimeResponse,
 };
 
 pub mod error;
@@ -103,6 +105,19 @@ impl Client {
             .await
     }
 
+    pub async fn get_services(&self) -> Result<Vec<ServiceInfo>, ValidatorAPIError> {
+        self.query_validator_api(&[routes::API_VERSION, routes::SERVICES], NO_PARAMS)
+            .await
+    }
+
+    pub async fn get_current_epoch(&self) -> Result<Option<Interval>, ValidatorAPIError> {
+        self.query_validator_api(
+            &[routes::API_VERSION, routes::EPOCH, routes::CURRENT],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_active_mixnodes(&self) -> Result<Vec<MixNodeBond>, ValidatorAPIError> {
         self.query_validator_api(
             &[routes::API_VERSION, routes::MIXNODES, routes::ACTIVE],
@@ -197,6 +212,23 @@ impl Client {
         }
     }
 
+    pub async fn get_gateway_uptime_history(
+        &self,
+        identity: IdentityKeyRef<'_>,
+    ) -> Result<UptimeHistoryResponse, ValidatorAPIError> {
+        self.query_validator_api(
+            &[
+                routes::API_VERSION,
+                routes::STATUS_ROUTES,
+                routes::GATEWAY,
+                identity,
+                routes::HISTORY,
+            ],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_mixnode_core_status_count(
         &self,
         identity: IdentityKeyRef<'_>,
@@ -375,6 +407,19 @@ impl Client {
         .await
     }
 
+    pub async fn get_coconut_dkg_status(&self) -> Result<DkgStatusResponse, ValidatorAPIError> {
+        self.query_validator_api(
+            &[
+                routes::API_VERSION,
+                routes::COCONUT_ROUTES,
+                routes::BANDWIDTH,
+                routes::COCONUT_DKG_STATUS,
+            ],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_cosmos_address(&self) -> Result<CosmosAddressResponse, ValidatorAPIError> {
         self.query_validator_api(
             &[
@@ -0,0 +1,81 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::{EncodedBTEPublicKeyWithProof, NodeIndex};
+use cosmwasm_std::{Addr, Binary};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DealerDetails {
+    pub address: Addr,
+    pub bte_public_key_with_proof: EncodedBTEPublicKeyWithProof,
+    pub announce_address: String,
+    pub assigned_index: NodeIndex,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DealerDetailsResponse {
+    pub details: Option<DealerDetails>,
+}
+
+impl DealerDetailsResponse {
+    pub fn new(details: Option<DealerDetails>) -> Self {
+        DealerDetailsResponse { details }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PagedDealerResponse {
+    pub dealers: Vec<DealerDetails>,
+    pub per_page: usize,
+    pub start_next_after: Option<Addr>,
+}
+
+impl PagedDealerResponse {
+    pub fn new(
+        dealers: Vec<DealerDetails>,
+        per_page: usize,
+        start_next_after: Option<Addr>,
+    ) -> Self {
+        PagedDealerResponse {
+            dealers,
+            per_page,
+            start_next_after,
+        }
+    }
+}
+
+/// Dealings committed by a single dealer, one for each scalar of the coconut secret key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ContractDealing {
+    pub dealer: Addr,
+    pub dealings: Vec<Binary>,
+}
+
+impl ContractDealing {
+    pub fn new(dealer: Addr, dealings: Vec<Binary>) -> Self {
+        ContractDealing { dealer, dealings }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PagedDealingsResponse {
+    pub dealings: Vec<ContractDealing>,
+    pub per_page: usize,
+    pub start_next_after: Option<Addr>,
+}
+
+impl PagedDealingsResponse {
+    pub fn new(
+        dealings: Vec<ContractDealing>,
+        per_page: usize,
+        start_next_after: Option<Addr>,
+    ) -> Self {
+        PagedDealingsResponse {
+            dealings,
+            per_page,
+            start_next_after,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod dealer;
+pub mod msg;
+pub mod types;
+pub mod verification_key;
@@ -0,0 +1,56 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::{EncodedBTEPublicKeyWithProof, EncodedVerificationKeyShare, TimeConfiguration};
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address of the cw4 group contract whose members are allowed to become dealers.
+    pub group_addr: String,
+    pub time_configuration: Option<TimeConfiguration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    RegisterDealer {
+        bte_key_with_proof: EncodedBTEPublicKeyWithProof,
+        announce_address: String,
+    },
+    CommitDealing {
+        dealings: Vec<Binary>,
+    },
+    CommitVerificationKeyShare {
+        share: EncodedVerificationKeyShare,
+    },
+    AdvanceEpochState {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetCurrentEpochState {},
+    GetCurrentThreshold {},
+    GetDealerDetails {
+        dealer_address: String,
+    },
+    GetCurrentDealers {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    GetDealings {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    GetVerificationKeyShares {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {}
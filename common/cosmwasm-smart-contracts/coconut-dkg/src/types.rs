@@ -0,0 +1,126 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::Timestamp;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+pub type NodeIndex = u64;
+pub type Threshold = u64;
+
+/// base58-encoded BTE public key alongside its proof of discrete log.
+pub type EncodedBTEPublicKeyWithProof = String;
+
+/// base58-encoded coconut verification key derived by one of the dealers.
+pub type EncodedVerificationKeyShare = String;
+
+// 10 minutes
+pub const DEFAULT_PUBLIC_KEY_SUBMISSION_TIME_SECS: u64 = 10 * 60;
+// 20 minutes
+pub const DEFAULT_DEALING_EXCHANGE_TIME_SECS: u64 = 20 * 60;
+// 10 minutes
+pub const DEFAULT_VERIFICATION_KEY_SUBMISSION_TIME_SECS: u64 = 10 * 60;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TimeConfiguration {
+    pub public_key_submission_time_secs: u64,
+    pub dealing_exchange_time_secs: u64,
+    pub verification_key_submission_time_secs: u64,
+}
+
+impl Default for TimeConfiguration {
+    fn default() -> Self {
+        TimeConfiguration {
+            public_key_submission_time_secs: DEFAULT_PUBLIC_KEY_SUBMISSION_TIME_SECS,
+            dealing_exchange_time_secs: DEFAULT_DEALING_EXCHANGE_TIME_SECS,
+            verification_key_submission_time_secs: DEFAULT_VERIFICATION_KEY_SUBMISSION_TIME_SECS,
+        }
+    }
+}
+
+impl TimeConfiguration {
+    /// Returns the duration of the specified state, if it's meant to ever finish.
+    pub fn state_duration_secs(&self, state: EpochState) -> Option<u64> {
+        match state {
+            EpochState::PublicKeySubmission => Some(self.public_key_submission_time_secs),
+            EpochState::DealingExchange => Some(self.dealing_exchange_time_secs),
+            EpochState::VerificationKeySubmission => {
+                Some(self.verification_key_submission_time_secs)
+            }
+            EpochState::Finished => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochState {
+    PublicKeySubmission,
+    DealingExchange,
+    VerificationKeySubmission,
+    Finished,
+}
+
+impl Default for EpochState {
+    fn default() -> Self {
+        EpochState::PublicKeySubmission
+    }
+}
+
+impl EpochState {
+    pub fn next(self) -> Option<Self> {
+        match self {
+            EpochState::PublicKeySubmission => Some(EpochState::DealingExchange),
+            EpochState::DealingExchange => Some(EpochState::VerificationKeySubmission),
+            EpochState::VerificationKeySubmission => Some(EpochState::Finished),
+            EpochState::Finished => None,
+        }
+    }
+}
+
+impl Display for EpochState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EpochState::PublicKeySubmission => write!(f, "public key submission"),
+            EpochState::DealingExchange => write!(f, "dealing exchange"),
+            EpochState::VerificationKeySubmission => write!(f, "verification key submission"),
+            EpochState::Finished => write!(f, "finished"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Epoch {
+    pub state: EpochState,
+
+    /// Timestamp after which the epoch is allowed to advance into its next state.
+    /// It's `None` once the ceremony has finished.
+    pub finish_timestamp: Option<Timestamp>,
+}
+
+impl Epoch {
+    pub fn new(
+        state: EpochState,
+        current_timestamp: Timestamp,
+        time_configuration: TimeConfiguration,
+    ) -> Self {
+        Epoch {
+            state,
+            finish_timestamp: time_configuration
+                .state_duration_secs(state)
+                .map(|duration| current_timestamp.plus_seconds(duration)),
+        }
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.state == EpochState::Finished
+    }
+
+    pub fn can_advance(&self, current_timestamp: Timestamp) -> bool {
+        match self.finish_timestamp {
+            Some(finish_timestamp) => finish_timestamp <= current_timestamp,
+            None => false,
+        }
+    }
+}
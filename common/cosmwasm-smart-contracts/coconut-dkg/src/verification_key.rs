@@ -0,0 +1,36 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::{EncodedVerificationKeyShare, NodeIndex};
+use cosmwasm_std::Addr;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ContractVKShare {
+    pub share: EncodedVerificationKeyShare,
+    pub announce_address: String,
+    pub node_index: NodeIndex,
+    pub owner: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PagedVKSharesResponse {
+    pub shares: Vec<ContractVKShare>,
+    pub per_page: usize,
+    pub start_next_after: Option<Addr>,
+}
+
+impl PagedVKSharesResponse {
+    pub fn new(
+        shares: Vec<ContractVKShare>,
+        per_page: usize,
+        start_next_after: Option<Addr>,
+    ) -> Self {
+        PagedVKSharesResponse {
+            shares,
+            per_page,
+            start_next_after,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 use crate::mixnode::NodeRewardResult;
-use crate::{ContractStateParams, IdentityKeyRef, Interval, Layer};
+use crate::{
+    ContractStateParams, GatewayConfigUpdate, IdentityKeyRef, Interval, Layer, SphinxKeyRotation,
+};
 use cosmwasm_std::{Addr, Coin, Event, Uint128};
 
 pub use contracts_common::events::*;
@@ -14,8 +16,10 @@ pub const UNDELEGATION_EVENT_TYPE: &str = "undelegation";
 pub const PENDING_UNDELEGATION_EVENT_TYPE: &str = "pending_undelegation";
 pub const GATEWAY_BONDING_EVENT_TYPE: &str = "gateway_bonding";
 pub const GATEWAY_UNBONDING_EVENT_TYPE: &str = "gateway_unbonding";
+pub const GATEWAY_CONFIG_UPDATE_EVENT_TYPE: &str = "gateway_config_update";
 pub const MIXNODE_BONDING_EVENT_TYPE: &str = "mixnode_bonding";
 pub const MIXNODE_UNBONDING_EVENT_TYPE: &str = "mixnode_unbonding";
+pub const SPHINX_KEY_ROTATION_EVENT_TYPE: &str = "sphinx_key_rotation";
 pub const SETTINGS_UPDATE_EVENT_TYPE: &str = "settings_update";
 pub const OPERATOR_REWARDING_EVENT_TYPE: &str = "mix_rewarding";
 pub const MIX_DELEGATORS_REWARDING_EVENT_TYPE: &str = "mix_delegators_rewarding";
@@ -44,6 +48,18 @@ pub const DELEGATION_HEIGHT_KEY: &str = "delegation_latest_block_height";
 pub const NODE_IDENTITY_KEY: &str = "identity";
 pub const ASSIGNED_LAYER_KEY: &str = "assigned_layer";
 
+// gateway config update
+pub const HOST_KEY: &str = "host";
+pub const MIX_PORT_KEY: &str = "mix_port";
+pub const CLIENTS_PORT_KEY: &str = "clients_port";
+pub const HTTP_API_PORT_KEY: &str = "http_api_port";
+pub const LOCATION_KEY: &str = "location";
+pub const VERSION_KEY: &str = "version";
+
+// sphinx key rotation
+pub const NEXT_SPHINX_KEY_KEY: &str = "next_sphinx_key";
+pub const ACTIVATION_EPOCH_KEY: &str = "activation_epoch";
+
 // settings change
 pub const OLD_MINIMUM_MIXNODE_PLEDGE_KEY: &str = "old_minimum_mixnode_pledge";
 pub const OLD_MINIMUM_GATEWAY_PLEDGE_KEY: &str = "old_minimum_gateway_pledge";
@@ -268,6 +284,48 @@ pub fn new_gateway_unbonding_event(
     event.add_attribute(AMOUNT_KEY, amount.to_string())
 }
 
+pub fn new_gateway_config_update_event(
+    owner: &Addr,
+    proxy: &Option<Addr>,
+    identity: IdentityKeyRef<'_>,
+    update: &GatewayConfigUpdate,
+) -> Event {
+    let mut event = Event::new(GATEWAY_CONFIG_UPDATE_EVENT_TYPE)
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NODE_IDENTITY_KEY, identity);
+
+    if let Some(proxy) = proxy {
+        event = event.add_attribute(PROXY_KEY, proxy)
+    }
+
+    event
+        .add_attribute(HOST_KEY, &update.host)
+        .add_attribute(MIX_PORT_KEY, update.mix_port.to_string())
+        .add_attribute(CLIENTS_PORT_KEY, update.clients_port.to_string())
+        .add_attribute(HTTP_API_PORT_KEY, update.http_api_port.to_string())
+        .add_attribute(LOCATION_KEY, &update.location)
+        .add_attribute(VERSION_KEY, &update.version)
+}
+
+pub fn new_sphinx_key_rotation_event(
+    owner: &Addr,
+    proxy: &Option<Addr>,
+    identity: IdentityKeyRef<'_>,
+    rotation: &SphinxKeyRotation,
+) -> Event {
+    let mut event = Event::new(SPHINX_KEY_ROTATION_EVENT_TYPE)
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NODE_IDENTITY_KEY, identity);
+
+    if let Some(proxy) = proxy {
+        event = event.add_attribute(PROXY_KEY, proxy)
+    }
+
+    event
+        .add_attribute(NEXT_SPHINX_KEY_KEY, &rotation.next_sphinx_key)
+        .add_attribute(ACTIVATION_EPOCH_KEY, rotation.activation_epoch.to_string())
+}
+
 pub fn new_mixnode_bonding_event(
     owner: &Addr,
     proxy: &Option<Addr>,
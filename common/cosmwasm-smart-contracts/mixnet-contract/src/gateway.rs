@@ -8,11 +8,21 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt::Display;
 
+/// Port of the gateway HTTP API assumed for gateways bonded before it was announced on chain.
+pub const DEFAULT_GATEWAY_HTTP_API_PORT: u16 = 8001;
+
+fn default_http_api_port() -> u16 {
+    DEFAULT_GATEWAY_HTTP_API_PORT
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, PartialOrd, Serialize, JsonSchema)]
 pub struct Gateway {
     pub host: String,
     pub mix_port: u16,
     pub clients_port: u16,
+    /// Port of the HTTP API exposing, among others, the description of the gateway
+    #[serde(default = "default_http_api_port")]
+    pub http_api_port: u16,
     pub location: String,
     pub sphinx_key: SphinxKey,
     /// Base58 encoded ed25519 EdDSA public key of the gateway used to derive shared keys with clients
@@ -20,6 +30,18 @@ pub struct Gateway {
     pub version: String,
 }
 
+/// Part of the gateway configuration that can be changed without having to unbond the gateway.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GatewayConfigUpdate {
+    pub host: String,
+    pub mix_port: u16,
+    pub clients_port: u16,
+    #[serde(default = "default_http_api_port")]
+    pub http_api_port: u16,
+    pub location: String,
+    pub version: String,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
 pub struct GatewayBond {
     pub pledge_amount: Coin,
@@ -151,6 +173,7 @@ mod tests {
             host: "1.1.1.1".to_string(),
             mix_port: 123,
             clients_port: 456,
+            http_api_port: 789,
             location: "foomplandia".to_string(),
             sphinx_key: "sphinxkey".to_string(),
             identity_key: "identitykey".to_string(),
@@ -227,4 +250,23 @@ mod tests {
         // same bond and delegation, so it's just ordered by height
         assert!(gate1 < gate2);
     }
+
+    #[test]
+    fn gateway_without_http_api_port_uses_the_default_one() {
+        let legacy = r#"{
+            "host": "1.1.1.1",
+            "mix_port": 1789,
+            "clients_port": 9000,
+            "location": "foomplandia",
+            "sphinx_key": "sphinxkey",
+            "identity_key": "identitykey",
+            "version": "0.11.0"
+        }"#;
+
+        let gateway: Gateway = cosmwasm_std::from_slice(legacy.as_bytes()).unwrap();
+        assert_eq!(gateway.http_api_port, DEFAULT_GATEWAY_HTTP_API_PORT);
+
+        let update: GatewayConfigUpdate = cosmwasm_std::from_slice(legacy.as_bytes()).unwrap();
+        assert_eq!(update.http_api_port, DEFAULT_GATEWAY_HTTP_API_PORT);
+    }
 }
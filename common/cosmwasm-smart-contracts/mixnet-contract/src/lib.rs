@@ -19,12 +19,13 @@ pub use delegation::{
     PagedMixDelegationsResponse,
 };
 pub use gateway::{
-    Gateway, GatewayBond, GatewayBondResponse, GatewayOwnershipResponse, PagedGatewayResponse,
+    Gateway, GatewayBond, GatewayBondResponse, GatewayConfigUpdate, GatewayOwnershipResponse,
+    PagedGatewayResponse,
 };
 pub use interval::Interval;
 pub use mixnode::{
     Layer, MixNode, MixNodeBond, MixOwnershipResponse, MixnodeBondResponse, PagedMixnodeResponse,
-    RewardedSetNodeStatus,
+    RewardedSetNodeStatus, SphinxKeyRotation, SPHINX_KEY_ROTATION_OVERLAP_EPOCHS,
 };
 pub use msg::*;
 pub use types::*;
@@ -129,6 +129,33 @@ pub struct MixNode {
     pub profit_margin_percent: u8,
 }
 
+/// Number of epochs following the activation of a new sphinx key during which the mixnode
+/// keeps on accepting packets encrypted to its previous key.
+/// With epochs lasting roughly an hour, it covers the 24h for which clients keep their reply SURBs,
+/// so that SURBs created just before the rotation would remain usable.
+pub const SPHINX_KEY_ROTATION_OVERLAP_EPOCHS: u32 = 25;
+
+/// Sphinx key announced by the mixnode operator that is going to replace the current one.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct SphinxKeyRotation {
+    /// Base58 encoded x25519 public key that becomes the sphinx key of the node
+    /// once `activation_epoch` begins.
+    pub next_sphinx_key: SphinxKey,
+    pub activation_epoch: u32,
+}
+
+impl SphinxKeyRotation {
+    pub fn is_active(&self, epoch_id: u32) -> bool {
+        epoch_id >= self.activation_epoch
+    }
+
+    /// First epoch during which the previous sphinx key is no longer accepted by the node.
+    pub fn overlap_end_epoch(&self) -> u32 {
+        self.activation_epoch
+            .saturating_add(SPHINX_KEY_ROTATION_OVERLAP_EPOCHS)
+    }
+}
+
 #[derive(
     Copy,
     Clone,
@@ -338,6 +365,8 @@ pub struct MixNodeBond {
     pub mix_node: MixNode,
     pub proxy: Option<Addr>,
     pub accumulated_rewards: Option<Uint128>,
+    #[serde(default)]
+    pub sphinx_key_rotation: Option<SphinxKeyRotation>,
 }
 
 impl MixNodeBond {
@@ -358,6 +387,7 @@ impl MixNodeBond {
             mix_node,
             proxy,
             accumulated_rewards: None,
+            sphinx_key_rotation: None,
         }
     }
 
@@ -385,6 +415,15 @@ impl MixNodeBond {
         &self.mix_node
     }
 
+    /// Returns the sphinx key that should be used for packets sent through this node
+    /// during the specified epoch.
+    pub fn sphinx_key_at_epoch(&self, epoch_id: u32) -> &SphinxKey {
+        match &self.sphinx_key_rotation {
+            Some(rotation) if rotation.is_active(epoch_id) => &rotation.next_sphinx_key,
+            _ => &self.mix_node.sphinx_key,
+        }
+    }
+
     // Takes into account accumulated rewards as well as current pledge and delegation amounts
     pub fn total_bond(&self) -> Option<u128> {
         if self.pledge_amount.denom != self.total_delegation.denom {
@@ -693,6 +732,7 @@ mod tests {
             mix_node: mixnode_fixture(),
             proxy: None,
             accumulated_rewards: Some(Uint128::zero()),
+            sphinx_key_rotation: None,
         };
 
         let mix2 = MixNodeBond {
@@ -704,6 +744,7 @@ mod tests {
             mix_node: mixnode_fixture(),
             proxy: None,
             accumulated_rewards: Some(Uint128::zero()),
+            sphinx_key_rotation: None,
         };
 
         let mix3 = MixNodeBond {
@@ -715,6 +756,7 @@ mod tests {
             mix_node: mixnode_fixture(),
             proxy: None,
             accumulated_rewards: Some(Uint128::zero()),
+            sphinx_key_rotation: None,
         };
 
         let mix4 = MixNodeBond {
@@ -726,6 +768,7 @@ mod tests {
             mix_node: mixnode_fixture(),
             proxy: None,
             accumulated_rewards: Some(Uint128::zero()),
+            sphinx_key_rotation: None,
         };
 
         let mix5 = MixNodeBond {
@@ -737,6 +780,7 @@ mod tests {
             mix_node: mixnode_fixture(),
             proxy: None,
             accumulated_rewards: Some(Uint128::zero()),
+            sphinx_key_rotation: None,
         };
 
         // summary:
@@ -762,4 +806,25 @@ mod tests {
         // same bond and delegation, so it's just ordered by height
         assert!(mix1 < mix2);
     }
+
+    #[test]
+    fn sphinx_key_is_chosen_based_on_rotation_activation() {
+        let mut bond = MixNodeBond::new(
+            Coin::new(150, "foo"),
+            Addr::unchecked("foo"),
+            Layer::One,
+            100,
+            mixnode_fixture(),
+            None,
+        );
+        assert_eq!(bond.sphinx_key_at_epoch(42), "sphinxkey");
+
+        bond.sphinx_key_rotation = Some(SphinxKeyRotation {
+            next_sphinx_key: "nextsphinxkey".to_string(),
+            activation_epoch: 10,
+        });
+        assert_eq!(bond.sphinx_key_at_epoch(9), "sphinxkey");
+        assert_eq!(bond.sphinx_key_at_epoch(10), "nextsphinxkey");
+        assert_eq!(bond.sphinx_key_at_epoch(11), "nextsphinxkey");
+    }
 }
@@ -3,7 +3,7 @@
 
 use crate::reward_params::NodeRewardParams;
 use crate::ContractStateParams;
-use crate::{Gateway, IdentityKey, MixNode};
+use crate::{Gateway, GatewayConfigUpdate, IdentityKey, MixNode, SphinxKeyRotation};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -51,11 +51,25 @@ pub enum ExecuteMsg {
         profit_margin_percent: u8,
         owner: String,
     },
+    AnnounceSphinxKeyRotation {
+        rotation: SphinxKeyRotation,
+    },
+    AnnounceSphinxKeyRotationOnBehalf {
+        rotation: SphinxKeyRotation,
+        owner: String,
+    },
     BondGateway {
         gateway: Gateway,
         owner_signature: String,
     },
     UnbondGateway {},
+    UpdateGatewayConfig {
+        new_config: GatewayConfigUpdate,
+    },
+    UpdateGatewayConfigOnBehalf {
+        new_config: GatewayConfigUpdate,
+        owner: String,
+    },
     UpdateContractStateParams(ContractStateParams),
 
     DelegateToMixnode {
@@ -0,0 +1,62 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::service::{ServiceId, ServiceType};
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Denomination of the pledges attached to announced services.
+    pub mix_denom: String,
+    /// Minimum amount that has to be pledged for a service to be announced.
+    pub minimum_pledge: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Announces a new service. The funds sent alongside the message become the pledge
+    /// of the service and are returned to the owner once the service is deleted.
+    /// `owner_signature` is the base58-encoded signature of the sender address made with
+    /// the identity key of the client, proving the sender actually runs it.
+    Announce {
+        client_address: String,
+        gateway: String,
+        service_type: ServiceType,
+        description: String,
+        owner_signature: String,
+    },
+    /// Updates the details of an existing service. Only the provided fields are changed.
+    /// A new `owner_signature` is required whenever the client address changes.
+    Update {
+        service_id: ServiceId,
+        client_address: Option<String>,
+        gateway: Option<String>,
+        description: Option<String>,
+        owner_signature: Option<String>,
+    },
+    Delete {
+        service_id: ServiceId,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetService {
+        service_id: ServiceId,
+    },
+    GetServices {
+        limit: Option<u32>,
+        start_after: Option<ServiceId>,
+    },
+    GetServicesByOwner {
+        owner: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {}
@@ -0,0 +1,98 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::{Addr, Coin};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+pub type ServiceId = u32;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceType {
+    NetworkRequester,
+}
+
+impl Display for ServiceType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceType::NetworkRequester => write!(f, "network requester"),
+        }
+    }
+}
+
+/// Service announced in the directory by its operator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Service {
+    /// Nym address of the client providing the service.
+    pub client_address: String,
+    /// Identity key of the gateway the client is connected to.
+    pub gateway: String,
+    pub service_type: ServiceType,
+    pub description: String,
+    pub owner: Addr,
+    /// Funds locked by the owner when announcing the service, if any.
+    pub pledge: Option<Coin>,
+    /// Height of the block at which the service was announced.
+    pub block_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ServiceInfo {
+    pub service_id: ServiceId,
+    pub service: Service,
+}
+
+impl ServiceInfo {
+    pub fn new(service_id: ServiceId, service: Service) -> Self {
+        ServiceInfo {
+            service_id,
+            service,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ServiceResponse {
+    pub service: Option<ServiceInfo>,
+}
+
+impl ServiceResponse {
+    pub fn new(service: Option<ServiceInfo>) -> Self {
+        ServiceResponse { service }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PagedServicesResponse {
+    pub services: Vec<ServiceInfo>,
+    pub per_page: usize,
+    pub start_next_after: Option<ServiceId>,
+}
+
+impl PagedServicesResponse {
+    pub fn new(
+        services: Vec<ServiceInfo>,
+        per_page: usize,
+        start_next_after: Option<ServiceId>,
+    ) -> Self {
+        PagedServicesResponse {
+            services,
+            per_page,
+            start_next_after,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OwnedServicesResponse {
+    pub owner: Addr,
+    pub services: Vec<ServiceInfo>,
+}
+
+impl OwnedServicesResponse {
+    pub fn new(owner: Addr, services: Vec<ServiceInfo>) -> Self {
+        OwnedServicesResponse { owner, services }
+    }
+}
@@ -1,5 +1,7 @@
 use cosmwasm_std::{Coin, Timestamp, Uint128};
-use mixnet_contract_common::{Gateway, IdentityKey, MixNode};
+use mixnet_contract_common::{
+    Gateway, GatewayConfigUpdate, IdentityKey, MixNode, SphinxKeyRotation,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -65,6 +67,12 @@ pub enum ExecuteMsg {
     UpdateMixnodeConfig {
         profit_margin_percent: u8,
     },
+    UpdateGatewayConfig {
+        new_config: GatewayConfigUpdate,
+    },
+    AnnounceSphinxKeyRotation {
+        rotation: SphinxKeyRotation,
+    },
     UpdateMixnetAddress {
         address: String,
     },
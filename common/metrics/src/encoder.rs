@@ -0,0 +1,147 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Write};
+
+/// Content type of the Prometheus text exposition format produced by the [`MetricsEncoder`].
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Builder of the Prometheus text exposition format.
+/// See <https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format>.
+#[derive(Debug, Default)]
+pub struct MetricsEncoder {
+    output: String,
+}
+
+impl MetricsEncoder {
+    pub fn new() -> Self {
+        MetricsEncoder::default()
+    }
+
+    fn write_header(&mut self, name: &str, help: &str, metric_type: &str) {
+        // writing to a String can't fail
+        let _ = writeln!(self.output, "# HELP {} {}", name, escape_help(help));
+        let _ = writeln!(self.output, "# TYPE {} {}", name, metric_type);
+    }
+
+    fn write_sample<V: Display>(&mut self, name: &str, value: V) {
+        let _ = writeln!(self.output, "{} {}", name, value);
+    }
+
+    fn write_labelled_sample<V: Display>(
+        &mut self,
+        name: &str,
+        label_name: &str,
+        label_value: &str,
+        value: V,
+    ) {
+        let _ = writeln!(
+            self.output,
+            "{}{{{}=\"{}\"}} {}",
+            name,
+            label_name,
+            escape_label_value(label_value),
+            value
+        );
+    }
+
+    pub fn counter(&mut self, name: &str, help: &str, value: u64) -> &mut Self {
+        self.write_header(name, help, "counter");
+        self.write_sample(name, value);
+        self
+    }
+
+    pub fn gauge<V: Display>(&mut self, name: &str, help: &str, value: V) -> &mut Self {
+        self.write_header(name, help, "gauge");
+        self.write_sample(name, value);
+        self
+    }
+
+    /// Writes a family of counters distinguished by the value of a single label,
+    /// for example the number of packets sent to each of the destinations.
+    pub fn labelled_counter<'a, I>(
+        &mut self,
+        name: &str,
+        help: &str,
+        label_name: &str,
+        values: I,
+    ) -> &mut Self
+    where
+        I: IntoIterator<Item = (&'a str, u64)>,
+    {
+        self.write_header(name, help, "counter");
+        for (label_value, value) in values {
+            self.write_labelled_sample(name, label_name, label_value, value);
+        }
+        self
+    }
+
+    pub fn finish(self) -> String {
+        self.output
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_counters_and_gauges() {
+        let mut encoder = MetricsEncoder::new();
+        encoder
+            .counter("packets_received_total", "Packets received", 42)
+            .gauge("active_clients", "Currently connected clients", 3);
+
+        let expected = "# HELP packets_received_total Packets received\n\
+                        # TYPE packets_received_total counter\n\
+                        packets_received_total 42\n\
+                        # HELP active_clients Currently connected clients\n\
+                        # TYPE active_clients gauge\n\
+                        active_clients 3\n";
+        assert_eq!(encoder.finish(), expected);
+    }
+
+    #[test]
+    fn encodes_labelled_counters() {
+        let mut encoder = MetricsEncoder::new();
+        encoder.labelled_counter(
+            "packets_sent_total",
+            "Packets sent",
+            "destination",
+            vec![("1.2.3.4:1789", 5), ("5.6.7.8:1789", 7)],
+        );
+
+        let expected = "# HELP packets_sent_total Packets sent\n\
+                        # TYPE packets_sent_total counter\n\
+                        packets_sent_total{destination=\"1.2.3.4:1789\"} 5\n\
+                        packets_sent_total{destination=\"5.6.7.8:1789\"} 7\n";
+        assert_eq!(encoder.finish(), expected);
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        let mut encoder = MetricsEncoder::new();
+        encoder.labelled_counter(
+            "foo",
+            "help with \\ and\nnewline",
+            "label",
+            vec![("a\"b\\c\nd", 1)],
+        );
+
+        let expected = "# HELP foo help with \\\\ and\\nnewline\n\
+                        # TYPE foo counter\n\
+                        foo{label=\"a\\\"b\\\\c\\nd\"} 1\n";
+        assert_eq!(encoder.finish(), expected);
+    }
+}
@@ -0,0 +1,15 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal building blocks for exposing metrics of nym binaries in the Prometheus text
+//! exposition format.
+
+pub mod encoder;
+pub mod metric;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+
+pub use encoder::{MetricsEncoder, PROMETHEUS_CONTENT_TYPE};
+pub use metric::{Counter, Gauge};
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::MetricsServer;
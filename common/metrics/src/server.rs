@@ -0,0 +1,229 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::encoder::PROMETHEUS_CONTENT_TYPE;
+use log::*;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+// we only ever care about the request line, so there's no point in accepting anything bigger
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+// the whole exchange, i.e. both reading the request and writing the response, has to complete
+// within this time, so that slow clients couldn't hold on to their connections
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+// metrics are meant to be scraped by a handful of monitoring services, so anything above this
+// is dropped straight away
+const MAX_CONCURRENT_CONNECTIONS: usize = 16;
+
+/// Bare-bones HTTP server exposing the metrics of binaries that are not running any HTTP API
+/// of their own. It only ever responds to `GET /metrics`.
+pub struct MetricsServer<F> {
+    address: SocketAddr,
+    render_metrics: Arc<F>,
+    connection_permits: Arc<Semaphore>,
+}
+
+impl<F> MetricsServer<F>
+where
+    F: Fn() -> String + Send + Sync + 'static,
+{
+    /// Creates new instance of the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `address`: address on which the server is going to be listening.
+    /// * `render_metrics`: closure producing current metrics in the Prometheus text format.
+    pub fn new(address: SocketAddr, render_metrics: F) -> Self {
+        MetricsServer {
+            address,
+            render_metrics: Arc::new(render_metrics),
+            connection_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_CONNECTIONS)),
+        }
+    }
+
+    async fn read_request_line(stream: &mut TcpStream) -> Option<String> {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let read = stream.read(&mut chunk).await.ok()?;
+            if read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
+            if buf.windows(4).any(|w| w == b"\r\n\r\n") || buf.len() >= MAX_REQUEST_SIZE {
+                break;
+            }
+        }
+
+        let request = String::from_utf8_lossy(&buf);
+        request.lines().next().map(ToString::to_string)
+    }
+
+    fn response(status: &str, content_type: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )
+    }
+
+    async fn serve_request(stream: &mut TcpStream, render_metrics: &F) {
+        let request_line = match Self::read_request_line(stream).await {
+            Some(request_line) => request_line,
+            None => return,
+        };
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default();
+
+        let response = match (method, path) {
+            ("GET", METRICS_PATH) => {
+                Self::response("200 OK", PROMETHEUS_CONTENT_TYPE, &render_metrics())
+            }
+            ("GET", _) => Self::response("404 Not Found", "text/plain", "not found"),
+            _ => Self::response("405 Method Not Allowed", "text/plain", "method not allowed"),
+        };
+
+        if let Err(err) = stream.write_all(response.as_bytes()).await {
+            debug!("Failed to write metrics response - {}", err);
+        }
+        let _ = stream.shutdown().await;
+    }
+
+    async fn handle_connection(
+        mut stream: TcpStream,
+        render_metrics: Arc<F>,
+        _permit: OwnedSemaphorePermit,
+    ) {
+        if tokio::time::timeout(
+            CONNECTION_TIMEOUT,
+            Self::serve_request(&mut stream, &render_metrics),
+        )
+        .await
+        .is_err()
+        {
+            debug!("Timed out while serving metrics request");
+        }
+    }
+
+    pub async fn run(&self) {
+        let listener = match TcpListener::bind(self.address).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!(
+                    "Failed to bind the metrics server to {} - {}. Metrics are not going to be available",
+                    self.address, err
+                );
+                return;
+            }
+        };
+        info!("Serving metrics on http://{}{}", self.address, METRICS_PATH);
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, remote_addr)) => {
+                    trace!("received a metrics request from {}", remote_addr);
+                    let permit = match Arc::clone(&self.connection_permits).try_acquire_owned() {
+                        Ok(permit) => permit,
+                        Err(_) => {
+                            debug!(
+                                "too many concurrent metrics connections - dropping connection from {}",
+                                remote_addr
+                            );
+                            // dropping the stream closes the connection
+                            continue;
+                        }
+                    };
+                    let render_metrics = Arc::clone(&self.render_metrics);
+                    tokio::spawn(Self::handle_connection(stream, render_metrics, permit));
+                }
+                Err(err) => warn!("failed to accept metrics connection - {}", err),
+            }
+        }
+    }
+
+    pub fn start(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn request(address: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    async fn start_test_server() -> SocketAddr {
+        // grab some free port
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        MetricsServer::new(address, || "foo 42\n".to_string()).start();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        address
+    }
+
+    #[tokio::test]
+    async fn serves_metrics() {
+        let address = start_test_server().await;
+
+        let response = request(address, "GET /metrics HTTP/1.1\r\nHost: foo\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(PROMETHEUS_CONTENT_TYPE));
+        assert!(response.ends_with("\r\n\r\nfoo 42\n"));
+    }
+
+    #[tokio::test]
+    async fn rejects_other_requests() {
+        let address = start_test_server().await;
+
+        let response = request(address, "GET /foo HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let response = request(address, "POST /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+    }
+
+    #[tokio::test]
+    async fn drops_connections_above_the_limit() {
+        let address = start_test_server().await;
+
+        let mut idle_connections = Vec::new();
+        for _ in 0..MAX_CONCURRENT_CONNECTIONS {
+            idle_connections.push(TcpStream::connect(address).await.unwrap());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // the connection is closed without a response, possibly even before the request is sent
+        let mut rejected = TcpStream::connect(address).await.unwrap();
+        let _ = rejected.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").await;
+        let mut response = String::new();
+        let _ = rejected.read_to_string(&mut response).await;
+        assert!(response.is_empty());
+
+        // once a slot frees up, requests are served again
+        idle_connections.pop();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let response = request(address, "GET /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+pub const DESCRIPTION_FILE: &str = "description.toml";
+
+/// Description of a mixnode or a gateway, as provided by its operator, telling people why they
+/// should use the node.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct NodeDescription {
+    pub name: String,
+    pub description: String,
+    pub link: String,
+    pub location: String,
+}
+
+impl Default for NodeDescription {
+    fn default() -> Self {
+        NodeDescription {
+            name: "This node has not yet set a name".to_string(),
+            description: "This node has not yet set a description".to_string(),
+            link: "https://nymtech.net".to_string(),
+            location: "This node has not yet set a location".to_string(),
+        }
+    }
+}
+
+impl NodeDescription {
+    fn description_file_path<P: AsRef<Path>>(config_path: P) -> PathBuf {
+        config_path.as_ref().join(DESCRIPTION_FILE)
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(config_path: P) -> io::Result<NodeDescription> {
+        let toml = fs::read_to_string(Self::description_file_path(config_path))?;
+        toml::from_str(&toml).map_err(|toml_err| io::Error::new(io::ErrorKind::Other, toml_err))
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, config_path: P) -> io::Result<()> {
+        let description_toml = toml::to_string(self).expect("could not encode description to toml");
+        fs::write(Self::description_file_path(config_path), description_toml)
+    }
+
+    /// Asks the operator for each part of the description of their node.
+    ///
+    /// # Arguments
+    ///
+    /// * `input`: where the answers are read from, usually stdin.
+    /// * `output`: where the questions are written to, usually stdout.
+    /// * `example_url`: example link to the website of the node.
+    pub fn prompt<R, W>(mut input: R, mut output: W, example_url: &str) -> io::Result<Self>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let mut ask = |question: String| -> io::Result<String> {
+            write!(output, "{}: ", question)?;
+            output.flush()?;
+            let mut answer = String::new();
+            input.read_line(&mut answer)?;
+            Ok(answer.trim().to_string())
+        };
+
+        let example_location = "City: London, Country: UK";
+        Ok(NodeDescription {
+            name: ask("name".to_string())?,
+            description: ask("description".to_string())?,
+            link: ask(format!("link, e.g. {}", example_url.bright_cyan()))?,
+            location: ask(format!("location, e.g. {}", example_location))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_reads_all_fields_in_order() {
+        let input =
+            "foomp\n  a very good node  \nhttps://foomp.com\nCity: Neuchatel, Country: CH\n";
+        let mut output = Vec::new();
+
+        let description =
+            NodeDescription::prompt(input.as_bytes(), &mut output, "https://node.yourdomain.com")
+                .unwrap();
+        assert_eq!(
+            description,
+            NodeDescription {
+                name: "foomp".to_string(),
+                description: "a very good node".to_string(),
+                link: "https://foomp.com".to_string(),
+                location: "City: Neuchatel, Country: CH".to_string(),
+            }
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("name: description: link, e.g. "));
+        assert!(output.contains("https://node.yourdomain.com"));
+    }
+
+    #[test]
+    fn description_survives_saving_and_loading() {
+        let dir = tempfile::tempdir().unwrap();
+        let description = NodeDescription {
+            name: "foomp".to_string(),
+            description: "a very good node".to_string(),
+            link: "https://foomp.com".to_string(),
+            location: "City: Neuchatel, Country: CH".to_string(),
+        };
+
+        description.save_to_file(dir.path()).unwrap();
+        assert_eq!(
+            NodeDescription::load_from_file(dir.path()).unwrap(),
+            description
+        );
+    }
+
+    #[test]
+    fn loading_missing_description_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(NodeDescription::load_from_file(dir.path()).is_err());
+    }
+}
@@ -14,6 +14,8 @@ pub enum MixProcessingError {
     MalformedSurbAck(SurbAckRecoveryError),
 
     ReceivedOldTypeVpnPacket,
+
+    ReplayedPacket,
 }
 
 impl From<SphinxError> for MixProcessingError {
@@ -59,6 +61,12 @@ impl Display for MixProcessingError {
             MixProcessingError::ReceivedOldTypeVpnPacket => {
                 write!(f, "Received an old-type unsafe 'VPN' mode packet")
             }
+            MixProcessingError::ReplayedPacket => {
+                write!(
+                    f,
+                    "Received a packet that has already been processed before"
+                )
+            }
         }
     }
 }
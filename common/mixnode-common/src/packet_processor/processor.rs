@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::packet_processor::error::MixProcessingError;
+use crate::packet_processor::replay_detection::{ReplayDetector, ReplayDetectorConfig, ReplayTag};
 use log::*;
 use nymsphinx_acknowledgements::surb_ack::SurbAck;
 use nymsphinx_addressing::nodes::NymNodeRoutingAddress;
@@ -13,7 +14,7 @@ use nymsphinx_types::{
     ProcessedPacket, SphinxPacket,
 };
 use std::convert::TryFrom;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 type ForwardAck = MixPacket;
 
@@ -31,29 +32,171 @@ pub enum MixProcessingResult {
     FinalHop(ProcessedFinalHop),
 }
 
+/// Private sphinx key of the node alongside the tags of the packets it has already unwrapped.
+/// A packet can only ever be replayed for as long as the key it was created for is accepted,
+/// so the replay cache gets dropped together with the key.
+struct SphinxKey {
+    private_key: PrivateKey,
+    replay_detector: ReplayDetector,
+}
+
+impl SphinxKey {
+    fn new(private_key: PrivateKey, replay_detection_config: ReplayDetectorConfig) -> Self {
+        SphinxKey {
+            private_key,
+            replay_detector: ReplayDetector::new(replay_detection_config),
+        }
+    }
+
+    /// Remembers the tag of the packet unwrapped with this key, unless it has already been seen.
+    fn check_replay(
+        &self,
+        replay_tag: &ReplayTag,
+        processed: ProcessedPacket,
+    ) -> Result<ProcessedPacket, MixProcessingError> {
+        if self.replay_detector.check_and_insert(replay_tag) {
+            return Err(MixProcessingError::ReplayedPacket);
+        }
+        Ok(processed)
+    }
+}
+
+/// Sphinx keys used by the node for unwrapping received packets.
+struct SphinxKeys {
+    /// Key that is currently announced in the topology of the node.
+    primary: SphinxKey,
+
+    /// Key that is also accepted while the node is rotating its keys, i.e. either the announced
+    /// next key before it gets activated or the previous key during the overlap window.
+    secondary: Option<SphinxKey>,
+}
+
 #[derive(Clone)]
 pub struct SphinxPacketProcessor {
-    /// Private sphinx key of this node required to unwrap received sphinx packet.
-    sphinx_key: Arc<PrivateKey>,
+    /// Private sphinx keys of this node required to unwrap received sphinx packet, each with
+    /// its own cache of tags of already processed packets, shared between all connections,
+    /// used for dropping replayed packets.
+    sphinx_keys: Arc<RwLock<SphinxKeys>>,
+
+    /// Configuration of the replay caches created for any new sphinx keys.
+    replay_detection_config: ReplayDetectorConfig,
 }
 
 impl SphinxPacketProcessor {
     /// Creates new instance of `CachedPacketProcessor`
     pub fn new(sphinx_key: PrivateKey) -> Self {
+        Self::new_with_replay_detection_config(sphinx_key, Default::default())
+    }
+
+    pub fn new_with_replay_detection_config(
+        sphinx_key: PrivateKey,
+        replay_detection_config: ReplayDetectorConfig,
+    ) -> Self {
         SphinxPacketProcessor {
-            sphinx_key: Arc::new(sphinx_key),
+            sphinx_keys: Arc::new(RwLock::new(SphinxKeys {
+                primary: SphinxKey::new(sphinx_key, replay_detection_config),
+                secondary: None,
+            })),
+            replay_detection_config,
         }
     }
 
-    /// Performs a fresh sphinx unwrapping using no cache.
+    /// Starts accepting packets created for the announced next sphinx key of this node
+    /// in addition to the ones created for its current key.
+    pub fn set_next_sphinx_key(&self, next_sphinx_key: PrivateKey) {
+        let next_sphinx_key = SphinxKey::new(next_sphinx_key, self.replay_detection_config);
+        let mut keys = self
+            .sphinx_keys
+            .write()
+            .expect("sphinx keys lock got poisoned");
+        keys.secondary = Some(next_sphinx_key);
+    }
+
+    /// Keeps on accepting packets created for the previous sphinx key of this node, that got
+    /// replaced before the node was restarted, until it gets explicitly retired.
+    pub fn set_previous_sphinx_key(&self, previous_sphinx_key: PrivateKey) {
+        let previous_sphinx_key = SphinxKey::new(previous_sphinx_key, self.replay_detection_config);
+        let mut keys = self
+            .sphinx_keys
+            .write()
+            .expect("sphinx keys lock got poisoned");
+        keys.secondary = Some(previous_sphinx_key);
+    }
+
+    /// Makes the next sphinx key the primary one. The previous key, alongside its replay cache,
+    /// is still accepted until it gets explicitly retired.
+    /// Returns `false` if there was no next key to activate.
+    pub fn activate_next_sphinx_key(&self) -> bool {
+        let mut keys = self
+            .sphinx_keys
+            .write()
+            .expect("sphinx keys lock got poisoned");
+        match keys.secondary.take() {
+            Some(next_sphinx_key) => {
+                let previous_sphinx_key = std::mem::replace(&mut keys.primary, next_sphinx_key);
+                keys.secondary = Some(previous_sphinx_key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops accepting packets created for any key other than the primary one
+    /// and drops the tags of the packets that were unwrapped with it.
+    pub fn retire_secondary_sphinx_key(&self) {
+        let mut keys = self
+            .sphinx_keys
+            .write()
+            .expect("sphinx keys lock got poisoned");
+        keys.secondary = None;
+    }
+
+    /// Extracts the tag identifying given packet for the purposes of replay detection, i.e. the
+    /// (blinded) shared secret from its header that is unique for each packet at each hop.
+    fn replay_tag(packet: &SphinxPacket) -> ReplayTag {
+        *packet.header.shared_secret.as_bytes()
+    }
+
+    /// Performs a fresh sphinx unwrapping using no cache and makes sure the packet has not already
+    /// been unwrapped with the same key.
+    /// If the node is rotating its keys, the secondary key is tried if the primary one failed.
     fn perform_initial_sphinx_packet_processing(
         &self,
         packet: SphinxPacket,
+        replay_tag: &ReplayTag,
     ) -> Result<ProcessedPacket, MixProcessingError> {
-        packet.process(&self.sphinx_key).map_err(|err| {
-            debug!("Failed to unwrap Sphinx packet: {:?}", err);
-            MixProcessingError::SphinxProcessingError(err)
-        })
+        let keys = self
+            .sphinx_keys
+            .read()
+            .expect("sphinx keys lock got poisoned");
+
+        let secondary = match &keys.secondary {
+            // processing consumes the packet, so keep its bytes around for the second attempt
+            Some(secondary_key) => Some((secondary_key, packet.to_bytes())),
+            None => None,
+        };
+
+        // only remember tags of packets that were successfully unwrapped so that malformed
+        // garbage would not fill up the caches
+        let err = match packet.process(&keys.primary.private_key) {
+            Ok(processed) => return keys.primary.check_replay(replay_tag, processed),
+            Err(err) => err,
+        };
+
+        let err = match secondary {
+            Some((secondary_key, packet_bytes)) => {
+                match SphinxPacket::from_bytes(&packet_bytes)
+                    .and_then(|packet| packet.process(&secondary_key.private_key))
+                {
+                    Ok(processed) => return secondary_key.check_replay(replay_tag, processed),
+                    Err(err) => err,
+                }
+            }
+            None => err,
+        };
+
+        debug!("Failed to unwrap Sphinx packet: {:?}", err);
+        Err(MixProcessingError::SphinxProcessingError(err))
     }
 
     /// Takes the received framed packet and tries to unwrap it from the sphinx encryption.
@@ -68,7 +211,8 @@ impl SphinxPacketProcessor {
             return Err(MixProcessingError::ReceivedOldTypeVpnPacket);
         }
 
-        self.perform_initial_sphinx_packet_processing(sphinx_packet)
+        let replay_tag = Self::replay_tag(&sphinx_packet);
+        self.perform_initial_sphinx_packet_processing(sphinx_packet, &replay_tag)
     }
 
     /// Processed received forward hop packet - tries to extract next hop address, sets delay
@@ -176,7 +320,7 @@ impl SphinxPacketProcessor {
         let packet_size = received.packet_size();
         let packet_mode = received.packet_mode();
 
-        // unwrap the sphinx packet and if possible and appropriate, cache keys
+        // unwrap the sphinx packet and make sure we haven't seen it before
         let processed_packet = self.perform_initial_unwrapping(received)?;
 
         // for forward packets, extract next hop and set delay (but do NOT delay here)
@@ -189,13 +333,199 @@ impl SphinxPacketProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nymsphinx_types::builder::SphinxPacketBuilder;
     use nymsphinx_types::crypto::keygen;
+    use nymsphinx_types::{
+        Destination, Node, PublicKey, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH,
+        NODE_ADDRESS_LENGTH,
+    };
 
     fn fixture() -> SphinxPacketProcessor {
         let local_keys = keygen();
         SphinxPacketProcessor::new(local_keys.0)
     }
 
+    // creates a single-hop ack-sized packet for which the processor is the final hop
+    fn make_final_hop_packet_bytes(node_key: PublicKey) -> Vec<u8> {
+        let node = Node::new(
+            NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+            node_key,
+        );
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![SphinxDelay::new_from_nanos(42)];
+
+        SphinxPacketBuilder::new()
+            .with_payload_size(PacketSize::AckPacket.payload_size())
+            .build_packet(b"foomp".to_vec(), &[node], &destination, &delays)
+            .unwrap()
+            .to_bytes()
+    }
+
+    fn framed_packet(packet_bytes: &[u8]) -> FramedSphinxPacket {
+        let packet = SphinxPacket::from_bytes(packet_bytes).unwrap();
+        FramedSphinxPacket::new(packet, Default::default())
+    }
+
+    #[test]
+    fn processing_the_same_packet_twice_is_detected_as_replay() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+        let packet_bytes = make_final_hop_packet_bytes(public_key);
+
+        let first = processor.process_received(framed_packet(&packet_bytes));
+        assert!(matches!(first, Ok(MixProcessingResult::FinalHop(..))));
+
+        let second = processor.process_received(framed_packet(&packet_bytes));
+        assert!(matches!(second, Err(MixProcessingError::ReplayedPacket)));
+    }
+
+    #[test]
+    fn replays_are_detected_across_cloned_processors() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+        let cloned_processor = processor.clone();
+        let packet_bytes = make_final_hop_packet_bytes(public_key);
+
+        assert!(processor
+            .process_received(framed_packet(&packet_bytes))
+            .is_ok());
+        assert!(matches!(
+            cloned_processor.process_received(framed_packet(&packet_bytes)),
+            Err(MixProcessingError::ReplayedPacket)
+        ));
+    }
+
+    #[test]
+    fn distinct_packets_are_not_detected_as_replays() {
+        let (private_key, public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+
+        for _ in 0..10 {
+            let packet_bytes = make_final_hop_packet_bytes(public_key);
+            assert!(processor
+                .process_received(framed_packet(&packet_bytes))
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn packets_that_failed_processing_are_not_remembered() {
+        let (private_key, _) = keygen();
+        let processor = SphinxPacketProcessor::new(private_key);
+        let (_, other_public_key) = keygen();
+        let packet_bytes = make_final_hop_packet_bytes(other_public_key);
+
+        // packet not meant for us fails on unwrapping both times rather than being a replay
+        for _ in 0..2 {
+            assert!(matches!(
+                processor.process_received(framed_packet(&packet_bytes)),
+                Err(MixProcessingError::SphinxProcessingError(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn packets_for_next_sphinx_key_are_accepted_once_it_is_known() {
+        let (current_private_key, current_public_key) = keygen();
+        let (next_private_key, next_public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(current_private_key);
+
+        let next_key_packet = make_final_hop_packet_bytes(next_public_key);
+        assert!(matches!(
+            processor.process_received(framed_packet(&next_key_packet)),
+            Err(MixProcessingError::SphinxProcessingError(..))
+        ));
+
+        // once the next key is known, packets for either key are accepted
+        processor.set_next_sphinx_key(next_private_key);
+        assert!(processor
+            .process_received(framed_packet(&next_key_packet))
+            .is_ok());
+        assert!(processor
+            .process_received(framed_packet(&make_final_hop_packet_bytes(
+                current_public_key
+            )))
+            .is_ok());
+    }
+
+    #[test]
+    fn previous_sphinx_key_is_rejected_after_being_retired() {
+        let (current_private_key, current_public_key) = keygen();
+        let (next_private_key, next_public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(current_private_key);
+        processor.set_next_sphinx_key(next_private_key);
+        assert!(processor.activate_next_sphinx_key());
+
+        // during the overlap window packets for both keys are accepted
+        for public_key in [current_public_key, next_public_key] {
+            assert!(processor
+                .process_received(framed_packet(&make_final_hop_packet_bytes(public_key)))
+                .is_ok());
+        }
+
+        processor.retire_secondary_sphinx_key();
+        assert!(processor
+            .process_received(framed_packet(&make_final_hop_packet_bytes(next_public_key)))
+            .is_ok());
+        assert!(matches!(
+            processor.process_received(framed_packet(&make_final_hop_packet_bytes(
+                current_public_key
+            ))),
+            Err(MixProcessingError::SphinxProcessingError(..))
+        ));
+        assert!(!processor.activate_next_sphinx_key());
+    }
+
+    #[test]
+    fn replays_are_detected_across_sphinx_key_activation() {
+        let (current_private_key, current_public_key) = keygen();
+        let (next_private_key, next_public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(current_private_key);
+        processor.set_next_sphinx_key(next_private_key);
+
+        let current_key_packet = make_final_hop_packet_bytes(current_public_key);
+        let next_key_packet = make_final_hop_packet_bytes(next_public_key);
+        for packet_bytes in [&current_key_packet, &next_key_packet] {
+            assert!(processor
+                .process_received(framed_packet(packet_bytes))
+                .is_ok());
+        }
+
+        // both keys keep their caches when they swap roles
+        assert!(processor.activate_next_sphinx_key());
+        for packet_bytes in [&current_key_packet, &next_key_packet] {
+            assert!(matches!(
+                processor.process_received(framed_packet(packet_bytes)),
+                Err(MixProcessingError::ReplayedPacket)
+            ));
+        }
+    }
+
+    #[test]
+    fn previous_sphinx_key_is_accepted_after_restart_until_retired() {
+        let (current_private_key, current_public_key) = keygen();
+        let (previous_private_key, previous_public_key) = keygen();
+        let processor = SphinxPacketProcessor::new(current_private_key);
+        processor.set_previous_sphinx_key(previous_private_key);
+
+        for public_key in [current_public_key, previous_public_key] {
+            assert!(processor
+                .process_received(framed_packet(&make_final_hop_packet_bytes(public_key)))
+                .is_ok());
+        }
+
+        processor.retire_secondary_sphinx_key();
+        assert!(matches!(
+            processor.process_received(framed_packet(&make_final_hop_packet_bytes(
+                previous_public_key
+            ))),
+            Err(MixProcessingError::SphinxProcessingError(..))
+        ));
+    }
+
     #[tokio::test]
     async fn splitting_hop_data_works_for_sufficiently_long_payload() {
         let processor = fixture();
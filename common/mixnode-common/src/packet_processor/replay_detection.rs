@@ -0,0 +1,240 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::Mutex;
+
+/// Length of the tag derived from a sphinx packet that is used for replay detection.
+/// It corresponds to the length of the (blinded) shared secret included in the packet header.
+pub const REPLAY_TAG_LENGTH: usize = 32;
+
+pub type ReplayTag = [u8; REPLAY_TAG_LENGTH];
+
+// A packet created for given sphinx key can be replayed for as long as the node accepts that key,
+// so the tags are never forgotten based on time. Instead, the cache grows in steps of new bloom
+// filters and is meant to be dropped together with the sphinx key it belongs to.
+const DEFAULT_EXPECTED_PACKETS_PER_FILTER: usize = 2_000_000;
+const DEFAULT_FALSE_POSITIVE_RATE: f64 = 1e-5;
+
+// Each subsequently created filter gets its false positive rate multiplied by this ratio so that
+// the overall rate stays bounded by `false_positive_rate / (1 - FALSE_POSITIVE_TIGHTENING_RATIO)`
+// no matter how many packets are received with the same key.
+const FALSE_POSITIVE_TIGHTENING_RATIO: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayDetectorConfig {
+    /// Number of packets after which another bloom filter is added to the cache.
+    /// It is used for determining size of the underlying bloom filters.
+    pub expected_packets_per_filter: usize,
+
+    /// Desired probability of incorrectly marking a fresh packet as a replay
+    /// by the first of the underlying bloom filters.
+    pub false_positive_rate: f64,
+}
+
+impl Default for ReplayDetectorConfig {
+    fn default() -> Self {
+        ReplayDetectorConfig {
+            expected_packets_per_filter: DEFAULT_EXPECTED_PACKETS_PER_FILTER,
+            false_positive_rate: DEFAULT_FALSE_POSITIVE_RATE,
+        }
+    }
+}
+
+struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let expected_items = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+
+        // standard formulas for optimal bloom filter parameters
+        let num_bits = (-(expected_items * false_positive_rate.ln()) / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / expected_items) * ln2).round().max(1.0) as u32;
+
+        let words = ((num_bits + 63) / 64) as usize;
+        BloomFilter {
+            bits: vec![0; words],
+            num_bits: words as u64 * 64,
+            num_hashes,
+        }
+    }
+
+    // uses the standard double hashing technique, i.e. h_i(x) = h1(x) + i * h2(x)
+    fn bit_indices(&self, h1: u64, h2: u64) -> impl Iterator<Item = u64> + '_ {
+        (0..self.num_hashes as u64)
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    fn contains(&self, h1: u64, h2: u64) -> bool {
+        self.bit_indices(h1, h2)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, h1: u64, h2: u64) {
+        let indices: Vec<_> = self.bit_indices(h1, h2).collect();
+        for bit in indices {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64)
+        }
+    }
+}
+
+struct GrowingFilters {
+    /// All filters created so far, with the currently written one being the last.
+    filters: Vec<BloomFilter>,
+
+    /// Number of tags inserted into the currently written filter.
+    current_items: usize,
+}
+
+/// Cache of replay tags of already processed sphinx packets created for a single sphinx key.
+///
+/// It is backed by a growing list of bloom filters - once the currently written one reaches
+/// its expected capacity, a new one, with a lower false positive rate, is added in its place,
+/// while the older ones are still consulted, but never updated. The tags are kept for as long
+/// as the detector itself, so it should be dropped when its sphinx key is no longer accepted.
+pub struct ReplayDetector {
+    config: ReplayDetectorConfig,
+    hasher_keys: (RandomState, RandomState),
+    filters: Mutex<GrowingFilters>,
+}
+
+impl ReplayDetector {
+    pub fn new(config: ReplayDetectorConfig) -> Self {
+        ReplayDetector {
+            config,
+            hasher_keys: (RandomState::new(), RandomState::new()),
+            filters: Mutex::new(GrowingFilters {
+                filters: vec![Self::new_filter(&config, 0)],
+                current_items: 0,
+            }),
+        }
+    }
+
+    fn new_filter(config: &ReplayDetectorConfig, index: usize) -> BloomFilter {
+        let false_positive_rate =
+            config.false_positive_rate * FALSE_POSITIVE_TIGHTENING_RATIO.powi(index as i32);
+        BloomFilter::new(
+            config.expected_packets_per_filter,
+            false_positive_rate.max(f64::MIN_POSITIVE),
+        )
+    }
+
+    fn hash_tag(&self, tag: &ReplayTag) -> (u64, u64) {
+        let mut h1 = self.hasher_keys.0.build_hasher();
+        let mut h2 = self.hasher_keys.1.build_hasher();
+        tag.hash(&mut h1);
+        tag.hash(&mut h2);
+
+        // make sure the second hash is odd so that all bit indices are distinct (for most sizes)
+        (h1.finish(), h2.finish() | 1)
+    }
+
+    /// Checks whether the provided tag has already been seen by this detector
+    /// and if not, it remembers it.
+    pub fn check_and_insert(&self, tag: &ReplayTag) -> bool {
+        let (h1, h2) = self.hash_tag(tag);
+
+        // if the lock is poisoned, another thread panicked while holding it which should be
+        // impossible as none of the operations on the filters can panic
+        let mut filters = self
+            .filters
+            .lock()
+            .expect("replay detector lock got poisoned");
+
+        if filters.filters.iter().any(|filter| filter.contains(h1, h2)) {
+            return true;
+        }
+
+        if filters.current_items >= self.config.expected_packets_per_filter {
+            let next_filter = Self::new_filter(&self.config, filters.filters.len());
+            filters.filters.push(next_filter);
+            filters.current_items = 0;
+        }
+
+        filters.current_items += 1;
+        filters
+            .filters
+            .last_mut()
+            .expect("there always exists at least a single filter")
+            .insert(h1, h2);
+        false
+    }
+
+    #[cfg(test)]
+    fn filters_count(&self) -> usize {
+        self.filters
+            .lock()
+            .expect("replay detector lock got poisoned")
+            .filters
+            .len()
+    }
+}
+
+impl Default for ReplayDetector {
+    fn default() -> Self {
+        ReplayDetector::new(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_detector(expected_packets_per_filter: usize) -> ReplayDetector {
+        ReplayDetector::new(ReplayDetectorConfig {
+            expected_packets_per_filter,
+            false_positive_rate: 1e-6,
+        })
+    }
+
+    #[test]
+    fn fresh_tags_are_not_marked_as_replays() {
+        let detector = small_detector(1000);
+        for i in 0..100u8 {
+            assert!(!detector.check_and_insert(&[i; REPLAY_TAG_LENGTH]))
+        }
+    }
+
+    #[test]
+    fn repeated_tags_are_marked_as_replays() {
+        let detector = small_detector(1000);
+        let tag = [42u8; REPLAY_TAG_LENGTH];
+
+        assert!(!detector.check_and_insert(&tag));
+        assert!(detector.check_and_insert(&tag));
+        assert!(detector.check_and_insert(&tag));
+    }
+
+    #[test]
+    fn new_filters_are_added_once_the_current_one_is_full() {
+        let detector = small_detector(10);
+        for i in 0..10u8 {
+            assert!(!detector.check_and_insert(&[i; REPLAY_TAG_LENGTH]))
+        }
+        assert_eq!(detector.filters_count(), 1);
+
+        assert!(!detector.check_and_insert(&[10u8; REPLAY_TAG_LENGTH]));
+        assert_eq!(detector.filters_count(), 2);
+    }
+
+    #[test]
+    fn tags_are_remembered_across_all_filters() {
+        let detector = small_detector(10);
+        for i in 0..50u8 {
+            assert!(!detector.check_and_insert(&[i; REPLAY_TAG_LENGTH]))
+        }
+        assert_eq!(detector.filters_count(), 5);
+
+        for i in 0..50u8 {
+            assert!(detector.check_and_insert(&[i; REPLAY_TAG_LENGTH]))
+        }
+    }
+}
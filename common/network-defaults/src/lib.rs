@@ -41,6 +41,8 @@ pub struct NymContracts {
     pub bandwidth_claim_contract_address: Option<String>,
     pub coconut_bandwidth_contract_address: Option<String>,
     pub multisig_contract_address: Option<String>,
+    pub coconut_dkg_contract_address: Option<String>,
+    pub service_provider_directory_contract_address: Option<String>,
 }
 
 // I wanted to use the simpler `NetworkDetails` name, but there's a clash
@@ -101,6 +103,12 @@ impl NymNetworkDetails {
             .with_multisig_contract(Some(
                 var(var_names::MULTISIG_CONTRACT_ADDRESS).expect("multisig contract not set"),
             ))
+            // the dkg contract is only relevant to the coconut signers
+            .with_coconut_dkg_contract(var(var_names::COCONUT_DKG_CONTRACT_ADDRESS).ok())
+            // the directory is optional as it is only used for discovering service providers
+            .with_service_provider_directory_contract(
+                var(var_names::SERVICE_PROVIDER_DIRECTORY_CONTRACT_ADDRESS).ok(),
+            )
     }
 
     pub fn new_mainnet() -> Self {
@@ -161,6 +169,19 @@ impl NymNetworkDetails {
         self.contracts.multisig_contract_address = contract.map(Into::into);
         self
     }
+
+    pub fn with_coconut_dkg_contract<S: Into<String>>(mut self, contract: Option<S>) -> Self {
+        self.contracts.coconut_dkg_contract_address = contract.map(Into::into);
+        self
+    }
+
+    pub fn with_service_provider_directory_contract<S: Into<String>>(
+        mut self,
+        contract: Option<S>,
+    ) -> Self {
+        self.contracts.service_provider_directory_contract_address = contract.map(Into::into);
+        self
+    }
 }
 
 // This conversion only exists for convenience reasons until
@@ -192,6 +213,12 @@ impl<'a> From<&'a DefaultNetworkDetails> for NymNetworkDetails {
                     details.coconut_bandwidth_contract_address,
                 ),
                 multisig_contract_address: parse_optional_str(details.multisig_contract_address),
+                coconut_dkg_contract_address: parse_optional_str(
+                    details.coconut_dkg_contract_address,
+                ),
+                service_provider_directory_contract_address: parse_optional_str(
+                    details.service_provider_directory_contract_address,
+                ),
             },
         }
     }
@@ -209,6 +236,8 @@ pub struct DefaultNetworkDetails {
     bandwidth_claim_contract_address: &'static str,
     coconut_bandwidth_contract_address: &'static str,
     multisig_contract_address: &'static str,
+    coconut_dkg_contract_address: &'static str,
+    service_provider_directory_contract_address: &'static str,
     #[allow(dead_code)]
     rewarding_validator_address: &'static str,
     statistics_service_url: &'static str,
@@ -224,6 +253,9 @@ static MAINNET_DEFAULTS: Lazy<DefaultNetworkDetails> = Lazy::new(|| DefaultNetwo
     bandwidth_claim_contract_address: mainnet::BANDWIDTH_CLAIM_CONTRACT_ADDRESS,
     coconut_bandwidth_contract_address: mainnet::COCONUT_BANDWIDTH_CONTRACT_ADDRESS,
     multisig_contract_address: mainnet::MULTISIG_CONTRACT_ADDRESS,
+    coconut_dkg_contract_address: mainnet::COCONUT_DKG_CONTRACT_ADDRESS,
+    service_provider_directory_contract_address:
+        mainnet::SERVICE_PROVIDER_DIRECTORY_CONTRACT_ADDRESS,
     rewarding_validator_address: mainnet::REWARDING_VALIDATOR_ADDRESS,
     statistics_service_url: mainnet::STATISTICS_SERVICE_DOMAIN_ADDRESS,
     validators: mainnet::validators(),
@@ -238,6 +270,9 @@ static SANDBOX_DEFAULTS: Lazy<DefaultNetworkDetails> = Lazy::new(|| DefaultNetwo
     bandwidth_claim_contract_address: sandbox::BANDWIDTH_CLAIM_CONTRACT_ADDRESS,
     coconut_bandwidth_contract_address: sandbox::COCONUT_BANDWIDTH_CONTRACT_ADDRESS,
     multisig_contract_address: sandbox::MULTISIG_CONTRACT_ADDRESS,
+    coconut_dkg_contract_address: sandbox::COCONUT_DKG_CONTRACT_ADDRESS,
+    service_provider_directory_contract_address:
+        sandbox::SERVICE_PROVIDER_DIRECTORY_CONTRACT_ADDRESS,
     rewarding_validator_address: sandbox::REWARDING_VALIDATOR_ADDRESS,
     statistics_service_url: sandbox::STATISTICS_SERVICE_DOMAIN_ADDRESS,
     validators: sandbox::validators(),
@@ -252,6 +287,8 @@ static QA_DEFAULTS: Lazy<DefaultNetworkDetails> = Lazy::new(|| DefaultNetworkDet
     bandwidth_claim_contract_address: qa::BANDWIDTH_CLAIM_CONTRACT_ADDRESS,
     coconut_bandwidth_contract_address: qa::COCONUT_BANDWIDTH_CONTRACT_ADDRESS,
     multisig_contract_address: qa::MULTISIG_CONTRACT_ADDRESS,
+    coconut_dkg_contract_address: qa::COCONUT_DKG_CONTRACT_ADDRESS,
+    service_provider_directory_contract_address: qa::SERVICE_PROVIDER_DIRECTORY_CONTRACT_ADDRESS,
     rewarding_validator_address: qa::REWARDING_VALIDATOR_ADDRESS,
     statistics_service_url: qa::STATISTICS_SERVICE_DOMAIN_ADDRESS,
     validators: qa::validators(),
@@ -391,6 +428,7 @@ pub const DEFAULT_MIX_LISTENING_PORT: u16 = 1789;
 
 // 'GATEWAY'
 pub const DEFAULT_CLIENT_LISTENING_PORT: u16 = 9000;
+pub const DEFAULT_GATEWAY_HTTP_API_LISTENING_PORT: u16 = 8001;
 
 // 'MIXNODE'
 pub const DEFAULT_VERLOC_LISTENING_PORT: u16 = 1790;
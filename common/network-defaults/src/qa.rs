@@ -17,6 +17,10 @@ pub(crate) const BANDWIDTH_CLAIM_CONTRACT_ADDRESS: &str =
 pub(crate) const COCONUT_BANDWIDTH_CONTRACT_ADDRESS: &str =
     "n1ghd753shjuwexxywmgs4xz7x2q732vcn7ty4yw";
 pub(crate) const MULTISIG_CONTRACT_ADDRESS: &str = "n17p9rzwnnfxcjp32un9ug7yhhzgtkhvl988qccs";
+// the dkg contract has not been deployed yet
+pub(crate) const COCONUT_DKG_CONTRACT_ADDRESS: &str = "";
+// the service provider directory contract has not been deployed yet
+pub(crate) const SERVICE_PROVIDER_DIRECTORY_CONTRACT_ADDRESS: &str = "";
 pub(crate) const _ETH_CONTRACT_ADDRESS: [u8; 20] =
     hex_literal::hex!("0000000000000000000000000000000000000000");
 pub(crate) const _ETH_ERC20_CONTRACT_ADDRESS: [u8; 20] =
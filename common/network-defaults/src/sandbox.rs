@@ -15,6 +15,10 @@ pub(crate) const BANDWIDTH_CLAIM_CONTRACT_ADDRESS: &str =
 pub(crate) const COCONUT_BANDWIDTH_CONTRACT_ADDRESS: &str =
     "nymt1nz0r0au8aj6dc00wmm3ufy4g4k86rjzlgq608r";
 pub(crate) const MULTISIG_CONTRACT_ADDRESS: &str = "nymt1k8re7jwz6rnnwrktnejdwkwnncte7ek7kk6fvg";
+// the dkg contract has not been deployed yet
+pub(crate) const COCONUT_DKG_CONTRACT_ADDRESS: &str = "";
+// the service provider directory contract has not been deployed yet
+pub(crate) const SERVICE_PROVIDER_DIRECTORY_CONTRACT_ADDRESS: &str = "";
 pub(crate) const _ETH_CONTRACT_ADDRESS: [u8; 20] =
     hex_literal::hex!("8e0DcFF7F3085235C32E845f3667aEB3f1e83133");
 pub(crate) const _ETH_ERC20_CONTRACT_ADDRESS: [u8; 20] =
@@ -4,12 +4,22 @@
 use std::pin::Pin;
 use std::task::{Context, Poll, Waker};
 use std::time::Duration;
-use tokio::time::Instant;
 use tokio_stream::Stream;
+
+#[cfg(not(target_arch = "wasm32"))]
 use tokio_util::time::{delay_queue, DelayQueue};
+#[cfg(target_arch = "wasm32")]
+use wasm_delay_queue::{self as delay_queue, DelayQueue};
+
+#[cfg(target_arch = "wasm32")]
+mod wasm_delay_queue;
+
+#[cfg(target_arch = "wasm32")]
+pub use fluvio_wasm_timer::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+pub use tokio::time::{error::Error as TimerError, Instant};
 
-pub use tokio::time::error::Error as TimerError;
-pub use tokio_util::time::delay_queue::Expired;
+pub use delay_queue::Expired;
 pub type QueueKey = delay_queue::Key;
 
 /// A variant of tokio's `DelayQueue`, such that its `Stream` implementation will never return a 'None'.
@@ -0,0 +1,140 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal replacement for tokio-util's `DelayQueue` for wasm targets, where the tokio timer
+//! is not available. It only implements the subset of the API used by `NonExhaustiveDelayQueue`.
+
+use fluvio_wasm_timer::{Delay, Instant};
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use tokio_stream::Stream;
+
+/// Token to a value stored in the `DelayQueue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(u64);
+
+/// An entry that has been removed from the `DelayQueue`, either explicitly or because it expired.
+#[derive(Debug)]
+pub struct Expired<T> {
+    data: T,
+    deadline: Instant,
+    key: Key,
+}
+
+impl<T> Expired<T> {
+    pub fn get_ref(&self) -> &T {
+        &self.data
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    pub fn key(&self) -> Key {
+        self.key
+    }
+}
+
+pub struct DelayQueue<T> {
+    entries: HashMap<Key, (T, Instant)>,
+
+    /// Deadlines of all stored entries, ordered by their expiration time.
+    expirations: BTreeSet<(Instant, Key)>,
+
+    /// Timer of the entry that is going to expire next alongside its deadline.
+    delay: Option<(Instant, Pin<Box<Delay>>)>,
+    next_key: u64,
+    waker: Option<Waker>,
+}
+
+// we never pin project into the stored values
+impl<T> Unpin for DelayQueue<T> {}
+
+impl<T> DelayQueue<T> {
+    pub fn new() -> Self {
+        DelayQueue {
+            entries: HashMap::new(),
+            expirations: BTreeSet::new(),
+            delay: None,
+            next_key: 0,
+            waker: None,
+        }
+    }
+
+    pub fn insert(&mut self, value: T, timeout: Duration) -> Key {
+        self.insert_at(value, Instant::now() + timeout)
+    }
+
+    pub fn insert_at(&mut self, value: T, when: Instant) -> Key {
+        let key = Key(self.next_key);
+        self.next_key += 1;
+
+        self.entries.insert(key, (value, when));
+        self.expirations.insert((when, key));
+
+        // the new entry might expire before the one we're currently waiting for
+        if let Some(waker) = self.waker.take() {
+            waker.wake()
+        }
+        key
+    }
+
+    /// Removes the entry associated with the key from the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not contained in the queue.
+    pub fn remove(&mut self, key: &Key) -> Expired<T> {
+        let (data, deadline) = self
+            .entries
+            .remove(key)
+            .expect("invalid key - the entry is not contained in the queue");
+        self.expirations.remove(&(deadline, *key));
+
+        Expired {
+            data,
+            deadline,
+            key: *key,
+        }
+    }
+}
+
+impl<T> Stream for DelayQueue<T> {
+    type Item = Expired<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let (deadline, key) = match self.expirations.iter().next() {
+            Some(next) => *next,
+            None => {
+                self.delay = None;
+                return Poll::Ready(None);
+            }
+        };
+
+        let now = Instant::now();
+        if deadline > now {
+            // (re)create the timer if we're not waiting for this particular entry yet
+            if !matches!(self.delay, Some((at, _)) if at == deadline) {
+                let delay = Box::pin(Delay::new(deadline.duration_since(now)));
+                self.delay = Some((deadline, delay));
+            }
+
+            let (_, delay) = self.delay.as_mut().unwrap();
+            // if the timer has failed, just treat the entry as expired
+            if delay.as_mut().poll(cx).is_pending() {
+                self.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        self.delay = None;
+        Poll::Ready(Some(self.remove(&key)))
+    }
+}
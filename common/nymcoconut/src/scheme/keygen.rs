@@ -350,7 +350,7 @@ impl Bytable for VerificationKey {
 
 impl Base58 for VerificationKey {}
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 pub struct KeyPair {
     secret_key: SecretKey,
@@ -363,6 +363,20 @@ pub struct KeyPair {
 impl KeyPair {
     const MARKER_BYTES: &'static [u8] = b"coconutkeypair";
 
+    /// Creates a keypair out of already existing keys, such as the ones derived
+    /// during distributed key generation.
+    pub fn from_keys(
+        secret_key: SecretKey,
+        verification_key: VerificationKey,
+        index: Option<SignerIndex>,
+    ) -> Self {
+        KeyPair {
+            secret_key,
+            verification_key,
+            index,
+        }
+    }
+
     pub fn secret_key(&self) -> SecretKey {
         self.secret_key.clone()
     }
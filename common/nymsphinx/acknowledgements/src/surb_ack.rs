@@ -6,7 +6,6 @@ use crate::AckKey;
 use nymsphinx_addressing::clients::Recipient;
 use nymsphinx_addressing::nodes::{NymNodeRoutingAddress, MAX_NODE_ADDRESS_UNPADDED_LEN};
 use nymsphinx_params::packet_sizes::PacketSize;
-use nymsphinx_params::DEFAULT_NUM_MIX_HOPS;
 use nymsphinx_types::builder::SphinxPacketBuilder;
 use nymsphinx_types::{
     delays::{self, Delay},
@@ -37,13 +36,13 @@ impl SurbAck {
         ack_key: &AckKey,
         marshaled_fragment_id: [u8; 5],
         average_delay: time::Duration,
+        num_mix_hops: u8,
         topology: &NymTopology,
     ) -> Result<Self, NymTopologyError>
     where
         R: RngCore + CryptoRng,
     {
-        let route =
-            topology.random_route_to_gateway(rng, DEFAULT_NUM_MIX_HOPS, recipient.gateway())?;
+        let route = topology.random_route_to_gateway(rng, num_mix_hops, recipient.gateway())?;
         let delays = delays::generate_from_average_duration(route.len(), average_delay);
         let destination = recipient.as_sphinx_destination();
 
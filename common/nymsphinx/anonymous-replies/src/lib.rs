@@ -3,6 +3,8 @@
 
 pub mod encryption_key;
 pub mod reply_surb;
+pub mod requests;
 
 pub use encryption_key::{SurbEncryptionKey, SurbEncryptionKeySize};
 pub use reply_surb::{ReplySurb, ReplySurbError};
+pub use requests::{AnonymousSenderTag, ReplyContent};
@@ -5,8 +5,9 @@ use crate::encryption_key::{SurbEncryptionKey, SurbEncryptionKeyError, SurbEncry
 use crypto::{generic_array::typenum::Unsigned, Digest};
 use nymsphinx_addressing::clients::Recipient;
 use nymsphinx_addressing::nodes::{NymNodeRoutingAddress, MAX_NODE_ADDRESS_UNPADDED_LEN};
+use nymsphinx_chunking::fragment::unlinked_fragment_payload_max_len;
 use nymsphinx_params::packet_sizes::PacketSize;
-use nymsphinx_params::{ReplySurbKeyDigestAlgorithm, DEFAULT_NUM_MIX_HOPS};
+use nymsphinx_params::ReplySurbKeyDigestAlgorithm;
 use nymsphinx_types::{delays, Error as SphinxError, SURBMaterial, SphinxPacket, SURB};
 use rand::{CryptoRng, RngCore};
 use serde::de::{Error as SerdeError, Visitor};
@@ -99,10 +100,17 @@ impl<'de> Deserialize<'de> for ReplySurb {
 }
 
 impl ReplySurb {
+    /// Maximum length of the message that can be sent back using a single reply SURB.
     pub fn max_msg_len(packet_size: PacketSize) -> usize {
         // For detailed explanation (of ack overhead) refer to common\nymsphinx\src\preparer.rs::available_plaintext_per_packet()
         let ack_overhead = MAX_NODE_ADDRESS_UNPADDED_LEN + PacketSize::AckPacket.size();
-        packet_size.plaintext_size() - ack_overhead - ReplySurbKeyDigestAlgorithm::output_size() - 1
+        let plaintext_per_packet = packet_size.plaintext_size()
+            - ack_overhead
+            - ReplySurbKeyDigestAlgorithm::output_size();
+
+        // the reply is sent as a single fragment that has to also fit the reply content tag
+        // and at least a single byte of padding
+        unlinked_fragment_payload_max_len(plaintext_per_packet) - 2
     }
 
     // TODO: should this return `ReplySURBError` for consistency sake
@@ -112,12 +120,12 @@ impl ReplySurb {
         recipient: &Recipient,
         average_delay: time::Duration,
         topology: &NymTopology,
+        num_mix_hops: u8,
     ) -> Result<Self, NymTopologyError>
     where
         R: RngCore + CryptoRng,
     {
-        let route =
-            topology.random_route_to_gateway(rng, DEFAULT_NUM_MIX_HOPS, recipient.gateway())?;
+        let route = topology.random_route_to_gateway(rng, num_mix_hops, recipient.gateway())?;
         let delays = delays::generate_from_average_duration(route.len(), average_delay);
         let destination = recipient.as_sphinx_destination();
 
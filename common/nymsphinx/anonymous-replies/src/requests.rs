@@ -0,0 +1,198 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::{CryptoRng, RngCore};
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+use std::mem::size_of;
+
+pub const SENDER_TAG_SIZE: usize = 16;
+
+const DATA_CONTENT_TAG: u8 = 0;
+const SURB_REQUEST_CONTENT_TAG: u8 = 1;
+
+#[derive(Debug)]
+pub enum InvalidReplyContentError {
+    EmptyContent,
+    UnknownContentTag(u8),
+    MalformedSurbRequest,
+    MalformedSenderTag(bs58::decode::Error),
+    InvalidSenderTagLength(usize),
+}
+
+impl Display for InvalidReplyContentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidReplyContentError::EmptyContent => write!(f, "the reply content is empty"),
+            InvalidReplyContentError::UnknownContentTag(tag) => {
+                write!(f, "{} is not a valid reply content tag", tag)
+            }
+            InvalidReplyContentError::MalformedSurbRequest => {
+                write!(f, "the reply SURB request is malformed")
+            }
+            InvalidReplyContentError::MalformedSenderTag(decode_err) => {
+                write!(f, "sender tag is incorrectly formatted: {}", decode_err)
+            }
+            InvalidReplyContentError::InvalidSenderTagLength(len) => write!(
+                f,
+                "sender tag has invalid length of {} bytes. Expected {}",
+                len, SENDER_TAG_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidReplyContentError {}
+
+/// Randomly generated identifier the recipient of reply SURBs uses to refer to their anonymous sender,
+/// so that it would know which SURBs to use for its replies without learning anything about the sender itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AnonymousSenderTag([u8; SENDER_TAG_SIZE]);
+
+impl AnonymousSenderTag {
+    pub fn new_random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut tag = [0u8; SENDER_TAG_SIZE];
+        rng.fill_bytes(&mut tag);
+        AnonymousSenderTag(tag)
+    }
+
+    pub fn from_bytes(bytes: [u8; SENDER_TAG_SIZE]) -> Self {
+        AnonymousSenderTag(bytes)
+    }
+
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self, InvalidReplyContentError> {
+        bytes
+            .try_into()
+            .map(AnonymousSenderTag)
+            .map_err(|_| InvalidReplyContentError::InvalidSenderTagLength(bytes.len()))
+    }
+
+    pub fn to_bytes(self) -> [u8; SENDER_TAG_SIZE] {
+        self.0
+    }
+
+    pub fn to_base58_string(self) -> String {
+        bs58::encode(&self.0).into_string()
+    }
+
+    pub fn try_from_base58_string<S: Into<String>>(
+        val: S,
+    ) -> Result<Self, InvalidReplyContentError> {
+        let bytes = bs58::decode(val.into())
+            .into_vec()
+            .map_err(InvalidReplyContentError::MalformedSenderTag)?;
+        Self::try_from_slice(&bytes)
+    }
+}
+
+impl Display for AnonymousSenderTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base58_string())
+    }
+}
+
+/// Content of a (potentially multi-packet) reply sent back using reply SURBs.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplyContent {
+    /// Actual data meant for the original sender.
+    Data(Vec<u8>),
+
+    /// Request for additional reply SURBs, sent by the holder of the SURBs
+    /// whenever it is running low on them.
+    SurbRequest {
+        sender_tag: AnonymousSenderTag,
+        amount: u32,
+    },
+}
+
+impl ReplyContent {
+    // DATA_CONTENT_TAG || data
+    // OR
+    // SURB_REQUEST_CONTENT_TAG || sender_tag || amount
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            ReplyContent::Data(data) => std::iter::once(DATA_CONTENT_TAG)
+                .chain(data.into_iter())
+                .collect(),
+            ReplyContent::SurbRequest { sender_tag, amount } => {
+                std::iter::once(SURB_REQUEST_CONTENT_TAG)
+                    .chain(sender_tag.to_bytes().iter().copied())
+                    .chain(amount.to_be_bytes().iter().copied())
+                    .collect()
+            }
+        }
+    }
+
+    pub fn try_from_bytes(mut bytes: Vec<u8>) -> Result<Self, InvalidReplyContentError> {
+        if bytes.is_empty() {
+            return Err(InvalidReplyContentError::EmptyContent);
+        }
+
+        match bytes[0] {
+            DATA_CONTENT_TAG => {
+                bytes.remove(0);
+                Ok(ReplyContent::Data(bytes))
+            }
+            SURB_REQUEST_CONTENT_TAG => {
+                if bytes.len() != 1 + SENDER_TAG_SIZE + size_of::<u32>() {
+                    return Err(InvalidReplyContentError::MalformedSurbRequest);
+                }
+                let sender_tag =
+                    AnonymousSenderTag::try_from_slice(&bytes[1..1 + SENDER_TAG_SIZE])?;
+                let amount = u32::from_be_bytes(bytes[1 + SENDER_TAG_SIZE..].try_into().unwrap());
+                Ok(ReplyContent::SurbRequest { sender_tag, amount })
+            }
+            n => Err(InvalidReplyContentError::UnknownContentTag(n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn sender_tag_base58_conversion_works() {
+        let tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let recovered = AnonymousSenderTag::try_from_base58_string(tag.to_base58_string()).unwrap();
+        assert_eq!(tag, recovered);
+
+        assert!(AnonymousSenderTag::try_from_base58_string("foomp").is_err());
+    }
+
+    #[test]
+    fn reply_content_serialization_works() {
+        let data = ReplyContent::Data(b"foomp".to_vec());
+        assert_eq!(
+            ReplyContent::try_from_bytes(data.into_bytes()).unwrap(),
+            ReplyContent::Data(b"foomp".to_vec())
+        );
+
+        let empty = ReplyContent::Data(Vec::new());
+        assert_eq!(
+            ReplyContent::try_from_bytes(empty.into_bytes()).unwrap(),
+            ReplyContent::Data(Vec::new())
+        );
+
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let request = ReplyContent::SurbRequest {
+            sender_tag,
+            amount: 42,
+        };
+        assert_eq!(
+            ReplyContent::try_from_bytes(request.into_bytes()).unwrap(),
+            ReplyContent::SurbRequest {
+                sender_tag,
+                amount: 42
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_reply_content_is_rejected() {
+        assert!(ReplyContent::try_from_bytes(Vec::new()).is_err());
+        assert!(ReplyContent::try_from_bytes(vec![42, 1, 2, 3]).is_err());
+        assert!(ReplyContent::try_from_bytes(vec![SURB_REQUEST_CONTENT_TAG, 1, 2, 3]).is_err());
+    }
+}
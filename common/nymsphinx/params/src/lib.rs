@@ -18,6 +18,13 @@ pub mod packet_sizes;
 // I will change this to [`usize`]
 pub const DEFAULT_NUM_MIX_HOPS: u8 = 3;
 
+/// Maximum number of mix hops a packet can take. Each hop has to go through a subsequent mix layer,
+/// so it is bounded by the number of layers in the network.
+pub const MAX_NUM_MIX_HOPS: u8 = 3;
+
+// the final hop of every sphinx packet is always a gateway, so the mix hops must leave room for it
+const _: () = assert!(MAX_NUM_MIX_HOPS as usize < nymsphinx_types::MAX_PATH_LENGTH);
+
 // TODO: not entirely sure how to feel about those being defined here, ideally it'd be where [`Fragment`]
 // is defined, but that'd introduce circular dependencies as the acknowledgements crate also needs
 // access to that
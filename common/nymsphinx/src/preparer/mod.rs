@@ -12,6 +12,7 @@ use nymsphinx_addressing::clients::Recipient;
 use nymsphinx_addressing::nodes::{NymNodeRoutingAddress, MAX_NODE_ADDRESS_UNPADDED_LEN};
 use nymsphinx_anonymous_replies::encryption_key::SurbEncryptionKey;
 use nymsphinx_anonymous_replies::reply_surb::ReplySurb;
+use nymsphinx_anonymous_replies::requests::{AnonymousSenderTag, ReplyContent};
 use nymsphinx_chunking::fragment::{Fragment, FragmentIdentifier};
 use nymsphinx_forwarding::packet::MixPacket;
 use nymsphinx_params::packet_sizes::PacketSize;
@@ -26,6 +27,12 @@ use std::convert::TryFrom;
 use std::time::Duration;
 use topology::{NymTopology, NymTopologyError};
 
+// prefixes of fresh messages indicating what kind of reply SURBs, if any, are attached to them
+pub(crate) const NO_REPLY_SURB_PREFIX: u8 = 0;
+pub(crate) const SINGLE_REPLY_SURB_PREFIX: u8 = 1;
+pub(crate) const TAGGED_REPLY_SURBS_PREFIX: u8 = 2;
+pub(crate) const ADDITIONAL_REPLY_SURBS_PREFIX: u8 = 3;
+
 /// Represents fully packed and prepared [`Fragment`] that can be sent through the mix network.
 pub struct PreparedFragment {
     /// Indicates the total expected round-trip time, i.e. delay from the sending of this message
@@ -72,7 +79,8 @@ pub struct MessagePreparer<R: CryptoRng + Rng> {
     /// Average delay an acknowledgement packet is going to get delay at a single mixnode.
     average_ack_delay: Duration,
 
-    /// Number of mix hops each packet ('real' message, ack, reply) is expected to take.
+    /// Number of mix hops each packet ('real' message, ack) is expected to take, unless specified
+    /// otherwise for a particular message.
     /// Note that it does not include gateway hops.
     num_mix_hops: u8,
 }
@@ -103,6 +111,11 @@ where
         self
     }
 
+    /// Returns the number of mix hops the packets are going to take by default.
+    pub fn num_mix_hops(&self) -> u8 {
+        self.num_mix_hops
+    }
+
     /// Allows setting non-default size of the sphinx packets sent out.
     pub fn with_packet_size(mut self, packet_size: PacketSize) -> Self {
         self.packet_size = packet_size;
@@ -131,15 +144,24 @@ where
         self.packet_size.plaintext_size() - ack_overhead - ephemeral_public_key_overhead
     }
 
+    /// Length of plaintext (from the sphinx point of view) data that is available per sphinx
+    /// packet created out of a reply SURB.
+    fn available_plaintext_per_reply_packet(&self) -> usize {
+        // rather than the ephemeral key, the digest of the reply key has to be included
+        // so that the recipient would know how to decrypt the content
+        let ack_overhead = MAX_NODE_ADDRESS_UNPADDED_LEN + PacketSize::AckPacket.size();
+        let key_digest_overhead = ReplySurbKeyDigestAlgorithm::output_size();
+
+        self.packet_size.plaintext_size() - ack_overhead - key_digest_overhead
+    }
+
     /// Pads the message so that after it gets chunked, it will occupy exactly N sphinx packets.
     /// Produces new_message = message || 1 || 0000....
-    fn pad_message(&self, message: Vec<u8>) -> Vec<u8> {
+    fn pad_message(&self, message: Vec<u8>, plaintext_per_packet: usize) -> Vec<u8> {
         // 1 is added as there will always have to be at least a single byte of padding (1) added
         // to be able to later distinguish the actual padding from the underlying message
-        let (_, space_left) = chunking::number_of_required_fragments(
-            message.len() + 1,
-            self.available_plaintext_per_packet(),
-        );
+        let (_, space_left) =
+            chunking::number_of_required_fragments(message.len() + 1, plaintext_per_packet);
 
         message
             .into_iter()
@@ -160,17 +182,20 @@ where
         topology: &NymTopology,
     ) -> Result<(Vec<u8>, Option<SurbEncryptionKey>), PreparationError> {
         if should_attach {
+            // the format does not include the number of hops the surb takes, so the recipient
+            // can only ever assume the default value
             let reply_surb = ReplySurb::construct(
                 &mut self.rng,
                 &self.sender_address,
                 self.average_packet_delay,
                 topology,
+                DEFAULT_NUM_MIX_HOPS,
             )?;
 
             let reply_key = reply_surb.encryption_key();
             // if there's a reply surb, the message takes form of `1 || REPLY_KEY || REPLY_SURB || MSG`
             Ok((
-                std::iter::once(true as u8)
+                std::iter::once(SINGLE_REPLY_SURB_PREFIX)
                     .chain(reply_surb.to_bytes().iter().cloned())
                     .chain(message.into_iter())
                     .collect(),
@@ -179,7 +204,7 @@ where
         } else {
             // but if there's no reply surb, the message takes form of `0 || MSG`
             Ok((
-                std::iter::once(false as u8)
+                std::iter::once(NO_REPLY_SURB_PREFIX)
                     .chain(message.into_iter())
                     .collect(),
                 None,
@@ -187,9 +212,45 @@ where
         }
     }
 
+    /// Attaches the specified number of reply-SURBs to the message alongside the tag the recipient
+    /// should be using for referring to us.
+    /// Results in:
+    /// new_message = prefix || SENDER_TAG || NUM_SURBS || NUM_MIX_HOPS || (REPLY_KEY || REPLY_SURB)* || message
+    fn attach_tagged_reply_surbs(
+        &mut self,
+        prefix: u8,
+        message: Vec<u8>,
+        sender_tag: AnonymousSenderTag,
+        num_reply_surbs: u32,
+        topology: &NymTopology,
+    ) -> Result<(Vec<u8>, Vec<SurbEncryptionKey>), PreparationError> {
+        let mut reply_keys = Vec::with_capacity(num_reply_surbs as usize);
+        let mut reply_surbs_bytes = Vec::new();
+        for _ in 0..num_reply_surbs {
+            let reply_surb = ReplySurb::construct(
+                &mut self.rng,
+                &self.sender_address,
+                self.average_packet_delay,
+                topology,
+                self.num_mix_hops,
+            )?;
+            reply_keys.push(reply_surb.encryption_key().clone());
+            reply_surbs_bytes.extend_from_slice(&reply_surb.to_bytes());
+        }
+
+        let message = std::iter::once(prefix)
+            .chain(sender_tag.to_bytes().iter().copied())
+            .chain(num_reply_surbs.to_be_bytes().iter().copied())
+            .chain(std::iter::once(self.num_mix_hops))
+            .chain(reply_surbs_bytes.into_iter())
+            .chain(message.into_iter())
+            .collect();
+
+        Ok((message, reply_keys))
+    }
+
     /// Splits the message into [`Fragment`] that are going to be put later put into sphinx packets.
-    fn split_message(&mut self, message: Vec<u8>) -> Vec<Fragment> {
-        let plaintext_per_packet = self.available_plaintext_per_packet();
+    fn split_message(&mut self, message: Vec<u8>, plaintext_per_packet: usize) -> Vec<Fragment> {
         chunking::split_into_sets(&mut self.rng, &message, plaintext_per_packet)
             .into_iter()
             .flat_map(|fragment_set| fragment_set.into_iter())
@@ -219,10 +280,36 @@ where
         topology: &NymTopology,
         ack_key: &AckKey,
         packet_recipient: &Recipient,
+    ) -> Result<PreparedFragment, NymTopologyError> {
+        let num_mix_hops = self.num_mix_hops;
+        self.prepare_chunk_for_sending_with_mix_hops(
+            fragment,
+            topology,
+            ack_key,
+            packet_recipient,
+            num_mix_hops,
+        )
+        .await
+    }
+
+    /// Same as [`Self::prepare_chunk_for_sending`], but both the packet and its acknowledgement
+    /// are going to go through the specified number of mix hops rather than the default one.
+    pub async fn prepare_chunk_for_sending_with_mix_hops(
+        &mut self,
+        fragment: Fragment,
+        topology: &NymTopology,
+        ack_key: &AckKey,
+        packet_recipient: &Recipient,
+        num_mix_hops: u8,
     ) -> Result<PreparedFragment, NymTopologyError> {
         // create an ack
         let (ack_delay, surb_ack_bytes) = self
-            .generate_surb_ack(fragment.fragment_identifier(), topology, ack_key)
+            .generate_surb_ack(
+                fragment.fragment_identifier(),
+                topology,
+                ack_key,
+                num_mix_hops,
+            )
             .await?
             .prepare_for_sending();
 
@@ -265,7 +352,7 @@ where
         // generate pseudorandom route for the packet
         let route = topology.random_route_to_gateway(
             &mut self.rng,
-            self.num_mix_hops,
+            num_mix_hops,
             packet_recipient.gateway(),
         )?;
         let destination = packet_recipient.as_sphinx_destination();
@@ -299,6 +386,7 @@ where
         fragment_id: FragmentIdentifier,
         topology: &NymTopology,
         ack_key: &AckKey,
+        num_mix_hops: u8,
     ) -> Result<SurbAck, NymTopologyError> {
         SurbAck::construct(
             &mut self.rng,
@@ -306,6 +394,7 @@ where
             ack_key,
             fragment_id.to_bytes(),
             self.average_ack_delay,
+            num_mix_hops,
             topology,
         )
     }
@@ -322,59 +411,114 @@ where
         let (message, reply_key) =
             self.optionally_attach_reply_surb(message, with_reply_surb, topology)?;
 
-        let message = self.pad_message(message);
+        let plaintext_per_packet = self.available_plaintext_per_packet();
+        let message = self.pad_message(message, plaintext_per_packet);
 
-        Ok((self.split_message(message), reply_key))
+        Ok((self.split_message(message, plaintext_per_packet), reply_key))
     }
 
-    // TODO: perhaps the return type could somehow be combined with [`PreparedFragment`] ?
-    pub async fn prepare_reply_for_use(
+    /// Attaches the specified number of reply-SURBs alongside the tag of this sender and correct
+    /// padding to the underlying message and splits it into [`Fragment`] that can be later packed
+    /// into sphinx packets to be sent through the mix network.
+    /// The recipient is going to store the SURBs under the provided tag and use them for
+    /// (potentially multi-packet) replies.
+    pub fn prepare_and_split_repliable_message(
         &mut self,
         message: Vec<u8>,
+        sender_tag: AnonymousSenderTag,
+        num_reply_surbs: u32,
+        topology: &NymTopology,
+    ) -> Result<(Vec<Fragment>, Vec<SurbEncryptionKey>), PreparationError> {
+        let (message, reply_keys) = self.attach_tagged_reply_surbs(
+            TAGGED_REPLY_SURBS_PREFIX,
+            message,
+            sender_tag,
+            num_reply_surbs,
+            topology,
+        )?;
+
+        let plaintext_per_packet = self.available_plaintext_per_packet();
+        let message = self.pad_message(message, plaintext_per_packet);
+
+        Ok((
+            self.split_message(message, plaintext_per_packet),
+            reply_keys,
+        ))
+    }
+
+    /// Creates a message carrying only additional reply-SURBs for the recipient that has
+    /// previously requested them using the provided tag and splits it into [`Fragment`]
+    /// that can be later packed into sphinx packets to be sent through the mix network.
+    pub fn prepare_and_split_additional_reply_surbs(
+        &mut self,
+        sender_tag: AnonymousSenderTag,
+        num_reply_surbs: u32,
+        topology: &NymTopology,
+    ) -> Result<(Vec<Fragment>, Vec<SurbEncryptionKey>), PreparationError> {
+        let (message, reply_keys) = self.attach_tagged_reply_surbs(
+            ADDITIONAL_REPLY_SURBS_PREFIX,
+            Vec::new(),
+            sender_tag,
+            num_reply_surbs,
+            topology,
+        )?;
+
+        let plaintext_per_packet = self.available_plaintext_per_packet();
+        let message = self.pad_message(message, plaintext_per_packet);
+
+        Ok((
+            self.split_message(message, plaintext_per_packet),
+            reply_keys,
+        ))
+    }
+
+    /// Determines how many reply-SURBs are required to send back a reply with the provided data.
+    pub fn number_of_reply_fragments(&self, reply_len: usize) -> usize {
+        // 1 byte is used for the reply content tag and another one is the minimum padding
+        chunking::number_of_required_fragments(
+            reply_len + 2,
+            self.available_plaintext_per_reply_packet(),
+        )
+        .0
+    }
+
+    /// Pads the reply content and splits it into [`Fragment`]s, each of which is going to be
+    /// sent back using a separate reply-SURB.
+    pub fn prepare_and_split_reply(&mut self, content: ReplyContent) -> Vec<Fragment> {
+        let plaintext_per_packet = self.available_plaintext_per_reply_packet();
+        let content = self.pad_message(content.into_bytes(), plaintext_per_packet);
+
+        self.split_message(content, plaintext_per_packet)
+    }
+
+    /// Puts the reply [`Fragment`] inside a sphinx packet created out of the provided reply-SURB.
+    ///
+    /// The procedure is as follows:
+    /// - compute SURB_ACK (it's never going to be used for retransmission, but must be present
+    ///   so that gateways could not distinguish reply packets from normal messages)
+    /// - compute v_b = AES-128-CTR(reply_key, serialized_fragment)
+    /// - compute sphinx_plaintext = SURB_ACK || H(reply_key) || v_b
+    /// - compute sphinx_packet = Sphinx(reply_surb, sphinx_plaintext)
+    // TODO: perhaps the return type could somehow be combined with [`PreparedFragment`] ?
+    pub async fn prepare_reply_fragment_for_sending(
+        &mut self,
+        fragment: Fragment,
         reply_surb: ReplySurb,
         topology: &NymTopology,
         ack_key: &AckKey,
     ) -> Result<(MixPacket, FragmentIdentifier), PreparationError> {
-        // there's no chunking in reply-surbs so there's a hard limit on message,
-        // we also need to put the key digest into the message (same size as ephemeral key)
-        // and need 1 byte to indicate padding length (this is not the case for 'normal' messages
-        // as there the padding is added for the whole message)
-        // so before doing any processing, let's see if we have enough space for it all
-        let ack_overhead = MAX_NODE_ADDRESS_UNPADDED_LEN + PacketSize::AckPacket.size();
-        if message.len()
-            > self.packet_size.plaintext_size()
-                - ack_overhead
-                - ReplySurbKeyDigestAlgorithm::output_size()
-                - 1
-        {
-            return Err(PreparationError::TooLongReplyMessageError);
-        }
-
         let reply_id = FragmentIdentifier::new_reply(&mut self.rng);
 
         // create an ack
-        // even though it won't be used for retransmission, it must be present so that
-        // gateways could not distinguish reply packets from normal messages due to lack of said acks
         // note: the ack delay is irrelevant since we do not know the delay of actual surb
+        let num_mix_hops = self.num_mix_hops;
         let (_, surb_ack_bytes) = self
-            .generate_surb_ack(reply_id, topology, ack_key)
+            .generate_surb_ack(reply_id, topology, ack_key, num_mix_hops)
             .await?
             .prepare_for_sending();
 
-        let zero_pad_len = self.packet_size.plaintext_size()
-            - message.len()
-            - ack_overhead
-            - ReplySurbKeyDigestAlgorithm::output_size()
-            - 1;
-
-        // create reply message that will reach the recipient:
-        let mut reply_content: Vec<_> = message
-            .into_iter()
-            .chain(std::iter::once(1))
-            .chain(std::iter::repeat(0).take(zero_pad_len))
-            .collect();
-
-        // encrypt the reply message
+        // encrypt the reply fragment
+        let mut reply_content = fragment.into_bytes();
         let zero_iv = stream_cipher::zero_iv::<ReplySurbEncryptionAlgorithm>();
         stream_cipher::encrypt_in_place::<ReplySurbEncryptionAlgorithm>(
             reply_surb.encryption_key().inner(),
@@ -383,7 +527,7 @@ where
         );
 
         // combine it together as follows:
-        // SURB_ACK_FIRST_HOP || SURB_ACK_DATA || KEY_DIGEST || E (REPLY_MESSAGE || 1 || 0*)
+        // SURB_ACK_FIRST_HOP || SURB_ACK_DATA || KEY_DIGEST || E (REPLY_FRAGMENT)
         // (note: surb_ack_bytes contains SURB_ACK_FIRST_HOP || SURB_ACK_DATA )
         let packet_payload: Vec<_> = surb_ack_bytes
             .into_iter()
@@ -393,7 +537,7 @@ where
 
         // finally put it all inside a sphinx packet
         // this can only fail if packet payload has incorrect size, but if it does, it means
-        // there's a bug in the above code
+        // there's a bug in the padding of the reply
         let (packet, first_hop) = reply_surb
             .apply_surb(&packet_payload, Some(self.packet_size))
             .unwrap();
@@ -404,6 +548,32 @@ where
         ))
     }
 
+    /// Prepares a reply that has to fit within a single reply-SURB.
+    pub async fn prepare_reply_for_use(
+        &mut self,
+        message: Vec<u8>,
+        reply_surb: ReplySurb,
+        topology: &NymTopology,
+        ack_key: &AckKey,
+    ) -> Result<(MixPacket, FragmentIdentifier), PreparationError> {
+        // there's only a single reply-SURB available so there's a hard limit on message,
+        // so before doing any processing, let's see if we have enough space for it
+        if message.len() > ReplySurb::max_msg_len(self.packet_size) {
+            return Err(PreparationError::TooLongReplyMessageError);
+        }
+
+        let mut fragments = self.prepare_and_split_reply(ReplyContent::Data(message));
+        debug_assert_eq!(fragments.len(), 1);
+
+        self.prepare_reply_fragment_for_sending(
+            fragments.pop().unwrap(),
+            reply_surb,
+            topology,
+            ack_key,
+        )
+        .await
+    }
+
     #[allow(dead_code)]
     #[cfg(test)]
     pub(crate) fn test_fixture() -> MessagePreparer<rand::rngs::OsRng> {
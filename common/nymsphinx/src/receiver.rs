@@ -1,13 +1,26 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::preparer::{
+    ADDITIONAL_REPLY_SURBS_PREFIX, NO_REPLY_SURB_PREFIX, SINGLE_REPLY_SURB_PREFIX,
+    TAGGED_REPLY_SURBS_PREFIX,
+};
 use crypto::asymmetric::encryption;
 use crypto::shared_key::recompute_shared_key;
 use crypto::symmetric::stream_cipher;
 use nymsphinx_anonymous_replies::reply_surb::{ReplySurb, ReplySurbError};
+use nymsphinx_anonymous_replies::requests::{
+    AnonymousSenderTag, InvalidReplyContentError, ReplyContent, SENDER_TAG_SIZE,
+};
+use nymsphinx_anonymous_replies::SurbEncryptionKey;
 use nymsphinx_chunking::fragment::Fragment;
 use nymsphinx_chunking::reconstruction::MessageReconstructor;
-use nymsphinx_params::{PacketEncryptionAlgorithm, PacketHkdfAlgorithm, DEFAULT_NUM_MIX_HOPS};
+use nymsphinx_params::{
+    PacketEncryptionAlgorithm, PacketHkdfAlgorithm, ReplySurbEncryptionAlgorithm,
+    DEFAULT_NUM_MIX_HOPS, MAX_NUM_MIX_HOPS,
+};
+use std::convert::TryInto;
+use std::mem::size_of;
 
 // TODO: should this live in this file?
 #[derive(Debug)]
@@ -17,17 +30,40 @@ pub struct ReconstructedMessage {
 
     /// Optional ReplySURB to allow for an anonymous reply to the sender.
     pub reply_surb: Option<ReplySurb>,
+
+    /// Optional tag of the anonymous sender that has attached multiple reply SURBs to the message.
+    /// It can be used to reply with arbitrary long messages.
+    pub sender_tag: Option<AnonymousSenderTag>,
+}
+
+/// Message fully recovered out of its received fragments.
+#[derive(Debug)]
+pub enum RecoveredMessage {
+    /// Message meant for the application alongside all reply SURBs an anonymous sender
+    /// might have attached to it under its tag.
+    Message {
+        message: ReconstructedMessage,
+        reply_surbs: Vec<ReplySurb>,
+    },
+
+    /// Additional reply SURBs sent by an anonymous sender in response to our request.
+    AdditionalReplySurbs {
+        sender_tag: AnonymousSenderTag,
+        reply_surbs: Vec<ReplySurb>,
+    },
 }
 
 #[derive(Debug)]
 pub enum MessageRecoveryError {
     InvalidSurbPrefixError,
     MalformedSurbError(ReplySurbError),
+    MalformedReplyContent(InvalidReplyContentError),
     InvalidRemoteEphemeralKey(encryption::KeyRecoveryError),
     MalformedFragmentError,
     InvalidMessagePaddingError,
     MalformedReconstructedMessage(Vec<i32>),
     TooShortMessageError,
+    InvalidNumberOfMixHops(u8),
 }
 
 impl From<ReplySurbError> for MessageRecoveryError {
@@ -36,6 +72,12 @@ impl From<ReplySurbError> for MessageRecoveryError {
     }
 }
 
+impl From<InvalidReplyContentError> for MessageRecoveryError {
+    fn from(err: InvalidReplyContentError) -> Self {
+        MessageRecoveryError::MalformedReplyContent(err)
+    }
+}
+
 impl From<encryption::KeyRecoveryError> for MessageRecoveryError {
     fn from(err: encryption::KeyRecoveryError) -> Self {
         MessageRecoveryError::InvalidRemoteEphemeralKey(err)
@@ -47,6 +89,10 @@ pub struct MessageReceiver {
     /// returning original messages that they encapsulate.
     reconstructor: MessageReconstructor,
 
+    /// Separate reconstructor for the [`Fragment`]s received via our reply SURBs, so that their
+    /// set ids could never clash with the ones of the regular messages.
+    reply_reconstructor: MessageReconstructor,
+
     /// Number of mix hops each packet ('real' message, ack, reply) is expected to take.
     /// Note that it does not include gateway hops.
     num_mix_hops: u8,
@@ -70,11 +116,11 @@ impl MessageReceiver {
         message: &mut Vec<u8>,
     ) -> Result<Option<ReplySurb>, MessageRecoveryError> {
         match message[0] {
-            n if n == false as u8 => {
+            NO_REPLY_SURB_PREFIX => {
                 message.remove(0);
                 Ok(None)
             }
-            n if n == true as u8 => {
+            SINGLE_REPLY_SURB_PREFIX => {
                 let surb_len: usize = ReplySurb::serialized_len(self.num_mix_hops);
                 // note the extra +1 (due to 0/1 message prefix)
                 let surb_bytes = &message[1..1 + surb_len];
@@ -87,6 +133,49 @@ impl MessageReceiver {
         }
     }
 
+    /// Parses the message to strip and recover the tag of its sender alongside all reply SURBs
+    /// it has attached.
+    /// The message is expected to take form of
+    /// `prefix || SENDER_TAG || NUM_SURBS || NUM_MIX_HOPS || (REPLY_KEY || REPLY_SURB)* || MSG`
+    fn recover_tagged_reply_surbs_from_message(
+        &self,
+        message: &mut Vec<u8>,
+    ) -> Result<(AnonymousSenderTag, Vec<ReplySurb>), MessageRecoveryError> {
+        let num_surbs_end = 1 + SENDER_TAG_SIZE + size_of::<u32>();
+        let header_len = num_surbs_end + 1;
+        if message.len() < header_len {
+            return Err(MessageRecoveryError::TooShortMessageError);
+        }
+
+        let sender_tag = AnonymousSenderTag::try_from_slice(&message[1..1 + SENDER_TAG_SIZE])?;
+        let num_surbs = u32::from_be_bytes(
+            message[1 + SENDER_TAG_SIZE..num_surbs_end]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+
+        // the sender decides on the number of hops its surbs are going to take
+        let num_mix_hops = message[num_surbs_end];
+        if num_mix_hops == 0 || num_mix_hops > MAX_NUM_MIX_HOPS {
+            return Err(MessageRecoveryError::InvalidNumberOfMixHops(num_mix_hops));
+        }
+
+        let surb_len = ReplySurb::serialized_len(num_mix_hops);
+        // make sure the sender is not lying about the number of attached surbs
+        // (and that we won't overflow when checking it)
+        if num_surbs > (message.len() - header_len) / surb_len {
+            return Err(MessageRecoveryError::TooShortMessageError);
+        }
+
+        let reply_surbs = message[header_len..header_len + num_surbs * surb_len]
+            .chunks_exact(surb_len)
+            .map(ReplySurb::from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        *message = message.drain(header_len + num_surbs * surb_len..).collect();
+        Ok((sender_tag, reply_surbs))
+    }
+
     /// Given raw fragment data, recovers the remote ephemeral key, recomputes shared secret,
     /// uses it to decrypt fragment data
     pub fn recover_plaintext(
@@ -115,6 +204,21 @@ impl MessageReceiver {
         ))
     }
 
+    /// Given the raw data received via one of our reply SURBs, alongside the encryption key
+    /// of the said SURB, decrypts the data of the underlying reply [`Fragment`].
+    pub fn recover_reply_plaintext(
+        &self,
+        reply_key: &SurbEncryptionKey,
+        reply_ciphertext: &[u8],
+    ) -> Vec<u8> {
+        let zero_iv = stream_cipher::zero_iv::<ReplySurbEncryptionAlgorithm>();
+        stream_cipher::decrypt::<ReplySurbEncryptionAlgorithm>(
+            reply_key.inner(),
+            &zero_iv,
+            reply_ciphertext,
+        )
+    }
+
     /// Given fragment data recovers [`Fragment`] itself.
     pub fn recover_fragment(&self, frag_data: &[u8]) -> Result<Fragment, MessageRecoveryError> {
         Fragment::try_from_bytes(frag_data)
@@ -139,35 +243,85 @@ impl MessageReceiver {
     /// and returned alongside all (if applicable) set ids used in the message.
     ///
     /// # Returns:
-    /// - The reconstructed message alongside optional reply SURB,
+    /// - The recovered message alongside all attached reply SURBs,
     /// - List of ids of all the [`Set`]s used during reconstruction to detect stale retransmissions.
     pub fn insert_new_fragment(
         &mut self,
         fragment: Fragment,
-    ) -> Result<Option<(ReconstructedMessage, Vec<i32>)>, MessageRecoveryError> {
+    ) -> Result<Option<(RecoveredMessage, Vec<i32>)>, MessageRecoveryError> {
         if let Some((mut message, used_sets)) = self.reconstructor.insert_new_fragment(fragment) {
-            // Split message into plaintext and reply-SURB
-            let reply_surb = match self.recover_reply_surb_from_message(&mut message) {
-                Ok(reply_surb) => reply_surb,
-                Err(_) => {
-                    return Err(MessageRecoveryError::MalformedReconstructedMessage(
-                        used_sets,
-                    ));
+            let malformed =
+                |_| MessageRecoveryError::MalformedReconstructedMessage(used_sets.clone());
+
+            // Split message into plaintext and reply-SURB(s)
+            let recovered = match message.first() {
+                Some(&TAGGED_REPLY_SURBS_PREFIX) | Some(&ADDITIONAL_REPLY_SURBS_PREFIX) => {
+                    let additional_surbs = message[0] == ADDITIONAL_REPLY_SURBS_PREFIX;
+                    let (sender_tag, reply_surbs) = self
+                        .recover_tagged_reply_surbs_from_message(&mut message)
+                        .map_err(malformed)?;
+
+                    if additional_surbs {
+                        RecoveredMessage::AdditionalReplySurbs {
+                            sender_tag,
+                            reply_surbs,
+                        }
+                    } else {
+                        // Finally, remove the zero padding from the message
+                        Self::remove_padding(&mut message).map_err(malformed)?;
+                        RecoveredMessage::Message {
+                            message: ReconstructedMessage {
+                                message,
+                                reply_surb: None,
+                                sender_tag: Some(sender_tag),
+                            },
+                            reply_surbs,
+                        }
+                    }
+                }
+                _ => {
+                    let reply_surb = self
+                        .recover_reply_surb_from_message(&mut message)
+                        .map_err(malformed)?;
+
+                    // Finally, remove the zero padding from the message
+                    Self::remove_padding(&mut message).map_err(malformed)?;
+                    RecoveredMessage::Message {
+                        message: ReconstructedMessage {
+                            message,
+                            reply_surb,
+                            sender_tag: None,
+                        },
+                        reply_surbs: Vec::new(),
+                    }
                 }
             };
 
-            // Finally, remove the zero padding from the message
-            Self::remove_padding(&mut message).map_err(|_| {
-                MessageRecoveryError::MalformedReconstructedMessage(used_sets.clone())
-            })?;
-
-            Ok(Some((
-                ReconstructedMessage {
-                    message,
-                    reply_surb,
-                },
-                used_sets,
-            )))
+            Ok(Some((recovered, used_sets)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Inserts given reply [`Fragment`], i.e. one received via one of our reply SURBs,
+    /// into the reply reconstructor.
+    /// If it was last remaining [`Fragment`] for the original reply, its content is recovered
+    /// and returned alongside all set ids used in the reply.
+    pub fn insert_new_reply_fragment(
+        &mut self,
+        fragment: Fragment,
+    ) -> Result<Option<(ReplyContent, Vec<i32>)>, MessageRecoveryError> {
+        if let Some((mut content, used_sets)) =
+            self.reply_reconstructor.insert_new_fragment(fragment)
+        {
+            let content = Self::remove_padding(&mut content)
+                .ok()
+                .and_then(|_| ReplyContent::try_from_bytes(content).ok())
+                .ok_or_else(|| {
+                    MessageRecoveryError::MalformedReconstructedMessage(used_sets.clone())
+                })?;
+
+            Ok(Some((content, used_sets)))
         } else {
             Ok(None)
         }
@@ -178,6 +332,7 @@ impl Default for MessageReceiver {
     fn default() -> Self {
         MessageReceiver {
             reconstructor: Default::default(),
+            reply_reconstructor: Default::default(),
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
         }
     }
@@ -186,9 +341,14 @@ impl Default for MessageReceiver {
 #[cfg(test)]
 mod message_receiver {
     use super::*;
+    use crate::preparer::MessagePreparer;
     use crypto::asymmetric::identity;
+    use crypto::Digest;
     use mixnet_contract_common::Layer;
     use nymsphinx_addressing::clients::Recipient;
+    use nymsphinx_addressing::nodes::MAX_NODE_ADDRESS_UNPADDED_LEN;
+    use nymsphinx_params::packet_sizes::PacketSize;
+    use nymsphinx_params::ReplySurbKeyDigestAlgorithm;
     use rand::rngs::OsRng;
     use std::collections::HashMap;
     use std::time::Duration;
@@ -216,6 +376,7 @@ mod message_receiver {
                     "B3GzG62aXAZNg14RoMCp3BhELNBrySLr2JqrwyfYFzRc",
                 )
                 .unwrap(),
+                sphinx_key_rotation: None,
                 layer: Layer::One,
                 version: "0.8.0-dev".to_string(),
             }],
@@ -237,6 +398,7 @@ mod message_receiver {
                     "5Z1VqYwM2xeKxd8H7fJpGWasNiDFijYBAee7MErkZ5QT",
                 )
                 .unwrap(),
+                sphinx_key_rotation: None,
                 layer: Layer::Two,
                 version: "0.8.0-dev".to_string(),
             }],
@@ -258,6 +420,7 @@ mod message_receiver {
                     "9EyjhCggr2QEA2nakR88YHmXgpy92DWxoe2draDRkYof",
                 )
                 .unwrap(),
+                sphinx_key_rotation: None,
                 layer: Layer::Three,
                 version: "0.8.0-dev".to_string(),
             }],
@@ -296,8 +459,14 @@ mod message_receiver {
         let average_delay = Duration::from_millis(500);
         let topology = topology_fixture();
 
-        let reply_surb =
-            ReplySurb::construct(&mut OsRng, &dummy_recipient, average_delay, &topology).unwrap();
+        let reply_surb = ReplySurb::construct(
+            &mut OsRng,
+            &dummy_recipient,
+            average_delay,
+            &topology,
+            DEFAULT_NUM_MIX_HOPS,
+        )
+        .unwrap();
 
         let reply_surb_bytes = reply_surb.to_bytes();
 
@@ -322,4 +491,123 @@ mod message_receiver {
         assert_eq!(received_with_surb, message);
         assert_eq!(reply_surb_bytes, reply_surb.unwrap().to_bytes());
     }
+
+    fn reconstruct_all<F, T>(fragments: Vec<Fragment>, mut insert: F) -> Option<T>
+    where
+        F: FnMut(Fragment) -> Result<Option<(T, Vec<i32>)>, MessageRecoveryError>,
+    {
+        let mut reconstructed = None;
+        for fragment in fragments {
+            assert!(reconstructed.is_none());
+            reconstructed = insert(fragment).unwrap().map(|(recovered, _)| recovered);
+        }
+        reconstructed
+    }
+
+    #[test]
+    fn correctly_recovers_tagged_reply_surbs() {
+        let mut message_receiver: MessageReceiver = Default::default();
+        // the surbs take a non-default number of hops that the receiver doesn't know about
+        let mut message_preparer = MessagePreparer::test_fixture().with_mix_hops(2);
+        let dummy_recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@FioFa8nMmPpQnYi7JyojoTuwGLeyNS8BF4ChPr29zUML").unwrap();
+        message_preparer.set_sender_address(dummy_recipient);
+        let topology = topology_fixture();
+
+        let message = vec![42; 5000];
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        let (fragments, reply_keys) = message_preparer
+            .prepare_and_split_repliable_message(message.clone(), sender_tag, 5, &topology)
+            .unwrap();
+        assert_eq!(reply_keys.len(), 5);
+
+        match reconstruct_all(fragments, |fragment| {
+            message_receiver.insert_new_fragment(fragment)
+        })
+        .unwrap()
+        {
+            RecoveredMessage::Message {
+                message: reconstructed,
+                reply_surbs,
+            } => {
+                assert_eq!(reconstructed.message, message);
+                assert!(reconstructed.reply_surb.is_none());
+                assert_eq!(reconstructed.sender_tag, Some(sender_tag));
+                assert_eq!(reply_surbs.len(), 5);
+                for (reply_surb, reply_key) in reply_surbs.iter().zip(reply_keys.iter()) {
+                    assert_eq!(reply_surb.encryption_key().to_bytes(), reply_key.to_bytes());
+                }
+            }
+            _ => panic!("expected regular message"),
+        }
+
+        let (fragments, reply_keys) = message_preparer
+            .prepare_and_split_additional_reply_surbs(sender_tag, 3, &topology)
+            .unwrap();
+        assert_eq!(reply_keys.len(), 3);
+
+        match reconstruct_all(fragments, |fragment| {
+            message_receiver.insert_new_fragment(fragment)
+        })
+        .unwrap()
+        {
+            RecoveredMessage::AdditionalReplySurbs {
+                sender_tag: recovered_tag,
+                reply_surbs,
+            } => {
+                assert_eq!(recovered_tag, sender_tag);
+                assert_eq!(reply_surbs.len(), 3);
+            }
+            _ => panic!("expected additional reply surbs"),
+        }
+    }
+
+    #[test]
+    fn message_lying_about_number_of_reply_surbs_is_rejected() {
+        let message_receiver: MessageReceiver = Default::default();
+        let sender_tag = AnonymousSenderTag::new_random(&mut OsRng);
+
+        let mut message: Vec<_> = std::iter::once(TAGGED_REPLY_SURBS_PREFIX)
+            .chain(sender_tag.to_bytes().iter().copied())
+            .chain(u32::MAX.to_be_bytes().iter().copied())
+            .chain(std::iter::once(DEFAULT_NUM_MIX_HOPS))
+            .chain(std::iter::repeat(42).take(100))
+            .collect();
+        assert!(message_receiver
+            .recover_tagged_reply_surbs_from_message(&mut message)
+            .is_err());
+    }
+
+    #[test]
+    fn correctly_reconstructs_multi_fragment_reply() {
+        let mut message_receiver: MessageReceiver = Default::default();
+        let mut message_preparer = MessagePreparer::test_fixture();
+
+        let expected_fragment_len = PacketSize::default().plaintext_size()
+            - MAX_NODE_ADDRESS_UNPADDED_LEN
+            - PacketSize::AckPacket.size()
+            - ReplySurbKeyDigestAlgorithm::output_size();
+
+        let reply = vec![42; 10000];
+        let fragments = message_preparer.prepare_and_split_reply(ReplyContent::Data(reply.clone()));
+        assert!(fragments.len() > 1);
+        for fragment in &fragments {
+            // each fragment has to fill the reply packet exactly
+            assert_eq!(fragment.clone().into_bytes().len(), expected_fragment_len);
+        }
+
+        let recovered = reconstruct_all(fragments, |fragment| {
+            message_receiver.insert_new_reply_fragment(fragment)
+        });
+        assert_eq!(recovered.unwrap(), ReplyContent::Data(reply));
+
+        // the largest reply that can be sent using a single reply SURB must fit in a single fragment
+        let reply = vec![42; ReplySurb::max_msg_len(Default::default())];
+        let fragments = message_preparer.prepare_and_split_reply(ReplyContent::Data(reply));
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(
+            fragments[0].clone().into_bytes().len(),
+            expected_fragment_len
+        );
+    }
 }
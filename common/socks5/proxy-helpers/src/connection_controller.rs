@@ -33,6 +33,10 @@ pub enum ControllerCommand {
     Insert(ConnectionId, ConnectionSender),
     Remove(ConnectionId),
     Send(ConnectionId, Vec<u8>, bool),
+
+    /// Unlike `Send`, datagrams are not subject to any ordering and are forwarded to the
+    /// connection as soon as they are received.
+    SendDatagram(ConnectionId, Vec<u8>),
 }
 
 struct ActiveConnection {
@@ -169,6 +173,29 @@ impl Controller {
         }
     }
 
+    fn send_datagram_to_connection(&mut self, conn_id: ConnectionId, payload: Vec<u8>) {
+        if let Some(active_connection) = self.active_connections.get_mut(&conn_id) {
+            if let Err(err) = active_connection
+                .connection_sender
+                .as_mut()
+                .unwrap()
+                .unbounded_send(ConnectionMessage {
+                    payload,
+                    socket_closed: false,
+                })
+            {
+                debug!("Failed to forward datagram to {} - {:?}", conn_id, err);
+            }
+        } else {
+            // there's no point in buffering datagrams - the delivery is best-effort anyway
+            debug!(
+                "Received a datagram for unknown association {} - dropping it ({} bytes)",
+                conn_id,
+                payload.len()
+            );
+        }
+    }
+
     pub async fn run(&mut self) {
         while let Some(command) = self.receiver.next().await {
             match command {
@@ -179,6 +206,9 @@ impl Controller {
                     self.insert_connection(conn_id, sender)
                 }
                 ControllerCommand::Remove(conn_id) => self.remove_connection(conn_id),
+                ControllerCommand::SendDatagram(conn_id, data) => {
+                    self.send_datagram_to_connection(conn_id, data)
+                }
             }
         }
     }
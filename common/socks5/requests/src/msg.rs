@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::request::{Request, RequestError};
-use crate::response::{Response, ResponseError};
+use crate::response::{ConnectResponse, DatagramResponse, Response, ResponseError};
 
 #[derive(Debug)]
 pub enum MessageError {
@@ -26,19 +26,26 @@ impl std::fmt::Display for MessageError {
 pub enum Message {
     Request(Request),
     Response(Response),
+    DatagramResponse(DatagramResponse),
+    ConnectResponse(ConnectResponse),
 }
 
 impl Message {
     const REQUEST_FLAG: u8 = 0;
     const RESPONSE_FLAG: u8 = 1;
+    const DATAGRAM_RESPONSE_FLAG: u8 = 2;
+    const CONNECT_RESPONSE_FLAG: u8 = 3;
 
     pub fn conn_id(&self) -> u64 {
         match self {
             Message::Request(req) => match req {
                 Request::Connect(c) => c.conn_id,
                 Request::Send(conn_id, _, _) => *conn_id,
+                Request::Datagram(d) => d.conn_id,
             },
             Message::Response(resp) => resp.connection_id,
+            Message::DatagramResponse(resp) => resp.connection_id,
+            Message::ConnectResponse(resp) => resp.connection_id,
         }
     }
 
@@ -47,8 +54,11 @@ impl Message {
             Message::Request(req) => match req {
                 Request::Connect(_) => 0,
                 Request::Send(_, data, _) => data.len(),
+                Request::Datagram(d) => d.data.len(),
             },
             Message::Response(resp) => resp.data.len(),
+            Message::DatagramResponse(resp) => resp.data.len(),
+            Message::ConnectResponse(_) => 0,
         }
     }
 
@@ -65,6 +75,14 @@ impl Message {
             Response::try_from_bytes(&b[1..])
                 .map(Message::Response)
                 .map_err(MessageError::Response)
+        } else if b[0] == Self::DATAGRAM_RESPONSE_FLAG {
+            DatagramResponse::try_from_bytes(&b[1..])
+                .map(Message::DatagramResponse)
+                .map_err(MessageError::Response)
+        } else if b[0] == Self::CONNECT_RESPONSE_FLAG {
+            ConnectResponse::try_from_bytes(&b[1..])
+                .map(Message::ConnectResponse)
+                .map_err(MessageError::Response)
         } else {
             Err(MessageError::UnknownMessageType)
         }
@@ -78,6 +96,12 @@ impl Message {
             Self::Response(r) => std::iter::once(Self::RESPONSE_FLAG)
                 .chain(r.into_bytes().iter().cloned())
                 .collect(),
+            Self::DatagramResponse(r) => std::iter::once(Self::DATAGRAM_RESPONSE_FLAG)
+                .chain(r.into_bytes().iter().cloned())
+                .collect(),
+            Self::ConnectResponse(r) => std::iter::once(Self::CONNECT_RESPONSE_FLAG)
+                .chain(r.into_bytes().iter().cloned())
+                .collect(),
         }
     }
 }
@@ -10,6 +10,9 @@ pub type RemoteAddress = String;
 pub enum RequestFlag {
     Connect = 0,
     Send = 1,
+    Datagram = 2,
+    AnonymousConnect = 3,
+    AnonymousDatagram = 4,
 }
 
 #[derive(Debug)]
@@ -58,6 +61,9 @@ impl TryFrom<u8> for RequestFlag {
         match value {
             _ if value == (RequestFlag::Connect as u8) => Ok(Self::Connect),
             _ if value == (RequestFlag::Send as u8) => Ok(Self::Send),
+            _ if value == (RequestFlag::Datagram as u8) => Ok(Self::Datagram),
+            _ if value == (RequestFlag::AnonymousConnect as u8) => Ok(Self::AnonymousConnect),
+            _ if value == (RequestFlag::AnonymousDatagram as u8) => Ok(Self::AnonymousDatagram),
             _ => Err(RequestError::UnknownRequestFlag),
         }
     }
@@ -67,7 +73,19 @@ impl TryFrom<u8> for RequestFlag {
 pub struct ConnectRequest {
     pub conn_id: ConnectionId,
     pub remote_addr: RemoteAddress,
-    pub return_address: Recipient,
+    /// Address to which all responses should be sent. It is `None` if the client chose not to
+    /// reveal it and attached reply SURBs to the request instead.
+    pub return_address: Option<Recipient>,
+}
+
+#[derive(Debug)]
+pub struct DatagramRequest {
+    pub conn_id: ConnectionId,
+    pub remote_addr: RemoteAddress,
+    /// Address to which all datagrams should be sent back. It is `None` if the client chose not to
+    /// reveal it and attached reply SURBs to the request instead.
+    pub return_address: Option<Recipient>,
+    pub data: Vec<u8>,
 }
 
 /// A request from a SOCKS5 client that a Nym Socks5 service provider should
@@ -77,10 +95,17 @@ pub enum Request {
     /// Start a new TCP connection to the specified `RemoteAddress` and send
     /// the request data up the connection.
     /// All responses produced on this `ConnectionId` should come back to the specified `Recipient`
+    /// or, if it's not specified, using the reply SURBs attached to the request.
     Connect(Box<ConnectRequest>),
 
     /// Re-use an existing TCP connection, sending more request data up it.
     Send(ConnectionId, Vec<u8>, bool),
+
+    /// Send a single UDP datagram to the specified `RemoteAddress`.
+    /// All datagrams sent back on this `ConnectionId` (i.e. UDP association) should come back
+    /// to the specified `Recipient` or, if it's not specified, using the reply SURBs attached
+    /// to the request.
+    Datagram(Box<DatagramRequest>),
 }
 
 impl Request {
@@ -93,7 +118,16 @@ impl Request {
         Request::Connect(Box::new(ConnectRequest {
             conn_id,
             remote_addr,
-            return_address,
+            return_address: Some(return_address),
+        }))
+    }
+
+    /// Construct a new Request::Connect instance that does not reveal the address of the client
+    pub fn new_anonymous_connect(conn_id: ConnectionId, remote_addr: RemoteAddress) -> Request {
+        Request::Connect(Box::new(ConnectRequest {
+            conn_id,
+            remote_addr,
+            return_address: None,
         }))
     }
 
@@ -102,6 +136,74 @@ impl Request {
         Request::Send(conn_id, data, local_closed)
     }
 
+    /// Construct a new Request::Datagram instance
+    pub fn new_datagram(
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        return_address: Recipient,
+        data: Vec<u8>,
+    ) -> Request {
+        Request::Datagram(Box::new(DatagramRequest {
+            conn_id,
+            remote_addr,
+            return_address: Some(return_address),
+            data,
+        }))
+    }
+
+    /// Construct a new Request::Datagram instance that does not reveal the address of the client
+    pub fn new_anonymous_datagram(
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+    ) -> Request {
+        Request::Datagram(Box::new(DatagramRequest {
+            conn_id,
+            remote_addr,
+            return_address: None,
+            data,
+        }))
+    }
+
+    // recovers the `REMOTE_LEN || REMOTE` part shared by `Connect` and `Datagram`
+    // requests, returning the remaining bytes
+    fn parse_remote_address(b: &[u8]) -> Result<(RemoteAddress, &[u8]), RequestError> {
+        // we need to be able to read at least 2 bytes that specify address length
+        if b.len() < 2 {
+            return Err(RequestError::AddressLengthTooShort);
+        }
+
+        let address_length = u16::from_be_bytes([b[0], b[1]]) as usize;
+
+        if b.len() < 2 + address_length {
+            return Err(RequestError::AddressTooShort);
+        }
+
+        let address_start = 2;
+        let address_end = address_start + address_length;
+        let address_bytes = &b[address_start..address_end];
+        let remote_address = String::from_utf8_lossy(address_bytes).to_string();
+
+        Ok((remote_address, &b[address_end..]))
+    }
+
+    // recovers the `RETURN` part of non-anonymous `Connect` and `Datagram` requests,
+    // returning the remaining bytes
+    fn parse_return_address(
+        recipient_data_bytes: &[u8],
+    ) -> Result<(Recipient, &[u8]), RequestError> {
+        if recipient_data_bytes.len() < Recipient::LEN {
+            return Err(RequestError::ReturnAddressTooShort);
+        }
+
+        let mut return_bytes = [0u8; Recipient::LEN];
+        return_bytes.copy_from_slice(&recipient_data_bytes[..Recipient::LEN]);
+        let return_address = Recipient::try_from_bytes(return_bytes)
+            .map_err(RequestError::MalformedReturnAddress)?;
+
+        Ok((return_address, &recipient_data_bytes[Recipient::LEN..]))
+    }
+
     /// Deserialize the request type, connection id, destination address and port,
     /// and the request body from bytes.
     ///
@@ -115,6 +217,7 @@ impl Request {
     /// The request_flag tells us whether this is a new connection request (`new_connect`),
     /// an already-established connection we should send up (`new_send`), or
     /// a request to close an established connection (`new_close`).
+    /// Anonymous variants of the connection and datagram requests do not include the return address.
     pub fn try_from_bytes(b: &[u8]) -> Result<Request, RequestError> {
         // each request needs to at least contain flag and ConnectionId
         if b.is_empty() {
@@ -127,37 +230,13 @@ impl Request {
         let connection_id = u64::from_be_bytes([b[1], b[2], b[3], b[4], b[5], b[6], b[7], b[8]]);
         match RequestFlag::try_from(b[0])? {
             RequestFlag::Connect => {
-                let connect_request_bytes = &b[9..];
-
-                // we need to be able to read at least 2 bytes that specify address length
-                if connect_request_bytes.len() < 2 {
-                    return Err(RequestError::AddressLengthTooShort);
-                }
+                let (remote_address, return_bytes) = Self::parse_remote_address(&b[9..])?;
 
-                let address_length =
-                    u16::from_be_bytes([connect_request_bytes[0], connect_request_bytes[1]])
-                        as usize;
-
-                if connect_request_bytes.len() < 2 + address_length {
-                    return Err(RequestError::AddressTooShort);
-                }
-
-                let address_start = 2;
-                let address_end = address_start + address_length;
-                let address_bytes = &connect_request_bytes[address_start..address_end];
-                let remote_address = String::from_utf8_lossy(address_bytes).to_string();
-
-                // just a temporary reference to mid-slice for ease of use
-                let recipient_data_bytes = &connect_request_bytes[address_end..];
-
-                if recipient_data_bytes.len() != Recipient::LEN {
+                // connect requests carry nothing but the return address after the remote one
+                if return_bytes.len() != Recipient::LEN {
                     return Err(RequestError::ReturnAddressTooShort);
                 }
-
-                let mut return_bytes = [0u8; Recipient::LEN];
-                return_bytes.copy_from_slice(&recipient_data_bytes[..Recipient::LEN]);
-                let return_address = Recipient::try_from_bytes(return_bytes)
-                    .map_err(RequestError::MalformedReturnAddress)?;
+                let (return_address, _) = Self::parse_return_address(return_bytes)?;
 
                 Ok(Request::new_connect(
                     connection_id,
@@ -165,12 +244,40 @@ impl Request {
                     return_address,
                 ))
             }
+            RequestFlag::AnonymousConnect => {
+                let (remote_address, _) = Self::parse_remote_address(&b[9..])?;
+
+                Ok(Request::new_anonymous_connect(
+                    connection_id,
+                    remote_address,
+                ))
+            }
             RequestFlag::Send => {
                 let local_closed = b[9] != 0;
                 let data = b[10..].as_ref().to_vec();
 
                 Ok(Request::Send(connection_id, data, local_closed))
             }
+            RequestFlag::Datagram => {
+                let (remote_address, return_bytes) = Self::parse_remote_address(&b[9..])?;
+                let (return_address, data) = Self::parse_return_address(return_bytes)?;
+
+                Ok(Request::new_datagram(
+                    connection_id,
+                    remote_address,
+                    return_address,
+                    data.to_vec(),
+                ))
+            }
+            RequestFlag::AnonymousDatagram => {
+                let (remote_address, data) = Self::parse_remote_address(&b[9..])?;
+
+                Ok(Request::new_anonymous_datagram(
+                    connection_id,
+                    remote_address,
+                    data.to_vec(),
+                ))
+            }
         }
     }
 
@@ -180,15 +287,20 @@ impl Request {
     pub fn into_bytes(self) -> Vec<u8> {
         match self {
             // connect is: CONN_FLAG || CONN_ID || REMOTE_LEN || REMOTE || RETURN
+            // (or ANONYMOUS_CONN_FLAG || CONN_ID || REMOTE_LEN || REMOTE)
             Request::Connect(req) => {
                 let remote_address_bytes = req.remote_addr.into_bytes();
                 let remote_address_bytes_len = remote_address_bytes.len() as u16;
+                let flag = match req.return_address {
+                    Some(_) => RequestFlag::Connect,
+                    None => RequestFlag::AnonymousConnect,
+                };
 
-                std::iter::once(RequestFlag::Connect as u8)
+                std::iter::once(flag as u8)
                     .chain(req.conn_id.to_be_bytes().iter().cloned())
                     .chain(remote_address_bytes_len.to_be_bytes().iter().cloned())
                     .chain(remote_address_bytes.into_iter())
-                    .chain(req.return_address.to_bytes().iter().cloned())
+                    .chain(req.return_address.iter().flat_map(|r| r.to_bytes()))
                     .collect()
             }
             Request::Send(conn_id, data, local_closed) => std::iter::once(RequestFlag::Send as u8)
@@ -196,6 +308,24 @@ impl Request {
                 .chain(std::iter::once(local_closed as u8))
                 .chain(data.into_iter())
                 .collect(),
+            // datagram is: DATAGRAM_FLAG || CONN_ID || REMOTE_LEN || REMOTE || RETURN || DATA
+            // (or ANONYMOUS_DATAGRAM_FLAG || CONN_ID || REMOTE_LEN || REMOTE || DATA)
+            Request::Datagram(req) => {
+                let remote_address_bytes = req.remote_addr.into_bytes();
+                let remote_address_bytes_len = remote_address_bytes.len() as u16;
+                let flag = match req.return_address {
+                    Some(_) => RequestFlag::Datagram,
+                    None => RequestFlag::AnonymousDatagram,
+                };
+
+                std::iter::once(flag as u8)
+                    .chain(req.conn_id.to_be_bytes().iter().cloned())
+                    .chain(remote_address_bytes_len.to_be_bytes().iter().cloned())
+                    .chain(remote_address_bytes.into_iter())
+                    .chain(req.return_address.iter().flat_map(|r| r.to_bytes()))
+                    .chain(req.data.into_iter())
+                    .collect()
+            }
         }
     }
 }
@@ -295,6 +425,46 @@ mod request_deserialization_tests {
             }
         }
 
+        #[test]
+        fn returns_error_for_when_return_address_is_followed_by_more_data() {
+            // this one has "foo.com" remote address and correct 8 bytes of connection_id
+            let request_bytes_prefix = [
+                RequestFlag::Connect as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                0,
+                7,
+                102,
+                111,
+                111,
+                46,
+                99,
+                111,
+                109,
+            ];
+
+            let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+            let recipient_bytes = recipient.to_bytes();
+
+            let request_bytes: Vec<_> = request_bytes_prefix
+                .iter()
+                .cloned()
+                .chain(recipient_bytes.iter().cloned())
+                .chain([1, 2, 3])
+                .collect();
+
+            match Request::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestError::ReturnAddressTooShort => {}
+                _ => unreachable!(),
+            }
+        }
+
         #[test]
         fn returns_error_for_when_return_address_is_malformed() {
             // this one has "foo.com" remote address and correct 8 bytes of connection_id
@@ -376,7 +546,7 @@ mod request_deserialization_tests {
                     assert_eq!("foo.com".to_string(), req.remote_addr);
                     assert_eq!(u64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]), req.conn_id);
                     assert_eq!(
-                        req.return_address.to_bytes().to_vec(),
+                        req.return_address.unwrap().to_bytes().to_vec(),
                         recipient.to_bytes().to_vec()
                     );
                 }
@@ -423,7 +593,7 @@ mod request_deserialization_tests {
                     assert_eq!("foo.com".to_string(), req.remote_addr);
                     assert_eq!(u64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]), req.conn_id);
                     assert_eq!(
-                        req.return_address.to_bytes().to_vec(),
+                        req.return_address.unwrap().to_bytes().to_vec(),
                         recipient.to_bytes().to_vec()
                     );
                 }
@@ -482,4 +652,147 @@ mod request_deserialization_tests {
             }
         }
     }
+
+    #[cfg(test)]
+    mod sending_datagrams {
+        use super::*;
+
+        fn recipient() -> Recipient {
+            Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap()
+        }
+
+        #[test]
+        fn returns_error_for_when_return_address_is_too_short() {
+            // "foo.com" remote address and correct 8 bytes of connection_id
+            let request_bytes: Vec<_> = [
+                RequestFlag::Datagram as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                0,
+                7,
+                102,
+                111,
+                111,
+                46,
+                99,
+                111,
+                109,
+            ]
+            .iter()
+            .cloned()
+            .chain(recipient().to_bytes().iter().take(40).cloned())
+            .collect();
+
+            match Request::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestError::ReturnAddressTooShort => {}
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn can_be_recovered_after_serialization() {
+            let request = Request::new_datagram(
+                u64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+                "1.1.1.1:53".to_string(),
+                recipient(),
+                vec![1, 2, 3, 255],
+            );
+
+            match Request::try_from_bytes(&request.into_bytes()).unwrap() {
+                Request::Datagram(req) => {
+                    assert_eq!("1.1.1.1:53".to_string(), req.remote_addr);
+                    assert_eq!(u64::from_be_bytes([1, 2, 3, 4, 5, 6, 7, 8]), req.conn_id);
+                    assert_eq!(
+                        req.return_address.unwrap().to_bytes().to_vec(),
+                        recipient().to_bytes().to_vec()
+                    );
+                    assert_eq!(vec![1, 2, 3, 255], req.data);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn works_without_data() {
+            let request =
+                Request::new_datagram(42, "1.1.1.1:53".to_string(), recipient(), Vec::new());
+
+            match Request::try_from_bytes(&request.into_bytes()).unwrap() {
+                Request::Datagram(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert!(req.data.is_empty());
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn anonymous_datagram_can_be_recovered_after_serialization() {
+            let request =
+                Request::new_anonymous_datagram(42, "1.1.1.1:53".to_string(), vec![1, 2, 3, 255]);
+
+            let request_bytes = request.into_bytes();
+            assert_eq!(RequestFlag::AnonymousDatagram as u8, request_bytes[0]);
+
+            match Request::try_from_bytes(&request_bytes).unwrap() {
+                Request::Datagram(req) => {
+                    assert_eq!("1.1.1.1:53".to_string(), req.remote_addr);
+                    assert_eq!(42, req.conn_id);
+                    assert!(req.return_address.is_none());
+                    assert_eq!(vec![1, 2, 3, 255], req.data);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod anonymous_connect_requests {
+        use super::*;
+
+        #[test]
+        fn can_be_recovered_after_serialization() {
+            let request = Request::new_anonymous_connect(42, "foo.com:443".to_string());
+
+            let request_bytes = request.into_bytes();
+            assert_eq!(RequestFlag::AnonymousConnect as u8, request_bytes[0]);
+
+            match Request::try_from_bytes(&request_bytes).unwrap() {
+                Request::Connect(req) => {
+                    assert_eq!("foo.com:443".to_string(), req.remote_addr);
+                    assert_eq!(42, req.conn_id);
+                    assert!(req.return_address.is_none());
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn returns_error_when_address_too_short_for_given_address_length() {
+            let request_bytes = [
+                RequestFlag::AnonymousConnect as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                0,
+                7,
+                102,
+            ];
+            match Request::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestError::AddressTooShort => {}
+                _ => unreachable!(),
+            }
+        }
+    }
 }
@@ -1,9 +1,15 @@
-use crate::ConnectionId;
+use crate::{ConnectionId, RemoteAddress};
+use std::convert::TryFrom;
+use std::io;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ResponseError {
+    AddressLengthTooShort,
+    AddressTooShort,
     ConnectionIdTooShort,
     NoData,
+    ConnectStatusMissing,
+    UnknownConnectStatus(u8),
 }
 /// A remote network response retrieved by the Socks5 service provider. This
 /// can be serialized and sent back through the mixnet to the requesting
@@ -64,6 +70,166 @@ impl Response {
     }
 }
 
+/// Result of the connection attempt made by the Socks5 service provider.
+/// The values correspond to the reply codes defined by RFC1928, apart from `LimitExceeded`
+/// which has no equivalent there.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectStatus {
+    Success = 0x00,
+    GeneralFailure = 0x01,
+    NotAllowed = 0x02,
+    NetworkUnreachable = 0x03,
+    HostUnreachable = 0x04,
+    ConnectionRefused = 0x05,
+    TtlExpired = 0x06,
+    /// The service provider refused the connection as the client has exceeded one of its
+    /// connection or bandwidth limits.
+    LimitExceeded = 0x09,
+}
+
+impl TryFrom<u8> for ConnectStatus {
+    type Error = ResponseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            _ if value == (ConnectStatus::Success as u8) => Ok(Self::Success),
+            _ if value == (ConnectStatus::GeneralFailure as u8) => Ok(Self::GeneralFailure),
+            _ if value == (ConnectStatus::NotAllowed as u8) => Ok(Self::NotAllowed),
+            _ if value == (ConnectStatus::NetworkUnreachable as u8) => Ok(Self::NetworkUnreachable),
+            _ if value == (ConnectStatus::HostUnreachable as u8) => Ok(Self::HostUnreachable),
+            _ if value == (ConnectStatus::ConnectionRefused as u8) => Ok(Self::ConnectionRefused),
+            _ if value == (ConnectStatus::TtlExpired as u8) => Ok(Self::TtlExpired),
+            _ if value == (ConnectStatus::LimitExceeded as u8) => Ok(Self::LimitExceeded),
+            _ => Err(ResponseError::UnknownConnectStatus(value)),
+        }
+    }
+}
+
+impl From<&io::Error> for ConnectStatus {
+    fn from(err: &io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::ConnectionRefused => ConnectStatus::ConnectionRefused,
+            io::ErrorKind::TimedOut => ConnectStatus::TtlExpired,
+            _ => ConnectStatus::GeneralFailure,
+        }
+    }
+}
+
+/// Response sent by the Socks5 service provider after it attempted to establish
+/// connection to the requested remote.
+#[derive(Debug)]
+pub struct ConnectResponse {
+    pub connection_id: ConnectionId,
+    pub status: ConnectStatus,
+}
+
+impl ConnectResponse {
+    pub fn new(connection_id: ConnectionId, status: ConnectStatus) -> Self {
+        ConnectResponse {
+            connection_id,
+            status,
+        }
+    }
+
+    pub fn try_from_bytes(b: &[u8]) -> Result<ConnectResponse, ResponseError> {
+        if b.is_empty() {
+            return Err(ResponseError::NoData);
+        }
+
+        if b.len() < 8 {
+            return Err(ResponseError::ConnectionIdTooShort);
+        }
+
+        let connection_id = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+        let status =
+            ConnectStatus::try_from(*b.get(8).ok_or(ResponseError::ConnectStatusMissing)?)?;
+
+        Ok(ConnectResponse::new(connection_id, status))
+    }
+
+    /// Serializes the response into bytes, i.e. `CONN_ID || STATUS`.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.connection_id
+            .to_be_bytes()
+            .iter()
+            .cloned()
+            .chain(std::iter::once(self.status as u8))
+            .collect()
+    }
+}
+
+/// A single UDP datagram received by the Socks5 service provider from the remote on
+/// an existing UDP association.
+#[derive(Debug)]
+pub struct DatagramResponse {
+    pub data: Vec<u8>,
+    pub connection_id: ConnectionId,
+    /// Address of the remote that has sent this datagram.
+    pub source_address: RemoteAddress,
+}
+
+impl DatagramResponse {
+    /// Constructor for datagram responses
+    pub fn new(connection_id: ConnectionId, source_address: RemoteAddress, data: Vec<u8>) -> Self {
+        DatagramResponse {
+            data,
+            connection_id,
+            source_address,
+        }
+    }
+
+    /// Serialized bytes look like this:
+    ///
+    /// -----------------------------------------------------------------------
+    ///  connection_id | address_length | source_address_bytes | datagram_data |
+    ///        8       |      2         |    address_length    |    ...        |
+    /// -----------------------------------------------------------------------
+    pub fn try_from_bytes(b: &[u8]) -> Result<DatagramResponse, ResponseError> {
+        if b.is_empty() {
+            return Err(ResponseError::NoData);
+        }
+
+        if b.len() < 8 {
+            return Err(ResponseError::ConnectionIdTooShort);
+        }
+
+        let connection_id = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+
+        let address_bytes = &b[8..];
+        if address_bytes.len() < 2 {
+            return Err(ResponseError::AddressLengthTooShort);
+        }
+
+        let address_length = u16::from_be_bytes([address_bytes[0], address_bytes[1]]) as usize;
+        if address_bytes.len() < 2 + address_length {
+            return Err(ResponseError::AddressTooShort);
+        }
+
+        let address_end = 2 + address_length;
+        let source_address = String::from_utf8_lossy(&address_bytes[2..address_end]).to_string();
+        let data = address_bytes[address_end..].to_vec();
+
+        Ok(DatagramResponse::new(connection_id, source_address, data))
+    }
+
+    /// Serializes the datagram response into bytes so that it can be sent back through
+    /// the mixnet to the requesting application.
+    pub fn into_bytes(self) -> Vec<u8> {
+        let source_address_bytes = self.source_address.into_bytes();
+        let source_address_bytes_len = source_address_bytes.len() as u16;
+
+        self.connection_id
+            .to_be_bytes()
+            .iter()
+            .cloned()
+            .chain(source_address_bytes_len.to_be_bytes().iter().cloned())
+            .chain(source_address_bytes.into_iter())
+            .chain(self.data.into_iter())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod constructing_socks5_responses_from_bytes {
     use super::*;
@@ -115,3 +281,109 @@ mod constructing_socks5_responses_from_bytes {
         assert_eq!(expected.is_closed, actual.is_closed);
     }
 }
+
+#[cfg(test)]
+mod constructing_datagram_responses_from_bytes {
+    use super::*;
+
+    #[test]
+    fn fails_when_zero_bytes_are_supplied() {
+        assert_eq!(
+            ResponseError::NoData,
+            DatagramResponse::try_from_bytes(&[]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn fails_when_connection_id_bytes_are_too_short() {
+        assert_eq!(
+            ResponseError::ConnectionIdTooShort,
+            DatagramResponse::try_from_bytes(&[0, 1, 2, 3, 4, 5, 6]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn fails_when_address_is_too_short() {
+        assert_eq!(
+            ResponseError::AddressLengthTooShort,
+            DatagramResponse::try_from_bytes(&[0, 1, 2, 3, 4, 5, 6, 7, 0]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::AddressTooShort,
+            DatagramResponse::try_from_bytes(&[0, 1, 2, 3, 4, 5, 6, 7, 0, 3, 49]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn can_be_recovered_after_serialization() {
+        let response = DatagramResponse::new(42, "1.1.1.1:53".to_string(), vec![255, 255, 255]);
+        let recovered = DatagramResponse::try_from_bytes(&response.into_bytes()).unwrap();
+        assert_eq!(42, recovered.connection_id);
+        assert_eq!("1.1.1.1:53".to_string(), recovered.source_address);
+        assert_eq!(vec![255, 255, 255], recovered.data);
+    }
+}
+
+#[cfg(test)]
+mod constructing_connect_responses_from_bytes {
+    use super::*;
+
+    #[test]
+    fn fails_when_data_is_missing() {
+        assert_eq!(
+            ResponseError::NoData,
+            ConnectResponse::try_from_bytes(&[]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::ConnectionIdTooShort,
+            ConnectResponse::try_from_bytes(&[0, 1, 2]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::ConnectStatusMissing,
+            ConnectResponse::try_from_bytes(&[0, 1, 2, 3, 4, 5, 6, 7]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn fails_for_unknown_status() {
+        assert_eq!(
+            ResponseError::UnknownConnectStatus(42),
+            ConnectResponse::try_from_bytes(&[0, 1, 2, 3, 4, 5, 6, 7, 42]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn can_be_recovered_after_serialization() {
+        let statuses = [
+            ConnectStatus::Success,
+            ConnectStatus::GeneralFailure,
+            ConnectStatus::NotAllowed,
+            ConnectStatus::NetworkUnreachable,
+            ConnectStatus::HostUnreachable,
+            ConnectStatus::ConnectionRefused,
+            ConnectStatus::TtlExpired,
+            ConnectStatus::LimitExceeded,
+        ];
+
+        for status in statuses {
+            let response = ConnectResponse::new(42, status);
+            let recovered = ConnectResponse::try_from_bytes(&response.into_bytes()).unwrap();
+            assert_eq!(42, recovered.connection_id);
+            assert_eq!(status, recovered.status);
+        }
+    }
+
+    #[test]
+    fn io_errors_are_mapped_to_appropriate_status() {
+        let refused = io::Error::from(io::ErrorKind::ConnectionRefused);
+        let timeout = io::Error::from(io::ErrorKind::TimedOut);
+        let other = io::Error::from(io::ErrorKind::PermissionDenied);
+
+        assert_eq!(
+            ConnectStatus::ConnectionRefused,
+            ConnectStatus::from(&refused)
+        );
+        assert_eq!(ConnectStatus::TtlExpired, ConnectStatus::from(&timeout));
+        assert_eq!(ConnectStatus::GeneralFailure, ConnectStatus::from(&other));
+    }
+}
@@ -35,11 +35,17 @@ pub enum StatsData {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StatsGatewayData {
     pub inbox_count: u32,
+    /// Number of expired messages removed from the inboxes of offline clients.
+    #[serde(default)]
+    pub pruned_messages: u64,
 }
 
 impl StatsGatewayData {
-    pub fn new(inbox_count: u32) -> Self {
-        StatsGatewayData { inbox_count }
+    pub fn new(inbox_count: u32, pruned_messages: u64) -> Self {
+        StatsGatewayData {
+            inbox_count,
+            pruned_messages,
+        }
     }
 }
 
@@ -48,14 +54,23 @@ pub struct StatsServiceData {
     pub requested_service: String,
     pub request_bytes: u32,
     pub response_bytes: u32,
+    /// Number of requests to the service rejected due to the client exceeding its limits.
+    #[serde(default)]
+    pub limited_requests: u32,
 }
 
 impl StatsServiceData {
-    pub fn new(requested_service: String, request_bytes: u32, response_bytes: u32) -> Self {
+    pub fn new(
+        requested_service: String,
+        request_bytes: u32,
+        response_bytes: u32,
+        limited_requests: u32,
+    ) -> Self {
         StatsServiceData {
             requested_service,
             request_bytes,
             response_bytes,
+            limited_requests,
         }
     }
 }
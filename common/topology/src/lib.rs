@@ -72,11 +72,25 @@ pub type MixLayer = u8;
 pub struct NymTopology {
     mixes: HashMap<MixLayer, Vec<mix::Node>>,
     gateways: Vec<gateway::Node>,
+    current_epoch: Option<u32>,
 }
 
 impl NymTopology {
     pub fn new(mixes: HashMap<MixLayer, Vec<mix::Node>>, gateways: Vec<gateway::Node>) -> Self {
-        NymTopology { mixes, gateways }
+        NymTopology {
+            mixes,
+            gateways,
+            current_epoch: None,
+        }
+    }
+
+    /// Sets the epoch used for choosing the sphinx keys of mixnodes that announced a key rotation.
+    pub fn set_current_epoch(&mut self, current_epoch: Option<u32>) {
+        self.current_epoch = current_epoch
+    }
+
+    pub fn current_epoch(&self) -> Option<u32> {
+        self.current_epoch
     }
 
     pub fn mixes(&self) -> &HashMap<MixLayer, Vec<mix::Node>> {
@@ -92,9 +106,25 @@ impl NymTopology {
         mixes
     }
 
+    /// Returns all mixes on the specified layer. If the layer does not exist,
+    /// an empty vec is returned.
     pub fn mixes_in_layer(&self, layer: MixLayer) -> Vec<mix::Node> {
-        assert!(vec![1, 2, 3].contains(&layer));
-        self.mixes.get(&layer).unwrap().to_owned()
+        self.mixes.get(&layer).cloned().unwrap_or_default()
+    }
+
+    /// Returns the number of consecutive, non-empty, mix layers (starting from layer 1),
+    /// i.e. the maximum number of mix hops a route through this topology could take.
+    pub fn num_usable_layers(&self) -> u8 {
+        let mut layers = 0;
+        while self
+            .mixes
+            .get(&(layers + 1))
+            .map(|layer| !layer.is_empty())
+            .unwrap_or_default()
+        {
+            layers += 1;
+        }
+        layers
     }
 
     pub fn gateways(&self) -> &[gateway::Node] {
@@ -128,7 +158,7 @@ impl NymTopology {
     {
         use rand::seq::SliceRandom;
 
-        if self.mixes.len() < num_mix_hops as usize {
+        if num_mix_hops == 0 || self.mixes.len() < num_mix_hops as usize {
             return Err(NymTopologyError::InvalidNumberOfHopsError);
         }
         let mut route = Vec::with_capacity(num_mix_hops as usize);
@@ -146,7 +176,7 @@ impl NymTopology {
             let random_mix = layer_mixes
                 .choose(rng)
                 .ok_or(NymTopologyError::NoMixesOnLayerAvailable(layer))?;
-            route.push(random_mix.into());
+            route.push(random_mix.to_sphinx_node(self.current_epoch));
         }
 
         Ok(route)
@@ -187,23 +217,13 @@ impl NymTopology {
             return false;
         }
 
-        // early termination
-        if self.mixes.is_empty() {
+        // a route always has to go through at least a single mix
+        if num_mix_hops == 0 {
             return false;
         }
 
         // make sure there's at least one mix per layer
-        for i in 1..=num_mix_hops {
-            match self.mixes.get(&i) {
-                None => return false,
-                Some(layer_entry) => {
-                    if layer_entry.is_empty() {
-                        return false;
-                    }
-                }
-            }
-        }
-        true
+        self.num_usable_layers() >= num_mix_hops
     }
 
     #[must_use]
@@ -220,6 +240,7 @@ impl NymTopology {
         NymTopology {
             mixes: self.mixes.filter_by_version(expected_mix_version),
             gateways: self.gateways.filter_by_version(expected_gateway_version),
+            current_epoch: self.current_epoch,
         }
     }
 }
@@ -292,6 +313,7 @@ mod converting_mixes_to_vec {
                     "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
                 )
                 .unwrap(),
+                sphinx_key_rotation: None,
                 layer: Layer::One,
                 version: "0.x.0".to_string(),
             };
@@ -328,3 +350,151 @@ mod converting_mixes_to_vec {
         }
     }
 }
+
+#[cfg(test)]
+mod path_construction {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+
+    fn mix_node(layer: Layer) -> mix::Node {
+        mix::Node {
+            owner: "N/A".to_string(),
+            stake: 0,
+            delegation: 0,
+            host: "3.3.3.3".parse().unwrap(),
+            mix_host: "3.3.3.3:1789".parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            sphinx_key_rotation: None,
+            layer,
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    fn gateway_node() -> gateway::Node {
+        gateway::Node {
+            owner: "N/A".to_string(),
+            stake: 0,
+            location: "N/A".to_string(),
+            host: "1.1.1.1".parse().unwrap(),
+            mix_host: "1.1.1.1:1789".parse().unwrap(),
+            clients_port: 9000,
+            identity_key: identity::PublicKey::from_base58_string(
+                "FioFa8nMmPpQnYi7JyojoTuwGLeyNS8BF4ChPr29zUML",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "EB42xvMFMD5rUCstE2CDazgQQJ22zLv8SPm1Luxni44c",
+            )
+            .unwrap(),
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    fn topology(layers: &[(MixLayer, usize)]) -> NymTopology {
+        let mixes = layers
+            .iter()
+            .map(|(layer, count)| (*layer, vec![mix_node(Layer::One); *count]))
+            .collect();
+        NymTopology::new(mixes, vec![gateway_node()])
+    }
+
+    #[test]
+    fn usable_layers_must_be_consecutive_and_non_empty() {
+        assert_eq!(0, topology(&[]).num_usable_layers());
+        assert_eq!(3, topology(&[(1, 1), (2, 2), (3, 1)]).num_usable_layers());
+        assert_eq!(
+            4,
+            topology(&[(1, 1), (2, 1), (3, 1), (4, 1)]).num_usable_layers()
+        );
+        assert_eq!(1, topology(&[(1, 1), (3, 1)]).num_usable_layers());
+        assert_eq!(2, topology(&[(1, 1), (2, 1), (3, 0)]).num_usable_layers());
+    }
+
+    #[test]
+    fn path_can_be_constructed_through_any_number_of_available_layers() {
+        let topology = topology(&[(1, 1), (2, 1), (3, 1), (4, 1)]);
+        assert!(!topology.can_construct_path_through(0));
+        for hops in 1..=4 {
+            assert!(topology.can_construct_path_through(hops));
+            assert_eq!(
+                hops as usize,
+                topology
+                    .random_mix_route(&mut rand::thread_rng(), hops)
+                    .unwrap()
+                    .len()
+            );
+        }
+        assert!(!topology.can_construct_path_through(5));
+        assert!(topology
+            .random_mix_route(&mut rand::thread_rng(), 5)
+            .is_err());
+    }
+
+    #[test]
+    fn path_cannot_be_constructed_without_gateways() {
+        let mut topology = topology(&[(1, 1), (2, 1), (3, 1)]);
+        topology.set_gateways(Vec::new());
+        assert!(!topology.can_construct_path_through(3));
+    }
+
+    #[test]
+    fn sphinx_key_is_chosen_based_on_current_epoch() {
+        let next_sphinx_key = encryption::PublicKey::from_base58_string(
+            "EB42xvMFMD5rUCstE2CDazgQQJ22zLv8SPm1Luxni44c",
+        )
+        .unwrap();
+        let mut node = mix_node(Layer::One);
+        node.sphinx_key_rotation = Some(mix::SphinxKeyRotation {
+            next_sphinx_key,
+            activation_epoch: 5,
+        });
+
+        assert_eq!(node.sphinx_key_at_epoch(4), &node.sphinx_key);
+        assert_eq!(node.sphinx_key_at_epoch(5), &next_sphinx_key);
+        assert_eq!(node.sphinx_key_at_epoch(6), &next_sphinx_key);
+
+        let mut topology = NymTopology::new([(1, vec![node.clone()])].into(), vec![]);
+        topology.set_current_epoch(Some(4));
+        let current_key_route = topology
+            .random_mix_route(&mut rand::thread_rng(), 1)
+            .unwrap();
+        assert_eq!(
+            current_key_route[0].pub_key.as_bytes(),
+            &node.sphinx_key.to_bytes()
+        );
+
+        topology.set_current_epoch(Some(5));
+        let next_key_route = topology
+            .random_mix_route(&mut rand::thread_rng(), 1)
+            .unwrap();
+        assert_eq!(
+            next_key_route[0].pub_key.as_bytes(),
+            &next_sphinx_key.to_bytes()
+        );
+
+        // without knowing the epoch, the key that is not going to get retired is used
+        topology.set_current_epoch(None);
+        let unknown_epoch_route = topology
+            .random_mix_route(&mut rand::thread_rng(), 1)
+            .unwrap();
+        assert_eq!(
+            unknown_epoch_route[0].pub_key.as_bytes(),
+            &next_sphinx_key.to_bytes()
+        );
+    }
+
+    #[test]
+    fn mixes_in_missing_layer_are_empty() {
+        let topology = topology(&[(1, 2)]);
+        assert_eq!(2, topology.mixes_in_layer(1).len());
+        assert!(topology.mixes_in_layer(2).is_empty());
+    }
+}
@@ -66,6 +66,13 @@ impl Display for MixnodeConversionError {
     }
 }
 
+/// Sphinx key announced by the node that replaces its current key starting from `activation_epoch`.
+#[derive(Debug, Clone)]
+pub struct SphinxKeyRotation {
+    pub next_sphinx_key: encryption::PublicKey,
+    pub activation_epoch: u32,
+}
+
 #[derive(Debug, Clone)]
 pub struct Node {
     pub owner: String,
@@ -79,10 +86,39 @@ pub struct Node {
     pub mix_host: SocketAddr,
     pub identity_key: identity::PublicKey,
     pub sphinx_key: encryption::PublicKey, // TODO: or nymsphinx::PublicKey? both are x25519
+    pub sphinx_key_rotation: Option<SphinxKeyRotation>,
     pub layer: Layer,
     pub version: String,
 }
 
+impl Node {
+    /// Returns the sphinx key that should be used for packets sent through this node
+    /// during the specified epoch.
+    pub fn sphinx_key_at_epoch(&self, epoch_id: u32) -> &encryption::PublicKey {
+        match &self.sphinx_key_rotation {
+            Some(rotation) if epoch_id >= rotation.activation_epoch => &rotation.next_sphinx_key,
+            _ => &self.sphinx_key,
+        }
+    }
+
+    /// Converts the node into a sphinx route hop using the key valid during the specified epoch.
+    /// If the epoch is unknown, the announced next key is used, if any. The node accepts it from
+    /// the moment the rotation is announced, while its previous key eventually gets retired.
+    pub fn to_sphinx_node(&self, epoch_id: Option<u32>) -> SphinxNode {
+        let node_address_bytes = NymNodeRoutingAddress::from(self.mix_host)
+            .try_into()
+            .unwrap();
+
+        let sphinx_key = match (epoch_id, &self.sphinx_key_rotation) {
+            (Some(epoch_id), _) => self.sphinx_key_at_epoch(epoch_id),
+            (None, Some(rotation)) => &rotation.next_sphinx_key,
+            (None, None) => &self.sphinx_key,
+        };
+
+        SphinxNode::new(node_address_bytes, sphinx_key.into())
+    }
+}
+
 impl filter::Versioned for Node {
     fn version(&self) -> String {
         self.version.clone()
@@ -91,11 +127,7 @@ impl filter::Versioned for Node {
 
 impl<'a> From<&'a Node> for SphinxNode {
     fn from(node: &'a Node) -> Self {
-        let node_address_bytes = NymNodeRoutingAddress::from(node.mix_host)
-            .try_into()
-            .unwrap();
-
-        SphinxNode::new(node_address_bytes, (&node.sphinx_key).into())
+        node.to_sphinx_node(None)
     }
 }
 
@@ -123,6 +155,18 @@ impl<'a> TryFrom<&'a MixNodeBond> for Node {
             mix_host,
             identity_key: identity::PublicKey::from_base58_string(&bond.mix_node.identity_key)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&bond.mix_node.sphinx_key)?,
+            sphinx_key_rotation: bond
+                .sphinx_key_rotation
+                .as_ref()
+                .map(|rotation| {
+                    Ok::<_, MixnodeConversionError>(SphinxKeyRotation {
+                        next_sphinx_key: encryption::PublicKey::from_base58_string(
+                            &rotation.next_sphinx_key,
+                        )?,
+                        activation_epoch: rotation.activation_epoch,
+                    })
+                })
+                .transpose()?,
             layer: bond.layer,
             version: bond.mix_node.version.clone(),
         })
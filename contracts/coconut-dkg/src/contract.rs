@@ -0,0 +1,130 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::{
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+use cw4::Cw4Contract;
+
+use coconut_dkg_common::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use coconut_dkg_common::types::{Epoch, EpochState};
+
+use crate::error::ContractError;
+use crate::queries::{
+    query_current_epoch, query_current_threshold, query_dealer_details, query_dealers_paged,
+    query_dealings_paged, query_vk_shares_paged,
+};
+use crate::state::{Config, CONFIG, CURRENT_EPOCH};
+use crate::transactions;
+
+/// Instantiate the contract.
+///
+/// `deps` contains Storage, API and Querier
+/// `msg` is the contract initialization message, sort of like a constructor call.
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut<'_>,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let group_addr = Cw4Contract(deps.api.addr_validate(&msg.group_addr)?);
+    let time_configuration = msg.time_configuration.unwrap_or_default();
+
+    let cfg = Config {
+        group_addr,
+        time_configuration,
+    };
+    CONFIG.save(deps.storage, &cfg)?;
+
+    let initial_epoch = Epoch::new(
+        EpochState::PublicKeySubmission,
+        env.block.time,
+        time_configuration,
+    );
+    CURRENT_EPOCH.save(deps.storage, &initial_epoch)?;
+
+    Ok(Response::default())
+}
+
+/// Handle an incoming message
+#[entry_point]
+pub fn execute(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RegisterDealer {
+            bte_key_with_proof,
+            announce_address,
+        } => transactions::try_add_dealer(deps, info, bte_key_with_proof, announce_address),
+        ExecuteMsg::CommitDealing { dealings } => {
+            transactions::try_commit_dealings(deps, info, dealings)
+        }
+        ExecuteMsg::CommitVerificationKeyShare { share } => {
+            transactions::try_commit_verification_key_share(deps, info, share)
+        }
+        ExecuteMsg::AdvanceEpochState {} => transactions::try_advance_epoch_state(deps, env),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps<'_>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetCurrentEpochState {} => to_binary(&query_current_epoch(deps)?),
+        QueryMsg::GetCurrentThreshold {} => to_binary(&query_current_threshold(deps)?),
+        QueryMsg::GetDealerDetails { dealer_address } => {
+            to_binary(&query_dealer_details(deps, dealer_address)?)
+        }
+        QueryMsg::GetCurrentDealers { limit, start_after } => {
+            to_binary(&query_dealers_paged(deps, start_after, limit)?)
+        }
+        QueryMsg::GetDealings { limit, start_after } => {
+            to_binary(&query_dealings_paged(deps, start_after, limit)?)
+        }
+        QueryMsg::GetVerificationKeyShares { limit, start_after } => {
+            to_binary(&query_vk_shares_paged(deps, start_after, limit)?)
+        }
+    }
+}
+
+#[entry_point]
+pub fn migrate(_deps: DepsMut<'_>, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Default::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::support::tests::helpers::GROUP_CONTRACT;
+    use coconut_dkg_common::types::TimeConfiguration;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    #[test]
+    fn initialize_contract() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            group_addr: String::from(GROUP_CONTRACT),
+            time_configuration: None,
+        };
+        let info = mock_info("creator", &[]);
+
+        let res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let epoch = query_current_epoch(deps.as_ref()).unwrap();
+        assert_eq!(EpochState::PublicKeySubmission, epoch.state);
+        assert_eq!(
+            Some(
+                env.block
+                    .time
+                    .plus_seconds(TimeConfiguration::default().public_key_submission_time_secs)
+            ),
+            epoch.finish_timestamp
+        );
+        assert!(query_current_threshold(deps.as_ref()).unwrap().is_none());
+    }
+}
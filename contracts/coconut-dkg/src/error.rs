@@ -0,0 +1,43 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use coconut_dkg_common::types::EpochState;
+use cosmwasm_std::{StdError, Timestamp};
+use thiserror::Error;
+
+/// Custom errors for contract failure conditions.
+///
+/// Add any other custom errors you like here.
+/// Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Only members of the group contract are allowed to become dealers")]
+    Unauthorized,
+
+    #[error("This sender is already registered as a dealer")]
+    AlreadyADealer,
+
+    #[error("This sender is not registered as a dealer")]
+    NotADealer,
+
+    #[error("This dealer has already committed its dealings")]
+    AlreadyCommitted,
+
+    #[error("At least a single dealing has to be provided")]
+    EmptyDealing,
+
+    #[error("The requested action can only be performed in the {expected} state, the current state is {current}")]
+    IncorrectEpochState {
+        current: EpochState,
+        expected: EpochState,
+    },
+
+    #[error("The epoch can't be advanced before {finish_timestamp}")]
+    EarlyEpochStateAdvancement { finish_timestamp: Timestamp },
+
+    #[error("The key generation ceremony has already finished")]
+    CeremonyFinished,
+}
@@ -0,0 +1,172 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use coconut_dkg_common::dealer::{
+    ContractDealing, DealerDetails, DealerDetailsResponse, PagedDealerResponse,
+    PagedDealingsResponse,
+};
+use coconut_dkg_common::types::{Epoch, Threshold};
+use coconut_dkg_common::verification_key::{ContractVKShare, PagedVKSharesResponse};
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::state::{CURRENT_EPOCH, THRESHOLD};
+use crate::storage::{
+    DEALERS, DEALERS_PAGE_DEFAULT_LIMIT, DEALERS_PAGE_MAX_LIMIT, DEALINGS,
+    DEALINGS_PAGE_DEFAULT_LIMIT, DEALINGS_PAGE_MAX_LIMIT, VK_SHARES, VK_SHARES_PAGE_DEFAULT_LIMIT,
+    VK_SHARES_PAGE_MAX_LIMIT,
+};
+
+pub(crate) fn query_current_epoch(deps: Deps<'_>) -> StdResult<Epoch> {
+    CURRENT_EPOCH.load(deps.storage)
+}
+
+pub(crate) fn query_current_threshold(deps: Deps<'_>) -> StdResult<Option<Threshold>> {
+    THRESHOLD.may_load(deps.storage)
+}
+
+pub(crate) fn query_dealer_details(
+    deps: Deps<'_>,
+    dealer_address: String,
+) -> StdResult<DealerDetailsResponse> {
+    let addr = deps.api.addr_validate(&dealer_address)?;
+    let details = DEALERS.may_load(deps.storage, &addr)?;
+    Ok(DealerDetailsResponse::new(details))
+}
+
+pub(crate) fn query_dealers_paged(
+    deps: Deps<'_>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PagedDealerResponse> {
+    let limit = limit
+        .unwrap_or(DEALERS_PAGE_DEFAULT_LIMIT)
+        .min(DEALERS_PAGE_MAX_LIMIT) as usize;
+
+    let addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let dealers = DEALERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, dealer)| dealer))
+        .collect::<StdResult<Vec<DealerDetails>>>()?;
+
+    let start_next_after = dealers.last().map(|dealer| dealer.address.clone());
+
+    Ok(PagedDealerResponse::new(dealers, limit, start_next_after))
+}
+
+pub(crate) fn query_dealings_paged(
+    deps: Deps<'_>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PagedDealingsResponse> {
+    let limit = limit
+        .unwrap_or(DEALINGS_PAGE_DEFAULT_LIMIT)
+        .min(DEALINGS_PAGE_MAX_LIMIT) as usize;
+
+    let addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let dealings = DEALINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, dealing)| dealing))
+        .collect::<StdResult<Vec<ContractDealing>>>()?;
+
+    let start_next_after = dealings.last().map(|dealing| dealing.dealer.clone());
+
+    Ok(PagedDealingsResponse::new(
+        dealings,
+        limit,
+        start_next_after,
+    ))
+}
+
+pub(crate) fn query_vk_shares_paged(
+    deps: Deps<'_>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PagedVKSharesResponse> {
+    let limit = limit
+        .unwrap_or(VK_SHARES_PAGE_DEFAULT_LIMIT)
+        .min(VK_SHARES_PAGE_MAX_LIMIT) as usize;
+
+    let addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let shares = VK_SHARES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, share)| share))
+        .collect::<StdResult<Vec<ContractVKShare>>>()?;
+
+    let start_next_after = shares.last().map(|share| share.owner.clone());
+
+    Ok(PagedVKSharesResponse::new(shares, limit, start_next_after))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::tests::fixtures::TEST_BTE_KEY;
+    use crate::support::tests::helpers::{init_contract, GROUP_MEMBERS};
+    use crate::transactions::try_add_dealer;
+    use cosmwasm_std::testing::mock_info;
+
+    #[test]
+    fn dealers_empty_on_init() {
+        let deps = init_contract();
+        let response = query_dealers_paged(deps.as_ref(), None, None).unwrap();
+        assert!(response.dealers.is_empty());
+        assert!(response.start_next_after.is_none());
+    }
+
+    #[test]
+    fn dealer_details_are_returned_for_registered_dealers_only() {
+        let mut deps = init_contract();
+        try_add_dealer(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[0], &[]),
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        )
+        .unwrap();
+
+        let registered = query_dealer_details(deps.as_ref(), GROUP_MEMBERS[0].to_string()).unwrap();
+        assert_eq!(1, registered.details.unwrap().assigned_index);
+
+        let unregistered =
+            query_dealer_details(deps.as_ref(), GROUP_MEMBERS[1].to_string()).unwrap();
+        assert!(unregistered.details.is_none());
+    }
+
+    #[test]
+    fn dealers_pagination_works() {
+        let mut deps = init_contract();
+        for member in GROUP_MEMBERS {
+            try_add_dealer(
+                deps.as_mut(),
+                mock_info(member, &[]),
+                TEST_BTE_KEY.to_string(),
+                "https://foomp.com".to_string(),
+            )
+            .unwrap();
+        }
+
+        let page1 = query_dealers_paged(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(2, page1.dealers.len());
+
+        let start_after = page1.start_next_after.unwrap().into_string();
+        let page2 = query_dealers_paged(deps.as_ref(), Some(start_after), Some(2)).unwrap();
+        assert_eq!(1, page2.dealers.len());
+        assert_eq!(GROUP_MEMBERS[2], page2.dealers[0].address.as_str());
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use coconut_dkg_common::types::{Epoch, NodeIndex, Threshold, TimeConfiguration};
+use cw4::Cw4Contract;
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub group_addr: Cw4Contract,
+    pub time_configuration: TimeConfiguration,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub(crate) const CURRENT_EPOCH: Item<Epoch> = Item::new("epoch");
+
+// set when the dealing exchange begins, based on the number of registered dealers
+pub(crate) const THRESHOLD: Item<Threshold> = Item::new("threshold");
+
+// node indices are used as x-coordinates of the polynomial evaluations, so they must never be 0
+pub(crate) const NODE_INDEX_COUNTER: Item<NodeIndex> = Item::new("node_index_counter");
@@ -0,0 +1,48 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::state::NODE_INDEX_COUNTER;
+use coconut_dkg_common::dealer::{ContractDealing, DealerDetails};
+use coconut_dkg_common::types::NodeIndex;
+use coconut_dkg_common::verification_key::ContractVKShare;
+use cosmwasm_std::{Addr, StdResult, Storage};
+use cw_storage_plus::Map;
+
+// storage prefixes
+const DEALERS_NAMESPACE: &str = "dlr";
+const DEALINGS_NAMESPACE: &str = "dlg";
+const VK_SHARES_NAMESPACE: &str = "vks";
+
+// paged retrieval limits for all queries and transactions
+// dealings are rather large, so they have their own, lower, limits
+pub(crate) const DEALERS_PAGE_MAX_LIMIT: u32 = 75;
+pub(crate) const DEALERS_PAGE_DEFAULT_LIMIT: u32 = 50;
+pub(crate) const DEALINGS_PAGE_MAX_LIMIT: u32 = 5;
+pub(crate) const DEALINGS_PAGE_DEFAULT_LIMIT: u32 = 2;
+pub(crate) const VK_SHARES_PAGE_MAX_LIMIT: u32 = 75;
+pub(crate) const VK_SHARES_PAGE_DEFAULT_LIMIT: u32 = 50;
+
+pub(crate) const DEALERS: Map<'_, &Addr, DealerDetails> = Map::new(DEALERS_NAMESPACE);
+pub(crate) const DEALINGS: Map<'_, &Addr, ContractDealing> = Map::new(DEALINGS_NAMESPACE);
+pub(crate) const VK_SHARES: Map<'_, &Addr, ContractVKShare> = Map::new(VK_SHARES_NAMESPACE);
+
+pub(crate) fn next_node_index(store: &mut dyn Storage) -> StdResult<NodeIndex> {
+    // make sure we don't start from 0, otherwise all the crypto breaks (kinda)
+    let id: NodeIndex = NODE_INDEX_COUNTER.may_load(store)?.unwrap_or_default() + 1;
+    NODE_INDEX_COUNTER.save(store, &id)?;
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn node_indices_start_from_one_and_are_increasing() {
+        let mut storage = MockStorage::new();
+        assert_eq!(1, next_node_index(&mut storage).unwrap());
+        assert_eq!(2, next_node_index(&mut storage).unwrap());
+        assert_eq!(3, next_node_index(&mut storage).unwrap());
+    }
+}
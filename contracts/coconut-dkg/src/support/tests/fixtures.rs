@@ -0,0 +1,15 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::Binary;
+
+// the contract does not validate the keys, so any string will do
+pub const TEST_BTE_KEY: &str = "bte public key with proof";
+
+pub fn dealing_fixture() -> Binary {
+    Binary::from(vec![42u8; 32])
+}
+
+pub fn vk_share_fixture() -> String {
+    "verification key share".to_string()
+}
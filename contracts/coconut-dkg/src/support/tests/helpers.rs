@@ -0,0 +1,53 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+pub const GROUP_CONTRACT: &str = "group contract address";
+pub const GROUP_MEMBERS: [&str; 3] = ["member1", "member2", "member3"];
+
+use crate::contract::instantiate;
+use crate::state::CURRENT_EPOCH;
+use coconut_dkg_common::msg::InstantiateMsg;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    to_binary, ContractResult, Empty, Env, MemoryStorage, OwnedDeps, SystemResult, WasmQuery,
+};
+
+// cw4 group membership is checked via raw queries of the `members` map,
+// the key of which is the length-prefixed namespace followed by the member address
+fn is_group_member(key: &[u8]) -> bool {
+    GROUP_MEMBERS
+        .iter()
+        .any(|member| key.ends_with(member.as_bytes()))
+}
+
+pub fn init_contract() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>> {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Raw { contract_addr, key } if contract_addr == GROUP_CONTRACT => {
+            if is_group_member(key) {
+                SystemResult::Ok(ContractResult::Ok(to_binary(&10u64).unwrap()))
+            } else {
+                SystemResult::Ok(ContractResult::Ok(Default::default()))
+            }
+        }
+        _ => panic!("unexpected query"),
+    });
+
+    let msg = InstantiateMsg {
+        group_addr: String::from(GROUP_CONTRACT),
+        time_configuration: None,
+    };
+    let env = mock_env();
+    let info = mock_info("creator", &[]);
+    instantiate(deps.as_mut(), env, info, msg).unwrap();
+    deps
+}
+
+pub fn advance_time_past_current_state(
+    deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>>,
+    env: &mut Env,
+) {
+    if let Some(finish_timestamp) = CURRENT_EPOCH.load(&deps.storage).unwrap().finish_timestamp {
+        env.block.time = finish_timestamp.plus_seconds(1);
+    }
+}
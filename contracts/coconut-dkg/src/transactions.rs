@@ -0,0 +1,402 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, CURRENT_EPOCH, THRESHOLD};
+use crate::storage::{self, DEALERS, DEALINGS, VK_SHARES};
+use coconut_dkg_common::dealer::{ContractDealing, DealerDetails};
+use coconut_dkg_common::types::{
+    EncodedBTEPublicKeyWithProof, EncodedVerificationKeyShare, Epoch, EpochState, Threshold,
+};
+use coconut_dkg_common::verification_key::ContractVKShare;
+use cosmwasm_std::{Binary, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+
+fn check_epoch_state(storage: &dyn Storage, expected: EpochState) -> Result<(), ContractError> {
+    let current = CURRENT_EPOCH.load(storage)?.state;
+    if current != expected {
+        Err(ContractError::IncorrectEpochState { current, expected })
+    } else {
+        Ok(())
+    }
+}
+
+// 2/3 of the dealers, rounded up
+fn threshold_for(dealers: u64) -> Threshold {
+    (2 * dealers + 2) / 3
+}
+
+pub(crate) fn try_add_dealer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    bte_key_with_proof: EncodedBTEPublicKeyWithProof,
+    announce_address: String,
+) -> Result<Response, ContractError> {
+    check_epoch_state(deps.storage, EpochState::PublicKeySubmission)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if config
+        .group_addr
+        .is_member(&deps.querier, &info.sender, None)?
+        .is_none()
+    {
+        return Err(ContractError::Unauthorized);
+    }
+
+    if DEALERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::AlreadyADealer);
+    }
+
+    let node_index = storage::next_node_index(deps.storage)?;
+    let dealer_details = DealerDetails {
+        address: info.sender.clone(),
+        bte_public_key_with_proof: bte_key_with_proof,
+        announce_address,
+        assigned_index: node_index,
+    };
+    DEALERS.save(deps.storage, &info.sender, &dealer_details)?;
+
+    Ok(Response::new().add_attribute("node_index", node_index.to_string()))
+}
+
+pub(crate) fn try_commit_dealings(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    dealings: Vec<Binary>,
+) -> Result<Response, ContractError> {
+    check_epoch_state(deps.storage, EpochState::DealingExchange)?;
+
+    if !DEALERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::NotADealer);
+    }
+
+    if DEALINGS.has(deps.storage, &info.sender) {
+        return Err(ContractError::AlreadyCommitted);
+    }
+
+    if dealings.is_empty() || dealings.iter().any(|dealing| dealing.is_empty()) {
+        return Err(ContractError::EmptyDealing);
+    }
+
+    let commitment = ContractDealing::new(info.sender.clone(), dealings);
+    DEALINGS.save(deps.storage, &info.sender, &commitment)?;
+
+    Ok(Response::default())
+}
+
+pub(crate) fn try_commit_verification_key_share(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    share: EncodedVerificationKeyShare,
+) -> Result<Response, ContractError> {
+    check_epoch_state(deps.storage, EpochState::VerificationKeySubmission)?;
+
+    let dealer_details = DEALERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotADealer)?;
+
+    if VK_SHARES.has(deps.storage, &info.sender) {
+        return Err(ContractError::AlreadyCommitted);
+    }
+
+    let vk_share = ContractVKShare {
+        share,
+        announce_address: dealer_details.announce_address,
+        node_index: dealer_details.assigned_index,
+        owner: info.sender.clone(),
+    };
+    VK_SHARES.save(deps.storage, &info.sender, &vk_share)?;
+
+    Ok(Response::default())
+}
+
+pub(crate) fn try_advance_epoch_state(
+    deps: DepsMut<'_>,
+    env: Env,
+) -> Result<Response, ContractError> {
+    let current_epoch = CURRENT_EPOCH.load(deps.storage)?;
+    let next_state = current_epoch
+        .state
+        .next()
+        .ok_or(ContractError::CeremonyFinished)?;
+
+    if !current_epoch.can_advance(env.block.time) {
+        // the unwrap is fine as only the final state has no finish timestamp
+        return Err(ContractError::EarlyEpochStateAdvancement {
+            finish_timestamp: current_epoch.finish_timestamp.unwrap(),
+        });
+    }
+
+    let time_configuration = CONFIG.load(deps.storage)?.time_configuration;
+
+    if current_epoch.state == EpochState::PublicKeySubmission {
+        let dealers = DEALERS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .len() as u64;
+
+        // if nobody has registered, there's nothing to do but wait for somebody to do so
+        if dealers == 0 {
+            let restarted = Epoch::new(current_epoch.state, env.block.time, time_configuration);
+            CURRENT_EPOCH.save(deps.storage, &restarted)?;
+            return Ok(Response::new().add_attribute("epoch_state", restarted.state.to_string()));
+        }
+
+        THRESHOLD.save(deps.storage, &threshold_for(dealers))?;
+    }
+
+    let next_epoch = Epoch::new(next_state, env.block.time, time_configuration);
+    CURRENT_EPOCH.save(deps.storage, &next_epoch)?;
+
+    Ok(Response::new().add_attribute("epoch_state", next_epoch.state.to_string()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::tests::fixtures::{dealing_fixture, vk_share_fixture, TEST_BTE_KEY};
+    use crate::support::tests::helpers::{
+        advance_time_past_current_state, init_contract, GROUP_MEMBERS,
+    };
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::Addr;
+
+    fn register_all_members(deps: DepsMut<'_>) {
+        let mut deps = deps;
+        for member in GROUP_MEMBERS {
+            try_add_dealer(
+                deps.branch(),
+                mock_info(member, &[]),
+                TEST_BTE_KEY.to_string(),
+                format!("https://{}.com", member),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn threshold_is_two_thirds_rounded_up() {
+        assert_eq!(1, threshold_for(1));
+        assert_eq!(2, threshold_for(2));
+        assert_eq!(2, threshold_for(3));
+        assert_eq!(3, threshold_for(4));
+        assert_eq!(4, threshold_for(5));
+        assert_eq!(4, threshold_for(6));
+        assert_eq!(67, threshold_for(100));
+    }
+
+    #[test]
+    fn only_group_members_can_become_dealers() {
+        let mut deps = init_contract();
+
+        let res = try_add_dealer(
+            deps.as_mut(),
+            mock_info("not-a-member", &[]),
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        );
+        assert_eq!(Err(ContractError::Unauthorized), res);
+
+        let res = try_add_dealer(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[0], &[]),
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        );
+        assert!(res.is_ok());
+
+        let details = DEALERS
+            .load(&deps.storage, &Addr::unchecked(GROUP_MEMBERS[0]))
+            .unwrap();
+        assert_eq!(1, details.assigned_index);
+        assert_eq!("https://foomp.com", details.announce_address);
+    }
+
+    #[test]
+    fn dealers_cant_register_twice() {
+        let mut deps = init_contract();
+        let info = mock_info(GROUP_MEMBERS[0], &[]);
+
+        try_add_dealer(
+            deps.as_mut(),
+            info.clone(),
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        )
+        .unwrap();
+        let res = try_add_dealer(
+            deps.as_mut(),
+            info,
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        );
+        assert_eq!(Err(ContractError::AlreadyADealer), res);
+    }
+
+    #[test]
+    fn dealers_can_only_register_during_public_key_submission() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+        try_add_dealer(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[0], &[]),
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        )
+        .unwrap();
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env).unwrap();
+
+        let res = try_add_dealer(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[1], &[]),
+            TEST_BTE_KEY.to_string(),
+            "https://foomp.com".to_string(),
+        );
+        assert_eq!(
+            Err(ContractError::IncorrectEpochState {
+                current: EpochState::DealingExchange,
+                expected: EpochState::PublicKeySubmission
+            }),
+            res
+        );
+    }
+
+    #[test]
+    fn epoch_state_cant_be_advanced_early() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+        register_all_members(deps.as_mut());
+
+        let finish_timestamp = CURRENT_EPOCH
+            .load(&deps.storage)
+            .unwrap()
+            .finish_timestamp
+            .unwrap();
+        let res = try_advance_epoch_state(deps.as_mut(), env.clone());
+        assert_eq!(
+            Err(ContractError::EarlyEpochStateAdvancement { finish_timestamp }),
+            res
+        );
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env).unwrap();
+        assert_eq!(
+            EpochState::DealingExchange,
+            CURRENT_EPOCH.load(&deps.storage).unwrap().state
+        );
+    }
+
+    #[test]
+    fn public_key_submission_is_restarted_if_nobody_registered() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+
+        let epoch = CURRENT_EPOCH.load(&deps.storage).unwrap();
+        assert_eq!(EpochState::PublicKeySubmission, epoch.state);
+        assert!(!epoch.can_advance(env.block.time));
+        assert!(THRESHOLD.may_load(&deps.storage).unwrap().is_none());
+    }
+
+    #[test]
+    fn threshold_is_set_when_dealing_exchange_begins() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+        register_all_members(deps.as_mut());
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env).unwrap();
+
+        assert_eq!(
+            threshold_for(GROUP_MEMBERS.len() as u64),
+            THRESHOLD.load(&deps.storage).unwrap()
+        );
+    }
+
+    #[test]
+    fn full_ceremony_flow() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+        register_all_members(deps.as_mut());
+
+        // dealings can't be submitted just yet
+        let res = try_commit_dealings(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[0], &[]),
+            vec![dealing_fixture()],
+        );
+        assert!(matches!(
+            res,
+            Err(ContractError::IncorrectEpochState { .. })
+        ));
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+
+        let res = try_commit_dealings(
+            deps.as_mut(),
+            mock_info("not-a-dealer", &[]),
+            vec![dealing_fixture()],
+        );
+        assert_eq!(Err(ContractError::NotADealer), res);
+
+        let res = try_commit_dealings(deps.as_mut(), mock_info(GROUP_MEMBERS[0], &[]), vec![]);
+        assert_eq!(Err(ContractError::EmptyDealing), res);
+
+        for member in GROUP_MEMBERS {
+            try_commit_dealings(
+                deps.as_mut(),
+                mock_info(member, &[]),
+                vec![dealing_fixture(), dealing_fixture()],
+            )
+            .unwrap();
+        }
+
+        let res = try_commit_dealings(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[0], &[]),
+            vec![dealing_fixture()],
+        );
+        assert_eq!(Err(ContractError::AlreadyCommitted), res);
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+
+        for member in GROUP_MEMBERS {
+            try_commit_verification_key_share(
+                deps.as_mut(),
+                mock_info(member, &[]),
+                vk_share_fixture(),
+            )
+            .unwrap();
+        }
+        let res = try_commit_verification_key_share(
+            deps.as_mut(),
+            mock_info(GROUP_MEMBERS[0], &[]),
+            vk_share_fixture(),
+        );
+        assert_eq!(Err(ContractError::AlreadyCommitted), res);
+
+        let share = VK_SHARES
+            .load(&deps.storage, &Addr::unchecked(GROUP_MEMBERS[1]))
+            .unwrap();
+        assert_eq!(2, share.node_index);
+        assert_eq!(
+            format!("https://{}.com", GROUP_MEMBERS[1]),
+            share.announce_address
+        );
+
+        advance_time_past_current_state(&deps, &mut env);
+        try_advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+
+        let epoch = CURRENT_EPOCH.load(&deps.storage).unwrap();
+        assert!(epoch.is_final());
+        assert!(epoch.finish_timestamp.is_none());
+
+        let res = try_advance_epoch_state(deps.as_mut(), env);
+        assert_eq!(Err(ContractError::CeremonyFinished), res);
+    }
+}
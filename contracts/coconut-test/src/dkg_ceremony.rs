@@ -0,0 +1,198 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::helpers::*;
+use coconut_dkg::error::ContractError;
+use coconut_dkg_common::dealer::{DealerDetailsResponse, PagedDealingsResponse};
+use coconut_dkg_common::msg::{
+    ExecuteMsg as DkgExecuteMsg, InstantiateMsg as DkgInstantiateMsg, QueryMsg as DkgQueryMsg,
+};
+use coconut_dkg_common::types::{Epoch, EpochState, TimeConfiguration};
+use coconut_dkg_common::verification_key::PagedVKSharesResponse;
+use cosmwasm_std::{coins, Addr, Binary};
+use cw4::Member;
+use cw4_group::msg::InstantiateMsg as GroupInstantiateMsg;
+use cw_multi_test::{App, Executor};
+
+const TEST_COIN_DENOM: &str = "unym";
+const DEALERS: [&str; 4] = ["dealer1", "dealer2", "dealer3", "dealer4"];
+
+fn current_epoch(app: &App, dkg_contract_addr: &Addr) -> Epoch {
+    app.wrap()
+        .query_wasm_smart(dkg_contract_addr, &DkgQueryMsg::GetCurrentEpochState {})
+        .unwrap()
+}
+
+fn advance_epoch(app: &mut App, dkg_contract_addr: &Addr) {
+    let finish_timestamp = current_epoch(app, dkg_contract_addr)
+        .finish_timestamp
+        .unwrap();
+    app.update_block(|block| block.time = finish_timestamp);
+    app.execute_contract(
+        Addr::unchecked(RANDOM_ADDRESS),
+        dkg_contract_addr.clone(),
+        &DkgExecuteMsg::AdvanceEpochState {},
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn dkg_ceremony() {
+    let init_funds = coins(10, TEST_COIN_DENOM);
+    let mut app = mock_app(&init_funds);
+
+    // only the first 3 dealers are members of the group
+    let group_code_id = app.store_code(contract_group());
+    let msg = GroupInstantiateMsg {
+        admin: Some(OWNER.to_string()),
+        members: DEALERS[..3]
+            .iter()
+            .map(|dealer| Member {
+                addr: dealer.to_string(),
+                weight: 10,
+            })
+            .collect(),
+    };
+    let group_contract_addr = app
+        .instantiate_contract(
+            group_code_id,
+            Addr::unchecked(OWNER),
+            &msg,
+            &[],
+            "group",
+            None,
+        )
+        .unwrap();
+
+    let dkg_code_id = app.store_code(contract_dkg());
+    let msg = DkgInstantiateMsg {
+        group_addr: group_contract_addr.to_string(),
+        time_configuration: Some(TimeConfiguration {
+            public_key_submission_time_secs: 100,
+            dealing_exchange_time_secs: 200,
+            verification_key_submission_time_secs: 100,
+        }),
+    };
+    let dkg_contract_addr = app
+        .instantiate_contract(dkg_code_id, Addr::unchecked(OWNER), &msg, &[], "dkg", None)
+        .unwrap();
+
+    for dealer in &DEALERS[..3] {
+        app.execute_contract(
+            Addr::unchecked(*dealer),
+            dkg_contract_addr.clone(),
+            &DkgExecuteMsg::RegisterDealer {
+                bte_key_with_proof: format!("{} bte key", dealer),
+                announce_address: format!("https://{}.com", dealer),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    // non-members can't take part in the ceremony
+    let err = app
+        .execute_contract(
+            Addr::unchecked(DEALERS[3]),
+            dkg_contract_addr.clone(),
+            &DkgExecuteMsg::RegisterDealer {
+                bte_key_with_proof: "bte key".to_string(),
+                announce_address: "https://dealer4.com".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+
+    let details: DealerDetailsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &dkg_contract_addr,
+            &DkgQueryMsg::GetDealerDetails {
+                dealer_address: DEALERS[2].to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(3, details.details.unwrap().assigned_index);
+
+    // the epoch can't be advanced before its time
+    let err = app
+        .execute_contract(
+            Addr::unchecked(RANDOM_ADDRESS),
+            dkg_contract_addr.clone(),
+            &DkgExecuteMsg::AdvanceEpochState {},
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::EarlyEpochStateAdvancement { .. }
+    ));
+
+    advance_epoch(&mut app, &dkg_contract_addr);
+    assert_eq!(
+        EpochState::DealingExchange,
+        current_epoch(&app, &dkg_contract_addr).state
+    );
+    let threshold: Option<u64> = app
+        .wrap()
+        .query_wasm_smart(&dkg_contract_addr, &DkgQueryMsg::GetCurrentThreshold {})
+        .unwrap();
+    assert_eq!(Some(2), threshold);
+
+    for dealer in &DEALERS[..3] {
+        app.execute_contract(
+            Addr::unchecked(*dealer),
+            dkg_contract_addr.clone(),
+            &DkgExecuteMsg::CommitDealing {
+                dealings: vec![Binary::from(dealer.as_bytes())],
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let dealings: PagedDealingsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &dkg_contract_addr,
+            &DkgQueryMsg::GetDealings {
+                limit: Some(5),
+                start_after: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(3, dealings.dealings.len());
+
+    advance_epoch(&mut app, &dkg_contract_addr);
+    for dealer in &DEALERS[..3] {
+        app.execute_contract(
+            Addr::unchecked(*dealer),
+            dkg_contract_addr.clone(),
+            &DkgExecuteMsg::CommitVerificationKeyShare {
+                share: format!("{} vk share", dealer),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    advance_epoch(&mut app, &dkg_contract_addr);
+    assert!(current_epoch(&app, &dkg_contract_addr).is_final());
+
+    let shares: PagedVKSharesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &dkg_contract_addr,
+            &DkgQueryMsg::GetVerificationKeyShares {
+                limit: None,
+                start_after: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(3, shares.shares.len());
+    for (share, dealer) in shares.shares.iter().zip(DEALERS.iter()) {
+        assert_eq!(&format!("https://{}.com", dealer), &share.announce_address);
+    }
+}
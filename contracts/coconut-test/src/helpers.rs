@@ -44,6 +44,15 @@ pub fn contract_bandwidth() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
+pub fn contract_dkg() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        coconut_dkg::contract::execute,
+        coconut_dkg::contract::instantiate,
+        coconut_dkg::contract::query,
+    );
+    Box::new(contract)
+}
+
 pub fn contract_multisig() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
         cw3_flex_multisig::contract::execute,
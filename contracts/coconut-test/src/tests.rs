@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod deposit_and_release;
+mod dkg_ceremony;
 mod helpers;
 mod spend_credential_creates_proposal;
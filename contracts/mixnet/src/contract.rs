@@ -160,6 +160,16 @@ pub fn execute(
             profit_margin_percent,
             owner,
         ),
+        ExecuteMsg::AnnounceSphinxKeyRotation { rotation } => {
+            crate::mixnodes::transactions::try_announce_sphinx_key_rotation(
+                deps, env, info, rotation,
+            )
+        }
+        ExecuteMsg::AnnounceSphinxKeyRotationOnBehalf { rotation, owner } => {
+            crate::mixnodes::transactions::try_announce_sphinx_key_rotation_on_behalf(
+                deps, env, info, rotation, owner,
+            )
+        }
         ExecuteMsg::BondGateway {
             gateway,
             owner_signature,
@@ -173,6 +183,14 @@ pub fn execute(
         ExecuteMsg::UnbondGateway {} => {
             crate::gateways::transactions::try_remove_gateway(deps, info)
         }
+        ExecuteMsg::UpdateGatewayConfig { new_config } => {
+            crate::gateways::transactions::try_update_gateway_config(deps, info, new_config)
+        }
+        ExecuteMsg::UpdateGatewayConfigOnBehalf { new_config, owner } => {
+            crate::gateways::transactions::try_update_gateway_config_on_behalf(
+                deps, info, new_config, owner,
+            )
+        }
         ExecuteMsg::UpdateContractStateParams(params) => {
             crate::mixnet_contract_settings::transactions::try_update_contract_settings(
                 deps, info, params,
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use cosmwasm_std::{Addr, StdError};
-use mixnet_contract_common::{error::MixnetContractError, IdentityKey};
+use mixnet_contract_common::{error::MixnetContractError, IdentityKey, SphinxKey};
 use thiserror::Error;
 
 /// Custom errors for contract failure conditions.
@@ -180,4 +180,15 @@ pub enum ContractError {
     },
     #[error("`mix_identity` is required when `delegator` is set")]
     MissingMixIdentity,
+    #[error("Sphinx key rotation has to be announced for a future epoch, got activation epoch {activation_epoch} while the current epoch is {current_epoch}")]
+    InvalidSphinxKeyRotationEpoch {
+        activation_epoch: u32,
+        current_epoch: u32,
+    },
+    #[error("The announced sphinx key is identical to the current sphinx key of the mixnode")]
+    UnchangedSphinxKey,
+    #[error("Sphinx key {sphinx_key} is already used by another mixnode")]
+    DuplicateSphinxKey { sphinx_key: SphinxKey },
+    #[error("The previous sphinx key rotation is still within its overlap window, which ends at epoch {overlap_end_epoch}")]
+    SphinxKeyRotationInProgress { overlap_end_epoch: u32 },
 }
@@ -8,8 +8,10 @@ use crate::support::helpers::{ensure_no_existing_bond, validate_node_identity_si
 use cosmwasm_std::{
     wasm_execute, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Uint128,
 };
-use mixnet_contract_common::events::{new_gateway_bonding_event, new_gateway_unbonding_event};
-use mixnet_contract_common::{Gateway, GatewayBond, Layer};
+use mixnet_contract_common::events::{
+    new_gateway_bonding_event, new_gateway_config_update_event, new_gateway_unbonding_event,
+};
+use mixnet_contract_common::{Gateway, GatewayBond, GatewayConfigUpdate, Layer};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 use vesting_contract_common::one_ucoin;
 
@@ -191,6 +193,80 @@ pub(crate) fn _try_remove_gateway(
     )))
 }
 
+pub fn try_update_gateway_config(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_config: GatewayConfigUpdate,
+) -> Result<Response, ContractError> {
+    _try_update_gateway_config(deps, info.sender.as_ref(), new_config, None)
+}
+
+pub fn try_update_gateway_config_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_config: GatewayConfigUpdate,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let proxy = info.sender;
+    _try_update_gateway_config(deps, &owner, new_config, Some(proxy))
+}
+
+pub(crate) fn _try_update_gateway_config(
+    deps: DepsMut<'_>,
+    owner: &str,
+    new_config: GatewayConfigUpdate,
+    proxy: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(owner)?;
+    let mix_denom = mixnet_params_storage::mix_denom(deps.storage)?;
+    // try to find the node of the sender
+    let mut gateway_bond = match storage::gateways()
+        .idx
+        .owner
+        .item(deps.storage, owner.clone())?
+    {
+        Some(record) => record.1,
+        None => return Err(ContractError::NoAssociatedGatewayBond { owner }),
+    };
+
+    if proxy != gateway_bond.proxy {
+        return Err(ContractError::ProxyMismatch {
+            existing: gateway_bond
+                .proxy
+                .map_or_else(|| "None".to_string(), |a| a.as_str().to_string()),
+            incoming: proxy.map_or_else(|| "None".to_string(), |a| a.as_str().to_string()),
+        });
+    }
+
+    // note that the bonding height is deliberately left untouched as the node has not been rebonded
+    gateway_bond.gateway.host = new_config.host.clone();
+    gateway_bond.gateway.mix_port = new_config.mix_port;
+    gateway_bond.gateway.clients_port = new_config.clients_port;
+    gateway_bond.gateway.http_api_port = new_config.http_api_port;
+    gateway_bond.gateway.location = new_config.location.clone();
+    gateway_bond.gateway.version = new_config.version.clone();
+
+    storage::gateways().save(deps.storage, gateway_bond.identity(), &gateway_bond)?;
+
+    let mut response = Response::new();
+
+    if let Some(proxy) = &proxy {
+        // return the one_ucoin the proxy had to send in order to execute the contract to contract transaction
+        let return_one_ucoin = BankMsg::Send {
+            to_address: proxy.as_str().to_string(),
+            amount: vec![one_ucoin(mix_denom)],
+        };
+        response = response.add_message(return_one_ucoin);
+    }
+
+    Ok(response.add_event(new_gateway_config_update_event(
+        &owner,
+        &proxy,
+        gateway_bond.identity(),
+        &new_config,
+    )))
+}
+
 fn validate_gateway_pledge(
     mut pledge: Vec<Coin>,
     minimum_pledge: Uint128,
@@ -544,6 +620,171 @@ pub mod tests {
         );
     }
 
+    fn gateway_config_update_fixture() -> GatewayConfigUpdate {
+        GatewayConfigUpdate {
+            host: "2.2.2.2".to_string(),
+            mix_port: 1790,
+            clients_port: 9001,
+            http_api_port: 8002,
+            location: "Norway".to_string(),
+            version: "1.0.2".to_string(),
+        }
+    }
+
+    #[test]
+    fn updating_gateway_config() {
+        let mut deps = test_helpers::init_contract();
+        let new_config = gateway_config_update_fixture();
+
+        // try to update the config when no node exists yet
+        let info = mock_info("gateway-owner", &[]);
+        let msg = ExecuteMsg::UpdateGatewayConfig {
+            new_config: new_config.clone(),
+        };
+        let result = execute(deps.as_mut(), mock_env(), info, msg);
+        assert_eq!(
+            result,
+            Err(ContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked("gateway-owner")
+            })
+        );
+
+        let env = mock_env();
+        let identity = test_helpers::add_gateway(
+            "gateway-owner",
+            tests::fixtures::good_gateway_pledge(),
+            deps.as_mut(),
+        );
+        let original_bond = tests::queries::get_gateways(&mut deps)[0].clone();
+
+        // somebody else cannot update the config of our node
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::UpdateGatewayConfig {
+            new_config: new_config.clone(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), info, msg),
+            Err(ContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked("alice")
+            })
+        );
+
+        // but the owner can
+        let info = mock_info("gateway-owner", &[]);
+        let msg = ExecuteMsg::UpdateGatewayConfig {
+            new_config: new_config.clone(),
+        };
+        let response = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            response,
+            Response::new().add_event(new_gateway_config_update_event(
+                &Addr::unchecked("gateway-owner"),
+                &None,
+                &identity,
+                &new_config,
+            ))
+        );
+
+        let gateways = tests::queries::get_gateways(&mut deps);
+        assert_eq!(1, gateways.len());
+        let updated_bond = &gateways[0];
+
+        assert_eq!(new_config.host, updated_bond.gateway.host);
+        assert_eq!(new_config.mix_port, updated_bond.gateway.mix_port);
+        assert_eq!(new_config.clients_port, updated_bond.gateway.clients_port);
+        assert_eq!(new_config.http_api_port, updated_bond.gateway.http_api_port);
+        assert_eq!(new_config.location, updated_bond.gateway.location);
+        assert_eq!(new_config.version, updated_bond.gateway.version);
+
+        // while everything else is left intact
+        assert_eq!(identity, updated_bond.gateway.identity_key);
+        assert_eq!(
+            original_bond.gateway.sphinx_key,
+            updated_bond.gateway.sphinx_key
+        );
+        assert_eq!(original_bond.pledge_amount, updated_bond.pledge_amount);
+        assert_eq!(original_bond.block_height, updated_bond.block_height);
+        assert_eq!(original_bond.owner, updated_bond.owner);
+    }
+
+    #[test]
+    fn updating_gateway_config_on_behalf() {
+        let mut deps = test_helpers::init_contract();
+        let new_config = gateway_config_update_fixture();
+
+        let keypair = crypto::asymmetric::identity::KeyPair::new(&mut rand::thread_rng());
+        let owner_signature = keypair
+            .private_key()
+            .sign("gateway-owner".as_bytes())
+            .to_base58_string();
+        let identity = keypair.public_key().to_base58_string();
+
+        let info = mock_info("vesting-contract", &tests::fixtures::good_gateway_pledge());
+        let msg = ExecuteMsg::BondGatewayOnBehalf {
+            gateway: Gateway {
+                identity_key: identity.clone(),
+                ..tests::fixtures::gateway_fixture()
+            },
+            owner: "gateway-owner".to_string(),
+            owner_signature,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the owner cannot update the config directly as the node was bonded with a proxy
+        let info = mock_info("gateway-owner", &[]);
+        let msg = ExecuteMsg::UpdateGatewayConfig {
+            new_config: new_config.clone(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::ProxyMismatch {
+                existing: "vesting-contract".to_string(),
+                incoming: "None".to_string(),
+            })
+        );
+
+        // and neither can a different proxy
+        let info = mock_info("other-proxy", &[]);
+        let msg = ExecuteMsg::UpdateGatewayConfigOnBehalf {
+            new_config: new_config.clone(),
+            owner: "gateway-owner".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::ProxyMismatch {
+                existing: "vesting-contract".to_string(),
+                incoming: "other-proxy".to_string(),
+            })
+        );
+
+        // while the original proxy gets its coin back
+        let info = mock_info(
+            "vesting-contract",
+            &[one_ucoin(TEST_COIN_DENOM.to_string())],
+        );
+        let msg = ExecuteMsg::UpdateGatewayConfigOnBehalf {
+            new_config: new_config.clone(),
+            owner: "gateway-owner".to_string(),
+        };
+        let response = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let expected_response = Response::new()
+            .add_message(BankMsg::Send {
+                to_address: "vesting-contract".to_string(),
+                amount: vec![one_ucoin(TEST_COIN_DENOM.to_string())],
+            })
+            .add_event(new_gateway_config_update_event(
+                &Addr::unchecked("gateway-owner"),
+                &Some(Addr::unchecked("vesting-contract")),
+                &identity,
+                &new_config,
+            ));
+        assert_eq!(expected_response, response);
+
+        let gateways = tests::queries::get_gateways(&mut deps);
+        assert_eq!(new_config.host, gateways[0].gateway.host);
+        assert_eq!(Some(Addr::unchecked("vesting-contract")), gateways[0].proxy);
+    }
+
     #[test]
     fn validating_gateway_bond() {
         // you must send SOME funds
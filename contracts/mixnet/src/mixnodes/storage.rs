@@ -6,7 +6,7 @@ use cw_storage_plus::{Index, IndexList, IndexedSnapshotMap, Map, Strategy, Uniqu
 use mixnet_contract_common::{
     reward_params::NodeEpochRewards, Addr, Coin, IdentityKeyRef, Layer, MixNode, MixNodeBond,
 };
-use mixnet_contract_common::{SphinxKey, U128};
+use mixnet_contract_common::{SphinxKey, SphinxKeyRotation, U128};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
@@ -76,6 +76,8 @@ pub struct StoredMixnodeBond {
     pub proxy: Option<Addr>,
     pub accumulated_rewards: Option<Uint128>,
     pub epoch_rewards: Option<NodeEpochRewards>,
+    #[serde(default)]
+    pub sphinx_key_rotation: Option<SphinxKeyRotation>,
 }
 
 impl From<MixNodeBond> for StoredMixnodeBond {
@@ -89,6 +91,7 @@ impl From<MixNodeBond> for StoredMixnodeBond {
             proxy: mixnode_bond.proxy,
             accumulated_rewards: mixnode_bond.accumulated_rewards,
             epoch_rewards: None,
+            sphinx_key_rotation: mixnode_bond.sphinx_key_rotation,
         }
     }
 }
@@ -114,6 +117,7 @@ impl StoredMixnodeBond {
             proxy,
             accumulated_rewards,
             epoch_rewards,
+            sphinx_key_rotation: None,
         }
     }
 
@@ -134,6 +138,7 @@ impl StoredMixnodeBond {
             mix_node: self.mix_node,
             proxy: self.proxy,
             accumulated_rewards: self.accumulated_rewards,
+            sphinx_key_rotation: self.sphinx_key_rotation,
         }
     }
 
@@ -181,6 +186,7 @@ pub(crate) fn read_full_mixnode_bond(
                 mix_node: stored_bond.mix_node,
                 proxy: stored_bond.proxy,
                 accumulated_rewards: stored_bond.accumulated_rewards,
+                sphinx_key_rotation: stored_bond.sphinx_key_rotation,
             }))
         }
     }
@@ -236,6 +242,7 @@ mod tests {
             proxy: None,
             accumulated_rewards: None,
             epoch_rewards: None,
+            sphinx_key_rotation: None,
         };
 
         storage::mixnodes()
@@ -3,6 +3,7 @@
 
 use super::storage::{self, LAST_PM_UPDATE_TIME};
 use crate::error::ContractError;
+use crate::interval::storage::current_epoch;
 use crate::mixnet_contract_settings::storage::{self as mixnet_params_storage, mix_denom};
 use crate::mixnodes::layer_queries::query_layer_distribution;
 use crate::mixnodes::storage::StoredMixnodeBond;
@@ -12,8 +13,9 @@ use cosmwasm_std::{
 };
 use mixnet_contract_common::events::{
     new_checkpoint_mixnodes_event, new_mixnode_bonding_event, new_mixnode_unbonding_event,
+    new_sphinx_key_rotation_event,
 };
-use mixnet_contract_common::MixNode;
+use mixnet_contract_common::{MixNode, SphinxKeyRotation};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 use vesting_contract_common::one_ucoin;
 
@@ -353,6 +355,113 @@ pub(crate) fn _try_update_mixnode_config(
     Ok(response)
 }
 
+pub(crate) fn try_announce_sphinx_key_rotation(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    rotation: SphinxKeyRotation,
+) -> Result<Response, ContractError> {
+    _try_announce_sphinx_key_rotation(deps, env, rotation, info.sender, None)
+}
+
+pub(crate) fn try_announce_sphinx_key_rotation_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    rotation: SphinxKeyRotation,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_announce_sphinx_key_rotation(deps, env, rotation, owner, Some(proxy))
+}
+
+pub(crate) fn _try_announce_sphinx_key_rotation(
+    deps: DepsMut<'_>,
+    env: Env,
+    rotation: SphinxKeyRotation,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let mut mixnode_bond = storage::mixnodes()
+        .idx
+        .owner
+        .item(deps.storage, owner.clone())?
+        .ok_or(ContractError::NoAssociatedMixNodeBond {
+            owner: owner.clone(),
+        })?
+        .1;
+
+    if proxy != mixnode_bond.proxy {
+        return Err(ContractError::ProxyMismatch {
+            existing: mixnode_bond
+                .proxy
+                .map_or_else(|| "None".to_string(), |a| a.as_str().to_string()),
+            incoming: proxy.map_or_else(|| "None".to_string(), |a| a.as_str().to_string()),
+        });
+    }
+
+    // clients need to learn about the new key before they can start using it
+    let current_epoch_id = current_epoch(deps.storage)?.id();
+    if rotation.activation_epoch <= current_epoch_id {
+        return Err(ContractError::InvalidSphinxKeyRotationEpoch {
+            activation_epoch: rotation.activation_epoch,
+            current_epoch: current_epoch_id,
+        });
+    }
+
+    // the node can only hold two keys at once, so the previous rotation has to fully complete first.
+    // Rotations that haven't been activated yet are simply replaced.
+    if let Some(previous_rotation) = mixnode_bond.sphinx_key_rotation.take() {
+        if previous_rotation.is_active(current_epoch_id) {
+            if current_epoch_id < previous_rotation.overlap_end_epoch() {
+                return Err(ContractError::SphinxKeyRotationInProgress {
+                    overlap_end_epoch: previous_rotation.overlap_end_epoch(),
+                });
+            }
+            mixnode_bond.mix_node.sphinx_key = previous_rotation.next_sphinx_key;
+        }
+    }
+
+    if rotation.next_sphinx_key == mixnode_bond.mix_node.sphinx_key {
+        return Err(ContractError::UnchangedSphinxKey);
+    }
+
+    if storage::mixnodes()
+        .idx
+        .sphinx_key
+        .item(deps.storage, rotation.next_sphinx_key.clone())?
+        .is_some()
+    {
+        return Err(ContractError::DuplicateSphinxKey {
+            sphinx_key: rotation.next_sphinx_key,
+        });
+    }
+
+    let event = new_sphinx_key_rotation_event(&owner, &proxy, mixnode_bond.identity(), &rotation);
+
+    mixnode_bond.sphinx_key_rotation = Some(rotation);
+    storage::mixnodes().save(
+        deps.storage,
+        mixnode_bond.identity(),
+        &mixnode_bond,
+        env.block.height,
+    )?;
+
+    let mut response = Response::new();
+
+    if let Some(proxy) = proxy {
+        // return the one_ucoin the proxy had to send in order to execute the contract to contract transaction
+        let return_one_ucoin = BankMsg::Send {
+            to_address: proxy.as_str().to_string(),
+            amount: vec![one_ucoin(mix_denom(deps.storage)?)],
+        };
+        response = response.add_message(return_one_ucoin);
+    }
+
+    Ok(response.add_event(event))
+}
+
 fn validate_mixnode_pledge(
     mut pledge: Vec<Coin>,
     minimum_pledge: Uint128,
@@ -397,6 +506,7 @@ pub mod tests {
     use cosmwasm_std::{from_binary, Addr, Uint128};
     use mixnet_contract_common::{
         ExecuteMsg, Layer, LayerDistribution, MixNode, PagedMixnodeResponse, QueryMsg,
+        SPHINX_KEY_ROTATION_OVERLAP_EPOCHS,
     };
     use rand::thread_rng;
 
@@ -990,4 +1100,215 @@ pub mod tests {
         // succeds after some time
         assert!(try_update_mixnode_config(deps.as_mut(), env, info_alice, 20).is_ok());
     }
+
+    fn sphinx_key_rotation(activation_epoch: u32) -> SphinxKeyRotation {
+        SphinxKeyRotation {
+            next_sphinx_key: crypto::asymmetric::encryption::KeyPair::new(&mut thread_rng())
+                .public_key()
+                .to_base58_string(),
+            activation_epoch,
+        }
+    }
+
+    #[test]
+    fn announcing_sphinx_key_rotation() {
+        let sender = "bob";
+        let mut env = mock_env();
+        let mut deps = test_helpers::init_contract();
+        let info = mock_info(sender, &[]);
+
+        // there's no mixnode to rotate the key of
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: sphinx_key_rotation(1),
+        };
+        let ret = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(
+            ret,
+            Err(ContractError::NoAssociatedMixNodeBond {
+                owner: Addr::unchecked(sender)
+            })
+        );
+
+        let identity = test_helpers::add_mixnode(
+            sender,
+            tests::fixtures::good_mixnode_pledge(),
+            deps.as_mut(),
+        );
+        let original_key = storage::mixnodes()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap()
+            .mix_node
+            .sphinx_key;
+
+        // the rotation can't be activated in the current epoch
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: sphinx_key_rotation(0),
+        };
+        let ret = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(
+            ret,
+            Err(ContractError::InvalidSphinxKeyRotationEpoch {
+                activation_epoch: 0,
+                current_epoch: 0
+            })
+        );
+
+        // nor can it use the same key
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: SphinxKeyRotation {
+                next_sphinx_key: original_key.clone(),
+                activation_epoch: 1,
+            },
+        };
+        let ret = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(ret, Err(ContractError::UnchangedSphinxKey));
+
+        // or the key of another node
+        let other_identity = test_helpers::add_mixnode(
+            "alice",
+            tests::fixtures::good_mixnode_pledge(),
+            deps.as_mut(),
+        );
+        let other_key = storage::mixnodes()
+            .load(deps.as_ref().storage, &other_identity)
+            .unwrap()
+            .mix_node
+            .sphinx_key;
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: SphinxKeyRotation {
+                next_sphinx_key: other_key.clone(),
+                activation_epoch: 1,
+            },
+        };
+        let ret = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(
+            ret,
+            Err(ContractError::DuplicateSphinxKey {
+                sphinx_key: other_key
+            })
+        );
+
+        let rotation = sphinx_key_rotation(2);
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: rotation.clone(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // rotation that hasn't been activated yet can be replaced
+        let rotation = sphinx_key_rotation(1);
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: rotation.clone(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let bond = storage::read_full_mixnode_bond(deps.as_ref().storage, &identity)
+            .unwrap()
+            .unwrap();
+        assert_eq!(bond.mix_node.sphinx_key, original_key);
+        assert_eq!(bond.sphinx_key_rotation, Some(rotation.clone()));
+        assert_eq!(bond.sphinx_key_at_epoch(0), &original_key);
+        assert_eq!(bond.sphinx_key_at_epoch(1), &rotation.next_sphinx_key);
+
+        // once activated, another rotation can't be announced until the overlap window ends
+        for _ in 0..SPHINX_KEY_ROTATION_OVERLAP_EPOCHS {
+            test_helpers::update_env_and_progress_interval(&mut env, deps.as_mut().storage);
+            let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+                rotation: sphinx_key_rotation(rotation.overlap_end_epoch() + 1),
+            };
+            let ret = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+            assert_eq!(
+                ret,
+                Err(ContractError::SphinxKeyRotationInProgress {
+                    overlap_end_epoch: rotation.overlap_end_epoch()
+                })
+            );
+        }
+
+        // and afterwards the previously announced key becomes the current one
+        test_helpers::update_env_and_progress_interval(&mut env, deps.as_mut().storage);
+        let next_rotation = sphinx_key_rotation(rotation.overlap_end_epoch() + 1);
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: next_rotation.clone(),
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let bond = storage::mixnodes()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.mix_node.sphinx_key, rotation.next_sphinx_key);
+        assert_eq!(bond.sphinx_key_rotation, Some(next_rotation));
+
+        // and the sphinx key index follows the change
+        let (indexed_identity, _) = storage::mixnodes()
+            .idx
+            .sphinx_key
+            .item(deps.as_ref().storage, rotation.next_sphinx_key)
+            .unwrap()
+            .unwrap();
+        assert_eq!(indexed_identity, identity.as_bytes());
+        assert!(storage::mixnodes()
+            .idx
+            .sphinx_key
+            .item(deps.as_ref().storage, original_key)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn announcing_sphinx_key_rotation_on_behalf() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let owner = "alice";
+        let proxy = "vesting-contract";
+
+        let (msg, (identity, _)) = tests::messages::valid_bond_mixnode_msg(owner);
+        let msg = match msg {
+            ExecuteMsg::BondMixnode {
+                mix_node,
+                owner_signature,
+            } => ExecuteMsg::BondMixnodeOnBehalf {
+                mix_node,
+                owner: owner.to_string(),
+                owner_signature,
+            },
+            _ => unreachable!(),
+        };
+        let info = mock_info(proxy, &tests::fixtures::good_mixnode_pledge());
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let rotation = sphinx_key_rotation(1);
+
+        // the owner can't bypass the proxy
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotation {
+            rotation: rotation.clone(),
+        };
+        let ret = execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg);
+        assert_eq!(
+            ret,
+            Err(ContractError::ProxyMismatch {
+                existing: proxy.to_string(),
+                incoming: "None".to_string(),
+            })
+        );
+
+        let msg = ExecuteMsg::AnnounceSphinxKeyRotationOnBehalf {
+            rotation: rotation.clone(),
+            owner: owner.to_string(),
+        };
+        let info = mock_info(proxy, &coins(1, TEST_COIN_DENOM));
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: proxy.to_string(),
+                amount: coins(1, TEST_COIN_DENOM),
+            }
+            .into()
+        );
+
+        let bond = storage::mixnodes()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.sphinx_key_rotation, Some(rotation));
+    }
 }
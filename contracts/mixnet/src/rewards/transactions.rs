@@ -949,6 +949,7 @@ pub mod tests {
             proxy: None,
             accumulated_rewards: None,
             epoch_rewards: None,
+            sphinx_key_rotation: None,
         };
 
         mixnodes_storage::mixnodes()
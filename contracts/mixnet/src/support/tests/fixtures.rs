@@ -26,6 +26,7 @@ pub fn gateway_fixture() -> Gateway {
         host: "1.1.1.1".to_string(),
         mix_port: 1789,
         clients_port: 9000,
+        http_api_port: 8001,
         location: "Sweden".to_string(),
         sphinx_key: "sphinx".to_string(),
         identity_key: "identity".to_string(),
@@ -0,0 +1,122 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::{
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+
+use service_provider_directory_common::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+use crate::error::ContractError;
+use crate::queries::{query_service, query_services_by_owner, query_services_paged};
+use crate::state::{Config, CONFIG};
+use crate::transactions;
+
+/// Instantiate the contract.
+///
+/// `deps` contains Storage, API and Querier
+/// `msg` is the contract initialization message, sort of like a constructor call.
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut<'_>,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let cfg = Config {
+        mix_denom: msg.mix_denom,
+        minimum_pledge: msg.minimum_pledge,
+    };
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::default())
+}
+
+/// Handle an incoming message
+#[entry_point]
+pub fn execute(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Announce {
+            client_address,
+            gateway,
+            service_type,
+            description,
+            owner_signature,
+        } => transactions::try_announce(
+            deps,
+            env,
+            info,
+            client_address,
+            gateway,
+            service_type,
+            description,
+            owner_signature,
+        ),
+        ExecuteMsg::Update {
+            service_id,
+            client_address,
+            gateway,
+            description,
+            owner_signature,
+        } => transactions::try_update(
+            deps,
+            info,
+            service_id,
+            client_address,
+            gateway,
+            description,
+            owner_signature,
+        ),
+        ExecuteMsg::Delete { service_id } => transactions::try_delete(deps, info, service_id),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps<'_>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetService { service_id } => to_binary(&query_service(deps, service_id)?),
+        QueryMsg::GetServices { limit, start_after } => {
+            to_binary(&query_services_paged(deps, start_after, limit)?)
+        }
+        QueryMsg::GetServicesByOwner { owner } => to_binary(&query_services_by_owner(deps, owner)?),
+    }
+}
+
+#[entry_point]
+pub fn migrate(_deps: DepsMut<'_>, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Default::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::support::tests::fixtures::{TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    #[test]
+    fn initialize_contract() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            mix_denom: TEST_MIX_DENOM.to_string(),
+            minimum_pledge: TEST_MINIMUM_PLEDGE.into(),
+        };
+        let info = mock_info("creator", &[]);
+
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(TEST_MIX_DENOM, config.mix_denom);
+        assert_eq!(TEST_MINIMUM_PLEDGE, config.minimum_pledge.u128());
+        assert!(query_services_paged(deps.as_ref(), None, None)
+            .unwrap()
+            .services
+            .is_empty());
+    }
+}
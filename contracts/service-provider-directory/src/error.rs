@@ -0,0 +1,64 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::{Addr, Coin, StdError};
+use service_provider_directory_common::service::ServiceId;
+use thiserror::Error;
+
+/// Custom errors for contract failure conditions.
+///
+/// Add any other custom errors you like here.
+/// Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Received multiple coin types")]
+    MultipleDenoms,
+
+    #[error("Wrong coin denomination, you must send {mix_denom}")]
+    WrongDenom { mix_denom: String },
+
+    #[error("At least {minimum} has to be pledged for the service")]
+    InsufficientPledge { minimum: Coin },
+
+    #[error("The client identity key of the service is malformed - {0}")]
+    MalformedEd25519IdentityKey(String),
+
+    #[error("The owner signature is malformed - {0}")]
+    MalformedEd25519Signature(String),
+
+    #[error("The owner signature was not made by the client identity key of the service")]
+    InvalidEd25519Signature,
+
+    #[error("A new owner signature is required when changing the client address")]
+    MissingOwnerSignature,
+
+    #[error("No funds are expected to be sent alongside this message")]
+    UnexpectedFunds,
+
+    #[error("The {field} of the service must not be empty")]
+    EmptyField { field: &'static str },
+
+    #[error("The description of the service is {length} characters long, while at most {max} are allowed")]
+    DescriptionTooLong { length: usize, max: usize },
+
+    #[error("The client address {client_address} does not belong to gateway {gateway}")]
+    GatewayMismatch {
+        client_address: String,
+        gateway: String,
+    },
+
+    #[error("A service with client address {client_address} has already been announced")]
+    DuplicateClientAddress { client_address: String },
+
+    #[error("{owner} has already announced the maximum of {max} services")]
+    TooManyServices { owner: Addr, max: usize },
+
+    #[error("Service {service_id} does not exist")]
+    ServiceNotFound { service_id: ServiceId },
+
+    #[error("Only the owner of service {service_id} is allowed to change it")]
+    Unauthorized { service_id: ServiceId },
+}
@@ -0,0 +1,10 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod contract;
+pub mod error;
+mod queries;
+mod state;
+mod storage;
+mod support;
+mod transactions;
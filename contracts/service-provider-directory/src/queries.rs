@@ -0,0 +1,136 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+use service_provider_directory_common::service::{
+    OwnedServicesResponse, PagedServicesResponse, ServiceId, ServiceInfo, ServiceResponse,
+};
+
+use crate::storage::{self, SERVICES_PAGE_DEFAULT_LIMIT, SERVICES_PAGE_MAX_LIMIT};
+
+pub(crate) fn query_service(deps: Deps<'_>, service_id: ServiceId) -> StdResult<ServiceResponse> {
+    let service = storage::services()
+        .may_load(deps.storage, service_id)?
+        .map(|service| ServiceInfo::new(service_id, service));
+    Ok(ServiceResponse::new(service))
+}
+
+pub(crate) fn query_services_paged(
+    deps: Deps<'_>,
+    start_after: Option<ServiceId>,
+    limit: Option<u32>,
+) -> StdResult<PagedServicesResponse> {
+    let limit = limit
+        .unwrap_or(SERVICES_PAGE_DEFAULT_LIMIT)
+        .min(SERVICES_PAGE_MAX_LIMIT) as usize;
+
+    let start = start_after.map(Bound::exclusive);
+
+    let services = storage::services()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(service_id, service)| ServiceInfo::new(service_id, service)))
+        .collect::<StdResult<Vec<ServiceInfo>>>()?;
+
+    let start_next_after = services.last().map(|info| info.service_id);
+
+    Ok(PagedServicesResponse::new(
+        services,
+        limit,
+        start_next_after,
+    ))
+}
+
+// the number of services of a single owner is bounded, so there's no need for paging here
+pub(crate) fn query_services_by_owner(
+    deps: Deps<'_>,
+    owner: String,
+) -> StdResult<OwnedServicesResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+
+    let services = storage::services()
+        .idx
+        .owner
+        .prefix(owner.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|(service_id, service)| ServiceInfo::new(service_id, service)))
+        .collect::<StdResult<Vec<ServiceInfo>>>()?;
+
+    Ok(OwnedServicesResponse::new(owner, services))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::tests::fixtures::client_address_fixture;
+    use crate::support::tests::helpers::{announce_service, init_contract};
+
+    #[test]
+    fn services_are_returned_in_pages() {
+        let mut deps = init_contract();
+        for i in 0..5 {
+            announce_service(&mut deps, "owner", i).unwrap();
+        }
+
+        let page1 = query_services_paged(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(vec![1, 2], service_ids(&page1));
+        assert_eq!(Some(2), page1.start_next_after);
+
+        let page2 = query_services_paged(deps.as_ref(), page1.start_next_after, Some(2)).unwrap();
+        assert_eq!(vec![3, 4], service_ids(&page2));
+
+        let page3 = query_services_paged(deps.as_ref(), page2.start_next_after, Some(2)).unwrap();
+        assert_eq!(vec![5], service_ids(&page3));
+
+        let page4 = query_services_paged(deps.as_ref(), page3.start_next_after, Some(2)).unwrap();
+        assert!(page4.services.is_empty());
+        assert!(page4.start_next_after.is_none());
+    }
+
+    #[test]
+    fn page_size_is_capped() {
+        let deps = init_contract();
+        let page = query_services_paged(deps.as_ref(), None, Some(1000)).unwrap();
+        assert_eq!(SERVICES_PAGE_MAX_LIMIT as usize, page.per_page);
+    }
+
+    #[test]
+    fn services_can_be_queried_by_owner() {
+        let mut deps = init_contract();
+        announce_service(&mut deps, "alice", 1).unwrap();
+        announce_service(&mut deps, "bob", 2).unwrap();
+        announce_service(&mut deps, "alice", 3).unwrap();
+
+        let alice = query_services_by_owner(deps.as_ref(), "alice".to_string()).unwrap();
+        assert_eq!(
+            vec![1, 3],
+            alice
+                .services
+                .iter()
+                .map(|info| info.service_id)
+                .collect::<Vec<_>>()
+        );
+
+        let carol = query_services_by_owner(deps.as_ref(), "carol".to_string()).unwrap();
+        assert!(carol.services.is_empty());
+    }
+
+    #[test]
+    fn single_service_can_be_queried() {
+        let mut deps = init_contract();
+        announce_service(&mut deps, "owner", 1).unwrap();
+
+        let service = query_service(deps.as_ref(), 1).unwrap().service.unwrap();
+        assert_eq!(client_address_fixture(1), service.service.client_address);
+        assert!(query_service(deps.as_ref(), 2).unwrap().service.is_none());
+    }
+
+    fn service_ids(response: &PagedServicesResponse) -> Vec<ServiceId> {
+        response
+            .services
+            .iter()
+            .map(|info| info.service_id)
+            .collect()
+    }
+}
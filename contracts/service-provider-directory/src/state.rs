@@ -0,0 +1,19 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::Uint128;
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use service_provider_directory_common::service::ServiceId;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub mix_denom: String,
+    pub minimum_pledge: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// ids are never reused, even after the service got deleted
+pub(crate) const SERVICE_ID_COUNTER: Item<ServiceId> = Item::new("service_id_counter");
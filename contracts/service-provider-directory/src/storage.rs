@@ -0,0 +1,65 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::state::SERVICE_ID_COUNTER;
+use cosmwasm_std::{Addr, StdResult, Storage};
+use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex, UniqueIndex};
+use service_provider_directory_common::service::{Service, ServiceId};
+
+// storage prefixes
+const SERVICES_PK_NAMESPACE: &str = "srv";
+const SERVICES_OWNER_IDX_NAMESPACE: &str = "sro";
+const SERVICES_CLIENT_ADDRESS_IDX_NAMESPACE: &str = "src";
+
+// paged retrieval limits for all queries and transactions
+pub(crate) const SERVICES_PAGE_MAX_LIMIT: u32 = 150;
+pub(crate) const SERVICES_PAGE_DEFAULT_LIMIT: u32 = 100;
+
+pub(crate) struct ServiceIndex<'a> {
+    pub(crate) owner: MultiIndex<'a, Addr, Service, ServiceId>,
+
+    pub(crate) client_address: UniqueIndex<'a, String, Service>,
+}
+
+impl<'a> IndexList<Service> for ServiceIndex<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Service>> + '_> {
+        let v: Vec<&dyn Index<Service>> = vec![&self.owner, &self.client_address];
+        Box::new(v.into_iter())
+    }
+}
+
+pub(crate) fn services<'a>() -> IndexedMap<'a, ServiceId, Service, ServiceIndex<'a>> {
+    let indexes = ServiceIndex {
+        owner: MultiIndex::new(
+            |d| d.owner.clone(),
+            SERVICES_PK_NAMESPACE,
+            SERVICES_OWNER_IDX_NAMESPACE,
+        ),
+        client_address: UniqueIndex::new(
+            |d| d.client_address.clone(),
+            SERVICES_CLIENT_ADDRESS_IDX_NAMESPACE,
+        ),
+    };
+
+    IndexedMap::new(SERVICES_PK_NAMESPACE, indexes)
+}
+
+pub(crate) fn next_service_id(store: &mut dyn Storage) -> StdResult<ServiceId> {
+    let id: ServiceId = SERVICE_ID_COUNTER.may_load(store)?.unwrap_or_default() + 1;
+    SERVICE_ID_COUNTER.save(store, &id)?;
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn service_ids_are_increasing() {
+        let mut storage = MockStorage::new();
+        assert_eq!(1, next_service_id(&mut storage).unwrap());
+        assert_eq!(2, next_service_id(&mut storage).unwrap());
+        assert_eq!(3, next_service_id(&mut storage).unwrap());
+    }
+}
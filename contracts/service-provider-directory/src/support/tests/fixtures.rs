@@ -0,0 +1,45 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::{coins, Coin};
+use crypto::asymmetric::identity;
+use rand_chacha::rand_core::SeedableRng;
+
+pub const TEST_MIX_DENOM: &str = "unym";
+pub const TEST_MINIMUM_PLEDGE: u128 = 100;
+
+// the contract only checks whether the client address belongs to the gateway,
+// so the gateway key itself does not have to be valid
+pub const TEST_GATEWAY: &str = "gateway-identity";
+
+pub fn minimum_pledge_fixture() -> Vec<Coin> {
+    coins(TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM)
+}
+
+pub fn client_identity_fixture(index: u8) -> identity::KeyPair {
+    // since those tests are NOT compiled to wasm, we can use rng-related dependency
+    let mut rng = rand_chacha::ChaCha20Rng::from_seed([index; 32]);
+    identity::KeyPair::new(&mut rng)
+}
+
+// only the identity key of the client is checked, so the encryption key does not have to be valid
+pub fn client_address_on_gateway(index: u8, gateway: &str) -> String {
+    format!(
+        "{}.client-encryption@{}",
+        client_identity_fixture(index)
+            .public_key()
+            .to_base58_string(),
+        gateway
+    )
+}
+
+pub fn client_address_fixture(index: u8) -> String {
+    client_address_on_gateway(index, TEST_GATEWAY)
+}
+
+pub fn owner_signature_fixture(index: u8, owner: &str) -> String {
+    client_identity_fixture(index)
+        .private_key()
+        .sign(owner.as_bytes())
+        .to_base58_string()
+}
@@ -0,0 +1,54 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::contract::instantiate;
+use crate::error::ContractError;
+use crate::support::tests::fixtures::{
+    client_address_fixture, minimum_pledge_fixture, owner_signature_fixture, TEST_GATEWAY,
+    TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM,
+};
+use crate::transactions::try_announce;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{Coin, Empty, MemoryStorage, OwnedDeps, Response};
+use service_provider_directory_common::msg::InstantiateMsg;
+use service_provider_directory_common::service::ServiceType;
+
+pub fn init_contract() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>> {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        mix_denom: TEST_MIX_DENOM.to_string(),
+        minimum_pledge: TEST_MINIMUM_PLEDGE.into(),
+    };
+    let env = mock_env();
+    let info = mock_info("creator", &[]);
+    instantiate(deps.as_mut(), env, info, msg).unwrap();
+    deps
+}
+
+/// Announces the service of the client with the provided fixture index, properly signed
+/// by its identity key.
+pub fn announce_service_with_pledge(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>>,
+    owner: &str,
+    client_index: u8,
+    pledge: &[Coin],
+) -> Result<Response, ContractError> {
+    try_announce(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(owner, pledge),
+        client_address_fixture(client_index),
+        TEST_GATEWAY.to_string(),
+        ServiceType::NetworkRequester,
+        "test service".to_string(),
+        owner_signature_fixture(client_index, owner),
+    )
+}
+
+pub fn announce_service(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>>,
+    owner: &str,
+    client_index: u8,
+) -> Result<Response, ContractError> {
+    announce_service_with_pledge(deps, owner, client_index, &minimum_pledge_fixture())
+}
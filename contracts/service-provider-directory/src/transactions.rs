@@ -0,0 +1,631 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::ContractError;
+use crate::state::CONFIG;
+use crate::storage::{self, services};
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Order, Response, Storage,
+};
+use service_provider_directory_common::service::{Service, ServiceId, ServiceType};
+
+// limits put in place so that the directory could not be easily spammed
+pub(crate) const MAX_DESCRIPTION_LENGTH: usize = 500;
+pub(crate) const MAX_SERVICES_PER_OWNER: usize = 10;
+
+fn validate_pledge(storage: &dyn Storage, funds: Vec<Coin>) -> Result<Option<Coin>, ContractError> {
+    if funds.len() > 1 {
+        return Err(ContractError::MultipleDenoms);
+    }
+
+    let config = CONFIG.load(storage)?;
+    let insufficient_pledge = || ContractError::InsufficientPledge {
+        minimum: Coin::new(config.minimum_pledge.u128(), &config.mix_denom),
+    };
+    let pledge = match funds.into_iter().next() {
+        Some(pledge) => pledge,
+        None if config.minimum_pledge.is_zero() => return Ok(None),
+        None => return Err(insufficient_pledge()),
+    };
+
+    if pledge.denom != config.mix_denom {
+        return Err(ContractError::WrongDenom {
+            mix_denom: config.mix_denom,
+        });
+    }
+    if pledge.amount < config.minimum_pledge {
+        return Err(insufficient_pledge());
+    }
+    Ok(Some(pledge))
+}
+
+/// Makes sure the owner signature was made by the identity key of the client, i.e. the owner
+/// actually runs it, so that nobody could announce addresses of clients they don't control.
+fn validate_owner_signature(
+    deps: Deps<'_>,
+    owner: &Addr,
+    signature: String,
+    client_address: &str,
+) -> Result<(), ContractError> {
+    // nym client addresses have the form of `<identity>.<encryption>@<gateway identity>`
+    let identity = client_address
+        .split_once('.')
+        .map(|(identity, _)| identity)
+        .ok_or_else(|| {
+            ContractError::MalformedEd25519IdentityKey("missing from the client address".into())
+        })?;
+
+    let mut identity_bytes = [0u8; 32];
+    let mut signature_bytes = [0u8; 64];
+
+    let identity_used_bytes = bs58::decode(identity)
+        .into(&mut identity_bytes)
+        .map_err(|err| ContractError::MalformedEd25519IdentityKey(err.to_string()))?;
+    let signature_used_bytes = bs58::decode(signature)
+        .into(&mut signature_bytes)
+        .map_err(|err| ContractError::MalformedEd25519Signature(err.to_string()))?;
+
+    if identity_used_bytes != 32 {
+        return Err(ContractError::MalformedEd25519IdentityKey(
+            "Too few bytes provided".into(),
+        ));
+    }
+
+    if signature_used_bytes != 64 {
+        return Err(ContractError::MalformedEd25519Signature(
+            "Too few bytes provided".into(),
+        ));
+    }
+
+    let res = deps
+        .api
+        .ed25519_verify(owner.as_bytes(), &signature_bytes, &identity_bytes)
+        .map_err(cosmwasm_std::StdError::verification_err)?;
+    if !res {
+        Err(ContractError::InvalidEd25519Signature)
+    } else {
+        Ok(())
+    }
+}
+
+fn ensure_no_funds(info: &MessageInfo) -> Result<(), ContractError> {
+    if info.funds.is_empty() {
+        Ok(())
+    } else {
+        Err(ContractError::UnexpectedFunds)
+    }
+}
+
+fn validate_details(
+    client_address: &str,
+    gateway: &str,
+    description: &str,
+) -> Result<(), ContractError> {
+    if client_address.is_empty() {
+        return Err(ContractError::EmptyField {
+            field: "client address",
+        });
+    }
+    if gateway.is_empty() {
+        return Err(ContractError::EmptyField { field: "gateway" });
+    }
+
+    // nym client addresses have the form of `<identity>.<encryption>@<gateway identity>`
+    if client_address.rsplit_once('@').map(|(_, gateway)| gateway) != Some(gateway) {
+        return Err(ContractError::GatewayMismatch {
+            client_address: client_address.to_string(),
+            gateway: gateway.to_string(),
+        });
+    }
+
+    let length = description.chars().count();
+    if length > MAX_DESCRIPTION_LENGTH {
+        return Err(ContractError::DescriptionTooLong {
+            length,
+            max: MAX_DESCRIPTION_LENGTH,
+        });
+    }
+    Ok(())
+}
+
+fn ensure_unique_client_address(
+    storage: &dyn Storage,
+    client_address: &str,
+) -> Result<(), ContractError> {
+    if services()
+        .idx
+        .client_address
+        .item(storage, client_address.to_string())?
+        .is_some()
+    {
+        Err(ContractError::DuplicateClientAddress {
+            client_address: client_address.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn load_owned_service(
+    storage: &dyn Storage,
+    owner: &Addr,
+    service_id: ServiceId,
+) -> Result<Service, ContractError> {
+    let service = services()
+        .may_load(storage, service_id)?
+        .ok_or(ContractError::ServiceNotFound { service_id })?;
+    if service.owner != *owner {
+        return Err(ContractError::Unauthorized { service_id });
+    }
+    Ok(service)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_announce(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    client_address: String,
+    gateway: String,
+    service_type: ServiceType,
+    description: String,
+    owner_signature: String,
+) -> Result<Response, ContractError> {
+    validate_details(&client_address, &gateway, &description)?;
+    validate_owner_signature(
+        deps.as_ref(),
+        &info.sender,
+        owner_signature,
+        &client_address,
+    )?;
+    ensure_unique_client_address(deps.storage, &client_address)?;
+
+    let owned = services()
+        .idx
+        .owner
+        .prefix(info.sender.clone())
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .count();
+    if owned >= MAX_SERVICES_PER_OWNER {
+        return Err(ContractError::TooManyServices {
+            owner: info.sender,
+            max: MAX_SERVICES_PER_OWNER,
+        });
+    }
+
+    let pledge = validate_pledge(deps.storage, info.funds)?;
+
+    let service_id = storage::next_service_id(deps.storage)?;
+    let service = Service {
+        client_address,
+        gateway,
+        service_type,
+        description,
+        owner: info.sender,
+        pledge,
+        block_height: env.block.height,
+    };
+    services().save(deps.storage, service_id, &service)?;
+
+    Ok(Response::new().add_attribute("service_id", service_id.to_string()))
+}
+
+pub(crate) fn try_update(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    service_id: ServiceId,
+    client_address: Option<String>,
+    gateway: Option<String>,
+    description: Option<String>,
+    owner_signature: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_no_funds(&info)?;
+    let mut service = load_owned_service(deps.storage, &info.sender, service_id)?;
+
+    if let Some(client_address) = client_address {
+        if client_address != service.client_address {
+            let owner_signature = owner_signature.ok_or(ContractError::MissingOwnerSignature)?;
+            validate_owner_signature(
+                deps.as_ref(),
+                &info.sender,
+                owner_signature,
+                &client_address,
+            )?;
+            ensure_unique_client_address(deps.storage, &client_address)?;
+            service.client_address = client_address;
+        }
+    }
+    if let Some(gateway) = gateway {
+        service.gateway = gateway;
+    }
+    if let Some(description) = description {
+        service.description = description;
+    }
+    validate_details(
+        &service.client_address,
+        &service.gateway,
+        &service.description,
+    )?;
+
+    services().save(deps.storage, service_id, &service)?;
+
+    Ok(Response::new().add_attribute("service_id", service_id.to_string()))
+}
+
+pub(crate) fn try_delete(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    service_id: ServiceId,
+) -> Result<Response, ContractError> {
+    ensure_no_funds(&info)?;
+    let service = load_owned_service(deps.storage, &info.sender, service_id)?;
+    services().remove(deps.storage, service_id)?;
+
+    let mut response = Response::new().add_attribute("service_id", service_id.to_string());
+    if let Some(pledge) = service.pledge {
+        response = response.add_message(BankMsg::Send {
+            to_address: service.owner.into_string(),
+            amount: vec![pledge],
+        });
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::queries::query_service;
+    use crate::support::tests::fixtures::{
+        client_address_fixture, client_address_on_gateway, minimum_pledge_fixture,
+        owner_signature_fixture, TEST_GATEWAY, TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM,
+    };
+    use crate::support::tests::helpers::{
+        announce_service, announce_service_with_pledge, init_contract,
+    };
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, CosmosMsg};
+
+    fn announce(
+        client_address: String,
+        owner_signature: String,
+    ) -> Result<Response, ContractError> {
+        let mut deps = init_contract();
+        try_announce(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &minimum_pledge_fixture()),
+            client_address,
+            TEST_GATEWAY.to_string(),
+            ServiceType::NetworkRequester,
+            "test service".to_string(),
+            owner_signature,
+        )
+    }
+
+    #[test]
+    fn announcing_a_service() {
+        let mut deps = init_contract();
+        let env = mock_env();
+
+        let res = announce_service(&mut deps, "owner", 1).unwrap();
+        assert_eq!(
+            Some("1"),
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "service_id")
+                .map(|attr| attr.value.as_str())
+        );
+
+        let service = query_service(deps.as_ref(), 1)
+            .unwrap()
+            .service
+            .unwrap()
+            .service;
+        assert_eq!(client_address_fixture(1), service.client_address);
+        assert_eq!(TEST_GATEWAY, service.gateway);
+        assert_eq!(ServiceType::NetworkRequester, service.service_type);
+        assert_eq!(Addr::unchecked("owner"), service.owner);
+        assert_eq!(
+            Some(coin(TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM)),
+            service.pledge
+        );
+        assert_eq!(env.block.height, service.block_height);
+    }
+
+    #[test]
+    fn announcing_requires_signature_of_the_client_identity_key() {
+        // signed by a different key
+        assert_eq!(
+            Err(ContractError::InvalidEd25519Signature),
+            announce(
+                client_address_fixture(1),
+                owner_signature_fixture(2, "owner")
+            )
+        );
+
+        // signed for a different owner, i.e. the signature was taken from someone else's announcement
+        assert_eq!(
+            Err(ContractError::InvalidEd25519Signature),
+            announce(
+                client_address_fixture(1),
+                owner_signature_fixture(1, "someone-else")
+            )
+        );
+
+        assert!(matches!(
+            announce(client_address_fixture(1), "not-a-signature".to_string()),
+            Err(ContractError::MalformedEd25519Signature(_))
+        ));
+        assert!(matches!(
+            announce(
+                format!("client-identity.client-encryption@{}", TEST_GATEWAY),
+                owner_signature_fixture(1, "owner")
+            ),
+            Err(ContractError::MalformedEd25519IdentityKey(_))
+        ));
+        assert!(matches!(
+            announce(
+                format!("client-identity@{}", TEST_GATEWAY),
+                owner_signature_fixture(1, "owner")
+            ),
+            Err(ContractError::MalformedEd25519IdentityKey(_))
+        ));
+
+        assert!(announce(
+            client_address_fixture(1),
+            owner_signature_fixture(1, "owner")
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn announcing_with_pledge() {
+        let mut deps = init_contract();
+
+        let pledge = coins(1000, TEST_MIX_DENOM);
+        announce_service_with_pledge(&mut deps, "owner", 1, &pledge).unwrap();
+        let service = query_service(deps.as_ref(), 1)
+            .unwrap()
+            .service
+            .unwrap()
+            .service;
+        assert_eq!(Some(coin(1000, TEST_MIX_DENOM)), service.pledge);
+
+        assert_eq!(
+            Err(ContractError::WrongDenom {
+                mix_denom: TEST_MIX_DENOM.to_string()
+            }),
+            announce_service_with_pledge(&mut deps, "owner", 2, &coins(1000, "unyx"))
+        );
+        assert_eq!(
+            Err(ContractError::MultipleDenoms),
+            announce_service_with_pledge(
+                &mut deps,
+                "owner",
+                2,
+                &[coin(1000, TEST_MIX_DENOM), coin(1000, "unyx")]
+            )
+        );
+    }
+
+    #[test]
+    fn announcing_requires_minimum_pledge() {
+        let mut deps = init_contract();
+        let insufficient_pledge = Err(ContractError::InsufficientPledge {
+            minimum: coin(TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM),
+        });
+
+        assert_eq!(
+            insufficient_pledge,
+            announce_service_with_pledge(&mut deps, "owner", 1, &[])
+        );
+        assert_eq!(
+            insufficient_pledge,
+            announce_service_with_pledge(
+                &mut deps,
+                "owner",
+                1,
+                &coins(TEST_MINIMUM_PLEDGE - 1, TEST_MIX_DENOM)
+            )
+        );
+        assert!(announce_service_with_pledge(
+            &mut deps,
+            "owner",
+            1,
+            &coins(TEST_MINIMUM_PLEDGE, TEST_MIX_DENOM)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn announcing_invalid_details() {
+        assert_eq!(
+            Err(ContractError::EmptyField {
+                field: "client address"
+            }),
+            announce("".to_string(), owner_signature_fixture(1, "owner"))
+        );
+
+        let foreign_address = client_address_on_gateway(1, "other-gateway");
+        assert_eq!(
+            Err(ContractError::GatewayMismatch {
+                client_address: foreign_address.clone(),
+                gateway: TEST_GATEWAY.to_string(),
+            }),
+            announce(foreign_address, owner_signature_fixture(1, "owner"))
+        );
+
+        let mut deps = init_contract();
+        let res = try_announce(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &minimum_pledge_fixture()),
+            client_address_fixture(1),
+            TEST_GATEWAY.to_string(),
+            ServiceType::NetworkRequester,
+            "a".repeat(MAX_DESCRIPTION_LENGTH + 1),
+            owner_signature_fixture(1, "owner"),
+        );
+        assert_eq!(
+            Err(ContractError::DescriptionTooLong {
+                length: MAX_DESCRIPTION_LENGTH + 1,
+                max: MAX_DESCRIPTION_LENGTH,
+            }),
+            res
+        );
+    }
+
+    #[test]
+    fn client_address_can_only_be_announced_once() {
+        let mut deps = init_contract();
+
+        announce_service(&mut deps, "owner", 1).unwrap();
+        // even if the client key is also used for signing the announcement of someone else
+        assert_eq!(
+            Err(ContractError::DuplicateClientAddress {
+                client_address: client_address_fixture(1)
+            }),
+            announce_service(&mut deps, "someone-else", 1)
+        );
+    }
+
+    #[test]
+    fn number_of_services_per_owner_is_limited() {
+        let mut deps = init_contract();
+
+        for i in 0..MAX_SERVICES_PER_OWNER {
+            announce_service(&mut deps, "owner", i as u8).unwrap();
+        }
+        assert_eq!(
+            Err(ContractError::TooManyServices {
+                owner: Addr::unchecked("owner"),
+                max: MAX_SERVICES_PER_OWNER,
+            }),
+            announce_service(&mut deps, "owner", 100)
+        );
+
+        // which doesn't affect anyone else
+        assert!(announce_service(&mut deps, "other-owner", 100).is_ok());
+    }
+
+    #[test]
+    fn updating_a_service() {
+        let mut deps = init_contract();
+        announce_service(&mut deps, "owner", 1).unwrap();
+        announce_service(&mut deps, "owner", 2).unwrap();
+
+        let res = try_update(
+            deps.as_mut(),
+            mock_info("someone-else", &[]),
+            1,
+            None,
+            None,
+            Some("new description".to_string()),
+            None,
+        );
+        assert_eq!(Err(ContractError::Unauthorized { service_id: 1 }), res);
+
+        let res = try_update(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            Some(client_address_fixture(2)),
+            None,
+            None,
+            Some(owner_signature_fixture(2, "owner")),
+        );
+        assert_eq!(
+            Err(ContractError::DuplicateClientAddress {
+                client_address: client_address_fixture(2)
+            }),
+            res
+        );
+
+        // changing the gateway requires a matching client address
+        let res = try_update(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            None,
+            Some("new-gateway".to_string()),
+            None,
+            None,
+        );
+        assert!(matches!(res, Err(ContractError::GatewayMismatch { .. })));
+
+        // and the new client address has to be signed by its identity key
+        let new_address = client_address_on_gateway(3, "new-gateway");
+        let res = try_update(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            Some(new_address.clone()),
+            Some("new-gateway".to_string()),
+            None,
+            None,
+        );
+        assert_eq!(Err(ContractError::MissingOwnerSignature), res);
+
+        let res = try_update(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            Some(new_address.clone()),
+            Some("new-gateway".to_string()),
+            None,
+            Some(owner_signature_fixture(1, "owner")),
+        );
+        assert_eq!(Err(ContractError::InvalidEd25519Signature), res);
+
+        try_update(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            Some(new_address.clone()),
+            Some("new-gateway".to_string()),
+            Some("new description".to_string()),
+            Some(owner_signature_fixture(3, "owner")),
+        )
+        .unwrap();
+
+        let service = query_service(deps.as_ref(), 1)
+            .unwrap()
+            .service
+            .unwrap()
+            .service;
+        assert_eq!(new_address, service.client_address);
+        assert_eq!("new-gateway", service.gateway);
+        assert_eq!("new description", service.description);
+
+        // and the old address is free to be announced again
+        assert!(announce_service(&mut deps, "owner", 1).is_ok());
+    }
+
+    #[test]
+    fn deleting_a_service_returns_the_pledge() {
+        let mut deps = init_contract();
+        announce_service_with_pledge(&mut deps, "owner", 1, &coins(1000, TEST_MIX_DENOM)).unwrap();
+
+        assert_eq!(
+            Err(ContractError::Unauthorized { service_id: 1 }),
+            try_delete(deps.as_mut(), mock_info("someone-else", &[]), 1)
+        );
+        assert_eq!(
+            Err(ContractError::ServiceNotFound { service_id: 42 }),
+            try_delete(deps.as_mut(), mock_info("owner", &[]), 42)
+        );
+
+        let res = try_delete(deps.as_mut(), mock_info("owner", &[]), 1).unwrap();
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: coins(1000, TEST_MIX_DENOM),
+            })],
+            res.messages
+                .into_iter()
+                .map(|sub_msg| sub_msg.msg)
+                .collect::<Vec<_>>()
+        );
+        assert!(query_service(deps.as_ref(), 1).unwrap().service.is_none());
+    }
+}
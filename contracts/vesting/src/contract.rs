@@ -11,7 +11,9 @@ use cosmwasm_std::{
     coin, entry_point, to_binary, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, QueryResponse,
     Response, Timestamp, Uint128,
 };
-use mixnet_contract_common::{Gateway, IdentityKey, MixNode};
+use mixnet_contract_common::{
+    Gateway, GatewayConfigUpdate, IdentityKey, MixNode, SphinxKeyRotation,
+};
 use vesting_contract_common::events::{
     new_ownership_transfer_event, new_periodic_vesting_account_event,
     new_staking_address_update_event, new_track_gateway_unbond_event,
@@ -69,6 +71,9 @@ pub fn execute(
         ExecuteMsg::UpdateMixnodeConfig {
             profit_margin_percent,
         } => try_update_mixnode_config(profit_margin_percent, info, deps),
+        ExecuteMsg::AnnounceSphinxKeyRotation { rotation } => {
+            try_announce_sphinx_key_rotation(rotation, info, deps)
+        }
         ExecuteMsg::UpdateMixnetAddress { address } => {
             try_update_mixnet_address(address, info, deps)
         }
@@ -114,6 +119,9 @@ pub fn execute(
             amount,
         } => try_bond_gateway(gateway, owner_signature, amount, info, env, deps),
         ExecuteMsg::UnbondGateway {} => try_unbond_gateway(info, deps),
+        ExecuteMsg::UpdateGatewayConfig { new_config } => {
+            try_update_gateway_config(new_config, info, deps)
+        }
         ExecuteMsg::TrackUnbondGateway { owner, amount } => {
             try_track_unbond_gateway(&owner, amount, info, deps)
         }
@@ -147,6 +155,15 @@ pub fn try_update_mixnode_config(
     account.try_update_mixnode_config(profit_margin_percent, deps.storage)
 }
 
+pub fn try_announce_sphinx_key_rotation(
+    rotation: SphinxKeyRotation,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_announce_sphinx_key_rotation(rotation, deps.storage)
+}
+
 // Only contract admin, set at init
 pub fn try_update_mixnet_address(
     address: String,
@@ -254,6 +271,15 @@ pub fn try_unbond_gateway(info: MessageInfo, deps: DepsMut<'_>) -> Result<Respon
     account.try_unbond_gateway(deps.storage)
 }
 
+pub fn try_update_gateway_config(
+    new_config: GatewayConfigUpdate,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_update_gateway_config(new_config, deps.storage)
+}
+
 pub fn try_track_unbond_gateway(
     owner: &str,
     amount: Coin,
@@ -1,6 +1,6 @@
 use crate::errors::ContractError;
 use cosmwasm_std::{Coin, Env, Response, Storage};
-use mixnet_contract_common::{Gateway, MixNode};
+use mixnet_contract_common::{Gateway, GatewayConfigUpdate, MixNode, SphinxKeyRotation};
 
 pub trait MixnodeBondingAccount {
     fn try_compound_operator_reward(
@@ -32,6 +32,12 @@ pub trait MixnodeBondingAccount {
         profit_margin_percent: u8,
         storage: &mut dyn Storage,
     ) -> Result<Response, ContractError>;
+
+    fn try_announce_sphinx_key_rotation(
+        &self,
+        rotation: SphinxKeyRotation,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
 }
 
 pub trait GatewayBondingAccount {
@@ -46,6 +52,12 @@ pub trait GatewayBondingAccount {
 
     fn try_unbond_gateway(&self, storage: &dyn Storage) -> Result<Response, ContractError>;
 
+    fn try_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
     fn try_track_unbond_gateway(
         &self,
         amount: Coin,
@@ -6,9 +6,10 @@ use crate::storage::MIX_DENOM;
 use crate::traits::GatewayBondingAccount;
 use crate::traits::VestingAccount;
 use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
-use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, Gateway};
+use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, Gateway, GatewayConfigUpdate};
 use vesting_contract_common::events::{
     new_vesting_gateway_bonding_event, new_vesting_gateway_unbonding_event,
+    new_vesting_update_gateway_config_event,
 };
 use vesting_contract_common::one_ucoin;
 
@@ -90,6 +91,33 @@ impl GatewayBondingAccount for Account {
         }
     }
 
+    fn try_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_gateway_pledge(storage)?.is_none() {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        let msg = MixnetExecuteMsg::UpdateGatewayConfigOnBehalf {
+            new_config,
+            owner: self.owner_address().into_string(),
+        };
+
+        let update_gateway_config_msg = wasm_execute(
+            MIXNET_CONTRACT_ADDRESS.load(storage)?,
+            &msg,
+            vec![one_ucoin(MIX_DENOM.load(storage)?)],
+        )?;
+
+        Ok(Response::new()
+            .add_message(update_gateway_config_msg)
+            .add_event(new_vesting_update_gateway_config_event()))
+    }
+
     fn try_track_unbond_gateway(
         &self,
         amount: Coin,
@@ -5,10 +5,10 @@ use crate::storage::MIX_DENOM;
 use crate::traits::MixnodeBondingAccount;
 use crate::traits::VestingAccount;
 use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
-use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, MixNode};
+use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, MixNode, SphinxKeyRotation};
 use vesting_contract_common::events::{
     new_vesting_mixnode_bonding_event, new_vesting_mixnode_unbonding_event,
-    new_vesting_update_mixnode_config_event,
+    new_vesting_sphinx_key_rotation_event, new_vesting_update_mixnode_config_event,
 };
 
 use vesting_contract_common::one_ucoin;
@@ -69,6 +69,33 @@ impl MixnodeBondingAccount for Account {
             .add_event(new_vesting_update_mixnode_config_event()))
     }
 
+    fn try_announce_sphinx_key_rotation(
+        &self,
+        rotation: SphinxKeyRotation,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_mixnode_pledge(storage)?.is_none() {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        let msg = MixnetExecuteMsg::AnnounceSphinxKeyRotationOnBehalf {
+            rotation,
+            owner: self.owner_address().into_string(),
+        };
+
+        let announce_sphinx_key_rotation_msg = wasm_execute(
+            MIXNET_CONTRACT_ADDRESS.load(storage)?,
+            &msg,
+            vec![one_ucoin(MIX_DENOM.load(storage)?)],
+        )?;
+
+        Ok(Response::new()
+            .add_message(announce_sphinx_key_rotation_msg)
+            .add_event(new_vesting_sphinx_key_rotation_event()))
+    }
+
     fn try_bond_mixnode(
         &self,
         mix_node: MixNode,
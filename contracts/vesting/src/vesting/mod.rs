@@ -46,7 +46,7 @@ mod tests {
     use crate::traits::{GatewayBondingAccount, MixnodeBondingAccount};
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{coins, Addr, Coin, Timestamp, Uint128};
-    use mixnet_contract_common::{Gateway, MixNode};
+    use mixnet_contract_common::{Gateway, GatewayConfigUpdate, MixNode, SphinxKeyRotation};
     use vesting_contract_common::messages::ExecuteMsg;
     use vesting_contract_common::Period;
 
@@ -649,6 +649,7 @@ mod tests {
             host: "1.1.1.1".to_string(),
             mix_port: 1789,
             clients_port: 9000,
+            http_api_port: 8001,
             location: "Sweden".to_string(),
             sphinx_key: "sphinx".to_string(),
             identity_key: "identity".to_string(),
@@ -757,4 +758,104 @@ mod tests {
             .unwrap();
         assert_eq!(Uint128::zero(), bonded_vesting.amount);
     }
+
+    #[test]
+    fn test_gateway_config_update() {
+        let mut deps = init_contract();
+        let env = mock_env();
+
+        let account = vesting_account_new_fixture(&mut deps.storage, &env);
+
+        let new_config = GatewayConfigUpdate {
+            host: "2.2.2.2".to_string(),
+            mix_port: 1790,
+            clients_port: 9001,
+            http_api_port: 8002,
+            location: "Norway".to_string(),
+            version: "1.0.2".to_string(),
+        };
+
+        // there's nothing to update without a bonded gateway
+        let err = account.try_update_gateway_config(new_config.clone(), &deps.storage);
+        assert!(err.is_err());
+
+        let gateway = Gateway {
+            host: "1.1.1.1".to_string(),
+            mix_port: 1789,
+            clients_port: 9000,
+            http_api_port: 8001,
+            location: "Sweden".to_string(),
+            sphinx_key: "sphinx".to_string(),
+            identity_key: "identity".to_string(),
+            version: "0.10.0".to_string(),
+        };
+        account
+            .try_bond_gateway(
+                gateway,
+                "alice".to_string(),
+                Coin {
+                    amount: Uint128::new(90_000_000_000),
+                    denom: TEST_COIN_DENOM.to_string(),
+                },
+                &env,
+                &mut deps.storage,
+            )
+            .unwrap();
+        let balance = account.load_balance(&deps.storage).unwrap();
+
+        let response = account
+            .try_update_gateway_config(new_config, &deps.storage)
+            .unwrap();
+        assert_eq!(1, response.messages.len());
+
+        // updating the config does not touch the pledge
+        assert_eq!(balance, account.load_balance(&deps.storage).unwrap());
+        let pledge = account.load_gateway_pledge(&deps.storage).unwrap().unwrap();
+        assert_eq!(Uint128::new(90_000_000_000), pledge.amount().amount);
+    }
+
+    #[test]
+    fn test_sphinx_key_rotation_announcement() {
+        let mut deps = init_contract();
+        let env = mock_env();
+
+        let account = vesting_account_new_fixture(&mut deps.storage, &env);
+
+        let rotation = SphinxKeyRotation {
+            next_sphinx_key: "nextsphinx".to_string(),
+            activation_epoch: 42,
+        };
+
+        // there's no key to rotate without a bonded mixnode
+        let err = account.try_announce_sphinx_key_rotation(rotation.clone(), &deps.storage);
+        assert!(err.is_err());
+
+        let mix_node = MixNode {
+            host: "mix.node.org".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: "sphinx".to_string(),
+            identity_key: "identity".to_string(),
+            version: "0.10.0".to_string(),
+            profit_margin_percent: 10,
+        };
+        account
+            .try_bond_mixnode(
+                mix_node,
+                "alice".to_string(),
+                Coin {
+                    amount: Uint128::new(90_000_000_000),
+                    denom: TEST_COIN_DENOM.to_string(),
+                },
+                &env,
+                &mut deps.storage,
+            )
+            .unwrap();
+
+        let response = account
+            .try_announce_sphinx_key_rotation(rotation, &deps.storage)
+            .unwrap();
+        assert_eq!(1, response.messages.len());
+    }
 }
@@ -0,0 +1,33 @@
+/*
+ * Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+CREATE TABLE mixnode_history
+(
+    id               INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    identity_key     VARCHAR NOT NULL,
+    status           VARCHAR NOT NULL,
+    -- amounts are stored as strings as they might not fit in sqlite integers
+    pledge_amount    VARCHAR NOT NULL,
+    total_delegation VARCHAR NOT NULL,
+    stake_saturation REAL    NOT NULL,
+    avg_uptime       INTEGER NOT NULL,
+    timestamp        INTEGER NOT NULL
+);
+
+CREATE TABLE network_history
+(
+    id                INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    mixnodes          INTEGER NOT NULL,
+    active_mixnodes   INTEGER NOT NULL,
+    standby_mixnodes  INTEGER NOT NULL,
+    gateways          INTEGER NOT NULL,
+    validators        INTEGER NOT NULL,
+    total_pledge      VARCHAR NOT NULL,
+    total_delegation  VARCHAR NOT NULL,
+    timestamp         INTEGER NOT NULL
+);
+
+CREATE INDEX mixnode_history_identity_timestamp ON mixnode_history(`identity_key`, `timestamp`);
+CREATE INDEX network_history_timestamp ON network_history(`timestamp`);
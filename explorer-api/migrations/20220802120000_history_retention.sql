@@ -0,0 +1,7 @@
+/*
+ * Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- old snapshots of all the mix nodes are removed at once, so they have to be found by the timestamp alone
+CREATE INDEX mixnode_history_timestamp ON mixnode_history(`timestamp`);
@@ -1,6 +1,7 @@
 use log::info;
 
 use crate::country_statistics::country_nodes_distribution::CountryNodesDistribution;
+use crate::location::LocationCache;
 use crate::COUNTRY_DATA_REFRESH_INTERVAL;
 
 use crate::state::ExplorerApiStateContext;
@@ -15,7 +16,7 @@ impl CountryStatisticsDistributionTask {
     }
 
     pub(crate) fn start(mut self) {
-        info!("Spawning mix node country distribution task runner...");
+        info!("Spawning node country distribution task runner...");
         tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(std::time::Duration::from_secs(
                 COUNTRY_DATA_REFRESH_INTERVAL,
@@ -28,25 +29,11 @@ impl CountryStatisticsDistributionTask {
         });
     }
 
-    /// Retrieves the current list of mixnodes from the validators and calculates how many nodes are in each country
+    /// Calculates how many of the located mixnodes and gateways are in each country
     async fn calculate_nodes_per_country(&mut self) {
-        let cache = self.state.inner.mixnodes.get_locations().await;
-
-        let three_letter_iso_country_codes: Vec<String> = cache
-            .values()
-            .flat_map(|i| {
-                i.location
-                    .as_ref()
-                    .map(|j| j.three_letter_iso_country_code.clone())
-            })
-            .collect();
-
-        let mut distribution = CountryNodesDistribution::new();
-
         info!("Calculating country distribution from located mixnodes...");
-        for three_letter_iso_country_code in three_letter_iso_country_codes {
-            *(distribution.entry(three_letter_iso_country_code)).or_insert(0) += 1;
-        }
+        let distribution =
+            distribution_from_locations(&*self.state.inner.mixnodes.locations().read().await);
 
         // replace the shared distribution to be the new distribution
         self.state
@@ -60,7 +47,34 @@ impl CountryStatisticsDistributionTask {
             self.state.inner.country_node_distribution.get_all().await
         );
 
+        info!("Calculating country distribution from located gateways...");
+        let distribution =
+            distribution_from_locations(&*self.state.inner.gateways.locations().read().await);
+
+        self.state
+            .inner
+            .gateway_country_node_distribution
+            .set_all(distribution)
+            .await;
+
+        info!(
+            "Gateway country distribution done: {:?}",
+            self.state
+                .inner
+                .gateway_country_node_distribution
+                .get_all()
+                .await
+        );
+
         // keep state on disk, so that when this process dies it can start up again and users get some data
         self.state.write_to_file().await;
     }
 }
+
+fn distribution_from_locations(locations: &LocationCache) -> CountryNodesDistribution {
+    let mut distribution = CountryNodesDistribution::new();
+    for location in locations.values().filter_map(|item| item.location.as_ref()) {
+        *(distribution.entry(location.three_letter_iso_country_code.clone())).or_insert(0) += 1;
+    }
+    distribution
+}
@@ -1,11 +1,10 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::mix_nodes::location::{GeoLocation, Location};
+use crate::geo_ip::{GeoLocator, LocateError};
+use crate::location::ThreadsafeLocationCache;
 use crate::state::ExplorerApiStateContext;
 use log::{info, warn};
-use reqwest::Error as ReqwestError;
-use thiserror::Error;
 
 pub(crate) struct GeoLocateTask {
     state: ExplorerApiStateContext,
@@ -16,26 +15,37 @@ impl GeoLocateTask {
         GeoLocateTask { state }
     }
 
-    pub(crate) fn start(mut self) {
-        if ::std::env::var("GEO_IP_SERVICE_API_KEY").is_err() {
-            error!(
-                "Env var GEO_IP_SERVICE_API_KEY is not set. Geolocation tasks will not be started."
-            );
-            return;
-        }
+    pub(crate) fn start(self) {
+        let locator = match GeoLocator::new_from_env() {
+            Ok(locator) => locator,
+            Err(err) => {
+                error!(
+                    "Failed to set up the geolocation backend - {}. Geolocation tasks will not be started.",
+                    err
+                );
+                return;
+            }
+        };
 
-        info!("Spawning mix node locator task runner...");
+        info!(
+            "Spawning node locator task runner using the '{}' geolocation backend...",
+            locator.backend_name()
+        );
         tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(std::time::Duration::from_millis(50));
             loop {
                 // wait for the next interval tick
                 interval_timer.tick().await;
-                self.locate_mix_nodes().await;
+                // mix nodes take priority, gateways are only located once all mix nodes are
+                if !self.locate_mix_nodes(&locator).await {
+                    self.locate_gateways(&locator).await;
+                }
             }
         });
     }
 
-    async fn locate_mix_nodes(&mut self) {
+    /// Locates a single mix node without a valid location. Returns whether any work was done.
+    async fn locate_mix_nodes(&self, locator: &GeoLocator) -> bool {
         // I'm unwrapping to the default value to get rid of an extra indentation level from the `if let Some(...) = ...`
         // If the value is None, we'll unwrap to an empty hashmap and the `values()` loop won't do any work anyway
         let mixnode_bonds = self
@@ -45,112 +55,83 @@ impl GeoLocateTask {
             .get_mixnodes()
             .await
             .unwrap_or_default();
+        let locations = self.state.inner.mixnodes.locations();
 
         for (i, cache_item) in mixnode_bonds.values().enumerate() {
-            if self
-                .state
-                .inner
-                .mixnodes
-                .is_location_valid(&cache_item.mix_node().identity_key)
-                .await
-            {
+            let mix_node = cache_item.mix_node();
+            if locations.is_location_valid(&mix_node.identity_key).await {
                 // when the cached location is valid, don't locate and continue to next mix node
                 continue;
             }
 
-            // the mix node has not been located or is the cache time has expired
-            match locate(&cache_item.mix_node().host).await {
-                Ok(geo_location) => {
-                    let location = Location::new(geo_location);
-
-                    trace!(
-                        "{} mix nodes already located. Ip {} is located in {:#?}",
-                        i,
-                        cache_item.mix_node().host,
-                        location.three_letter_iso_country_code,
-                    );
-
-                    if i > 0 && (i % 100) == 0 {
-                        info!(
-                            "Located {} mixnodes...",
-                            i + 1,
-                        );
-                    }
-
-                    self.state
-                        .inner
-                        .mixnodes
-                        .set_location(&cache_item.mix_node().identity_key, Some(location))
-                        .await;
-
-                    // one node has been located, so return out of the loop
-                    return;
-                }
-                Err(e) => match e {
-                    LocateError::ReqwestError(e) => warn!(
-                        "❌ Oh no! Location for {} failed {}",
-                        cache_item.mix_node().host, e
-                    ),
-                    LocateError::NotFound(e) => {
-                            warn!(
-                            "❌ Location for {} not found. Response body: {}",
-                            cache_item.mix_node().host, e
-                        );
-                        self.state
-                            .inner
-                            .mixnodes
-                            .set_location(&cache_item.mix_node().identity_key, None)
-                            .await;
-                    },
-                    LocateError::RateLimited(e) => warn!(
-                        "❌ Oh no, we've been rate limited! Location for {} failed. Response body: {}",
-                        cache_item.mix_node().host, e
-                    ),
-                },
+            if i > 0 && (i % 100) == 0 {
+                info!("Located {} mixnodes...", i);
             }
+
+            // the mix node has not been located or is the cache time has expired
+            Self::locate_node(locator, locations, &mix_node.identity_key, &mix_node.host).await;
+            // one node has been located, so return out of the loop
+            return true;
         }
 
         trace!("All mix nodes located");
+        false
     }
-}
 
-#[derive(Debug, Error)]
-enum LocateError {
-    #[error("Oops, we have made too many requests and are being rate limited. Request body: {0}")]
-    RateLimited(String),
+    /// Locates a single gateway without a valid location.
+    async fn locate_gateways(&self, locator: &GeoLocator) {
+        let gateway_bonds = self.state.inner.gateways.get_gateways().await;
+        let locations = self.state.inner.gateways.locations();
 
-    #[error("Geolocation not found. Request body: {0}")]
-    NotFound(String),
+        for bond in &gateway_bonds {
+            let gateway = &bond.gateway;
+            if locations.is_location_valid(&gateway.identity_key).await {
+                continue;
+            }
 
-    #[error(transparent)]
-    ReqwestError(#[from] ReqwestError),
-}
+            Self::locate_node(locator, locations, &gateway.identity_key, &gateway.host).await;
+            return;
+        }
 
-async fn locate(ip: &str) -> Result<GeoLocation, LocateError> {
-    let api_key = ::std::env::var("GEO_IP_SERVICE_API_KEY")
-        .expect("Env var GEO_IP_SERVICE_API_KEY is not set");
-    let uri = format!("{}/?apikey={}&ip={}", crate::GEO_IP_SERVICE, api_key, ip);
-    match reqwest::get(uri.clone()).await {
-        Ok(response) => {
-            if response.status() == 429 {
-                return Err(LocateError::RateLimited(
-                    response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| "(the response body is empty)".to_string()),
-                ));
+        trace!("All gateways located");
+    }
+
+    async fn locate_node(
+        locator: &GeoLocator,
+        locations: &ThreadsafeLocationCache,
+        identity_key: &str,
+        host: &str,
+    ) {
+        match locator.locate(host).await {
+            Ok(Some(location)) => {
+                trace!(
+                    "Node {} on {} is located in {:#?}",
+                    identity_key,
+                    host,
+                    location.three_letter_iso_country_code,
+                );
+                locations
+                    .set_location(identity_key, Some(location), locator.ttl())
+                    .await;
+            }
+            Ok(None) => {
+                warn!("❌ Location for {} not found", host);
+                locations
+                    .set_location(identity_key, None, locator.ttl())
+                    .await;
             }
-            if response.status() == 404 {
-                return Err(LocateError::NotFound(
-                    response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| "(the response body is empty)".to_string()),
-                ));
+            Err(LocateError::UnresolvableHost(host)) => {
+                warn!("❌ Could not resolve {}", host);
+                // don't keep retrying it on every tick, it will be retried once the entry expires
+                locations
+                    .set_location(identity_key, None, locator.ttl())
+                    .await;
             }
-            let location = response.json::<GeoLocation>().await?;
-            Ok(location)
+            Err(LocateError::RateLimited(e)) => warn!(
+                "❌ Oh no, we've been rate limited! Location for {} failed. Response body: {}",
+                host, e
+            ),
+            Err(e) => warn!("❌ Oh no! Location for {} failed {}", host, e),
         }
-        Err(e) => Err(LocateError::ReqwestError(e)),
     }
 }
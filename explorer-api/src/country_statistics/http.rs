@@ -7,7 +7,7 @@ use rocket_okapi::openapi_get_routes_spec;
 use rocket_okapi::settings::OpenApiSettings;
 
 pub fn country_statistics_make_default_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
-    openapi_get_routes_spec![settings: index]
+    openapi_get_routes_spec![settings: index, gateways]
 }
 
 // We could either separate stuff by structure (like this, http is separate), or we could just
@@ -20,3 +20,17 @@ pub(crate) async fn index(
 ) -> Json<CountryNodesDistribution> {
     Json(state.inner.country_node_distribution.get_all().await)
 }
+
+#[openapi(tag = "country_statistics")]
+#[get("/gateways")]
+pub(crate) async fn gateways(
+    state: &State<ExplorerApiStateContext>,
+) -> Json<CountryNodesDistribution> {
+    Json(
+        state
+            .inner
+            .gateway_country_node_distribution
+            .get_all()
+            .await,
+    )
+}
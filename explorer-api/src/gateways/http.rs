@@ -1,18 +1,30 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use reqwest::Error as ReqwestError;
 use rocket::response::status::NotFound;
 use rocket::serde::json::Json;
 use rocket::{Route, State};
 use rocket_okapi::okapi::openapi3::OpenApi;
 use rocket_okapi::openapi_get_routes_spec;
 use rocket_okapi::settings::OpenApiSettings;
+use validator_client::models::UptimeHistoryResponse;
 
+use crate::gateways::models::PrettyDetailedGatewayBond;
+use crate::mix_node::models::NodeDescription;
+use crate::ping::http::ping_node;
+use crate::ping::models::PingResponse;
 use crate::state::ExplorerApiStateContext;
 use mixnet_contract_common::GatewayBond;
 
 pub fn gateways_make_default_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
-    openapi_get_routes_spec![settings: list]
+    openapi_get_routes_spec![
+        settings: list,
+        get_by_id,
+        get_description,
+        get_uptime_history,
+        get_ping,
+    ]
 }
 
 #[openapi(tag = "gateways")]
@@ -22,3 +34,99 @@ pub(crate) async fn list(
 ) -> Result<Json<Vec<GatewayBond>>, NotFound<String>> {
     Ok(Json(state.inner.gateways.get_gateways().await))
 }
+
+#[openapi(tag = "gateways")]
+#[get("/<pubkey>")]
+pub(crate) async fn get_by_id(
+    pubkey: &str,
+    state: &State<ExplorerApiStateContext>,
+) -> Result<Json<PrettyDetailedGatewayBond>, NotFound<String>> {
+    match state.inner.gateways.get_detailed_gateway(pubkey).await {
+        Some(gateway) => Ok(Json(gateway)),
+        None => Err(NotFound("Gateway not found".to_string())),
+    }
+}
+
+#[openapi(tag = "gateways")]
+#[get("/<pubkey>/description")]
+pub(crate) async fn get_description(
+    pubkey: &str,
+    state: &State<ExplorerApiStateContext>,
+) -> Option<Json<NodeDescription>> {
+    if let Some(cache_value) = state.inner.gateways.get_description(pubkey).await {
+        trace!("Returning cached value for {}", pubkey);
+        return Some(Json(cache_value));
+    }
+
+    trace!("No valid cache value for {}", pubkey);
+    let bond = state.inner.gateways.get_gateway(pubkey).await?;
+    match get_gateway_description(&bond.gateway.host, bond.gateway.http_api_port).await {
+        Ok(response) => {
+            // cache the response and return as the HTTP response
+            state
+                .inner
+                .gateways
+                .set_description(pubkey, response.clone())
+                .await;
+            Some(Json(response))
+        }
+        Err(e) => {
+            error!(
+                "Unable to get description for {} on {}:{} -> {}",
+                pubkey, bond.gateway.host, bond.gateway.http_api_port, e
+            );
+            None
+        }
+    }
+}
+
+#[openapi(tag = "gateways")]
+#[get("/<pubkey>/uptime-history")]
+pub(crate) async fn get_uptime_history(
+    pubkey: &str,
+    state: &State<ExplorerApiStateContext>,
+) -> Option<Json<UptimeHistoryResponse>> {
+    match state
+        .inner
+        .validator_client
+        .0
+        .validator_api
+        .get_gateway_uptime_history(pubkey)
+        .await
+    {
+        Ok(history) => Some(Json(history)),
+        Err(e) => {
+            error!("Unable to get uptime history for {} -> {}", pubkey, e);
+            None
+        }
+    }
+}
+
+#[openapi(tag = "gateways")]
+#[get("/<pubkey>/ping")]
+pub(crate) async fn get_ping(
+    pubkey: &str,
+    state: &State<ExplorerApiStateContext>,
+) -> Option<Json<PingResponse>> {
+    if let Some(cache_value) = state.inner.ping.get(pubkey).await {
+        trace!("Returning cached value for {}", pubkey);
+        return Some(Json(cache_value));
+    }
+
+    trace!("No cache value for {}", pubkey);
+    let bond = state.inner.gateways.get_gateway(pubkey).await?;
+    let ports_to_test = vec![bond.gateway.mix_port, bond.gateway.clients_port];
+    Some(Json(
+        ping_node(&state.inner.ping, pubkey, &bond.gateway.host, ports_to_test).await,
+    ))
+}
+
+async fn get_gateway_description(
+    host: &str,
+    http_api_port: u16,
+) -> Result<NodeDescription, ReqwestError> {
+    reqwest::get(format!("http://{}:{}/description", host, http_api_port))
+        .await?
+        .json::<NodeDescription>()
+        .await
+}
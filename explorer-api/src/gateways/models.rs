@@ -6,12 +6,25 @@ use std::sync::Arc;
 use serde::Serialize;
 use tokio::sync::RwLock;
 
-use mixnet_contract_common::GatewayBond;
+use mixnet_contract_common::{Addr, Coin, Gateway, GatewayBond};
 
 use crate::cache::Cache;
+use crate::location::{Location, LocationCache, ThreadsafeLocationCache};
+use crate::mix_node::models::NodeDescription;
 
 pub(crate) struct GatewayCache {
     pub(crate) gateways: Cache<GatewayBond>,
+    pub(crate) descriptions: Cache<NodeDescription>,
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub(crate) struct PrettyDetailedGatewayBond {
+    pub location: Option<Location>,
+    pub pledge_amount: Coin,
+    pub owner: Addr,
+    pub block_height: u64,
+    pub gateway: Gateway,
+    pub proxy: Option<Addr>,
 }
 
 #[derive(Clone, Debug, Serialize, JsonSchema)]
@@ -22,21 +35,70 @@ pub(crate) struct GatewaySummary {
 #[derive(Clone)]
 pub(crate) struct ThreadsafeGatewayCache {
     inner: Arc<RwLock<GatewayCache>>,
+    locations: ThreadsafeLocationCache,
 }
 
 impl ThreadsafeGatewayCache {
     pub(crate) fn new() -> Self {
+        ThreadsafeGatewayCache::new_with_location_cache(LocationCache::new())
+    }
+
+    pub(crate) fn new_with_location_cache(locations: LocationCache) -> Self {
         ThreadsafeGatewayCache {
             inner: Arc::new(RwLock::new(GatewayCache {
                 gateways: Cache::new(),
+                descriptions: Cache::new(),
             })),
+            locations: ThreadsafeLocationCache::new_with_cache(locations),
         }
     }
 
+    pub(crate) fn locations(&self) -> &ThreadsafeLocationCache {
+        &self.locations
+    }
+
     pub(crate) async fn get_gateways(&self) -> Vec<GatewayBond> {
         self.inner.read().await.gateways.get_all()
     }
 
+    pub(crate) async fn get_gateway(&self, identity_key: &str) -> Option<GatewayBond> {
+        self.inner.read().await.gateways.get(identity_key)
+    }
+
+    pub(crate) async fn get_detailed_gateway(
+        &self,
+        identity_key: &str,
+    ) -> Option<PrettyDetailedGatewayBond> {
+        let bond = self.get_gateway(identity_key).await?;
+        let location = self
+            .locations
+            .read()
+            .await
+            .get(identity_key)
+            .and_then(|cache_item| cache_item.location.clone());
+
+        Some(PrettyDetailedGatewayBond {
+            location,
+            pledge_amount: bond.pledge_amount,
+            owner: bond.owner,
+            block_height: bond.block_height,
+            gateway: bond.gateway,
+            proxy: bond.proxy,
+        })
+    }
+
+    pub(crate) async fn get_description(&self, identity_key: &str) -> Option<NodeDescription> {
+        self.inner.read().await.descriptions.get(identity_key)
+    }
+
+    pub(crate) async fn set_description(&self, identity_key: &str, description: NodeDescription) {
+        self.inner
+            .write()
+            .await
+            .descriptions
+            .set(identity_key, description);
+    }
+
     pub(crate) async fn get_gateway_summary(&self) -> GatewaySummary {
         GatewaySummary {
             count: self.inner.read().await.gateways.len(),
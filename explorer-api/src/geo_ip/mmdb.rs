@@ -0,0 +1,68 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::geo_ip::LocateError;
+use crate::location::Location;
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use std::net::IpAddr;
+
+pub(super) struct MmdbLocator {
+    reader: Reader<Vec<u8>>,
+}
+
+impl MmdbLocator {
+    pub(super) fn open(path: &str) -> Result<Self, MaxMindDBError> {
+        Ok(MmdbLocator {
+            reader: Reader::open_readfile(path)?,
+        })
+    }
+
+    pub(super) fn locate(&self, ip: IpAddr) -> Result<Option<Location>, LocateError> {
+        let city: geoip2::City = match self.reader.lookup(ip) {
+            Ok(city) => city,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let country = match city.country {
+            Some(country) => country,
+            None => return Ok(None),
+        };
+        let two_letter_iso_country_code = match country.iso_code {
+            Some(iso_code) => iso_code.to_string(),
+            None => return Ok(None),
+        };
+        let country_name = country
+            .names
+            .as_ref()
+            .and_then(|names| names.get("en"))
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let (lat, lng) = city
+            .location
+            .map(|location| {
+                (
+                    location.latitude.unwrap_or_default() as f32,
+                    location.longitude.unwrap_or_default() as f32,
+                )
+            })
+            .unwrap_or_default();
+
+        Ok(Some(Location::new(
+            two_letter_iso_country_code,
+            country_name,
+            lat,
+            lng,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opening_missing_database_fails() {
+        assert!(MmdbLocator::open("this-database-does-not-exist.mmdb").is_err());
+    }
+}
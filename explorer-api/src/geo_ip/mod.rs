@@ -0,0 +1,286 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::location::{Location, LocationCacheItem};
+use maxminddb::MaxMindDBError;
+use reqwest::Error as ReqwestError;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+mod mmdb;
+mod service;
+
+const DEFAULT_DATABASE_PATH: &str = "GeoLite2-City.mmdb";
+const DEFAULT_LOCATION_TTL: Duration = Duration::from_secs(60 * 60 * 24); // valid for 1 day
+
+#[derive(Debug, Error)]
+pub(crate) enum LocateError {
+    #[error("Oops, we have made too many requests and are being rate limited. Request body: {0}")]
+    RateLimited(String),
+
+    #[error("Could not resolve the address of '{0}'")]
+    UnresolvableHost(String),
+
+    #[error(transparent)]
+    ReqwestError(#[from] ReqwestError),
+
+    #[error(transparent)]
+    MmdbError(#[from] MaxMindDBError),
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum GeoIpSetupError {
+    #[error("Unknown geolocation backend '{0}'. Supported backends are 'mmdb' and 'service'")]
+    UnknownBackend(String),
+
+    #[error("Env var GEO_IP_SERVICE_API_KEY is not set")]
+    MissingServiceApiKey,
+
+    #[error("Could not open the geolocation database at '{path}' - {source}")]
+    DatabaseUnavailable {
+        path: String,
+        #[source]
+        source: MaxMindDBError,
+    },
+
+    #[error("GEO_IP_CACHE_TTL_SECS is not a valid number of seconds - {0}")]
+    MalformedTtl(#[from] std::num::ParseIntError),
+}
+
+enum GeoIpBackend {
+    /// Local database in the MaxMind DB format, such as GeoLite2 City.
+    Mmdb(mmdb::MmdbLocator),
+
+    /// Remote geolocation service.
+    Service(service::ServiceLocator),
+}
+
+impl GeoIpBackend {
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>, LocateError> {
+        match self {
+            GeoIpBackend::Mmdb(locator) => locator.locate(ip),
+            GeoIpBackend::Service(locator) => locator.locate(ip).await,
+        }
+    }
+}
+
+/// Geolocates hosts using the configured backend. The results are cached per host, so that
+/// nodes sharing the same machine are only ever looked up (and resolved) once.
+#[derive(Clone)]
+pub(crate) struct GeoLocator {
+    backend: Arc<GeoIpBackend>,
+    cache: Arc<RwLock<HashMap<String, LocationCacheItem>>>,
+    ttl: Duration,
+}
+
+impl GeoLocator {
+    /// Creates the locator based on the environment:
+    ///
+    /// * `GEO_IP_BACKEND`: either `mmdb` or `service`. Defaults to `service` if
+    ///   `GEO_IP_SERVICE_API_KEY` is set and to `mmdb` otherwise,
+    /// * `GEO_IP_DATABASE_PATH`: path to the MMDB file used by the `mmdb` backend,
+    /// * `GEO_IP_SERVICE_API_KEY`: api key used by the `service` backend,
+    /// * `GEO_IP_CACHE_TTL_SECS`: for how long the location of a given host is cached.
+    pub(crate) fn new_from_env() -> Result<Self, GeoIpSetupError> {
+        Self::new(
+            std::env::var("GEO_IP_BACKEND").ok(),
+            std::env::var("GEO_IP_DATABASE_PATH").ok(),
+            std::env::var("GEO_IP_SERVICE_API_KEY").ok(),
+            std::env::var("GEO_IP_CACHE_TTL_SECS").ok(),
+        )
+    }
+
+    fn new(
+        backend: Option<String>,
+        database_path: Option<String>,
+        service_api_key: Option<String>,
+        cache_ttl_secs: Option<String>,
+    ) -> Result<Self, GeoIpSetupError> {
+        // deployments that predate the `mmdb` backend only ever set the api key
+        let backend = backend.unwrap_or_else(|| {
+            if service_api_key.is_some() {
+                "service".to_string()
+            } else {
+                "mmdb".to_string()
+            }
+        });
+
+        let backend = match backend.as_str() {
+            "mmdb" => {
+                let path = database_path.unwrap_or_else(|| DEFAULT_DATABASE_PATH.to_string());
+                let locator = mmdb::MmdbLocator::open(&path)
+                    .map_err(|source| GeoIpSetupError::DatabaseUnavailable { path, source })?;
+                GeoIpBackend::Mmdb(locator)
+            }
+            "service" => {
+                let api_key = service_api_key.ok_or(GeoIpSetupError::MissingServiceApiKey)?;
+                GeoIpBackend::Service(service::ServiceLocator::new(
+                    crate::GEO_IP_SERVICE.to_string(),
+                    api_key,
+                ))
+            }
+            other => return Err(GeoIpSetupError::UnknownBackend(other.to_string())),
+        };
+
+        let ttl = match cache_ttl_secs {
+            Some(secs) => Duration::from_secs(secs.parse()?),
+            None => DEFAULT_LOCATION_TTL,
+        };
+
+        Ok(GeoLocator {
+            backend: Arc::new(backend),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            ttl,
+        })
+    }
+
+    /// For how long the located hosts, and the nodes running on them, should be cached.
+    pub(crate) fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub(crate) fn backend_name(&self) -> &'static str {
+        match *self.backend {
+            GeoIpBackend::Mmdb(_) => "mmdb",
+            GeoIpBackend::Service(_) => "service",
+        }
+    }
+
+    async fn resolve(host: &str) -> Result<IpAddr, LocateError> {
+        if let Ok(ip) = host.parse() {
+            return Ok(ip);
+        }
+        tokio::net::lookup_host((host, 0))
+            .await
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .map(|addr| addr.ip())
+            .ok_or_else(|| LocateError::UnresolvableHost(host.to_string()))
+    }
+
+    /// Returns location of the given host (either an IP address or a hostname),
+    /// or `None` if the backend does not know where it is.
+    pub(crate) async fn locate(&self, host: &str) -> Result<Option<Location>, LocateError> {
+        if let Some(cached) = self.cache.read().await.get(host) {
+            if cached.is_valid() {
+                return Ok(cached.location.clone());
+            }
+        }
+
+        let ip = Self::resolve(host).await?;
+        let location = self.backend.locate(ip).await?;
+        self.cache.write().await.insert(
+            host.to_string(),
+            LocationCacheItem::new_with_validity(location.clone(), self.ttl),
+        );
+        Ok(location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locator(
+        backend: Option<&str>,
+        api_key: Option<&str>,
+    ) -> Result<GeoLocator, GeoIpSetupError> {
+        GeoLocator::new(
+            backend.map(ToString::to_string),
+            Some("this-database-does-not-exist.mmdb".to_string()),
+            api_key.map(ToString::to_string),
+            None,
+        )
+    }
+
+    fn location() -> Location {
+        Location::new("CH".to_string(), "Switzerland".to_string(), 46.8, 8.2)
+    }
+
+    #[test]
+    fn defaults_to_service_backend_if_api_key_is_set() {
+        let locator = locator(None, Some("api-key")).unwrap();
+        assert_eq!(locator.backend_name(), "service");
+    }
+
+    #[test]
+    fn defaults_to_mmdb_backend_without_api_key() {
+        assert!(matches!(
+            locator(None, None),
+            Err(GeoIpSetupError::DatabaseUnavailable { .. })
+        ));
+    }
+
+    #[test]
+    fn explicit_backend_takes_precedence() {
+        assert!(matches!(
+            locator(Some("mmdb"), Some("api-key")),
+            Err(GeoIpSetupError::DatabaseUnavailable { .. })
+        ));
+        assert!(matches!(
+            locator(Some("service"), None),
+            Err(GeoIpSetupError::MissingServiceApiKey)
+        ));
+        assert!(matches!(
+            locator(Some("foomp"), Some("api-key")),
+            Err(GeoIpSetupError::UnknownBackend(_))
+        ));
+    }
+
+    #[test]
+    fn cache_ttl_is_configurable() {
+        let default_ttl = locator(Some("service"), Some("api-key")).unwrap();
+        assert_eq!(default_ttl.ttl(), DEFAULT_LOCATION_TTL);
+
+        let custom_ttl = GeoLocator::new(
+            Some("service".to_string()),
+            None,
+            Some("api-key".to_string()),
+            Some("42".to_string()),
+        )
+        .unwrap();
+        assert_eq!(custom_ttl.ttl(), Duration::from_secs(42));
+
+        assert!(matches!(
+            GeoLocator::new(
+                Some("service".to_string()),
+                None,
+                Some("api-key".to_string()),
+                Some("foomp".to_string()),
+            ),
+            Err(GeoIpSetupError::MalformedTtl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn cached_hosts_are_not_resolved_again() {
+        let locator = locator(Some("service"), Some("api-key")).unwrap();
+        let host = "some-unknown-hostname-that-will-never-resolve.nymtech.net";
+        locator.cache.write().await.insert(
+            host.to_string(),
+            LocationCacheItem::new_with_validity(Some(location()), locator.ttl()),
+        );
+
+        let located = locator.locate(host).await.unwrap().unwrap();
+        assert_eq!(located.two_letter_iso_country_code, "CH");
+    }
+
+    #[tokio::test]
+    async fn expired_hosts_are_located_again() {
+        let locator = locator(Some("service"), Some("api-key")).unwrap();
+        let host = "some-unknown-hostname-that-will-never-resolve.nymtech.net";
+        locator.cache.write().await.insert(
+            host.to_string(),
+            LocationCacheItem::new_with_validity(Some(location()), Duration::ZERO),
+        );
+
+        assert!(matches!(
+            locator.locate(host).await,
+            Err(LocateError::UnresolvableHost(_))
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::geo_ip::LocateError;
+use crate::location::{GeoLocation, Location};
+use std::net::IpAddr;
+
+pub(super) struct ServiceLocator {
+    service_url: String,
+    api_key: String,
+}
+
+impl ServiceLocator {
+    pub(super) fn new(service_url: String, api_key: String) -> Self {
+        ServiceLocator {
+            service_url,
+            api_key,
+        }
+    }
+
+    pub(super) async fn locate(&self, ip: IpAddr) -> Result<Option<Location>, LocateError> {
+        let uri = format!("{}/?apikey={}&ip={}", self.service_url, self.api_key, ip);
+        let response = reqwest::get(uri).await?;
+        if response.status() == 429 {
+            return Err(LocateError::RateLimited(
+                response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "(the response body is empty)".to_string()),
+            ));
+        }
+        if response.status() == 404 {
+            return Ok(None);
+        }
+        let location = response.json::<GeoLocation>().await?;
+        Ok(Some(location.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // serves a single request with the provided status and body and returns url of the server
+    async fn serve_once(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{}", address)
+    }
+
+    fn locator(service_url: String) -> ServiceLocator {
+        ServiceLocator::new(service_url, "api-key".to_string())
+    }
+
+    #[tokio::test]
+    async fn locates_known_address() {
+        let body = r#"{"ip":"1.2.3.4","country_code":"CH","country_name":"Switzerland","region_code":"ZH","region_name":"Zurich","city":"Zurich","zip_code":"8000","time_zone":"Europe/Zurich","latitude":47.37,"longitude":8.54,"metro_code":0}"#;
+        let url = serve_once("200 OK", body).await;
+
+        let location = locator(url)
+            .locate("1.2.3.4".parse().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(location.two_letter_iso_country_code, "CH");
+        assert_eq!(location.three_letter_iso_country_code, "CHE");
+        assert_eq!(location.country_name, "Switzerland");
+    }
+
+    #[tokio::test]
+    async fn unknown_address_has_no_location() {
+        let url = serve_once("404 Not Found", "{}").await;
+        assert!(locator(url)
+            .locate("1.2.3.4".parse().unwrap())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn reports_rate_limiting() {
+        let url = serve_once("429 Too Many Requests", "slow down").await;
+        assert!(matches!(
+            locator(url).locate("1.2.3.4".parse().unwrap()).await,
+            Err(LocateError::RateLimited(body)) if body == "slow down"
+        ));
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use rocket::http::Status;
+use rocket::response::{self, Responder};
+use rocket::Request;
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::util::ensure_status_code_exists;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum HistoryStorageError {
+    #[error("SQL error - {0}")]
+    InternalDatabase(#[from] sqlx::Error),
+
+    #[error("SQL migrate error - {0}")]
+    DatabaseMigration(#[from] sqlx::migrate::MigrateError),
+}
+
+// the details of what went wrong are only logged, the users don't need to know about them
+impl<'r, 'o: 'r> Responder<'r, 'o> for HistoryStorageError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        Status::InternalServerError.respond_to(request)
+    }
+}
+
+impl OpenApiResponderInner for HistoryStorageError {
+    fn responses(_gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        ensure_status_code_exists(&mut responses, 500);
+        Ok(responses)
+    }
+}
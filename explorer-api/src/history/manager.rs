@@ -0,0 +1,153 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::history::models::{HistoryQuery, MixnodeHistoryEntry, NetworkHistoryEntry};
+
+#[derive(Clone)]
+pub(crate) struct StorageManager {
+    pub(crate) connection_pool: sqlx::SqlitePool,
+}
+
+// all SQL goes here
+impl StorageManager {
+    /// Inserts the snapshot of all the mix nodes into the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries`: identity keys of the mix nodes alongside their current state.
+    pub(super) async fn insert_mixnode_history(
+        &self,
+        entries: Vec<(String, MixnodeHistoryEntry)>,
+    ) -> Result<(), sqlx::Error> {
+        // insert it all in a transaction so that the snapshot is never partially visible
+        let mut tx = self.connection_pool.begin().await?;
+        for (identity_key, entry) in entries {
+            sqlx::query!(
+                r#"
+                    INSERT INTO mixnode_history
+                    (identity_key, status, pledge_amount, total_delegation, stake_saturation, avg_uptime, timestamp)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+                identity_key,
+                entry.status,
+                entry.pledge_amount,
+                entry.total_delegation,
+                entry.stake_saturation,
+                entry.avg_uptime,
+                entry.timestamp,
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Inserts the snapshot of the network into the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry`: current state of the network.
+    pub(super) async fn insert_network_history(
+        &self,
+        entry: NetworkHistoryEntry,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT INTO network_history
+                (mixnodes, active_mixnodes, standby_mixnodes, gateways, validators, total_pledge, total_delegation, timestamp)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            entry.mixnodes,
+            entry.active_mixnodes,
+            entry.standby_mixnodes,
+            entry.gateways,
+            entry.validators,
+            entry.total_pledge,
+            entry.total_delegation,
+            entry.timestamp,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes all the history entries taken before the provided timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp`: unix timestamp of the oldest entries to keep.
+    pub(super) async fn remove_history_before(&self, timestamp: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        sqlx::query!("DELETE FROM mixnode_history WHERE timestamp < ?", timestamp)
+            .execute(&mut tx)
+            .await?;
+        sqlx::query!("DELETE FROM network_history WHERE timestamp < ?", timestamp)
+            .execute(&mut tx)
+            .await?;
+
+        tx.commit().await
+    }
+
+    /// Returns a page of the history of the particular mix node, oldest entries first.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity_key`: identity of the mix node.
+    /// * `query`: time interval and page of the history.
+    pub(super) async fn get_mixnode_history(
+        &self,
+        identity_key: &str,
+        query: HistoryQuery,
+    ) -> Result<Vec<MixnodeHistoryEntry>, sqlx::Error> {
+        let limit = query.limit();
+        let offset = query.offset();
+        sqlx::query_as!(
+            MixnodeHistoryEntry,
+            r#"
+                SELECT timestamp, status, pledge_amount, total_delegation, stake_saturation, avg_uptime
+                FROM mixnode_history
+                WHERE identity_key = ? AND timestamp BETWEEN ? AND ?
+                ORDER BY timestamp
+                LIMIT ? OFFSET ?
+            "#,
+            identity_key,
+            query.from,
+            query.to,
+            limit,
+            offset,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Returns a page of the history of the network, oldest entries first.
+    ///
+    /// # Arguments
+    ///
+    /// * `query`: time interval and page of the history.
+    pub(super) async fn get_network_history(
+        &self,
+        query: HistoryQuery,
+    ) -> Result<Vec<NetworkHistoryEntry>, sqlx::Error> {
+        let limit = query.limit();
+        let offset = query.offset();
+        sqlx::query_as!(
+            NetworkHistoryEntry,
+            r#"
+                SELECT timestamp, mixnodes, active_mixnodes, standby_mixnodes, gateways, validators, total_pledge, total_delegation
+                FROM network_history
+                WHERE timestamp BETWEEN ? AND ?
+                ORDER BY timestamp
+                LIMIT ? OFFSET ?
+            "#,
+            query.from,
+            query.to,
+            limit,
+            offset,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::info;
+use sqlx::ConnectOptions;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::history::error::HistoryStorageError;
+use crate::history::manager::StorageManager;
+use crate::history::models::{HistoryPage, HistoryQuery, MixnodeHistoryEntry, NetworkHistoryEntry};
+use crate::mix_node::models::{MixnodeStatus, PrettyDetailedMixNodeBond};
+
+pub(crate) mod error;
+mod manager;
+pub(crate) mod models;
+
+const HISTORY_DATABASE_FILE: &str = "explorer-api-history.sqlite";
+const DEFAULT_HISTORY_RETENTION_DAYS: u64 = 90;
+
+/// Persistent history of the mix nodes and of the network as a whole.
+// note that clone here is fine as upon cloning the same underlying pool will be used
+#[derive(Clone)]
+pub(crate) struct HistoryStorage {
+    manager: StorageManager,
+
+    /// For how long the snapshots are kept before being removed.
+    retention: Duration,
+}
+
+impl HistoryStorage {
+    pub(crate) async fn init<P: AsRef<Path>>(
+        database_path: P,
+        retention: Duration,
+    ) -> Result<Self, HistoryStorageError> {
+        let mut opts = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        opts.disable_statement_logging();
+
+        let connection_pool = sqlx::SqlitePool::connect_with(opts).await?;
+
+        sqlx::migrate!("./migrations").run(&connection_pool).await?;
+        info!("Database migration finished!");
+
+        Ok(HistoryStorage {
+            manager: StorageManager { connection_pool },
+            retention,
+        })
+    }
+
+    /// Stores the current state of the mix nodes and of the network and removes the snapshots
+    /// that are past their retention.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp`: unix timestamp of the snapshot.
+    /// * `mixnodes`: current state of all the mix nodes.
+    /// * `gateways`: current number of gateways.
+    /// * `validators`: current number of validators.
+    pub(crate) async fn insert_snapshot(
+        &self,
+        timestamp: i64,
+        mixnodes: &[PrettyDetailedMixNodeBond],
+        gateways: usize,
+        validators: usize,
+    ) -> Result<(), HistoryStorageError> {
+        let mixnode_entries = mixnodes
+            .iter()
+            .map(|bond| {
+                (
+                    bond.mix_node.identity_key.clone(),
+                    MixnodeHistoryEntry {
+                        timestamp,
+                        status: bond.status.as_str().to_string(),
+                        pledge_amount: bond.pledge_amount.amount.to_string(),
+                        total_delegation: bond.total_delegation.amount.to_string(),
+                        stake_saturation: bond.stake_saturation as f64,
+                        avg_uptime: bond.avg_uptime as i64,
+                    },
+                )
+            })
+            .collect();
+
+        let count_with_status = |status: MixnodeStatus| {
+            mixnodes.iter().filter(|bond| bond.status == status).count() as i64
+        };
+        let network_entry = NetworkHistoryEntry {
+            timestamp,
+            mixnodes: mixnodes.len() as i64,
+            active_mixnodes: count_with_status(MixnodeStatus::Active),
+            standby_mixnodes: count_with_status(MixnodeStatus::Standby),
+            gateways: gateways as i64,
+            validators: validators as i64,
+            total_pledge: mixnodes
+                .iter()
+                .map(|bond| bond.pledge_amount.amount.u128())
+                .sum::<u128>()
+                .to_string(),
+            total_delegation: mixnodes
+                .iter()
+                .map(|bond| bond.total_delegation.amount.u128())
+                .sum::<u128>()
+                .to_string(),
+        };
+
+        self.manager.insert_mixnode_history(mixnode_entries).await?;
+        self.manager.insert_network_history(network_entry).await?;
+        self.manager
+            .remove_history_before(timestamp.saturating_sub(self.retention.as_secs() as i64))
+            .await?;
+        Ok(())
+    }
+
+    /// Returns a page of the history of the particular mix node within the requested time interval.
+    pub(crate) async fn get_mixnode_history(
+        &self,
+        identity_key: &str,
+        query: HistoryQuery,
+    ) -> Result<HistoryPage<MixnodeHistoryEntry>, HistoryStorageError> {
+        let entries = self
+            .manager
+            .get_mixnode_history(identity_key, query)
+            .await?;
+        Ok(query.into_page(entries))
+    }
+
+    /// Returns a page of the history of the network within the requested time interval.
+    pub(crate) async fn get_network_history(
+        &self,
+        query: HistoryQuery,
+    ) -> Result<HistoryPage<NetworkHistoryEntry>, HistoryStorageError> {
+        let entries = self.manager.get_network_history(query).await?;
+        Ok(query.into_page(entries))
+    }
+}
+
+pub(crate) fn get_history_database_path() -> String {
+    std::env::var("API_HISTORY_DATABASE").unwrap_or_else(|_| HISTORY_DATABASE_FILE.to_string())
+}
+
+pub(crate) fn get_history_retention() -> Duration {
+    let days = std::env::var("API_HISTORY_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_HISTORY_RETENTION_DAYS);
+    Duration::from_secs(days * 24 * 60 * 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_storage(retention: Duration) -> (HistoryStorage, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = HistoryStorage::init(dir.path().join("history.sqlite"), retention)
+            .await
+            .unwrap();
+        (storage, dir)
+    }
+
+    fn network_entry(timestamp: i64) -> NetworkHistoryEntry {
+        NetworkHistoryEntry {
+            timestamp,
+            mixnodes: 0,
+            active_mixnodes: 0,
+            standby_mixnodes: 0,
+            gateways: 0,
+            validators: 0,
+            total_pledge: "0".to_string(),
+            total_delegation: "0".to_string(),
+        }
+    }
+
+    fn mixnode_entry(timestamp: i64) -> MixnodeHistoryEntry {
+        MixnodeHistoryEntry {
+            timestamp,
+            status: "active".to_string(),
+            pledge_amount: "100".to_string(),
+            total_delegation: "200".to_string(),
+            stake_saturation: 0.5,
+            avg_uptime: 99,
+        }
+    }
+
+    fn timestamps<T>(page: HistoryPage<T>, timestamp: impl Fn(&T) -> i64) -> Vec<i64> {
+        page.entries.iter().map(timestamp).collect()
+    }
+
+    #[tokio::test]
+    async fn network_history_is_paginated_oldest_first() {
+        let (storage, _dir) = test_storage(Duration::from_secs(1000)).await;
+        for timestamp in [5, 3, 1, 4, 2] {
+            storage
+                .manager
+                .insert_network_history(network_entry(timestamp))
+                .await
+                .unwrap();
+        }
+
+        for (page, expected) in [(0, vec![1, 2]), (1, vec![3, 4]), (2, vec![5]), (3, vec![])] {
+            let query = HistoryQuery::new(None, None, Some(page), Some(2));
+            let page = storage.get_network_history(query).await.unwrap();
+            assert_eq!(timestamps(page, |entry| entry.timestamp), expected);
+        }
+
+        let query = HistoryQuery::new(Some(2), Some(4), None, None);
+        let page = storage.get_network_history(query).await.unwrap();
+        assert_eq!(timestamps(page, |entry| entry.timestamp), vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn mixnode_history_only_contains_requested_node() {
+        let (storage, _dir) = test_storage(Duration::from_secs(1000)).await;
+        storage
+            .manager
+            .insert_mixnode_history(vec![
+                ("foo".to_string(), mixnode_entry(1)),
+                ("bar".to_string(), mixnode_entry(1)),
+                ("foo".to_string(), mixnode_entry(2)),
+            ])
+            .await
+            .unwrap();
+
+        let query = HistoryQuery::new(None, None, None, None);
+        let page = storage.get_mixnode_history("foo", query).await.unwrap();
+        assert_eq!(timestamps(page, |entry| entry.timestamp), vec![1, 2]);
+
+        let page = storage.get_mixnode_history("baz", query).await.unwrap();
+        assert!(page.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshots_past_retention_are_removed() {
+        let (storage, _dir) = test_storage(Duration::from_secs(100)).await;
+        for timestamp in [100, 250] {
+            storage
+                .manager
+                .insert_network_history(network_entry(timestamp))
+                .await
+                .unwrap();
+            storage
+                .manager
+                .insert_mixnode_history(vec![("foo".to_string(), mixnode_entry(timestamp))])
+                .await
+                .unwrap();
+        }
+
+        storage.insert_snapshot(300, &[], 1, 1).await.unwrap();
+
+        let query = HistoryQuery::new(None, None, None, None);
+        let network_history = storage.get_network_history(query).await.unwrap();
+        assert_eq!(
+            timestamps(network_history, |entry| entry.timestamp),
+            vec![250, 300]
+        );
+        let mixnode_history = storage.get_mixnode_history("foo", query).await.unwrap();
+        assert_eq!(
+            timestamps(mixnode_history, |entry| entry.timestamp),
+            vec![250]
+        );
+    }
+}
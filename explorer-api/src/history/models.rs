@@ -0,0 +1,108 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Serialize;
+
+pub(crate) const DEFAULT_PAGE_SIZE: u32 = 100;
+pub(crate) const MAX_PAGE_SIZE: u32 = 1000;
+
+/// State of a mix node at given point in time.
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub(crate) struct MixnodeHistoryEntry {
+    /// Unix timestamp of when the snapshot was taken.
+    pub(crate) timestamp: i64,
+    pub(crate) status: String,
+    pub(crate) pledge_amount: String,
+    pub(crate) total_delegation: String,
+    pub(crate) stake_saturation: f64,
+    pub(crate) avg_uptime: i64,
+}
+
+/// State of the whole network at given point in time.
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub(crate) struct NetworkHistoryEntry {
+    /// Unix timestamp of when the snapshot was taken.
+    pub(crate) timestamp: i64,
+    pub(crate) mixnodes: i64,
+    pub(crate) active_mixnodes: i64,
+    pub(crate) standby_mixnodes: i64,
+    pub(crate) gateways: i64,
+    pub(crate) validators: i64,
+    pub(crate) total_pledge: String,
+    pub(crate) total_delegation: String,
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub(crate) struct HistoryPage<T> {
+    pub(crate) page: u32,
+    pub(crate) size: u32,
+    pub(crate) entries: Vec<T>,
+}
+
+/// Time interval and page of the history requested by the user.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HistoryQuery {
+    pub(crate) from: i64,
+    pub(crate) to: i64,
+    pub(crate) page: u32,
+    pub(crate) size: u32,
+}
+
+impl HistoryQuery {
+    /// Fills in the missing parameters of the query. By default the entire history is returned
+    /// in pages of [`DEFAULT_PAGE_SIZE`] entries.
+    pub(crate) fn new(
+        from: Option<i64>,
+        to: Option<i64>,
+        page: Option<u32>,
+        size: Option<u32>,
+    ) -> Self {
+        HistoryQuery {
+            from: from.unwrap_or(0),
+            to: to.unwrap_or(i64::MAX),
+            page: page.unwrap_or(0),
+            size: size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> i64 {
+        self.size as i64
+    }
+
+    pub(crate) fn offset(&self) -> i64 {
+        self.page as i64 * self.size as i64
+    }
+
+    pub(crate) fn into_page<T>(self, entries: Vec<T>) -> HistoryPage<T> {
+        HistoryPage {
+            page: self.page,
+            size: self.size,
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_defaults_to_entire_history() {
+        let query = HistoryQuery::new(None, None, None, None);
+        assert_eq!(query.from, 0);
+        assert_eq!(query.to, i64::MAX);
+        assert_eq!(query.limit(), DEFAULT_PAGE_SIZE as i64);
+        assert_eq!(query.offset(), 0);
+    }
+
+    #[test]
+    fn query_page_size_is_bounded() {
+        let query = HistoryQuery::new(None, None, Some(3), Some(MAX_PAGE_SIZE + 1));
+        assert_eq!(query.limit(), MAX_PAGE_SIZE as i64);
+        assert_eq!(query.offset(), 3 * MAX_PAGE_SIZE as i64);
+
+        let query = HistoryQuery::new(None, None, Some(3), Some(0));
+        assert_eq!(query.limit(), 1);
+        assert_eq!(query.offset(), 3);
+    }
+}
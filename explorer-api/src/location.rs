@@ -0,0 +1,152 @@
+// Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::mix_nodes::utils::map_2_letter_to_3_letter_country_code;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+pub(crate) type LocationCache = HashMap<String, LocationCacheItem>;
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub(crate) struct GeoLocation {
+    pub(crate) ip: String,
+    pub(crate) country_code: String,
+    pub(crate) country_name: String,
+    pub(crate) region_code: String,
+    pub(crate) region_name: String,
+    pub(crate) city: String,
+    pub(crate) zip_code: String,
+    pub(crate) time_zone: String,
+    pub(crate) latitude: f32,
+    pub(crate) longitude: f32,
+    pub(crate) metro_code: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct LocationCacheItem {
+    pub(crate) location: Option<Location>,
+    pub(crate) valid_until: SystemTime,
+}
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+pub(crate) struct Location {
+    pub(crate) two_letter_iso_country_code: String,
+    pub(crate) three_letter_iso_country_code: String,
+    pub(crate) country_name: String,
+    pub(crate) lat: f32,
+    pub(crate) lng: f32,
+}
+
+impl Location {
+    pub(crate) fn new(
+        two_letter_iso_country_code: String,
+        country_name: String,
+        lat: f32,
+        lng: f32,
+    ) -> Self {
+        let three_letter_iso_country_code =
+            map_2_letter_to_3_letter_country_code(&two_letter_iso_country_code);
+        Location {
+            two_letter_iso_country_code,
+            three_letter_iso_country_code,
+            country_name,
+            lat,
+            lng,
+        }
+    }
+}
+
+impl From<GeoLocation> for Location {
+    fn from(geo_location: GeoLocation) -> Self {
+        Location::new(
+            geo_location.country_code,
+            geo_location.country_name,
+            geo_location.latitude,
+            geo_location.longitude,
+        )
+    }
+}
+
+impl LocationCacheItem {
+    pub(crate) fn new_with_validity(location: Option<Location>, validity: Duration) -> Self {
+        LocationCacheItem {
+            location,
+            valid_until: SystemTime::now() + validity,
+        }
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.valid_until > SystemTime::now()
+    }
+}
+
+/// Locations of nodes, identified by their identity keys. Shared by mix nodes and gateways.
+#[derive(Clone)]
+pub(crate) struct ThreadsafeLocationCache {
+    inner: Arc<RwLock<LocationCache>>,
+}
+
+impl ThreadsafeLocationCache {
+    pub(crate) fn new() -> Self {
+        ThreadsafeLocationCache::new_with_cache(LocationCache::new())
+    }
+
+    pub(crate) fn new_with_cache(locations: LocationCache) -> Self {
+        ThreadsafeLocationCache {
+            inner: Arc::new(RwLock::new(locations)),
+        }
+    }
+
+    pub(crate) async fn read(&self) -> RwLockReadGuard<'_, LocationCache> {
+        self.inner.read().await
+    }
+
+    pub(crate) async fn is_location_valid(&self, identity_key: &str) -> bool {
+        self.inner
+            .read()
+            .await
+            .get(identity_key)
+            .map_or(false, LocationCacheItem::is_valid)
+    }
+
+    pub(crate) async fn get_locations(&self) -> LocationCache {
+        self.inner.read().await.clone()
+    }
+
+    pub(crate) async fn set_location(
+        &self,
+        identity_key: &str,
+        location: Option<Location>,
+        validity: Duration,
+    ) {
+        // cache the location for this node so that it can be used when the node list is refreshed
+        self.inner.write().await.insert(
+            identity_key.to_string(),
+            LocationCacheItem::new_with_validity(location, validity),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn node_locations_expire_after_their_validity() {
+        let locations = ThreadsafeLocationCache::new();
+        locations
+            .set_location("valid", None, Duration::from_secs(60))
+            .await;
+        locations
+            .set_location("expired", None, Duration::ZERO)
+            .await;
+
+        assert!(locations.is_location_valid("valid").await);
+        assert!(!locations.is_location_valid("expired").await);
+        assert!(!locations.is_location_valid("unknown").await);
+    }
+}
@@ -6,13 +6,17 @@ extern crate rocket_okapi;
 use clap::Parser;
 use log::info;
 use network_defaults::setup_env;
+use std::time::Duration;
 
 pub(crate) mod cache;
 mod client;
 pub(crate) mod commands;
 mod country_statistics;
 mod gateways;
+mod geo_ip;
+mod history;
 mod http;
+pub(crate) mod location;
 mod mix_node;
 pub(crate) mod mix_nodes;
 mod overview;
@@ -23,13 +27,25 @@ mod validators;
 
 const GEO_IP_SERVICE: &str = "https://api.ipbase.com/json";
 const COUNTRY_DATA_REFRESH_INTERVAL: u64 = 60 * 15; // every 15 minutes
+const HISTORY_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60 * 60); // every hour
 
 #[tokio::main]
 async fn main() {
     setup_logging();
     let args = commands::Cli::parse();
     setup_env(args.config_env_file);
-    let mut explorer_api = ExplorerApi::new();
+
+    let history_database = history::get_history_database_path();
+    let history_retention = history::get_history_retention();
+    info!(
+        "Using history database {:?}, keeping the history for {:?}",
+        history_database, history_retention
+    );
+    let history = history::HistoryStorage::init(&history_database, history_retention)
+        .await
+        .expect("failed to open the history database");
+
+    let mut explorer_api = ExplorerApi::new(history);
     explorer_api.run().await;
 }
 
@@ -38,9 +54,9 @@ pub struct ExplorerApi {
 }
 
 impl ExplorerApi {
-    fn new() -> ExplorerApi {
+    fn new(history: history::HistoryStorage) -> ExplorerApi {
         ExplorerApi {
-            state: state::ExplorerApiStateContext::new(),
+            state: state::ExplorerApiStateContext::new(history),
         }
     }
 
@@ -11,6 +11,8 @@ use rocket_okapi::settings::OpenApiSettings;
 
 use mixnet_contract_common::Delegation;
 
+use crate::history::error::HistoryStorageError;
+use crate::history::models::{HistoryPage, HistoryQuery, MixnodeHistoryEntry};
 use crate::mix_node::delegations::{
     get_single_mixnode_delegations, get_single_mixnode_delegations_summed,
 };
@@ -28,6 +30,7 @@ pub fn mix_node_make_default_routes(settings: &OpenApiSettings) -> (Vec<Route>,
         get_description,
         get_stats,
         get_economic_dynamics_stats,
+        get_history,
     ]
 }
 
@@ -188,6 +191,26 @@ pub(crate) async fn get_economic_dynamics_stats(
     }
 }
 
+#[openapi(tag = "mix_node")]
+#[get("/<pubkey>/history?<from>&<to>&<page>&<size>")]
+pub(crate) async fn get_history(
+    pubkey: &str,
+    from: Option<i64>,
+    to: Option<i64>,
+    page: Option<u32>,
+    size: Option<u32>,
+    state: &State<ExplorerApiStateContext>,
+) -> Result<Json<HistoryPage<MixnodeHistoryEntry>>, HistoryStorageError> {
+    let query = HistoryQuery::new(from, to, page, size);
+    match state.inner.history.get_mixnode_history(pubkey, query).await {
+        Ok(history) => Ok(Json(history)),
+        Err(e) => {
+            error!("Unable to get history of {} -> {}", pubkey, e);
+            Err(e)
+        }
+    }
+}
+
 async fn get_mix_node_description(host: &str, port: u16) -> Result<NodeDescription, ReqwestError> {
     reqwest::get(format!("http://{}:{}/description", host, port))
         .await?
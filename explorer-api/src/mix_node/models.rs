@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::cache::Cache;
-use crate::mix_nodes::location::Location;
+use crate::location::Location;
 use mixnet_contract_common::{Addr, Coin, Layer, MixNode};
 use mixnet_contract_common::{Delegation, IdentityKey};
 use serde::Deserialize;
@@ -20,6 +20,16 @@ pub(crate) enum MixnodeStatus {
     Inactive, // in neither the rewarded set nor the active set
 }
 
+impl MixnodeStatus {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            MixnodeStatus::Active => "active",
+            MixnodeStatus::Standby => "standby",
+            MixnodeStatus::Inactive => "inactive",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, JsonSchema)]
 pub(crate) struct PrettyDetailedMixNodeBond {
     pub location: Option<Location>,
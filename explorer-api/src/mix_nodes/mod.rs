@@ -4,7 +4,6 @@
 use std::time::Duration;
 
 pub(crate) mod http;
-pub(crate) mod location;
 pub(crate) mod models;
 pub(crate) mod utils;
 
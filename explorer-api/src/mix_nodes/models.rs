@@ -10,8 +10,8 @@ use tokio::sync::RwLock;
 
 use validator_client::models::MixNodeBondAnnotated;
 
+use crate::location::{LocationCache, ThreadsafeLocationCache};
 use crate::mix_node::models::{MixnodeStatus, PrettyDetailedMixNodeBond};
-use crate::mix_nodes::location::{Location, LocationCache, LocationCacheItem};
 use crate::mix_nodes::CACHE_ENTRY_TTL;
 
 #[derive(Clone, Debug, Serialize, JsonSchema)]
@@ -79,44 +79,26 @@ impl MixNodesResult {
 #[derive(Clone)]
 pub(crate) struct ThreadsafeMixNodesCache {
     mixnodes: Arc<RwLock<MixNodesResult>>,
-    locations: Arc<RwLock<LocationCache>>,
+    locations: ThreadsafeLocationCache,
 }
 
 impl ThreadsafeMixNodesCache {
     pub(crate) fn new() -> Self {
         ThreadsafeMixNodesCache {
             mixnodes: Arc::new(RwLock::new(MixNodesResult::new())),
-            locations: Arc::new(RwLock::new(LocationCache::new())),
+            locations: ThreadsafeLocationCache::new(),
         }
     }
 
     pub(crate) fn new_with_location_cache(locations: LocationCache) -> Self {
         ThreadsafeMixNodesCache {
             mixnodes: Arc::new(RwLock::new(MixNodesResult::new())),
-            locations: Arc::new(RwLock::new(locations)),
+            locations: ThreadsafeLocationCache::new_with_cache(locations),
         }
     }
 
-    pub(crate) async fn is_location_valid(&self, identity_key: &str) -> bool {
-        self.locations
-            .read()
-            .await
-            .get(identity_key)
-            .map_or(false, |cache_item| {
-                cache_item.valid_until > SystemTime::now()
-            })
-    }
-
-    pub(crate) async fn get_locations(&self) -> LocationCache {
-        self.locations.read().await.clone()
-    }
-
-    pub(crate) async fn set_location(&self, identity_key: &str, location: Option<Location>) {
-        // cache the location for this mix node so that it can be used when the mix node list is refreshed
-        self.locations.write().await.insert(
-            identity_key.to_string(),
-            LocationCacheItem::new_from_location(location),
-        );
+    pub(crate) fn locations(&self) -> &ThreadsafeLocationCache {
+        &self.locations
     }
 
     pub(crate) async fn get_mixnode(&self, pubkey: &str) -> Option<MixNodeBondAnnotated> {
@@ -1,16 +1,15 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::mix_nodes::location::GeoLocation;
 use isocountry::CountryCode;
 
-pub(crate) fn map_2_letter_to_3_letter_country_code(geo: &GeoLocation) -> String {
-    match CountryCode::for_alpha2(&geo.country_code) {
+pub(crate) fn map_2_letter_to_3_letter_country_code(two_letter_country_code: &str) -> String {
+    match CountryCode::for_alpha2(two_letter_country_code) {
         Ok(three_letter_country_code) => three_letter_country_code.alpha3().to_string(),
         Err(_e) => {
             warn!(
-                "❌ Oh no! map_2_letter_to_3_letter_country_code failed for '{:#?}'",
-                geo
+                "❌ Oh no! map_2_letter_to_3_letter_country_code failed for '{}'",
+                two_letter_country_code
             );
             "???".to_string()
         }
@@ -4,12 +4,14 @@ use rocket_okapi::okapi::openapi3::OpenApi;
 use rocket_okapi::openapi_get_routes_spec;
 use rocket_okapi::settings::OpenApiSettings;
 
+use crate::history::error::HistoryStorageError;
+use crate::history::models::{HistoryPage, HistoryQuery, NetworkHistoryEntry};
 use crate::mix_nodes::http::get_mixnode_summary;
 use crate::overview::models::OverviewSummary;
 use crate::state::ExplorerApiStateContext;
 
 pub fn overview_make_default_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
-    openapi_get_routes_spec![settings: summary]
+    openapi_get_routes_spec![settings: summary, history]
 }
 
 #[openapi(tag = "overview")]
@@ -21,3 +23,22 @@ pub(crate) async fn summary(state: &State<ExplorerApiStateContext>) -> Json<Over
         gateways: state.inner.gateways.get_gateway_summary().await,
     })
 }
+
+#[openapi(tag = "overview")]
+#[get("/history?<from>&<to>&<page>&<size>")]
+pub(crate) async fn history(
+    from: Option<i64>,
+    to: Option<i64>,
+    page: Option<u32>,
+    size: Option<u32>,
+    state: &State<ExplorerApiStateContext>,
+) -> Result<Json<HistoryPage<NetworkHistoryEntry>>, HistoryStorageError> {
+    let query = HistoryQuery::new(from, to, page, size);
+    match state.inner.history.get_network_history(query).await {
+        Ok(history) => Ok(Json(history)),
+        Err(e) => {
+            error!("Unable to get network history -> {}", e);
+            Err(e)
+        }
+    }
+}
@@ -8,9 +8,7 @@ use rocket_okapi::okapi::openapi3::OpenApi;
 use rocket_okapi::openapi_get_routes_spec;
 use rocket_okapi::settings::OpenApiSettings;
 
-use mixnet_contract_common::MixNodeBond;
-
-use crate::ping::models::PingResponse;
+use crate::ping::models::{PingResponse, ThreadsafePingCache};
 use crate::state::ExplorerApiStateContext;
 
 const CONNECTION_TIMEOUT_SECONDS: Duration = Duration::from_secs(10);
@@ -38,23 +36,15 @@ pub(crate) async fn index(
 
             match state.inner.get_mix_node(pubkey).await {
                 Some(bond) => {
-                    // set status to pending, so that any HTTP requests are pending
-                    state.inner.ping.set_pending(pubkey).await;
-
-                    // do the check
-                    let ports = Some(port_check(&bond.mixnode_bond).await);
-                    trace!("Tested mix node {}: {:?}", pubkey, ports);
-                    let response = PingResponse {
-                        ports,
-                        pending: false,
-                    };
-
-                    // cache for 1 min
-                    trace!("Caching value for {}", pubkey);
-                    state.inner.ping.set(pubkey, response.clone()).await;
-
-                    // return response
-                    Some(Json(response))
+                    let mix_node = bond.mix_node();
+                    let ports_to_test = vec![
+                        mix_node.http_api_port,
+                        mix_node.mix_port,
+                        mix_node.verloc_port,
+                    ];
+                    Some(Json(
+                        ping_node(&state.inner.ping, pubkey, &mix_node.host, ports_to_test).await,
+                    ))
                 }
                 None => None,
             }
@@ -62,23 +52,42 @@ pub(crate) async fn index(
     }
 }
 
-async fn port_check(bond: &MixNodeBond) -> HashMap<u16, bool> {
-    let mut ports: HashMap<u16, bool> = HashMap::new();
+/// Checks whether the node is reachable on all of the provided ports and caches the result.
+pub(crate) async fn ping_node(
+    ping_cache: &ThreadsafePingCache,
+    identity_key: &str,
+    host: &str,
+    ports_to_test: Vec<u16>,
+) -> PingResponse {
+    // set status to pending, so that any HTTP requests are pending
+    ping_cache.set_pending(identity_key).await;
+
+    // do the check
+    let ports = Some(port_check(identity_key, host, ports_to_test).await);
+    trace!("Tested node {}: {:?}", identity_key, ports);
+    let response = PingResponse {
+        ports,
+        pending: false,
+    };
+
+    // cache for 1 min
+    trace!("Caching value for {}", identity_key);
+    ping_cache.set(identity_key, response.clone()).await;
+
+    response
+}
 
-    let ports_to_test = vec![
-        bond.mix_node.http_api_port,
-        bond.mix_node.mix_port,
-        bond.mix_node.verloc_port,
-    ];
+async fn port_check(identity_key: &str, host: &str, ports_to_test: Vec<u16>) -> HashMap<u16, bool> {
+    let mut ports: HashMap<u16, bool> = HashMap::new();
 
     trace!(
-        "Testing mix node {} on ports {:?}...",
-        bond.mix_node.identity_key,
+        "Testing node {} on ports {:?}...",
+        identity_key,
         ports_to_test
     );
 
     for port in ports_to_test {
-        ports.insert(port, do_port_check(&bond.mix_node.host, port).await);
+        ports.insert(port, do_port_check(host, port).await);
     }
 
     ports
@@ -12,8 +12,9 @@ use crate::country_statistics::country_nodes_distribution::{
     CountryNodesDistribution, ThreadsafeCountryNodesDistribution,
 };
 use crate::gateways::models::ThreadsafeGatewayCache;
+use crate::history::HistoryStorage;
+use crate::location::LocationCache;
 use crate::mix_node::models::ThreadsafeMixNodeCache;
-use crate::mix_nodes::location::LocationCache;
 use crate::mix_nodes::models::ThreadsafeMixNodesCache;
 use crate::ping::models::ThreadsafePingCache;
 use crate::validators::models::ThreadsafeValidatorCache;
@@ -24,7 +25,9 @@ const STATE_FILE: &str = "explorer-api-state.json";
 #[derive(Clone)]
 pub struct ExplorerApiState {
     pub(crate) country_node_distribution: ThreadsafeCountryNodesDistribution,
+    pub(crate) gateway_country_node_distribution: ThreadsafeCountryNodesDistribution,
     pub(crate) gateways: ThreadsafeGatewayCache,
+    pub(crate) history: HistoryStorage,
     pub(crate) mixnode: ThreadsafeMixNodeCache,
     pub(crate) mixnodes: ThreadsafeMixNodesCache,
     pub(crate) ping: ThreadsafePingCache,
@@ -44,6 +47,10 @@ impl ExplorerApiState {
 pub struct ExplorerApiStateOnDisk {
     pub(crate) country_node_distribution: CountryNodesDistribution,
     pub(crate) location_cache: LocationCache,
+    #[serde(default)]
+    pub(crate) gateway_country_node_distribution: CountryNodesDistribution,
+    #[serde(default)]
+    pub(crate) gateway_location_cache: LocationCache,
     pub(crate) as_at: DateTime<Utc>,
 }
 
@@ -53,13 +60,13 @@ pub(crate) struct ExplorerApiStateContext {
 }
 
 impl ExplorerApiStateContext {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(history: HistoryStorage) -> Self {
         ExplorerApiStateContext {
-            inner: ExplorerApiStateContext::read_from_file(),
+            inner: ExplorerApiStateContext::read_from_file(history),
         }
     }
 
-    pub(crate) fn read_from_file() -> ExplorerApiState {
+    pub(crate) fn read_from_file(history: HistoryStorage) -> ExplorerApiState {
         let json_file = get_state_file_path();
         let json_file_path = Path::new(&json_file);
         info!("Loading state from file {:?}...", json_file);
@@ -73,7 +80,14 @@ impl ExplorerApiStateContext {
                     ThreadsafeCountryNodesDistribution::new_from_distribution(
                         state.country_node_distribution,
                     ),
-                gateways: ThreadsafeGatewayCache::new(),
+                gateway_country_node_distribution:
+                    ThreadsafeCountryNodesDistribution::new_from_distribution(
+                        state.gateway_country_node_distribution,
+                    ),
+                gateways: ThreadsafeGatewayCache::new_with_location_cache(
+                    state.gateway_location_cache,
+                ),
+                history,
                 mixnode: ThreadsafeMixNodeCache::new(),
                 mixnodes: ThreadsafeMixNodesCache::new_with_location_cache(state.location_cache),
                 ping: ThreadsafePingCache::new(),
@@ -88,7 +102,9 @@ impl ExplorerApiStateContext {
 
             ExplorerApiState {
                 country_node_distribution: ThreadsafeCountryNodesDistribution::new(),
+                gateway_country_node_distribution: ThreadsafeCountryNodesDistribution::new(),
                 gateways: ThreadsafeGatewayCache::new(),
+                history,
                 mixnode: ThreadsafeMixNodeCache::new(),
                 mixnodes: ThreadsafeMixNodesCache::new(),
                 ping: ThreadsafePingCache::new(),
@@ -104,7 +120,13 @@ impl ExplorerApiStateContext {
         let file = File::create(json_file_path).expect("unable to create state json file");
         let state = ExplorerApiStateOnDisk {
             country_node_distribution: self.inner.country_node_distribution.get_all().await,
-            location_cache: self.inner.mixnodes.get_locations().await,
+            location_cache: self.inner.mixnodes.locations().get_locations().await,
+            gateway_country_node_distribution: self
+                .inner
+                .gateway_country_node_distribution
+                .get_all()
+                .await,
+            gateway_location_cache: self.inner.gateways.locations().get_locations().await,
             as_at: Utc::now(),
         };
         serde_json::to_writer(file, &state).expect("error writing state to disk");
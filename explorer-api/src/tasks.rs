@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::future::Future;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use mixnet_contract_common::GatewayBond;
 use validator_client::models::MixNodeBondAnnotated;
@@ -11,14 +12,19 @@ use validator_client::ValidatorClientError;
 
 use crate::mix_nodes::CACHE_REFRESH_RATE;
 use crate::state::ExplorerApiStateContext;
+use crate::HISTORY_SNAPSHOT_INTERVAL;
 
 pub(crate) struct ExplorerApiTasks {
     state: ExplorerApiStateContext,
+    last_history_snapshot: Option<Instant>,
 }
 
 impl ExplorerApiTasks {
     pub(crate) fn new(state: ExplorerApiStateContext) -> Self {
-        ExplorerApiTasks { state }
+        ExplorerApiTasks {
+            state,
+            last_history_snapshot: None,
+        }
     }
 
     // a helper to remove duplicate code when grabbing active/rewarded/all mixnodes
@@ -128,7 +134,46 @@ impl ExplorerApiTasks {
         }
     }
 
-    pub(crate) fn start(self) {
+    async fn record_history(&mut self) {
+        // the caches are refreshed far more often than we'd want to grow the history
+        if let Some(last_snapshot) = self.last_history_snapshot {
+            if last_snapshot.elapsed() < HISTORY_SNAPSHOT_INTERVAL {
+                return;
+            }
+        }
+
+        let mixnodes = self.state.inner.mixnodes.get_detailed_mixnodes().await;
+        if mixnodes.is_empty() {
+            // there's no point in recording history before the caches got populated
+            return;
+        }
+        let gateways = self.state.inner.gateways.get_gateway_summary().await.count;
+        let validators = self
+            .state
+            .inner
+            .validators
+            .get_validator_summary()
+            .await
+            .count;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the system clock is set before the unix epoch")
+            .as_secs() as i64;
+
+        match self
+            .state
+            .inner
+            .history
+            .insert_snapshot(timestamp, &mixnodes, gateways, validators as usize)
+            .await
+        {
+            Ok(_) => self.last_history_snapshot = Some(Instant::now()),
+            Err(e) => error!("Failed to record network history: {}", e),
+        }
+    }
+
+    pub(crate) fn start(mut self) {
         info!("Spawning mix nodes task runner...");
         tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(CACHE_REFRESH_RATE);
@@ -146,6 +191,8 @@ impl ExplorerApiTasks {
 
                 info!("Updating mix node cache...");
                 self.update_mixnode_cache().await;
+
+                self.record_history().await;
             }
         });
     }
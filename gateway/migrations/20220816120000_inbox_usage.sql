@@ -0,0 +1,42 @@
+/*
+ * Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- number and total size of messages stored for each client, so that the inbox quota could be
+-- checked without scanning all of the stored messages
+CREATE TABLE inbox_usage
+(
+    client_address_bs58 TEXT    NOT NULL PRIMARY KEY UNIQUE,
+    message_count       INTEGER NOT NULL,
+    total_size          INTEGER NOT NULL
+);
+
+INSERT INTO inbox_usage(client_address_bs58, message_count, total_size)
+SELECT client_address_bs58, COUNT(*), SUM(LENGTH(content))
+FROM message_store
+GROUP BY client_address_bs58;
+
+-- the triggers run as part of the statement modifying the message store,
+-- so the usage can never get out of sync with the actual messages
+CREATE TRIGGER inbox_usage_on_insert
+    AFTER INSERT
+    ON message_store
+BEGIN
+    INSERT INTO inbox_usage(client_address_bs58, message_count, total_size)
+    VALUES (NEW.client_address_bs58, 1, LENGTH(NEW.content))
+    ON CONFLICT(client_address_bs58) DO UPDATE SET message_count = message_count + 1,
+                                                   total_size    = total_size + LENGTH(NEW.content);
+END;
+
+CREATE TRIGGER inbox_usage_on_delete
+    AFTER DELETE
+    ON message_store
+BEGIN
+    UPDATE inbox_usage
+    SET message_count = message_count - 1,
+        total_size    = total_size - LENGTH(OLD.content)
+    WHERE client_address_bs58 = OLD.client_address_bs58;
+
+    DELETE FROM inbox_usage WHERE client_address_bs58 = OLD.client_address_bs58 AND message_count <= 0;
+END;
@@ -31,6 +31,10 @@ pub struct Init {
     #[clap(long)]
     clients_port: Option<u16>,
 
+    /// The port on which the gateway will be listening for HTTP API requests, such as for its description
+    #[clap(long)]
+    http_api_port: Option<u16>,
+
     /// The host that will be reported to the directory server
     #[clap(long)]
     announce_host: Option<String>,
@@ -39,6 +43,10 @@ pub struct Init {
     #[clap(long)]
     datastore: Option<String>,
 
+    /// Keep all client data in memory only, without ever writing it to the disk
+    #[clap(long)]
+    in_memory_storage: Option<bool>,
+
     /// Comma separated list of endpoints of the validators APIs
     #[clap(long)]
     validator_apis: Option<String>,
@@ -77,7 +85,9 @@ impl From<Init> for OverrideConfig {
             wallet_address: Some(init_config.wallet_address),
             mix_port: init_config.mix_port,
             clients_port: init_config.clients_port,
+            http_api_port: init_config.http_api_port,
             datastore: init_config.datastore,
+            in_memory_storage: init_config.in_memory_storage,
             announce_host: init_config.announce_host,
             validator_apis: init_config.validator_apis,
             validators: init_config.validators,
@@ -149,16 +159,25 @@ pub async fn execute(args: &Init) {
     println!("Saved configuration file to {:?}", config_save_location);
     println!("Gateway configuration completed.\n\n\n");
 
-    crate::node::create_gateway(config)
-        .await
-        .print_node_details();
+    if config.get_in_memory_storage() {
+        crate::node::create_ephemeral_gateway(config)
+            .await
+            .print_node_details();
+    } else {
+        crate::node::create_gateway(config)
+            .await
+            .print_node_details();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use network_defaults::var_names::BECH32_PREFIX;
 
-    use crate::node::{storage::InMemStorage, Gateway};
+    use crate::node::{
+        storage::{InMemStorage, InboxQuota},
+        Gateway,
+    };
 
     use super::*;
 
@@ -170,8 +189,10 @@ mod tests {
             wallet_address: "n1z9egw0knv47nmur0p8vk4rcx59h9gg4zjx9ede".to_string(),
             mix_port: Some(42),
             clients_port: Some(43),
+            http_api_port: Some(44),
             announce_host: Some("foo-announce-host".to_string()),
             datastore: Some("foo-datastore".to_string()),
+            in_memory_storage: None,
             validator_apis: None,
             validators: None,
             mnemonic: None,
@@ -186,6 +207,7 @@ mod tests {
 
         let config = Config::new(&args.id);
         let config = override_config(config, OverrideConfig::from(args.clone()));
+        assert_eq!(config.get_http_api_port(), 44);
 
         let (identity_keys, sphinx_keys) = {
             let mut rng = rand::rngs::OsRng;
@@ -195,9 +217,16 @@ mod tests {
             )
         };
 
+        let storage = InMemStorage::new(
+            config.get_message_retrieval_limit(),
+            InboxQuota {
+                max_messages: config.get_max_client_inbox_messages(),
+                max_bytes: config.get_max_client_inbox_bytes(),
+            },
+        );
+
         // The test is really if this instantiates with InMemStorage without panics
         let _gateway =
-            Gateway::new_from_keys_and_storage(config, identity_keys, sphinx_keys, InMemStorage)
-                .await;
+            Gateway::new_from_keys_and_storage(config, identity_keys, sphinx_keys, storage).await;
     }
 }
@@ -12,6 +12,7 @@ use network_defaults::var_names::{
 };
 use url::Url;
 
+pub(crate) mod describe;
 pub(crate) mod init;
 pub(crate) mod node_details;
 pub(crate) mod run;
@@ -22,6 +23,9 @@ pub(crate) mod upgrade;
 const DEFAULT_ETH_ENDPOINT: &str = "https://rinkeby.infura.io/v3/00000000000000000000000000000000";
 #[derive(Subcommand)]
 pub(crate) enum Commands {
+    /// Describe your gateway and tell people why they should use it
+    Describe(describe::Describe),
+
     /// Initialise the gateway
     Init(init::Init),
 
@@ -44,7 +48,9 @@ pub(crate) struct OverrideConfig {
     wallet_address: Option<String>,
     mix_port: Option<u16>,
     clients_port: Option<u16>,
+    http_api_port: Option<u16>,
     datastore: Option<String>,
+    in_memory_storage: Option<bool>,
     announce_host: Option<String>,
     enabled_statistics: Option<bool>,
     statistics_service_url: Option<String>,
@@ -61,6 +67,7 @@ pub(crate) struct OverrideConfig {
 
 pub(crate) async fn execute(args: Cli) {
     match &args.command {
+        Commands::Describe(m) => describe::execute(m),
         Commands::Init(m) => init::execute(m).await,
         Commands::NodeDetails(m) => node_details::execute(m).await,
         Commands::Run(m) => run::execute(m).await,
@@ -95,6 +102,10 @@ pub(crate) fn override_config(mut config: Config, args: OverrideConfig) -> Confi
         config = config.with_clients_port(clients_port);
     }
 
+    if let Some(http_api_port) = args.http_api_port {
+        config = config.with_http_api_port(http_api_port);
+    }
+
     if let Some(announce_host) = args.announce_host {
         config = config.with_announce_address(announce_host);
     } else if was_host_overridden {
@@ -146,6 +157,10 @@ pub(crate) fn override_config(mut config: Config, args: OverrideConfig) -> Confi
         config = config.with_custom_persistent_store(datastore_path);
     }
 
+    if let Some(in_memory_storage) = args.in_memory_storage {
+        config = config.with_in_memory_storage(in_memory_storage);
+    }
+
     if let Some(cosmos_mnemonic) = args.mnemonic {
         config = config.with_cosmos_mnemonic(
             bip39::Mnemonic::from_str(&cosmos_mnemonic).expect("Provided mnemonic is invalid"),
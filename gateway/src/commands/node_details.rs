@@ -26,7 +26,13 @@ pub async fn execute(args: &NodeDetails) {
         }
     };
 
-    crate::node::create_gateway(config)
-        .await
-        .print_node_details();
+    if config.get_in_memory_storage() {
+        crate::node::create_ephemeral_gateway(config)
+            .await
+            .print_node_details();
+    } else {
+        crate::node::create_gateway(config)
+            .await
+            .print_node_details();
+    }
 }
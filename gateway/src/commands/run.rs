@@ -1,6 +1,8 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::node::storage::Storage;
+use crate::node::Gateway;
 use crate::{
     commands::{override_config, version_check, OverrideConfig},
     config::Config,
@@ -31,6 +33,10 @@ pub struct Run {
     #[clap(long)]
     clients_port: Option<u16>,
 
+    /// The port on which the gateway will be listening for HTTP API requests, such as for its description
+    #[clap(long)]
+    http_api_port: Option<u16>,
+
     /// The host that will be reported to the directory server
     #[clap(long)]
     announce_host: Option<String>,
@@ -39,6 +45,10 @@ pub struct Run {
     #[clap(long)]
     datastore: Option<String>,
 
+    /// Keep all client data in memory only, without ever writing it to the disk
+    #[clap(long)]
+    in_memory_storage: Option<bool>,
+
     /// Comma separated list of endpoints of the validators APIs
     #[clap(long)]
     validator_apis: Option<String>,
@@ -77,7 +87,9 @@ impl From<Run> for OverrideConfig {
             wallet_address: run_config.wallet_address,
             mix_port: run_config.mix_port,
             clients_port: run_config.clients_port,
+            http_api_port: run_config.http_api_port,
             datastore: run_config.datastore,
+            in_memory_storage: run_config.in_memory_storage,
             announce_host: run_config.announce_host,
             validator_apis: run_config.validator_apis,
             validators: run_config.validators,
@@ -136,7 +148,17 @@ pub async fn execute(args: &Run) {
         show_binding_warning(config.get_listening_address().to_string());
     }
 
-    let mut gateway = crate::node::create_gateway(config).await;
+    if config.get_in_memory_storage() {
+        start_gateway(crate::node::create_ephemeral_gateway(config).await).await
+    } else {
+        start_gateway(crate::node::create_gateway(config).await).await
+    }
+}
+
+async fn start_gateway<St>(mut gateway: Gateway<St>)
+where
+    St: Storage + Clone + 'static,
+{
     println!(
         "\nTo bond your gateway you will need to install the Nym wallet, go to https://nymtech.net/get-involved and select the Download button.\n\
          Select the correct version and install it to your machine. You will need to provide the following: \n ");
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::template::config_template;
-use config::defaults::{DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT};
+use config::defaults::{
+    DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_GATEWAY_HTTP_API_LISTENING_PORT,
+    DEFAULT_MIX_LISTENING_PORT,
+};
 use config::NymConfig;
 use log::error;
 use serde::{Deserialize, Serialize};
@@ -27,6 +30,10 @@ const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 128;
 
 const DEFAULT_STORED_MESSAGE_FILENAME_LENGTH: u16 = 16;
 const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
+const DEFAULT_MESSAGE_TIME_TO_LIVE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const DEFAULT_INBOX_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_MAX_CLIENT_INBOX_MESSAGES: i64 = 20_000;
+const DEFAULT_MAX_CLIENT_INBOX_BYTES: i64 = 64 * 1024 * 1024;
 
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
@@ -44,10 +51,16 @@ fn default_clients_port() -> u16 {
     DEFAULT_CLIENT_LISTENING_PORT
 }
 
+fn default_http_api_port() -> u16 {
+    DEFAULT_GATEWAY_HTTP_API_LISTENING_PORT
+}
+
 #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Config {
     gateway: Gateway,
 
+    #[serde(default)]
+    metrics: Metrics,
     #[serde(default)]
     logging: Logging,
     #[serde(default)]
@@ -187,6 +200,11 @@ impl Config {
         self
     }
 
+    pub fn with_http_api_port(mut self, port: u16) -> Self {
+        self.gateway.http_api_port = port;
+        self
+    }
+
     pub fn announce_host_from_listening_host(mut self) -> Self {
         self.gateway.announce_address = self.gateway.listening_address.to_string();
         self
@@ -197,6 +215,11 @@ impl Config {
         self
     }
 
+    pub fn with_in_memory_storage(mut self, in_memory_storage: bool) -> Self {
+        self.gateway.in_memory_storage = in_memory_storage;
+        self
+    }
+
     pub fn with_custom_version(mut self, version: &str) -> Self {
         self.gateway.version = version.to_string();
         self
@@ -245,6 +268,10 @@ impl Config {
         self.gateway.statistics_service_url.clone()
     }
 
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.metrics.enabled
+    }
+
     pub fn get_validator_api_endpoints(&self) -> Vec<Url> {
         self.gateway.validator_api_urls.clone()
     }
@@ -273,10 +300,18 @@ impl Config {
         self.gateway.clients_port
     }
 
+    pub fn get_http_api_port(&self) -> u16 {
+        self.gateway.http_api_port
+    }
+
     pub fn get_persistent_store_path(&self) -> PathBuf {
         self.gateway.persistent_storage.clone()
     }
 
+    pub fn get_in_memory_storage(&self) -> bool {
+        self.gateway.in_memory_storage
+    }
+
     pub fn get_packet_forwarding_initial_backoff(&self) -> Duration {
         self.debug.packet_forwarding_initial_backoff
     }
@@ -297,6 +332,22 @@ impl Config {
         self.debug.message_retrieval_limit
     }
 
+    pub fn get_message_time_to_live(&self) -> Duration {
+        self.debug.message_time_to_live
+    }
+
+    pub fn get_inbox_pruning_interval(&self) -> Duration {
+        self.debug.inbox_pruning_interval
+    }
+
+    pub fn get_max_client_inbox_messages(&self) -> i64 {
+        self.debug.max_client_inbox_messages
+    }
+
+    pub fn get_max_client_inbox_bytes(&self) -> i64 {
+        self.debug.max_client_inbox_bytes
+    }
+
     pub fn get_version(&self) -> &str {
         &self.gateway.version
     }
@@ -340,6 +391,11 @@ pub struct Gateway {
     #[serde(default = "default_clients_port")]
     clients_port: u16,
 
+    /// Port used for the HTTP API exposing the description of the gateway.
+    /// (default: 8001)
+    #[serde(default = "default_http_api_port")]
+    http_api_port: u16,
+
     /// Path to file containing private identity key.
     private_identity_key_file: PathBuf,
 
@@ -379,6 +435,12 @@ pub struct Gateway {
     /// derived shared keys and available client bandwidths.
     persistent_storage: PathBuf,
 
+    /// Indicates whether all of the client data (derived shared keys, messages for offline
+    /// clients and available bandwidths) should only be kept in memory and never written to
+    /// the disk. Note that in that case all clients have to register again after a restart.
+    #[serde(default)]
+    in_memory_storage: bool,
+
     /// The Cosmos wallet address that will control this gateway
     wallet_address: String,
 }
@@ -415,6 +477,7 @@ impl Default for Gateway {
             announce_address: "127.0.0.1".to_string(),
             mix_port: DEFAULT_MIX_LISTENING_PORT,
             clients_port: DEFAULT_CLIENT_LISTENING_PORT,
+            http_api_port: DEFAULT_GATEWAY_HTTP_API_LISTENING_PORT,
             private_identity_key_file: Default::default(),
             public_identity_key_file: Default::default(),
             private_sphinx_key_file: Default::default(),
@@ -428,6 +491,7 @@ impl Default for Gateway {
             cosmos_mnemonic: bip39::Mnemonic::from_str("exact antique hybrid width raise anchor puzzle degree fee quit long crack net vague hip despair write put useless civil mechanic broom music day").unwrap(),
             nym_root_directory: Config::default_root_directory(),
             persistent_storage: Default::default(),
+            in_memory_storage: false,
             wallet_address: "nymXXXXXXXX".to_string(),
         }
     }
@@ -437,6 +501,14 @@ impl Default for Gateway {
 #[serde(deny_unknown_fields)]
 struct Logging {}
 
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct Metrics {
+    /// Indicates whether the gateway should expose its metrics in the Prometheus text format
+    /// under the `/metrics` endpoint of its HTTP API.
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 struct Debug {
@@ -466,6 +538,21 @@ struct Debug {
 
     /// Number of messages from offline client that can be pulled at once from the storage.
     message_retrieval_limit: i64,
+
+    /// Duration for which messages for offline clients are kept in the storage
+    /// before getting removed.
+    #[serde(with = "humantime_serde")]
+    message_time_to_live: Duration,
+
+    /// Delay between subsequent attempts at removing expired messages from the storage.
+    #[serde(with = "humantime_serde")]
+    inbox_pruning_interval: Duration,
+
+    /// Maximum number of messages that can be stored for a single offline client.
+    max_client_inbox_messages: i64,
+
+    /// Maximum total size (in bytes) of messages that can be stored for a single offline client.
+    max_client_inbox_bytes: i64,
 }
 
 impl Default for Debug {
@@ -478,6 +565,10 @@ impl Default for Debug {
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
             stored_messages_filename_length: DEFAULT_STORED_MESSAGE_FILENAME_LENGTH,
             message_retrieval_limit: DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
+            message_time_to_live: DEFAULT_MESSAGE_TIME_TO_LIVE,
+            inbox_pruning_interval: DEFAULT_INBOX_PRUNING_INTERVAL,
+            max_client_inbox_messages: DEFAULT_MAX_CLIENT_INBOX_MESSAGES,
+            max_client_inbox_bytes: DEFAULT_MAX_CLIENT_INBOX_BYTES,
         }
     }
 }
@@ -56,6 +56,10 @@ mix_port = {{ gateway.mix_port }}
 # (default: 9000)
 clients_port = {{ gateway.clients_port }}
 
+# Port used for the HTTP API exposing the description of the gateway.
+# (default: 8001)
+http_api_port = {{ gateway.http_api_port }}
+
 # Wheather gateway collects and sends anonymized statistics
 enabled_statistics = {{ gateway.enabled_statistics }}
 
@@ -91,6 +95,19 @@ nym_root_directory = '{{ gateway.nym_root_directory }}'
 # derived shared keys and available client bandwidths.
 persistent_storage = '{{ gateway.persistent_storage }}'
 
+# Indicates whether all of the client data (derived shared keys, messages for offline
+# clients and available bandwidths) should only be kept in memory and never written to
+# the disk. Note that in that case all clients have to register again after a restart.
+in_memory_storage = {{ gateway.in_memory_storage }}
+
+##### metrics configuration options #####
+
+[metrics]
+
+# Indicates whether the gateway should expose its metrics in the Prometheus text format
+# under the `/metrics` endpoint of its HTTP API.
+enabled = {{ metrics.enabled }}
+
 ##### logging configuration options #####
 
 [logging]
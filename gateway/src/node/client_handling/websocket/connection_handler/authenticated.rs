@@ -184,6 +184,7 @@ where
             .storage
             .consume_bandwidth(self.client.address, amount)
             .await?;
+        self.inner.metrics.bandwidth_consumed.inc_by(amount as u64);
         Ok(())
     }
 
@@ -197,6 +198,7 @@ where
             error!("We failed to forward requested mix packet - {}. Presumably our mix forwarder has crashed. We cannot continue.", err);
             process::exit(1);
         }
+        self.inner.metrics.packets_forwarded.inc();
     }
 
     #[cfg(feature = "coconut")]
@@ -483,3 +485,122 @@ where
         trace!("The stream was closed!");
     }
 }
+
+#[cfg(all(test, not(feature = "coconut")))]
+mod tests {
+    use super::*;
+    use crate::node::client_handling::websocket::connection_handler::testing::{
+        mix_packet, TestGateway,
+    };
+
+    #[tokio::test]
+    async fn testnet_bandwidth_can_be_claimed_in_disabled_credentials_mode() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+        client.register().await;
+
+        let response = client
+            .send_request(ClientControlRequest::ClaimFreeTestnetBandwidth.into())
+            .await;
+        assert!(matches!(
+            response,
+            ServerResponse::Bandwidth { available_total } if available_total == FREE_TESTNET_BANDWIDTH_VALUE
+        ));
+    }
+
+    #[tokio::test]
+    async fn testnet_bandwidth_cannot_be_claimed_with_enabled_credentials() {
+        let gateway = TestGateway::new(false);
+        let mut client = gateway.connect().await;
+        client.register().await;
+
+        assert!(client
+            .send_request(ClientControlRequest::ClaimFreeTestnetBandwidth.into())
+            .await
+            .is_error());
+        assert_eq!(
+            Some(0),
+            gateway
+                .storage
+                .get_available_bandwidth(client.address())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn packets_are_only_forwarded_with_sufficient_bandwidth() {
+        let mut gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+        let (shared_keys, _) = client.register().await;
+
+        assert!(client
+            .forward_packet(&shared_keys, mix_packet())
+            .await
+            .is_error());
+        assert!(gateway.forwarded_packets.try_next().is_err());
+
+        client
+            .send_request(ClientControlRequest::ClaimFreeTestnetBandwidth.into())
+            .await;
+
+        let packet = mix_packet();
+        let packet_size = packet.sphinx_packet().len() as i64;
+        let response = client.forward_packet(&shared_keys, packet).await;
+        assert!(matches!(
+            response,
+            ServerResponse::Send { remaining_bandwidth } if remaining_bandwidth == FREE_TESTNET_BANDWIDTH_VALUE - packet_size
+        ));
+        assert!(gateway.forwarded_packets.try_next().unwrap().is_some());
+
+        assert_eq!(gateway.metrics.packets_forwarded.get(), 1);
+        assert_eq!(gateway.metrics.bandwidth_consumed.get(), packet_size as u64);
+    }
+
+    #[tokio::test]
+    async fn mix_messages_are_pushed_to_the_client() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+        let (shared_keys, _) = client.register().await;
+
+        // getting any response after the registration implies the handler got upgraded
+        // and the client is present in the active clients store
+        client
+            .send_request(ClientControlRequest::ClaimFreeTestnetBandwidth.into())
+            .await;
+
+        let mix_sender = gateway.active_clients_store.get(client.address()).unwrap();
+        mix_sender
+            .unbounded_send(vec![b"foo".to_vec(), b"bar".to_vec()])
+            .unwrap();
+
+        assert_eq!(
+            b"foo".to_vec(),
+            client.read_pushed_message(&shared_keys).await
+        );
+        assert_eq!(
+            b"bar".to_vec(),
+            client.read_pushed_message(&shared_keys).await
+        );
+    }
+
+    #[tokio::test]
+    async fn client_is_removed_from_active_clients_upon_disconnection() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+        client.register().await;
+        client
+            .send_request(ClientControlRequest::ClaimFreeTestnetBandwidth.into())
+            .await;
+
+        let address = client.address();
+        let mix_sender = gateway.active_clients_store.get(address).unwrap();
+        client.disconnect();
+
+        // the sender gets closed once the handler is dropped
+        while !mix_sender.is_closed() {
+            tokio::task::yield_now().await
+        }
+        assert!(gateway.active_clients_store.get(address).is_none());
+    }
+}
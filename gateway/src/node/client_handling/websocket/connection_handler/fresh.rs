@@ -7,6 +7,7 @@ use crate::node::client_handling::websocket::connection_handler::coconut::Coconu
 use crate::node::client_handling::websocket::connection_handler::{
     AuthenticatedHandler, ClientDetails, InitialAuthResult, SocketStream,
 };
+use crate::node::metrics::GatewayMetrics;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::Storage;
 use crypto::asymmetric::identity;
@@ -72,6 +73,7 @@ pub(crate) struct FreshHandler<R, S, St> {
     local_identity: Arc<identity::KeyPair>,
     pub(crate) disabled_credentials_mode: bool,
     pub(crate) active_clients_store: ActiveClientsStore,
+    pub(crate) metrics: GatewayMetrics,
     pub(crate) outbound_mix_sender: MixForwardingSender,
     pub(crate) socket_connection: SocketStream<S>,
     pub(crate) storage: St,
@@ -100,12 +102,14 @@ where
         local_identity: Arc<identity::KeyPair>,
         storage: St,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
         #[cfg(feature = "coconut")] coconut_verifier: Arc<CoconutVerifier>,
         #[cfg(not(feature = "coconut"))] erc20_bridge: Arc<ERC20Bridge>,
     ) -> Self {
         FreshHandler {
             rng,
             active_clients_store,
+            metrics,
             disabled_credentials_mode,
             outbound_mix_sender,
             socket_connection: SocketStream::RawTcp(conn),
@@ -632,3 +636,130 @@ where
         super::handle_connection(self).await
     }
 }
+
+#[cfg(all(test, not(feature = "coconut")))]
+mod tests {
+    use super::*;
+    use crate::node::client_handling::websocket::connection_handler::testing::TestGateway;
+
+    #[tokio::test]
+    async fn registration_stores_shared_keys_and_creates_bandwidth_entry() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+
+        let (shared_keys, response) = client.register().await;
+        assert!(matches!(
+            response,
+            ServerResponse::Register { status: true }
+        ));
+
+        let stored_keys = gateway
+            .storage
+            .get_shared_keys(client.address())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            shared_keys.to_base58_string(),
+            stored_keys.derived_aes128_ctr_blake3_hmac_keys_bs58
+        );
+        assert_eq!(
+            Some(0),
+            gateway
+                .storage
+                .get_available_bandwidth(client.address())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn stored_messages_are_pushed_upon_registration() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+
+        gateway
+            .storage
+            .store_message(client.address(), b"foomp".to_vec())
+            .await
+            .unwrap();
+
+        let shared_keys = client.register_handshake().await;
+        assert_eq!(
+            b"foomp".to_vec(),
+            client.read_pushed_message(&shared_keys).await
+        );
+        assert!(matches!(
+            client.read_response().await,
+            ServerResponse::Register { status: true }
+        ));
+
+        let (remaining, _) = gateway
+            .storage
+            .retrieve_messages(client.address(), None)
+            .await
+            .unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn authentication_with_previously_derived_keys_succeeds() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+        let (shared_keys, _) = client.register().await;
+
+        // reconnect using the same identity
+        let identity = client.disconnect();
+        let mut reconnected = gateway.connect_as(identity).await;
+
+        // the previous connection might not have been cleaned up yet
+        let response = loop {
+            match reconnected.authenticate(&shared_keys).await {
+                ServerResponse::Error { .. } => tokio::task::yield_now().await,
+                response => break response,
+            }
+        };
+        assert!(matches!(
+            response,
+            ServerResponse::Authenticate {
+                status: true,
+                bandwidth_remaining: 0
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn authentication_of_unknown_client_fails() {
+        let gateway = TestGateway::new(true);
+        let mut registered = gateway.connect().await;
+        let (shared_keys, _) = registered.register().await;
+
+        let mut client = gateway.connect().await;
+        assert!(matches!(
+            client.authenticate(&shared_keys).await,
+            ServerResponse::Authenticate { status: false, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn binary_request_before_authentication_is_rejected() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+
+        assert!(client
+            .send_request(Message::Binary(vec![1, 2, 3]))
+            .await
+            .is_error());
+    }
+
+    #[tokio::test]
+    async fn bandwidth_request_before_authentication_is_rejected() {
+        let gateway = TestGateway::new(true);
+        let mut client = gateway.connect().await;
+
+        assert!(client
+            .send_request(ClientControlRequest::ClaimFreeTestnetBandwidth.into())
+            .await
+            .is_error());
+    }
+}
@@ -20,6 +20,9 @@ pub(crate) mod coconut;
 #[cfg(not(feature = "coconut"))]
 pub(crate) mod eth_events;
 mod fresh;
+// the handler tests currently only support the erc20 bandwidth credentials
+#[cfg(all(test, not(feature = "coconut")))]
+pub(crate) mod testing;
 
 //// TODO: note for my future self to consider the following idea:
 //// split the socket connection into sink and stream
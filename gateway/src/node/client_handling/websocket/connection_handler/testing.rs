@@ -0,0 +1,240 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for exercising the connection handlers against an in-memory gateway and a client
+//! connected to it over an in-process duplex stream.
+
+use crate::config::Config;
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::client_handling::websocket::connection_handler::eth_events::ERC20Bridge;
+use crate::node::client_handling::websocket::connection_handler::FreshHandler;
+use crate::node::metrics::GatewayMetrics;
+use crate::node::storage::{InMemStorage, InboxQuota};
+use crypto::asymmetric::identity;
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use gateway_requests::authentication::encrypted_address::EncryptedAddressBytes;
+use gateway_requests::iv::IV;
+use gateway_requests::registration::handshake::{client_handshake, SharedKeys};
+use gateway_requests::{BinaryRequest, BinaryResponse, ClientControlRequest, ServerResponse};
+use mixnet_client::forwarder::MixForwardingSender;
+use network_defaults::NymNetworkDetails;
+use nymsphinx::addressing::nodes::NymNodeRoutingAddress;
+use nymsphinx::builder::SphinxPacketBuilder;
+use nymsphinx::forwarding::packet::MixPacket;
+use nymsphinx::params::{PacketMode, PacketSize};
+use nymsphinx::{
+    Delay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
+    DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+};
+use rand::rngs::OsRng;
+use std::convert::TryFrom;
+use std::sync::Arc;
+use tokio::io::DuplexStream;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::WebSocketStream;
+use validator_client::nymd::{self, NymdClient};
+
+const CONNECTION_BUFFER_SIZE: usize = 64 * 1024;
+
+pub(crate) type TestHandler = FreshHandler<OsRng, DuplexStream, InMemStorage>;
+
+fn erc20_bridge() -> Arc<ERC20Bridge> {
+    // neither of those clients attempts to connect to anything until it's actually used
+    let client_config =
+        nymd::Config::try_from_nym_network_details(&NymNetworkDetails::new_mainnet()).unwrap();
+    let nymd_client = NymdClient::connect_with_mnemonic(
+        client_config,
+        "http://localhost:26657",
+        Config::default().get_cosmos_mnemonic(),
+        None,
+    )
+    .unwrap();
+
+    Arc::new(ERC20Bridge::new(
+        "http://localhost:8545".to_string(),
+        nymd_client,
+    ))
+}
+
+pub(crate) struct TestGateway {
+    pub(crate) identity: Arc<identity::KeyPair>,
+    pub(crate) storage: InMemStorage,
+    pub(crate) active_clients_store: ActiveClientsStore,
+    pub(crate) metrics: GatewayMetrics,
+    pub(crate) forwarded_packets: mpsc::UnboundedReceiver<MixPacket>,
+    outbound_mix_sender: MixForwardingSender,
+    disabled_credentials_mode: bool,
+    erc20_bridge: Arc<ERC20Bridge>,
+}
+
+impl TestGateway {
+    pub(crate) fn new(disabled_credentials_mode: bool) -> Self {
+        let (outbound_mix_sender, forwarded_packets) = mpsc::unbounded();
+        TestGateway {
+            identity: Arc::new(identity::KeyPair::new(&mut OsRng)),
+            storage: InMemStorage::new(
+                100,
+                InboxQuota {
+                    max_messages: 100,
+                    max_bytes: 1024 * 1024,
+                },
+            ),
+            active_clients_store: ActiveClientsStore::new(),
+            metrics: GatewayMetrics::new(),
+            forwarded_packets,
+            outbound_mix_sender,
+            disabled_credentials_mode,
+            erc20_bridge: erc20_bridge(),
+        }
+    }
+
+    pub(crate) fn fresh_handler(&self, conn: DuplexStream) -> TestHandler {
+        FreshHandler::new(
+            OsRng,
+            conn,
+            self.disabled_credentials_mode,
+            self.outbound_mix_sender.clone(),
+            Arc::clone(&self.identity),
+            self.storage.clone(),
+            self.active_clients_store.clone(),
+            self.metrics.clone(),
+            Arc::clone(&self.erc20_bridge),
+        )
+    }
+
+    /// Spawns a handler for a new connection and returns the client end of it
+    /// after completing the websocket handshake.
+    pub(crate) async fn connect(&self) -> TestClient {
+        self.connect_as(identity::KeyPair::new(&mut OsRng)).await
+    }
+
+    /// Same as `connect`, but uses the provided identity for the client.
+    pub(crate) async fn connect_as(&self, identity: identity::KeyPair) -> TestClient {
+        let (client_conn, gateway_conn) = tokio::io::duplex(CONNECTION_BUFFER_SIZE);
+        let handler = self.fresh_handler(gateway_conn);
+        tokio::spawn(handler.start_handling());
+
+        let (ws_stream, _) = tokio_tungstenite::client_async("ws://gateway", client_conn)
+            .await
+            .unwrap();
+        TestClient {
+            identity,
+            gateway_identity: *self.identity.public_key(),
+            ws_stream,
+        }
+    }
+}
+
+pub(crate) struct TestClient {
+    identity: identity::KeyPair,
+    gateway_identity: identity::PublicKey,
+    ws_stream: WebSocketStream<DuplexStream>,
+}
+
+impl TestClient {
+    pub(crate) fn address(&self) -> DestinationAddressBytes {
+        self.identity.public_key().derive_destination_address()
+    }
+
+    /// Closes the connection and returns the identity of the client so that it could reconnect.
+    pub(crate) fn disconnect(self) -> identity::KeyPair {
+        self.identity
+    }
+
+    pub(crate) async fn send(&mut self, msg: Message) {
+        self.ws_stream.send(msg).await.unwrap()
+    }
+
+    pub(crate) async fn read_message(&mut self) -> Message {
+        self.ws_stream.next().await.unwrap().unwrap()
+    }
+
+    pub(crate) async fn read_response(&mut self) -> ServerResponse {
+        match self.read_message().await {
+            Message::Text(text) => ServerResponse::try_from(text).unwrap(),
+            other => panic!("received unexpected websocket message {:?}", other),
+        }
+    }
+
+    pub(crate) async fn read_pushed_message(&mut self, shared_keys: &SharedKeys) -> Vec<u8> {
+        match self.read_message().await {
+            Message::Binary(bin) => {
+                match BinaryResponse::try_from_encrypted_tagged_bytes(bin, shared_keys).unwrap() {
+                    BinaryResponse::PushedMixMessage(message) => message,
+                }
+            }
+            other => panic!("received unexpected websocket message {:?}", other),
+        }
+    }
+
+    pub(crate) async fn send_request(&mut self, msg: Message) -> ServerResponse {
+        self.send(msg).await;
+        self.read_response().await
+    }
+
+    /// Performs the registration handshake with the gateway and returns the derived shared keys.
+    /// Note that it does not read the final registration response.
+    pub(crate) async fn register_handshake(&mut self) -> SharedKeys {
+        client_handshake(
+            &mut OsRng,
+            &mut self.ws_stream,
+            &self.identity,
+            self.gateway_identity,
+        )
+        .await
+        .unwrap()
+    }
+
+    pub(crate) async fn register(&mut self) -> (SharedKeys, ServerResponse) {
+        let shared_keys = self.register_handshake().await;
+        (shared_keys, self.read_response().await)
+    }
+
+    pub(crate) async fn authenticate(&mut self, shared_keys: &SharedKeys) -> ServerResponse {
+        let iv = IV::new_random(&mut OsRng);
+        let address = self.address();
+        let encrypted_address = EncryptedAddressBytes::new(&address, shared_keys, &iv);
+
+        self.send_request(
+            ClientControlRequest::new_authenticate(address, encrypted_address, iv).into(),
+        )
+        .await
+    }
+
+    pub(crate) async fn forward_packet(
+        &mut self,
+        shared_keys: &SharedKeys,
+        mix_packet: MixPacket,
+    ) -> ServerResponse {
+        self.send_request(
+            BinaryRequest::new_forward_request(mix_packet).into_ws_message(shared_keys),
+        )
+        .await
+    }
+}
+
+/// Creates a valid, single-hop, mix packet.
+pub(crate) fn mix_packet() -> MixPacket {
+    let (_, node_key) = nymsphinx::crypto::keygen();
+    let node = Node::new(
+        NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+        node_key,
+    );
+    let destination = Destination::new(
+        DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+        [4u8; IDENTIFIER_LENGTH],
+    );
+    let delays = vec![Delay::new_from_nanos(42)];
+
+    let sphinx_packet = SphinxPacketBuilder::new()
+        .with_payload_size(PacketSize::default().payload_size())
+        .build_packet(b"foomp".to_vec(), &[node], &destination, &delays)
+        .unwrap();
+
+    MixPacket::new(
+        NymNodeRoutingAddress::from("1.2.3.4:1789".parse::<std::net::SocketAddr>().unwrap()),
+        sphinx_packet,
+        PacketMode::default(),
+    )
+}
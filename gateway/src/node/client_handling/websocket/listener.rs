@@ -3,6 +3,7 @@
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket::connection_handler::FreshHandler;
+use crate::node::metrics::GatewayMetrics;
 use crate::node::storage::Storage;
 use crypto::asymmetric::identity;
 use log::*;
@@ -56,6 +57,7 @@ impl Listener {
         outbound_mix_sender: MixForwardingSender,
         storage: St,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
     ) where
         St: Storage + Clone + 'static,
     {
@@ -82,6 +84,7 @@ impl Listener {
                         Arc::clone(&self.local_identity),
                         storage.clone(),
                         active_clients_store.clone(),
+                        metrics.clone(),
                         #[cfg(feature = "coconut")]
                         Arc::clone(&self.coconut_verifier),
                         #[cfg(not(feature = "coconut"))]
@@ -99,12 +102,13 @@ impl Listener {
         outbound_mix_sender: MixForwardingSender,
         storage: St,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
     ) -> JoinHandle<()>
     where
         St: Storage + Clone + 'static,
     {
         tokio::spawn(async move {
-            self.run(outbound_mix_sender, storage, active_clients_store)
+            self.run(outbound_mix_sender, storage, active_clients_store, metrics)
                 .await
         })
     }
@@ -0,0 +1,97 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::metrics::GatewayMetrics;
+use mixnode_common::node_description::NodeDescription;
+use rocket::http::ContentType;
+use rocket::serde::json::Json;
+use rocket::{catch, catchers, get, routes, Build, Request, Rocket, State};
+
+/// Builds the HTTP API of the gateway. The metrics are only exposed if they are provided.
+pub(crate) fn build_rocket(
+    config: rocket::Config,
+    descriptor: NodeDescription,
+    exposed_metrics: Option<(GatewayMetrics, ActiveClientsStore)>,
+) -> Rocket<Build> {
+    let rocket = rocket::build()
+        .configure(config)
+        .mount("/", routes![description])
+        .register("/", catchers![not_found])
+        .manage(descriptor);
+
+    match exposed_metrics {
+        Some((gateway_metrics, active_clients_store)) => rocket
+            .mount("/", routes![metrics])
+            .manage(gateway_metrics)
+            .manage(active_clients_store),
+        None => rocket,
+    }
+}
+
+/// Returns a description of the gateway and why someone might want to use it.
+#[get("/description")]
+pub(crate) fn description(description: &State<NodeDescription>) -> Json<NodeDescription> {
+    Json(description.inner().clone())
+}
+
+/// Returns the metrics of the gateway in the Prometheus text format.
+#[get("/metrics")]
+pub(crate) fn metrics(
+    metrics: &State<GatewayMetrics>,
+    active_clients_store: &State<ActiveClientsStore>,
+) -> (ContentType, String) {
+    (ContentType::Plain, metrics.encode(active_clients_store))
+}
+
+#[catch(404)]
+pub(crate) fn not_found(req: &Request<'_>) -> String {
+    format!("I couldn't find '{}'. Try something else?", req.uri())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+
+    fn client(metrics: Option<(GatewayMetrics, ActiveClientsStore)>) -> Client {
+        let descriptor = NodeDescription {
+            name: "foomp".to_string(),
+            ..Default::default()
+        };
+        Client::tracked(build_rocket(
+            rocket::Config::debug_default(),
+            descriptor,
+            metrics,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn serves_description() {
+        let client = client(None);
+        let response = client.get("/description").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let description: NodeDescription = response.into_json().unwrap();
+        assert_eq!(description.name, "foomp");
+    }
+
+    #[test]
+    fn serves_metrics_only_if_enabled() {
+        let client_without_metrics = client(None);
+        let response = client_without_metrics.get("/metrics").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let metrics = GatewayMetrics::new();
+        metrics.packets_forwarded.inc_by(42);
+        let client_with_metrics = client(Some((metrics, ActiveClientsStore::new())));
+        let response = client_with_metrics.get("/metrics").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains("nym_gateway_packets_forwarded_total 42"));
+        assert!(body.contains("nym_gateway_active_clients 0"));
+    }
+}
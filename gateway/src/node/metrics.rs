@@ -0,0 +1,40 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use metrics_common::{Counter, MetricsEncoder};
+
+/// Counters updated by the client handlers and exposed, alongside the number of currently
+/// connected clients, under the `/metrics` endpoint if it's enabled.
+#[derive(Clone, Default)]
+pub(crate) struct GatewayMetrics {
+    pub(crate) bandwidth_consumed: Counter,
+    pub(crate) packets_forwarded: Counter,
+}
+
+impl GatewayMetrics {
+    pub(crate) fn new() -> Self {
+        GatewayMetrics::default()
+    }
+
+    pub(crate) fn encode(&self, active_clients_store: &ActiveClientsStore) -> String {
+        let mut encoder = MetricsEncoder::new();
+        encoder
+            .gauge(
+                "nym_gateway_active_clients",
+                "Number of clients currently connected over websocket",
+                active_clients_store.size(),
+            )
+            .counter(
+                "nym_gateway_bandwidth_consumed_bytes_total",
+                "Amount of client bandwidth consumed since startup",
+                self.bandwidth_consumed.get(),
+            )
+            .counter(
+                "nym_gateway_packets_forwarded_total",
+                "Number of packets received from clients and forwarded into the mixnet since startup",
+                self.packets_forwarded.get(),
+            );
+        encoder.finish()
+    }
+}
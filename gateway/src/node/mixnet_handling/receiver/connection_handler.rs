@@ -142,6 +142,9 @@ impl<St: Storage> ConnectionHandler<St> {
                 .store_processed_packet_payload(client_address, unsent_plaintext)
                 .await
             {
+                Err(StorageError::InboxQuotaExceeded(client)) => {
+                    warn!("Dropping message for {} as its inbox is full", client)
+                }
                 Err(err) => error!("Failed to store client data - {}", err),
                 Ok(_) => trace!("Stored packet for {}", client_address),
             },
@@ -155,12 +158,8 @@ impl<St: Storage> ConnectionHandler<St> {
     }
 
     async fn handle_received_packet(&mut self, framed_sphinx_packet: FramedSphinxPacket) {
-        //
-        // TODO: here be replay attack detection - it will require similar key cache to the one in
-        // packet processor for vpn packets,
-        // question: can it also be per connection vs global?
-        //
-
+        // note: replay detection is performed by the packet processor, which is shared
+        // between all connections
         let processed_final_hop = match self.packet_processor.process_received(framed_sphinx_packet)
         {
             Err(e) => {
@@ -6,12 +6,16 @@ use crate::commands::validate_bech32_address_or_exit;
 use crate::config::Config;
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket;
+use crate::node::metrics::GatewayMetrics;
 use crate::node::mixnet_handling::receiver::connection_handler::ConnectionHandler;
 use crate::node::statistics::collector::GatewayStatisticsCollector;
-use crate::node::storage::Storage;
+use crate::node::storage::pruner::{InboxPruner, PrunedMessagesCounter};
+use crate::node::storage::{InboxQuota, Storage};
+use config::NymConfig;
 use crypto::asymmetric::{encryption, identity};
 use log::*;
 use mixnet_client::forwarder::{MixForwardingSender, PacketForwarder};
+use mixnode_common::node_description::NodeDescription;
 use network_defaults::NymNetworkDetails;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -19,6 +23,7 @@ use statistics_common::collector::StatisticsSender;
 use std::net::SocketAddr;
 use std::process;
 use std::sync::Arc;
+use task::{ShutdownListener, ShutdownNotifier};
 
 use crate::config::persistence::pathfinder::GatewayPathfinder;
 #[cfg(feature = "coconut")]
@@ -29,9 +34,11 @@ use crate::node::client_handling::websocket::connection_handler::eth_events::ERC
 use credentials::obtain_aggregate_verification_key;
 use validator_client::nymd;
 
-use self::storage::PersistentStorage;
+use self::storage::{InMemStorage, PersistentStorage};
 
 pub(crate) mod client_handling;
+pub(crate) mod http;
+pub(crate) mod metrics;
 pub(crate) mod mixnet_handling;
 pub(crate) mod statistics;
 pub(crate) mod storage;
@@ -42,10 +49,23 @@ pub(crate) async fn create_gateway(config: Config) -> Gateway<PersistentStorage>
     Gateway::new(config, storage).await
 }
 
+/// Wire up and create Gateway instance that never writes any client data to the disk
+pub(crate) async fn create_ephemeral_gateway(config: Config) -> Gateway<InMemStorage> {
+    let storage = InMemStorage::new(config.get_message_retrieval_limit(), inbox_quota(&config));
+    Gateway::new(config, storage).await
+}
+
+fn inbox_quota(config: &Config) -> InboxQuota {
+    InboxQuota {
+        max_messages: config.get_max_client_inbox_messages(),
+        max_bytes: config.get_max_client_inbox_bytes(),
+    }
+}
+
 async fn initialise_storage(config: &Config) -> PersistentStorage {
     let path = config.get_persistent_store_path();
     let retrieval_limit = config.get_message_retrieval_limit();
-    match PersistentStorage::init(path, retrieval_limit).await {
+    match PersistentStorage::init(path, retrieval_limit, inbox_quota(config)).await {
         Err(err) => panic!("failed to initialise gateway storage - {}", err),
         Ok(storage) => storage,
     }
@@ -140,15 +160,20 @@ where
         );
         println!("Version: {}", self.config.get_version());
         println!(
-            "Mix Port: {}, Clients port: {}",
+            "Mix Port: {}, Clients port: {}, HTTP API port: {}",
             self.config.get_mix_port(),
-            self.config.get_clients_port()
+            self.config.get_clients_port(),
+            self.config.get_http_api_port()
         );
 
-        println!(
-            "Data store is at: {:?}",
-            self.config.get_persistent_store_path()
-        );
+        if self.config.get_in_memory_storage() {
+            println!("Data store is kept in memory only");
+        } else {
+            println!(
+                "Data store is at: {:?}",
+                self.config.get_persistent_store_path()
+            );
+        }
     }
 
     fn start_mix_socket_listener(
@@ -180,6 +205,7 @@ where
         &self,
         forwarding_channel: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
         #[cfg(feature = "coconut")] coconut_verifier: Arc<CoconutVerifier>,
         #[cfg(not(feature = "coconut"))] erc20_bridge: ERC20Bridge,
     ) {
@@ -203,9 +229,41 @@ where
             forwarding_channel,
             self.storage.clone(),
             active_clients_store,
+            metrics,
         );
     }
 
+    fn start_http_api(
+        &self,
+        gateway_metrics: GatewayMetrics,
+        active_clients_store: ActiveClientsStore,
+    ) {
+        info!(
+            "Starting HTTP API on http://{}:{}",
+            self.config.get_listening_address(),
+            self.config.get_http_api_port()
+        );
+
+        let mut config = rocket::config::Config::release_default();
+
+        // bind to the same address as we are using for mix and client traffic
+        config.address = self.config.get_listening_address();
+        config.port = self.config.get_http_api_port();
+
+        let descriptor =
+            NodeDescription::load_from_file(self.config.config_directory()).unwrap_or_default();
+
+        let metrics = if self.config.get_metrics_enabled() {
+            info!("Exposing gateway metrics under /metrics");
+            Some((gateway_metrics, active_clients_store))
+        } else {
+            None
+        };
+
+        let rocket = http::build_rocket(config, descriptor, metrics);
+        tokio::spawn(async move { rocket.launch().await });
+    }
+
     fn start_packet_forwarder(&self) -> MixForwardingSender {
         info!("Starting mix packet forwarder...");
 
@@ -220,7 +278,25 @@ where
         packet_sender
     }
 
-    async fn wait_for_interrupt(&self) {
+    fn start_inbox_pruner(
+        &self,
+        pruned_messages: PrunedMessagesCounter,
+        shutdown: ShutdownListener,
+    ) {
+        info!("Starting inbox pruner...");
+
+        let mut inbox_pruner = InboxPruner::new(
+            self.storage.clone(),
+            self.config.get_message_time_to_live(),
+            self.config.get_inbox_pruning_interval(),
+            pruned_messages,
+            shutdown,
+        );
+
+        tokio::spawn(async move { inbox_pruner.run().await });
+    }
+
+    async fn wait_for_interrupt(&self, mut shutdown: ShutdownNotifier) {
         if let Err(e) = tokio::signal::ctrl_c().await {
             error!(
                 "There was an error while capturing SIGINT - {:?}. We will terminate regardless",
@@ -230,6 +306,12 @@ where
         println!(
             "Received SIGINT - the gateway will terminate now (threads are not yet nicely stopped, if you see stack traces that's alright)."
         );
+
+        info!("Sending shutdown");
+        shutdown.signal_shutdown().ok();
+
+        info!("Waiting for tasks to finish... (Press ctrl-c to force)");
+        shutdown.wait_for_shutdown().await;
     }
 
     fn random_api_client(&self) -> validator_client::ApiClient {
@@ -325,8 +407,13 @@ where
         #[cfg(not(feature = "coconut"))]
         let erc20_bridge = ERC20Bridge::new(self.config.get_eth_endpoint(), nymd_client);
 
+        let shutdown = ShutdownNotifier::default();
+
         let mix_forwarding_channel = self.start_packet_forwarder();
 
+        let pruned_messages = PrunedMessagesCounter::default();
+        self.start_inbox_pruner(pruned_messages.clone(), shutdown.subscribe());
+
         let active_clients_store = ActiveClientsStore::new();
         self.start_mix_socket_listener(
             mix_forwarding_channel.clone(),
@@ -337,6 +424,7 @@ where
             let statistics_service_url = self.config.get_statistics_service_url();
             let stats_collector = GatewayStatisticsCollector::new(
                 active_clients_store.clone(),
+                pruned_messages,
                 statistics_service_url,
             );
             let mut stats_sender = StatisticsSender::new(stats_collector);
@@ -345,9 +433,13 @@ where
             });
         }
 
+        let metrics = GatewayMetrics::new();
+        self.start_http_api(metrics.clone(), active_clients_store.clone());
+
         self.start_client_websocket_listener(
             mix_forwarding_channel,
             active_clients_store,
+            metrics,
             #[cfg(feature = "coconut")]
             Arc::new(coconut_verifier),
             #[cfg(not(feature = "coconut"))]
@@ -356,6 +448,6 @@ where
 
         info!("Finished nym gateway startup procedure - it should now be able to receive mix and client traffic!");
 
-        self.wait_for_interrupt().await
+        self.wait_for_interrupt(shutdown).await
     }
 }
@@ -12,16 +12,23 @@ use statistics_common::{
 };
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::storage::pruner::PrunedMessagesCounter;
 
 pub(crate) struct GatewayStatisticsCollector {
     active_clients_store: ActiveClientsStore,
+    pruned_messages: PrunedMessagesCounter,
     statistics_service_url: Url,
 }
 
 impl GatewayStatisticsCollector {
-    pub fn new(active_clients_store: ActiveClientsStore, statistics_service_url: Url) -> Self {
+    pub fn new(
+        active_clients_store: ActiveClientsStore,
+        pruned_messages: PrunedMessagesCounter,
+        statistics_service_url: Url,
+    ) -> Self {
         GatewayStatisticsCollector {
             active_clients_store,
+            pruned_messages,
             statistics_service_url,
         }
     }
@@ -35,7 +42,11 @@ impl StatisticsCollector for GatewayStatisticsCollector {
         timestamp: DateTime<Utc>,
     ) -> StatsMessage {
         let inbox_count = self.active_clients_store.size() as u32;
-        let stats_data = vec![StatsData::Gateway(StatsGatewayData { inbox_count })];
+        let pruned_messages = self.pruned_messages.get();
+        let stats_data = vec![StatsData::Gateway(StatsGatewayData::new(
+            inbox_count,
+            pruned_messages,
+        ))];
         StatsMessage {
             stats_data,
             interval_seconds: interval.as_secs() as u32,
@@ -48,5 +59,7 @@ impl StatisticsCollector for GatewayStatisticsCollector {
             .await
     }
 
-    async fn reset_stats(&mut self) {}
+    async fn reset_stats(&mut self) {
+        self.pruned_messages.reset()
+    }
 }
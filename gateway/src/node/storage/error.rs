@@ -10,4 +10,7 @@ pub(crate) enum StorageError {
 
     #[error("Failed to perform database migration - {0}")]
     MigrationError(#[from] sqlx::migrate::MigrateError),
+
+    #[error("Inbox of client {0} has reached its quota")]
+    InboxQuotaExceeded(String),
 }
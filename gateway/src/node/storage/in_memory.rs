@@ -0,0 +1,434 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::storage::error::StorageError;
+use crate::node::storage::inboxes::{current_unix_timestamp, InboxQuota};
+use crate::node::storage::models::{PersistedSharedKeys, StoredMessage};
+use crate::node::storage::Storage;
+use async_trait::async_trait;
+use gateway_requests::registration::handshake::SharedKeys;
+use nymsphinx::DestinationAddressBytes;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Messages stored for a single offline client.
+#[derive(Default)]
+struct Inbox {
+    /// The stored messages, ordered by their ids.
+    messages: BTreeMap<i64, StoredMessage>,
+
+    /// Total size (in bytes) of the content of all of the stored messages.
+    total_size: usize,
+}
+
+impl Inbox {
+    fn insert(&mut self, message: StoredMessage) {
+        self.total_size += message.content.len();
+        self.messages.insert(message.id, message);
+    }
+
+    fn remove(&mut self, id: i64) {
+        if let Some(message) = self.messages.remove(&id) {
+            self.total_size -= message.content.len();
+        }
+    }
+}
+
+#[derive(Default)]
+struct InMemStorageInner {
+    /// Base58-encoded derived shared keys of each registered client.
+    shared_keys: HashMap<DestinationAddressBytes, String>,
+
+    /// Messages stored for each offline client.
+    inboxes: HashMap<DestinationAddressBytes, Inbox>,
+
+    /// Owner of each of the stored messages, so that they could be removed by their ids.
+    message_owners: HashMap<i64, DestinationAddressBytes>,
+
+    /// Id that is going to get assigned to the next stored message.
+    next_message_id: i64,
+
+    /// Bandwidth available to each client.
+    bandwidths: HashMap<DestinationAddressBytes, i64>,
+}
+
+impl InMemStorageInner {
+    fn remove_message(&mut self, id: i64) {
+        if let Some(owner) = self.message_owners.remove(&id) {
+            if let Some(inbox) = self.inboxes.get_mut(&owner) {
+                inbox.remove(id);
+                if inbox.messages.is_empty() {
+                    self.inboxes.remove(&owner);
+                }
+            }
+        }
+    }
+}
+
+/// In-memory implementation of `Storage`. It can be used for ephemeral gateways that should never
+/// write any client data to the disk as well as in testing environments.
+///
+/// Note that all of the data (including derived shared keys) is lost upon restart,
+/// meaning all clients will have to register again.
+// note that clone here is fine as upon cloning the same underlying data will be used
+#[derive(Clone)]
+pub(crate) struct InMemStorage {
+    inner: Arc<Mutex<InMemStorageInner>>,
+
+    /// Maximum number of messages that can be obtained from the storage per operation.
+    retrieval_limit: i64,
+
+    /// Limits on the data stored for each client.
+    inbox_quota: InboxQuota,
+}
+
+impl InMemStorage {
+    /// Creates new, empty, instance of the `InMemStorage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    /// * `inbox_quota`: limits on the amount of messages that can be stored for a single client.
+    pub(crate) fn new(message_retrieval_limit: i64, inbox_quota: InboxQuota) -> Self {
+        InMemStorage {
+            inner: Arc::new(Mutex::new(InMemStorageInner {
+                next_message_id: 1,
+                ..Default::default()
+            })),
+            retrieval_limit: message_retrieval_limit,
+            inbox_quota,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, InMemStorageInner> {
+        // none of the operations performed while holding the lock can panic
+        self.inner
+            .lock()
+            .expect("in-memory storage lock got poisoned")
+    }
+}
+
+#[async_trait]
+impl Storage for InMemStorage {
+    async fn insert_shared_keys(
+        &self,
+        client_address: DestinationAddressBytes,
+        shared_keys: SharedKeys,
+    ) -> Result<(), StorageError> {
+        self.lock()
+            .shared_keys
+            .insert(client_address, shared_keys.to_base58_string());
+        Ok(())
+    }
+
+    async fn get_shared_keys(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<Option<PersistedSharedKeys>, StorageError> {
+        Ok(self
+            .lock()
+            .shared_keys
+            .get(&client_address)
+            .map(|keys| PersistedSharedKeys {
+                client_address_bs58: client_address.as_base58_string(),
+                derived_aes128_ctr_blake3_hmac_keys_bs58: keys.clone(),
+            }))
+    }
+
+    async fn remove_shared_keys(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError> {
+        self.lock().shared_keys.remove(&client_address);
+        Ok(())
+    }
+
+    async fn store_message(
+        &self,
+        client_address: DestinationAddressBytes,
+        message: Vec<u8>,
+    ) -> Result<(), StorageError> {
+        let mut inner = self.lock();
+
+        let (stored_messages, stored_bytes) = inner
+            .inboxes
+            .get(&client_address)
+            .map(|inbox| (inbox.messages.len(), inbox.total_size))
+            .unwrap_or_default();
+
+        if stored_messages as i64 >= self.inbox_quota.max_messages
+            || (stored_bytes + message.len()) as i64 > self.inbox_quota.max_bytes
+        {
+            return Err(StorageError::InboxQuotaExceeded(
+                client_address.as_base58_string(),
+            ));
+        }
+
+        let id = inner.next_message_id;
+        inner.next_message_id += 1;
+        inner.message_owners.insert(id, client_address);
+        inner
+            .inboxes
+            .entry(client_address)
+            .or_default()
+            .insert(StoredMessage {
+                id,
+                client_address_bs58: client_address.as_base58_string(),
+                content: message,
+                timestamp: current_unix_timestamp(),
+            });
+
+        Ok(())
+    }
+
+    async fn retrieve_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+        start_after: Option<i64>,
+    ) -> Result<(Vec<StoredMessage>, Option<i64>), StorageError> {
+        let inner = self.lock();
+        let inbox = match inner.inboxes.get(&client_address) {
+            Some(inbox) => inbox,
+            None => return Ok((Vec::new(), None)),
+        };
+
+        let start = start_after.map(|id| id + 1).unwrap_or(i64::MIN);
+        // get 1 additional message to check whether there will be more to grab next time
+        let mut messages: Vec<_> = inbox
+            .messages
+            .range(start..)
+            .take(self.retrieval_limit as usize + 1)
+            .map(|(_, msg)| msg.clone())
+            .collect();
+
+        if messages.len() > self.retrieval_limit as usize {
+            messages.truncate(self.retrieval_limit as usize);
+            // assuming retrieval_limit > 0, unwrap will not fail
+            let start_after = messages.last().unwrap().id;
+            Ok((messages, Some(start_after)))
+        } else {
+            Ok((messages, None))
+        }
+    }
+
+    async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError> {
+        let mut inner = self.lock();
+        for id in ids {
+            inner.remove_message(id)
+        }
+        Ok(())
+    }
+
+    async fn remove_expired_messages(&self, max_age: Duration) -> Result<u64, StorageError> {
+        let cutoff = current_unix_timestamp() - max_age.as_secs() as i64;
+
+        let mut inner = self.lock();
+        let expired: Vec<_> = inner
+            .inboxes
+            .values()
+            .flat_map(|inbox| inbox.messages.values())
+            .filter(|msg| msg.timestamp < cutoff)
+            .map(|msg| msg.id)
+            .collect();
+
+        for id in &expired {
+            inner.remove_message(*id)
+        }
+        Ok(expired.len() as u64)
+    }
+
+    async fn create_bandwidth_entry(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<(), StorageError> {
+        self.lock().bandwidths.entry(client_address).or_insert(0);
+        Ok(())
+    }
+
+    async fn get_available_bandwidth(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<Option<i64>, StorageError> {
+        Ok(self.lock().bandwidths.get(&client_address).copied())
+    }
+
+    async fn increase_bandwidth(
+        &self,
+        client_address: DestinationAddressBytes,
+        amount: i64,
+    ) -> Result<(), StorageError> {
+        // as with the persistent storage, the bandwidth of clients without an entry is not updated
+        if let Some(available) = self.lock().bandwidths.get_mut(&client_address) {
+            *available += amount
+        }
+        Ok(())
+    }
+
+    async fn consume_bandwidth(
+        &self,
+        client_address: DestinationAddressBytes,
+        amount: i64,
+    ) -> Result<(), StorageError> {
+        if let Some(available) = self.lock().bandwidths.get_mut(&client_address) {
+            *available -= amount
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage(retrieval_limit: i64) -> InMemStorage {
+        InMemStorage::new(
+            retrieval_limit,
+            InboxQuota {
+                max_messages: 100,
+                max_bytes: 1000,
+            },
+        )
+    }
+
+    fn client(byte: u8) -> DestinationAddressBytes {
+        DestinationAddressBytes::from_bytes([byte; 32])
+    }
+
+    #[tokio::test]
+    async fn messages_are_retrieved_respecting_retrieval_limit() {
+        let storage = storage(2);
+        for i in 0..5u8 {
+            storage.store_message(client(1), vec![i]).await.unwrap();
+        }
+        storage.store_message(client(2), vec![42]).await.unwrap();
+
+        let (first, start_after) = storage.retrieve_messages(client(1), None).await.unwrap();
+        assert_eq!(
+            vec![vec![0], vec![1]],
+            first.into_iter().map(|msg| msg.content).collect::<Vec<_>>()
+        );
+        assert!(start_after.is_some());
+
+        let (second, start_after) = storage
+            .retrieve_messages(client(1), start_after)
+            .await
+            .unwrap();
+        assert_eq!(2, second.len());
+
+        let (third, start_after) = storage
+            .retrieve_messages(client(1), start_after)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![vec![4]],
+            third.into_iter().map(|msg| msg.content).collect::<Vec<_>>()
+        );
+        assert!(start_after.is_none());
+    }
+
+    #[tokio::test]
+    async fn removed_messages_are_no_longer_retrieved() {
+        let storage = storage(100);
+        storage.store_message(client(1), vec![1]).await.unwrap();
+        storage.store_message(client(1), vec![2]).await.unwrap();
+
+        let (messages, _) = storage.retrieve_messages(client(1), None).await.unwrap();
+        storage.remove_messages(vec![messages[0].id]).await.unwrap();
+
+        let (messages, _) = storage.retrieve_messages(client(1), None).await.unwrap();
+        assert_eq!(1, messages.len());
+        assert_eq!(vec![2], messages[0].content);
+    }
+
+    #[tokio::test]
+    async fn inbox_quota_is_enforced() {
+        let storage = InMemStorage::new(
+            100,
+            InboxQuota {
+                max_messages: 2,
+                max_bytes: 10,
+            },
+        );
+
+        assert!(matches!(
+            storage.store_message(client(1), vec![0; 11]).await,
+            Err(StorageError::InboxQuotaExceeded(_))
+        ));
+        storage.store_message(client(1), vec![0; 5]).await.unwrap();
+        assert!(matches!(
+            storage.store_message(client(1), vec![0; 6]).await,
+            Err(StorageError::InboxQuotaExceeded(_))
+        ));
+        storage.store_message(client(1), vec![0; 5]).await.unwrap();
+        assert!(matches!(
+            storage.store_message(client(1), vec![]).await,
+            Err(StorageError::InboxQuotaExceeded(_))
+        ));
+
+        // removed messages no longer count towards the quota
+        let (messages, _) = storage.retrieve_messages(client(1), None).await.unwrap();
+        storage.remove_messages(vec![messages[0].id]).await.unwrap();
+        storage.store_message(client(1), vec![0; 5]).await.unwrap();
+        assert!(matches!(
+            storage.store_message(client(1), vec![0; 1]).await,
+            Err(StorageError::InboxQuotaExceeded(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn expired_messages_are_removed() {
+        let storage = storage(100);
+        storage.store_message(client(1), vec![1]).await.unwrap();
+        storage.store_message(client(2), vec![2]).await.unwrap();
+
+        assert_eq!(
+            0,
+            storage
+                .remove_expired_messages(Duration::from_secs(60))
+                .await
+                .unwrap()
+        );
+
+        // make sure the stored messages are considered old enough
+        for inbox in storage.lock().inboxes.values_mut() {
+            for message in inbox.messages.values_mut() {
+                message.timestamp -= 120
+            }
+        }
+
+        assert_eq!(
+            2,
+            storage
+                .remove_expired_messages(Duration::from_secs(60))
+                .await
+                .unwrap()
+        );
+        assert!(storage
+            .retrieve_messages(client(1), None)
+            .await
+            .unwrap()
+            .0
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn bandwidth_is_only_tracked_for_clients_with_entries() {
+        let storage = storage(100);
+
+        storage.increase_bandwidth(client(1), 100).await.unwrap();
+        assert!(storage
+            .get_available_bandwidth(client(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        storage.create_bandwidth_entry(client(1)).await.unwrap();
+        storage.increase_bandwidth(client(1), 100).await.unwrap();
+        storage.consume_bandwidth(client(1), 42).await.unwrap();
+        assert_eq!(
+            Some(58),
+            storage.get_available_bandwidth(client(1)).await.unwrap()
+        );
+    }
+}
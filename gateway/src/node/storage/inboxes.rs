@@ -1,7 +1,26 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::node::storage::error::StorageError;
 use crate::node::storage::models::StoredMessage;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Limits on the amount of data that can be stored for a single client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InboxQuota {
+    /// Maximum number of messages that can be stored for a single client.
+    pub(crate) max_messages: i64,
+
+    /// Maximum total size (in bytes) of messages that can be stored for a single client.
+    pub(crate) max_bytes: i64,
+}
+
+pub(crate) fn current_unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the unix epoch")
+        .as_secs() as i64
+}
 
 #[derive(Clone)]
 pub(crate) struct InboxManager {
@@ -10,6 +29,10 @@ pub(crate) struct InboxManager {
     /// It is used to prevent out of memory errors in the case of client receiving a lot of data while
     /// offline and then loading it all at once when he comes back online.
     retrieval_limit: i64,
+
+    /// Limits on the data stored for each client, so that a single abandoned address
+    /// could not fill up the entire disk.
+    quota: InboxQuota,
 }
 
 impl InboxManager {
@@ -18,14 +41,26 @@ impl InboxManager {
     /// # Arguments
     ///
     /// * `connection_pool`: database connection pool to use.
-    pub(crate) fn new(connection_pool: sqlx::SqlitePool, retrieval_limit: i64) -> Self {
+    /// * `retrieval_limit`: maximum number of messages that can be retrieved at once.
+    /// * `quota`: limits on the data stored for each client.
+    pub(crate) fn new(
+        connection_pool: sqlx::SqlitePool,
+        retrieval_limit: i64,
+        quota: InboxQuota,
+    ) -> Self {
         InboxManager {
             connection_pool,
             retrieval_limit,
+            quota,
         }
     }
 
     /// Inserts new message to the storage for an offline client for future retrieval.
+    /// The message is rejected if storing it would exceed the quota of the client.
+    ///
+    /// The quota is checked against the per-client usage counters (kept up to date by the database
+    /// triggers) within the same statement as the insertion, so concurrent inserts for the same
+    /// client can't exceed it either.
     ///
     /// # Arguments
     ///
@@ -35,14 +70,42 @@ impl InboxManager {
         &self,
         client_address_bs58: &str,
         content: Vec<u8>,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "INSERT INTO message_store(client_address_bs58, content) VALUES (?, ?)",
+    ) -> Result<(), StorageError> {
+        let content_size = content.len() as i64;
+
+        // clients without any stored messages don't have their usage recorded at all
+        if self.quota.max_messages <= 0 || content_size > self.quota.max_bytes {
+            return Err(StorageError::InboxQuotaExceeded(
+                client_address_bs58.to_owned(),
+            ));
+        }
+
+        let max_size_before_insert = self.quota.max_bytes - content_size;
+        let timestamp = current_unix_timestamp();
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO message_store(client_address_bs58, content, timestamp)
+                SELECT ?, ?, ?
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM inbox_usage
+                    WHERE client_address_bs58 = ? AND (message_count >= ? OR total_size > ?)
+                )
+            "#,
             client_address_bs58,
             content,
+            timestamp,
+            client_address_bs58,
+            self.quota.max_messages,
+            max_size_before_insert,
         )
         .execute(&self.connection_pool)
         .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(StorageError::InboxQuotaExceeded(
+                client_address_bs58.to_owned(),
+            ));
+        }
         Ok(())
     }
 
@@ -119,4 +182,158 @@ impl InboxManager {
             .await?;
         Ok(())
     }
+
+    /// Removes all messages that were stored before the specified time.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff`: unix timestamp (in seconds) before which messages are considered expired
+    ///
+    /// returns the number of removed messages.
+    pub(crate) async fn remove_messages_stored_before(
+        &self,
+        cutoff: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let res = sqlx::query!("DELETE FROM message_store WHERE timestamp < ?", cutoff)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const CLIENT: &str = "client";
+    const OTHER_CLIENT: &str = "other-client";
+
+    async fn inbox_manager(quota: InboxQuota) -> InboxManager {
+        // every connection to an in-memory database creates a new database,
+        // so make sure there's only a single one
+        let connection_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations")
+            .run(&connection_pool)
+            .await
+            .unwrap();
+
+        InboxManager::new(connection_pool, 100, quota)
+    }
+
+    #[tokio::test]
+    async fn messages_above_count_quota_are_rejected() {
+        let inbox_manager = inbox_manager(InboxQuota {
+            max_messages: 2,
+            max_bytes: 1000,
+        })
+        .await;
+
+        assert!(inbox_manager.insert_message(CLIENT, vec![1]).await.is_ok());
+        assert!(inbox_manager.insert_message(CLIENT, vec![2]).await.is_ok());
+        assert!(matches!(
+            inbox_manager.insert_message(CLIENT, vec![3]).await,
+            Err(StorageError::InboxQuotaExceeded(_))
+        ));
+
+        // quota is applied per client
+        assert!(inbox_manager
+            .insert_message(OTHER_CLIENT, vec![4])
+            .await
+            .is_ok());
+
+        let (messages, _) = inbox_manager.get_messages(CLIENT, None).await.unwrap();
+        assert_eq!(2, messages.len());
+    }
+
+    #[tokio::test]
+    async fn messages_above_size_quota_are_rejected() {
+        let inbox_manager = inbox_manager(InboxQuota {
+            max_messages: 100,
+            max_bytes: 10,
+        })
+        .await;
+
+        assert!(inbox_manager
+            .insert_message(CLIENT, vec![0; 6])
+            .await
+            .is_ok());
+        assert!(matches!(
+            inbox_manager.insert_message(CLIENT, vec![0; 5]).await,
+            Err(StorageError::InboxQuotaExceeded(_))
+        ));
+        assert!(inbox_manager
+            .insert_message(CLIENT, vec![0; 4])
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn removed_messages_no_longer_count_towards_quota() {
+        let inbox_manager = inbox_manager(InboxQuota {
+            max_messages: 1,
+            max_bytes: 10,
+        })
+        .await;
+
+        inbox_manager
+            .insert_message(CLIENT, vec![0; 10])
+            .await
+            .unwrap();
+        assert!(inbox_manager.insert_message(CLIENT, vec![1]).await.is_err());
+
+        let (messages, _) = inbox_manager.get_messages(CLIENT, None).await.unwrap();
+        inbox_manager.remove_message(messages[0].id).await.unwrap();
+        assert!(inbox_manager
+            .insert_message(CLIENT, vec![0; 10])
+            .await
+            .is_ok());
+
+        inbox_manager
+            .remove_messages_stored_before(current_unix_timestamp() + 60)
+            .await
+            .unwrap();
+        assert!(inbox_manager
+            .insert_message(CLIENT, vec![0; 10])
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn only_messages_stored_before_cutoff_are_removed() {
+        let inbox_manager = inbox_manager(InboxQuota {
+            max_messages: 100,
+            max_bytes: 1000,
+        })
+        .await;
+
+        inbox_manager.insert_message(CLIENT, vec![1]).await.unwrap();
+        inbox_manager
+            .insert_message(OTHER_CLIENT, vec![2])
+            .await
+            .unwrap();
+
+        let now = current_unix_timestamp();
+        assert_eq!(
+            0,
+            inbox_manager
+                .remove_messages_stored_before(now - 60)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            2,
+            inbox_manager
+                .remove_messages_stored_before(now + 60)
+                .await
+                .unwrap()
+        );
+
+        let (messages, _) = inbox_manager.get_messages(CLIENT, None).await.unwrap();
+        assert!(messages.is_empty());
+    }
 }
@@ -12,11 +12,17 @@ use log::{debug, error};
 use nymsphinx::DestinationAddressBytes;
 use sqlx::ConnectOptions;
 use std::path::Path;
+use std::time::Duration;
+
+pub(crate) use crate::node::storage::in_memory::InMemStorage;
+pub(crate) use crate::node::storage::inboxes::InboxQuota;
 
 mod bandwidth;
 pub(crate) mod error;
+mod in_memory;
 mod inboxes;
 mod models;
+pub(crate) mod pruner;
 mod shared_keys;
 
 #[async_trait]
@@ -91,6 +97,15 @@ pub(crate) trait Storage: Send + Sync {
     /// * `ids`: ids of the messages to remove
     async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError>;
 
+    /// Removes all messages that have been stored for longer than the specified time.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age`: maximum amount of time a message can remain in the storage
+    ///
+    /// returns the number of removed messages.
+    async fn remove_expired_messages(&self, max_age: Duration) -> Result<u64, StorageError>;
+
     /// Creates a new bandwidth entry for the particular client.
     ///
     /// # Arguments
@@ -151,9 +166,11 @@ impl PersistentStorage {
     ///
     /// * `database_path`: path to the database.
     /// * `message_retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    /// * `inbox_quota`: limits on the amount of messages that can be stored for a single client.
     pub async fn init<P: AsRef<Path> + Send>(
         database_path: P,
         message_retrieval_limit: i64,
+        inbox_quota: InboxQuota,
     ) -> Result<Self, StorageError> {
         debug!(
             "Attempting to connect to database {:?}",
@@ -186,7 +203,11 @@ impl PersistentStorage {
         // the cloning here are cheap as connection pool is stored behind an Arc
         Ok(PersistentStorage {
             shared_key_manager: SharedKeysManager::new(connection_pool.clone()),
-            inbox_manager: InboxManager::new(connection_pool.clone(), message_retrieval_limit),
+            inbox_manager: InboxManager::new(
+                connection_pool.clone(),
+                message_retrieval_limit,
+                inbox_quota,
+            ),
             bandwidth_manager: BandwidthManager::new(connection_pool),
         })
     }
@@ -261,6 +282,15 @@ impl Storage for PersistentStorage {
         Ok(())
     }
 
+    async fn remove_expired_messages(&self, max_age: Duration) -> Result<u64, StorageError> {
+        let cutoff = inboxes::current_unix_timestamp() - max_age.as_secs() as i64;
+        let removed = self
+            .inbox_manager
+            .remove_messages_stored_before(cutoff)
+            .await?;
+        Ok(removed)
+    }
+
     async fn create_bandwidth_entry(
         &self,
         client_address: DestinationAddressBytes,
@@ -305,92 +335,3 @@ impl Storage for PersistentStorage {
         Ok(())
     }
 }
-
-/// In-memory implementation of `Storage`. The intention is primarily in testing environments.
-#[cfg(test)]
-#[derive(Clone)]
-pub(crate) struct InMemStorage;
-
-#[cfg(test)]
-impl InMemStorage {
-    #[allow(unused)]
-    async fn init<P: AsRef<Path> + Send>() -> Result<Self, StorageError> {
-        todo!()
-    }
-}
-
-#[cfg(test)]
-#[async_trait]
-impl Storage for InMemStorage {
-    async fn insert_shared_keys(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _shared_keys: SharedKeys,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn get_shared_keys(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<Option<PersistedSharedKeys>, StorageError> {
-        todo!()
-    }
-
-    async fn remove_shared_keys(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn store_message(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _message: Vec<u8>,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn retrieve_messages(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _start_after: Option<i64>,
-    ) -> Result<(Vec<StoredMessage>, Option<i64>), StorageError> {
-        todo!()
-    }
-
-    async fn remove_messages(&self, _ids: Vec<i64>) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn create_bandwidth_entry(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn get_available_bandwidth(
-        &self,
-        _client_address: DestinationAddressBytes,
-    ) -> Result<Option<i64>, StorageError> {
-        todo!()
-    }
-
-    async fn increase_bandwidth(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _amount: i64,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-
-    async fn consume_bandwidth(
-        &self,
-        _client_address: DestinationAddressBytes,
-        _amount: i64,
-    ) -> Result<(), StorageError> {
-        todo!()
-    }
-}
@@ -6,11 +6,15 @@ pub(crate) struct PersistedSharedKeys {
     pub(crate) derived_aes128_ctr_blake3_hmac_keys_bs58: String,
 }
 
+#[derive(Clone)]
 pub(crate) struct StoredMessage {
     pub(crate) id: i64,
     #[allow(dead_code)]
     pub(crate) client_address_bs58: String,
     pub(crate) content: Vec<u8>,
+    /// Unix timestamp (in seconds) of when the message got stored.
+    #[allow(dead_code)]
+    pub(crate) timestamp: i64,
 }
 
 pub(crate) struct PersistedBandwidth {
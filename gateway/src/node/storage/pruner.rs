@@ -0,0 +1,80 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::storage::Storage;
+use log::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use task::ShutdownListener;
+
+/// Number of expired messages removed from the storage since the last reset.
+#[derive(Clone, Default)]
+pub(crate) struct PrunedMessagesCounter(Arc<AtomicU64>);
+
+impl PrunedMessagesCounter {
+    fn increment_by(&self, removed: u64) {
+        self.0.fetch_add(removed, Ordering::Relaxed);
+    }
+
+    pub(crate) fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed)
+    }
+}
+
+/// Periodically removes messages that have been stored for offline clients for longer than
+/// the configured time to live.
+pub(crate) struct InboxPruner<St: Storage> {
+    storage: St,
+    message_ttl: Duration,
+    pruning_interval: Duration,
+    pruned_messages: PrunedMessagesCounter,
+    shutdown: ShutdownListener,
+}
+
+impl<St: Storage> InboxPruner<St> {
+    pub(crate) fn new(
+        storage: St,
+        message_ttl: Duration,
+        pruning_interval: Duration,
+        pruned_messages: PrunedMessagesCounter,
+        shutdown: ShutdownListener,
+    ) -> Self {
+        InboxPruner {
+            storage,
+            message_ttl,
+            pruning_interval,
+            pruned_messages,
+            shutdown,
+        }
+    }
+
+    async fn prune(&self) {
+        match self.storage.remove_expired_messages(self.message_ttl).await {
+            Ok(0) => trace!("There were no expired messages to remove"),
+            Ok(removed) => {
+                info!("Removed {} expired messages from the inboxes", removed);
+                self.pruned_messages.increment_by(removed)
+            }
+            Err(err) => error!("Failed to remove expired messages - {}", err),
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        trace!("Starting InboxPruner");
+        let mut interval = tokio::time::interval(self.pruning_interval);
+        while !self.shutdown.is_shutdown() {
+            tokio::select! {
+                _ = interval.tick() => self.prune().await,
+                _ = self.shutdown.recv() => {
+                    trace!("InboxPruner: Received shutdown");
+                }
+            }
+        }
+        trace!("InboxPruner: Exiting");
+    }
+}
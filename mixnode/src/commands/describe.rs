@@ -1,10 +1,8 @@
 use crate::config::Config;
-use crate::node::node_description::NodeDescription;
 use clap::Args;
-use colored::Colorize;
 use config::NymConfig;
+use mixnode_common::node_description::NodeDescription;
 use std::io;
-use std::io::Write;
 
 #[derive(Args)]
 pub(crate) struct Describe {
@@ -24,44 +22,15 @@ pub(crate) fn execute(args: &Describe) {
     };
 
     // get input from the user
-    print!("name: ");
-    io::stdout().flush().unwrap();
-    let mut name_buf = String::new();
-    io::stdin().read_line(&mut name_buf).unwrap();
-    let name = name_buf.trim().to_string();
-
-    print!("description: ");
-    io::stdout().flush().unwrap();
-    let mut desc_buf = String::new();
-    io::stdin().read_line(&mut desc_buf).unwrap();
-    let description = desc_buf.trim().to_string();
-
-    let example_url = "https://mixnode.yourdomain.com".bright_cyan();
-    let example_location = "City: London, Country: UK";
-
-    print!("link, e.g. {}: ", example_url);
-    io::stdout().flush().unwrap();
-    let mut link_buf = String::new();
-    io::stdin().read_line(&mut link_buf).unwrap();
-    let link = link_buf.trim().to_string();
-
-    print!("location, e.g. {}: ", example_location);
-    io::stdout().flush().unwrap();
-    let mut location_buf = String::new();
-    io::stdin().read_line(&mut location_buf).unwrap();
-    let location = location_buf.trim().to_string();
-
-    let node_description = NodeDescription {
-        name,
-        description,
-        link,
-        location,
-    };
+    let node_description = NodeDescription::prompt(
+        io::stdin().lock(),
+        io::stdout(),
+        "https://mixnode.yourdomain.com",
+    )
+    .unwrap();
 
     // save the struct
-    NodeDescription::save_to_file(
-        &node_description,
-        Config::default_config_directory(Some(&args.id)),
-    )
-    .unwrap()
+    node_description
+        .save_to_file(Config::default_config_directory(Some(&args.id)))
+        .unwrap()
 }
@@ -13,6 +13,7 @@ use url::Url;
 mod describe;
 mod init;
 mod node_details;
+mod rotate_sphinx_key;
 mod run;
 mod sign;
 mod upgrade;
@@ -36,6 +37,9 @@ pub(crate) enum Commands {
 
     /// Show details of this mixnode
     NodeDetails(node_details::NodeDetails),
+
+    /// Generate the sphinx key that is going to replace the current one after an announced rotation
+    RotateSphinxKey(rotate_sphinx_key::RotateSphinxKey),
 }
 
 // Configuration that can be overridden.
@@ -58,6 +62,7 @@ pub(crate) async fn execute(args: Cli) {
         Commands::Sign(m) => sign::execute(m),
         Commands::Upgrade(m) => upgrade::execute(m),
         Commands::NodeDetails(m) => node_details::execute(m),
+        Commands::RotateSphinxKey(m) => rotate_sphinx_key::execute(m),
     }
 }
 
@@ -0,0 +1,74 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{persistence::pathfinder::MixNodePathfinder, Config};
+use clap::Args;
+use config::NymConfig;
+use crypto::asymmetric::encryption;
+
+#[derive(Args)]
+pub(crate) struct RotateSphinxKey {
+    /// The id of the mixnode you want to generate the next sphinx key for
+    #[clap(long)]
+    id: String,
+
+    /// Overwrite the previously generated next sphinx key, if it exists
+    #[clap(long)]
+    force: bool,
+}
+
+pub(crate) fn execute(args: &RotateSphinxKey) {
+    let config = match Config::load_from_file(Some(&args.id)) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            error!(
+                "Failed to load config for {}. Are you sure you have run `init` before? (Error was: {})",
+                args.id,
+                err,
+            );
+            return;
+        }
+    };
+
+    let pathfinder = MixNodePathfinder::new_from_config(&config);
+    let next_key_paths = pemstore::KeyPairPath::new(
+        pathfinder.next_private_encryption_key().to_owned(),
+        pathfinder.next_public_encryption_key().to_owned(),
+    );
+
+    // the node can only hold two keys at once
+    if pathfinder.previous_private_encryption_key().exists() {
+        println!(
+            "The previous sphinx key is still accepted by the mixnode as the overlap window of the last \
+            rotation hasn't ended yet. Generate the next key once the previous one gets retired"
+        );
+        return;
+    }
+
+    if !args.force && pathfinder.next_private_encryption_key().exists() {
+        match pemstore::load_keypair::<encryption::KeyPair>(&next_key_paths) {
+            Ok(next_keys) => {
+                println!(
+                    "The next sphinx key has already been generated: {}",
+                    next_keys.public_key().to_base58_string()
+                );
+                println!("Use --force if you want to overwrite it");
+            }
+            Err(err) => error!("Failed to read the stored next sphinx key files - {}", err),
+        }
+        return;
+    }
+
+    let next_keys = encryption::KeyPair::new(&mut rand::rngs::OsRng);
+    pemstore::store_keypair(&next_keys, &next_key_paths)
+        .expect("Failed to save the next sphinx keys");
+
+    println!(
+        "Next Sphinx Key: {}",
+        next_keys.public_key().to_base58_string()
+    );
+    println!(
+        "Restart the mixnode so that it would start accepting packets for the next key \
+        and only then announce the key rotation with an activation epoch in the future"
+    );
+}
@@ -34,6 +34,7 @@ const DEFAULT_PACKET_FORWARDING_INITIAL_BACKOFF: Duration = Duration::from_milli
 const DEFAULT_PACKET_FORWARDING_MAXIMUM_BACKOFF: Duration = Duration::from_millis(300_000);
 const DEFAULT_INITIAL_CONNECTION_TIMEOUT: Duration = Duration::from_millis(1_500);
 const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 128;
+const DEFAULT_SPHINX_KEY_ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
 pub fn missing_string_value<T: From<String>>() -> T {
     MISSING_VALUE.to_string().into()
@@ -79,6 +80,8 @@ pub struct Config {
     #[serde(default)]
     verloc: Verloc,
     #[serde(default)]
+    metrics: Metrics,
+    #[serde(default)]
     logging: Logging,
     #[serde(default)]
     debug: Debug,
@@ -146,6 +149,44 @@ impl Config {
                 self::MixNode::default_public_sphinx_key_file(&id);
         }
 
+        if self
+            .mixnode
+            .next_private_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            self.mixnode.next_private_sphinx_key_file =
+                self::MixNode::default_next_private_sphinx_key_file(&id);
+        }
+        if self
+            .mixnode
+            .next_public_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            self.mixnode.next_public_sphinx_key_file =
+                self::MixNode::default_next_public_sphinx_key_file(&id);
+        }
+
+        if self
+            .mixnode
+            .previous_private_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            self.mixnode.previous_private_sphinx_key_file =
+                self::MixNode::default_previous_private_sphinx_key_file(&id);
+        }
+        if self
+            .mixnode
+            .previous_public_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            self.mixnode.previous_public_sphinx_key_file =
+                self::MixNode::default_previous_public_sphinx_key_file(&id);
+        }
+
         self.mixnode.id = id;
         self
     }
@@ -228,6 +269,59 @@ impl Config {
         self.mixnode.public_sphinx_key_file.clone()
     }
 
+    // configs created before sphinx key rotation was introduced do not specify those paths
+    pub fn get_next_private_sphinx_key_file(&self) -> PathBuf {
+        if self
+            .mixnode
+            .next_private_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            MixNode::default_next_private_sphinx_key_file(&self.mixnode.id)
+        } else {
+            self.mixnode.next_private_sphinx_key_file.clone()
+        }
+    }
+
+    pub fn get_next_public_sphinx_key_file(&self) -> PathBuf {
+        if self
+            .mixnode
+            .next_public_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            MixNode::default_next_public_sphinx_key_file(&self.mixnode.id)
+        } else {
+            self.mixnode.next_public_sphinx_key_file.clone()
+        }
+    }
+
+    pub fn get_previous_private_sphinx_key_file(&self) -> PathBuf {
+        if self
+            .mixnode
+            .previous_private_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            MixNode::default_previous_private_sphinx_key_file(&self.mixnode.id)
+        } else {
+            self.mixnode.previous_private_sphinx_key_file.clone()
+        }
+    }
+
+    pub fn get_previous_public_sphinx_key_file(&self) -> PathBuf {
+        if self
+            .mixnode
+            .previous_public_sphinx_key_file
+            .as_os_str()
+            .is_empty()
+        {
+            MixNode::default_previous_public_sphinx_key_file(&self.mixnode.id)
+        } else {
+            self.mixnode.previous_public_sphinx_key_file.clone()
+        }
+    }
+
     pub fn get_validator_api_endpoints(&self) -> Vec<Url> {
         self.mixnode.validator_api_urls.clone()
     }
@@ -311,6 +405,14 @@ impl Config {
     pub fn get_wallet_address(&self) -> &str {
         &self.mixnode.wallet_address
     }
+
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.metrics.enabled
+    }
+
+    pub fn get_sphinx_key_rotation_check_interval(&self) -> Duration {
+        self.debug.sphinx_key_rotation_check_interval
+    }
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -360,6 +462,26 @@ struct MixNode {
     /// Path to file containing public sphinx key.
     public_sphinx_key_file: PathBuf,
 
+    /// Path to file containing private sphinx key that is going to replace the current one
+    /// after an announced key rotation.
+    #[serde(default)]
+    next_private_sphinx_key_file: PathBuf,
+
+    /// Path to file containing public sphinx key that is going to replace the current one
+    /// after an announced key rotation.
+    #[serde(default)]
+    next_public_sphinx_key_file: PathBuf,
+
+    /// Path to file containing private sphinx key that got replaced by the current one and that
+    /// is still accepted until the overlap window of the rotation ends.
+    #[serde(default)]
+    previous_private_sphinx_key_file: PathBuf,
+
+    /// Path to file containing public sphinx key that got replaced by the current one and that
+    /// is still accepted until the overlap window of the rotation ends.
+    #[serde(default)]
+    previous_public_sphinx_key_file: PathBuf,
+
     /// Addresses to APIs running on validator from which the node gets the view of the network.
     validator_api_urls: Vec<Url>,
 
@@ -387,6 +509,22 @@ impl MixNode {
     fn default_public_sphinx_key_file(id: &str) -> PathBuf {
         Config::default_data_directory(Some(id)).join("public_sphinx.pem")
     }
+
+    fn default_next_private_sphinx_key_file(id: &str) -> PathBuf {
+        Config::default_data_directory(Some(id)).join("next_private_sphinx.pem")
+    }
+
+    fn default_next_public_sphinx_key_file(id: &str) -> PathBuf {
+        Config::default_data_directory(Some(id)).join("next_public_sphinx.pem")
+    }
+
+    fn default_previous_private_sphinx_key_file(id: &str) -> PathBuf {
+        Config::default_data_directory(Some(id)).join("previous_private_sphinx.pem")
+    }
+
+    fn default_previous_public_sphinx_key_file(id: &str) -> PathBuf {
+        Config::default_data_directory(Some(id)).join("previous_public_sphinx.pem")
+    }
 }
 
 impl Default for MixNode {
@@ -403,6 +541,10 @@ impl Default for MixNode {
             public_identity_key_file: Default::default(),
             private_sphinx_key_file: Default::default(),
             public_sphinx_key_file: Default::default(),
+            next_private_sphinx_key_file: Default::default(),
+            next_public_sphinx_key_file: Default::default(),
+            previous_private_sphinx_key_file: Default::default(),
+            previous_public_sphinx_key_file: Default::default(),
             validator_api_urls: vec![],
             nym_root_directory: Config::default_root_directory(),
             wallet_address: "nymXXXXXXXX".to_string(),
@@ -414,6 +556,14 @@ impl Default for MixNode {
 #[serde(deny_unknown_fields)]
 struct Logging {}
 
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct Metrics {
+    /// Indicates whether the mixnode should expose its metrics in the Prometheus text format
+    /// under the `/metrics` endpoint of its HTTP API.
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 struct Verloc {
@@ -481,6 +631,11 @@ struct Debug {
 
     /// Maximum number of packets that can be stored waiting to get sent to a particular connection.
     maximum_connection_buffer_size: usize,
+
+    /// Delay between each subsequent check of the state of the sphinx key rotation announced
+    /// by this node.
+    #[serde(with = "humantime_serde")]
+    sphinx_key_rotation_check_interval: Duration,
 }
 
 impl Default for Debug {
@@ -492,6 +647,7 @@ impl Default for Debug {
             packet_forwarding_maximum_backoff: DEFAULT_PACKET_FORWARDING_MAXIMUM_BACKOFF,
             initial_connection_timeout: DEFAULT_INITIAL_CONNECTION_TIMEOUT,
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
+            sphinx_key_rotation_check_interval: DEFAULT_SPHINX_KEY_ROTATION_CHECK_INTERVAL,
         }
     }
 }
@@ -10,6 +10,10 @@ pub struct MixNodePathfinder {
     identity_public_key: PathBuf,
     private_sphinx_key: PathBuf,
     public_sphinx_key: PathBuf,
+    next_private_sphinx_key: PathBuf,
+    next_public_sphinx_key: PathBuf,
+    previous_private_sphinx_key: PathBuf,
+    previous_public_sphinx_key: PathBuf,
 }
 
 impl MixNodePathfinder {
@@ -19,6 +23,10 @@ impl MixNodePathfinder {
             identity_public_key: config.get_public_identity_key_file(),
             private_sphinx_key: config.get_private_sphinx_key_file(),
             public_sphinx_key: config.get_public_sphinx_key_file(),
+            next_private_sphinx_key: config.get_next_private_sphinx_key_file(),
+            next_public_sphinx_key: config.get_next_public_sphinx_key_file(),
+            previous_private_sphinx_key: config.get_previous_private_sphinx_key_file(),
+            previous_public_sphinx_key: config.get_previous_public_sphinx_key_file(),
         }
     }
 
@@ -37,4 +45,20 @@ impl MixNodePathfinder {
     pub fn public_encryption_key(&self) -> &Path {
         &self.public_sphinx_key
     }
+
+    pub fn next_private_encryption_key(&self) -> &Path {
+        &self.next_private_sphinx_key
+    }
+
+    pub fn next_public_encryption_key(&self) -> &Path {
+        &self.next_public_sphinx_key
+    }
+
+    pub fn previous_private_encryption_key(&self) -> &Path {
+        &self.previous_private_sphinx_key
+    }
+
+    pub fn previous_public_encryption_key(&self) -> &Path {
+        &self.previous_public_sphinx_key
+    }
 }
@@ -34,6 +34,18 @@ private_sphinx_key_file = '{{ mixnode.private_sphinx_key_file }}'
 # Path to file containing public sphinx key.
 public_sphinx_key_file = '{{ mixnode.public_sphinx_key_file }}'
 
+# Path to file containing private sphinx key that is going to replace the current one.
+next_private_sphinx_key_file = '{{ mixnode.next_private_sphinx_key_file }}'
+
+# Path to file containing public sphinx key that is going to replace the current one.
+next_public_sphinx_key_file = '{{ mixnode.next_public_sphinx_key_file }}'
+
+# Path to file containing private sphinx key that is still accepted after it got replaced.
+previous_private_sphinx_key_file = '{{ mixnode.previous_private_sphinx_key_file }}'
+
+# Path to file containing public sphinx key that is still accepted after it got replaced.
+previous_public_sphinx_key_file = '{{ mixnode.previous_public_sphinx_key_file }}'
+
 ##### additional mixnode config options #####
 
 # Optional address announced to the directory server for the clients to connect to.
@@ -69,6 +81,14 @@ wallet_address = '{{ mixnode.wallet_address }}'
 nym_root_directory = '{{ mixnode.nym_root_directory }}'
 
 
+##### metrics configuration options #####
+
+[metrics]
+
+# Indicates whether the mixnode should expose its metrics in the Prometheus text format
+# under the `/metrics` endpoint of its HTTP API.
+enabled = {{ metrics.enabled }}
+
 ##### logging configuration options #####
 
 [logging]
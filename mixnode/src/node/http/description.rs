@@ -1,4 +1,4 @@
-use crate::node::node_description::NodeDescription;
+use mixnode_common::node_description::NodeDescription;
 use rocket::serde::json::Json;
 use rocket::State;
 
@@ -0,0 +1,28 @@
+use crate::node::node_statistics::SharedNodeStats;
+use metrics_common::{Gauge, MetricsEncoder};
+use rocket::http::ContentType;
+use rocket::State;
+
+/// Number of packets currently being delayed before getting forwarded to the next hop.
+#[derive(Clone, Default)]
+pub(crate) struct DelayQueueDepth(pub(crate) Gauge);
+
+/// Returns the metrics of the node in the Prometheus text format.
+/// Note that the packet counters are only refreshed whenever the node stats get updated.
+#[get("/metrics")]
+pub(crate) async fn metrics(
+    stats: &State<SharedNodeStats>,
+    delay_queue_depth: &State<DelayQueueDepth>,
+) -> (ContentType, String) {
+    let snapshot_data = stats.clone_data().await;
+
+    let mut encoder = MetricsEncoder::new();
+    snapshot_data.encode_metrics(&mut encoder);
+    encoder.gauge(
+        "nym_mixnode_delay_queue_packets",
+        "Number of packets currently being delayed before getting forwarded",
+        delay_queue_depth.0.get(),
+    );
+
+    (ContentType::Plain, encoder.finish())
+}
@@ -49,13 +49,7 @@ impl ConnectionHandler {
     }
 
     fn handle_received_packet(&self, framed_sphinx_packet: FramedSphinxPacket) {
-        //
-        // TODO: here be replay attack detection - it will require similar key cache to the one in
-        // packet processor for vpn packets,
-        // question: can it also be per connection vs global?
-        //
-
-        // all processing such, key caching, etc. was done.
+        // all processing such as replay detection, etc. was done.
         // however, if it was a forward hop, we still need to delay it
         match self.packet_processor.process_received(framed_sphinx_packet) {
             Err(e) => debug!("We failed to process received sphinx packet - {:?}", e),
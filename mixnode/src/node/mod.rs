@@ -7,6 +7,7 @@ use crate::config::Config;
 use crate::node::http::{
     description::description,
     hardware::hardware,
+    metrics::{metrics, DelayQueueDepth},
     not_found,
     stats::stats,
     verloc::{verloc as verlocRoute, VerlocState},
@@ -14,12 +15,14 @@ use crate::node::http::{
 use crate::node::listener::connection_handler::packet_processing::PacketProcessor;
 use crate::node::listener::connection_handler::ConnectionHandler;
 use crate::node::listener::Listener;
-use crate::node::node_description::NodeDescription;
 use crate::node::node_statistics::SharedNodeStats;
 use crate::node::packet_delayforwarder::{DelayForwarder, PacketDelayForwardSender};
+use crate::node::sphinx_key_rotation::{RotationState, SphinxKeyRotationController};
 use ::crypto::asymmetric::{encryption, identity};
 use config::NymConfig;
 use log::{error, info, warn};
+use mixnode_common::node_description::NodeDescription;
+use mixnode_common::packet_processor::processor::SphinxPacketProcessor;
 use mixnode_common::verloc::{self, AtomicVerlocResult, VerlocMeasurer};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -31,9 +34,9 @@ use version_checker::parse_version;
 
 mod http;
 mod listener;
-pub(crate) mod node_description;
 mod node_statistics;
 mod packet_delayforwarder;
+mod sphinx_key_rotation;
 
 // the MixNode will live for whole duration of this program
 pub struct MixNode {
@@ -41,16 +44,26 @@ pub struct MixNode {
     descriptor: NodeDescription,
     identity_keypair: Arc<identity::KeyPair>,
     sphinx_keypair: Arc<encryption::KeyPair>,
+    next_sphinx_keypair: Option<encryption::KeyPair>,
+    previous_sphinx_keypair: Option<encryption::KeyPair>,
 }
 
 impl MixNode {
     pub fn new(config: Config) -> Self {
         let pathfinder = MixNodePathfinder::new_from_config(&config);
 
+        let sphinx_keypair = Self::load_sphinx_keys(&pathfinder);
+        // if the node got stopped just after activating the next key, the leftover next key
+        // files contain its current key
+        let next_sphinx_keypair = Self::load_next_sphinx_keys(&pathfinder)
+            .filter(|next| next.public_key() != sphinx_keypair.public_key());
+
         MixNode {
             descriptor: Self::load_node_description(&config),
             identity_keypair: Arc::new(Self::load_identity_keys(&pathfinder)),
-            sphinx_keypair: Arc::new(Self::load_sphinx_keys(&pathfinder)),
+            sphinx_keypair: Arc::new(sphinx_keypair),
+            next_sphinx_keypair,
+            previous_sphinx_keypair: Self::load_previous_sphinx_keys(&pathfinder),
             config,
         }
     }
@@ -82,6 +95,37 @@ impl MixNode {
         sphinx_keypair
     }
 
+    /// Loads the next Sphinx keys stored on disk, if they were generated
+    fn load_next_sphinx_keys(pathfinder: &MixNodePathfinder) -> Option<encryption::KeyPair> {
+        if !pathfinder.next_private_encryption_key().exists() {
+            return None;
+        }
+
+        let next_sphinx_keypair: encryption::KeyPair =
+            pemstore::load_keypair(&pemstore::KeyPairPath::new(
+                pathfinder.next_private_encryption_key().to_owned(),
+                pathfinder.next_public_encryption_key().to_owned(),
+            ))
+            .expect("Failed to read stored next sphinx key files");
+        Some(next_sphinx_keypair)
+    }
+
+    /// Loads the previous Sphinx keys stored on disk, if the overlap window of the last
+    /// rotation hasn't ended yet
+    fn load_previous_sphinx_keys(pathfinder: &MixNodePathfinder) -> Option<encryption::KeyPair> {
+        if !pathfinder.previous_private_encryption_key().exists() {
+            return None;
+        }
+
+        let previous_sphinx_keypair: encryption::KeyPair =
+            pemstore::load_keypair(&pemstore::KeyPairPath::new(
+                pathfinder.previous_private_encryption_key().to_owned(),
+                pathfinder.previous_public_encryption_key().to_owned(),
+            ))
+            .expect("Failed to read stored previous sphinx key files");
+        Some(previous_sphinx_keypair)
+    }
+
     /// Signs the node config's bech32 address to produce a verification code for use in the wallet.
     /// Exits if the address isn't valid (which should protect against manual edits).
     fn generate_owner_signature(&self) -> String {
@@ -126,6 +170,7 @@ impl MixNode {
         &self,
         atomic_verloc_result: AtomicVerlocResult,
         node_stats_pointer: SharedNodeStats,
+        delay_queue_depth: DelayQueueDepth,
     ) {
         info!("Starting HTTP API on http://localhost:8000");
 
@@ -138,17 +183,22 @@ impl MixNode {
         let verloc_state = VerlocState::new(atomic_verloc_result);
         let descriptor = self.descriptor.clone();
 
-        tokio::spawn(async move {
-            rocket::build()
-                .configure(config)
-                .mount("/", routes![verlocRoute, description, stats, hardware])
-                .register("/", catchers![not_found])
-                .manage(verloc_state)
-                .manage(descriptor)
-                .manage(node_stats_pointer)
-                .launch()
-                .await
-        });
+        let mut rocket = rocket::build()
+            .configure(config)
+            .mount("/", routes![verlocRoute, description, stats, hardware])
+            .register("/", catchers![not_found])
+            .manage(verloc_state)
+            .manage(descriptor)
+            .manage(node_stats_pointer);
+
+        if self.config.get_metrics_enabled() {
+            info!("Exposing node metrics under /metrics");
+            rocket = rocket
+                .mount("/", routes![metrics])
+                .manage(delay_queue_depth);
+        }
+
+        tokio::spawn(async move { rocket.launch().await });
     }
 
     fn start_node_stats_controller(
@@ -167,8 +217,53 @@ impl MixNode {
         (node_stats_pointer, update_sender)
     }
 
+    fn start_sphinx_key_rotation_controller(
+        &mut self,
+        sphinx_packet_processor: SphinxPacketProcessor,
+        shutdown: ShutdownListener,
+    ) {
+        // the previous rotation has to complete before the next one can begin
+        let state = match (
+            self.previous_sphinx_keypair.take(),
+            self.next_sphinx_keypair.take(),
+        ) {
+            (Some(previous_sphinx_keypair), next_sphinx_keypair) => {
+                if next_sphinx_keypair.is_some() {
+                    warn!("The next sphinx key is going to be ignored until the previous one gets retired");
+                }
+                sphinx_packet_processor
+                    .set_previous_sphinx_key(previous_sphinx_keypair.private_key().into());
+                RotationState::Overlapping {
+                    current_sphinx_key: self.sphinx_keypair.public_key().to_base58_string(),
+                    overlap_end_epoch: None,
+                }
+            }
+            (None, Some(next_sphinx_keypair)) => {
+                sphinx_packet_processor
+                    .set_next_sphinx_key(next_sphinx_keypair.private_key().into());
+                RotationState::AwaitingActivation(next_sphinx_keypair)
+            }
+            // there's nothing to rotate to if the next key hasn't been generated
+            (None, None) => return,
+        };
+
+        info!("Starting sphinx key rotation controller...");
+
+        let mut controller = SphinxKeyRotationController::new(
+            self.config.get_sphinx_key_rotation_check_interval(),
+            self.identity_keypair.public_key().to_base58_string(),
+            self.config.get_validator_api_endpoints(),
+            MixNodePathfinder::new_from_config(&self.config),
+            sphinx_packet_processor,
+            state,
+            shutdown,
+        );
+        tokio::spawn(async move { controller.run().await });
+    }
+
     fn start_socket_listener(
         &self,
+        sphinx_packet_processor: SphinxPacketProcessor,
         node_stats_update_sender: node_statistics::UpdateSender,
         delay_forwarding_channel: PacketDelayForwardSender,
         shutdown: ShutdownListener,
@@ -176,7 +271,7 @@ impl MixNode {
         info!("Starting socket listener...");
 
         let packet_processor =
-            PacketProcessor::new(self.sphinx_keypair.private_key(), node_stats_update_sender);
+            PacketProcessor::new(sphinx_packet_processor, node_stats_update_sender);
 
         let connection_handler = ConnectionHandler::new(packet_processor, delay_forwarding_channel);
 
@@ -191,6 +286,7 @@ impl MixNode {
     fn start_packet_delay_forwarder(
         &mut self,
         node_stats_update_sender: node_statistics::UpdateSender,
+        delay_queue_depth: DelayQueueDepth,
         shutdown: ShutdownListener,
     ) -> PacketDelayForwardSender {
         info!("Starting packet delay-forwarder...");
@@ -205,6 +301,7 @@ impl MixNode {
         let mut packet_forwarder = DelayForwarder::new(
             mixnet_client::Client::new(client_config),
             node_stats_update_sender,
+            delay_queue_depth.0,
             shutdown,
         );
 
@@ -316,9 +413,20 @@ impl MixNode {
 
         let (node_stats_pointer, node_stats_update_sender) =
             self.start_node_stats_controller(shutdown.subscribe());
-        let delay_forwarding_channel = self
-            .start_packet_delay_forwarder(node_stats_update_sender.clone(), shutdown.subscribe());
+        let delay_queue_depth = DelayQueueDepth::default();
+        let delay_forwarding_channel = self.start_packet_delay_forwarder(
+            node_stats_update_sender.clone(),
+            delay_queue_depth.clone(),
+            shutdown.subscribe(),
+        );
+        let sphinx_packet_processor =
+            SphinxPacketProcessor::new(self.sphinx_keypair.private_key().into());
+        self.start_sphinx_key_rotation_controller(
+            sphinx_packet_processor.clone(),
+            shutdown.subscribe(),
+        );
         self.start_socket_listener(
+            sphinx_packet_processor,
             node_stats_update_sender,
             delay_forwarding_channel,
             shutdown.subscribe(),
@@ -328,7 +436,7 @@ impl MixNode {
         // Rocket handles shutdown on it's own, but its shutdown handling should be incorporated
         // with that of the rest of the tasks.
         // Currently it's runtime is forcefully terminated once the mixnode exits.
-        self.start_http_api(atomic_verloc_results, node_stats_pointer);
+        self.start_http_api(atomic_verloc_results, node_stats_pointer, delay_queue_depth);
 
         info!("Finished nym mixnode startup procedure - it should now be able to receive mix traffic!");
         self.wait_for_interrupt(shutdown).await
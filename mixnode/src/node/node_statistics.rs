@@ -1,6 +1,7 @@
 use futures::channel::mpsc;
 use futures::lock::Mutex;
 use futures::StreamExt;
+use metrics_common::MetricsEncoder;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::ops::DerefMut;
@@ -34,6 +35,8 @@ impl SharedNodeStats {
                 packets_received_since_last_update: 0,
                 packets_sent_since_last_update: HashMap::new(),
                 packets_explicitly_dropped_since_last_update: HashMap::new(),
+                packets_replayed_since_startup: 0,
+                packets_replayed_since_last_update: 0,
             })),
         }
     }
@@ -43,6 +46,7 @@ impl SharedNodeStats {
         new_received: u64,
         new_sent: PacketsMap,
         new_dropped: PacketsMap,
+        new_replayed: u64,
     ) {
         let mut guard = self.inner.write().await;
         let snapshot_time = SystemTime::now();
@@ -51,6 +55,7 @@ impl SharedNodeStats {
         guard.update_time = snapshot_time;
 
         guard.packets_received_since_startup += new_received;
+        guard.packets_replayed_since_startup += new_replayed;
         for (mix, count) in &new_sent {
             *guard
                 .packets_sent_since_startup
@@ -60,7 +65,7 @@ impl SharedNodeStats {
 
         for (mix, count) in &new_dropped {
             *guard
-                .packets_explicitly_dropped_since_last_update
+                .packets_explicitly_dropped_since_startup
                 .entry(mix.clone())
                 .or_insert(0) += *count;
         }
@@ -68,6 +73,7 @@ impl SharedNodeStats {
         guard.packets_received_since_last_update = new_received;
         guard.packets_sent_since_last_update = new_sent;
         guard.packets_explicitly_dropped_since_last_update = new_dropped;
+        guard.packets_replayed_since_last_update = new_replayed;
     }
 
     pub(crate) async fn clone_data(&self) -> NodeStats {
@@ -102,9 +108,40 @@ pub(crate) struct NodeStats {
 
     // we know for sure we dropped packets to those destinations
     packets_explicitly_dropped_since_last_update: PacketsMap,
+
+    // packets that we have already seen before and thus dropped
+    packets_replayed_since_startup: u64,
+
+    packets_replayed_since_last_update: u64,
 }
 
 impl NodeStats {
+    pub(crate) fn encode_metrics(&self, encoder: &mut MetricsEncoder) {
+        encoder
+            .counter(
+                "nym_mixnode_packets_received_total",
+                "Number of packets received since startup",
+                self.packets_received_since_startup,
+            )
+            .labelled_counter(
+                "nym_mixnode_packets_sent_total",
+                "Number of packets sent since startup, per destination",
+                "destination",
+                sorted_packets(&self.packets_sent_since_startup),
+            )
+            .labelled_counter(
+                "nym_mixnode_packets_dropped_total",
+                "Number of packets explicitly dropped since startup, per destination",
+                "destination",
+                sorted_packets(&self.packets_explicitly_dropped_since_startup),
+            )
+            .counter(
+                "nym_mixnode_packets_replayed_total",
+                "Number of replayed packets dropped since startup",
+                self.packets_replayed_since_startup,
+            );
+    }
+
     pub(crate) fn simplify(&self) -> NodeStatsSimple {
         NodeStatsSimple {
             update_time: self.update_time,
@@ -121,10 +158,22 @@ impl NodeStats {
                 .packets_explicitly_dropped_since_last_update
                 .values()
                 .sum(),
+            packets_replayed_since_startup: self.packets_replayed_since_startup,
+            packets_replayed_since_last_update: self.packets_replayed_since_last_update,
         }
     }
 }
 
+// keep the output stable between scrapes
+fn sorted_packets(packets: &PacketsMap) -> Vec<(&str, u64)> {
+    let mut sorted: Vec<_> = packets
+        .iter()
+        .map(|(destination, count)| (destination.as_str(), *count))
+        .collect();
+    sorted.sort_unstable();
+    sorted
+}
+
 #[derive(Serialize, Clone)]
 pub(crate) struct NodeStatsSimple {
     #[serde(serialize_with = "humantime_serde::serialize")]
@@ -148,12 +197,18 @@ pub(crate) struct NodeStatsSimple {
 
     // we know for sure we dropped those packets
     packets_explicitly_dropped_since_last_update: u64,
+
+    // packets that we have already seen before and thus dropped
+    packets_replayed_since_startup: u64,
+
+    packets_replayed_since_last_update: u64,
 }
 
 pub(crate) enum PacketEvent {
     Sent(String),
     Received,
     Dropped(String),
+    Replayed,
 }
 
 #[derive(Debug, Clone)]
@@ -166,6 +221,7 @@ struct PacketDataInner {
     received: AtomicU64,
     sent: Mutex<PacketsMap>,
     dropped: Mutex<PacketsMap>,
+    replayed: AtomicU64,
 }
 
 impl CurrentPacketData {
@@ -175,6 +231,7 @@ impl CurrentPacketData {
                 received: AtomicU64::new(0),
                 sent: Mutex::new(HashMap::new()),
                 dropped: Mutex::new(HashMap::new()),
+                replayed: AtomicU64::new(0),
             }),
         }
     }
@@ -183,6 +240,10 @@ impl CurrentPacketData {
         self.inner.received.fetch_add(1, Ordering::SeqCst);
     }
 
+    fn increment_replayed(&self) {
+        self.inner.replayed.fetch_add(1, Ordering::SeqCst);
+    }
+
     async fn increment_sent(&self, destination: String) {
         let mut unlocked = self.inner.sent.lock().await;
         let receiver_count = unlocked.entry(destination).or_insert(0);
@@ -195,15 +256,16 @@ impl CurrentPacketData {
         *dropped_count += 1;
     }
 
-    async fn acquire_and_reset(&self) -> (u64, PacketsMap, PacketsMap) {
+    async fn acquire_and_reset(&self) -> (u64, PacketsMap, PacketsMap, u64) {
         let mut unlocked_sent = self.inner.sent.lock().await;
         let mut unlocked_dropped = self.inner.dropped.lock().await;
         let received = self.inner.received.swap(0, Ordering::SeqCst);
+        let replayed = self.inner.replayed.swap(0, Ordering::SeqCst);
 
         let sent = std::mem::take(unlocked_sent.deref_mut());
         let dropped = std::mem::take(unlocked_dropped.deref_mut());
 
-        (received, sent, dropped)
+        (received, sent, dropped, replayed)
     }
 }
 
@@ -234,6 +296,7 @@ impl UpdateHandler {
                 Some(packet_data) = self.update_receiver.next() => {
                     match packet_data {
                         PacketEvent::Received => self.current_data.increment_received(),
+                        PacketEvent::Replayed => self.current_data.increment_replayed(),
                         PacketEvent::Sent(destination) => {
                             self.current_data.increment_sent(destination).await
                         }
@@ -278,6 +341,12 @@ impl UpdateSender {
         self.0.unbounded_send(PacketEvent::Received).unwrap()
     }
 
+    pub(crate) fn report_replayed(&self) {
+        // in unbounded_send() failed it means that the receiver channel was disconnected
+        // and hence something weird must have happened without a way of recovering
+        self.0.unbounded_send(PacketEvent::Replayed).unwrap()
+    }
+
     pub(crate) fn report_dropped(&self, destination: String) {
         // in unbounded_send() failed it means that the receiver channel was disconnected
         // and hence something weird must have happened without a way of recovering
@@ -313,8 +382,11 @@ impl StatsUpdater {
 
     async fn update_stats(&self) {
         // grab new data since last update
-        let (received, sent, dropped) = self.current_packet_data.acquire_and_reset().await;
-        self.current_stats.update(received, sent, dropped).await;
+        let (received, sent, dropped, replayed) =
+            self.current_packet_data.acquire_and_reset().await;
+        self.current_stats
+            .update(received, sent, dropped, replayed)
+            .await;
     }
 
     async fn run(&mut self) {
@@ -362,6 +434,14 @@ impl PacketStatsConsoleLogger {
                 stats.packets_sent_since_last_update.values().sum::<u64>(),
                 difference_secs,
             );
+            if stats.packets_replayed_since_startup > 0 {
+                info!(
+                    "Since startup dropped {} replayed packets! ({} in last {} seconds)",
+                    stats.packets_replayed_since_startup,
+                    stats.packets_replayed_since_last_update,
+                    difference_secs,
+                );
+            }
             if !stats.packets_explicitly_dropped_since_startup.is_empty() {
                 info!(
                     "Since startup dropped {} packets! ({} in last {} seconds)",
@@ -536,5 +616,54 @@ mod tests {
         assert_eq!(&stats.packets_sent_since_last_update.len(), &1);
         assert_eq!(&stats.packets_received_since_startup, &0u64);
         assert!(&stats.packets_explicitly_dropped_since_startup.is_empty());
+        assert_eq!(&stats.packets_replayed_since_startup, &0u64);
+    }
+
+    #[tokio::test]
+    async fn replayed_packets_reported_are_received() {
+        let logging_delay = Duration::from_millis(20);
+        let stats_updating_delay = Duration::from_millis(10);
+        let shutdown = ShutdownNotifier::default();
+        let node_stats_controller =
+            Controller::new(logging_delay, stats_updating_delay, shutdown.subscribe());
+
+        let node_stats_pointer = node_stats_controller.get_node_stats_data_pointer();
+        let update_sender = node_stats_controller.start();
+        tokio::time::pause();
+
+        update_sender.report_received();
+        update_sender.report_received();
+        update_sender.report_replayed();
+        tokio::task::yield_now().await;
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        tokio::task::yield_now().await;
+
+        let stats = node_stats_pointer.read().await;
+        assert_eq!(&stats.packets_received_since_startup, &2u64);
+        assert_eq!(&stats.packets_replayed_since_startup, &1u64);
+        assert_eq!(&stats.packets_replayed_since_last_update, &1u64);
+    }
+
+    #[tokio::test]
+    async fn dropped_packets_are_encoded_as_metrics() {
+        let shared_stats = SharedNodeStats::new();
+        let dropped: PacketsMap = vec![("bar".to_string(), 3)].into_iter().collect();
+        shared_stats
+            .update(5, HashMap::new(), dropped.clone(), 0)
+            .await;
+        shared_stats.update(0, HashMap::new(), dropped, 0).await;
+
+        let stats = shared_stats.clone_data().await;
+        assert_eq!(
+            stats.packets_explicitly_dropped_since_startup.get("bar"),
+            Some(&6u64)
+        );
+
+        let mut encoder = MetricsEncoder::new();
+        stats.encode_metrics(&mut encoder);
+        let encoded = encoder.finish();
+        assert!(encoded.contains("nym_mixnode_packets_received_total 5\n"));
+        assert!(encoded.contains("nym_mixnode_packets_dropped_total{destination=\"bar\"} 6\n"));
     }
 }
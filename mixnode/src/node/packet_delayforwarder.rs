@@ -4,6 +4,7 @@
 use crate::node::node_statistics::UpdateSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
+use metrics_common::Gauge;
 use nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue};
 use nymsphinx::forwarding::packet::MixPacket;
 use std::io;
@@ -28,6 +29,7 @@ where
     packet_sender: PacketDelayForwardSender,
     packet_receiver: PacketDelayForwardReceiver,
     node_stats_update_sender: UpdateSender,
+    delay_queue_depth: Gauge,
     shutdown: ShutdownListener,
 }
 
@@ -38,6 +40,7 @@ where
     pub(crate) fn new(
         client: C,
         node_stats_update_sender: UpdateSender,
+        delay_queue_depth: Gauge,
         shutdown: ShutdownListener,
     ) -> DelayForwarder<C> {
         let (packet_sender, packet_receiver) = mpsc::unbounded();
@@ -48,6 +51,7 @@ where
             packet_sender,
             packet_receiver,
             node_stats_update_sender,
+            delay_queue_depth,
             shutdown,
         }
     }
@@ -84,6 +88,7 @@ where
 
     /// Upon packet being finished getting delayed, forward it to the mixnet.
     fn handle_done_delaying(&mut self, packet: Expired<MixPacket>) {
+        self.delay_queue_depth.dec();
         let delayed_packet = packet.into_inner();
         self.forward_packet(delayed_packet)
     }
@@ -98,6 +103,7 @@ where
                 self.forward_packet(new_packet.0)
             } else {
                 self.delay_queue.insert_at(new_packet.0, instant);
+                self.delay_queue_depth.inc();
             }
         } else {
             self.forward_packet(new_packet.0)
@@ -206,8 +212,12 @@ mod tests {
         let client = TestClient::default();
         let client_packets_sent = client.packets_sent.clone();
         let shutdown = ShutdownNotifier::default();
-        let mut delay_forwarder =
-            DelayForwarder::new(client, node_stats_update_sender, shutdown.subscribe());
+        let mut delay_forwarder = DelayForwarder::new(
+            client,
+            node_stats_update_sender,
+            Gauge::new(),
+            shutdown.subscribe(),
+        );
         let packet_sender = delay_forwarder.sender();
 
         // Spawn the worker, listening on packet_sender channel
@@ -0,0 +1,275 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::persistence::pathfinder::MixNodePathfinder;
+use ::crypto::asymmetric::encryption;
+use log::{debug, error, info, warn};
+use mixnode_common::packet_processor::processor::SphinxPacketProcessor;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::io;
+use std::time::Duration;
+use task::ShutdownListener;
+use url::Url;
+
+/// Stage of the sphinx key rotation the node is going through.
+pub(crate) enum RotationState {
+    /// The next key has been generated and is accepted alongside the current one while waiting
+    /// for the announced rotation to activate.
+    AwaitingActivation(encryption::KeyPair),
+
+    /// The next key became the current one and the previous key is still accepted until
+    /// the overlap window ends. The end of the window is not known if the node got restarted
+    /// during it.
+    Overlapping {
+        current_sphinx_key: String,
+        overlap_end_epoch: Option<u32>,
+    },
+}
+
+/// Follows the sphinx key rotation announced in the bond of this node and switches the keys
+/// used for unwrapping packets once the rotation becomes active and then once its overlap
+/// window is over.
+pub(crate) struct SphinxKeyRotationController {
+    check_interval: Duration,
+    identity_key: String,
+    validator_api_urls: Vec<Url>,
+    pathfinder: MixNodePathfinder,
+    packet_processor: SphinxPacketProcessor,
+
+    /// `None` once the previous key got retired and there's nothing left to rotate.
+    state: Option<RotationState>,
+
+    shutdown: ShutdownListener,
+}
+
+impl SphinxKeyRotationController {
+    pub(crate) fn new(
+        check_interval: Duration,
+        identity_key: String,
+        validator_api_urls: Vec<Url>,
+        pathfinder: MixNodePathfinder,
+        packet_processor: SphinxPacketProcessor,
+        state: RotationState,
+        shutdown: ShutdownListener,
+    ) -> Self {
+        SphinxKeyRotationController {
+            check_interval,
+            identity_key,
+            validator_api_urls,
+            pathfinder,
+            packet_processor,
+            state: Some(state),
+            shutdown,
+        }
+    }
+
+    fn random_api_client(&self) -> validator_client::ApiClient {
+        let validator_api = self
+            .validator_api_urls
+            .choose(&mut thread_rng())
+            .expect("The list of validator apis is empty");
+
+        validator_client::ApiClient::new(validator_api.clone())
+    }
+
+    /// Stores the next sphinx keys as the current ones so that they would be used after
+    /// the node restarts. The previous keys are kept aside until the overlap window ends,
+    /// so that a restart during it would not stop the node from accepting them early.
+    fn persist_activated_sphinx_keys(
+        &self,
+        next_sphinx_keypair: &encryption::KeyPair,
+    ) -> io::Result<()> {
+        std::fs::copy(
+            self.pathfinder.private_encryption_key(),
+            self.pathfinder.previous_private_encryption_key(),
+        )?;
+        std::fs::copy(
+            self.pathfinder.public_encryption_key(),
+            self.pathfinder.previous_public_encryption_key(),
+        )?;
+        pemstore::store_keypair(
+            next_sphinx_keypair,
+            &pemstore::KeyPairPath::new(
+                self.pathfinder.private_encryption_key().to_owned(),
+                self.pathfinder.public_encryption_key().to_owned(),
+            ),
+        )?;
+
+        // at this point the rotation has been persisted, the leftover files are ignored
+        // by the node as they contain its current key
+        if let Err(err) = std::fs::remove_file(self.pathfinder.next_private_encryption_key())
+            .and_then(|_| std::fs::remove_file(self.pathfinder.next_public_encryption_key()))
+        {
+            warn!("Failed to remove the next sphinx key files - {}", err)
+        }
+        Ok(())
+    }
+
+    fn remove_previous_sphinx_keys(&self) -> io::Result<()> {
+        std::fs::remove_file(self.pathfinder.previous_private_encryption_key())?;
+        std::fs::remove_file(self.pathfinder.previous_public_encryption_key())
+    }
+
+    fn activate_next_sphinx_key(&mut self, overlap_end_epoch: u32) {
+        let next_sphinx_keypair = match self.state.take() {
+            Some(RotationState::AwaitingActivation(keypair)) => keypair,
+            state => {
+                self.state = state;
+                return;
+            }
+        };
+
+        if let Err(err) = self.persist_activated_sphinx_keys(&next_sphinx_keypair) {
+            // we'll try again during the next check
+            error!(
+                "Failed to store the next sphinx keys as the current ones - {}",
+                err
+            );
+            self.state = Some(RotationState::AwaitingActivation(next_sphinx_keypair));
+            return;
+        }
+
+        let current_sphinx_key = next_sphinx_keypair.public_key().to_base58_string();
+        info!(
+            "Activating the next sphinx key {}. The previous key is going to be accepted until epoch {}",
+            current_sphinx_key, overlap_end_epoch
+        );
+        self.packet_processor.activate_next_sphinx_key();
+        self.state = Some(RotationState::Overlapping {
+            current_sphinx_key,
+            overlap_end_epoch: Some(overlap_end_epoch),
+        });
+    }
+
+    fn retire_previous_sphinx_key(&mut self) {
+        info!("Retiring the previous sphinx key");
+        self.packet_processor.retire_secondary_sphinx_key();
+        if let Err(err) = self.remove_previous_sphinx_keys() {
+            // if the node restarts, it's going to find out the key should have already been retired
+            warn!("Failed to remove the previous sphinx key files - {}", err)
+        }
+        self.state = None;
+    }
+
+    /// Returns the epoch at which the overlap window of the announced rotation to the specified
+    /// key ends, if that rotation has already been activated.
+    async fn activated_rotation_overlap_end(
+        &self,
+        validator_client: &validator_client::ApiClient,
+        sphinx_key: &str,
+        current_epoch: u32,
+    ) -> Option<u32> {
+        let mixnodes = match validator_client.get_cached_mixnodes().await {
+            Ok(mixnodes) => mixnodes,
+            Err(err) => {
+                warn!("Failed to get network mixnodes - {}", err);
+                return None;
+            }
+        };
+
+        let rotation = match mixnodes
+            .into_iter()
+            .find(|bond| bond.mix_node.identity_key == self.identity_key)
+            .and_then(|bond| bond.sphinx_key_rotation)
+        {
+            Some(rotation) => rotation,
+            None => {
+                debug!("There is no sphinx key rotation announced for this node");
+                return None;
+            }
+        };
+
+        if rotation.next_sphinx_key != sphinx_key {
+            warn!(
+                "The announced next sphinx key ({}) is different from the one of this node ({})",
+                rotation.next_sphinx_key, sphinx_key
+            );
+            return None;
+        }
+
+        if rotation.is_active(current_epoch) {
+            Some(rotation.overlap_end_epoch())
+        } else {
+            None
+        }
+    }
+
+    async fn check_rotation(&mut self) {
+        let validator_client = self.random_api_client();
+
+        let current_epoch = match validator_client.get_cached_current_epoch().await {
+            Ok(Some(epoch)) => epoch.id(),
+            Ok(None) => {
+                debug!("The current epoch is not known yet");
+                return;
+            }
+            Err(err) => {
+                warn!("Failed to get the current epoch - {}", err);
+                return;
+            }
+        };
+
+        match &self.state {
+            Some(RotationState::AwaitingActivation(next_sphinx_keypair)) => {
+                let next_sphinx_key = next_sphinx_keypair.public_key().to_base58_string();
+                if let Some(overlap_end_epoch) = self
+                    .activated_rotation_overlap_end(
+                        &validator_client,
+                        &next_sphinx_key,
+                        current_epoch,
+                    )
+                    .await
+                {
+                    self.activate_next_sphinx_key(overlap_end_epoch)
+                }
+            }
+            Some(RotationState::Overlapping {
+                current_sphinx_key,
+                overlap_end_epoch: None,
+            }) => {
+                // the node got restarted during the overlap window, so we have to find out its end
+                let current_sphinx_key = current_sphinx_key.clone();
+                if let Some(overlap_end_epoch) = self
+                    .activated_rotation_overlap_end(
+                        &validator_client,
+                        &current_sphinx_key,
+                        current_epoch,
+                    )
+                    .await
+                {
+                    self.state = Some(RotationState::Overlapping {
+                        current_sphinx_key,
+                        overlap_end_epoch: Some(overlap_end_epoch),
+                    });
+                }
+            }
+            Some(RotationState::Overlapping {
+                overlap_end_epoch: Some(overlap_end_epoch),
+                ..
+            }) => {
+                if current_epoch >= *overlap_end_epoch {
+                    self.retire_previous_sphinx_key()
+                }
+            }
+            None => (),
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        while !self.shutdown.is_shutdown() {
+            tokio::select! {
+                _ = tokio::time::sleep(self.check_interval) => self.check_rotation().await,
+                _ = self.shutdown.recv() => {
+                    log::trace!("SphinxKeyRotationController: Received shutdown");
+                }
+            }
+
+            // nothing left to do once the previous key got retired
+            if self.state.is_none() {
+                break;
+            }
+        }
+        log::trace!("SphinxKeyRotationController: Exiting");
+    }
+}
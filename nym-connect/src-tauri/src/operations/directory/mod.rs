@@ -1,14 +1,71 @@
 use crate::error::Result;
-use crate::models::DirectoryService;
+use crate::models::{DirectoryService, DirectoryServiceProvider};
+use config_common::defaults::var_names::API_VALIDATOR;
+use nym_socks5::commands::parse_validators;
+use service_provider_directory_common::service::{ServiceInfo, ServiceType};
+use validator_client::ApiClient;
 
 static SERVICE_PROVIDER_WELLKNOWN_URL: &str =
     "https://nymtech.net/.wellknown/connect/service-providers.json";
 
 #[tauri::command]
 pub async fn get_services() -> Result<Vec<DirectoryService>> {
+    match get_services_from_directory().await {
+        Some(services) if !services.is_empty() => Ok(group_by_service_type(services)),
+        // until the directory contract gets deployed and populated, keep using the hosted list
+        _ => get_services_from_wellknown_url().await,
+    }
+}
+
+async fn get_services_from_directory() -> Option<Vec<ServiceInfo>> {
+    let raw_validators = std::env::var(API_VALIDATOR).ok()?;
+    let api_url = parse_validators(&raw_validators).into_iter().next()?;
+
+    match ApiClient::new(api_url).get_cached_services().await {
+        Ok(services) => Some(services),
+        Err(err) => {
+            log::warn!("Failed to get services from the service provider directory: {err}");
+            None
+        }
+    }
+}
+
+async fn get_services_from_wellknown_url() -> Result<Vec<DirectoryService>> {
     let res = reqwest::get(SERVICE_PROVIDER_WELLKNOWN_URL)
         .await?
         .json::<Vec<DirectoryService>>()
         .await?;
     Ok(res)
 }
+
+fn directory_service(service_type: ServiceType) -> DirectoryService {
+    let (id, description) = match service_type {
+        ServiceType::NetworkRequester => ("network-requester", "Network Requesters"),
+    };
+    DirectoryService {
+        id: id.to_string(),
+        description: description.to_string(),
+        items: Vec::new(),
+    }
+}
+
+fn group_by_service_type(services: Vec<ServiceInfo>) -> Vec<DirectoryService> {
+    let mut grouped: Vec<(ServiceType, DirectoryService)> = Vec::new();
+    for info in services {
+        let service_type = info.service.service_type;
+        let index = match grouped.iter().position(|(t, _)| *t == service_type) {
+            Some(index) => index,
+            None => {
+                grouped.push((service_type, directory_service(service_type)));
+                grouped.len() - 1
+            }
+        };
+        grouped[index].1.items.push(DirectoryServiceProvider {
+            id: info.service_id.to_string(),
+            description: info.service.description,
+            address: info.service.client_address,
+            gateway: info.service.gateway,
+        });
+    }
+    grouped.into_iter().map(|(_, service)| service).collect()
+}
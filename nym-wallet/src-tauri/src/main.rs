@@ -57,6 +57,7 @@ fn main() {
             mixnet::bond::mixnode_bond_details,
             mixnet::bond::unbond_gateway,
             mixnet::bond::unbond_mixnode,
+            mixnet::bond::update_gateway_config,
             mixnet::bond::update_mixnode,
             mixnet::delegate::delegate_to_mixnode,
             mixnet::delegate::get_delegator_rewards,
@@ -101,6 +102,7 @@ fn main() {
             vesting::bond::vesting_bond_mixnode,
             vesting::bond::vesting_unbond_gateway,
             vesting::bond::vesting_unbond_mixnode,
+            vesting::bond::vesting_update_gateway_config,
             vesting::bond::vesting_update_mixnode,
             vesting::bond::withdraw_vested_coins,
             vesting::delegate::get_pending_vesting_delegation_events,
@@ -125,6 +127,7 @@ fn main() {
             simulate::mixnet::simulate_unbond_gateway,
             simulate::mixnet::simulate_bond_mixnode,
             simulate::mixnet::simulate_unbond_mixnode,
+            simulate::mixnet::simulate_update_gateway_config,
             simulate::mixnet::simulate_update_mixnode,
             simulate::mixnet::simulate_delegate_to_mixnode,
             simulate::mixnet::simulate_undelegate_from_mixnode,
@@ -134,6 +137,7 @@ fn main() {
             simulate::vesting::simulate_vesting_unbond_gateway,
             simulate::vesting::simulate_vesting_bond_mixnode,
             simulate::vesting::simulate_vesting_unbond_mixnode,
+            simulate::vesting::simulate_vesting_update_gateway_config,
             simulate::vesting::simulate_vesting_update_mixnode,
             simulate::vesting::simulate_withdraw_vested_coins,
             simulate::vesting::simulate_vesting_claim_delegator_reward,
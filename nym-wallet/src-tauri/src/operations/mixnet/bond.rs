@@ -1,6 +1,7 @@
 use crate::error::BackendError;
 use crate::state::WalletState;
 use crate::{Gateway, MixNode};
+use mixnet_contract_common::GatewayConfigUpdate;
 use nym_types::currency::DecCoin;
 use nym_types::gateway::GatewayBond;
 use nym_types::mixnode::MixNodeBond;
@@ -54,6 +55,35 @@ pub async fn unbond_gateway(
     )?)
 }
 
+#[tauri::command]
+pub async fn update_gateway_config(
+    new_config: GatewayConfigUpdate,
+    fee: Option<Fee>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<TransactionExecuteResult, BackendError> {
+    let guard = state.read().await;
+    let fee_amount = guard.convert_tx_fee(fee.as_ref());
+    log::info!(
+        ">>> Update gateway config: host = {}, mix_port = {}, clients_port = {}, location = {}, version = {}, fee = {:?}",
+        new_config.host,
+        new_config.mix_port,
+        new_config.clients_port,
+        new_config.location,
+        new_config.version,
+        fee,
+    );
+    let res = guard
+        .current_client()?
+        .nymd
+        .update_gateway_config(new_config, fee)
+        .await?;
+    log::info!("<<< tx hash = {}", res.transaction_hash);
+    log::trace!("<<< {:?}", res);
+    Ok(TransactionExecuteResult::from_execute_result(
+        res, fee_amount,
+    )?)
+}
+
 #[tauri::command]
 pub async fn bond_mixnode(
     mixnode: MixNode,
@@ -5,7 +5,7 @@ use crate::error::BackendError;
 use crate::operations::simulate::FeeDetails;
 use crate::WalletState;
 use mixnet_contract_common::IdentityKey;
-use mixnet_contract_common::{ExecuteMsg, Gateway, MixNode};
+use mixnet_contract_common::{ExecuteMsg, Gateway, GatewayConfigUpdate, MixNode};
 use nym_types::currency::DecCoin;
 
 #[tauri::command]
@@ -53,6 +53,25 @@ pub async fn simulate_unbond_gateway(
     guard.create_detailed_fee(result)
 }
 
+#[tauri::command]
+pub async fn simulate_update_gateway_config(
+    new_config: GatewayConfigUpdate,
+    state: tauri::State<'_, WalletState>,
+) -> Result<FeeDetails, BackendError> {
+    let guard = state.read().await;
+    let client = guard.current_client()?;
+    let mixnet_contract = client.nymd.mixnet_contract_address();
+
+    let msg = client.nymd.wrap_contract_execute_message(
+        mixnet_contract,
+        &ExecuteMsg::UpdateGatewayConfig { new_config },
+        vec![],
+    )?;
+
+    let result = client.nymd.simulate(vec![msg]).await?;
+    guard.create_detailed_fee(result)
+}
+
 #[tauri::command]
 pub async fn simulate_bond_mixnode(
     mixnode: MixNode,
@@ -5,7 +5,7 @@ use crate::error::BackendError;
 use crate::operations::simulate::FeeDetails;
 use crate::WalletState;
 use mixnet_contract_common::IdentityKey;
-use mixnet_contract_common::{Gateway, MixNode};
+use mixnet_contract_common::{Gateway, GatewayConfigUpdate, MixNode};
 use nym_types::currency::DecCoin;
 use vesting_contract_common::ExecuteMsg;
 
@@ -55,6 +55,26 @@ pub async fn simulate_vesting_unbond_gateway(
     guard.create_detailed_fee(result)
 }
 
+#[tauri::command]
+pub async fn simulate_vesting_update_gateway_config(
+    new_config: GatewayConfigUpdate,
+    state: tauri::State<'_, WalletState>,
+) -> Result<FeeDetails, BackendError> {
+    let guard = state.read().await;
+
+    let client = guard.current_client()?;
+    let vesting_contract = client.nymd.vesting_contract_address();
+
+    let msg = client.nymd.wrap_contract_execute_message(
+        vesting_contract,
+        &ExecuteMsg::UpdateGatewayConfig { new_config },
+        vec![],
+    )?;
+
+    let result = client.nymd.simulate(vec![msg]).await?;
+    guard.create_detailed_fee(result)
+}
+
 #[tauri::command]
 pub async fn simulate_vesting_bond_mixnode(
     mixnode: MixNode,
@@ -60,6 +60,8 @@ pub enum Operation {
     BondGatewayOnBehalf,
     UnbondGateway,
     UnbondGatewayOnBehalf,
+    UpdateGatewayConfig,
+    UpdateGatewayConfigOnBehalf,
 
     UpdateContractSettings,
 
@@ -104,6 +106,8 @@ impl Operation {
             Operation::BondGatewayOnBehalf => 200_000u64.into(),
             Operation::UnbondGateway => 175_000u64.into(),
             Operation::UnbondGatewayOnBehalf => 200_000u64.into(),
+            Operation::UpdateGatewayConfig => 175_000u64.into(),
+            Operation::UpdateGatewayConfigOnBehalf => 200_000u64.into(),
 
             Operation::UpdateContractSettings => 175_000u64.into(),
             Operation::BeginMixnodeRewarding => 175_000u64.into(),
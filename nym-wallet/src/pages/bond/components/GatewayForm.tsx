@@ -25,6 +25,7 @@ type TBondFormFields = {
   location: string;
   mixPort: number;
   clientsPort: number;
+  httpApiPort: number;
 };
 
 const defaultValues = {
@@ -39,6 +40,7 @@ const defaultValues = {
   location: '',
   mixPort: 1789,
   clientsPort: 9000,
+  httpApiPort: 8001,
 };
 
 export const GatewayForm = ({
@@ -97,6 +99,8 @@ export const GatewayForm = ({
           mix_port: data.mixPort,
           location: data.location,
           clients_port: data.clientsPort,
+        http_api_port: data.httpApiPort,
+          http_api_port: data.httpApiPort,
         },
         pledge: data.amount,
       });
@@ -117,6 +121,7 @@ export const GatewayForm = ({
         mix_port: data.mixPort,
         location: data.location,
         clients_port: data.clientsPort,
+        http_api_port: data.httpApiPort,
       },
       pledge: data.amount,
       fee: fee?.fee,
@@ -272,6 +277,9 @@ export const GatewayForm = ({
                       setValue('clientsPort', defaultValues.clientsPort, {
                         shouldValidate: true,
                       });
+                      setValue('httpApiPort', defaultValues.httpApiPort, {
+                        shouldValidate: true,
+                      });
                       setValue('withAdvancedOptions', false);
                     } else {
                       setValue('withAdvancedOptions', true);
@@ -311,6 +319,20 @@ export const GatewayForm = ({
                   disabled={disabled}
                 />
               </Grid>
+
+              <Grid item xs={12} sm={4}>
+                <TextField
+                  {...register('httpApiPort', { valueAsNumber: true })}
+                  variant="outlined"
+                  id="httpApiPort"
+                  name="httpApiPort"
+                  label="HTTP API Port"
+                  fullWidth
+                  error={!!errors.httpApiPort}
+                  helperText={errors.httpApiPort?.message && 'A valid port value is required'}
+                  disabled={disabled}
+                />
+              </Grid>
             </>
           )}
         </Grid>
@@ -1,5 +1,5 @@
 import { Fee, DecCoin, SendTxResult, TransactionExecuteResult } from '@nymproject/types';
-import { EnumNodeType, TBondGatewayArgs, TBondMixNodeArgs } from '../types';
+import { EnumNodeType, TBondGatewayArgs, TBondMixNodeArgs, TUpdateGatewayConfigArgs } from '../types';
 import { invokeWrapper } from './wrapper';
 
 export const bondGateway = async (args: TBondGatewayArgs) =>
@@ -7,6 +7,9 @@ export const bondGateway = async (args: TBondGatewayArgs) =>
 
 export const unbondGateway = async (fee?: Fee) => invokeWrapper<TransactionExecuteResult>('unbond_gateway', { fee });
 
+export const updateGatewayConfig = async (args: TUpdateGatewayConfigArgs) =>
+  invokeWrapper<TransactionExecuteResult>('update_gateway_config', args);
+
 export const bondMixNode = async (args: TBondMixNodeArgs) =>
   invokeWrapper<TransactionExecuteResult>('bond_mixnode', args);
 
@@ -1,5 +1,5 @@
 import { FeeDetails, DecCoin, Gateway, MixNode } from '@nymproject/types';
-import { TBondGatewayArgs, TBondMixNodeArgs } from 'src/types';
+import { TBondGatewayArgs, TBondMixNodeArgs, TGatewayConfigUpdate } from 'src/types';
 import { invokeWrapper } from './wrapper';
 
 export const simulateBondGateway = async (args: TBondGatewayArgs) =>
@@ -7,6 +7,9 @@ export const simulateBondGateway = async (args: TBondGatewayArgs) =>
 
 export const simulateUnbondGateway = async (args: any) => invokeWrapper<FeeDetails>('simulate_unbond_gateway', args);
 
+export const simulateUpdateGatewayConfig = async (args: { newConfig: TGatewayConfigUpdate }) =>
+  invokeWrapper<FeeDetails>('simulate_update_gateway_config', args);
+
 export const simulateBondMixnode = async (args: TBondMixNodeArgs) =>
   invokeWrapper<FeeDetails>('simulate_bond_mixnode', args);
 
@@ -41,6 +44,9 @@ export const simulateVestingBondGateway = async (args: { gateway: Gateway; pledg
 export const simulateVestingUnbondGateway = async (args: any) =>
   invokeWrapper<FeeDetails>('simulate_vesting_unbond_gateway', args);
 
+export const simulateVestingUpdateGatewayConfig = async (args: { newConfig: TGatewayConfigUpdate }) =>
+  invokeWrapper<FeeDetails>('simulate_vesting_update_gateway_config', args);
+
 export const simulateVestingDelegateToMixnode = async (args: { identity: string }) =>
   invokeWrapper<FeeDetails>('simulate_vesting_delegate_to_mixnode', args);
 
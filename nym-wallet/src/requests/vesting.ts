@@ -11,6 +11,7 @@ import {
   VestingAccountInfo,
 } from '@nymproject/types';
 import { Fee } from '@nymproject/types/dist/types/rust/Fee';
+import { TUpdateGatewayConfigArgs } from 'src/types';
 import { invokeWrapper } from './wrapper';
 
 export const getLockedCoins = async (): Promise<DecCoin> => invokeWrapper<DecCoin>('locked_coins');
@@ -44,6 +45,9 @@ export const vestingBondGateway = async ({
 export const vestingUnbondGateway = async (fee?: Fee) =>
   invokeWrapper<TransactionExecuteResult>('vesting_unbond_gateway', { fee });
 
+export const vestingUpdateGatewayConfig = async (args: TUpdateGatewayConfigArgs) =>
+  invokeWrapper<TransactionExecuteResult>('vesting_update_gateway_config', args);
+
 export const vestingBondMixNode = async ({
   mixnode,
   pledge,
@@ -39,6 +39,20 @@ export type TBondMixNodeArgs = {
   fee?: Fee;
 };
 
+export type TGatewayConfigUpdate = {
+  host: string;
+  mix_port: number;
+  clients_port: number;
+  http_api_port: number;
+  location: string;
+  version: string;
+};
+
+export type TUpdateGatewayConfigArgs = {
+  newConfig: TGatewayConfigUpdate;
+  fee?: Fee;
+};
+
 export type TDelegateArgs = {
   identity: string;
   amount: DecCoin;
@@ -1,6 +1,9 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::exit_policy::{
+    Destination, DestinationHost, ExitPolicy, PolicyDecision, SharedExitPolicy,
+};
 use fs::OpenOptions;
 use io::BufReader;
 use ipnetwork::IpNetwork;
@@ -14,7 +17,21 @@ use std::net::{IpAddr, SocketAddr};
 use std::path::Path;
 use std::path::PathBuf;
 
-/// Filters outbound requests based on what's in an `allowed_hosts` list.
+/// Settings of the filtering of outbound requests.
+#[derive(Debug, Clone)]
+pub struct RequestFilterConfig {
+    pub(crate) open_proxy: bool,
+    pub(crate) allowed_list_location: PathBuf,
+    pub(crate) unknown_list_location: PathBuf,
+    pub(crate) exit_policy_location: PathBuf,
+    pub(crate) reject_non_public_networks: bool,
+}
+
+/// Filters outbound requests based on the exit policy and what's in an `allowed_hosts` list.
+///
+/// Requests rejected by the exit policy are always blocked, even in the open proxy mode, while
+/// the ones it explicitly accepts are always allowed. All other requests are allowed only
+/// if they're on the `allowed_hosts` list, unless running as an open proxy.
 ///
 /// Requests to unknown hosts are automatically written to an `unknown_hosts`
 /// list so that they can be copy/pasted into the `allowed_hosts` list if desired.
@@ -27,65 +44,70 @@ use std::path::PathBuf;
 /// .com, .co.uk, .co.jp, uk.com, etc, so that we can distinguish correct root-ish
 /// domains as allowed. That list is loaded once at startup from the network.
 pub(crate) struct OutboundRequestFilter {
+    open_proxy: bool,
     allowed_hosts: HostsStore,
     domain_list: publicsuffix::List,
+    exit_policy: SharedExitPolicy,
     unknown_hosts: HostsStore,
 }
 
 impl OutboundRequestFilter {
     pub(crate) fn new(
+        open_proxy: bool,
         allowed_hosts: HostsStore,
         unknown_hosts: HostsStore,
+        exit_policy: SharedExitPolicy,
     ) -> OutboundRequestFilter {
         let domain_list = match Self::fetch_domain_list() {
             Ok(list) => list,
             Err(e) => panic!("Couldn't fetch domain list for request filtering, do you have an internet connection?: {:?}", e),
         };
         OutboundRequestFilter {
+            open_proxy,
             allowed_hosts,
             domain_list,
+            exit_policy,
             unknown_hosts,
         }
     }
 
+    /// Reloads the `allowed_hosts` list from its storefile and switches to the provided exit policy.
+    pub(crate) fn reload(&mut self, exit_policy: ExitPolicy) -> io::Result<()> {
+        self.allowed_hosts.reload()?;
+        self.exit_policy.replace(exit_policy);
+        Ok(())
+    }
+
     fn fetch_domain_list() -> Result<List, errors::Error> {
         publicsuffix::List::fetch()
     }
 
-    /// Returns `true` if a host's root domain is in the `allowed_hosts` list.
+    /// Returns `true` if the exit policy accepts the host or, if the policy doesn't
+    /// say anything about it, if the host's root domain is in the `allowed_hosts` list.
     ///
     /// If it's not in the list, return `false` and write it to the `unknown_hosts` storefile.
     pub(crate) fn check(&mut self, host: &str) -> bool {
-        // first check if it's a socket address (ip:port)
-        // (this check is performed to not incorrectly strip what we think might be a port
-        // from ipv6 address, as for example ::1 contains colons but has no port
-        let allowed = if let Ok(socketaddr) = host.parse::<SocketAddr>() {
-            if !self.allowed_hosts.contains_ip_address(socketaddr.ip()) {
-                self.unknown_hosts.maybe_add_ip(socketaddr.ip());
+        let destination = match Self::parse_destination(host) {
+            Some(destination) => destination,
+            None => {
+                log::warn!(
+                    "Blocked outbound connection to malformed address {:?}",
+                    host
+                );
                 return false;
             }
-            true
-        } else if let Ok(ipaddr) = host.parse::<IpAddr>() {
-            // then check if it was an ip address
-            if !self.allowed_hosts.contains_ip_address(ipaddr) {
-                self.unknown_hosts.maybe_add_ip(ipaddr);
+        };
+
+        let allowed = match self.exit_policy.evaluate(&destination) {
+            PolicyDecision::Reject => {
+                log::warn!(
+                    "Blocked outbound connection to {:?} as it is rejected by the exit policy",
+                    host
+                );
                 return false;
             }
-            true
-        } else {
-            // finally, then assume it might be a domain
-            let trimmed = Self::trim_port(host);
-            if let Some(domain_root) = self.get_domain_root(&trimmed) {
-                // it's a domain
-                if !self.allowed_hosts.contains_domain(&domain_root) {
-                    self.unknown_hosts.maybe_add_domain(&trimmed);
-                    return false;
-                }
-                true
-            } else {
-                // it's something else, no idea what, probably some nonsense
-                false
-            }
+            PolicyDecision::Accept => true,
+            PolicyDecision::NoMatch => self.open_proxy || self.check_allowed_hosts(&destination),
         };
 
         if !allowed {
@@ -98,6 +120,51 @@ impl OutboundRequestFilter {
         allowed
     }
 
+    fn check_allowed_hosts(&mut self, destination: &Destination) -> bool {
+        match destination.host() {
+            DestinationHost::Ip(ipaddr) => {
+                if !self.allowed_hosts.contains_ip_address(*ipaddr) {
+                    self.unknown_hosts.maybe_add_ip(*ipaddr);
+                    return false;
+                }
+                true
+            }
+            DestinationHost::Domain(domain) => {
+                if let Some(domain_root) = self.get_domain_root(domain) {
+                    // it's a domain
+                    if !self.allowed_hosts.contains_domain(&domain_root) {
+                        self.unknown_hosts.maybe_add_domain(domain);
+                        return false;
+                    }
+                    true
+                } else {
+                    // it's something else, no idea what, probably some nonsense
+                    false
+                }
+            }
+        }
+    }
+
+    fn parse_destination(host: &str) -> Option<Destination> {
+        // first check if it's a socket address (ip:port)
+        // (this check is performed to not incorrectly strip what we think might be a port
+        // from ipv6 address, as for example ::1 contains colons but has no port
+        if let Ok(socketaddr) = host.parse::<SocketAddr>() {
+            Some(socketaddr.into())
+        } else if let Ok(ipaddr) = host.parse::<IpAddr>() {
+            // then check if it was an ip address
+            Some(Destination::new_ip(ipaddr, None))
+        } else {
+            // finally, then assume it might be a domain
+            let trimmed = Self::trim_port(host);
+            let port = match host.get(trimmed.len() + 1..) {
+                Some(port) => Some(port.parse().ok()?),
+                None => None,
+            };
+            Some(Destination::new_domain(&trimmed, port))
+        }
+    }
+
     fn trim_port(host: &str) -> String {
         let mut tmp: Vec<_> = host.split(':').collect();
         if tmp.len() > 1 {
@@ -172,20 +239,33 @@ pub(crate) struct HostsStore {
 }
 
 impl HostsStore {
-    /// Constructs a new HostsStore
-    pub(crate) fn new(base_dir: PathBuf, filename: PathBuf) -> HostsStore {
-        let storefile = HostsStore::setup_storefile(base_dir, filename);
-        let hosts = HostsStore::load_from_storefile(&storefile)
-            .unwrap_or_else(|_| panic!("Could not load hosts from storefile at {:?}", storefile));
+    /// Constructs a new HostsStore backed by the provided storefile, creating it if needed.
+    pub(crate) fn new(storefile: PathBuf) -> HostsStore {
+        HostsStore::setup_storefile(&storefile);
+        let mut store = HostsStore {
+            storefile,
+            domains: HashSet::new(),
+            ip_nets: Vec::new(),
+        };
+        store.reload().unwrap_or_else(|_| {
+            panic!(
+                "Could not load hosts from storefile at {:?}",
+                store.storefile
+            )
+        });
+        store
+    }
+
+    /// Replaces the hosts in memory with the current contents of the storefile.
+    fn reload(&mut self) -> io::Result<()> {
+        let hosts = HostsStore::load_from_storefile(&self.storefile)?;
 
         let (domains, ip_nets): (Vec<_>, Vec<_>) =
             hosts.into_iter().partition(|host| host.is_domain());
 
-        HostsStore {
-            storefile,
-            domains: domains.into_iter().map(Host::extract_domain).collect(),
-            ip_nets: ip_nets.into_iter().map(Host::extract_ipnetwork).collect(),
-        }
+        self.domains = domains.into_iter().map(Host::extract_domain).collect();
+        self.ip_nets = ip_nets.into_iter().map(Host::extract_ipnetwork).collect();
+        Ok(())
     }
 
     fn append(path: &Path, text: &str) {
@@ -222,15 +302,6 @@ impl HostsStore {
         false
     }
 
-    /// Returns the default base directory for the storefile.
-    ///
-    /// This is split out so we can easily inject our own base_dir for unit tests.
-    pub fn default_base_dir() -> PathBuf {
-        dirs::home_dir()
-            .expect("no home directory known for this OS")
-            .join(".nym")
-    }
-
     fn maybe_add_ip(&mut self, ip: IpAddr) {
         if !self.contains_ip_address(ip) {
             self.ip_nets.push(ip.into());
@@ -245,16 +316,14 @@ impl HostsStore {
         }
     }
 
-    fn setup_storefile(base_dir: PathBuf, filename: PathBuf) -> PathBuf {
-        let dirpath = base_dir.join("service-providers").join("network-requester");
-        fs::create_dir_all(&dirpath)
-            .unwrap_or_else(|_| panic!("could not create storage directory at {:?}", dirpath));
-        let storefile = dirpath.join(filename);
-        let exists = std::path::Path::new(&storefile).exists();
-        if !exists {
-            File::create(&storefile).unwrap();
+    fn setup_storefile(storefile: &Path) {
+        if let Some(dirpath) = storefile.parent() {
+            fs::create_dir_all(dirpath)
+                .unwrap_or_else(|_| panic!("could not create storage directory at {:?}", dirpath));
+        }
+        if !storefile.exists() {
+            File::create(storefile).unwrap();
         }
-        storefile
     }
 
     /// Loads the storefile contents into memory.
@@ -298,11 +367,11 @@ mod tests {
 
         fn setup() -> OutboundRequestFilter {
             let base_dir = test_base_dir();
-            let allowed_filename = PathBuf::from(format!("allowed-{}.list", random_string()));
-            let unknown_filename = PathBuf::from(&format!("unknown-{}.list", random_string()));
-            let allowed = HostsStore::new(base_dir.clone(), allowed_filename);
-            let unknown = HostsStore::new(base_dir, unknown_filename);
-            OutboundRequestFilter::new(allowed, unknown)
+            let allowed_storefile = base_dir.join(format!("allowed-{}.list", random_string()));
+            let unknown_storefile = base_dir.join(format!("unknown-{}.list", random_string()));
+            let allowed = HostsStore::new(allowed_storefile);
+            let unknown = HostsStore::new(unknown_storefile);
+            OutboundRequestFilter::new(false, allowed, unknown, SharedExitPolicy::default())
         }
 
         #[test]
@@ -360,11 +429,11 @@ mod tests {
 
         fn setup() -> OutboundRequestFilter {
             let base_dir = test_base_dir();
-            let allowed_filename = PathBuf::from(format!("allowed-{}.list", random_string()));
-            let unknown_filename = PathBuf::from(&format!("unknown-{}.list", random_string()));
-            let allowed = HostsStore::new(base_dir.clone(), allowed_filename);
-            let unknown = HostsStore::new(base_dir, unknown_filename);
-            OutboundRequestFilter::new(allowed, unknown)
+            let allowed_storefile = base_dir.join(format!("allowed-{}.list", random_string()));
+            let unknown_storefile = base_dir.join(format!("unknown-{}.list", random_string()));
+            let allowed = HostsStore::new(allowed_storefile);
+            let unknown = HostsStore::new(unknown_storefile);
+            OutboundRequestFilter::new(false, allowed, unknown, SharedExitPolicy::default())
         }
 
         #[test]
@@ -392,16 +461,16 @@ mod tests {
         use super::*;
 
         fn setup(allowed: &[&str]) -> OutboundRequestFilter {
-            let (allowed_storefile, base_dir1, allowed_filename) = create_test_storefile();
-            let (_, base_dir2, unknown_filename) = create_test_storefile();
+            let allowed_storefile = create_test_storefile();
+            let unknown_storefile = create_test_storefile();
 
             for allowed_host in allowed {
                 HostsStore::append(&allowed_storefile, allowed_host)
             }
 
-            let allowed = HostsStore::new(base_dir1, allowed_filename);
-            let unknown = HostsStore::new(base_dir2, unknown_filename);
-            OutboundRequestFilter::new(allowed, unknown)
+            let allowed = HostsStore::new(allowed_storefile);
+            let unknown = HostsStore::new(unknown_storefile);
+            OutboundRequestFilter::new(false, allowed, unknown, SharedExitPolicy::default())
         }
 
         #[test]
@@ -512,6 +581,76 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod requests_under_exit_policy {
+        use super::*;
+
+        fn setup(open_proxy: bool, allowed: &[&str], rules: &str) -> OutboundRequestFilter {
+            let allowed_storefile = create_test_storefile();
+            let unknown_storefile = create_test_storefile();
+
+            for allowed_host in allowed {
+                HostsStore::append(&allowed_storefile, allowed_host)
+            }
+
+            let allowed = HostsStore::new(allowed_storefile);
+            let unknown = HostsStore::new(unknown_storefile);
+            let exit_policy = ExitPolicy::new(ExitPolicy::parse_rules(rules).unwrap(), true);
+            OutboundRequestFilter::new(
+                open_proxy,
+                allowed,
+                unknown,
+                SharedExitPolicy::new(exit_policy),
+            )
+        }
+
+        #[test]
+        fn are_rejected_even_if_on_the_allowed_list() {
+            let mut filter = setup(false, &["nymtech.net"], "reject *.nymtech.net:25");
+            assert!(filter.check("nymtech.net:25"));
+            assert!(filter.check("mail.nymtech.net:587"));
+            assert!(!filter.check("mail.nymtech.net:25"));
+        }
+
+        #[test]
+        fn are_rejected_for_private_addresses_even_in_open_proxy_mode() {
+            let mut filter = setup(true, &["10.0.0.0/8"], "accept 192.168.0.0/16");
+            assert!(filter.check("1.1.1.1:443"));
+            assert!(!filter.check("10.1.2.3:443"));
+            assert!(!filter.check("192.168.1.1:80"));
+            assert!(!filter.check("[::1]:80"));
+        }
+
+        #[test]
+        fn are_allowed_if_accepted_without_being_on_the_allowed_list() {
+            let mut filter = setup(false, &[], "accept *.nymtech.net:443");
+            assert!(filter.check("foomp.nymtech.net:443"));
+            assert!(!filter.check("foomp.nymtech.net:80"));
+        }
+
+        #[test]
+        fn are_not_appended_to_unknown_hosts_list_if_explicitly_rejected() {
+            let mut filter = setup(false, &[], "reject rejected.com");
+            filter.check("rejected.com:443");
+            filter.check("unknown.com:443");
+            assert!(!filter.unknown_hosts.domains.contains("rejected.com"));
+            assert!(filter.unknown_hosts.domains.contains("unknown.com"));
+        }
+
+        #[test]
+        fn see_the_reloaded_rules() {
+            let mut filter = setup(false, &[], "accept nymtech.net");
+            assert!(filter.check("nymtech.net:443"));
+
+            HostsStore::append(&filter.allowed_hosts.storefile, "edwardsnowden.com");
+            let reloaded = ExitPolicy::new(ExitPolicy::parse_rules("").unwrap(), true);
+            filter.reload(reloaded).unwrap();
+
+            assert!(!filter.check("nymtech.net:443"));
+            assert!(filter.check("edwardsnowden.com:443"));
+        }
+    }
+
     fn random_string() -> String {
         format!("{:?}", rand::random::<u32>())
     }
@@ -520,15 +659,13 @@ mod tests {
         ["/tmp/nym-tests"].iter().collect()
     }
 
-    fn create_test_storefile() -> (PathBuf, PathBuf, PathBuf) {
-        let base_dir = test_base_dir();
-        let filename = PathBuf::from(format!("hosts-store-{}.list", random_string()));
-        let dirpath = base_dir.join("service-providers").join("network-requester");
+    fn create_test_storefile() -> PathBuf {
+        let dirpath = test_base_dir();
         fs::create_dir_all(&dirpath)
             .unwrap_or_else(|_| panic!("could not create storage directory at {:?}", dirpath));
-        let storefile = dirpath.join(&filename);
+        let storefile = dirpath.join(format!("hosts-store-{}.list", random_string()));
         File::create(&storefile).unwrap();
-        (storefile, base_dir, filename)
+        storefile
     }
 
     #[cfg(test)]
@@ -537,7 +674,7 @@ mod tests {
 
         #[test]
         fn loads_its_host_list_from_storefile() {
-            let (storefile, base_dir, filename) = create_test_storefile();
+            let storefile = create_test_storefile();
             HostsStore::append(&storefile, "nymtech.net");
             HostsStore::append(&storefile, "edwardsnowden.com");
             HostsStore::append(&storefile, "1.2.3.4");
@@ -545,7 +682,7 @@ mod tests {
             HostsStore::append(&storefile, "1:2:3::");
             HostsStore::append(&storefile, "5:6:7::/48");
 
-            let host_store = HostsStore::new(base_dir, filename);
+            let host_store = HostsStore::new(storefile);
             assert!(host_store.domains.contains("nymtech.net"));
             assert!(host_store.domains.contains("edwardsnowden.com"));
 
@@ -0,0 +1,288 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::config::template::config_template;
+use client_core::config::Config as BaseConfig;
+pub use client_core::config::MISSING_VALUE;
+use config::NymConfig;
+use nymsphinx::addressing::clients::Recipient;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+mod template;
+
+const DEFAULT_ALLOWED_LIST_FILENAME: &str = "allowed.list";
+const DEFAULT_UNKNOWN_LIST_FILENAME: &str = "unknown.list";
+const DEFAULT_EXIT_POLICY_FILENAME: &str = "exit.policy";
+
+// if nothing was sent nor received on an udp association in this time, we assume the client
+// is no longer interested in it
+const DEFAULT_UDP_ASSOCIATION_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+const DEFAULT_MAX_CONCURRENT_CONNECTIONS: usize = 1000;
+const DEFAULT_MAX_CONNECTIONS_PER_SENDER: usize = 100;
+const DEFAULT_MAX_BYTES_PER_SENDER: u64 = 1024 * 1024 * 1024;
+const DEFAULT_BANDWIDTH_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(flatten)]
+    base: BaseConfig<Config>,
+
+    network_requester: NetworkRequester,
+
+    #[serde(default)]
+    limits: Limits,
+}
+
+impl NymConfig for Config {
+    fn template() -> &'static str {
+        config_template()
+    }
+
+    fn default_root_directory() -> PathBuf {
+        dirs::home_dir()
+            .expect("Failed to evaluate $HOME value")
+            .join(".nym")
+            .join("service-providers")
+            .join("network-requester")
+    }
+
+    fn root_directory(&self) -> PathBuf {
+        self.base.get_nym_root_directory()
+    }
+
+    fn config_directory(&self) -> PathBuf {
+        self.root_directory()
+            .join(self.base.get_id())
+            .join("config")
+    }
+
+    fn data_directory(&self) -> PathBuf {
+        self.root_directory().join(self.base.get_id()).join("data")
+    }
+}
+
+impl Config {
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        let mut base = BaseConfig::new(id);
+        base.set_custom_version(env!("CARGO_PKG_VERSION"));
+
+        Config {
+            base,
+            network_requester: Default::default(),
+            limits: Default::default(),
+        }
+    }
+
+    pub fn with_open_proxy(mut self, open_proxy: bool) -> Self {
+        self.network_requester.open_proxy = open_proxy;
+        self
+    }
+
+    pub fn with_enabled_statistics(mut self, enabled_statistics: bool) -> Self {
+        self.network_requester.enabled_statistics = enabled_statistics;
+        self
+    }
+
+    pub fn with_statistics_recipient(mut self, statistics_recipient: String) -> Self {
+        self.network_requester.statistics_recipient = statistics_recipient;
+        self
+    }
+
+    // getters
+    pub fn get_config_file_save_location(&self) -> PathBuf {
+        self.config_directory().join(Self::config_file_name())
+    }
+
+    pub fn get_base(&self) -> &BaseConfig<Self> {
+        &self.base
+    }
+
+    pub fn get_base_mut(&mut self) -> &mut BaseConfig<Self> {
+        &mut self.base
+    }
+
+    pub fn get_open_proxy(&self) -> bool {
+        self.network_requester.open_proxy
+    }
+
+    pub fn get_enabled_statistics(&self) -> bool {
+        self.network_requester.enabled_statistics
+    }
+
+    pub fn get_statistics_recipient(&self) -> Option<Recipient> {
+        if self.network_requester.statistics_recipient.is_empty() {
+            None
+        } else {
+            Some(
+                Recipient::try_from_base58_string(&self.network_requester.statistics_recipient)
+                    .expect("malformed statistics recipient address"),
+            )
+        }
+    }
+
+    pub fn get_allowed_list_location(&self) -> PathBuf {
+        self.network_requester.allowed_list_location.clone()
+    }
+
+    pub fn get_unknown_list_location(&self) -> PathBuf {
+        self.network_requester.unknown_list_location.clone()
+    }
+
+    pub fn get_exit_policy_location(&self) -> PathBuf {
+        self.network_requester.exit_policy_location.clone()
+    }
+
+    pub fn get_reject_non_public_networks(&self) -> bool {
+        self.network_requester.reject_non_public_networks
+    }
+
+    pub fn get_udp_association_idle_timeout(&self) -> Duration {
+        self.limits.udp_association_idle_timeout
+    }
+
+    pub fn get_max_concurrent_connections(&self) -> usize {
+        self.limits.max_concurrent_connections
+    }
+
+    pub fn get_max_connections_per_sender(&self) -> usize {
+        self.limits.max_connections_per_sender
+    }
+
+    pub fn get_max_bytes_per_sender(&self) -> u64 {
+        self.limits.max_bytes_per_sender
+    }
+
+    pub fn get_bandwidth_window(&self) -> Duration {
+        self.limits.bandwidth_window
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkRequester {
+    /// Specifies whether this network requester is going to run in 'open-proxy' mode,
+    /// i.e. proxy requests to any host, ignoring the allowed list.
+    open_proxy: bool,
+
+    /// Specifies whether this network requester is going to send anonymized statistics
+    /// about the proxied traffic to a statistics aggregator.
+    enabled_statistics: bool,
+
+    /// Mix address of the statistics aggregator. If empty, the default Nym aggregator is used.
+    statistics_recipient: String,
+
+    /// Path to the file containing the hosts this network requester is allowed to connect to.
+    allowed_list_location: PathBuf,
+
+    /// Path to the file into which requests to hosts not on the allowed list are logged.
+    unknown_list_location: PathBuf,
+
+    /// Path to the file containing the exit policy, i.e. the rules accepting or rejecting
+    /// requests to particular hosts and ports. The rejections apply even in 'open-proxy' mode.
+    exit_policy_location: PathBuf,
+
+    /// Specifies whether requests to private, loopback and other non-publicly routable
+    /// addresses are always rejected, regardless of the exit policy and the allowed list.
+    reject_non_public_networks: bool,
+}
+
+impl Default for NetworkRequester {
+    fn default() -> Self {
+        // note: the lists are shared by all network requesters running on this machine
+        NetworkRequester {
+            open_proxy: false,
+            enabled_statistics: false,
+            statistics_recipient: "".into(),
+            allowed_list_location: Config::default_root_directory()
+                .join(DEFAULT_ALLOWED_LIST_FILENAME),
+            unknown_list_location: Config::default_root_directory()
+                .join(DEFAULT_UNKNOWN_LIST_FILENAME),
+            exit_policy_location: Config::default_root_directory()
+                .join(DEFAULT_EXIT_POLICY_FILENAME),
+            reject_non_public_networks: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Limits {
+    /// Duration after which an idle udp association is closed.
+    #[serde(with = "humantime_serde")]
+    udp_association_idle_timeout: Duration,
+
+    /// Maximum number of connections handled at the same time across all senders.
+    /// Setting it to 0 disables the limit.
+    max_concurrent_connections: usize,
+
+    /// Maximum number of connections a single sender can have open at the same time.
+    /// Setting it to 0 disables the limit.
+    max_connections_per_sender: usize,
+
+    /// Maximum number of bytes a single sender can send and receive within `bandwidth_window`.
+    /// Setting it to 0 disables the limit.
+    max_bytes_per_sender: u64,
+
+    /// Duration of the window within which the bandwidth used by each sender is accounted for.
+    #[serde(with = "humantime_serde")]
+    bandwidth_window: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            udp_association_idle_timeout: DEFAULT_UDP_ASSOCIATION_IDLE_TIMEOUT,
+            max_concurrent_connections: DEFAULT_MAX_CONCURRENT_CONNECTIONS,
+            max_connections_per_sender: DEFAULT_MAX_CONNECTIONS_PER_SENDER,
+            max_bytes_per_sender: DEFAULT_MAX_BYTES_PER_SENDER,
+            bandwidth_window: DEFAULT_BANDWIDTH_WINDOW,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn save_and_load(config: &Config) -> String {
+        let config_dir = tempfile::tempdir().unwrap();
+        let location = config_dir.path().join("config.toml");
+        config.save_to_file(Some(location.clone())).unwrap();
+        fs::read_to_string(location).unwrap()
+    }
+
+    #[test]
+    fn config_is_unchanged_after_saving_it_from_the_template() {
+        let config = Config::new("foo")
+            .with_open_proxy(true)
+            .with_enabled_statistics(true)
+            .with_statistics_recipient("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f".into());
+
+        let loaded: Config = toml::from_str(&save_and_load(&config)).unwrap();
+        assert_eq!(config, loaded);
+    }
+
+    #[test]
+    fn missing_limits_section_falls_back_to_the_defaults() {
+        let config = Config::new("foo");
+        let saved = save_and_load(&config);
+
+        // i.e. a config created before the limits had been introduced
+        let (before_limits, limits_onwards) = saved
+            .split_once("##### limits configuration options #####")
+            .unwrap();
+        let (_, after_limits) = limits_onwards
+            .split_once("##### metrics configuration options #####")
+            .unwrap();
+        let without_limits = format!("{}{}", before_limits, after_limits);
+
+        let loaded: Config = toml::from_str(&without_limits).unwrap();
+        assert_eq!(Limits::default(), loaded.limits);
+        assert_eq!(config, loaded);
+    }
+}
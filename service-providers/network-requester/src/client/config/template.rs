@@ -0,0 +1,190 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+pub(crate) fn config_template() -> &'static str {
+    // While using normal toml marshalling would have been way simpler with less overhead,
+    // I think it's useful to have comments attached to the saved config file to explain behaviour of
+    // particular fields.
+    // Note: any changes to the template must be reflected in the appropriate structs.
+    r#"
+# This is a TOML config file.
+# For more information, see https://github.com/toml-lang/toml
+
+##### main base client config options #####
+
+[client]
+# Version of the client for which this configuration was created.
+version = '{{ client.version }}'
+
+# Human readable ID of this particular client.
+id = '{{ client.id }}'
+
+# Indicates whether this client is running in a disabled credentials mode, thus attempting
+# to claim bandwidth without presenting bandwidth credentials.
+disabled_credentials_mode = {{ client.disabled_credentials_mode }}
+
+# Addresses to APIs running on validator from which the client gets the view of the network.
+validator_api_urls = [
+    {{#each client.validator_api_urls }}
+        '{{this}}',
+    {{/each}}
+]
+
+# Path to file containing private identity key.
+private_identity_key_file = '{{ client.private_identity_key_file }}'
+
+# Path to file containing public identity key.
+public_identity_key_file = '{{ client.public_identity_key_file }}'
+
+# Path to file containing private encryption key.
+private_encryption_key_file = '{{ client.private_encryption_key_file }}'
+
+# Path to file containing public encryption key.
+public_encryption_key_file = '{{ client.public_encryption_key_file }}'
+
+# Full path to file containing reply encryption keys of all reply-SURBs we have ever
+# sent but not received back.
+reply_encryption_key_store_path = '{{ client.reply_encryption_key_store_path }}'
+
+# Path to the database containing bandwidth credentials
+database_path = '{{ client.database_path }}'
+
+# Ethereum private key.
+eth_private_key = '{{ client.eth_private_key }}'
+
+# Addess to an Ethereum full node.
+eth_endpoint = '{{ client.eth_endpoint }}'
+
+##### additional client config options #####
+
+# A gateway specific, optional, base58 stringified shared key used for
+# communication with particular gateway.
+gateway_shared_key_file = '{{ client.gateway_shared_key_file }}'
+
+# Path to the directory containing shared keys derived with each of the backup gateways.
+backup_gateway_shared_keys_directory = '{{ client.backup_gateway_shared_keys_directory }}'
+
+# Path to file containing key used for encrypting and decrypting the content of an
+# acknowledgement so that nobody besides the client knows which packet it refers to.
+ack_key_file = '{{ client.ack_key_file }}'
+
+##### advanced configuration options #####
+
+# Absolute path to the home Nym Clients directory.
+nym_root_directory = '{{ client.nym_root_directory }}'
+
+[client.gateway_endpoint]
+# ID of the gateway from which the client should be fetching messages.
+gateway_id = '{{ client.gateway_endpoint.gateway_id }}'
+
+# Address of the gateway owner to which the client should send messages.
+gateway_owner = '{{ client.gateway_endpoint.gateway_owner }}'
+
+# Address of the gateway listener to which all client requests should be sent.
+gateway_listener = '{{ client.gateway_endpoint.gateway_listener }}'
+
+# Gateways the client has also registered with and is going to switch over to, in order,
+# if the gateway above becomes persistently unreachable.
+{{#each client.backup_gateway_endpoints }}
+[[client.backup_gateway_endpoints]]
+gateway_id = '{{ this.gateway_id }}'
+gateway_owner = '{{ this.gateway_owner }}'
+gateway_listener = '{{ this.gateway_listener }}'
+{{/each}}
+
+
+##### network requester config options #####
+
+[network_requester]
+
+# Specifies whether this network requester is going to run in 'open-proxy' mode, i.e. proxy
+# requests to any host, ignoring the allowed list. ANYONE WITH YOUR CLIENT ADDRESS IS THEN
+# GOING TO BE ABLE TO MAKE REQUESTS FROM YOUR MACHINE.
+open_proxy = {{ network_requester.open_proxy }}
+
+# Specifies whether this network requester is going to send anonymized statistics about
+# the proxied traffic to a statistics aggregator.
+enabled_statistics = {{ network_requester.enabled_statistics }}
+
+# Mix address of the statistics aggregator. If empty, the default Nym aggregator is used.
+statistics_recipient = '{{ network_requester.statistics_recipient }}'
+
+# Path to the file containing the hosts this network requester is allowed to connect to.
+allowed_list_location = '{{ network_requester.allowed_list_location }}'
+
+# Path to the file into which requests to hosts not on the allowed list are logged.
+unknown_list_location = '{{ network_requester.unknown_list_location }}'
+
+# Path to the file containing the exit policy, i.e. the rules accepting or rejecting
+# requests to particular hosts and ports. The rejections apply even in 'open-proxy' mode.
+# It's reloaded, alongside the allowed list, whenever the process receives SIGHUP.
+exit_policy_location = '{{ network_requester.exit_policy_location }}'
+
+# Specifies whether requests to private, loopback and other non-publicly routable
+# addresses are always rejected, regardless of the exit policy and the allowed list.
+reject_non_public_networks = {{ network_requester.reject_non_public_networks }}
+
+
+##### limits configuration options #####
+
+[limits]
+
+# Duration after which an idle udp association is closed.
+udp_association_idle_timeout = '{{ limits.udp_association_idle_timeout }}'
+
+# Maximum number of connections handled at the same time across all senders.
+# Setting it to 0 disables the limit.
+max_concurrent_connections = {{ limits.max_concurrent_connections }}
+
+# Maximum number of connections a single sender can have open at the same time.
+# Setting it to 0 disables the limit.
+max_connections_per_sender = {{ limits.max_connections_per_sender }}
+
+# Maximum number of bytes a single sender can send and receive within `bandwidth_window`.
+# Setting it to 0 disables the limit.
+max_bytes_per_sender = {{ limits.max_bytes_per_sender }}
+
+# Duration of the window within which the bandwidth used by each sender is accounted for.
+bandwidth_window = '{{ limits.bandwidth_window }}'
+
+
+##### metrics configuration options #####
+
+[metrics]
+
+# Indicates whether the client should expose its metrics in the Prometheus text format
+# under the `/metrics` endpoint.
+enabled = {{ metrics.enabled }}
+
+# Socket address on which the metrics endpoint is going to be listening.
+listening_address = '{{ metrics.listening_address }}'
+
+##### logging configuration options #####
+
+[logging]
+
+# TODO
+
+
+##### debug configuration options #####
+# The following options should not be modified unless you know EXACTLY what you are doing
+# as if set incorrectly, they may impact your anonymity.
+
+[debug]
+
+average_packet_delay = '{{ debug.average_packet_delay }}'
+average_ack_delay = '{{ debug.average_ack_delay }}'
+loop_cover_traffic_average_delay = '{{ debug.loop_cover_traffic_average_delay }}'
+message_sending_average_delay = '{{ debug.message_sending_average_delay }}'
+
+# Maximum number of times a single packet is going to be retransmitted before the client
+# gives up on delivering the message it belongs to. If not set, only messages whose delivery
+# is being tracked are given up on and all other packets are retransmitted until acknowledged.
+# maximum_retransmissions = 10
+
+# Number of mix hops each packet is going to take before reaching the recipient's gateway.
+# More hops improve anonymity at the cost of latency. It must be between 1 and 3.
+num_mix_hops = {{ debug.num_mix_hops }}
+
+"#
+}
@@ -0,0 +1,187 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    client::{config::Config, new_service_provider, NymClient},
+    commands::{override_config, OverrideConfig},
+};
+
+use clap::Args;
+use config::NymConfig;
+use log::*;
+use nymsphinx::params::MAX_NUM_MIX_HOPS;
+use version_checker::is_minor_version_compatible;
+
+#[derive(Args, Clone)]
+pub(crate) struct Run {
+    /// Id of the network requester we want to run.
+    #[clap(long)]
+    id: String,
+
+    /// Comma separated list of rest endpoints of the validators
+    #[clap(long)]
+    validators: Option<String>,
+
+    /// Specifies whether this network requester should run in 'open-proxy' mode
+    #[clap(long)]
+    open_proxy: bool,
+
+    /// Enable service anonymized statistics that get sent to a statistics aggregator server
+    #[clap(long)]
+    enable_statistics: bool,
+
+    /// Mixnet client address where a statistics aggregator is running. The default value is
+    /// a Nym aggregator client
+    #[clap(long, requires = "enable-statistics")]
+    statistics_recipient: Option<String>,
+
+    /// Port of the websocket of a separately running native client to use instead of the
+    /// embedded one. DEPRECATED: this mode is going to be removed in a future release
+    #[clap(long)]
+    websocket_port: Option<u16>,
+
+    /// Set this client to work in a enabled credentials mode that would attempt to use gateway
+    /// with bandwidth credential requirement. If this value is set, --eth-endpoint and
+    /// --eth-private-key don't need to be set.
+    #[cfg(all(feature = "eth", not(feature = "coconut")))]
+    #[clap(long, conflicts_with_all = &["eth-endpoint", "eth-private-key"])]
+    enabled_credentials_mode: bool,
+
+    /// URL of an Ethereum full node that we want to use for getting bandwidth tokens from ERC20
+    /// tokens. If you don't want to set this value, use --enabled-credentials-mode instead
+    #[cfg(all(feature = "eth", not(feature = "coconut")))]
+    #[clap(long)]
+    eth_endpoint: Option<String>,
+
+    /// Ethereum private key used for obtaining bandwidth tokens from ERC20 tokens. If you don't
+    /// want to set this value, use --enabled-credentials-mode instead
+    #[cfg(all(feature = "eth", not(feature = "coconut")))]
+    #[clap(long)]
+    eth_private_key: Option<String>,
+}
+
+impl From<Run> for OverrideConfig {
+    fn from(run_config: Run) -> Self {
+        OverrideConfig {
+            validators: run_config.validators,
+            open_proxy: run_config.open_proxy,
+            enable_statistics: run_config.enable_statistics,
+            statistics_recipient: run_config.statistics_recipient,
+
+            #[cfg(all(feature = "eth", not(feature = "coconut")))]
+            enabled_credentials_mode: run_config.enabled_credentials_mode,
+
+            #[cfg(all(feature = "eth", not(feature = "coconut")))]
+            eth_private_key: run_config.eth_private_key,
+
+            #[cfg(all(feature = "eth", not(feature = "coconut")))]
+            eth_endpoint: run_config.eth_endpoint,
+        }
+    }
+}
+
+// this only checks compatibility between config the binary. It does not take into consideration
+// network version. It might do so in the future.
+fn version_check(cfg: &Config) -> bool {
+    let binary_version = env!("CARGO_PKG_VERSION");
+    let config_version = cfg.get_base().get_version();
+    if binary_version == config_version {
+        true
+    } else {
+        warn!(
+            "The network requester binary has different version than what is specified in config file! {} and {}",
+            binary_version, config_version
+        );
+        if is_minor_version_compatible(binary_version, config_version) {
+            info!("but they are still semver compatible. However, consider running the `upgrade` command");
+            true
+        } else {
+            error!("and they are semver incompatible! - please run the `upgrade` command before attempting `run` again");
+            false
+        }
+    }
+}
+
+pub(crate) async fn execute(args: &Run) {
+    let id = &args.id;
+
+    let mut config = match Config::load_from_file(Some(id)) {
+        Ok(cfg) => cfg,
+        // the native client has its own keys, so our config is optional in that case
+        Err(err) if args.websocket_port.is_some() => {
+            warn!(
+                "Failed to load config for {}, the default network requester settings are going to be used. (Error was: {})",
+                id, err
+            );
+            Config::new(id)
+        }
+        Err(err) => {
+            error!("Failed to load config for {}. Are you sure you have run `init` before? (Error was: {})", id, err);
+            return;
+        }
+    };
+
+    let override_config_fields = OverrideConfig::from(args.clone());
+    config = override_config(config, override_config_fields);
+
+    if !version_check(&config) {
+        error!("failed the local version check");
+        return;
+    }
+
+    if !config.get_base().has_valid_num_mix_hops() {
+        error!(
+            "the configured number of mix hops ({}) is invalid - it must be between 1 and {}",
+            config.get_base().get_num_mix_hops(),
+            MAX_NUM_MIX_HOPS
+        );
+        return;
+    }
+
+    if let Some(websocket_port) = args.websocket_port {
+        warn!(
+            "Running with a separate native client is deprecated and is going to be removed in a future release. \
+            Please run `init` and restart without the --websocket-port flag to use the embedded client instead."
+        );
+        let websocket_uri = format!("ws://localhost:{}", websocket_port);
+        new_service_provider(&config)
+            .run_with_native_client(&websocket_uri)
+            .await;
+        return;
+    }
+
+    NymClient::new(config).run_forever().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Cli, Commands};
+    use clap::Parser;
+
+    fn parse_run(args: &[&str]) -> Run {
+        let args = ["nym-network-requester", "run"].iter().chain(args);
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Run(run) => run,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn embedded_client_is_used_by_default() {
+        assert!(parse_run(&["--id", "foo"]).websocket_port.is_none());
+        assert_eq!(
+            Some(1977),
+            parse_run(&["--id", "foo", "--websocket-port", "1977"]).websocket_port
+        );
+    }
+
+    #[test]
+    fn config_of_incompatible_version_is_rejected() {
+        let mut config = Config::new("foo");
+        assert!(version_check(&config));
+
+        config.get_base_mut().set_custom_version("0.1.0");
+        assert!(!version_check(&config));
+    }
+}
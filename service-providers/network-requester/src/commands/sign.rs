@@ -0,0 +1,54 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::config::Config;
+use clap::Args;
+use client_core::client::key_manager::KeyManager;
+use client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use config::NymConfig;
+use log::*;
+
+#[derive(Args, Clone)]
+pub(crate) struct Sign {
+    /// Id of the network requester we want to sign with.
+    #[clap(long)]
+    id: String,
+
+    /// Blockchain address of the account announcing this network requester in the service
+    /// provider directory
+    #[clap(long)]
+    address: String,
+}
+
+pub(crate) fn execute(args: &Sign) {
+    let config = match Config::load_from_file(Some(&args.id)) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            error!(
+                "Failed to load config for {}. Are you sure you have run `init` before? (Error was: {})",
+                args.id, err
+            );
+            return;
+        }
+    };
+
+    let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
+    let key_manager = match KeyManager::load_keys(&pathfinder) {
+        Ok(key_manager) => key_manager,
+        Err(err) => {
+            error!("Failed to load the keys of {} - {}", args.id, err);
+            return;
+        }
+    };
+
+    let address = args.address.trim();
+    let signature = key_manager
+        .identity_keypair()
+        .private_key()
+        .sign_text(address);
+
+    println!(
+        "The base58-encoded signature on '{}' is: {}",
+        address, signature
+    );
+}
@@ -1,14 +1,38 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use futures::channel::mpsc;
-use nymsphinx::addressing::clients::Recipient;
+use crate::exit_policy::SharedExitPolicy;
+use crate::reply::{MixnetMessageSender, ReturnAddress};
 use proxy_helpers::connection_controller::ConnectionReceiver;
 use proxy_helpers::proxy_runner::ProxyRunner;
-use socks5_requests::{ConnectionId, Message as Socks5Message, RemoteAddress, Response};
+use socks5_requests::{
+    ConnectStatus, ConnectionId, Message as Socks5Message, RemoteAddress, Response,
+};
 use std::io;
 use tokio::net::TcpStream;
 
+#[derive(Debug)]
+pub(crate) enum ConnectionError {
+    /// The remote address could not have been resolved.
+    AddressResolution(io::Error),
+
+    /// All of the resolved addresses are rejected by the exit policy.
+    NotAllowed,
+
+    /// We failed to establish the connection to any of the resolved addresses.
+    Connection(io::Error),
+}
+
+impl ConnectionError {
+    pub(crate) fn connect_status(&self) -> ConnectStatus {
+        match self {
+            ConnectionError::AddressResolution(_) => ConnectStatus::HostUnreachable,
+            ConnectionError::NotAllowed => ConnectStatus::NotAllowed,
+            ConnectionError::Connection(err) => err.into(),
+        }
+    }
+}
+
 /// A TCP connection between the Socks5 service provider, which makes
 /// outbound requests on behalf of users and returns the responses through
 /// the mixnet.
@@ -17,16 +41,37 @@ pub(crate) struct Connection {
     id: ConnectionId,
     address: RemoteAddress,
     conn: Option<TcpStream>,
-    return_address: Recipient,
+    return_address: ReturnAddress,
 }
 
 impl Connection {
     pub(crate) async fn new(
         id: ConnectionId,
         address: RemoteAddress,
-        return_address: Recipient,
-    ) -> io::Result<Self> {
-        let conn = TcpStream::connect(&address).await?;
+        return_address: ReturnAddress,
+        exit_policy: &SharedExitPolicy,
+    ) -> Result<Self, ConnectionError> {
+        let mut resolved: Vec<_> = tokio::net::lookup_host(&address)
+            .await
+            .map_err(ConnectionError::AddressResolution)?
+            .collect();
+
+        if resolved.is_empty() {
+            return Err(ConnectionError::AddressResolution(io::Error::new(
+                io::ErrorKind::NotFound,
+                "the address did not resolve to anything",
+            )));
+        }
+
+        // the domain might have been allowed, but it could resolve to, say, a private address
+        resolved.retain(|address| exit_policy.allows_address(*address));
+        if resolved.is_empty() {
+            return Err(ConnectionError::NotAllowed);
+        }
+
+        let conn = TcpStream::connect(resolved.as_slice())
+            .await
+            .map_err(ConnectionError::Connection)?;
 
         Ok(Connection {
             id,
@@ -39,7 +84,7 @@ impl Connection {
     pub(crate) async fn run_proxy(
         &mut self,
         mix_receiver: ConnectionReceiver,
-        mix_sender: mpsc::UnboundedSender<(Socks5Message, Recipient)>,
+        mix_sender: MixnetMessageSender,
     ) {
         let stream = self.conn.take().unwrap();
         let remote_source_address = "???".to_string(); // we don't know ip address of requester
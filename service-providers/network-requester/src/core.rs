@@ -1,73 +1,138 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::allowed_hosts::{HostsStore, OutboundRequestFilter};
+use crate::allowed_hosts::{HostsStore, OutboundRequestFilter, RequestFilterConfig};
 use crate::connection::Connection;
+use crate::exit_policy::{ExitPolicy, SharedExitPolicy};
+use crate::limits::{ConnectionPermit, RequestLimiter, RequestLimits};
+use crate::reply::{MixnetMessageReceiver, MixnetMessageSender, ResponseSink, ReturnAddress};
 use crate::statistics::ServiceStatisticsCollector;
+use crate::udp::{DatagramSender, UdpAssociation};
 use crate::websocket;
-use crate::websocket::TSWebsocketStream;
+use crate::websocket::{TSWebsocketStream, WebsocketConnectionError};
+use client_core::client::inbound_messages::InputMessageSender;
+use client_core::client::received_buffer::{
+    ReceivedBufferMessage, ReceivedBufferRequestSender, ReconstructedMessagesReceiver,
+};
 use futures::channel::mpsc;
-use futures::stream::{SplitSink, SplitStream};
-use futures::{SinkExt, StreamExt};
+use futures::stream::SplitStream;
+use futures::StreamExt;
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
+use nymsphinx::anonymous_replies::AnonymousSenderTag;
 use nymsphinx::receiver::ReconstructedMessage;
 use proxy_helpers::connection_controller::{Controller, ControllerCommand, ControllerSender};
-use socks5_requests::{ConnectionId, Message as Socks5Message, Request, Response};
+use socks5_requests::{
+    ConnectResponse, ConnectStatus, ConnectionId, DatagramRequest, Message as Socks5Message,
+    Request, Response,
+};
 use statistics_common::collector::StatisticsSender;
-use std::path::PathBuf;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio_tungstenite::tungstenite::protocol::Message;
-use websocket::WebsocketConnectionError;
-use websocket_requests::{requests::ClientRequest, responses::ServerResponse};
+use std::time::Duration;
+use websocket_requests::responses::ServerResponse;
 
 // Since it's an atomic, it's safe to be kept static and shared across threads
 static ACTIVE_PROXIES: AtomicUsize = AtomicUsize::new(0);
 
+/// Requests to reload the outbound policy, i.e. SIGHUP on unix systems.
+struct ReloadSignal {
+    #[cfg(unix)]
+    hangup: tokio::signal::unix::Signal,
+}
+
+impl ReloadSignal {
+    fn new() -> Self {
+        ReloadSignal {
+            #[cfg(unix)]
+            hangup: tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+                .expect("failed to register the SIGHUP handler"),
+        }
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if self.hangup.recv().await.is_some() {
+            return;
+        }
+
+        // there's nothing that could ever trigger the reload
+        futures::future::pending::<()>().await
+    }
+}
+
 pub struct ServiceProvider {
-    listening_address: String,
+    filter_config: RequestFilterConfig,
     outbound_request_filter: OutboundRequestFilter,
-    open_proxy: bool,
+    exit_policy: SharedExitPolicy,
     enable_statistics: bool,
     stats_provider_addr: Option<Recipient>,
+    // connection ids are chosen by the clients, so they are only unique per return address
+    udp_associations: HashMap<(ConnectionId, ReturnAddress), DatagramSender>,
+    udp_association_idle_timeout: Duration,
+    request_limiter: RequestLimiter,
 }
 
 impl ServiceProvider {
     pub fn new(
-        listening_address: String,
-        open_proxy: bool,
+        filter_config: RequestFilterConfig,
         enable_statistics: bool,
         stats_provider_addr: Option<Recipient>,
+        udp_association_idle_timeout: Duration,
+        limits: RequestLimits,
     ) -> ServiceProvider {
-        let allowed_hosts = HostsStore::new(
-            HostsStore::default_base_dir(),
-            PathBuf::from("allowed.list"),
-        );
+        if filter_config.open_proxy {
+            println!("\n\nYOU HAVE STARTED IN 'OPEN PROXY' MODE. ANYONE WITH YOUR CLIENT ADDRESS CAN MAKE REQUESTS FROM YOUR MACHINE. PLEASE QUIT IF YOU DON'T UNDERSTAND WHAT YOU'RE DOING.\n\n");
+        }
 
-        let unknown_hosts = HostsStore::new(
-            HostsStore::default_base_dir(),
-            PathBuf::from("unknown.list"),
-        );
+        if enable_statistics {
+            println!("\n\nTHE NETWORK REQUESTER STATISTICS ARE ENABLED. IT WILL COLLECT AND SEND ANONYMIZED STATISTICS TO A CENTRAL SERVER. PLEASE QUIT IF YOU DON'T WANT THIS TO HAPPEN AND DISABLE THEM IN THE CONFIG FILE.\n\n");
+        }
 
-        let outbound_request_filter = OutboundRequestFilter::new(allowed_hosts, unknown_hosts);
+        if !filter_config.reject_non_public_networks {
+            println!("\n\nREQUESTS TO PRIVATE AND LOOPBACK ADDRESSES ARE NOT REJECTED BY DEFAULT. THEY MIGHT GIVE ACCESS TO THE NETWORK YOUR MACHINE IS IN. PLEASE QUIT IF YOU DON'T UNDERSTAND WHAT YOU'RE DOING.\n\n");
+        }
+
+        let allowed_hosts = HostsStore::new(filter_config.allowed_list_location.clone());
+        let unknown_hosts = HostsStore::new(filter_config.unknown_list_location.clone());
+        let exit_policy = ExitPolicy::load(
+            &filter_config.exit_policy_location,
+            filter_config.reject_non_public_networks,
+        )
+        .unwrap_or_else(|err| panic!("Could not load the exit policy - {}", err));
+        let exit_policy = SharedExitPolicy::new(exit_policy);
+
+        let outbound_request_filter = OutboundRequestFilter::new(
+            filter_config.open_proxy,
+            allowed_hosts,
+            unknown_hosts,
+            exit_policy.clone(),
+        );
         ServiceProvider {
-            listening_address,
+            filter_config,
             outbound_request_filter,
-            open_proxy,
+            exit_policy,
             enable_statistics,
             stats_provider_addr,
+            udp_associations: HashMap::new(),
+            udp_association_idle_timeout,
+            request_limiter: RequestLimiter::new(limits),
         }
     }
 
     /// Listens for any messages from `mix_reader` that should be written back to the mix network
-    /// via the `websocket_writer`.
+    /// via the `response_sink` of our client.
     async fn mixnet_response_listener(
-        mut websocket_writer: SplitSink<TSWebsocketStream, Message>,
-        mut mix_reader: mpsc::UnboundedReceiver<(Socks5Message, Recipient)>,
+        mut response_sink: ResponseSink,
+        mut mix_reader: MixnetMessageReceiver,
         stats_collector: Option<ServiceStatisticsCollector>,
+        request_limiter: RequestLimiter,
     ) {
-        // TODO: wire SURBs in here once they're available
         while let Some((msg, return_address)) = mix_reader.next().await {
+            // responses are always sent back, but they count towards the bandwidth of the sender
+            // so that any of its further requests would get rejected once it's over the limit
+            let _ = request_limiter.consume_bandwidth(return_address, msg.size());
+
             if let Some(stats_collector) = stats_collector.as_ref() {
                 if let Some(remote_addr) = stats_collector
                     .connected_services
@@ -82,15 +147,7 @@ impl ServiceProvider {
                         .processed(remote_addr, msg.size() as u32);
                 }
             }
-            // make 'request' to native-websocket client
-            let response_message = ClientRequest::Send {
-                recipient: return_address,
-                message: msg.into_bytes(),
-                with_reply_surb: false,
-            };
-
-            let message = Message::Binary(response_message.serialize());
-            websocket_writer.send(message).await.unwrap();
+            response_sink.send(msg.into_bytes(), return_address).await;
         }
     }
 
@@ -119,6 +176,13 @@ impl ServiceProvider {
                 ServerResponse::Error(err) => {
                     panic!("received error from native client! - {}", err)
                 }
+                ServerResponse::AddressChanged(address) => {
+                    warn!(
+                        "the native client has switched over to a different gateway. The new address of this service provider is: {}",
+                        address
+                    );
+                    continue;
+                }
                 _ => unimplemented!("probably should never be reached?"),
             };
             return Some(received);
@@ -129,30 +193,57 @@ impl ServiceProvider {
     async fn start_proxy(
         conn_id: ConnectionId,
         remote_addr: String,
-        return_address: Recipient,
+        return_address: ReturnAddress,
         controller_sender: ControllerSender,
-        mix_input_sender: mpsc::UnboundedSender<(Socks5Message, Recipient)>,
+        mix_input_sender: MixnetMessageSender,
+        // keeps the connection counted towards the limits for as long as the proxy is running
+        _permit: ConnectionPermit,
+        exit_policy: SharedExitPolicy,
     ) {
-        let mut conn = match Connection::new(conn_id, remote_addr.clone(), return_address).await {
-            Ok(conn) => conn,
-            Err(err) => {
-                error!(
-                    "error while connecting to {:?} ! - {:?}",
-                    remote_addr.clone(),
-                    err
-                );
+        let mut conn =
+            match Connection::new(conn_id, remote_addr.clone(), return_address, &exit_policy).await
+            {
+                Ok(conn) => conn,
+                Err(err) => {
+                    error!(
+                        "error while connecting to {:?} ! - {:?}",
+                        remote_addr.clone(),
+                        err
+                    );
 
-                // inform the remote that the connection is closed before it even was established
-                mix_input_sender
-                    .unbounded_send((
-                        Socks5Message::Response(Response::new(conn_id, Vec::new(), true)),
-                        return_address,
-                    ))
-                    .unwrap();
+                    // inform the remote why the connection couldn't be established
+                    mix_input_sender
+                        .unbounded_send((
+                            Socks5Message::ConnectResponse(ConnectResponse::new(
+                                conn_id,
+                                err.connect_status(),
+                            )),
+                            return_address,
+                        ))
+                        .unwrap();
+
+                    // and, for the clients not understanding the above, that the connection
+                    // is closed before it even was established
+                    mix_input_sender
+                        .unbounded_send((
+                            Socks5Message::Response(Response::new(conn_id, Vec::new(), true)),
+                            return_address,
+                        ))
+                        .unwrap();
 
-                return;
-            }
-        };
+                    return;
+                }
+            };
+
+        mix_input_sender
+            .unbounded_send((
+                Socks5Message::ConnectResponse(ConnectResponse::new(
+                    conn_id,
+                    ConnectStatus::Success,
+                )),
+                return_address,
+            ))
+            .unwrap();
 
         // Connect implies it's a fresh connection - register it with our controller
         let (mix_sender, mix_receiver) = mpsc::unbounded();
@@ -183,21 +274,65 @@ impl ServiceProvider {
         );
     }
 
-    fn handle_proxy_connect(
+    async fn handle_proxy_connect(
         &mut self,
         controller_sender: &mut ControllerSender,
-        mix_input_sender: &mpsc::UnboundedSender<(Socks5Message, Recipient)>,
+        mix_input_sender: &MixnetMessageSender,
         conn_id: ConnectionId,
         remote_addr: String,
-        return_address: Recipient,
+        return_address: ReturnAddress,
+        stats_collector: Option<ServiceStatisticsCollector>,
     ) {
-        if !self.open_proxy && !self.outbound_request_filter.check(&remote_addr) {
+        if !self.outbound_request_filter.check(&remote_addr) {
             log::info!("Domain {:?} failed filter check", remote_addr);
+            mix_input_sender
+                .unbounded_send((
+                    Socks5Message::ConnectResponse(ConnectResponse::new(
+                        conn_id,
+                        ConnectStatus::NotAllowed,
+                    )),
+                    return_address,
+                ))
+                .unwrap();
             return;
         }
 
+        let permit = match self
+            .request_limiter
+            .try_open_connection(conn_id, return_address)
+        {
+            Ok(permit) => permit,
+            Err(limit) => {
+                info!(
+                    "Rejecting connection to {:?} as the sender has exceeded {}",
+                    remote_addr, limit
+                );
+                if let Some(stats_collector) = stats_collector {
+                    stats_collector.limit_hit(&remote_addr).await;
+                }
+                mix_input_sender
+                    .unbounded_send((
+                        Socks5Message::ConnectResponse(ConnectResponse::new(
+                            conn_id,
+                            ConnectStatus::LimitExceeded,
+                        )),
+                        return_address,
+                    ))
+                    .unwrap();
+                // and, for the clients not understanding the above, that the connection is closed
+                mix_input_sender
+                    .unbounded_send((
+                        Socks5Message::Response(Response::new(conn_id, Vec::new(), true)),
+                        return_address,
+                    ))
+                    .unwrap();
+                return;
+            }
+        };
+
         let controller_sender_clone = controller_sender.clone();
         let mix_input_sender_clone = mix_input_sender.clone();
+        let exit_policy = self.exit_policy.clone();
 
         // and start the proxy for this connection
         tokio::spawn(async move {
@@ -207,6 +342,8 @@ impl ServiceProvider {
                 return_address,
                 controller_sender_clone,
                 mix_input_sender_clone,
+                permit,
+                exit_policy,
             )
             .await
         });
@@ -224,11 +361,73 @@ impl ServiceProvider {
             .unwrap()
     }
 
+    async fn handle_proxy_datagram(
+        &mut self,
+        mix_input_sender: &MixnetMessageSender,
+        req: DatagramRequest,
+        return_address: ReturnAddress,
+        stats_collector: Option<ServiceStatisticsCollector>,
+    ) {
+        if !self.outbound_request_filter.check(&req.remote_addr) {
+            log::info!("Domain {:?} failed filter check", req.remote_addr);
+            return;
+        }
+
+        if let Err(limit) = self
+            .request_limiter
+            .consume_bandwidth(return_address, req.data.len())
+        {
+            // there's no way of telling the client about it, datagrams are unreliable anyway
+            debug!(
+                "Dropping datagram to {:?} as the sender has exceeded {}",
+                req.remote_addr, limit
+            );
+            if let Some(stats_collector) = stats_collector {
+                stats_collector.limit_hit(&req.remote_addr).await;
+            }
+            return;
+        }
+
+        let mut datagram = (req.remote_addr, req.data);
+        let association_key = (req.conn_id, return_address);
+        if let Some(association) = self.udp_associations.get(&association_key) {
+            match association.unbounded_send(datagram) {
+                Ok(_) => return,
+                // the association has finished (most likely due to being idle) - start a new one
+                Err(err) => datagram = err.into_inner(),
+            }
+        }
+
+        match UdpAssociation::start(
+            req.conn_id,
+            return_address,
+            self.udp_association_idle_timeout,
+            self.exit_policy.clone(),
+            mix_input_sender.clone(),
+        )
+        .await
+        {
+            Ok(association) => {
+                association.unbounded_send(datagram).unwrap();
+                self.udp_associations.insert(association_key, association);
+            }
+            Err(err) => error!(
+                "failed to start udp association {} - {:?}",
+                req.conn_id, err
+            ),
+        }
+
+        // get rid of all associations that have already finished
+        self.udp_associations
+            .retain(|_, association| !association.is_closed());
+    }
+
     async fn handle_proxy_message(
         &mut self,
         raw_request: &[u8],
+        sender_tag: Option<AnonymousSenderTag>,
         controller_sender: &mut ControllerSender,
-        mix_input_sender: &mpsc::UnboundedSender<(Socks5Message, Recipient)>,
+        mix_input_sender: &MixnetMessageSender,
         stats_collector: Option<ServiceStatisticsCollector>,
     ) {
         let deserialized_msg = match Socks5Message::try_from_bytes(raw_request) {
@@ -241,7 +440,17 @@ impl ServiceProvider {
         match deserialized_msg {
             Socks5Message::Request(deserialized_request) => match deserialized_request {
                 Request::Connect(req) => {
-                    if let Some(stats_collector) = stats_collector {
+                    let return_address = match ReturnAddress::new(req.return_address, sender_tag) {
+                        Some(return_address) => return_address,
+                        None => {
+                            warn!(
+                                "Connection request {} has neither the return address nor reply SURBs attached",
+                                req.conn_id
+                            );
+                            return;
+                        }
+                    };
+                    if let Some(stats_collector) = stats_collector.as_ref() {
                         stats_collector
                             .connected_services
                             .write()
@@ -253,11 +462,48 @@ impl ServiceProvider {
                         mix_input_sender,
                         req.conn_id,
                         req.remote_addr,
-                        req.return_address,
+                        return_address,
+                        stats_collector,
                     )
+                    .await
                 }
 
                 Request::Send(conn_id, data, closed) => {
+                    if let Some(return_address) = self.request_limiter.sender_of(conn_id) {
+                        if let Err(limit) = self
+                            .request_limiter
+                            .consume_bandwidth(return_address, data.len())
+                        {
+                            info!(
+                                "Closing connection {} as the sender has exceeded {}",
+                                conn_id, limit
+                            );
+                            if let Some(stats_collector) = stats_collector.as_ref() {
+                                if let Some(remote_addr) = stats_collector
+                                    .connected_services
+                                    .read()
+                                    .await
+                                    .get(&conn_id)
+                                {
+                                    stats_collector.limit_hit(remote_addr).await;
+                                }
+                            }
+                            // close the connection on our side and let the client know about it
+                            self.handle_proxy_send(controller_sender, conn_id, Vec::new(), true);
+                            mix_input_sender
+                                .unbounded_send((
+                                    Socks5Message::Response(Response::new(
+                                        conn_id,
+                                        Vec::new(),
+                                        true,
+                                    )),
+                                    return_address,
+                                ))
+                                .unwrap();
+                            return;
+                        }
+                    }
+
                     if let Some(stats_collector) = stats_collector {
                         if let Some(remote_addr) = stats_collector
                             .connected_services
@@ -274,22 +520,124 @@ impl ServiceProvider {
                     }
                     self.handle_proxy_send(controller_sender, conn_id, data, closed)
                 }
+
+                Request::Datagram(req) => {
+                    let return_address = match ReturnAddress::new(req.return_address, sender_tag) {
+                        Some(return_address) => return_address,
+                        None => {
+                            warn!(
+                                "Datagram request {} has neither the return address nor reply SURBs attached",
+                                req.conn_id
+                            );
+                            return;
+                        }
+                    };
+                    if let Some(stats_collector) = stats_collector.as_ref() {
+                        stats_collector
+                            .request_stats_data
+                            .write()
+                            .await
+                            .processed(&req.remote_addr, req.data.len() as u32);
+                    }
+                    self.handle_proxy_datagram(
+                        mix_input_sender,
+                        *req,
+                        return_address,
+                        stats_collector,
+                    )
+                    .await
+                }
             },
-            Socks5Message::Response(_) => {}
+            Socks5Message::Response(_)
+            | Socks5Message::DatagramResponse(_)
+            | Socks5Message::ConnectResponse(_) => {}
         }
     }
 
-    /// Start all subsystems
-    pub async fn run(&mut self) {
-        let websocket_stream = self.connect_websocket(&self.listening_address).await;
+    /// Reloads the exit policy and the allowed list. If that fails, the current ones stay in force.
+    fn reload_outbound_policy(&mut self) {
+        info!("Reloading the exit policy and the allowed list");
+        let exit_policy = match ExitPolicy::load(
+            &self.filter_config.exit_policy_location,
+            self.filter_config.reject_non_public_networks,
+        ) {
+            Ok(exit_policy) => exit_policy,
+            Err(err) => {
+                error!("Failed to reload the exit policy - {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = self.outbound_request_filter.reload(exit_policy) {
+            error!("Failed to reload the allowed list - {}", err);
+        }
+    }
+
+    /// Start all subsystems using the client embedded in this binary
+    pub async fn run(
+        &mut self,
+        buffer_requester: ReceivedBufferRequestSender,
+        input_sender: InputMessageSender,
+    ) {
+        // announce ourselves to the received messages buffer of our client so that all
+        // reconstructed mix messages would get pushed to us
+        let (reconstructed_sender, reconstructed_receiver) = mpsc::unbounded();
+        buffer_requester
+            .unbounded_send(ReceivedBufferMessage::ReceiverAnnounce(
+                reconstructed_sender,
+            ))
+            .expect("the buffer request failed!");
+
+        self.run_service(reconstructed_receiver, ResponseSink::Embedded(input_sender))
+            .await
+    }
+
+    /// Start all subsystems using a separately running native client listening on `websocket_uri`
+    pub async fn run_with_native_client(&mut self, websocket_uri: &str) {
+        let websocket_stream = Self::connect_websocket(websocket_uri).await;
 
         // split the websocket so that we could read and write from separate threads
         let (websocket_writer, mut websocket_reader) = websocket_stream.split();
 
+        // push everything received by the native client as if it was reconstructed by our own
+        let (reconstructed_sender, reconstructed_receiver) = mpsc::unbounded();
+        tokio::spawn(async move {
+            while let Some(received) = Self::read_websocket_message(&mut websocket_reader).await {
+                if reconstructed_sender.unbounded_send(vec![received]).is_err() {
+                    return;
+                }
+            }
+            error!("The websocket stream has finished!");
+        });
+
+        self.run_service(
+            reconstructed_receiver,
+            ResponseSink::NativeClient(websocket_writer),
+        )
+        .await
+    }
+
+    // Make the websocket connection so we can receive incoming Mixnet messages.
+    async fn connect_websocket(uri: &str) -> TSWebsocketStream {
+        match websocket::Connection::new(uri).connect().await {
+            Ok(ws_stream) => {
+                info!("* connected to local websocket server at {}", uri);
+                ws_stream
+            }
+            Err(WebsocketConnectionError::ConnectionNotEstablished) => {
+                panic!("Error: websocket connection attempt failed, is the Nym client running?")
+            }
+        }
+    }
+
+    async fn run_service(
+        &mut self,
+        mut reconstructed_receiver: ReconstructedMessagesReceiver,
+        response_sink: ResponseSink,
+    ) {
         // channels responsible for managing messages that are to be sent to the mix network. The receiver is
         // going to be used by `mixnet_response_listener`
-        let (mix_input_sender, mix_input_receiver) =
-            mpsc::unbounded::<(Socks5Message, Recipient)>();
+        let (mix_input_sender, mix_input_receiver) = mpsc::unbounded();
 
         // controller for managing all active connections
         let (mut active_connections_controller, mut controller_sender) = Controller::new();
@@ -313,50 +661,46 @@ impl ServiceProvider {
         };
 
         let stats_collector_clone = stats_collector.clone();
+        let request_limiter = self.request_limiter.clone();
         // start the listener for mix messages
         tokio::spawn(async move {
             Self::mixnet_response_listener(
-                websocket_writer,
+                response_sink,
                 mix_input_receiver,
                 stats_collector_clone,
+                request_limiter,
             )
             .await;
         });
 
+        let mut reload_signal = ReloadSignal::new();
+
         println!("\nAll systems go. Press CTRL-C to stop the server.");
-        // for each incoming message from the websocket... (which in 99.99% cases is going to be a mix message)
         loop {
-            let received = match Self::read_websocket_message(&mut websocket_reader).await {
-                Some(msg) => msg,
-                None => {
-                    error!("The websocket stream has finished!");
-                    return;
+            tokio::select! {
+                // for each batch of messages reconstructed by our client...
+                reconstructed_messages = reconstructed_receiver.next() => {
+                    let reconstructed_messages = match reconstructed_messages {
+                        Some(reconstructed_messages) => reconstructed_messages,
+                        None => break,
+                    };
+                    for received in reconstructed_messages {
+                        // anonymous clients attach reply SURBs instead of their address, in which
+                        // case all responses are sent back under their sender tag
+                        self.handle_proxy_message(
+                            &received.message,
+                            received.sender_tag,
+                            &mut controller_sender,
+                            &mix_input_sender,
+                            stats_collector.clone(),
+                        )
+                        .await;
+                    }
                 }
-            };
-
-            let raw_message = received.message;
-            // TODO: here be potential SURB (i.e. received.reply_SURB)
-
-            self.handle_proxy_message(
-                &raw_message,
-                &mut controller_sender,
-                &mix_input_sender,
-                stats_collector.clone(),
-            )
-            .await;
-        }
-    }
-
-    // Make the websocket connection so we can receive incoming Mixnet messages.
-    async fn connect_websocket(&self, uri: &str) -> TSWebsocketStream {
-        match websocket::Connection::new(uri).connect().await {
-            Ok(ws_stream) => {
-                info!("* connected to local websocket server at {}", uri);
-                ws_stream
-            }
-            Err(WebsocketConnectionError::ConnectionNotEstablished) => {
-                panic!("Error: websocket connection attempt failed, is the Nym client running?")
+                _ = reload_signal.recv() => self.reload_outbound_policy(),
             }
         }
+
+        error!("The stream of reconstructed messages has finished!");
     }
 }
@@ -0,0 +1,767 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use ipnetwork::IpNetwork;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+/// Networks that are not publicly routable, i.e. private (RFC1918), carrier-grade NAT, loopback,
+/// link-local, unique local, multicast, reserved and unspecified addresses. Ipv6 networks
+/// embedding ipv4 addresses (ipv4-compatible, NAT64 and 6to4) are included as well, as they
+/// could be used for reaching any of the former. Unless explicitly disabled, requests to any
+/// of them are always rejected, regardless of any other rules.
+const NON_PUBLIC_NETWORKS: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "100.64.0.0/10",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.0.0.0/24",
+    "192.168.0.0/16",
+    "198.18.0.0/15",
+    "224.0.0.0/4",
+    "240.0.0.0/4",
+    "::/96",
+    "::1/128",
+    "64:ff9b::/96",
+    "64:ff9b:1::/48",
+    "2002::/16",
+    "fc00::/7",
+    "fe80::/10",
+    "ff00::/8",
+];
+
+const POLICY_FILE_HEADER: &str = r#"# Exit policy of this network requester.
+#
+# Each line contains a single rule in the form of `<accept|reject> <host>[:<ports>]`, where:
+#  - <host> is either `*` (any host), a domain (`example.com`), a wildcard matching all
+#    subdomains of a domain (`*.example.com`), an ip address (`1.2.3.4`) or an ip network
+#    (`1.2.3.0/24`). Ipv6 addresses and networks have to be put into brackets if followed by
+#    ports (`[2001:db8::/32]:443`).
+#  - <ports> is either `*` (any port, which is also the default), a single port (`443`)
+#    or an inclusive range of ports (`8000-8999`).
+#
+# Rejections always take precedence over acceptances, regardless of their order in this file.
+# Requests that match neither are checked against the allowed list.
+# Send SIGHUP to the process to reload this file without restarting it.
+"#;
+
+#[derive(Debug, Error)]
+pub(crate) enum ExitPolicyError {
+    #[error("failed to access the exit policy file at {path:?} - {source}")]
+    FileAccess { path: PathBuf, source: io::Error },
+
+    #[error("line {line} of the exit policy ('{rule}') is malformed - {reason}")]
+    MalformedRule {
+        line: usize,
+        rule: String,
+        reason: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PolicyAction {
+    Accept,
+    Reject,
+}
+
+/// Outcome of checking a destination against the exit policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PolicyDecision {
+    Accept,
+    Reject,
+
+    /// None of the rules apply to the destination.
+    NoMatch,
+}
+
+/// Host of an outbound request, as understood by the exit policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DestinationHost {
+    Domain(String),
+    Ip(IpAddr),
+}
+
+/// Destination of an outbound request. The port is unknown if it hasn't been specified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Destination {
+    host: DestinationHost,
+    port: Option<u16>,
+}
+
+impl Destination {
+    pub(crate) fn new_domain(domain: &str, port: Option<u16>) -> Self {
+        Destination {
+            host: DestinationHost::Domain(normalise_domain(domain)),
+            port,
+        }
+    }
+
+    pub(crate) fn new_ip(ip: IpAddr, port: Option<u16>) -> Self {
+        Destination {
+            host: DestinationHost::Ip(unmap_ipv4(ip)),
+            port,
+        }
+    }
+
+    pub(crate) fn host(&self) -> &DestinationHost {
+        &self.host
+    }
+}
+
+impl From<SocketAddr> for Destination {
+    fn from(address: SocketAddr) -> Self {
+        Destination::new_ip(address.ip(), Some(address.port()))
+    }
+}
+
+fn normalise_domain(domain: &str) -> String {
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
+
+// ipv4-mapped ipv6 addresses (::ffff:a.b.c.d) reach the very same hosts as their ipv4
+// counterparts, so they must be subject to exactly the same rules
+fn unmap_ipv4(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, high, low] => IpAddr::V4(Ipv4Addr::new(
+                (high >> 8) as u8,
+                high as u8,
+                (low >> 8) as u8,
+                low as u8,
+            )),
+            _ => ip,
+        },
+        v4 => v4,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    Any,
+
+    /// Exactly the specified domain.
+    Domain(String),
+
+    /// Any subdomain of the specified domain, but not the domain itself.
+    Subdomains(String),
+
+    Network(IpNetwork),
+}
+
+impl HostPattern {
+    fn parse(raw: &str) -> Result<Self, &'static str> {
+        if raw == "*" {
+            return Ok(HostPattern::Any);
+        }
+        if let Ok(network) = raw.parse() {
+            return Ok(HostPattern::Network(network));
+        }
+
+        let (pattern, domain): (fn(String) -> HostPattern, _) = match raw.strip_prefix("*.") {
+            Some(parent) => (HostPattern::Subdomains, parent),
+            None => (HostPattern::Domain, raw),
+        };
+        let domain = normalise_domain(domain);
+        let is_valid = !domain.is_empty()
+            && domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !is_valid {
+            return Err("the host is neither a valid domain nor an ip network");
+        }
+
+        Ok(pattern(domain))
+    }
+
+    fn matches(&self, host: &DestinationHost) -> bool {
+        match (self, host) {
+            (HostPattern::Any, _) => true,
+            (HostPattern::Domain(pattern), DestinationHost::Domain(domain)) => pattern == domain,
+            (HostPattern::Subdomains(parent), DestinationHost::Domain(domain)) => matches!(
+                domain
+                    .strip_suffix(parent.as_str())
+                    .and_then(|subdomain| subdomain.strip_suffix('.')),
+                Some(subdomain) if !subdomain.is_empty()
+            ),
+            (HostPattern::Network(network), DestinationHost::Ip(ip)) => network.contains(*ip),
+            _ => false,
+        }
+    }
+}
+
+/// Inclusive range of ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PortRange {
+    start: u16,
+    end: u16,
+}
+
+impl PortRange {
+    const ANY: PortRange = PortRange {
+        start: 0,
+        end: u16::MAX,
+    };
+
+    fn parse(raw: &str) -> Result<Self, &'static str> {
+        const INVALID_PORT: &str = "the port is not a number between 0 and 65535";
+
+        if raw == "*" {
+            return Ok(PortRange::ANY);
+        }
+        let (start, end) = raw.split_once('-').unwrap_or((raw, raw));
+        let start = start.parse().map_err(|_| INVALID_PORT)?;
+        let end = end.parse().map_err(|_| INVALID_PORT)?;
+        if start > end {
+            return Err("the port range is empty");
+        }
+
+        Ok(PortRange { start, end })
+    }
+
+    // if the port of the destination is unknown, only the rules for all ports can apply to it
+    fn matches(&self, port: Option<u16>) -> bool {
+        match port {
+            Some(port) => self.start <= port && port <= self.end,
+            None => *self == PortRange::ANY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PolicyRule {
+    action: PolicyAction,
+    host: HostPattern,
+    ports: PortRange,
+}
+
+impl PolicyRule {
+    fn parse(raw: &str) -> Result<Self, &'static str> {
+        let mut tokens = raw.split_whitespace();
+        let action = match tokens.next().map(|action| action.to_ascii_lowercase()) {
+            Some(action) if action == "accept" => PolicyAction::Accept,
+            Some(action) if action == "reject" => PolicyAction::Reject,
+            _ => return Err("the rule has to start with either 'accept' or 'reject'"),
+        };
+        let target = tokens
+            .next()
+            .ok_or("the rule does not specify the host it applies to")?;
+        if tokens.next().is_some() {
+            return Err("the rule contains unexpected trailing data");
+        }
+
+        let (host, ports) = if let Some(bracketed) = target.strip_prefix('[') {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .ok_or("the opening bracket is not closed")?;
+            match rest {
+                "" => (host, None),
+                _ => (
+                    host,
+                    Some(
+                        rest.strip_prefix(':')
+                            .ok_or("expected ports after the host")?,
+                    ),
+                ),
+            }
+        } else if target.matches(':').count() > 1 {
+            // an ipv6 address or network without any ports
+            (target, None)
+        } else {
+            match target.split_once(':') {
+                Some((host, ports)) => (host, Some(ports)),
+                None => (target, None),
+            }
+        };
+
+        Ok(PolicyRule {
+            action,
+            host: HostPattern::parse(host)?,
+            ports: ports
+                .map(PortRange::parse)
+                .transpose()?
+                .unwrap_or(PortRange::ANY),
+        })
+    }
+
+    fn matches(&self, destination: &Destination) -> bool {
+        self.ports.matches(destination.port) && self.host.matches(&destination.host)
+    }
+}
+
+/// Set of rules, similar to Tor's ExitPolicy, deciding which hosts and ports the network
+/// requester is allowed to connect to.
+#[derive(Debug, Default)]
+pub(crate) struct ExitPolicy {
+    rules: Vec<PolicyRule>,
+    rejected_networks: Vec<IpNetwork>,
+}
+
+impl ExitPolicy {
+    pub(crate) fn new(rules: Vec<PolicyRule>, reject_non_public_networks: bool) -> Self {
+        let rejected_networks = if reject_non_public_networks {
+            NON_PUBLIC_NETWORKS
+                .iter()
+                .map(|network| network.parse().unwrap())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        ExitPolicy {
+            rules,
+            rejected_networks,
+        }
+    }
+
+    /// Loads the rules from the provided file, creating it if needed.
+    pub(crate) fn load(
+        policy_file: &Path,
+        reject_non_public_networks: bool,
+    ) -> Result<Self, ExitPolicyError> {
+        let file_access = |source: io::Error| ExitPolicyError::FileAccess {
+            path: policy_file.to_path_buf(),
+            source,
+        };
+
+        if !policy_file.exists() {
+            if let Some(dirpath) = policy_file.parent() {
+                fs::create_dir_all(dirpath).map_err(file_access)?;
+            }
+            File::create(policy_file)
+                .and_then(|mut file| file.write_all(POLICY_FILE_HEADER.as_bytes()))
+                .map_err(file_access)?;
+        }
+
+        let content = fs::read_to_string(policy_file).map_err(file_access)?;
+        Ok(ExitPolicy::new(
+            Self::parse_rules(&content)?,
+            reject_non_public_networks,
+        ))
+    }
+
+    /// Parses the rules, one per line. Empty lines and everything following a `#` are ignored.
+    pub(crate) fn parse_rules(content: &str) -> Result<Vec<PolicyRule>, ExitPolicyError> {
+        content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+            .filter(|(_, rule)| !rule.is_empty())
+            .map(|(line, rule)| {
+                PolicyRule::parse(rule).map_err(|reason| ExitPolicyError::MalformedRule {
+                    line,
+                    rule: rule.to_string(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    fn rejects(&self, destination: &Destination) -> bool {
+        let in_rejected_network = match destination.host {
+            DestinationHost::Ip(ip) => self
+                .rejected_networks
+                .iter()
+                .any(|network| network.contains(ip)),
+            DestinationHost::Domain(_) => false,
+        };
+
+        in_rejected_network
+            || self
+                .rules
+                .iter()
+                .any(|rule| rule.action == PolicyAction::Reject && rule.matches(destination))
+    }
+
+    pub(crate) fn evaluate(&self, destination: &Destination) -> PolicyDecision {
+        // rejections always take precedence, so that nothing could accidentally override them
+        if self.rejects(destination) {
+            PolicyDecision::Reject
+        } else if self
+            .rules
+            .iter()
+            .any(|rule| rule.action == PolicyAction::Accept && rule.matches(destination))
+        {
+            PolicyDecision::Accept
+        } else {
+            PolicyDecision::NoMatch
+        }
+    }
+}
+
+/// Exit policy shared between the request filter and the tasks connecting to the remotes,
+/// so that they would all see the reloaded rules.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedExitPolicy {
+    inner: Arc<RwLock<ExitPolicy>>,
+}
+
+impl SharedExitPolicy {
+    pub(crate) fn new(policy: ExitPolicy) -> Self {
+        SharedExitPolicy {
+            inner: Arc::new(RwLock::new(policy)),
+        }
+    }
+
+    pub(crate) fn evaluate(&self, destination: &Destination) -> PolicyDecision {
+        self.inner
+            .read()
+            .expect("exit policy lock got poisoned")
+            .evaluate(destination)
+    }
+
+    /// Checks whether an already resolved address can be connected to. This guards against
+    /// domains resolving to addresses that would have been rejected if requested directly.
+    pub(crate) fn allows_address(&self, address: SocketAddr) -> bool {
+        !self
+            .inner
+            .read()
+            .expect("exit policy lock got poisoned")
+            .rejects(&address.into())
+    }
+
+    pub(crate) fn replace(&self, policy: ExitPolicy) {
+        *self.inner.write().expect("exit policy lock got poisoned") = policy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(rules: &str) -> ExitPolicy {
+        ExitPolicy::new(ExitPolicy::parse_rules(rules).unwrap(), true)
+    }
+
+    fn domain(domain: &str, port: u16) -> Destination {
+        Destination::new_domain(domain, Some(port))
+    }
+
+    fn address(address: &str) -> Destination {
+        address.parse::<SocketAddr>().unwrap().into()
+    }
+
+    #[cfg(test)]
+    mod parsing_rules {
+        use super::*;
+
+        fn parse(rule: &str) -> PolicyRule {
+            PolicyRule::parse(rule).unwrap()
+        }
+
+        #[test]
+        fn works_for_all_host_patterns() {
+            assert_eq!(HostPattern::Any, parse("accept *").host);
+            assert_eq!(
+                HostPattern::Domain("nymtech.net".into()),
+                parse("accept NymTech.net.").host
+            );
+            assert_eq!(
+                HostPattern::Subdomains("nymtech.net".into()),
+                parse("accept *.nymtech.net").host
+            );
+            assert_eq!(
+                HostPattern::Network("10.0.0.0/8".parse().unwrap()),
+                parse("reject 10.0.0.0/8").host
+            );
+            assert_eq!(
+                HostPattern::Network("2001:db8::/32".parse().unwrap()),
+                parse("reject 2001:db8::/32").host
+            );
+        }
+
+        #[test]
+        fn works_for_all_port_specifications() {
+            assert_eq!(PortRange::ANY, parse("accept nymtech.net").ports);
+            assert_eq!(PortRange::ANY, parse("accept nymtech.net:*").ports);
+            assert_eq!(
+                PortRange {
+                    start: 443,
+                    end: 443
+                },
+                parse("accept nymtech.net:443").ports
+            );
+            assert_eq!(
+                PortRange {
+                    start: 8000,
+                    end: 8999
+                },
+                parse("accept 1.2.3.4:8000-8999").ports
+            );
+
+            let rule = parse("reject [2001:db8::/32]:25");
+            assert_eq!(
+                HostPattern::Network("2001:db8::/32".parse().unwrap()),
+                rule.host
+            );
+            assert_eq!(PortRange { start: 25, end: 25 }, rule.ports);
+        }
+
+        #[test]
+        fn ignores_comments_and_empty_lines() {
+            let rules = ExitPolicy::parse_rules(
+                "# some comment\n\naccept nymtech.net:443 # trailing comment\n   \nreject *:25\n",
+            )
+            .unwrap();
+            assert_eq!(2, rules.len());
+            assert_eq!(PolicyAction::Accept, rules[0].action);
+            assert_eq!(PolicyAction::Reject, rules[1].action);
+        }
+
+        #[test]
+        fn fails_for_malformed_rules() {
+            for malformed in [
+                "allow nymtech.net",
+                "accept",
+                "accept nymtech.net 443",
+                "accept nymtech.net:http",
+                "accept nymtech.net:70000",
+                "accept nymtech.net:443-80",
+                "accept [::1:443",
+                "accept nym*tech.net",
+                "accept *.",
+            ] {
+                assert!(PolicyRule::parse(malformed).is_err(), "{}", malformed);
+            }
+        }
+
+        #[test]
+        fn reports_line_of_malformed_rule() {
+            match ExitPolicy::parse_rules("accept nymtech.net\n\nreject foo:bar") {
+                Err(ExitPolicyError::MalformedRule { line, .. }) => assert_eq!(3, line),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod rule_precedence {
+        use super::*;
+
+        #[test]
+        fn rejections_override_acceptances_regardless_of_order() {
+            let accept_first = policy("accept *.nymtech.net\nreject blog.nymtech.net");
+            let reject_first = policy("reject blog.nymtech.net\naccept *.nymtech.net");
+
+            for policy in [accept_first, reject_first] {
+                assert_eq!(
+                    PolicyDecision::Reject,
+                    policy.evaluate(&domain("blog.nymtech.net", 443))
+                );
+                assert_eq!(
+                    PolicyDecision::Accept,
+                    policy.evaluate(&domain("shop.nymtech.net", 443))
+                );
+            }
+        }
+
+        #[test]
+        fn rejected_ports_override_accepted_hosts() {
+            let policy = policy("accept *\nreject *:25\nreject *:6881-6889");
+
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&domain("nymtech.net", 443))
+            );
+            assert_eq!(
+                PolicyDecision::Reject,
+                policy.evaluate(&domain("nymtech.net", 25))
+            );
+            assert_eq!(
+                PolicyDecision::Reject,
+                policy.evaluate(&address("1.2.3.4:6885"))
+            );
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&address("1.2.3.4:6890"))
+            );
+        }
+
+        #[test]
+        fn non_public_networks_are_rejected_even_if_accepted_explicitly() {
+            let policy = policy("accept *\naccept 192.168.0.0/16\naccept [::1]:80");
+
+            for rejected in [
+                "10.1.2.3:80",
+                "172.16.0.1:443",
+                "172.31.255.255:443",
+                "192.168.1.1:80",
+                "127.0.0.1:80",
+                "169.254.169.254:80",
+                "100.64.0.1:80",
+                "0.0.0.0:80",
+                "[::1]:80",
+                "[fd00::1]:80",
+                "[fe80::1]:80",
+                "[::ffff:10.0.0.1]:80",
+                "[::ffff:127.0.0.1]:80",
+                "192.0.0.1:80",
+                "198.18.0.1:80",
+                "198.19.255.255:80",
+                "224.0.0.1:80",
+                "239.255.255.250:1900",
+                "240.0.0.1:80",
+                "255.255.255.255:80",
+                "[::10.0.0.1]:80",
+                "[64:ff9b::10.0.0.1]:80",
+                "[64:ff9b:1::a00:1]:80",
+                "[2002:a00:1::1]:80",
+                "[ff02::1]:80",
+            ] {
+                assert_eq!(
+                    PolicyDecision::Reject,
+                    policy.evaluate(&address(rejected)),
+                    "{}",
+                    rejected
+                );
+            }
+
+            // but the neighbouring public addresses are fine
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&address("172.32.0.1:443"))
+            );
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&address("[::ffff:1.1.1.1]:443"))
+            );
+        }
+
+        #[test]
+        fn non_public_networks_can_be_allowed_if_their_rejection_is_disabled() {
+            let rules = ExitPolicy::parse_rules("accept 192.168.0.0/16").unwrap();
+            let policy = ExitPolicy::new(rules, false);
+
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&address("192.168.1.1:80"))
+            );
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&address("10.0.0.1:80"))
+            );
+        }
+
+        #[test]
+        fn wildcards_match_subdomains_only() {
+            let policy = policy("accept *.nymtech.net");
+
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&domain("foomp.nymtech.net", 443))
+            );
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&domain("a.b.nymtech.net", 443))
+            );
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&domain("nymtech.net", 443))
+            );
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&domain("evilnymtech.net", 443))
+            );
+        }
+
+        #[test]
+        fn domains_match_regardless_of_case() {
+            let policy = policy("reject Nymtech.NET");
+
+            assert_eq!(
+                PolicyDecision::Reject,
+                policy.evaluate(&domain("NYMTECH.net.", 443))
+            );
+        }
+
+        #[test]
+        fn port_ranges_are_inclusive() {
+            let policy = policy("accept nymtech.net:8000-8999");
+
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&domain("nymtech.net", 7999))
+            );
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&domain("nymtech.net", 8000))
+            );
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&domain("nymtech.net", 8999))
+            );
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&domain("nymtech.net", 9000))
+            );
+        }
+
+        #[test]
+        fn destinations_without_port_only_match_rules_for_all_ports() {
+            let policy = policy("accept nymtech.net:443\naccept 1.2.3.4");
+
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&Destination::new_domain("nymtech.net", None))
+            );
+            assert_eq!(
+                PolicyDecision::Accept,
+                policy.evaluate(&Destination::new_ip("1.2.3.4".parse().unwrap(), None))
+            );
+        }
+
+        #[test]
+        fn network_rules_do_not_apply_to_domains() {
+            let policy = policy("reject 1.2.3.0/24");
+
+            assert_eq!(
+                PolicyDecision::NoMatch,
+                policy.evaluate(&domain("nymtech.net", 443))
+            );
+        }
+    }
+
+    #[test]
+    fn resolved_addresses_are_subject_to_rejections() {
+        let shared = SharedExitPolicy::new(policy("accept nymtech.net\nreject 1.2.3.4"));
+
+        assert!(shared.allows_address("5.6.7.8:443".parse().unwrap()));
+        assert!(!shared.allows_address("1.2.3.4:443".parse().unwrap()));
+        assert!(!shared.allows_address("192.168.1.1:443".parse().unwrap()));
+    }
+
+    #[test]
+    fn replaced_policy_is_seen_by_all_clones() {
+        let shared = SharedExitPolicy::new(policy(""));
+        let clone = shared.clone();
+        assert!(clone.allows_address("1.2.3.4:443".parse().unwrap()));
+
+        shared.replace(policy("reject 1.2.3.4"));
+        assert!(!clone.allows_address("1.2.3.4:443".parse().unwrap()));
+    }
+
+    #[test]
+    fn loading_creates_the_policy_file() {
+        let policy_file = std::env::temp_dir()
+            .join("nym-tests")
+            .join(format!("exit-{:?}.policy", rand::random::<u32>()));
+
+        let policy = ExitPolicy::load(&policy_file, true).unwrap();
+        assert!(policy.rules.is_empty());
+        assert!(policy_file.exists());
+
+        // rules added by the operator are picked up on the next load
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&policy_file)
+            .unwrap();
+        writeln!(file, "reject *:25").unwrap();
+        let policy = ExitPolicy::load(&policy_file, true).unwrap();
+        assert_eq!(1, policy.rules.len());
+    }
+}
@@ -0,0 +1,394 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reply::ReturnAddress;
+use socks5_requests::ConnectionId;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Limit that would have been exceeded by the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LimitExceeded {
+    /// The network requester is already handling the maximum number of connections.
+    ConcurrentConnections,
+
+    /// The sender already has the maximum number of connections open.
+    SenderConnections,
+
+    /// The sender has already used up its bandwidth in the current window.
+    SenderBandwidth,
+
+    /// The sender already has an open connection with the same id.
+    DuplicateConnection,
+}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitExceeded::ConcurrentConnections => {
+                write!(f, "the maximum number of concurrent connections")
+            }
+            LimitExceeded::SenderConnections => {
+                write!(f, "the maximum number of connections per sender")
+            }
+            LimitExceeded::SenderBandwidth => write!(f, "the bandwidth limit per sender"),
+            LimitExceeded::DuplicateConnection => {
+                write!(f, "the single connection allowed per connection id")
+            }
+        }
+    }
+}
+
+/// Limits imposed on the clients of this network requester. A value of 0 disables given limit.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestLimits {
+    pub(crate) max_concurrent_connections: usize,
+    pub(crate) max_connections_per_sender: usize,
+    pub(crate) max_bytes_per_sender: u64,
+    pub(crate) bandwidth_window: Duration,
+}
+
+#[derive(Debug)]
+struct SenderUsage {
+    active_connections: usize,
+    window_start: Instant,
+    bytes_in_window: u64,
+}
+
+impl SenderUsage {
+    fn new(now: Instant) -> Self {
+        SenderUsage {
+            active_connections: 0,
+            window_start: now,
+            bytes_in_window: 0,
+        }
+    }
+
+    fn refresh_window(&mut self, now: Instant, window: Duration) {
+        if now.saturating_duration_since(self.window_start) >= window {
+            self.window_start = now;
+            self.bytes_in_window = 0;
+        }
+    }
+
+    fn is_idle(&self, now: Instant, window: Duration) -> bool {
+        self.active_connections == 0 && now.saturating_duration_since(self.window_start) >= window
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    // connection ids are chosen by the clients, so they are only unique per sender
+    connections: HashSet<(ReturnAddress, ConnectionId)>,
+    // sender that has most recently opened a connection with given id, i.e. the one whose
+    // connection receives the data sent with that id
+    latest_senders: HashMap<ConnectionId, ReturnAddress>,
+    senders: HashMap<ReturnAddress, SenderUsage>,
+}
+
+/// Keeps track of connections and bandwidth used by each sender and enforces the configured
+/// `RequestLimits`.
+///
+/// Note that senders are identified by their return addresses, which are entirely controlled
+/// by the clients: a plain address is not authenticated in any way, so it can be spoofed,
+/// and an anonymous sender tag can be freshly generated for every request. The per-sender
+/// limits therefore only prevent well-behaved clients from hogging the resources and
+/// the limit on concurrent connections is the only real guarantee.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestLimiter {
+    limits: RequestLimits,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl RequestLimiter {
+    pub(crate) fn new(limits: RequestLimits) -> Self {
+        RequestLimiter {
+            limits,
+            state: Arc::new(Mutex::new(LimiterState::default())),
+        }
+    }
+
+    /// Attempts to register a new connection of the sender. The connection is considered
+    /// active for as long as the returned permit is alive.
+    pub(crate) fn try_open_connection(
+        &self,
+        connection_id: ConnectionId,
+        sender: ReturnAddress,
+    ) -> Result<ConnectionPermit, LimitExceeded> {
+        self.try_open_connection_at(connection_id, sender, Instant::now())
+    }
+
+    fn try_open_connection_at(
+        &self,
+        connection_id: ConnectionId,
+        sender: ReturnAddress,
+        now: Instant,
+    ) -> Result<ConnectionPermit, LimitExceeded> {
+        let mut state = self
+            .state
+            .lock()
+            .expect("request limiter lock got poisoned");
+        let window = self.limits.bandwidth_window;
+
+        // forget about senders that have not done anything in a while
+        state.senders.retain(|_, usage| !usage.is_idle(now, window));
+
+        if self.limits.max_concurrent_connections != 0
+            && state.connections.len() >= self.limits.max_concurrent_connections
+        {
+            return Err(LimitExceeded::ConcurrentConnections);
+        }
+
+        // every open connection must be accounted for, so the ids can't be reused while
+        // the previous connection is still running
+        if state.connections.contains(&(sender, connection_id)) {
+            return Err(LimitExceeded::DuplicateConnection);
+        }
+
+        let usage = state
+            .senders
+            .entry(sender)
+            .or_insert_with(|| SenderUsage::new(now));
+        usage.refresh_window(now, window);
+
+        if self.limits.max_connections_per_sender != 0
+            && usage.active_connections >= self.limits.max_connections_per_sender
+        {
+            return Err(LimitExceeded::SenderConnections);
+        }
+        if self.limits.max_bytes_per_sender != 0
+            && usage.bytes_in_window >= self.limits.max_bytes_per_sender
+        {
+            return Err(LimitExceeded::SenderBandwidth);
+        }
+
+        usage.active_connections += 1;
+        state.connections.insert((sender, connection_id));
+        state.latest_senders.insert(connection_id, sender);
+
+        Ok(ConnectionPermit {
+            sender,
+            connection_id,
+            state: Arc::clone(&self.state),
+        })
+    }
+
+    /// Returns the sender of the active connection with the provided id. If multiple senders
+    /// have used the same id, it is the one that has opened its connection most recently.
+    pub(crate) fn sender_of(&self, connection_id: ConnectionId) -> Option<ReturnAddress> {
+        self.state
+            .lock()
+            .expect("request limiter lock got poisoned")
+            .latest_senders
+            .get(&connection_id)
+            .copied()
+    }
+
+    /// Accounts for the bytes sent to or received from the sender. It returns an error if
+    /// the sender has gone over its bandwidth limit in the current window.
+    pub(crate) fn consume_bandwidth(
+        &self,
+        sender: ReturnAddress,
+        bytes: usize,
+    ) -> Result<(), LimitExceeded> {
+        self.consume_bandwidth_at(sender, bytes, Instant::now())
+    }
+
+    fn consume_bandwidth_at(
+        &self,
+        sender: ReturnAddress,
+        bytes: usize,
+        now: Instant,
+    ) -> Result<(), LimitExceeded> {
+        let mut state = self
+            .state
+            .lock()
+            .expect("request limiter lock got poisoned");
+        let usage = state
+            .senders
+            .entry(sender)
+            .or_insert_with(|| SenderUsage::new(now));
+        usage.refresh_window(now, self.limits.bandwidth_window);
+        usage.bytes_in_window = usage.bytes_in_window.saturating_add(bytes as u64);
+
+        if self.limits.max_bytes_per_sender != 0
+            && usage.bytes_in_window > self.limits.max_bytes_per_sender
+        {
+            Err(LimitExceeded::SenderBandwidth)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Marks the connection as active until dropped.
+#[derive(Debug)]
+pub(crate) struct ConnectionPermit {
+    sender: ReturnAddress,
+    connection_id: ConnectionId,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let (sender, connection_id) = (self.sender, self.connection_id);
+        let mut state = self
+            .state
+            .lock()
+            .expect("request limiter lock got poisoned");
+        if state.connections.remove(&(sender, connection_id)) {
+            if let Some(usage) = state.senders.get_mut(&sender) {
+                usage.active_connections = usage.active_connections.saturating_sub(1);
+            }
+        }
+        if state.latest_senders.get(&connection_id) == Some(&sender) {
+            state.latest_senders.remove(&connection_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymsphinx::anonymous_replies::AnonymousSenderTag;
+
+    fn sender(tag: u8) -> ReturnAddress {
+        ReturnAddress::Anonymous(AnonymousSenderTag::from_bytes([tag; 16]))
+    }
+
+    fn limiter(
+        max_concurrent_connections: usize,
+        max_connections_per_sender: usize,
+        max_bytes_per_sender: u64,
+    ) -> RequestLimiter {
+        RequestLimiter::new(RequestLimits {
+            max_concurrent_connections,
+            max_connections_per_sender,
+            max_bytes_per_sender,
+            bandwidth_window: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn connections_per_sender_are_limited() {
+        let limiter = limiter(0, 2, 0);
+
+        let _first = limiter.try_open_connection(1, sender(1)).unwrap();
+        let second = limiter.try_open_connection(2, sender(1)).unwrap();
+        assert_eq!(
+            LimitExceeded::SenderConnections,
+            limiter.try_open_connection(3, sender(1)).unwrap_err()
+        );
+
+        // other senders are not affected
+        assert!(limiter.try_open_connection(4, sender(2)).is_ok());
+
+        // and closing a connection frees up the slot
+        drop(second);
+        assert!(limiter.try_open_connection(5, sender(1)).is_ok());
+    }
+
+    #[test]
+    fn concurrent_connections_are_limited_globally() {
+        let limiter = limiter(2, 0, 0);
+
+        let _first = limiter.try_open_connection(1, sender(1)).unwrap();
+        let _second = limiter.try_open_connection(2, sender(2)).unwrap();
+        assert_eq!(
+            LimitExceeded::ConcurrentConnections,
+            limiter.try_open_connection(3, sender(3)).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn bandwidth_is_limited_within_the_window() {
+        let limiter = limiter(0, 0, 100);
+        let start = Instant::now();
+
+        assert!(limiter.consume_bandwidth_at(sender(1), 60, start).is_ok());
+        assert_eq!(
+            LimitExceeded::SenderBandwidth,
+            limiter
+                .consume_bandwidth_at(sender(1), 60, start)
+                .unwrap_err()
+        );
+
+        // no new connections can be opened either
+        assert_eq!(
+            LimitExceeded::SenderBandwidth,
+            limiter
+                .try_open_connection_at(1, sender(1), start)
+                .unwrap_err()
+        );
+
+        // until the next window starts
+        let next_window = start + Duration::from_secs(60);
+        assert!(limiter
+            .try_open_connection_at(2, sender(1), next_window)
+            .is_ok());
+        assert!(limiter
+            .consume_bandwidth_at(sender(1), 60, next_window)
+            .is_ok());
+    }
+
+    #[test]
+    fn sender_of_connection_is_known_while_it_is_active() {
+        let limiter = limiter(0, 0, 0);
+
+        let permit = limiter.try_open_connection(42, sender(1)).unwrap();
+        assert_eq!(Some(sender(1)), limiter.sender_of(42));
+
+        drop(permit);
+        assert!(limiter.sender_of(42).is_none());
+    }
+
+    #[test]
+    fn colliding_connection_ids_of_different_senders_are_kept_apart() {
+        let limiter = limiter(0, 1, 0);
+
+        let first = limiter.try_open_connection(42, sender(1)).unwrap();
+        let second = limiter.try_open_connection(42, sender(2)).unwrap();
+        assert_eq!(Some(sender(2)), limiter.sender_of(42));
+
+        // closing the connection of one sender does not free up the slot of the other one
+        drop(second);
+        assert!(limiter.sender_of(42).is_none());
+        assert_eq!(
+            LimitExceeded::SenderConnections,
+            limiter.try_open_connection(43, sender(1)).unwrap_err()
+        );
+
+        drop(first);
+        assert!(limiter.try_open_connection(43, sender(1)).is_ok());
+    }
+
+    #[test]
+    fn reopening_the_same_connection_id_is_rejected() {
+        let limiter = limiter(2, 0, 0);
+
+        let first = limiter.try_open_connection(42, sender(1)).unwrap();
+        for _ in 0..5 {
+            assert_eq!(
+                LimitExceeded::DuplicateConnection,
+                limiter.try_open_connection(42, sender(1)).unwrap_err()
+            );
+        }
+
+        // so the reopened connections never got past the global limit either
+        let _second = limiter.try_open_connection(43, sender(2)).unwrap();
+        assert_eq!(
+            LimitExceeded::ConcurrentConnections,
+            limiter.try_open_connection(42, sender(1)).unwrap_err()
+        );
+        assert_eq!(
+            LimitExceeded::ConcurrentConnections,
+            limiter.try_open_connection(44, sender(3)).unwrap_err()
+        );
+
+        // the id can be used again once the previous connection is closed
+        drop(first);
+        assert!(limiter.try_open_connection(42, sender(1)).is_ok());
+    }
+}
@@ -1,86 +1,46 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::{App, Arg, ArgMatches};
-
-use network_defaults::DEFAULT_WEBSOCKET_LISTENING_PORT;
-use nymsphinx::addressing::clients::Recipient;
+use clap::{crate_version, Parser};
+use network_defaults::setup_env;
 
 mod allowed_hosts;
+mod client;
+mod commands;
 mod connection;
 mod core;
+mod exit_policy;
+mod limits;
+mod reply;
 mod statistics;
+mod udp;
 mod websocket;
 
-const OPEN_PROXY_ARG: &str = "open-proxy";
-const WS_PORT: &str = "websocket-port";
-const ENABLE_STATISTICS: &str = "enable-statistics";
-const STATISTICS_RECIPIENT: &str = "statistics-recipient";
-
-fn parse_args<'a>() -> ArgMatches<'a> {
-    App::new("Nym Network Requester")
-        .version(env!("CARGO_PKG_VERSION"))
-        .author("Nymtech")
-        .arg(
-            Arg::with_name(OPEN_PROXY_ARG)
-                .help("specifies whether this network requester should run in 'open-proxy' mode")
-                .long(OPEN_PROXY_ARG)
-                .short("o"),
-        )
-        .arg(
-            Arg::with_name(WS_PORT)
-                .help("websocket port to bind to")
-                .long(WS_PORT)
-                .short("p")
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name(ENABLE_STATISTICS)
-                .help("enable service anonymized statistics that get sent to a statistics aggregator server")
-                .long(ENABLE_STATISTICS),
-        )
-        .arg(
-            Arg::with_name(STATISTICS_RECIPIENT)
-                .help("mixnet client address where a statistics aggregator is running. The default value is a Nym aggregator client")
-                .long(STATISTICS_RECIPIENT)
-                .requires(ENABLE_STATISTICS)
-                .takes_value(true),
-        )
-        .get_matches()
-}
-
 #[tokio::main]
 async fn main() {
     setup_logging();
-    let matches = parse_args();
+    println!("{}", banner());
 
-    let open_proxy = matches.is_present(OPEN_PROXY_ARG);
-    if open_proxy {
-        println!("\n\nYOU HAVE STARTED IN 'OPEN PROXY' MODE. ANYONE WITH YOUR CLIENT ADDRESS CAN MAKE REQUESTS FROM YOUR MACHINE. PLEASE QUIT IF YOU DON'T UNDERSTAND WHAT YOU'RE DOING.\n\n");
-    }
+    let args = commands::Cli::parse();
+    setup_env(args.config_env_file.clone());
+    commands::execute(&args).await;
+}
 
-    let enable_statistics = matches.is_present(ENABLE_STATISTICS);
-    if enable_statistics {
-        println!("\n\nTHE NETWORK REQUESTER STATISTICS ARE ENABLED. IT WILL COLLECT AND SEND ANONYMIZED STATISTICS TO A CENTRAL SERVER. PLEASE QUIT IF YOU DON'T WANT THIS TO HAPPEN AND START WITHOUT THE {} FLAG .\n\n", ENABLE_STATISTICS);
-    }
+fn banner() -> String {
+    format!(
+        r#"
 
-    let stats_provider_addr = matches
-        .value_of(STATISTICS_RECIPIENT)
-        .map(Recipient::try_from_base58_string)
-        .transpose()
-        .unwrap_or(None);
+      _ __  _   _ _ __ ___
+     | '_ \| | | | '_ \ _ \
+     | | | | |_| | | | | | |
+     |_| |_|\__, |_| |_| |_|
+            |___/
 
-    let uri = format!(
-        "ws://localhost:{}",
-        matches
-            .value_of(WS_PORT)
-            .unwrap_or(&DEFAULT_WEBSOCKET_LISTENING_PORT.to_string())
-    );
+             (network requester - version {:})
 
-    println!("Starting socks5 service provider:");
-    let mut server =
-        core::ServiceProvider::new(uri, open_proxy, enable_statistics, stats_provider_addr);
-    server.run().await;
+    "#,
+        crate_version!()
+    )
 }
 
 fn setup_logging() {
@@ -98,5 +58,7 @@ fn setup_logging() {
         .filter_module("reqwest", log::LevelFilter::Warn)
         .filter_module("mio", log::LevelFilter::Warn)
         .filter_module("want", log::LevelFilter::Warn)
+        .filter_module("tungstenite", log::LevelFilter::Warn)
+        .filter_module("tokio_tungstenite", log::LevelFilter::Warn)
         .init();
 }
@@ -0,0 +1,202 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::websocket::TSWebsocketStream;
+use client_core::client::inbound_messages::{InputMessage, InputMessageSender};
+use futures::channel::mpsc;
+use futures::stream::SplitSink;
+use futures::SinkExt;
+use nymsphinx::addressing::clients::Recipient;
+use nymsphinx::anonymous_replies::AnonymousSenderTag;
+use socks5_requests::Message as Socks5Message;
+use std::hash::{Hash, Hasher};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use websocket_requests::requests::ClientRequest;
+
+pub(crate) type MixnetMessageSender = mpsc::UnboundedSender<(Socks5Message, ReturnAddress)>;
+pub(crate) type MixnetMessageReceiver = mpsc::UnboundedReceiver<(Socks5Message, ReturnAddress)>;
+
+/// Destination of all responses produced for a particular request.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReturnAddress {
+    /// Address the client has explicitly included in its request.
+    Known(Recipient),
+
+    /// Tag of the anonymous client that has attached reply SURBs to its request instead.
+    /// Our client keeps track of the received SURBs and asks for more once they run low.
+    Anonymous(AnonymousSenderTag),
+}
+
+// `Recipient` does not implement `Eq` nor `Hash`, so compare the byte representations instead
+impl PartialEq for ReturnAddress {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ReturnAddress::Known(a), ReturnAddress::Known(b)) => a.to_bytes() == b.to_bytes(),
+            (ReturnAddress::Anonymous(a), ReturnAddress::Anonymous(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ReturnAddress {}
+
+impl Hash for ReturnAddress {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            ReturnAddress::Known(recipient) => recipient.to_bytes().hash(state),
+            ReturnAddress::Anonymous(sender_tag) => sender_tag.hash(state),
+        }
+    }
+}
+
+impl From<Recipient> for ReturnAddress {
+    fn from(recipient: Recipient) -> Self {
+        ReturnAddress::Known(recipient)
+    }
+}
+
+impl ReturnAddress {
+    /// Resolves the destination of responses to the request, i.e. either the address included
+    /// in it or the tag of its anonymous sender.
+    pub(crate) fn new(
+        return_address: Option<Recipient>,
+        sender_tag: Option<AnonymousSenderTag>,
+    ) -> Option<Self> {
+        match (return_address, sender_tag) {
+            (Some(recipient), _) => Some(ReturnAddress::Known(recipient)),
+            (None, Some(sender_tag)) => Some(ReturnAddress::Anonymous(sender_tag)),
+            (None, None) => None,
+        }
+    }
+
+    /// Creates the input message for our client to send the message back to this address.
+    pub(crate) fn send_back_to(self, message: Vec<u8>) -> InputMessage {
+        match self {
+            ReturnAddress::Known(recipient) => InputMessage::new_fresh(recipient, message, false),
+            ReturnAddress::Anonymous(sender_tag) => {
+                InputMessage::new_tagged_reply(sender_tag, message)
+            }
+        }
+    }
+
+    /// Creates the request for a native client to send the message back to this address.
+    pub(crate) fn websocket_request(self, message: Vec<u8>) -> ClientRequest {
+        match self {
+            ReturnAddress::Known(recipient) => ClientRequest::Send {
+                recipient,
+                message,
+                with_reply_surb: false,
+                mix_hops: None,
+            },
+            ReturnAddress::Anonymous(sender_tag) => ClientRequest::ReplyWithTag {
+                sender_tag,
+                message,
+            },
+        }
+    }
+}
+
+/// Mixnet client through which all responses are sent back.
+pub(crate) enum ResponseSink {
+    /// Client embedded in this binary.
+    Embedded(InputMessageSender),
+
+    /// Separately running native client, talked to over its websocket.
+    NativeClient(SplitSink<TSWebsocketStream, Message>),
+}
+
+impl ResponseSink {
+    pub(crate) async fn send(&mut self, message: Vec<u8>, return_address: ReturnAddress) {
+        match self {
+            ResponseSink::Embedded(input_sender) => input_sender
+                .unbounded_send(return_address.send_back_to(message))
+                .unwrap(),
+            ResponseSink::NativeClient(websocket_writer) => {
+                let request = return_address.websocket_request(message);
+                websocket_writer
+                    .send(Message::Binary(request.serialize()))
+                    .await
+                    .unwrap()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipient() -> Recipient {
+        Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap()
+    }
+
+    #[test]
+    fn sender_tag_is_only_used_without_explicit_return_address() {
+        let sender_tag = AnonymousSenderTag::from_bytes([42; 16]);
+
+        assert!(matches!(
+            ReturnAddress::new(Some(recipient()), Some(sender_tag)),
+            Some(ReturnAddress::Known(_))
+        ));
+        assert!(matches!(
+            ReturnAddress::new(None, Some(sender_tag)),
+            Some(ReturnAddress::Anonymous(tag)) if tag == sender_tag
+        ));
+        assert!(ReturnAddress::new(None, None).is_none());
+    }
+
+    #[test]
+    fn anonymous_responses_are_sent_using_reply_surbs() {
+        let sender_tag = AnonymousSenderTag::from_bytes([42; 16]);
+
+        match ReturnAddress::Anonymous(sender_tag).send_back_to(vec![1, 2, 3]) {
+            InputMessage::TaggedReply {
+                sender_tag: tag,
+                data,
+                ..
+            } => {
+                assert_eq!(sender_tag, tag);
+                assert_eq!(vec![1, 2, 3], data);
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(matches!(
+            ReturnAddress::Known(recipient()).send_back_to(vec![1, 2, 3]),
+            InputMessage::Fresh { .. }
+        ));
+    }
+
+    #[test]
+    fn native_client_is_asked_to_reply_with_tag_to_anonymous_senders() {
+        let sender_tag = AnonymousSenderTag::from_bytes([42; 16]);
+
+        // make sure the request survives the trip over the websocket
+        let request = ReturnAddress::Anonymous(sender_tag).websocket_request(vec![1, 2, 3]);
+        match ClientRequest::deserialize(&request.serialize()).unwrap() {
+            ClientRequest::ReplyWithTag {
+                sender_tag: tag,
+                message,
+            } => {
+                assert_eq!(sender_tag, tag);
+                assert_eq!(vec![1, 2, 3], message);
+            }
+            _ => unreachable!(),
+        }
+
+        let request = ReturnAddress::Known(recipient()).websocket_request(vec![1, 2, 3]);
+        match ClientRequest::deserialize(&request.serialize()).unwrap() {
+            ClientRequest::Send {
+                recipient: destination,
+                message,
+                with_reply_surb,
+                ..
+            } => {
+                assert_eq!(recipient().to_bytes(), destination.to_bytes());
+                assert_eq!(vec![1, 2, 3], message);
+                assert!(!with_reply_surb);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
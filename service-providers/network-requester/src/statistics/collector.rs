@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
-use futures::channel::mpsc;
 use log::*;
 use rand::RngCore;
 use serde::Deserialize;
@@ -25,6 +24,7 @@ use statistics_common::{
 };
 
 use super::error::StatsError;
+use crate::reply::MixnetMessageSender;
 
 const REMOTE_SOURCE_OF_STATS_PROVIDER_CONFIG: &str =
     "https://nymtech.net/.wellknown/network-requester/stats-provider.json";
@@ -76,14 +76,15 @@ pub struct ServiceStatisticsCollector {
     pub(crate) request_stats_data: Arc<RwLock<StatsData>>,
     pub(crate) response_stats_data: Arc<RwLock<StatsData>>,
     pub(crate) connected_services: Arc<RwLock<HashMap<ConnectionId, RemoteAddress>>>,
+    limited_requests: Arc<RwLock<HashMap<RemoteAddress, u32>>>,
     stats_provider_addr: Recipient,
-    mix_input_sender: mpsc::UnboundedSender<(Socks5Message, Recipient)>,
+    mix_input_sender: MixnetMessageSender,
 }
 
 impl ServiceStatisticsCollector {
     pub async fn new(
         stats_provider_addr: Option<Recipient>,
-        mix_input_sender: mpsc::UnboundedSender<(Socks5Message, Recipient)>,
+        mix_input_sender: MixnetMessageSender,
     ) -> Result<Self, StatsError> {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(3))
@@ -107,10 +108,21 @@ impl ServiceStatisticsCollector {
             request_stats_data: Arc::new(RwLock::new(StatsData::new())),
             response_stats_data: Arc::new(RwLock::new(StatsData::new())),
             connected_services: Arc::new(RwLock::new(HashMap::new())),
+            limited_requests: Arc::new(RwLock::new(HashMap::new())),
             stats_provider_addr,
             mix_input_sender,
         })
     }
+
+    /// Records a request to the remote that got rejected due to the client exceeding its limits.
+    pub(crate) async fn limit_hit(&self, remote_addr: &str) {
+        *self
+            .limited_requests
+            .write()
+            .await
+            .entry(remote_addr.to_string())
+            .or_default() += 1;
+    }
 }
 
 #[async_trait]
@@ -123,10 +135,12 @@ impl StatisticsCollector for ServiceStatisticsCollector {
         let stats_data = {
             let request_data_bytes = self.request_stats_data.read().await;
             let response_data_bytes = self.response_stats_data.read().await;
+            let limited_requests = self.limited_requests.read().await;
             let services: HashSet<String> = request_data_bytes
                 .client_processed_bytes
                 .keys()
                 .chain(response_data_bytes.client_processed_bytes.keys())
+                .chain(limited_requests.keys())
                 .cloned()
                 .collect();
             services
@@ -142,10 +156,15 @@ impl StatisticsCollector for ServiceStatisticsCollector {
                         .get(&requested_service)
                         .copied()
                         .unwrap_or(0);
+                    let limited = limited_requests
+                        .get(&requested_service)
+                        .copied()
+                        .unwrap_or(0);
                     statistics_common::StatsData::Service(StatsServiceData::new(
                         requested_service,
                         request_bytes,
                         response_bytes,
+                        limited,
                     ))
                 })
                 .collect()
@@ -178,7 +197,7 @@ impl StatisticsCollector for ServiceStatisticsCollector {
         self.mix_input_sender
             .unbounded_send((
                 Socks5Message::Request(connect_req),
-                self.stats_provider_addr,
+                self.stats_provider_addr.into(),
             ))
             .unwrap();
 
@@ -187,7 +206,10 @@ impl StatisticsCollector for ServiceStatisticsCollector {
         let ordered_msg = message_sender.wrap_message(msg).into_bytes();
         let send_req = Request::new_send(conn_id, ordered_msg, true);
         self.mix_input_sender
-            .unbounded_send((Socks5Message::Request(send_req), self.stats_provider_addr))
+            .unbounded_send((
+                Socks5Message::Request(send_req),
+                self.stats_provider_addr.into(),
+            ))
             .unwrap();
 
         Ok(())
@@ -206,5 +228,6 @@ impl StatisticsCollector for ServiceStatisticsCollector {
             .client_processed_bytes
             .iter_mut()
             .for_each(|(_, b)| *b = 0);
+        self.limited_requests.write().await.clear();
     }
 }
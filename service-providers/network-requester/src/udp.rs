@@ -0,0 +1,271 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::exit_policy::SharedExitPolicy;
+use crate::reply::{MixnetMessageSender, ReturnAddress};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use log::*;
+use socks5_requests::{ConnectionId, DatagramResponse, Message as Socks5Message, RemoteAddress};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+// maximum size of a UDP datagram we're willing to receive from the remote
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Channel for passing datagrams (alongside their destination) to an active `UdpAssociation`.
+pub(crate) type DatagramSender = mpsc::UnboundedSender<(RemoteAddress, Vec<u8>)>;
+type DatagramReceiver = mpsc::UnboundedReceiver<(RemoteAddress, Vec<u8>)>;
+
+/// Channel for passing datagrams, whose destination has already been resolved, back to the
+/// `UdpAssociation` so that it would never have to wait for DNS itself.
+type ResolvedDatagramSender = mpsc::UnboundedSender<(SocketAddr, Vec<u8>)>;
+
+/// An outbound UDP socket used on behalf of a single socks5 UDP association.
+/// All datagrams received on it are sent back to the `return_address`.
+pub(crate) struct UdpAssociation {
+    id: ConnectionId,
+    socket: UdpSocket,
+    return_address: ReturnAddress,
+    // if nothing was sent nor received on the association in this time, we assume the client
+    // is no longer interested in it
+    idle_timeout: Duration,
+    exit_policy: SharedExitPolicy,
+    // whether the socket is bound to the ipv6 wildcard address and thus can reach ipv6 remotes
+    // as well as ipv4 ones (through the ipv4-mapped addresses)
+    dual_stack: bool,
+}
+
+impl UdpAssociation {
+    pub(crate) async fn new(
+        id: ConnectionId,
+        return_address: ReturnAddress,
+        idle_timeout: Duration,
+        exit_policy: SharedExitPolicy,
+    ) -> std::io::Result<Self> {
+        // dual-stack socket so that we could reach both ipv4 and ipv6 remotes,
+        // unless ipv6 is disabled on this machine altogether
+        let (socket, dual_stack) =
+            match UdpSocket::bind(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)).await {
+                Ok(socket) => (socket, true),
+                Err(err) => {
+                    debug!(
+                        "failed to bind a dual-stack udp socket ({}) - falling back to ipv4 only",
+                        err
+                    );
+                    let ipv4_socket =
+                        UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)).await?;
+                    (ipv4_socket, false)
+                }
+            };
+
+        Ok(UdpAssociation {
+            id,
+            socket,
+            return_address,
+            idle_timeout,
+            exit_policy,
+            dual_stack,
+        })
+    }
+
+    /// Picks the first of the resolved addresses the socket can use and the exit policy allows,
+    /// mapped to ipv6 if the socket is dual-stack.
+    fn select_target(
+        mut addresses: impl Iterator<Item = SocketAddr>,
+        dual_stack: bool,
+        exit_policy: &SharedExitPolicy,
+    ) -> Option<SocketAddr> {
+        let target =
+            addresses.find(|a| (dual_stack || a.is_ipv4()) && exit_policy.allows_address(*a))?;
+
+        Some(match target {
+            SocketAddr::V4(v4) if dual_stack => {
+                SocketAddr::new(v4.ip().to_ipv6_mapped().into(), v4.port())
+            }
+            target => target,
+        })
+    }
+
+    async fn send_datagram(&self, remote_addr: &str, target: SocketAddr, data: &[u8]) {
+        if let Err(err) = self.socket.send_to(data, target).await {
+            debug!("failed to send datagram to {} - {}", remote_addr, err)
+        }
+    }
+
+    /// Sends the datagram straight away if its destination is an ip address. Otherwise the domain
+    /// is resolved in a separate task, which passes the datagram back through `resolved_sender`.
+    async fn handle_outbound_datagram(
+        &self,
+        remote_addr: RemoteAddress,
+        data: Vec<u8>,
+        resolved_sender: &ResolvedDatagramSender,
+    ) {
+        // resolve the address ourselves so that we could pick an address the socket can use
+        // (and make sure the domain doesn't resolve to an address rejected by the exit policy)
+        if let Ok(address) = remote_addr.parse::<SocketAddr>() {
+            match Self::select_target(std::iter::once(address), self.dual_stack, &self.exit_policy)
+            {
+                Some(target) => self.send_datagram(&remote_addr, target, &data).await,
+                None => debug!("{} is not a reachable and allowed address", remote_addr),
+            }
+            return;
+        }
+
+        let dual_stack = self.dual_stack;
+        let exit_policy = self.exit_policy.clone();
+        let resolved_sender = resolved_sender.clone();
+        tokio::spawn(async move {
+            let addresses = match tokio::net::lookup_host(&remote_addr).await {
+                Ok(addresses) => addresses,
+                Err(err) => {
+                    debug!("failed to resolve {} - {}", remote_addr, err);
+                    return;
+                }
+            };
+
+            match Self::select_target(addresses, dual_stack, &exit_policy) {
+                // if the association is already gone, there's nothing left to do with the datagram
+                Some(target) => {
+                    let _ = resolved_sender.unbounded_send((target, data));
+                }
+                None => debug!(
+                    "{} did not resolve to any reachable and allowed address",
+                    remote_addr
+                ),
+            }
+        });
+    }
+
+    fn source_address(source: SocketAddr) -> RemoteAddress {
+        // undo the mapping so the client would see the address it has actually sent data to
+        match source {
+            SocketAddr::V6(v6) => match v6.ip().to_ipv4() {
+                Some(v4) if v6.ip().segments()[..6] == [0, 0, 0, 0, 0, 0xffff] => {
+                    SocketAddr::new(v4.into(), v6.port()).to_string()
+                }
+                _ => source.to_string(),
+            },
+            v4 => v4.to_string(),
+        }
+    }
+
+    /// Runs the association until it becomes idle or the datagram channel gets closed.
+    pub(crate) async fn run(
+        self,
+        mut datagram_receiver: DatagramReceiver,
+        mix_sender: MixnetMessageSender,
+    ) {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let (resolved_sender, mut resolved_receiver) = mpsc::unbounded();
+
+        loop {
+            tokio::select! {
+                outbound = datagram_receiver.next() => match outbound {
+                    Some((remote_addr, data)) => {
+                        self.handle_outbound_datagram(remote_addr, data, &resolved_sender).await
+                    }
+                    None => break,
+                },
+                // we hold the sender ourselves, so the channel can never get closed
+                Some((target, data)) = resolved_receiver.next() => {
+                    self.send_datagram(&target.to_string(), target, &data).await
+                },
+                inbound = self.socket.recv_from(&mut buf) => match inbound {
+                    Ok((n, source)) => {
+                        let response = DatagramResponse::new(
+                            self.id,
+                            Self::source_address(source),
+                            buf[..n].to_vec(),
+                        );
+                        mix_sender
+                            .unbounded_send((
+                                Socks5Message::DatagramResponse(response),
+                                self.return_address,
+                            ))
+                            .unwrap();
+                    }
+                    Err(err) => {
+                        warn!("failed to read from udp socket of association {} - {}", self.id, err);
+                        break;
+                    }
+                },
+                _ = tokio::time::sleep(self.idle_timeout) => {
+                    debug!("udp association {} has become idle", self.id);
+                    break;
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn start(
+        id: ConnectionId,
+        return_address: ReturnAddress,
+        idle_timeout: Duration,
+        exit_policy: SharedExitPolicy,
+        mix_sender: MixnetMessageSender,
+    ) -> std::io::Result<DatagramSender> {
+        let association =
+            UdpAssociation::new(id, return_address, idle_timeout, exit_policy).await?;
+        let (datagram_sender, datagram_receiver) = mpsc::unbounded();
+
+        tokio::spawn(async move {
+            info!("Starting udp association {}", id);
+            association.run(datagram_receiver, mix_sender).await;
+            info!("Udp association {} is finished", id);
+        });
+
+        Ok(datagram_sender)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_is_the_first_reachable_and_allowed_address() {
+        let exit_policy = SharedExitPolicy::default();
+        let addresses: Vec<SocketAddr> = vec![
+            "[2001:db8::1]:53".parse().unwrap(),
+            "1.2.3.4:53".parse().unwrap(),
+        ];
+
+        assert_eq!(
+            UdpAssociation::select_target(addresses.clone().into_iter(), false, &exit_policy),
+            Some("1.2.3.4:53".parse().unwrap())
+        );
+        assert_eq!(
+            UdpAssociation::select_target(addresses.into_iter().rev(), true, &exit_policy),
+            Some("[::ffff:1.2.3.4]:53".parse().unwrap())
+        );
+        assert_eq!(
+            UdpAssociation::select_target(
+                std::iter::once("[2001:db8::1]:53".parse().unwrap()),
+                false,
+                &exit_policy
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn source_address_of_mapped_ipv4_is_unmapped() {
+        let mapped: SocketAddr = "[::ffff:1.2.3.4]:53".parse().unwrap();
+        assert_eq!("1.2.3.4:53", UdpAssociation::source_address(mapped));
+    }
+
+    #[test]
+    fn source_address_of_ipv6_is_unchanged() {
+        let v6: SocketAddr = "[2001:db8::1]:53".parse().unwrap();
+        assert_eq!("[2001:db8::1]:53", UdpAssociation::source_address(v6));
+
+        // note: `to_ipv4` would have also converted this one (ipv4-compatible address)
+        let compatible: SocketAddr = "[::1.2.3.4]:53".parse().unwrap();
+        assert_eq!(
+            compatible.to_string(),
+            UdpAssociation::source_address(compatible)
+        );
+    }
+}
@@ -0,0 +1,421 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Subcommand};
+use mixnet_contract_common::ExecuteMsg;
+use network_defaults::NymNetworkDetails;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use validator_client::nymd::cosmwasm_client::types::SignerData;
+use validator_client::nymd::offline_signing::{
+    DecodedMessage, SignedTransaction, UnsignedTransaction,
+};
+use validator_client::nymd::wallet::DirectSecp256k1HdWallet;
+use validator_client::nymd::{self, tx, AccountId, Coin, Gas, NymdClient};
+use vesting_contract_common::ExecuteMsg as VestingExecuteMsg;
+
+#[derive(Subcommand)]
+pub(crate) enum Commands {
+    /// Queries the chain for the account number and the sequence required for signing offline
+    AccountInfo(AccountInfo),
+    /// Builds an unsigned transaction executing the provided mixnet or vesting contract message
+    Build(Build),
+    /// Signs a previously built transaction without contacting the chain
+    Sign(Sign),
+    /// Broadcasts a signed transaction to the chain
+    Broadcast(Broadcast),
+}
+
+pub(crate) async fn execute(command: Commands, network_details: &NymNetworkDetails) -> Result<()> {
+    match command {
+        Commands::AccountInfo(args) => args.execute(network_details).await,
+        Commands::Build(args) => args.execute(network_details),
+        Commands::Sign(args) => args.execute(network_details),
+        Commands::Broadcast(args) => args.execute(network_details).await,
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Contract {
+    Mixnet,
+    Vesting,
+}
+
+impl FromStr for Contract {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mixnet" => Ok(Contract::Mixnet),
+            "vesting" => Ok(Contract::Vesting),
+            other => Err(format!(
+                "{} is not a supported contract - use either 'mixnet' or 'vesting'",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct AccountInfo {
+    /// Address of the account that is going to sign the transaction
+    #[clap(long)]
+    address: String,
+
+    /// Nymd endpoint to use instead of the one configured for the network
+    #[clap(long)]
+    nymd_url: Option<String>,
+}
+
+impl AccountInfo {
+    async fn execute(&self, network_details: &NymNetworkDetails) -> Result<()> {
+        let address = parse_account(&self.address)?;
+        let client = connect(network_details, self.nymd_url.as_deref())?;
+
+        let sequence = client.get_sequence(&address).await?;
+        let chain_id = client.get_chain_id().await?;
+
+        println!("chain id: {}", chain_id);
+        println!("account number: {}", sequence.account_number);
+        println!("sequence: {}", sequence.sequence);
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct Build {
+    /// Address of the account that is going to sign the transaction
+    #[clap(long)]
+    signer: String,
+
+    /// Contract the message is meant for, either 'mixnet' or 'vesting'
+    #[clap(long)]
+    contract: Contract,
+
+    /// JSON representation of the contract's `ExecuteMsg`
+    #[clap(long)]
+    msg: String,
+
+    /// Funds to send alongside the message, for example '100000000unym'
+    #[clap(long)]
+    funds: Option<String>,
+
+    /// Memo attached to the transaction
+    #[clap(long, default_value = "")]
+    memo: String,
+
+    /// Path to the file the unsigned transaction is going to be written to
+    #[clap(long)]
+    output: PathBuf,
+}
+
+impl Build {
+    fn execute(&self, network_details: &NymNetworkDetails) -> Result<()> {
+        let signer = parse_account(&self.signer)?;
+        let funds = self.funds.as_deref().map(parse_coin).transpose()?;
+        let config = nymd::Config::try_from_nym_network_details(network_details)?;
+
+        let mut unsigned = UnsignedTransaction::new(&signer, self.memo.clone());
+        match self.contract {
+            Contract::Mixnet => {
+                let msg: ExecuteMsg = serde_json::from_str(&self.msg)?;
+                unsigned.add_mixnet_contract_execution(
+                    &config,
+                    &msg,
+                    funds.into_iter().collect(),
+                )?
+            }
+            Contract::Vesting => {
+                let msg: VestingExecuteMsg = serde_json::from_str(&self.msg)?;
+                unsigned.add_vesting_contract_execution(
+                    &config,
+                    &msg,
+                    funds.into_iter().collect(),
+                )?
+            }
+        }
+
+        write_json(&self.output, &unsigned)?;
+        println!("unsigned transaction written to {}", self.output.display());
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct Sign {
+    /// Path to the unsigned transaction
+    #[clap(long)]
+    input: PathBuf,
+
+    /// Path to the file containing the mnemonic of the signing account
+    #[clap(long)]
+    mnemonic_file: PathBuf,
+
+    /// Chain id of the network the transaction is meant for
+    #[clap(long)]
+    chain_id: String,
+
+    /// Account number of the signing account
+    #[clap(long)]
+    account_number: u64,
+
+    /// Sequence of the signing account the transaction is going to be included with
+    #[clap(long)]
+    sequence: u64,
+
+    /// Fee paid for the transaction, for example '5000unym'
+    #[clap(long)]
+    fee: String,
+
+    /// Gas limit of the transaction
+    #[clap(long)]
+    gas_limit: u64,
+
+    /// Path to the file the signed transaction is going to be written to
+    #[clap(long)]
+    output: PathBuf,
+
+    /// Sign the transaction even if it contains messages of types that cannot be decoded
+    /// and thus reviewed
+    #[clap(long)]
+    allow_unknown_messages: bool,
+
+    /// Do not ask for confirmation before signing the transaction
+    #[clap(long)]
+    yes: bool,
+}
+
+impl Sign {
+    fn execute(&self, network_details: &NymNetworkDetails) -> Result<()> {
+        let unsigned: UnsignedTransaction = read_json(&self.input)?;
+
+        let mnemonic = fs::read_to_string(&self.mnemonic_file)?;
+        let mnemonic = bip39::Mnemonic::from_str(mnemonic.trim())?;
+        let wallet = DirectSecp256k1HdWallet::from_mnemonic(
+            &network_details.chain_details.bech32_account_prefix,
+            mnemonic,
+        )?;
+
+        let fee =
+            tx::Fee::from_amount_and_gas(parse_coin(&self.fee)?.into(), Gas::from(self.gas_limit));
+        let signer_data = SignerData {
+            account_number: self.account_number,
+            sequence: self.sequence,
+            chain_id: self
+                .chain_id
+                .parse()
+                .map_err(|_| anyhow!("{} is not a valid chain id", self.chain_id))?,
+        };
+
+        let messages = unsigned.decoded_messages()?;
+        println!("signing transaction of {}", unsigned.signer_address()?);
+        println!("memo: {}", unsigned.memo());
+        println!("fee: {} (gas limit: {})", self.fee, self.gas_limit);
+        for message in &messages {
+            println!("{}", describe_message(message));
+        }
+
+        let unknown_messages = messages
+            .iter()
+            .filter(|message| matches!(message, DecodedMessage::Unknown { .. }))
+            .count();
+        if unknown_messages > 0 && !self.allow_unknown_messages {
+            bail!(
+                "the transaction contains {} message(s) of unknown type that cannot be reviewed - use --allow-unknown-messages to sign it anyway",
+                unknown_messages
+            )
+        }
+
+        if !self.yes && !confirm(io::stdin().lock(), io::stdout())? {
+            bail!("signing got aborted")
+        }
+
+        let signed = unsigned.sign(&wallet, fee, signer_data)?;
+        write_json(&self.output, &signed)?;
+        println!(
+            "signed transaction {} written to {}",
+            signed.hash(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct Broadcast {
+    /// Path to the signed transaction
+    #[clap(long)]
+    input: PathBuf,
+
+    /// Nymd endpoint to use instead of the one configured for the network
+    #[clap(long)]
+    nymd_url: Option<String>,
+}
+
+impl Broadcast {
+    async fn execute(&self, network_details: &NymNetworkDetails) -> Result<()> {
+        let signed: SignedTransaction = read_json(&self.input)?;
+        let client = connect(network_details, self.nymd_url.as_deref())?;
+
+        println!("broadcasting transaction {}...", signed.hash());
+        let response = client.broadcast_signed_transaction(signed).await?;
+        println!(
+            "transaction {} got included in block {}",
+            response.hash, response.height
+        );
+        Ok(())
+    }
+}
+
+fn connect(
+    network_details: &NymNetworkDetails,
+    nymd_url: Option<&str>,
+) -> Result<NymdClient<nymd::QueryNymdClient>> {
+    let nymd_url = match nymd_url {
+        Some(nymd_url) => nymd_url.to_owned(),
+        None => match network_details.endpoints.first() {
+            Some(endpoint) => endpoint.nymd_url.clone(),
+            None => bail!("no nymd endpoint is configured for the network"),
+        },
+    };
+    let config = nymd::Config::try_from_nym_network_details(network_details)?;
+    Ok(NymdClient::connect(config, nymd_url.as_str())?)
+}
+
+fn describe_message(message: &DecodedMessage) -> String {
+    let format_coins = |coins: &[Coin]| {
+        if coins.is_empty() {
+            "none".to_owned()
+        } else {
+            coins
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    match message {
+        DecodedMessage::ExecuteContract {
+            sender,
+            contract,
+            msg,
+            funds,
+        } => format!(
+            "execute contract {} as {}\n  msg: {}\n  funds: {}",
+            contract,
+            sender,
+            msg,
+            format_coins(funds)
+        ),
+        DecodedMessage::Send {
+            from_address,
+            to_address,
+            amount,
+        } => format!(
+            "send {} from {} to {}",
+            format_coins(amount),
+            from_address,
+            to_address
+        ),
+        DecodedMessage::Unknown { type_url } => {
+            format!(
+                "message of unknown type {} - its content cannot be shown",
+                type_url
+            )
+        }
+    }
+}
+
+/// Asks whether the transaction should be signed. Anything other than an explicit 'y' or 'yes'
+/// is treated as a refusal.
+fn confirm<R, W>(mut input: R, mut output: W) -> io::Result<bool>
+where
+    R: BufRead,
+    W: Write,
+{
+    write!(output, "sign the above transaction? [y/N]: ")?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn parse_account(raw: &str) -> Result<AccountId> {
+    raw.parse()
+        .map_err(|_| anyhow!("{} is not a valid account address", raw))
+}
+
+fn parse_coin(raw: &str) -> Result<Coin> {
+    let denom_start = raw
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("{} does not specify the denomination", raw))?;
+    let (amount, denom) = raw.split_at(denom_start);
+    let amount = amount
+        .parse()
+        .map_err(|_| anyhow!("{} does not specify a valid amount", raw))?;
+    Ok(Coin::new(amount, denom))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    Ok(fs::write(path, serde_json::to_string_pretty(value)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_coins() {
+        let coin = parse_coin("5000unym").unwrap();
+        assert_eq!(coin, Coin::new(5000, "unym"));
+
+        assert!(parse_coin("unym").is_err());
+        assert!(parse_coin("5000").is_err());
+        assert!(parse_coin("").is_err());
+    }
+
+    #[test]
+    fn describing_contract_executions() {
+        let message = DecodedMessage::ExecuteContract {
+            sender: "n1h5hgn94nsq4kh99rjj794hr5h5q6yfm2lr52es".parse().unwrap(),
+            contract: "n14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sjyvg3g"
+                .parse()
+                .unwrap(),
+            msg: serde_json::json!({ "unbond_mixnode": {} }),
+            funds: vec![Coin::new(100, "unym")],
+        };
+
+        let description = describe_message(&message);
+        assert!(
+            description.contains("n14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sjyvg3g")
+        );
+        assert!(description.contains(r#"{"unbond_mixnode":{}}"#));
+        assert!(description.contains("100unym"));
+    }
+
+    #[test]
+    fn only_explicit_confirmation_is_accepted() {
+        for (answer, expected) in [
+            ("y\n", true),
+            ("YES\n", true),
+            ("n\n", false),
+            ("\n", false),
+            ("", false),
+            ("sure\n", false),
+        ] {
+            let mut output = Vec::new();
+            assert_eq!(confirm(answer.as_bytes(), &mut output).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Utility for signing mixnet and vesting contract transactions on a machine without network access.
+//!
+//! The intended flow is:
+//! 1. `account-info` (online) to learn the account number and the current sequence of the signer,
+//! 2. `build` (online or offline) to create the unsigned transaction file,
+//! 3. `sign` (offline) to review the decoded messages and sign them with the mnemonic of the signer,
+//! 4. `broadcast` (online) to submit the signed transaction file to the chain.
+
+use anyhow::Result;
+use clap::Parser;
+use network_defaults::{setup_env, NymNetworkDetails};
+use std::path::PathBuf;
+
+mod commands;
+
+#[derive(Parser)]
+#[clap(author = "Nymtech", version, about)]
+struct Cli {
+    /// Path pointing to an env file that configures the network.
+    #[clap(short, long)]
+    config_env_file: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: commands::Commands,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup_logging();
+    let args = Cli::parse();
+    setup_env(args.config_env_file);
+
+    let network_details = NymNetworkDetails::new_from_env();
+    commands::execute(args.command, &network_details).await
+}
+
+fn setup_logging() {
+    let mut log_builder = pretty_env_logger::formatted_timed_builder();
+    if let Ok(s) = ::std::env::var("RUST_LOG") {
+        log_builder.parse_filters(&s);
+    } else {
+        // default to 'Warn' so that the output of the commands is not cluttered
+        log_builder.filter(None, log::LevelFilter::Warn);
+    }
+
+    log_builder.init();
+}
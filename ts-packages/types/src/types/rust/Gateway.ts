@@ -2,6 +2,7 @@ export interface Gateway {
   host: string;
   mix_port: number;
   clients_port: number;
+  http_api_port: number;
   location: string;
   sphinx_key: string;
   identity_key: string;
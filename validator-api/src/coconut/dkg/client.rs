@@ -0,0 +1,31 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::error::Result;
+use coconut_dkg_common::dealer::{ContractDealing, DealerDetails, DealerDetailsResponse};
+use coconut_dkg_common::types::{
+    EncodedBTEPublicKeyWithProof, EncodedVerificationKeyShare, Epoch, Threshold,
+};
+use coconut_dkg_common::verification_key::ContractVKShare;
+use cosmwasm_std::Binary;
+use validator_client::nymd::AccountId;
+
+#[async_trait]
+pub trait DkgClient {
+    async fn address(&self) -> AccountId;
+    async fn get_current_epoch(&self) -> Result<Epoch>;
+    async fn get_current_epoch_threshold(&self) -> Result<Option<Threshold>>;
+    async fn get_self_registered_dealer_details(&self) -> Result<DealerDetailsResponse>;
+    async fn get_current_dealers(&self) -> Result<Vec<DealerDetails>>;
+    async fn get_dealings(&self) -> Result<Vec<ContractDealing>>;
+    async fn get_verification_key_shares(&self) -> Result<Vec<ContractVKShare>>;
+    async fn register_dealer(
+        &self,
+        bte_key: EncodedBTEPublicKeyWithProof,
+        announce_address: String,
+    ) -> Result<()>;
+    async fn submit_dealings(&self, dealings: Vec<Binary>) -> Result<()>;
+    async fn submit_verification_key_share(&self, share: EncodedVerificationKeyShare)
+        -> Result<()>;
+    async fn advance_epoch_state(&self) -> Result<()>;
+}
@@ -0,0 +1,250 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::dkg::client::DkgClient;
+use crate::coconut::dkg::dealing::{
+    create_dealings, dealing_epoch, extract_receivers, verified_dealings,
+};
+use crate::coconut::dkg::key_derivation::derive_keypair;
+use crate::coconut::error::{CoconutError, Result};
+use coconut_dkg_common::dealer::DealerDetails;
+use coconut_dkg_common::types::{EpochState, Threshold};
+use coconut_interface::{Base58, KeyPair};
+use cosmwasm_std::Timestamp;
+use dkg::bte::encryption::BabyStepGiantStepLookup;
+use dkg::bte::{DecryptionKey, Params, PublicKeyWithProof};
+use log::{debug, info, warn};
+use rand::RngCore;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// Drives this validator api through the distributed key generation ceremony
+/// coordinated by the DKG contract.
+///
+/// All of its progress is recovered from the contract itself, so the ceremony can be safely
+/// resumed after a restart.
+pub(crate) struct DkgController<C, R> {
+    client: C,
+    rng: R,
+    params: Params,
+    decryption_key: DecryptionKey,
+    public_key: PublicKeyWithProof,
+    announce_address: Url,
+    key_size: usize,
+    polling_rate: Duration,
+
+    // the table is fairly expensive to compute, so it's only created once it's needed
+    lookup_table: Option<BabyStepGiantStepLookup>,
+    derived_keypair: Option<KeyPair>,
+}
+
+impl<C, R> DkgController<C, R>
+where
+    C: DkgClient + Send + Sync,
+    R: RngCore + Send,
+{
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        client: C,
+        mut rng: R,
+        params: Params,
+        mut decryption_key: DecryptionKey,
+        public_key: PublicKeyWithProof,
+        announce_address: Url,
+        key_size: usize,
+        polling_rate: Duration,
+    ) -> Result<Self> {
+        // the key is persisted at its root node, so it has to be updated every time it's loaded
+        decryption_key.try_update_to(dealing_epoch(), &params, &mut rng)?;
+
+        Ok(DkgController {
+            client,
+            rng,
+            params,
+            decryption_key,
+            public_key,
+            announce_address,
+            key_size,
+            polling_rate,
+            lookup_table: None,
+            derived_keypair: None,
+        })
+    }
+
+    fn current_timestamp() -> Timestamp {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the system clock is set before the unix epoch");
+        Timestamp::from_seconds(now.as_secs())
+    }
+
+    async fn registered_details(&self, state: EpochState) -> Result<DealerDetails> {
+        self.client
+            .get_self_registered_dealer_details()
+            .await?
+            .details
+            .ok_or(CoconutError::DkgCeremonyMissed { state })
+    }
+
+    async fn current_threshold(&self) -> Result<Threshold> {
+        self.client
+            .get_current_epoch_threshold()
+            .await?
+            .ok_or(CoconutError::UnknownDkgThreshold)
+    }
+
+    async fn register(&mut self) -> Result<()> {
+        if self
+            .client
+            .get_self_registered_dealer_details()
+            .await?
+            .details
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        info!("Registering as a dealer for the DKG ceremony");
+        let bte_key = bs58::encode(self.public_key.to_bytes()).into_string();
+        self.client
+            .register_dealer(bte_key, self.announce_address.to_string())
+            .await
+    }
+
+    async fn deal(&mut self) -> Result<()> {
+        let details = self.registered_details(EpochState::DealingExchange).await?;
+        let address = self.client.address().await.to_string();
+        if self
+            .client
+            .get_dealings()
+            .await?
+            .iter()
+            .any(|dealing| dealing.dealer.as_str() == address)
+        {
+            return Ok(());
+        }
+
+        let threshold = self.current_threshold().await?;
+        let dealers = self.client.get_current_dealers().await?;
+        let receivers = extract_receivers(&dealers);
+
+        info!(
+            "Submitting dealings for {} receivers with threshold {}",
+            receivers.len(),
+            threshold
+        );
+        let dealings = create_dealings(
+            &mut self.rng,
+            &self.params,
+            details.assigned_index,
+            threshold,
+            self.key_size,
+            &receivers,
+        );
+        self.client.submit_dealings(dealings).await
+    }
+
+    async fn obtain_keypair(&mut self, state: EpochState) -> Result<KeyPair> {
+        if let Some(keypair) = &self.derived_keypair {
+            return Ok(keypair.clone());
+        }
+
+        let details = self.registered_details(state).await?;
+        let threshold = self.current_threshold().await?;
+        let dealers = self.client.get_current_dealers().await?;
+        let receivers = extract_receivers(&dealers);
+        let dealings = verified_dealings(
+            &self.params,
+            threshold,
+            self.key_size,
+            &dealers,
+            &receivers,
+            self.client.get_dealings().await?,
+        );
+
+        info!(
+            "Deriving the coconut keypair out of dealings of {} dealers",
+            dealings.len()
+        );
+        let lookup_table = self
+            .lookup_table
+            .get_or_insert_with(BabyStepGiantStepLookup::default);
+        let keypair = derive_keypair(
+            &self.decryption_key,
+            lookup_table,
+            details.assigned_index,
+            threshold,
+            self.key_size,
+            &receivers,
+            dealings,
+        )?;
+        self.derived_keypair = Some(keypair.clone());
+        Ok(keypair)
+    }
+
+    async fn submit_verification_key_share(&mut self) -> Result<()> {
+        let address = self.client.address().await.to_string();
+        if self
+            .client
+            .get_verification_key_shares()
+            .await?
+            .iter()
+            .any(|share| share.owner.as_str() == address)
+        {
+            return Ok(());
+        }
+
+        let keypair = self
+            .obtain_keypair(EpochState::VerificationKeySubmission)
+            .await?;
+
+        info!("Submitting the verification key share");
+        self.client
+            .submit_verification_key_share(keypair.verification_key().to_bs58())
+            .await
+    }
+
+    /// Performs whatever action is required in the current state of the ceremony and attempts
+    /// to advance it if its deadline has passed.
+    ///
+    /// Returns the derived keypair once the ceremony is finished.
+    pub(crate) async fn handle_epoch_state(&mut self) -> Result<Option<KeyPair>> {
+        let epoch = self.client.get_current_epoch().await?;
+        debug!("DKG ceremony is in the {} state", epoch.state);
+
+        match epoch.state {
+            EpochState::PublicKeySubmission => self.register().await?,
+            EpochState::DealingExchange => self.deal().await?,
+            EpochState::VerificationKeySubmission => self.submit_verification_key_share().await?,
+            EpochState::Finished => {
+                return self.obtain_keypair(epoch.state).await.map(Some);
+            }
+        }
+
+        // anyone is allowed to advance the state once its time has passed, so it's perfectly
+        // fine if some other signer has beaten us to it
+        if epoch.can_advance(Self::current_timestamp()) {
+            if let Err(err) = self.client.advance_epoch_state().await {
+                debug!("Failed to advance the DKG epoch state - {}", err)
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Keeps on participating in the ceremony until it's finished and the keypair got derived.
+    pub(crate) async fn run_ceremony(mut self) -> Result<KeyPair> {
+        loop {
+            match self.handle_epoch_state().await {
+                Ok(Some(keypair)) => return Ok(keypair),
+                Ok(None) => (),
+                // network issues shouldn't make us give up on the ceremony
+                Err(CoconutError::NymdError(err)) => {
+                    warn!("Failed to interact with the DKG contract - {}", err)
+                }
+                Err(err) => return Err(err),
+            }
+            tokio::time::sleep(self.polling_rate).await;
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use coconut_dkg_common::dealer::{ContractDealing, DealerDetails};
+use cosmwasm_std::{Addr, Binary};
+use dkg::bte::{Params, PublicKey, PublicKeyWithProof};
+use dkg::{Dealing, NodeIndex, Threshold};
+use log::{debug, warn};
+use rand::RngCore;
+use std::collections::{BTreeMap, HashMap};
+
+// the ceremony is currently only ever run once, so all dealings are created for the same,
+// fixed, epoch of the forward-secure encryption scheme
+pub(crate) const DEALING_EPOCH: u32 = 1;
+
+pub(crate) fn dealing_epoch() -> dkg::bte::Epoch {
+    dkg::bte::Epoch::new(DEALING_EPOCH)
+}
+
+/// Determines the set of receivers of the dealings, i.e. all registered dealers
+/// that have submitted a valid BTE public key alongside a correct proof of its possession.
+///
+/// It's fully deterministic so that every participant ends up with an identical set.
+pub(crate) fn extract_receivers(dealers: &[DealerDetails]) -> BTreeMap<NodeIndex, PublicKey> {
+    let mut receivers = BTreeMap::new();
+    for dealer in dealers {
+        let public_key = match bs58::decode(&dealer.bte_public_key_with_proof)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| PublicKeyWithProof::try_from_bytes(&bytes).ok())
+        {
+            Some(public_key) => public_key,
+            None => {
+                warn!(
+                    "Dealer {} has submitted a malformed BTE public key",
+                    dealer.address
+                );
+                continue;
+            }
+        };
+        if !public_key.verify() {
+            warn!(
+                "Dealer {} has submitted an invalid proof of BTE key possession",
+                dealer.address
+            );
+            continue;
+        }
+        receivers.insert(dealer.assigned_index, *public_key.public_key());
+    }
+    receivers
+}
+
+/// Creates a dealing for each of the `key_size` secret scalars of the final coconut key.
+pub(crate) fn create_dealings(
+    mut rng: impl RngCore,
+    params: &Params,
+    dealer_index: NodeIndex,
+    threshold: Threshold,
+    key_size: usize,
+    receivers: &BTreeMap<NodeIndex, PublicKey>,
+) -> Vec<Binary> {
+    (0..key_size)
+        .map(|_| {
+            let (dealing, _) = Dealing::create(
+                &mut rng,
+                params,
+                dealer_index,
+                threshold,
+                dealing_epoch(),
+                receivers,
+                None,
+            );
+            dealing.to_bytes().into()
+        })
+        .collect()
+}
+
+/// Parses and verifies all dealings submitted to the contract and returns only the ones
+/// coming from dealers that have submitted exactly `key_size` valid dealings,
+/// keyed by the indices of those dealers.
+pub(crate) fn verified_dealings(
+    params: &Params,
+    threshold: Threshold,
+    key_size: usize,
+    dealers: &[DealerDetails],
+    receivers: &BTreeMap<NodeIndex, PublicKey>,
+    contract_dealings: Vec<ContractDealing>,
+) -> BTreeMap<NodeIndex, Vec<Dealing>> {
+    let dealer_indices: HashMap<&Addr, NodeIndex> = dealers
+        .iter()
+        .map(|dealer| (&dealer.address, dealer.assigned_index))
+        .collect();
+
+    let mut verified = BTreeMap::new();
+    for contract_dealing in contract_dealings {
+        let dealer_index = match dealer_indices.get(&contract_dealing.dealer) {
+            Some(index) if receivers.contains_key(index) => *index,
+            _ => {
+                debug!(
+                    "Ignoring dealings of {} as it's not a valid dealer",
+                    contract_dealing.dealer
+                );
+                continue;
+            }
+        };
+
+        if contract_dealing.dealings.len() != key_size {
+            warn!(
+                "Dealer {} has submitted {} dealings while {} were expected",
+                contract_dealing.dealer,
+                contract_dealing.dealings.len(),
+                key_size
+            );
+            continue;
+        }
+
+        let dealings = contract_dealing
+            .dealings
+            .iter()
+            .map(|raw| Dealing::try_from_bytes(raw.as_slice()))
+            .collect::<Result<Vec<_>, _>>();
+
+        let dealings = match dealings {
+            Ok(dealings) => dealings,
+            Err(err) => {
+                warn!(
+                    "Dealer {} has submitted a malformed dealing - {}",
+                    contract_dealing.dealer, err
+                );
+                continue;
+            }
+        };
+
+        if let Some(err) = dealings.iter().find_map(|dealing| {
+            dealing
+                .verify(params, dealing_epoch(), threshold, receivers, None)
+                .err()
+        }) {
+            warn!(
+                "Dealer {} has submitted an invalid dealing - {}",
+                contract_dealing.dealer, err
+            );
+            continue;
+        }
+
+        verified.insert(dealer_index, dealings);
+    }
+
+    verified
+}
@@ -0,0 +1,107 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::dkg::dealing::dealing_epoch;
+use crate::coconut::error::{CoconutError, Result};
+use bls12_381::G2Projective;
+use coconut_interface::error::CoconutInterfaceError;
+use coconut_interface::{KeyPair, Parameters, SecretKey};
+use dkg::bte::encryption::BabyStepGiantStepLookup;
+use dkg::bte::{decrypt_share, DecryptionKey, PublicKey};
+use dkg::{combine_shares, try_recover_verification_keys, Dealing, NodeIndex, Threshold};
+use std::collections::BTreeMap;
+
+/// Derives the coconut keypair of this signer out of the verified dealings of all other dealers.
+///
+/// `dealings` must only contain the dealings that have already been verified and each
+/// dealer is expected to have contributed exactly `key_size` of them.
+pub(crate) fn derive_keypair(
+    decryption_key: &DecryptionKey,
+    lookup_table: &BabyStepGiantStepLookup,
+    own_index: NodeIndex,
+    threshold: Threshold,
+    key_size: usize,
+    receivers: &BTreeMap<NodeIndex, PublicKey>,
+    dealings: BTreeMap<NodeIndex, Vec<Dealing>>,
+) -> Result<KeyPair> {
+    if dealings.len() < threshold as usize {
+        return Err(CoconutError::NotEnoughDealings {
+            available: dealings.len(),
+            threshold,
+        });
+    }
+
+    // position of our ciphertexts within each dealing
+    let receiver_position = receivers
+        .keys()
+        .position(|index| *index == own_index)
+        .ok_or(CoconutError::NotADkgReceiver)?;
+
+    let dealer_indices = dealings.keys().copied().collect::<Vec<_>>();
+    let dealers = receivers
+        .iter()
+        .filter(|(index, _)| dealings.contains_key(index))
+        .map(|(index, key)| (*index, *key))
+        .collect::<BTreeMap<_, _>>();
+    // we might have failed to submit our own dealings, in which case we can't cross-check
+    // our share against the recovered verification keys
+    let dealer_position = dealer_indices.iter().position(|index| *index == own_index);
+
+    // transpose the dealings so that they'd be grouped by the secret scalar they're contributing to
+    let mut component_dealings = (0..key_size)
+        .map(|_| Vec::with_capacity(dealings.len()))
+        .collect::<Vec<_>>();
+    for dealer_dealings in dealings.into_values() {
+        for (component, dealing) in dealer_dealings.into_iter().enumerate() {
+            component_dealings[component].push(dealing)
+        }
+    }
+
+    let mut secrets = Vec::with_capacity(key_size);
+    for dealings in component_dealings {
+        let shares = dealings
+            .iter()
+            .map(|dealing| {
+                decrypt_share(
+                    decryption_key,
+                    receiver_position,
+                    &dealing.ciphertexts,
+                    dealing_epoch(),
+                    Some(lookup_table),
+                )
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let secret = combine_shares(shares, &dealer_indices)?;
+
+        if let Some(position) = dealer_position {
+            let (_, verification_key_shares) =
+                try_recover_verification_keys(&dealings, threshold, &dealers)?;
+            if verification_key_shares[position] != G2Projective::generator() * secret {
+                return Err(CoconutError::MismatchedVerificationKeyShare);
+            }
+        }
+
+        secrets.push(secret)
+    }
+
+    // the dkg crate relies on a different version of the curve library than coconut does,
+    // so the scalars have to be converted via their byte representation
+    // x || ys.len() || ys
+    let ys_len = secrets.len() - 1;
+    let mut sk_bytes = Vec::with_capacity(8 + secrets.len() * 32);
+    sk_bytes.extend_from_slice(&secrets[0].to_bytes());
+    sk_bytes.extend_from_slice(&(ys_len as u64).to_le_bytes());
+    for y in &secrets[1..] {
+        sk_bytes.extend_from_slice(&y.to_bytes())
+    }
+
+    let secret_key = SecretKey::from_bytes(&sk_bytes).map_err(CoconutInterfaceError::from)?;
+    let params = Parameters::new(ys_len as u32).map_err(CoconutInterfaceError::from)?;
+    let verification_key = secret_key.verification_key(&params);
+
+    Ok(KeyPair::from_keys(
+        secret_key,
+        verification_key,
+        Some(own_index),
+    ))
+}
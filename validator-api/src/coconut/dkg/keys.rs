@@ -0,0 +1,48 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::error::Result;
+use dkg::bte::{keygen, DecryptionKey, Params, PublicKeyWithProof};
+use log::info;
+use rand::RngCore;
+use std::fs;
+use std::path::Path;
+
+/// Loads the BTE keys used for the distributed key generation from the provided paths,
+/// or generates (and stores) fresh ones if they do not exist yet.
+pub(crate) fn load_or_generate_bte_keys<P: AsRef<Path>>(
+    params: &Params,
+    decryption_key_path: P,
+    public_key_with_proof_path: P,
+    rng: impl RngCore,
+) -> Result<(DecryptionKey, PublicKeyWithProof)> {
+    let decryption_key_path = decryption_key_path.as_ref();
+    let public_key_with_proof_path = public_key_with_proof_path.as_ref();
+
+    if decryption_key_path.exists() && public_key_with_proof_path.exists() {
+        let decryption_key = load_bs58(decryption_key_path)?;
+        let public_key = load_bs58(public_key_with_proof_path)?;
+        return Ok((
+            DecryptionKey::try_from_bytes(&decryption_key)?,
+            PublicKeyWithProof::try_from_bytes(&public_key)?,
+        ));
+    }
+
+    info!("Generating fresh BTE keys for the DKG ceremony");
+    let (decryption_key, public_key) = keygen(params, rng);
+    fs::write(
+        decryption_key_path,
+        bs58::encode(decryption_key.to_bytes()).into_string(),
+    )?;
+    fs::write(
+        public_key_with_proof_path,
+        bs58::encode(public_key.to_bytes()).into_string(),
+    )?;
+
+    Ok((decryption_key, public_key))
+}
+
+fn load_bs58(path: &Path) -> Result<Vec<u8>> {
+    let encoded = fs::read_to_string(path)?;
+    Ok(bs58::decode(encoded.trim()).into_vec()?)
+}
@@ -0,0 +1,130 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+pub(crate) mod client;
+pub(crate) mod controller;
+mod dealing;
+mod key_derivation;
+pub(crate) mod keys;
+#[cfg(test)]
+mod tests;
+
+use crate::coconut::dkg::client::DkgClient;
+use crate::coconut::dkg::controller::DkgController;
+use crate::coconut::dkg::keys::load_or_generate_bte_keys;
+use crate::coconut::error::{CoconutError, Result};
+use crate::config::Config;
+use coconut_interface::{Base58, KeyPair};
+use credentials::coconut::bandwidth::TOTAL_ATTRIBUTES;
+use dkg::bte::setup;
+use log::{error, info};
+use rand::rngs::OsRng;
+use std::fs;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use validator_api_requests::coconut::DkgCeremonyStatus;
+
+// the secret key consists of the `x` scalar alongside a `y` scalar for each of the attributes
+const KEY_SIZE: usize = TOTAL_ATTRIBUTES as usize + 1;
+
+struct DkgStateInner {
+    status: DkgCeremonyStatus,
+    keypair: Option<KeyPair>,
+}
+
+/// Progress of the DKG ceremony alongside the coconut keypair derived out of it.
+///
+/// It is shared between the task running the ceremony and the coconut routes, which can only
+/// issue credentials once the keypair is available.
+#[derive(Clone)]
+pub(crate) struct DkgState {
+    inner: Arc<RwLock<DkgStateInner>>,
+}
+
+impl DkgState {
+    fn new(status: DkgCeremonyStatus, keypair: Option<KeyPair>) -> Self {
+        DkgState {
+            inner: Arc::new(RwLock::new(DkgStateInner { status, keypair })),
+        }
+    }
+
+    pub(crate) fn in_progress() -> Self {
+        DkgState::new(DkgCeremonyStatus::InProgress, None)
+    }
+
+    pub(crate) fn with_keypair(keypair: KeyPair) -> Self {
+        DkgState::new(DkgCeremonyStatus::Finished, Some(keypair))
+    }
+
+    pub(crate) async fn status(&self) -> DkgCeremonyStatus {
+        self.inner.read().await.status.clone()
+    }
+
+    pub(crate) async fn keypair(&self) -> Result<KeyPair> {
+        let inner = self.inner.read().await;
+        inner
+            .keypair
+            .clone()
+            .ok_or_else(|| CoconutError::KeyPairUnavailable {
+                status: inner.status.clone(),
+            })
+    }
+
+    pub(crate) async fn finish(&self, result: Result<KeyPair>) {
+        let mut inner = self.inner.write().await;
+        match result {
+            Ok(keypair) => {
+                inner.status = DkgCeremonyStatus::Finished;
+                inner.keypair = Some(keypair);
+            }
+            Err(err) => {
+                error!("The DKG ceremony has failed - {}", err);
+                inner.status = DkgCeremonyStatus::Failed {
+                    reason: err.to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// Starts taking part in the DKG ceremony in the background in order to derive the coconut
+/// keypair of this signer. Once it's finished, the keypair is stored at the configured keypair
+/// path and made available through the returned [`DkgState`].
+pub(crate) fn start_ceremony<C>(config: &Config, client: C) -> Result<DkgState>
+where
+    C: DkgClient + Send + Sync + 'static,
+{
+    let params = setup();
+    let (decryption_key, public_key) = load_or_generate_bte_keys(
+        &params,
+        config.get_decryption_key_path(),
+        config.get_public_key_with_proof_path(),
+        OsRng,
+    )?;
+
+    let controller = DkgController::new(
+        client,
+        OsRng,
+        params,
+        decryption_key,
+        public_key,
+        config.get_announce_address(),
+        KEY_SIZE,
+        config.get_dkg_contract_polling_rate(),
+    )?;
+
+    let keypair_path = config.keypair_path();
+    let state = DkgState::in_progress();
+    let shared_state = state.clone();
+    tokio::spawn(async move {
+        info!("Starting the DKG ceremony");
+        let result = controller.run_ceremony().await.and_then(|keypair| {
+            fs::write(keypair_path, keypair.to_bs58())?;
+            info!("The DKG ceremony has finished and the derived keypair has been stored");
+            Ok(keypair)
+        });
+        shared_state.finish(result).await
+    });
+
+    Ok(state)
+}
@@ -0,0 +1,248 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::client::DkgClient;
+use super::controller::DkgController;
+use crate::coconut::error::{CoconutError, Result};
+use async_trait::async_trait;
+use coconut_dkg_common::dealer::{ContractDealing, DealerDetails, DealerDetailsResponse};
+use coconut_dkg_common::types::{
+    EncodedBTEPublicKeyWithProof, EncodedVerificationKeyShare, Epoch, EpochState, Threshold,
+};
+use coconut_dkg_common::verification_key::ContractVKShare;
+use cosmwasm_std::{Addr, Binary};
+use dkg::bte::{keygen, setup};
+use nymcoconut::{aggregate_verification_keys, Base58, VerificationKey};
+use rand::rngs::OsRng;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use url::Url;
+use validator_client::nymd::AccountId;
+
+const TEST_KEY_SIZE: usize = 2;
+
+#[derive(Debug, Default)]
+struct DummyContractState {
+    state: EpochState,
+    threshold: Option<Threshold>,
+    dealers: BTreeMap<String, DealerDetails>,
+    dealings: BTreeMap<String, ContractDealing>,
+    vk_shares: BTreeMap<String, ContractVKShare>,
+}
+
+#[derive(Clone, Debug)]
+struct DummyDkgClient {
+    address: AccountId,
+    contract: Arc<RwLock<DummyContractState>>,
+}
+
+impl DummyDkgClient {
+    fn new(address: AccountId, contract: &Arc<RwLock<DummyContractState>>) -> Self {
+        DummyDkgClient {
+            address,
+            contract: Arc::clone(contract),
+        }
+    }
+}
+
+#[async_trait]
+impl DkgClient for DummyDkgClient {
+    async fn address(&self) -> AccountId {
+        self.address.clone()
+    }
+
+    async fn get_current_epoch(&self) -> Result<Epoch> {
+        // the test is in full control of the state transitions
+        Ok(Epoch {
+            state: self.contract.read().unwrap().state,
+            finish_timestamp: None,
+        })
+    }
+
+    async fn get_current_epoch_threshold(&self) -> Result<Option<Threshold>> {
+        Ok(self.contract.read().unwrap().threshold)
+    }
+
+    async fn get_self_registered_dealer_details(&self) -> Result<DealerDetailsResponse> {
+        Ok(DealerDetailsResponse::new(
+            self.contract
+                .read()
+                .unwrap()
+                .dealers
+                .get(self.address.as_ref())
+                .cloned(),
+        ))
+    }
+
+    async fn get_current_dealers(&self) -> Result<Vec<DealerDetails>> {
+        Ok(self
+            .contract
+            .read()
+            .unwrap()
+            .dealers
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get_dealings(&self) -> Result<Vec<ContractDealing>> {
+        Ok(self
+            .contract
+            .read()
+            .unwrap()
+            .dealings
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get_verification_key_shares(&self) -> Result<Vec<ContractVKShare>> {
+        Ok(self
+            .contract
+            .read()
+            .unwrap()
+            .vk_shares
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn register_dealer(
+        &self,
+        bte_key: EncodedBTEPublicKeyWithProof,
+        announce_address: String,
+    ) -> Result<()> {
+        let mut contract = self.contract.write().unwrap();
+        let assigned_index = contract.dealers.len() as u64 + 1;
+        contract.dealers.insert(
+            self.address.to_string(),
+            DealerDetails {
+                address: Addr::unchecked(self.address.as_ref()),
+                bte_public_key_with_proof: bte_key,
+                announce_address,
+                assigned_index,
+            },
+        );
+        Ok(())
+    }
+
+    async fn submit_dealings(&self, dealings: Vec<Binary>) -> Result<()> {
+        self.contract.write().unwrap().dealings.insert(
+            self.address.to_string(),
+            ContractDealing::new(Addr::unchecked(self.address.as_ref()), dealings),
+        );
+        Ok(())
+    }
+
+    async fn submit_verification_key_share(
+        &self,
+        share: EncodedVerificationKeyShare,
+    ) -> Result<()> {
+        let mut contract = self.contract.write().unwrap();
+        let details = contract.dealers.get(self.address.as_ref()).unwrap().clone();
+        contract.vk_shares.insert(
+            self.address.to_string(),
+            ContractVKShare {
+                share,
+                announce_address: details.announce_address,
+                node_index: details.assigned_index,
+                owner: details.address,
+            },
+        );
+        Ok(())
+    }
+
+    async fn advance_epoch_state(&self) -> Result<()> {
+        unreachable!("the epoch never reports it's ready to advance")
+    }
+}
+
+fn set_state(contract: &Arc<RwLock<DummyContractState>>, state: EpochState) {
+    contract.write().unwrap().state = state;
+}
+
+fn dummy_controllers(
+    contract: &Arc<RwLock<DummyContractState>>,
+    n: u8,
+) -> Vec<DkgController<DummyDkgClient, OsRng>> {
+    (1..=n)
+        .map(|i| {
+            let params = setup();
+            let (decryption_key, public_key) = keygen(&params, OsRng);
+            let address = AccountId::new("n", &[i; 20]).unwrap();
+            DkgController::new(
+                DummyDkgClient::new(address, contract),
+                OsRng,
+                params,
+                decryption_key,
+                public_key,
+                Url::parse(&format!("http://localhost:{}", 8000 + i as u16)).unwrap(),
+                TEST_KEY_SIZE,
+                Duration::from_secs(1),
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn ceremony_derives_consistent_keys() {
+    let contract = Arc::new(RwLock::new(DummyContractState::default()));
+    let mut controllers = dummy_controllers(&contract, 3);
+
+    for controller in controllers.iter_mut() {
+        assert!(controller.handle_epoch_state().await.unwrap().is_none());
+    }
+    assert_eq!(contract.read().unwrap().dealers.len(), 3);
+
+    set_state(&contract, EpochState::DealingExchange);
+    contract.write().unwrap().threshold = Some(2);
+    for controller in controllers.iter_mut() {
+        assert!(controller.handle_epoch_state().await.unwrap().is_none());
+    }
+    assert_eq!(contract.read().unwrap().dealings.len(), 3);
+
+    set_state(&contract, EpochState::VerificationKeySubmission);
+    for controller in controllers.iter_mut() {
+        assert!(controller.handle_epoch_state().await.unwrap().is_none());
+    }
+    assert_eq!(contract.read().unwrap().vk_shares.len(), 3);
+
+    set_state(&contract, EpochState::Finished);
+    let mut keypairs = Vec::new();
+    for controller in controllers.iter_mut() {
+        keypairs.push(controller.handle_epoch_state().await.unwrap().unwrap());
+    }
+
+    // the submitted shares must match the derived keys
+    for share in contract.read().unwrap().vk_shares.values() {
+        assert_eq!(
+            keypairs[share.node_index as usize - 1].verification_key(),
+            VerificationKey::try_from_bs58(&share.share).unwrap()
+        );
+    }
+
+    // and any threshold-sized subset of them must result in the same master key
+    let vks = keypairs
+        .iter()
+        .map(|keypair| keypair.verification_key())
+        .collect::<Vec<_>>();
+    let master1 = aggregate_verification_keys(&vks[..2], Some(&[1, 2])).unwrap();
+    let master2 = aggregate_verification_keys(&vks[1..], Some(&[2, 3])).unwrap();
+    assert_eq!(master1, master2);
+}
+
+#[tokio::test]
+async fn late_participant_misses_the_ceremony() {
+    let contract = Arc::new(RwLock::new(DummyContractState::default()));
+    let mut controllers = dummy_controllers(&contract, 1);
+    set_state(&contract, EpochState::DealingExchange);
+
+    assert!(matches!(
+        controllers[0].handle_epoch_state().await,
+        Err(CoconutError::DkgCeremonyMissed {
+            state: EpochState::DealingExchange
+        })
+    ));
+}
@@ -7,10 +7,13 @@ use rocket::{response, Request, Response};
 use std::io::Cursor;
 use thiserror::Error;
 
+use coconut_dkg_common::types::EpochState;
 use crypto::asymmetric::{
     encryption::KeyRecoveryError,
     identity::{Ed25519RecoveryError, SignatureError},
 };
+use dkg::error::DkgError;
+use validator_api_requests::coconut::DkgCeremonyStatus;
 use validator_client::nymd::error::NymdError;
 
 use crate::node_status_api::models::ValidatorApiStorageError;
@@ -74,6 +77,33 @@ pub enum CoconutError {
 
     #[error("Invalid status of credential: {status}")]
     InvalidCredentialStatus { status: String },
+
+    #[error("DKG error - {0}")]
+    DkgError(#[from] DkgError),
+
+    #[error("IO error - {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Could not decode base58 data - {0}")]
+    Bs58DecodeError(#[from] bs58::decode::Error),
+
+    #[error("This validator api has not registered for the DKG ceremony, which is already in the {state} state")]
+    DkgCeremonyMissed { state: EpochState },
+
+    #[error("The DKG threshold has not been established yet")]
+    UnknownDkgThreshold,
+
+    #[error("This validator api is not one of the receivers of the DKG dealings")]
+    NotADkgReceiver,
+
+    #[error("Only {available} valid dealers have been found, while the threshold is {threshold}")]
+    NotEnoughDealings { available: usize, threshold: u64 },
+
+    #[error("The derived secret key does not match the verification key share recovered from the dealings")]
+    MismatchedVerificationKeyShare,
+
+    #[error("The coconut keypair is not available, the DKG ceremony is in the {status:?} status")]
+    KeyPairUnavailable { status: DkgCeremonyStatus },
 }
 
 impl<'r, 'o: 'r> Responder<'r, 'o> for CoconutError {
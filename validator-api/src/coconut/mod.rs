@@ -4,12 +4,14 @@
 pub(crate) mod client;
 pub(crate) mod comm;
 mod deposit;
+pub(crate) mod dkg;
 pub(crate) mod error;
 #[cfg(test)]
 mod tests;
 
 use crate::coconut::client::Client as LocalClient;
 use crate::coconut::deposit::extract_encryption_key;
+use crate::coconut::dkg::DkgState;
 use crate::coconut::error::{CoconutError, Result};
 use crate::ValidatorApiStorage;
 
@@ -27,8 +29,8 @@ use crypto::asymmetric::encryption;
 use crypto::shared_key::new_ephemeral_shared_key;
 use crypto::symmetric::stream_cipher;
 use validator_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, VerificationKeyResponse,
-    VerifyCredentialBody, VerifyCredentialResponse,
+    BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, DkgStatusResponse,
+    VerificationKeyResponse, VerifyCredentialBody, VerifyCredentialResponse,
 };
 use validator_client::nymd::{Coin, Fee};
 use validator_client::validator_api::routes::{BANDWIDTH, COCONUT_ROUTES};
@@ -46,7 +48,7 @@ use self::comm::APICommunicationChannel;
 pub struct State {
     client: Arc<dyn LocalClient + Send + Sync>,
     mix_denom: String,
-    key_pair: KeyPair,
+    dkg_state: DkgState,
     comm_channel: Arc<dyn APICommunicationChannel + Send + Sync>,
     storage: ValidatorApiStorage,
     rng: Arc<Mutex<OsRng>>,
@@ -56,7 +58,7 @@ impl State {
     pub(crate) fn new<C, D>(
         client: C,
         mix_denom: String,
-        key_pair: KeyPair,
+        dkg_state: DkgState,
         comm_channel: D,
         storage: ValidatorApiStorage,
     ) -> Self
@@ -70,7 +72,7 @@ impl State {
         Self {
             client,
             mix_denom,
-            key_pair,
+            dkg_state,
             comm_channel,
             storage,
             rng,
@@ -164,7 +166,7 @@ impl InternalSignRequest {
     pub fn stage<C, D>(
         client: C,
         mix_denom: String,
-        key_pair: KeyPair,
+        dkg_state: DkgState,
         comm_channel: D,
         storage: ValidatorApiStorage,
     ) -> AdHoc
@@ -172,7 +174,7 @@ impl InternalSignRequest {
         C: LocalClient + Send + Sync + 'static,
         D: APICommunicationChannel + Send + Sync + 'static,
     {
-        let state = State::new(client, mix_denom, key_pair, comm_channel, storage);
+        let state = State::new(client, mix_denom, dkg_state, comm_channel, storage);
         AdHoc::on_ignite("Internal Sign Request Stage", |rocket| async {
             rocket.manage(state).mount(
                 // this format! is so ugly...
@@ -183,6 +185,7 @@ impl InternalSignRequest {
                 routes![
                     post_blind_sign,
                     get_verification_key,
+                    get_dkg_status,
                     get_cosmos_address,
                     post_partial_bandwidth_credential,
                     verify_bandwidth_credential
@@ -226,7 +229,8 @@ pub async fn post_blind_sign(
         blind_sign_request_body.public_attributes(),
         blind_sign_request_body.blind_sign_request().clone(),
     );
-    let blinded_signature = blind_sign(internal_request, &state.key_pair);
+    let key_pair = state.dkg_state.keypair().await?;
+    let blinded_signature = blind_sign(internal_request, &key_pair);
 
     let response = state
         .encrypt_and_store(
@@ -256,10 +260,15 @@ pub async fn get_verification_key(
     state: &RocketState<State>,
 ) -> Result<Json<VerificationKeyResponse>> {
     Ok(Json(VerificationKeyResponse::new(
-        state.key_pair.verification_key(),
+        state.dkg_state.keypair().await?.verification_key(),
     )))
 }
 
+#[get("/dkg-status")]
+pub async fn get_dkg_status(state: &RocketState<State>) -> Json<DkgStatusResponse> {
+    Json(DkgStatusResponse::new(state.dkg_state.status().await))
+}
+
 #[get("/cosmos-address")]
 pub async fn get_cosmos_address(state: &RocketState<State>) -> Result<Json<CosmosAddressResponse>> {
     Ok(Json(CosmosAddressResponse::new(
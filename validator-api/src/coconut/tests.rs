@@ -25,17 +25,18 @@ use nymcoconut::{
     prepare_blind_sign, ttp_keygen, Base58, BlindSignRequest, BlindedSignature, KeyPair, Parameters,
 };
 use validator_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, VerificationKeyResponse,
-    VerifyCredentialBody, VerifyCredentialResponse,
+    BlindSignRequestBody, BlindedSignatureResponse, CosmosAddressResponse, DkgCeremonyStatus,
+    DkgStatusResponse, VerificationKeyResponse, VerifyCredentialBody, VerifyCredentialResponse,
 };
 use validator_client::nymd::Coin;
 use validator_client::nymd::{tx::Hash, AccountId, DeliverTx, Event, Fee, Tag, TxResponse};
 use validator_client::validator_api::routes::{
-    API_VERSION, BANDWIDTH, COCONUT_BLIND_SIGN, COCONUT_COSMOS_ADDRESS,
+    API_VERSION, BANDWIDTH, COCONUT_BLIND_SIGN, COCONUT_COSMOS_ADDRESS, COCONUT_DKG_STATUS,
     COCONUT_PARTIAL_BANDWIDTH_CREDENTIAL, COCONUT_ROUTES, COCONUT_VERIFICATION_KEY,
     COCONUT_VERIFY_BANDWIDTH_CREDENTIAL,
 };
 
+use crate::coconut::dkg::DkgState;
 use crate::coconut::State;
 use crate::ValidatorApiStorage;
 use async_trait::async_trait;
@@ -191,7 +192,7 @@ async fn check_signer_verif_key(key_pair: KeyPair) {
     let rocket = rocket::build().attach(InternalSignRequest::stage(
         nymd_client,
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel,
         storage,
     ));
@@ -285,7 +286,7 @@ async fn signed_before() {
     let rocket = rocket::build().attach(InternalSignRequest::stage(
         nymd_client,
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel,
         storage.clone(),
     ));
@@ -354,7 +355,7 @@ async fn state_functions() {
     let state = State::new(
         nymd_client,
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel,
         storage.clone(),
     );
@@ -525,7 +526,7 @@ async fn blind_sign_correct() {
     let rocket = rocket::build().attach(InternalSignRequest::stage(
         nymd_client,
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel,
         storage.clone(),
     ));
@@ -604,7 +605,7 @@ async fn signature_test() {
     let rocket = rocket::build().attach(InternalSignRequest::stage(
         nymd_client,
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel,
         storage.clone(),
     ));
@@ -675,7 +676,7 @@ async fn get_cosmos_address() {
     let rocket = rocket::build().attach(InternalSignRequest::stage(
         nymd_client,
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel,
         storage.clone(),
     ));
@@ -697,6 +698,65 @@ async fn get_cosmos_address() {
     assert_eq!(validator_address, cosmos_addr_response.addr);
 }
 
+#[tokio::test]
+async fn keys_are_only_available_once_dkg_finishes() {
+    let nymd_client = DummyClient::new(
+        AccountId::from_str(TEST_REWARDING_VALIDATOR_ADDRESS).unwrap(),
+        &Arc::new(RwLock::new(HashMap::new())),
+        &Arc::new(RwLock::new(HashMap::new())),
+        &Arc::new(RwLock::new(HashMap::new())),
+    );
+    let mut db_dir = std::env::temp_dir();
+    let key_pair = ttp_keygen(&Parameters::new(4).unwrap(), 1, 1)
+        .unwrap()
+        .remove(0);
+    db_dir.push(&key_pair.verification_key().to_bs58()[..8]);
+    let storage = ValidatorApiStorage::init(db_dir).await.unwrap();
+    let comm_channel = DummyCommunicationChannel::new(key_pair.verification_key());
+    let dkg_state = DkgState::in_progress();
+    let rocket = rocket::build().attach(InternalSignRequest::stage(
+        nymd_client,
+        TEST_COIN_DENOM.to_string(),
+        dkg_state.clone(),
+        comm_channel,
+        storage.clone(),
+    ));
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    let verification_key_route = format!(
+        "/{}/{}/{}/{}",
+        API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_VERIFICATION_KEY
+    );
+    let dkg_status_route = format!(
+        "/{}/{}/{}/{}",
+        API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_DKG_STATUS
+    );
+
+    let response = client.get(&verification_key_route).dispatch().await;
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get(&dkg_status_route).dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let status_response =
+        serde_json::from_str::<DkgStatusResponse>(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(status_response.status, DkgCeremonyStatus::InProgress);
+
+    let verification_key = key_pair.verification_key();
+    dkg_state.finish(Ok(key_pair)).await;
+
+    let response = client.get(&verification_key_route).dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let verification_key_response =
+        serde_json::from_str::<VerificationKeyResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert_eq!(verification_key_response.key, verification_key);
+    let response = client.get(&dkg_status_route).dispatch().await;
+    let status_response =
+        serde_json::from_str::<DkgStatusResponse>(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(status_response.status, DkgCeremonyStatus::Finished);
+}
+
 #[tokio::test]
 async fn verification_of_bandwidth_credential() {
     // Setup variables
@@ -726,7 +786,7 @@ async fn verification_of_bandwidth_credential() {
     let rocket = rocket::build().attach(InternalSignRequest::stage(
         nymd_client.clone(),
         TEST_COIN_DENOM.to_string(),
-        key_pair,
+        DkgState::with_keypair(key_pair),
         comm_channel.clone(),
         storage1.clone(),
     ));
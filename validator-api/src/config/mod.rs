@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::template::config_template;
+use config::defaults::DEFAULT_VALIDATOR_API_PORT;
 use config::NymConfig;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -39,6 +40,8 @@ const DEFAULT_MONITOR_THRESHOLD: u8 = 60;
 const DEFAULT_MIN_MIXNODE_RELIABILITY: u8 = 50;
 const DEFAULT_MIN_GATEWAY_RELIABILITY: u8 = 20;
 
+const DEFAULT_DKG_CONTRACT_POLLING_RATE: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 pub struct Config {
     #[serde(default)]
@@ -276,7 +279,7 @@ impl Default for Rewarding {
     }
 }
 
-#[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
 pub struct CoconutSigner {
     /// Specifies whether rewarding service is enabled in this process.
@@ -289,6 +292,59 @@ pub struct CoconutSigner {
     /// A special care must be taken to ensure they are in correct order.
     /// The list must also contain THIS validator that is running the test
     all_validator_apis: Vec<Url>,
+
+    /// Specifies whether the signing keypair should be generated by taking part in
+    /// the distributed key generation ceremony, if it doesn't exist yet.
+    dkg_enabled: bool,
+
+    /// Address of this validator api announced to other signers during the distributed key generation.
+    announce_address: Url,
+
+    /// Path to the BTE decryption key used for decrypting the dealings during the
+    /// distributed key generation.
+    decryption_key_path: PathBuf,
+
+    /// Path to the BTE public key, alongside its proof of possession, used during the
+    /// distributed key generation.
+    public_key_with_proof_path: PathBuf,
+
+    /// Specifies how often the DKG contract should be polled for changes in the ceremony state.
+    #[serde(with = "humantime_serde")]
+    dkg_contract_polling_rate: Duration,
+}
+
+impl CoconutSigner {
+    pub const DKG_DECRYPTION_KEY_FILE: &'static str = "dkg_decryption_key";
+    pub const DKG_PUBLIC_KEY_WITH_PROOF_FILE: &'static str = "dkg_public_key_with_proof";
+
+    fn default_announce_address() -> Url {
+        format!("http://localhost:{}", DEFAULT_VALIDATOR_API_PORT)
+            .parse()
+            .expect("default announce address is malformed!")
+    }
+
+    fn default_decryption_key_path() -> PathBuf {
+        Config::default_data_directory(None).join(Self::DKG_DECRYPTION_KEY_FILE)
+    }
+
+    fn default_public_key_with_proof_path() -> PathBuf {
+        Config::default_data_directory(None).join(Self::DKG_PUBLIC_KEY_WITH_PROOF_FILE)
+    }
+}
+
+impl Default for CoconutSigner {
+    fn default() -> Self {
+        CoconutSigner {
+            enabled: false,
+            keypair_path: PathBuf::default(),
+            all_validator_apis: Vec::new(),
+            dkg_enabled: false,
+            announce_address: Self::default_announce_address(),
+            decryption_key_path: Self::default_decryption_key_path(),
+            public_key_with_proof_path: Self::default_public_key_with_proof_path(),
+            dkg_contract_polling_rate: DEFAULT_DKG_CONTRACT_POLLING_RATE,
+        }
+    }
 }
 
 impl Config {
@@ -302,6 +358,10 @@ impl Config {
             Config::default_data_directory(Some(id)).join(NodeStatusAPI::DB_FILE);
         self.network_monitor.credentials_database_path =
             Config::default_data_directory(Some(id)).join(NetworkMonitor::DB_FILE);
+        self.coconut_signer.decryption_key_path =
+            Config::default_data_directory(Some(id)).join(CoconutSigner::DKG_DECRYPTION_KEY_FILE);
+        self.coconut_signer.public_key_with_proof_path = Config::default_data_directory(Some(id))
+            .join(CoconutSigner::DKG_PUBLIC_KEY_WITH_PROOF_FILE);
         self
     }
 
@@ -358,6 +418,18 @@ impl Config {
         self
     }
 
+    #[cfg(feature = "coconut")]
+    pub fn with_dkg_enabled(mut self, enabled: bool) -> Self {
+        self.coconut_signer.dkg_enabled = enabled;
+        self
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn with_announce_address(mut self, announce_address: Url) -> Self {
+        self.coconut_signer.announce_address = announce_address;
+        self
+    }
+
     pub fn with_minimum_interval_monitor_threshold(mut self, threshold: u8) -> Self {
         self.rewarding.minimum_interval_monitor_threshold = threshold;
         self
@@ -492,6 +564,31 @@ impl Config {
         self.coconut_signer.all_validator_apis.clone()
     }
 
+    #[cfg(feature = "coconut")]
+    pub fn get_dkg_enabled(&self) -> bool {
+        self.coconut_signer.dkg_enabled
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_announce_address(&self) -> Url {
+        self.coconut_signer.announce_address.clone()
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_decryption_key_path(&self) -> PathBuf {
+        self.coconut_signer.decryption_key_path.clone()
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_public_key_with_proof_path(&self) -> PathBuf {
+        self.coconut_signer.public_key_with_proof_path.clone()
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_dkg_contract_polling_rate(&self) -> Duration {
+        self.coconut_signer.dkg_contract_polling_rate
+    }
+
     // TODO: Remove if still unused
     #[allow(dead_code)]
     pub fn get_minimum_interval_monitor_threshold(&self) -> u8 {
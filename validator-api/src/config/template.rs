@@ -111,5 +111,23 @@ all_validator_apis = [
     {{/each}}
 ]
 
+# Specifies whether the signing keypair should be generated by taking part in
+# the distributed key generation ceremony, if it doesn't exist yet.
+dkg_enabled = {{ coconut_signer.dkg_enabled }}
+
+# Address of this validator api announced to other signers during the distributed key generation.
+announce_address = '{{ coconut_signer.announce_address }}'
+
+# Path to the BTE decryption key used for decrypting the dealings during the
+# distributed key generation.
+decryption_key_path = '{{ coconut_signer.decryption_key_path }}'
+
+# Path to the BTE public key, alongside its proof of possession, used during the
+# distributed key generation.
+public_key_with_proof_path = '{{ coconut_signer.public_key_with_proof_path }}'
+
+# Specifies how often the DKG contract should be polled for changes in the ceremony state.
+dkg_contract_polling_rate = '{{ coconut_signer.dkg_contract_polling_rate }}'
+
 "#
 }
@@ -17,6 +17,7 @@ use rocket_okapi::settings::OpenApiSettings;
 
 use rocket::fairing::AdHoc;
 use serde::Serialize;
+use service_provider_directory_common::service::ServiceInfo;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -48,6 +49,7 @@ pub struct ValidatorCache {
 struct ValidatorCacheInner {
     mixnodes: Cache<Vec<MixNodeBondAnnotated>>,
     gateways: Cache<Vec<GatewayBond>>,
+    services: Cache<Vec<ServiceInfo>>,
 
     mixnodes_blacklist: Cache<HashSet<IdentityKey>>,
     gateways_blacklist: Cache<HashSet<IdentityKey>>,
@@ -203,7 +205,7 @@ impl<C> ValidatorCacheRefresher<C> {
 
     async fn refresh_cache(&self) -> Result<()>
     where
-        C: CosmWasmClient + Sync,
+        C: CosmWasmClient + Sync + Send,
     {
         let epoch_rewarding_params = self.nymd_client.get_current_epoch_reward_params().await?;
         let current_epoch = self.nymd_client.get_current_epoch().await?;
@@ -215,6 +217,16 @@ impl<C> ValidatorCacheRefresher<C> {
             self.nymd_client.get_gateways(),
         )?;
 
+        // the directory is not crucial for the network itself, so failing to query it
+        // shouldn't prevent the rest of the cache from getting refreshed
+        let services = match self.nymd_client.get_services().await {
+            Ok(services) => Some(services),
+            Err(err) => {
+                warn!("Failed to query the service provider directory - {}", err);
+                None
+            }
+        };
+
         let rewarded_set_identities = self.get_rewarded_set_identities().await;
 
         let mixnodes = self
@@ -241,6 +253,7 @@ impl<C> ValidatorCacheRefresher<C> {
             .update_cache(
                 mixnodes,
                 gateways,
+                services,
                 rewarded_set,
                 active_set,
                 epoch_rewarding_params,
@@ -254,7 +267,7 @@ impl<C> ValidatorCacheRefresher<C> {
 
     pub(crate) async fn run(&self)
     where
-        C: CosmWasmClient + Sync,
+        C: CosmWasmClient + Sync + Send,
     {
         let mut interval = time::interval(self.caching_interval);
         loop {
@@ -276,6 +289,7 @@ pub(crate) fn validator_cache_routes(settings: &OpenApiSettings) -> (Vec<Route>,
         settings: routes::get_mixnodes,
         routes::get_mixnodes_detailed,
         routes::get_gateways,
+        routes::get_services,
         routes::get_active_set,
         routes::get_active_set_detailed,
         routes::get_rewarded_set,
@@ -306,6 +320,7 @@ impl ValidatorCache {
         &self,
         mixnodes: Vec<MixNodeBondAnnotated>,
         gateways: Vec<GatewayBond>,
+        services: Option<Vec<ServiceInfo>>,
         rewarded_set: Vec<MixNodeBondAnnotated>,
         active_set: Vec<MixNodeBondAnnotated>,
         epoch_rewarding_params: EpochRewardParams,
@@ -316,6 +331,9 @@ impl ValidatorCache {
             Ok(mut cache) => {
                 cache.mixnodes.update(mixnodes);
                 cache.gateways.update(gateways);
+                if let Some(services) = services {
+                    cache.services.update(services);
+                }
                 cache.rewarded_set.update(rewarded_set);
                 cache.active_set.update(active_set);
                 cache.current_reward_params.update(epoch_rewarding_params);
@@ -488,6 +506,16 @@ impl ValidatorCache {
         }
     }
 
+    pub async fn services(&self) -> Vec<ServiceInfo> {
+        match time::timeout(Duration::from_millis(100), self.inner.read()).await {
+            Ok(cache) => cache.services.value.clone(),
+            Err(e) => {
+                error!("{}", e);
+                Vec::new()
+            }
+        }
+    }
+
     pub async fn rewarded_set_detailed(&self) -> Cache<Vec<MixNodeBondAnnotated>> {
         match time::timeout(Duration::from_millis(100), self.inner.read()).await {
             Ok(cache) => cache.rewarded_set.clone(),
@@ -616,6 +644,7 @@ impl ValidatorCacheInner {
         ValidatorCacheInner {
             mixnodes: Cache::default(),
             gateways: Cache::default(),
+            services: Cache::default(),
             rewarded_set: Cache::default(),
             active_set: Cache::default(),
             current_reward_params: Cache::new(EpochRewardParams::new_empty()),
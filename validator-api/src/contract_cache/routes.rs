@@ -7,6 +7,7 @@ use mixnet_contract_common::{GatewayBond, Interval, MixNodeBond};
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::openapi;
+use service_provider_directory_common::service::ServiceInfo;
 use std::collections::HashSet;
 use validator_api_requests::models::MixNodeBondAnnotated;
 
@@ -30,6 +31,12 @@ pub async fn get_gateways(cache: &State<ValidatorCache>) -> Json<Vec<GatewayBond
     Json(cache.gateways().await)
 }
 
+#[openapi(tag = "contract-cache")]
+#[get("/services")]
+pub async fn get_services(cache: &State<ValidatorCache>) -> Json<Vec<ServiceInfo>> {
+    Json(cache.services().await)
+}
+
 #[openapi(tag = "contract-cache")]
 #[get("/mixnodes/rewarded")]
 pub async fn get_rewarded_set(cache: &State<ValidatorCache>) -> Json<Vec<MixNodeBond>> {
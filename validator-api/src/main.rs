@@ -37,7 +37,7 @@ use tokio::sync::Notify;
 
 use crate::rewarded_set_updater::RewardedSetUpdater;
 #[cfg(feature = "coconut")]
-use coconut::{comm::QueryCommunicationChannel, InternalSignRequest};
+use coconut::{comm::QueryCommunicationChannel, dkg::DkgState, InternalSignRequest};
 #[cfg(feature = "coconut")]
 use coconut_interface::{Base58, KeyPair};
 use validator_client::nymd::SigningNymdClient;
@@ -70,6 +70,10 @@ const API_VALIDATORS_ARG: &str = "api-validators";
 const KEYPAIR_ARG: &str = "keypair";
 #[cfg(feature = "coconut")]
 const COCONUT_ENABLED: &str = "enable-coconut";
+#[cfg(feature = "coconut")]
+const DKG_ENABLED: &str = "enable-dkg";
+#[cfg(feature = "coconut")]
+const ANNOUNCE_ADDRESS_ARG: &str = "announce-address";
 
 #[cfg(not(feature = "coconut"))]
 const ETH_ENDPOINT: &str = "eth_endpoint";
@@ -208,6 +212,18 @@ fn parse_args<'a>() -> ArgMatches<'a> {
                 .help("Flag to indicate whether coconut signer authority is enabled on this API")
                 .requires_all(&[KEYPAIR_ARG, MNEMONIC_ARG, API_VALIDATORS_ARG])
                 .long(COCONUT_ENABLED),
+        )
+        .arg(
+            Arg::with_name(DKG_ENABLED)
+                .help("Flag to indicate whether the signing keypair should be derived by taking part in the DKG ceremony if it doesn't exist yet")
+                .requires(COCONUT_ENABLED)
+                .long(DKG_ENABLED),
+        )
+        .arg(
+            Arg::with_name(ANNOUNCE_ADDRESS_ARG)
+                .help("Address of this validator api announced to other signers during the DKG ceremony")
+                .takes_value(true)
+                .long(ANNOUNCE_ADDRESS_ARG),
         );
 
     #[cfg(not(feature = "coconut"))]
@@ -279,6 +295,23 @@ fn override_config(mut config: Config, matches: &ArgMatches<'_>) -> Config {
         config = config.with_coconut_signer_enabled(true)
     }
 
+    #[cfg(feature = "coconut")]
+    if matches.is_present(DKG_ENABLED) {
+        config = config.with_dkg_enabled(true)
+    }
+
+    #[cfg(feature = "coconut")]
+    if let Some(raw_address) = matches.value_of(ANNOUNCE_ADDRESS_ARG) {
+        let parsed = match raw_address.parse() {
+            Err(err) => {
+                error!("Passed announce address is invalid - {}", err);
+                process::exit(1)
+            }
+            Ok(url) => url,
+        };
+        config = config.with_announce_address(parsed);
+    }
+
     #[cfg(feature = "coconut")]
     if let Some(raw_validators) = matches.value_of(API_VALIDATORS_ARG) {
         config = config.with_custom_validator_apis(parse_validators(raw_validators));
@@ -463,14 +496,20 @@ async fn setup_rocket(
 
     #[cfg(feature = "coconut")]
     let rocket = if config.get_coconut_signer_enabled() {
-        let keypair_bs58 = fs::read_to_string(config.keypair_path())?
-            .trim()
-            .to_string();
-        let keypair = KeyPair::try_from_bs58(keypair_bs58)?;
+        // the ceremony might take a long while, so it's run in the background and until it's
+        // finished, the coconut routes only report its status
+        let dkg_state = if config.get_dkg_enabled() && !config.keypair_path().exists() {
+            coconut::dkg::start_ceremony(config, _nymd_client.clone())?
+        } else {
+            let keypair_bs58 = fs::read_to_string(config.keypair_path())?
+                .trim()
+                .to_string();
+            DkgState::with_keypair(KeyPair::try_from_bs58(keypair_bs58)?)
+        };
         rocket.attach(InternalSignRequest::stage(
             _nymd_client,
             _mix_denom,
-            keypair,
+            dkg_state,
             QueryCommunicationChannel::new(config.get_all_validator_api_endpoints()),
             storage.clone().unwrap(),
         ))
@@ -258,6 +258,13 @@ impl PacketPreparer {
         blacklist: &mut HashSet<String>,
     ) -> Option<Vec<TestRoute>> {
         let (mixnodes, gateways) = self.all_mixnodes_and_gateways().await;
+        let current_epoch = self
+            .validator_cache
+            .current_epoch()
+            .await
+            .into_inner()
+            .map(|epoch| epoch.id());
+
         // separate mixes into layers for easier selection
         let mut layered_mixes = HashMap::new();
         for mix in mixnodes {
@@ -332,6 +339,7 @@ impl PacketPreparer {
                 routes.push(TestRoute::new(
                     rng.gen(),
                     &self.system_version,
+                    current_epoch,
                     node_1,
                     node_2,
                     node_3,
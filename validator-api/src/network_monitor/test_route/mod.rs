@@ -18,6 +18,7 @@ impl TestRoute {
     pub(crate) fn new(
         id: u64,
         system_version: &str,
+        current_epoch: Option<u32>,
         l1_mix: mix::Node,
         l2_mix: mix::Node,
         l3_mix: mix::Node,
@@ -31,10 +32,14 @@ impl TestRoute {
         .into_iter()
         .collect();
 
+        // so that the packets would be encrypted to the sphinx keys the nodes currently accept
+        let mut nodes = NymTopology::new(layered_mixes, vec![gateway]);
+        nodes.set_current_epoch(current_epoch);
+
         TestRoute {
             id,
             system_version: system_version.to_string(),
-            nodes: NymTopology::new(layered_mixes, vec![gateway]),
+            nodes,
         }
     }
 
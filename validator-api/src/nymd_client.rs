@@ -5,7 +5,16 @@
 use async_trait::async_trait;
 #[cfg(feature = "coconut")]
 use coconut_bandwidth_contract_common::spend_credential::SpendCredentialResponse;
+#[cfg(feature = "coconut")]
+use coconut_dkg_common::{
+    dealer::{ContractDealing, DealerDetails, DealerDetailsResponse},
+    types::{EncodedBTEPublicKeyWithProof, EncodedVerificationKeyShare, Epoch, Threshold},
+    verification_key::ContractVKShare,
+};
+#[cfg(feature = "coconut")]
+use cosmwasm_std::Binary;
 use serde::Serialize;
+use service_provider_directory_common::service::ServiceInfo;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -25,7 +34,10 @@ use validator_client::nymd::{
 };
 #[cfg(feature = "coconut")]
 use validator_client::nymd::{
-    traits::{CoconutBandwidthQueryClient, MultisigQueryClient, MultisigSigningClient},
+    traits::{
+        CoconutBandwidthQueryClient, DkgQueryClient, DkgSigningClient, MultisigQueryClient,
+        MultisigSigningClient,
+    },
     AccountId,
 };
 use validator_client::ValidatorClientError;
@@ -128,6 +140,18 @@ impl<C> Client<C> {
         self.0.read().await.get_all_nymd_gateways().await
     }
 
+    pub(crate) async fn get_services(&self) -> Result<Vec<ServiceInfo>, ValidatorClientError>
+    where
+        C: CosmWasmClient + Sync + Send,
+    {
+        let client = self.0.read().await;
+        // the directory is optional, so there is nothing to query if it hasn't been configured
+        if !client.has_service_provider_directory_contract() {
+            return Ok(Vec::new());
+        }
+        client.get_all_nymd_services().await
+    }
+
     #[allow(dead_code)]
     // I've got a feeling we will need this again very soon, so I'd rather not remove this
     // (and all subcalls in the various clients) just yet
@@ -479,3 +503,146 @@ where
         Ok(())
     }
 }
+
+#[async_trait]
+#[cfg(feature = "coconut")]
+impl<C> crate::coconut::dkg::client::DkgClient for Client<C>
+where
+    C: SigningCosmWasmClient + Sync + Send,
+{
+    async fn address(&self) -> AccountId {
+        self.0.read().await.nymd.address().clone()
+    }
+
+    async fn get_current_epoch(&self) -> Result<Epoch, CoconutError> {
+        Ok(self.0.read().await.nymd.get_current_dkg_epoch().await?)
+    }
+
+    async fn get_current_epoch_threshold(&self) -> Result<Option<Threshold>, CoconutError> {
+        Ok(self
+            .0
+            .read()
+            .await
+            .nymd
+            .get_current_dkg_epoch_threshold()
+            .await?)
+    }
+
+    async fn get_self_registered_dealer_details(
+        &self,
+    ) -> Result<DealerDetailsResponse, CoconutError> {
+        let client = self.0.read().await;
+        let address = client.nymd.address().to_string();
+        Ok(client.nymd.get_dealer_details(address).await?)
+    }
+
+    async fn get_current_dealers(&self) -> Result<Vec<DealerDetails>, CoconutError> {
+        let client = self.0.read().await;
+        let mut dealers = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = client
+                .nymd
+                .get_current_dealers_paged(start_after.take(), None)
+                .await?;
+            dealers.append(&mut paged_response.dealers);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res.into_string())
+            } else {
+                break;
+            }
+        }
+
+        Ok(dealers)
+    }
+
+    async fn get_dealings(&self) -> Result<Vec<ContractDealing>, CoconutError> {
+        let client = self.0.read().await;
+        let mut dealings = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = client
+                .nymd
+                .get_dealings_paged(start_after.take(), None)
+                .await?;
+            dealings.append(&mut paged_response.dealings);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res.into_string())
+            } else {
+                break;
+            }
+        }
+
+        Ok(dealings)
+    }
+
+    async fn get_verification_key_shares(&self) -> Result<Vec<ContractVKShare>, CoconutError> {
+        let client = self.0.read().await;
+        let mut shares = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = client
+                .nymd
+                .get_vk_shares_paged(start_after.take(), None)
+                .await?;
+            shares.append(&mut paged_response.shares);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res.into_string())
+            } else {
+                break;
+            }
+        }
+
+        Ok(shares)
+    }
+
+    async fn register_dealer(
+        &self,
+        bte_key: EncodedBTEPublicKeyWithProof,
+        announce_address: String,
+    ) -> Result<(), CoconutError> {
+        self.0
+            .read()
+            .await
+            .nymd
+            .register_dealer(bte_key, announce_address, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn submit_dealings(&self, dealings: Vec<Binary>) -> Result<(), CoconutError> {
+        self.0
+            .read()
+            .await
+            .nymd
+            .submit_dealings(dealings, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn submit_verification_key_share(
+        &self,
+        share: EncodedVerificationKeyShare,
+    ) -> Result<(), CoconutError> {
+        self.0
+            .read()
+            .await
+            .nymd
+            .submit_verification_key_share(share, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn advance_epoch_state(&self) -> Result<(), CoconutError> {
+        self.0
+            .read()
+            .await
+            .nymd
+            .advance_dkg_epoch_state(None)
+            .await?;
+        Ok(())
+    }
+}
@@ -146,6 +146,26 @@ impl VerificationKeyResponse {
     }
 }
 
+/// Progress of the DKG ceremony the coconut keypair of a signer is derived from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DkgCeremonyStatus {
+    InProgress,
+    Finished,
+    Failed { reason: String },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DkgStatusResponse {
+    pub status: DkgCeremonyStatus,
+}
+
+impl DkgStatusResponse {
+    pub fn new(status: DkgCeremonyStatus) -> DkgStatusResponse {
+        DkgStatusResponse { status }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CosmosAddressResponse {
     pub addr: AccountId,